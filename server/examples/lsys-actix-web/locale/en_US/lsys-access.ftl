@@ -3,6 +3,10 @@ access-bad-code = Error parsing login CODE: {$msg}
 access-not-login = User not logged in
 access-not-account = Non-system internal account, belongs to app: {$appid}
 access-parse-error = Error parsing user ID, details: {$msg}
+access-refresh-token-reuse = Refresh token has already been used, related login revoked: Session({$id})
+access-need-reauth = This operation requires re-authentication within {$time} seconds
+access-impersonate-deny = This operation is not allowed while impersonating (operator user: {$user_id})
+access-step-up-deny = Sensitive operations are not allowed with this login type ({$login_type})
 # Status
 status-SessionStatus-Enable = Active
 status-SessionStatus-Delete = Deleted
@@ -7,4 +7,7 @@ barcode-file-data-error = Failed to parse image - upload data read failed: {$msg
 barcode-file-write-error = Failed to parse image - upload data write to temporary file failed: {$msg}
 barcode-seek-data-error = Failed to parse image - temporary file seek failed: {$msg}
 barcode-read-data-error = Failed to parse image - temporary file read failed: {$msg}
-barcode-format-error = Failed to parse image - unsupported format, details: {$msg}
\ No newline at end of file
+barcode-format-error = Failed to parse image - unsupported format, details: {$msg}
+
+const-OAUTH_TYPE_WECHAT="WeChat login"
+const-OAUTH_TYPE_OIDC="OpenID Connect login"
@@ -19,6 +19,7 @@ app-config-bad = App {$name} configuration error
 app-session-get-error = Failed to retrieve authorization info for app {$client_id}: {$msg}
 app-session-refresh-error = Failed to refresh authorization info for app {$client_id}: {$msg}
 app-session-clear-error = Failed to clear app login info
+app-jwt-key-error = JWT signing key error: {$msg}
 papp-not-match-parent = Selected parent app {$name} does not match account app
 papp-bad-parent = Please select a parent app
 notify-reqwest-build-error = Callback notification request build error: {$msg}
@@ -72,4 +73,16 @@ valid-rule-name-callback_domain = Login redirect domain
 valid-rule-name-scope_data = Authorization scope
 valid-rule-name-oauth_setting_key = OAuth authorization key
 valid-rule-name-oauth_setting_name = OAuth authorization name
-valid-rule-name-oauth_setting_desc = OAuth authorization description
\ No newline at end of file
+valid-rule-name-oauth_setting_desc = OAuth authorization description
+app-org-not-found = Organization ({$id}) not found
+app-org-no-perm = You do not have permission on organization {$name}
+app-org-exists-app = Organization {$name} still owns ({$total}) apps, transfer them before deleting
+app-org-last-owner = Organization {$name} must keep at least one owner
+app-org-not-member = User ({$user_id}) is not a member of organization {$name}
+app-org-invite-bad-role = Can not invite a user as organization owner
+app-org-invite-invalid = Invitation is invalid or expired
+app-org-invite-email-mismatch = Please bind and verify email {$email} before accepting the invitation
+app-transfer-not-support = App {$name} is a sub app and can not be transferred
+app-transfer-bad-user = Invalid target user
+app-transfer-invalid = The transfer request is invalid or has been handled
+valid-rule-name-org_name = Organization name
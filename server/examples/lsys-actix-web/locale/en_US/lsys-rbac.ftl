@@ -1,5 +1,6 @@
 rbac-access-unauth = User {$user_id} is not authorized for resource {$res_name}, operation {$op_name}
 rbac-access-block = User {$user_id} is blocked from resource {$res_name}, operation {$op_name}
+rbac-access-scope = Resource {$res_name} operation {$op_name} is outside the scope of the personal access token (user ID: {$user_id})
 rbac-parse-res-str-fail = Failed to parse permission string: {$token}
 rbac-res-exits = Resource [{$name}:{$res_type}:{$res_data}] already exists
 rbac-role-exist = Role already exists: {$name}-{$key}
@@ -41,4 +42,31 @@ valid-rule-name-op_key_data = Operation ID
 valid-rule-name-op_key = Operation ID
 valid-rule-name-op_name = Operation name
 valid-rule-name-role_key = Role ID
-valid-rule-name-role_name = Role name
\ No newline at end of file
+valid-rule-name-role_name = Role name
+valid-rule-name-reason = Request reason
+valid-rule-name-duration = Access duration
+valid-rule-name-audit_note = Review note
+rbac-role-request-exist = A pending request for role {$name} already exists
+rbac-role-request-is-member = You are already a member of role {$name}
+rbac-role-request-status-wrong = Request {$id} in status [{$status}] cannot perform this operation
+rbac-role-request-role-wrong = Request {$id} does not belong to role {$role_id}
+status-RbacRoleRequestStatus-Pending = Pending
+status-RbacRoleRequestStatus-Approved = Approved
+status-RbacRoleRequestStatus-Rejected = Rejected
+status-RbacRoleRequestStatus-Cancel = Cancelled
+status-RbacRoleRequestStatus-Expired = Expired
+valid-rule-name-exclusive_name = Exclusive constraint name
+rbac-role-exclusive-min-role = An exclusive constraint requires at least two different roles
+rbac-role-exclusive-bad-role = Role [{$name}:{$role_id}] does not exist or does not belong to the constraint owner
+rbac-role-exclusive-conflict = User {$user_id} already holds role {$role_id}, which is mutually exclusive with role {$role_name}: {$name}
+rbac-role-merge-exclusive-conflict = Merging user {$from_user_id} into user {$to_user_id} would give it mutually exclusive roles {$role_ids}: {$name}
+rbac-access-exclusive = User {$user_id} is not authorized for resource {$res_name}, operation {$op_name}: active roles {$role_id} are mutually exclusive
+status-RbacRoleExclusiveStatus-Enable = Active
+status-RbacRoleExclusiveStatus-Delete = Deleted
+rbac-delegate-bad-user = Cannot delegate administration to user {$user_id}
+rbac-delegate-bad-scope = Role [{$name}:{$role_id}] cannot be used as a delegation scope; it must be a custom user role of the same owner
+rbac-delegate-self = Delegated admin {$user_id} cannot change their own membership of role [{$name}:{$role_id}]
+rbac-delegate-role-denied = Delegated admin {$user_id} does not hold role [{$name}:{$role_id}] and the role is not delegable
+rbac-delegate-user-out-scope = User {$user_id} is outside the delegation scope role {$role_id}
+status-RbacRoleDelegateStatus-Enable = Active
+status-RbacRoleDelegateStatus-Delete = Deleted
\ No newline at end of file
@@ -1,9 +1,12 @@
 auth-not-user = User {$name} does not exist
 auth-bad-password = Incorrect password
 auth-not-set-password = Password login not enabled for user
+auth-reauth-not-match = The verified account is not the current login account
 user-status-invalid = User status is invalid [{$user}:{$status}]
 auth-user-captcha = Captcha required for user {$user} login
 auth-user-disable = User {$user} has been disabled
+auth-ldap-disable = LDAP login is not enabled
+auth-ldap-error = LDAP authentication failed for user {$name}: {$msg}
 account-email-exits-other-account = Email [{$email}] is already linked to another account [{$id}]
 account-external-other-bind = External account [{$name}] is already linked to another account [{$id}]
 account-mobile-exits = Phone number [{$mobile}] is already linked to another account [{$id}]
@@ -23,10 +26,20 @@ auth-not-support-reload = Failed to reload user info
 check-user-lock = User {$user} is locked, will unlock in {$time} seconds
 account-bad-session = Login account parsing error: {$msg}
 auth-bad-session = Failed to reload login account: {$msg}
+auth-impersonate-status-bad = Account {$account_id} is not enabled and cannot be impersonated
 # Status
 status-AccountStatus-Enable = Active
 status-AccountStatus-Init = Initial
 status-AccountStatus-Delete = Deleted
+status-AccountDeleteStatus-Pending = Pending deletion
+status-AccountDeleteStatus-Restore = Restored
+status-AccountDeleteStatus-Finish = Deleted
+status-AccountDeleteStatus-Fail = Deletion failed
+status-AccountInviteStatus-Enable = Available
+status-AccountInviteStatus-Disable = Disabled
+status-AccountRegisterStatus-Pending = Pending approval
+status-AccountRegisterStatus-Approved = Approved
+status-AccountRegisterStatus-Reject = Rejected
 status-AccountEmailStatus-Init = Pending verification
 status-AccountEmailStatus-Valid = Verified
 status-AccountEmailStatus-Delete = Deleted
@@ -81,4 +94,45 @@ valid-rule-name-reg_ip = Registration IP
 valid-rule-name-username = User login name
 valid-rule-name-user_password = User password
 valid-rule-name-nickname = Nickname
-valid-rule-name-login_code = Login verification code
\ No newline at end of file
+valid-rule-name-login_code = Login verification code
+account-token-scope-empty = Personal access token scope cannot be empty
+account-token-scope-long = Too many personal access token scopes
+account-token-bad-expire = Personal access token must expire within {$max_day} days
+account-token-not-allow = This operation is not allowed with a personal access token
+valid-rule-name-token_name = Token name
+valid-rule-name-token_scope = Token scope
+account-security-event-not-find = Security event {$id} does not exist or has been handled
+account-merge-same = Cannot merge an account into itself
+account-merge-bad-token = Merging requires a login token of the account being merged, personal access tokens are not supported
+account-export-interval = Personal data was exported recently, please try again after {$time}
+account-export-expired = The export file does not exist or the download link has expired
+account-export-file-error = Failed to read export file {$id}: {$msg}
+login-email-link-interval = Login links are sent too frequently, please try again in {$time} seconds
+login-email-link-expired = The login link does not exist or has expired
+login-email-link-need-confirm = This login link must be completed on the device that started the login
+login-email-link-not-confirm = This login link does not need confirmation, open it directly to log in
+valid-rule-name-login_token = Login link token
+valid-rule-name-wait_id = Login wait ID
+account-password-min-length = The password must be at least {$len} characters long
+account-password-need-lower = The password must contain a lowercase letter
+account-password-need-upper = The password must contain an uppercase letter
+account-password-need-number = The password must contain a number
+account-password-need-symbol = The password must contain a symbol
+account-password-contain-account = The password must not contain your login name or email
+account-password-history = The password must not match any of your last {$num} passwords
+account-password-breached = This password has appeared {$count} times in public data breaches, please choose another one
+account-invite-bad-param = The invitation code usage limit must be greater than 0 and the expiry must be in the future
+account-invite-invalid = The invitation code is invalid or has expired
+account-invite-not-found = The invitation code does not exist
+account-invite-user-disable = Users are not allowed to issue invitation codes
+account-register-need-invite = Registration currently requires an invitation code
+account-register-email-domain = Only emails from these domains may register: {$domain}
+account-register-not-pending = This registration has already been processed
+account-search-not-enable = The account search index is unavailable, please check the index directory setting
+account-search-index-error = Account search index error: {$msg}
+account-search-bad-facet = Unsupported search filter: {$facet}
+account-import-empty = Import data cannot be empty
+account-import-exists = {$type} {$value} is already used by account {$id}
+account-import-duplicate = Duplicate {$type} {$value} in import data
+account-import-bad-mobile = Mobile {$mobile} is invalid, expected area code-mobile
+account-import-bad-password = Password format {$format} is unsupported or the hash is invalid
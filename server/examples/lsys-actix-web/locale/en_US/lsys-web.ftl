@@ -5,6 +5,7 @@ user-external-other-bind = Account {$name} is already linked to another user: {$
 rbac-unkown-res    = Resource {$res} does not exist
 address-miss-city = Please select a district/county-level address
 address-bad-area = Submitted area code is invalid
+address-info-mismatch = The area name does not match the area code, expected: {$info}
 mail-bind-other-user = Email is already linked to another account [{$other_user_id}]
 db-not-found = No records: {$msg}
 db-error = Database error: {$msg}
@@ -29,11 +30,13 @@ area-not-enable = Address library not enabled
 barcode-bad-format-error = Barcode format error {$msg}
 bad-session-data = Unsupported login data type
 auth-need-captcha = Please enter verification code for CODE login
+auth-reauth-param-bad = Please provide a password, or an email or mobile with its verification code
 app-oauth-login-bad-scope = App lacks authorization: {$scope_data}
 not-system-app-confirm = Not a system app
 role-perm-bad-op = Invalid resource operation ID: {$op_id}
 role-perm-bad-res = Invalid resource ID: {$res_id}
 role-user-not-system-user = User {$user_name}[{$user_id}] is not a system user, belongs to app: {$app_id}
+role-delegable-not-system = Role {$name}[{$role_id}] is not a system role and cannot be marked delegable here
 role-user-not-found = User ID {$user_id} does not exist
 not-user-app-confirm = Not a user app
 app-is-subapp = This app is a sub-app
@@ -56,6 +59,8 @@ res-op-admin-app-mail-config = Email app configuration
 res-op-admin-app-mail-mgr = Email app management
 res-op-admin-site-setting = Site configuration
 res-op-admin-manage-user = User management
+res-op-admin-impersonate-user = Impersonate user
+res-op-admin-export-user-password = Export user password hashes
 res-op-admin-see-change-log = View change logs
 res-admin-global-public = System public permission
 res-op-admin-register = User registration
@@ -88,4 +93,24 @@ res-op-user-app-sms-send = Send user SMS app messages
 valid-rule-name-area_code = Area code
 # Dictionaries 
 const-SMS_NOTIFY_METHOD = SMS send result callback
-const-SUB_APP_SECRET_NOTIFY_METHOD = Sub-app secret key change callback
\ No newline at end of file
+const-SUB_APP_SECRET_NOTIFY_METHOD = Sub-app secret key change callback
+role-request-not-system-role = Role {$role_id} belongs to app {$app_id}, only system users can request system roles
+role-request-not-found = Request {$id} not found
+role-delegate-not-found = Delegated admin record {$id} not found
+rbac-audit-stat-bad-group = Unsupported statistics group: {$group}
+rbac-audit-stat-bad-period = Unsupported statistics period: {$period}
+rbac-audit-export-error = Export audit data failed: {$msg}
+rbac-token-scope-bad = Scope {$scope} is not available for personal access tokens
+scim-auth-fail = SCIM authentication failed, use Bearer client_id:app_secret
+scim-not-sub-app = Sub apps cannot enable user provisioning (SCIM)
+scim-bad-filter = Invalid filter {$filter}: {$msg}
+scim-filter-not-support = Filter must contain an eq condition on one of: {$attrs}
+scim-bad-value = Invalid value for attribute {$attr}
+scim-bad-patch = Invalid PATCH request: {$msg}
+scim-bad-body = Request body is not a valid JSON object
+scim-user-exists = A user with {$attr} {$value} already exists
+scim-group-exists = A group named {$name} already exists
+account-import-parse-error = Parse import data failed: {$msg}
+account-import-bad-format = Unsupported data format: {$format}
+account-export-error = Export accounts failed: {$msg}
+account-impersonate-deny = Account {$account_id} cannot be impersonated
@@ -5,6 +5,10 @@ access-bad-code = 解析登陆CODE异常:{$msg}
 access-not-login = 用户未登录
 access-not-account = 非系统内部账号,属于应用:{$appid}
 access-parse-error = 解析用户ID错误,详细:{$msg}
+access-refresh-token-reuse = 刷新token已被使用,相关登录已注销:Session({$id})
+access-need-reauth = 该操作需在{$time}秒内重新验证身份
+access-impersonate-deny = 模拟登录(操作用户:{$user_id})不允许执行该操作
+access-step-up-deny = 该登录方式({$login_type})不允许执行敏感操作
 
 # 状态
 
@@ -11,6 +11,7 @@ barcode-format-error = 无法解析图片，格式不支持,详细:{$msg}
 
 
 const-OAUTH_TYPE_WECHAT="微信登陆"
+const-OAUTH_TYPE_OIDC="OpenID Connect 登陆"
 
 
 
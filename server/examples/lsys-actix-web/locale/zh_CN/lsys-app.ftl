@@ -23,6 +23,7 @@ app-config-bad = 应用 {$name}配置异常
 app-session-get-error = 获取应用{$client_id}的授权信息失败:{$msg}
 app-session-refresh-error = 重置应用{$client_id}的授权信息失败:{$msg}
 app-session-clear-error = 清除应用的登录信息失败:{$msg}
+app-jwt-key-error = JWT签名密钥处理失败:{$msg}
 papp-not-match-parent = 当前选择的父应用{$name}于账号应用不匹配
 papp-bad-parent = 请选择父应用
 del-notify-data-bad-status = 当前回调不可删除
@@ -107,3 +108,16 @@ valid-rule-name-scope_data = 授权范围
 valid-rule-name-oauth_setting_key = OAUTH授权KEY
 valid-rule-name-oauth_setting_name = OAUTH授权名称
 valid-rule-name-oauth_setting_desc = OAUTH授权介绍
+
+app-org-not-found = 组织({$id})不存在
+app-org-no-perm = 你没有组织 {$name} 的操作权限
+app-org-exists-app = 组织 {$name} 下存在({$total})个应用，请先转移应用后再删除
+app-org-last-owner = 组织 {$name} 至少需保留一个所有者
+app-org-not-member = 用户({$user_id})不是组织 {$name} 的成员
+app-org-invite-bad-role = 不能邀请用户成为组织所有者
+app-org-invite-invalid = 邀请无效或已过期
+app-org-invite-email-mismatch = 请先绑定并验证邮箱 {$email} 后再接受邀请
+app-transfer-not-support = 应用 {$name} 为子应用，不支持转移
+app-transfer-bad-user = 目标用户无效
+app-transfer-invalid = 转移请求无效或已处理
+valid-rule-name-org_name = 组织名称
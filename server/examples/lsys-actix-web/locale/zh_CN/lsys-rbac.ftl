@@ -1,5 +1,6 @@
 rbac-access-unauth = 资源 {$res_name} 对应的操作 {$op_name} 未授权 (用户ID:{$user_id})
 rbac-access-block = 资源 {$res_name} 对应的操作 {$op_name} 被禁用 (用户ID:{$user_id})
+rbac-access-scope = 资源 {$res_name} 对应的操作 {$op_name} 超出个人访问令牌授权范围 (用户ID:{$user_id})
 rbac-parse-res-str-fail = 解析权限字符串失败:{$token}
 rbac-res-exits = 资源 {$res_type}({$res_data}) 已经存在,名称为:{$old_name}
 rbac-role-exist = 存在角色( {$name} : {$key} )  跟当前操作冲突,请检查key或名是否重复
@@ -64,4 +65,34 @@ valid-rule-name-op_key_data = 操作标识
 valid-rule-name-op_key = 操作标识
 valid-rule-name-op_name = 操作名称
 valid-rule-name-role_key = 角色标识
-valid-rule-name-role_name = 角色名称
\ No newline at end of file
+valid-rule-name-role_name = 角色名称
+valid-rule-name-reason = 申请原因
+valid-rule-name-duration = 授权时长
+valid-rule-name-audit_note = 审核备注
+
+rbac-role-request-exist = 已存在待审核的角色({$name})申请
+rbac-role-request-is-member = 你已是角色({$name})的成员,无需申请
+rbac-role-request-status-wrong = 申请({$id})当前状态[{$status}]不能进行此操作
+rbac-role-request-role-wrong = 申请({$id})不属于角色({$role_id})
+
+status-RbacRoleRequestStatus-Pending = 待审核
+status-RbacRoleRequestStatus-Approved = 已授权
+status-RbacRoleRequestStatus-Rejected = 已拒绝
+status-RbacRoleRequestStatus-Cancel = 已取消
+status-RbacRoleRequestStatus-Expired = 已到期
+
+valid-rule-name-exclusive_name = 互斥约束名称
+rbac-role-exclusive-min-role = 互斥约束至少需要两个不同角色
+rbac-role-exclusive-bad-role = 角色({$name}:{$role_id})不存在或不属于当前约束所有者
+rbac-role-exclusive-conflict = 用户({$user_id})已拥有角色({$role_id}),与角色({$role_name})存在互斥约束:{$name}
+rbac-role-merge-exclusive-conflict = 用户({$from_user_id})合并到用户({$to_user_id})后将同时拥有互斥角色({$role_ids}):{$name}
+rbac-access-exclusive = 资源 {$res_name} 对应的操作 {$op_name} 未授权,当前生效角色({$role_id})存在互斥约束 (用户ID:{$user_id})
+status-RbacRoleExclusiveStatus-Enable = 启用
+status-RbacRoleExclusiveStatus-Delete = 删除
+rbac-delegate-bad-user = 不能委托给用户({$user_id})
+rbac-delegate-bad-scope = 角色({$name}:{$role_id})不能作为委托用户范围,需为同一所有者的自定义用户角色
+rbac-delegate-self = 委托管理员({$user_id})不能变更自身在角色({$name}:{$role_id})中的授权
+rbac-delegate-role-denied = 委托管理员({$user_id})未拥有角色({$name}:{$role_id})且该角色不允许委托分配
+rbac-delegate-user-out-scope = 用户({$user_id})不在委托范围角色({$role_id})内
+status-RbacRoleDelegateStatus-Enable = 启用
+status-RbacRoleDelegateStatus-Delete = 删除
\ No newline at end of file
@@ -3,9 +3,12 @@
 auth-not-user = 用户 {$name} 不存在
 auth-bad-password = 用户密码错误
 auth-not-set-password = 用户未启用密码登录
+auth-reauth-not-match = 验证的账号与当前登录账号不一致
 user-status-invalid = 用户状态异常({$user}:{$status})
 auth-user-captcha = 用户{$user}登录时需要验证码
 auth-user-disable = 用户{$user}已被禁用
+auth-ldap-disable = 未启用LDAP登录
+auth-ldap-error = 用户{$name}LDAP验证失败:{$msg}
 account-email-exits-other-account = 邮箱({$email})已绑定其他账号({$id})
 account-external-other-bind = 外部账号({$name})已绑定其他账号({$id})
 account-mobile-exits = 手机号({$mobile})已绑定其他账号({$id})
@@ -25,6 +28,7 @@ auth-not-support-reload = 重新加载当前用户信息失败
 check-user-lock = 用户{$user}已被锁定,将在{$time}秒后恢复
 account-bad-session = 登陆账号解析异常:{$msg},数据为:{$data}
 auth-bad-session = 重新加载登陆账号异常:{$msg}
+auth-impersonate-status-bad = 账号{$account_id}未启用,无法模拟登录
 
 
 # 状态
@@ -34,6 +38,15 @@ auth-bad-session = 重新加载登陆账号异常:{$msg}
 status-AccountStatus-Enable = 启用
 status-AccountStatus-Init = 初始
 status-AccountStatus-Delete = 删除
+status-AccountDeleteStatus-Pending = 等待注销
+status-AccountDeleteStatus-Restore = 已恢复
+status-AccountDeleteStatus-Finish = 已注销
+status-AccountDeleteStatus-Fail = 注销失败
+status-AccountInviteStatus-Enable = 可用
+status-AccountInviteStatus-Disable = 已停用
+status-AccountRegisterStatus-Pending = 等待审核
+status-AccountRegisterStatus-Approved = 已通过
+status-AccountRegisterStatus-Reject = 已拒绝
 
 
 
@@ -129,3 +142,44 @@ valid-rule-name-user_password = 用户密码
 valid-rule-name-nickname = 昵称
 valid-rule-name-login_code = 登陆验证码
 
+account-token-scope-empty = 个人访问令牌授权范围不能为空
+account-token-scope-long = 个人访问令牌授权范围过多
+account-token-bad-expire = 个人访问令牌有效期需在 {$max_day} 天内
+account-token-not-allow = 个人访问令牌不能执行该操作
+valid-rule-name-token_name = 令牌名称
+valid-rule-name-token_scope = 令牌授权范围
+account-security-event-not-find = 安全事件 {$id} 不存在或已处理
+account-merge-same = 不能将账号合并到自身
+account-merge-bad-token = 合并账号需提供被合并账号的登录令牌,不支持个人访问令牌
+account-export-interval = 近期已导出过个人数据,请在 {$time} 后重试
+account-export-expired = 导出文件不存在或下载链接已过期
+account-export-file-error = 读取导出文件 {$id} 失败: {$msg}
+login-email-link-interval = 登录链接发送过于频繁,请在 {$time} 秒后重试
+login-email-link-expired = 登录链接不存在或已过期
+login-email-link-need-confirm = 该登录链接需在发起登录的设备上完成登录
+login-email-link-not-confirm = 该登录链接无需确认,请直接打开链接登录
+valid-rule-name-login_token = 登录链接令牌
+valid-rule-name-wait_id = 登录等待标识
+account-password-min-length = 密码长度不能少于 {$len} 位
+account-password-need-lower = 密码必须包含小写字母
+account-password-need-upper = 密码必须包含大写字母
+account-password-need-number = 密码必须包含数字
+account-password-need-symbol = 密码必须包含符号
+account-password-contain-account = 密码不能包含登录名或邮箱
+account-password-history = 不能使用最近 {$num} 次使用过的密码
+account-password-breached = 该密码已在公开泄露的数据中出现 {$count} 次,请更换密码
+account-invite-bad-param = 邀请码使用次数需大于0且过期时间需晚于当前时间
+account-invite-invalid = 邀请码无效或已过期
+account-invite-not-found = 邀请码不存在
+account-invite-user-disable = 系统未开放用户发放邀请码
+account-register-need-invite = 当前仅允许持有邀请码注册
+account-register-email-domain = 仅允许以下域名的邮箱注册:{$domain}
+account-register-not-pending = 该注册申请已处理
+account-search-not-enable = 账号搜索索引不可用,请检查索引目录配置
+account-search-index-error = 账号搜索索引异常:{$msg}
+account-search-bad-facet = 不支持的筛选条件:{$facet}
+account-import-empty = 导入数据不能为空
+account-import-exists = {$type} {$value} 已被账号 {$id} 使用
+account-import-duplicate = 导入数据中 {$type} {$value} 重复
+account-import-bad-mobile = 手机号 {$mobile} 格式错误,应为 区号-手机号
+account-import-bad-password = 密码格式 {$format} 不支持或密码HASH无效
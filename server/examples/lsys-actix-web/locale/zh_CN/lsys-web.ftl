@@ -6,6 +6,7 @@ user-external-other-bind = 账号{$name}已绑定其他用户:{$name}
 rbac-unkown-res = 资源{$res}不存在
 address-miss-city = 地址请选择到县区一级
 address-bad-area = 提交的区域编码不存在
+address-info-mismatch = 区域名称与区域编码不一致,应为:{$info}
 mail-bind-other-user = 邮箱已绑定其他账号({$other_user_id})
 db-not-found = 不存在相关记录
 db-error = sqlx错误:{$msg}
@@ -30,11 +31,13 @@ area-not-enable = 地址库未启用
 barcode-bad-format-error = 条码格式异常{$msg}
 bad-session-data = 登陆数据类型不支持
 auth-need-captcha = Code登陆请输入验证码
+auth-reauth-param-bad = 请提供密码,或邮箱、手机号及其验证码
 app-oauth-login-bad-scope = 应用不存在授权:{$scope_data}
 not-system-app-confirm = 非系统应用
 role-perm-bad-op = 资源操作id:{$op_id}数据丢失或不可用
 role-perm-bad-res = 资源id:{$res_id}数据丢失或不可用
 role-user-not-system-user = 用户{$user_name}({$user_id})非系统用户，属于应用:{$app_id}
+role-delegable-not-system = 角色{$name}({$role_id})非系统角色,不能在此设置为可委托分配
 role-user-not-found = 无法添加用户ID{$user_id}到角色,用户id不存在
 not-user-app-confirm = 非子应用
 app-is-subapp = 该应用为子应用
@@ -61,6 +64,8 @@ res-op-admin-sys-mail-config = 邮件应用配置
 res-op-admin-sys-mail-mgr = 邮件应用管理
 res-op-admin-site-setting = 站点配置
 res-op-admin-manage-user = 用户管理
+res-op-admin-impersonate-user = 模拟用户登录
+res-op-admin-export-user-password = 导出用户密码HASH
 res-op-admin-see-change-log = 查看变更日志
 res-op-admin-sys-sms-manage = 短信设置管理
 res-op-admin-sys-sms-send = 短信发送设置
@@ -113,13 +118,16 @@ const-APP_FEATURE_BARCODE = 条码服务
 const-APP_FEATURE_MAIL = 邮件服务
 const-APP_FEATURE_RBAC = 权限服务
 const-APP_FEATURE_SMS = 短信服务
+const-APP_FEATURE_SCIM = 用户同步(SCIM)
 
 var-login-type-email = 邮箱
 var-login-type-email-code = 邮箱验证码
+var-login-type-email-link = 邮箱登录链接
 var-login-type-name = 账号
 var-login-type-mobile = 手机号
 var-login-type-mobile-code = 短信验证码
 var-login-type-external = 外部账号
+var-login-type-ldap = LDAP账号
 
 
 var-smtp-config= SMTP服务器配置
@@ -142,6 +150,7 @@ var-app = 应用操作
 var-app-request = 应用请求处理
 var-app-oauth-client-set = 应用oauth设置
 var-app-oauth-client-secret-set = 应用oauth密钥修改
+var-app-oauth-client-refresh-reuse = 应用oauth刷新token重复使用
 var-app-oauth-server-set = 应用oauth服务设置
 var-app-view-secret = 应用查看密钥
 var-app-notify-set = 应用回调设置
@@ -152,6 +161,12 @@ var-account-external =  账号关联外部账号
 var-account-info = 设置账号信息
 var-account-mobile = 账号手机修改
 var-account-name =  账号名称修改
+var-account-delete = 账号注销
+var-account-invite = 注册邀请码
+var-account-register = 注册审核
+var-account-import = 账号批量导入
+var-account-bulk-export = 账号批量导出
+var-account-impersonate = 模拟登录
 var-user =  用户信息
 var-set-password = 账号设置密码
 var-rbac-op = 权限资源操作管理
@@ -167,4 +182,24 @@ var-sender-app-config = 发送应用配置
 var-sender-config = 发送配置
 var-barcode-create-config = 二维码创建
 var-barcode-parse-record = 二维码解析
-var-message-view = 发送消息查看
\ No newline at end of file
+var-message-view = 发送消息查看
+role-request-not-system-role = 角色({$role_id})属于应用{$app_id},仅系统用户可申请系统角色
+role-request-not-found = 申请记录({$id})不存在
+role-delegate-not-found = 委托管理记录({$id})不存在
+rbac-audit-stat-bad-group = 不支持的统计分组:{$group}
+rbac-audit-stat-bad-period = 不支持的统计周期:{$period}
+rbac-audit-export-error = 导出审计记录失败:{$msg}
+rbac-token-scope-bad = 授权范围 {$scope} 不可用于个人访问令牌
+scim-auth-fail = SCIM 认证失败,请使用 Bearer client_id:app_secret 认证
+scim-not-sub-app = 子应用不能开通用户同步(SCIM)功能
+scim-bad-filter = 过滤条件 {$filter} 有误:{$msg}
+scim-filter-not-support = 过滤条件需包含以下任一属性的 eq 条件:{$attrs}
+scim-bad-value = 属性 {$attr} 的值有误
+scim-bad-patch = PATCH 请求有误:{$msg}
+scim-bad-body = 请求内容不是有效的JSON对象
+scim-user-exists = 属性 {$attr} 值为 {$value} 的用户已存在
+scim-group-exists = 名称为 {$name} 的组已存在
+account-import-parse-error = 解析导入数据失败:{$msg}
+account-import-bad-format = 不支持的数据格式:{$format}
+account-export-error = 导出账号失败:{$msg}
+account-impersonate-deny = 账号{$account_id}不允许模拟登录
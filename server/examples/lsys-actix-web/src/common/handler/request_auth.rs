@@ -11,7 +11,7 @@ use lsys_web::lsys_core::{now_time, IntoFluentMessage, RequestEnv};
 
 use actix_http::header;
 use async_trait::async_trait;
-use lsys_web::lsys_user::dao::{UserAuthSession, UserAuthToken};
+use lsys_web::lsys_user::dao::{AccountToken, UserAuthSession, UserAuthToken};
 use lsys_web::{
     common::{
         JsonData, JsonResponse, RequestAuthDao as Request, RequestSessionToken, RestAuthQueryDao,
@@ -20,7 +20,7 @@ use lsys_web::{
     dao::WebDao,
 };
 
-use super::{ResponseJson, AUTH_COOKIE_NAME};
+use super::{personal_token_header, ResponseJson, AUTH_COOKIE_NAME};
 
 //正常用户登陆，如cookie登陆
 
@@ -63,7 +63,7 @@ impl FromRequest for UserAuthQuery {
                     .get("X-Device-ID")
                     .and_then(|e| e.to_str().ok());
                 let ip = req.connection_info();
-                let env = match RequestEnv::new(
+                let mut env = match RequestEnv::new(
                     Some(&user_lang),
                     ip.realip_remote_addr(),
                     request_id,
@@ -81,6 +81,8 @@ impl FromRequest for UserAuthQuery {
                         .into())
                     }
                 };
+                env.personal_token = personal_token_header(req).map(AccountToken::token_hash);
+                env.request_path = Some(req.path().to_string());
                 ok(Self {
                     inner: Request::new(
                         app_dao.clone().into_inner(),
@@ -1,34 +1,32 @@
-use std::{pin::Pin, str::FromStr};
+use std::{pin::Pin, str::FromStr, sync::Arc};
 
 use actix_web::{dev::Payload, FromRequest, HttpRequest};
 
 use lsys_web::common::{
     JsonData, JsonResponse, JsonResult, RequestSessionToken, RequestSessionTokenPaser,
 };
-use lsys_web::lsys_user::dao::UserAuthToken;
+use lsys_web::lsys_app::dao::AppJwtKey;
+use lsys_web::lsys_user::dao::{AccountToken, UserAuthToken};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 
 use async_trait::async_trait;
-use jsonwebtoken::{decode, DecodingKey, TokenData, Validation};
-use std::{
-    future::Future,
-    task::{Context, Poll},
-};
+use jsonwebtoken::{Header, TokenData, Validation};
+use std::future::Future;
 
 use super::ResponseJson;
 
 #[derive(Clone)]
 pub struct JwtQueryConfig {
-    pub decode_key: DecodingKey,
+    pub jwt_key: Arc<AppJwtKey>,
     pub validation: Validation,
 }
 
 impl JwtQueryConfig {
-    /// Set maximum accepted payload size. By default this limit is 16kB.
-    pub fn new(decode_key: DecodingKey, validation: Validation) -> Self {
+    /// 使用系统管理的签名密钥验证,签名算法由密钥决定
+    pub fn new(jwt_key: Arc<AppJwtKey>, validation: Validation) -> Self {
         Self {
-            decode_key,
+            jwt_key,
             validation,
         }
     }
@@ -47,86 +45,91 @@ impl JwtClaims {
     }
 }
 
-pub struct JwtExtractFut {
-    req: HttpRequest,
+//Authorization 中的个人访问令牌
+pub(crate) fn personal_token_header(req: &HttpRequest) -> Option<&str> {
+    req.headers()
+        .get("Authorization")?
+        .to_str()
+        .ok()?
+        .trim_start()
+        .strip_prefix("Bearer ")
+        .map(|e| e.trim())
+        .filter(|e| AccountToken::is_token(e))
 }
 
-impl Future for JwtExtractFut {
-    type Output = Result<JwtQuery, ResponseJson>;
-    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
-        let res = match self.req.headers().get("Authorization") {
-            Some(head) => {
-                match head.to_str() {
-                    Ok(mut token) => {
-                        token = token.trim_start();
-                        if !token.starts_with("Bearer ") {
-                            Err(JsonResponse::data(
-                                JsonData::error().set_sub_code("jwt_parse_header"),
-                            )
-                            .set_message("not bearer header")
-                            .into())
-                        } else {
-                            token = &token[7..];
-                            token = token.trim();
-
-                            let config_opt = self.req.app_data::<JwtQueryConfig>();
-                            match config_opt {
-                                Some(config) => {
-                                    let token_data_opt = decode::<JwtClaims>(
-                                        token,
-                                        &config.decode_key,
-                                        &config.validation,
-                                    );
-                                    match token_data_opt {
-                                    Ok(token_data) => {
-                                       // let token_str = token.to_owned();
-                                        Ok(JwtQuery {
-                                            token_data,
-                                        //    token_str,
-                                        })
-                                    }
-                                    Err(e) => Err(match e.kind() {
-                                        jsonwebtoken::errors::ErrorKind::InvalidToken
-                                        | jsonwebtoken::errors::ErrorKind::InvalidSignature
-                                        | jsonwebtoken::errors::ErrorKind::InvalidAlgorithmName
-                                        | jsonwebtoken::errors::ErrorKind::RsaFailedSigning
-                                        | jsonwebtoken::errors::ErrorKind::MissingRequiredClaim(_)
-                                        | jsonwebtoken::errors::ErrorKind::ExpiredSignature
-                                        | jsonwebtoken::errors::ErrorKind::MissingAlgorithm
-                                        | jsonwebtoken::errors::ErrorKind::InvalidAlgorithm
-                                        | jsonwebtoken::errors::ErrorKind::ImmatureSignature
-                                        | jsonwebtoken::errors::ErrorKind::InvalidSubject => {
-                                            JsonResponse::data(JsonData::error().set_sub_code("jwt_bad_token"))
-                                            .set_message(e).into()
-                                        }
-                                        _ => JsonResponse::data(JsonData::error().set_sub_code("jwt_parse_system"))
-                                        .set_message(e)
-                                            .into(),
-                                    }),
-                                }
-                                }
-                                None => Err(JsonResponse::data(
-                                    JsonData::error().set_sub_code("jwt_config"),
-                                )
-                                .set_message("jwt config not find")
-                                .into()),
-                            }
-                        }
-                    }
-                    Err(e) => Err(JsonResponse::data(
-                        JsonData::error().set_sub_code("jwt_parse_header"),
-                    )
-                    .set_message(e)
-                    .into()),
-                }
-            }
-            None => Err(
+async fn jwt_extract(req: HttpRequest) -> Result<JwtQuery, ResponseJson> {
+    let head = match req.headers().get("Authorization") {
+        Some(head) => head,
+        None => {
+            return Err(
                 JsonResponse::data(JsonData::error().set_sub_code("jwt_miss_header"))
                     .set_message("jwt miss Authorization header")
                     .into(),
-            ),
-        };
-        Poll::Ready(res)
+            )
+        }
+    };
+    let mut token = match head.to_str() {
+        Ok(token) => token.trim_start(),
+        Err(e) => {
+            return Err(
+                JsonResponse::data(JsonData::error().set_sub_code("jwt_parse_header"))
+                    .set_message(e)
+                    .into(),
+            )
+        }
+    };
+    if !token.starts_with("Bearer ") {
+        return Err(
+            JsonResponse::data(JsonData::error().set_sub_code("jwt_parse_header"))
+                .set_message("not bearer header")
+                .into(),
+        );
+    }
+    token = token[7..].trim();
+    //个人访问令牌不经过JWT解码,服务器端按登录token处理
+    if AccountToken::is_token(token) {
+        return Ok(JwtQuery {
+            token_data: TokenData {
+                header: Header::default(),
+                claims: JwtClaims::new(0, UserAuthToken::new(0, token, 0, 0).to_string(), None),
+            },
+        });
+    }
+    let config = match req.app_data::<JwtQueryConfig>() {
+        Some(config) => config,
+        None => {
+            return Err(
+                JsonResponse::data(JsonData::error().set_sub_code("jwt_config"))
+                    .set_message("jwt config not find")
+                    .into(),
+            )
+        }
+    };
+    match config
+        .jwt_key
+        .decode::<JwtClaims>(token, &config.validation)
+        .await
+    {
+        Ok(token_data) => Ok(JwtQuery { token_data }),
+        Err(e) => Err(match e.kind() {
+            jsonwebtoken::errors::ErrorKind::InvalidToken
+            | jsonwebtoken::errors::ErrorKind::InvalidSignature
+            | jsonwebtoken::errors::ErrorKind::InvalidAlgorithmName
+            | jsonwebtoken::errors::ErrorKind::RsaFailedSigning
+            | jsonwebtoken::errors::ErrorKind::MissingRequiredClaim(_)
+            | jsonwebtoken::errors::ErrorKind::ExpiredSignature
+            | jsonwebtoken::errors::ErrorKind::MissingAlgorithm
+            | jsonwebtoken::errors::ErrorKind::InvalidAlgorithm
+            | jsonwebtoken::errors::ErrorKind::ImmatureSignature
+            | jsonwebtoken::errors::ErrorKind::InvalidSubject => {
+                JsonResponse::data(JsonData::error().set_sub_code("jwt_bad_token"))
+                    .set_message(e)
+                    .into()
+            }
+            _ => JsonResponse::data(JsonData::error().set_sub_code("jwt_parse_system"))
+                .set_message(e)
+                .into(),
+        }),
     }
 }
 
@@ -177,8 +180,8 @@ impl JwtQuery {
 
 impl FromRequest for JwtQuery {
     type Error = ResponseJson;
-    type Future = JwtExtractFut;
+    type Future = Pin<Box<dyn Future<Output = Result<JwtQuery, ResponseJson>>>>;
     fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
-        JwtExtractFut { req: req.clone() }
+        Box::pin(jwt_extract(req.clone()))
     }
 }
@@ -4,9 +4,7 @@ use crate::common::handler::{
 
 use actix_web::post;
 
-use jsonwebtoken::{encode, EncodingKey, Header};
 use lsys_web::const_json_format;
-use lsys_web::lsys_core::fluent_message;
 use lsys_web::lsys_user::dao::UserAuthToken;
 
 use lsys_web::common::{JsonData, JsonError, JsonResponse, JsonResult};
@@ -14,15 +12,22 @@ use lsys_web::dao::ShowUserAuthData;
 use lsys_web::handler::api::auth::user_login_from_app_code;
 use lsys_web::handler::api::auth::user_login_from_email;
 use lsys_web::handler::api::auth::user_login_from_email_code;
+use lsys_web::handler::api::auth::user_login_from_email_link;
 use lsys_web::handler::api::auth::user_login_from_external;
+use lsys_web::handler::api::auth::user_login_from_ldap;
 use lsys_web::handler::api::auth::user_login_from_mobile;
 use lsys_web::handler::api::auth::user_login_from_mobile_code;
 use lsys_web::handler::api::auth::user_login_from_name;
 use lsys_web::handler::api::auth::user_login_mobile_send_code;
 use lsys_web::handler::api::auth::AppCodeLoginParam;
 use lsys_web::handler::api::auth::EmailCodeLoginParam;
+use lsys_web::handler::api::auth::EmailLinkConfirmParam;
+use lsys_web::handler::api::auth::EmailLinkLoginParam;
+use lsys_web::handler::api::auth::EmailLinkWaitParam;
 use lsys_web::handler::api::auth::EmailLoginParam;
 use lsys_web::handler::api::auth::EmailSendCodeLoginParam;
+use lsys_web::handler::api::auth::EmailSendLinkLoginParam;
+use lsys_web::handler::api::auth::LdapLoginParam;
 use lsys_web::handler::api::auth::MobileCodeLoginParam;
 use lsys_web::handler::api::auth::MobileLoginParam;
 use lsys_web::handler::api::auth::MobileSendCodeLoginParam;
@@ -30,31 +35,34 @@ use lsys_web::handler::api::auth::NameLoginParam;
 use lsys_web::handler::api::auth::UserAuthDataOptionParam;
 use lsys_web::handler::api::auth::{login_data_from_user_auth, user_external_login_url};
 use lsys_web::handler::api::auth::{mapping_data, user_login_email_send_code};
+use lsys_web::handler::api::auth::{
+    user_login_email_link_confirm, user_login_email_link_wait, user_login_email_send_link,
+};
 use lsys_web::lsys_access::dao::AccessSession;
+use lsys_web_module_oauth::handler::oidc_provider_list;
 use lsys_web_module_oauth::module::{
-    WeChatConfig, WechatCallbackParam, WechatLogin, WechatLoginParam, OAUTH_TYPE_WECHAT,
+    OidcCallbackParam, OidcLogin, OidcLoginParam, WeChatConfig, WechatCallbackParam, WechatLogin,
+    WechatLoginParam, OAUTH_TYPE_OIDC, OAUTH_TYPE_WECHAT,
 };
 use serde::Deserialize;
 use serde_json::json;
 
-async fn jwt_login_data(
+pub(crate) async fn jwt_login_data(
     auth_dao: &UserAuthQuery,
     token: UserAuthToken,
     data: ShowUserAuthData,
 ) -> JsonResult<JsonResponse> {
-    let app_jwt_key = auth_dao
+    let token = auth_dao
         .web_dao
-        .app_core
-        .config
-        .find(None)
-        .get_string("app_jwt_key")
-        .unwrap_or_default();
-    let token = encode(
-        &Header::default(),
-        &JwtClaims::new(token.time_out as i64, token.to_string(), Some(json!(data))),
-        &EncodingKey::from_secret(app_jwt_key.as_bytes()),
-    )
-    .map_err(|e| JsonError::Message(fluent_message!("jwt-encode-error", e)))?;
+        .web_app
+        .app_dao
+        .jwt_key
+        .encode(&JwtClaims::new(
+            token.time_out as i64,
+            token.to_string(),
+            Some(json!(data)),
+        ))
+        .await?;
     let passwrod_timeout = auth_dao
         .web_dao
         .web_user
@@ -82,10 +90,14 @@ pub(crate) async fn login(
         "mapping" => {
             mapping_data(
                 &auth_dao,
-                json!([const_json_format!(auth_dao, OAUTH_TYPE_WECHAT)]),
+                json!([
+                    const_json_format!(auth_dao, OAUTH_TYPE_WECHAT),
+                    const_json_format!(auth_dao, OAUTH_TYPE_OIDC)
+                ]),
             )
             .await
         }
+        "oidc-provider" => oidc_provider_list(&auth_dao).await,
         "sms-send-code" => {
             user_login_mobile_send_code(&json_param.param::<MobileSendCodeLoginParam>()?, &auth_dao)
                 .await
@@ -94,6 +106,26 @@ pub(crate) async fn login(
             user_login_email_send_code(&json_param.param::<EmailSendCodeLoginParam>()?, &auth_dao)
                 .await
         }
+        "email-send-link" => {
+            user_login_email_send_link(&json_param.param::<EmailSendLinkLoginParam>()?, &auth_dao)
+                .await
+        }
+        "email-link-confirm" => {
+            user_login_email_link_confirm(&json_param.param::<EmailLinkConfirmParam>()?, &auth_dao)
+                .await
+        }
+        //原设备等待邮件链接确认,未确认时返回reload,客户端需重新请求
+        "email-link-wait" => {
+            match user_login_email_link_wait(&json_param.param::<EmailLinkWaitParam>()?, &auth_dao)
+                .await
+                .map_err(|e| auth_dao.fluent_error_json_response(&e))?
+            {
+                Some((token, data)) => jwt_login_data(&auth_dao, token, data).await,
+                None => Ok(JsonResponse::data(JsonData::body(
+                    json!({ "reload": true }),
+                ))),
+            }
+        }
         e => {
             let (token, data) = match e {
                 "name" => {
@@ -106,6 +138,9 @@ pub(crate) async fn login(
                 "email" => {
                     user_login_from_email(&json_param.param::<EmailLoginParam>()?, &auth_dao).await
                 }
+                "ldap" => {
+                    user_login_from_ldap(&json_param.param::<LdapLoginParam>()?, &auth_dao).await
+                }
                 "sms-code" => {
                     user_login_from_mobile_code(
                         &json_param.param::<MobileCodeLoginParam>()?,
@@ -120,6 +155,13 @@ pub(crate) async fn login(
                     )
                     .await
                 }
+                "email-link" => {
+                    user_login_from_email_link(
+                        &json_param.param::<EmailLinkLoginParam>()?,
+                        &auth_dao,
+                    )
+                    .await
+                }
                 "app-code" => {
                     user_login_from_app_code(&json_param.param::<AppCodeLoginParam>()?, &auth_dao)
                         .await
@@ -151,23 +193,19 @@ pub(crate) async fn user_data(
             .await
             .map_err(|e| auth_dao.fluent_error_json_response(&e))?;
     let jwt = if let Some(ref ua) = out_auth_data {
-        let app_jwt_key = auth_dao
-            .web_dao
-            .app_core
-            .config
-            .find(None)
-            .get_string("app_jwt_key")
-            .unwrap_or_default();
         let utoken = UserAuthToken::from(&token_data);
-        let token = encode(
-            &Header::default(),
-            &JwtClaims::new(utoken.time_out as i64, utoken.to_string(), Some(json!(ua))),
-            &EncodingKey::from_secret(app_jwt_key.as_bytes()),
-        )
-        .map_err(|e| {
-            auth_dao
-                .fluent_error_json_response(&JsonError::Message(fluent_message!("system-error", e)))
-        })?;
+        let token = auth_dao
+            .web_dao
+            .web_app
+            .app_dao
+            .jwt_key
+            .encode(&JwtClaims::new(
+                utoken.time_out as i64,
+                utoken.to_string(),
+                Some(json!(ua)),
+            ))
+            .await
+            .map_err(|e| auth_dao.fluent_error_json_response(&JsonError::from(e)))?;
         Some(token)
     } else {
         None
@@ -228,6 +266,12 @@ pub struct WechatExternalLoginParam {
     pub login_callback: String,
     pub login_state: String,
 }
+#[derive(Debug, Deserialize)]
+pub struct OidcExternalLoginParam {
+    #[serde(deserialize_with = "lsys_web::common::deserialize_u64")]
+    pub config_id: u64,
+    pub login_callback: String,
+}
 //获取外部登录URL地址
 #[post("/exter_login_url/{method}")]
 pub async fn external_login_url(
@@ -261,6 +305,20 @@ pub async fn external_login_url(
             )
             .await
         }
+        OAUTH_TYPE_OIDC => {
+            let login_param = json_param.param::<OidcExternalLoginParam>()?;
+            let oidc = OidcLogin::load(req_dao.web_dao.clone(), login_param.config_id)
+                .await
+                .map_err(|e| req_dao.fluent_error_json_response(&e.into()))?;
+            user_external_login_url(
+                &oidc,
+                &OidcLoginParam {
+                    callback_url: login_param.login_callback,
+                },
+                &req_dao,
+            )
+            .await
+        }
         name => handler_not_found!(name),
     };
     Ok(res
@@ -368,52 +426,42 @@ pub async fn external_state_callback(
         .into())
 }
 
-// //外部登录完成回调地址,不包含扫码登录,目前没用到
-// #[derive(Debug, Deserialize)]
-// pub struct ExternalCallbackParam {
-//     pub login_type: String,
-//     pub code: String,
-//     pub callback_state: String,
-// }
-
-// #[post("/exter_login_callback")]
-// pub async fn external_login_callback(
-//     json_param: JsonQuery,
-//     auth_dao: UserAuthQuery,
-// ) -> ResponseJsonResult<ResponseJson> {
-//     let login_param = json_param.param::<ExternalCallbackParam>()?;
-//     let res = match login_param.login_type.as_str() {
-//         "qq" => {
-//             //调用外部API检查 code??
-//             let config = auth_dao
-//                 .web_dao
-//                 .web_setting
-//                 .setting_dao
-//                 .single
-//                 .load::<WeChatConfig>(None)
-//                 .await
-//                 .map_err(|e| auth_dao.fluent_error_json_response(&e.into()))?;
-//             let wechat = WechatLogin::new(
-//                 auth_dao.web_dao.clone(),
-//                 &config.app_id,
-//                 &config.app_secret,
-//                OAUTH_TYPE_WECHAT,
-//             );
-//             let (_, login_data) = wechat
-//                 .state_check(&auth_dao, &login_param.callback_state)
-//                 .await
-//                 .map_err(|e| auth_dao.fluent_error_json_response(&e))?;
-//             if let Some(ldat) = login_data {
-//                 let (token, _) = user_login_from_external::<WechatLogin, WechatLoginParam, _, _>(
-//                     &wechat, &ldat, 0, &auth_dao,
-//                 )
-//                 .await
-//                 .map_err(|e| auth_dao.fluent_error_json_response(&e))?;
-//                 return Ok(JsonResponse::data(JsonData::body(json!({ "token": token.to_string() }))).into());
-//             }
-//             Ok(JsonResponse::message("unimplemented"))
-//         }
-//         name => handler_not_found!(name),
-//     };
-//     Ok(res.map_err(|e| auth_dao.fluent_error_json_response(&e))?.into())
-// }
+#[derive(Debug, Deserialize)]
+pub struct OidcExternalCallbackParam {
+    #[serde(deserialize_with = "lsys_web::common::deserialize_u64")]
+    pub config_id: u64,
+    pub code: String,
+    pub callback_state: String,
+}
+//外部登录跳转回来后,用回调得到的code完成登录
+#[post("/exter_login_callback/{method}")]
+pub async fn external_login_callback(
+    path: actix_web::web::Path<String>,
+    json_param: JsonQuery,
+    auth_dao: UserAuthQuery,
+) -> ResponseJsonResult<ResponseJson> {
+    let res = match path.into_inner().as_str() {
+        OAUTH_TYPE_OIDC => {
+            let login_param = json_param.param::<OidcExternalCallbackParam>()?;
+            let oidc = OidcLogin::load(auth_dao.web_dao.clone(), login_param.config_id)
+                .await
+                .map_err(|e| auth_dao.fluent_error_json_response(&e.into()))?;
+            let (token, data) = user_login_from_external::<OidcLogin, OidcLoginParam, _, _>(
+                &oidc,
+                &OidcCallbackParam {
+                    code: login_param.code,
+                    state: login_param.callback_state,
+                },
+                0,
+                &auth_dao,
+            )
+            .await
+            .map_err(|e| auth_dao.fluent_error_json_response(&e))?;
+            jwt_login_data(&auth_dao, token, data).await
+        }
+        name => handler_not_found!(name),
+    };
+    Ok(res
+        .map_err(|e| auth_dao.fluent_error_json_response(&e))?
+        .into())
+}
@@ -33,6 +33,7 @@ where
                 .service(auth::logout)
                 .service(auth::user_data)
                 .service(auth::external_login_url)
+                .service(auth::external_login_callback)
                 .service(auth::external_state_callback)
                 .service(auth::external_state_check)
                 .service(auth::password)
@@ -53,6 +54,11 @@ where
             scope("/area")
                 .service(public::area_data)
                 .service(public::options),
+        )
+        .service(
+            scope("/account_export")
+                .service(public::account_export)
+                .service(public::options),
         );
 
     let mut system_scope = scope("/system");
@@ -70,6 +76,7 @@ where
     system_scope = system_scope
         .service(
             scope("/user")
+                .service(system::user_bulk_export)
                 .service(system::user)
                 .service(public::options),
         )
@@ -89,6 +96,7 @@ where
         .service(
             scope("/rbac")
                 .service(system::rbac::base)
+                .service(system::rbac::audit_export_csv)
                 .service(system::rbac::op)
                 .service(system::rbac::res)
                 .service(system::rbac::role)
@@ -132,7 +140,8 @@ where
             scope("/app")
                 .service(user::app::base)
                 .service(public::options),
-        );
+        )
+        .service(scope("/org").service(user::org).service(public::options));
     #[cfg(feature = "barcode")]
     {
         user_scope = user_scope.service(
@@ -0,0 +1,24 @@
+use crate::common::handler::ReqQuery;
+use actix_web::http::header::{ContentDisposition, DispositionParam, DispositionType};
+use actix_web::{get, HttpResponse};
+use lsys_web::handler::api::user::account::{
+    account_export_download, AccountExportDownloadParam,
+};
+
+//个人数据导出文件下载,令牌由导出完成邮件发送
+#[get("/{token}", name = "account_export_download")]
+pub(crate) async fn account_export(
+    param: actix_web::web::Path<AccountExportDownloadParam>,
+    req_dao: ReqQuery,
+) -> HttpResponse {
+    match account_export_download(&param, &req_dao).await {
+        Ok((file_name, body)) => HttpResponse::Ok()
+            .content_type("application/zip")
+            .insert_header(ContentDisposition {
+                disposition: DispositionType::Attachment,
+                parameters: vec![DispositionParam::Filename(file_name)],
+            })
+            .body(body),
+        Err(ref err) => HttpResponse::NotFound().body(req_dao.fluent_error_string(err)),
+    }
+}
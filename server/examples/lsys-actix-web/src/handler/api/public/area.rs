@@ -2,6 +2,7 @@ use crate::common::handler::ReqQuery;
 use crate::common::handler::{JsonQuery, ResponseJson, ResponseJsonResult};
 use actix_web::post;
 
+use lsys_web::handler::api::public::area::{address_parse, AddressParseParam};
 use lsys_web::handler::api::public::area::{search, CodeParam, GeoParam, SearchParam};
 
 use lsys_web::handler::api::public::area::{code_find, list_data};
@@ -21,6 +22,7 @@ pub async fn area_data(
             "related" => related_find(&json_param.param::<CodeParam>()?, &req_dao),
             "find" => code_find(&json_param.param::<CodeParam>()?, &req_dao),
             "geo" => geo_find(&json_param.param::<GeoParam>()?, &req_dao),
+            "parse" => address_parse(&json_param.param::<AddressParseParam>()?, &req_dao),
             name => handler_not_found!(name),
         }
         .map_err(|e| req_dao.fluent_error_json_response(&e))
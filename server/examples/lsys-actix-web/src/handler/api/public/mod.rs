@@ -1,10 +1,12 @@
 //公开接口
 
 pub mod app;
+mod account_export;
 mod area;
 mod captcha;
 mod options;
 mod site;
+pub use account_export::*;
 pub use area::*;
 pub use captcha::*;
 pub use options::*;
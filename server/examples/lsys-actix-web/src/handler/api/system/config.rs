@@ -2,11 +2,18 @@ use crate::common::handler::{
     JsonQuery, JwtQuery, ResponseJson, ResponseJsonResult, UserAuthQuery,
 };
 use actix_web::post;
+use lsys_web::handler::api::system::setting::ldap_config_get;
+use lsys_web::handler::api::system::setting::ldap_config_set;
 use lsys_web::handler::api::system::setting::site_config_get;
 use lsys_web::handler::api::system::setting::site_config_set;
+use lsys_web::handler::api::system::setting::LdapConfigParam;
 use lsys_web::handler::api::system::setting::SiteConfigParam;
+use lsys_web_module_oauth::handler::{
+    oidc_config_add, oidc_config_del, oidc_config_edit, oidc_config_list, OidcConfigDelParam,
+    OidcConfigEditParam, OidcConfigListParam, OidcConfigParam,
+};
 use lsys_web_module_oauth::handler::{wechat_get_config, wechat_set_config, WechatSetConfigParam};
-use lsys_web_module_oauth::module::OAUTH_TYPE_WECHAT;
+use lsys_web_module_oauth::module::{OAUTH_TYPE_OIDC, OAUTH_TYPE_WECHAT};
 
 //OAUTH配置
 #[post("site_config/{type}")]
@@ -23,6 +30,8 @@ pub async fn site_config(
     let res = match path.into_inner().as_str() {
         "get" => site_config_get(&auth_dao).await,
         "set" => site_config_set(&json_param.param::<SiteConfigParam>()?, &auth_dao).await,
+        "ldap_get" => ldap_config_get(&auth_dao).await,
+        "ldap_set" => ldap_config_set(&json_param.param::<LdapConfigParam>()?, &auth_dao).await,
         name => handler_not_found!(name),
     };
     Ok(res
@@ -55,6 +64,17 @@ pub async fn oauth_config(
             }
             name => handler_not_found!(name),
         },
+        OAUTH_TYPE_OIDC => match param.op_type.as_str() {
+            "list" => {
+                oidc_config_list(&json_param.param::<OidcConfigListParam>()?, &auth_dao).await
+            }
+            "add" => oidc_config_add(&json_param.param::<OidcConfigParam>()?, &auth_dao).await,
+            "edit" => {
+                oidc_config_edit(&json_param.param::<OidcConfigEditParam>()?, &auth_dao).await
+            }
+            "del" => oidc_config_del(&json_param.param::<OidcConfigDelParam>()?, &auth_dao).await,
+            name => handler_not_found!(name),
+        },
         name => handler_not_found!(name),
     };
     Ok(res
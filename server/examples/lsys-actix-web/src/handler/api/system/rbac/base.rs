@@ -1,11 +1,13 @@
 use crate::common::handler::{
     JsonQuery, JwtQuery, ResponseJson, ResponseJsonResult, UserAuthQuery,
 };
-use actix_web::post;
+use actix_web::http::header::{ContentDisposition, DispositionParam, DispositionType};
+use actix_web::{post, HttpResponse};
 use lsys_web::handler::api::system::rbac::{
-    audit_data, check_res_info_from_session, check_res_info_from_user, check_res_list_from_user,
-    check_res_role_data_from_res, check_res_user_data_from_res, check_res_user_from_res,
-    check_res_user_from_user, mapping_data, AuditParam, ResInfoFromUserParam,
+    audit_data, audit_deny_stat, audit_export, audit_unused_perm, check_res_info_from_session,
+    check_res_info_from_user, check_res_list_from_user, check_res_role_data_from_res,
+    check_res_user_data_from_res, check_res_user_from_res, check_res_user_from_user, mapping_data,
+    AuditDenyStatParam, AuditParam, AuditUnusedPermParam, ResInfoFromUserParam,
     ResListFromSessionParam, ResListFromUserParam, ResRoleFromResParam, ResUserDataFromResParam,
     ResUserFromUserParam, UserFromResParam,
 };
@@ -24,6 +26,12 @@ pub async fn base(
     let data = match path.into_inner().as_str() {
         "mapping" => mapping_data(&auth_dao).await,
         "audit_data" => audit_data(&json_param.param::<AuditParam>()?, &auth_dao).await,
+        "audit_deny_stat" => {
+            audit_deny_stat(&json_param.param::<AuditDenyStatParam>()?, &auth_dao).await
+        }
+        "audit_unused_perm" => {
+            audit_unused_perm(&json_param.param::<AuditUnusedPermParam>()?, &auth_dao).await
+        }
         "check_res_user_from_user" => {
             check_res_user_from_user(&json_param.param::<ResUserFromUserParam>()?, &auth_dao).await
         }
@@ -54,3 +62,29 @@ pub async fn base(
         .map_err(|e| auth_dao.fluent_error_json_response(&e))?
         .into())
 }
+
+//审计记录CSV下载,还有更多记录时通过 X-Next-Id 返回下一页起始ID
+#[post("/audit_export")]
+pub async fn audit_export_csv(
+    jwt: JwtQuery,
+    json_param: JsonQuery,
+    auth_dao: UserAuthQuery,
+) -> ResponseJsonResult<HttpResponse> {
+    auth_dao
+        .set_request_token(&jwt)
+        .await
+        .map_err(|e| auth_dao.fluent_error_json_response(&e))?;
+    let (file_name, body, next) = audit_export(&json_param.param::<AuditParam>()?, &auth_dao)
+        .await
+        .map_err(|e| auth_dao.fluent_error_json_response(&e))?;
+    let mut res = HttpResponse::Ok();
+    res.content_type("text/csv; charset=utf-8")
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(file_name)],
+        });
+    if let Some(next) = next {
+        res.insert_header(("X-Next-Id", next.to_string()));
+    }
+    Ok(res.body(body))
+}
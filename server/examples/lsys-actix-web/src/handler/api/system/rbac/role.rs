@@ -3,10 +3,16 @@ use crate::common::handler::{
 };
 use actix_web::post;
 use lsys_web::handler::api::system::rbac::{
-    role_add, role_data, role_del, role_edit, role_perm_add, role_perm_data, role_perm_del,
-    role_user_add, role_user_available, role_user_data, role_user_del, RoleAddParam, RoleDataParam,
-    RoleDelParam, RoleEditParam, RolePermAddParam, RolePermDelParam, RolePermParam,
-    RoleUserAddParam, RoleUserAvailableParam, RoleUserDataParam, RoleUserDelParam,
+    role_add, role_data, role_del, role_delegable, role_delegate_add, role_delegate_data,
+    role_delegate_del, role_edit, role_exclusive_add, role_exclusive_data, role_exclusive_del,
+    role_exclusive_edit, role_exclusive_violation, role_perm_add, role_perm_data, role_perm_del,
+    role_request_audit, role_request_data, role_user_add, role_user_available, role_user_data,
+    role_user_del, RoleAddParam, RoleDataParam, RoleDelParam, RoleDelegableParam,
+    RoleDelegateAddParam, RoleDelegateDelParam, RoleDelegateListParam, RoleEditParam,
+    RoleExclusiveAddParam, RoleExclusiveDelParam, RoleExclusiveEditParam, RoleExclusiveListParam,
+    RoleExclusiveViolationParam, RolePermAddParam, RolePermDelParam, RolePermParam,
+    RoleRequestAuditParam, RoleRequestListParam, RoleUserAddParam, RoleUserAvailableParam,
+    RoleUserDataParam, RoleUserDelParam,
 };
 
 #[post("/role/{method}")]
@@ -34,6 +40,41 @@ pub async fn role(
         "available_user" => {
             role_user_available(&json_param.param::<RoleUserAvailableParam>()?, &auth_dao).await
         }
+        "delegate_add" => {
+            role_delegate_add(&json_param.param::<RoleDelegateAddParam>()?, &auth_dao).await
+        }
+        "delegate_delete" => {
+            role_delegate_del(&json_param.param::<RoleDelegateDelParam>()?, &auth_dao).await
+        }
+        "delegate_data" => {
+            role_delegate_data(&json_param.param::<RoleDelegateListParam>()?, &auth_dao).await
+        }
+        "delegable" => role_delegable(&json_param.param::<RoleDelegableParam>()?, &auth_dao).await,
+        "exclusive_add" => {
+            role_exclusive_add(&json_param.param::<RoleExclusiveAddParam>()?, &auth_dao).await
+        }
+        "exclusive_edit" => {
+            role_exclusive_edit(&json_param.param::<RoleExclusiveEditParam>()?, &auth_dao).await
+        }
+        "exclusive_delete" => {
+            role_exclusive_del(&json_param.param::<RoleExclusiveDelParam>()?, &auth_dao).await
+        }
+        "exclusive_data" => {
+            role_exclusive_data(&json_param.param::<RoleExclusiveListParam>()?, &auth_dao).await
+        }
+        "exclusive_violation" => {
+            role_exclusive_violation(
+                &json_param.param::<RoleExclusiveViolationParam>()?,
+                &auth_dao,
+            )
+            .await
+        }
+        "request_data" => {
+            role_request_data(&json_param.param::<RoleRequestListParam>()?, &auth_dao).await
+        }
+        "request_audit" => {
+            role_request_audit(&json_param.param::<RoleRequestAuditParam>()?, &auth_dao).await
+        }
         name => handler_not_found!(name),
     };
     Ok(data
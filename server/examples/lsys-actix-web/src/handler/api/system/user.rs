@@ -1,11 +1,32 @@
 use crate::common::handler::{
     JsonQuery, JwtQuery, ResponseJson, ResponseJsonResult, UserAuthQuery,
 };
-use actix_web::post;
+use crate::handler::api::auth::jwt_login_data;
+use actix_web::http::header::{ContentDisposition, DispositionParam, DispositionType};
+use actix_web::{post, HttpResponse};
+use lsys_web::handler::api::system::user::account_address_normalize;
 use lsys_web::handler::api::system::user::{
-    account_detail, account_search, change_logs_list, login_history, mapping_data, user_logout,
-    AccountDetailParam, AccountSearchParam, ChangeLogsListParam, LoginHistoryParam,
-    UserLogoutParam,
+    account_bulk_export, account_import_add, account_import_list, account_import_row_list,
+    account_import_run, AccountBulkExportParam, AccountImportAddParam, AccountImportListParam,
+    AccountImportRowListParam, AccountImportRunParam,
+};
+use lsys_web::handler::api::system::user::{
+    account_delete_list, account_delete_restore, AccountDeleteListParam, AccountDeleteRestoreParam,
+};
+use lsys_web::handler::api::system::user::{
+    account_detail, account_merge, account_search, change_logs_list, login_history, mapping_data,
+    user_logout, user_session_logout, AccountDetailParam, AccountMergeParam, AccountSearchParam,
+    ChangeLogsListParam, LoginHistoryParam, UserLogoutParam, UserSessionLogoutParam,
+};
+use lsys_web::handler::api::system::user::{account_impersonate, AccountImpersonateParam};
+use lsys_web::handler::api::system::user::{
+    account_invite_add, account_invite_disable, account_invite_list, account_register_approve,
+    account_register_list, account_register_reject, AccountInviteAddParam,
+    AccountInviteDisableParam, AccountInviteListParam, AccountRegisterApproveParam,
+    AccountRegisterListParam, AccountRegisterRejectParam,
+};
+use lsys_web::handler::api::system::user::{
+    account_search_index, account_search_rebuild, AccountSearchIndexParam,
 };
 
 #[post("/{method}")]
@@ -25,12 +46,77 @@ pub(crate) async fn user(
             login_history(&json_param.param::<LoginHistoryParam>()?, &auth_dao).await
         }
         "user_logout" => user_logout(&json_param.param::<UserLogoutParam>()?, &auth_dao).await,
+        "user_session_logout" => {
+            user_session_logout(&json_param.param::<UserSessionLogoutParam>()?, &auth_dao).await
+        }
         "account_search" => {
             account_search(&json_param.param::<AccountSearchParam>()?, &auth_dao).await
         }
+        "account_search_index" => {
+            account_search_index(&json_param.param::<AccountSearchIndexParam>()?, &auth_dao).await
+        }
+        "account_search_rebuild" => account_search_rebuild(&auth_dao).await,
         "account_detail" => {
             account_detail(&json_param.param::<AccountDetailParam>()?, &auth_dao).await
         }
+        "account_merge" => {
+            account_merge(&json_param.param::<AccountMergeParam>()?, &auth_dao).await
+        }
+        "account_delete_list" => {
+            account_delete_list(&json_param.param::<AccountDeleteListParam>()?, &auth_dao).await
+        }
+        "account_delete_restore" => {
+            account_delete_restore(&json_param.param::<AccountDeleteRestoreParam>()?, &auth_dao)
+                .await
+        }
+        "account_invite_add" => {
+            account_invite_add(&json_param.param::<AccountInviteAddParam>()?, &auth_dao).await
+        }
+        "account_invite_list" => {
+            account_invite_list(&json_param.param::<AccountInviteListParam>()?, &auth_dao).await
+        }
+        "account_invite_disable" => {
+            account_invite_disable(&json_param.param::<AccountInviteDisableParam>()?, &auth_dao)
+                .await
+        }
+        "account_register_list" => {
+            account_register_list(&json_param.param::<AccountRegisterListParam>()?, &auth_dao).await
+        }
+        "account_register_approve" => {
+            account_register_approve(
+                &json_param.param::<AccountRegisterApproveParam>()?,
+                &auth_dao,
+            )
+            .await
+        }
+        "account_register_reject" => {
+            account_register_reject(
+                &json_param.param::<AccountRegisterRejectParam>()?,
+                &auth_dao,
+            )
+            .await
+        }
+        "account_address_normalize" => account_address_normalize(&auth_dao).await,
+        "account_impersonate" => {
+            let (token, data) =
+                account_impersonate(&json_param.param::<AccountImpersonateParam>()?, &auth_dao)
+                    .await
+                    .map_err(|e| auth_dao.fluent_error_json_response(&e))?;
+            jwt_login_data(&auth_dao, token, data).await
+        }
+        "account_import_add" => {
+            account_import_add(&json_param.param::<AccountImportAddParam>()?, &auth_dao).await
+        }
+        "account_import_run" => {
+            account_import_run(&json_param.param::<AccountImportRunParam>()?, &auth_dao).await
+        }
+        "account_import_list" => {
+            account_import_list(&json_param.param::<AccountImportListParam>()?, &auth_dao).await
+        }
+        "account_import_row_list" => {
+            account_import_row_list(&json_param.param::<AccountImportRowListParam>()?, &auth_dao)
+                .await
+        }
         "change_logs" => {
             change_logs_list(&json_param.param::<ChangeLogsListParam>()?, &auth_dao).await
         }
@@ -39,3 +125,34 @@ pub(crate) async fn user(
     .map_err(|e| auth_dao.fluent_error_json_response(&e))?
     .into())
 }
+
+//账号批量导出文件下载,还有更多账号时通过 X-Next-Id 返回下次导出的 start_id
+#[post("/account_bulk_export")]
+pub(crate) async fn user_bulk_export(
+    jwt: JwtQuery,
+    json_param: JsonQuery,
+    auth_dao: UserAuthQuery,
+) -> ResponseJsonResult<HttpResponse> {
+    auth_dao
+        .set_request_token(&jwt)
+        .await
+        .map_err(|e| auth_dao.fluent_error_json_response(&e))?;
+    let param = json_param.param::<AccountBulkExportParam>()?;
+    let (file_name, body, next) = account_bulk_export(&param, &auth_dao)
+        .await
+        .map_err(|e| auth_dao.fluent_error_json_response(&e))?;
+    let mut res = HttpResponse::Ok();
+    res.content_type(if param.format == "csv" {
+        "text/csv; charset=utf-8"
+    } else {
+        "application/json; charset=utf-8"
+    })
+    .insert_header(ContentDisposition {
+        disposition: DispositionType::Attachment,
+        parameters: vec![DispositionParam::Filename(file_name)],
+    });
+    if let Some(next) = next {
+        res.insert_header(("X-Next-Id", next.to_string()));
+    }
+    Ok(res.body(body))
+}
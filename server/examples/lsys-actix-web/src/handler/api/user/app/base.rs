@@ -21,6 +21,10 @@ use lsys_web::handler::api::user::app::{
     RequestListParam, SecretViewSecretParam, SubAppListParam, SubAppNotifyGetConfigParam,
     SubAppNotifySetConfigParam, SubRequestListParam, UserAppListParam, UserParentAppListParam,
 };
+use lsys_web::handler::api::user::app::{
+    transfer, transfer_accept, transfer_cancel, transfer_list, transfer_reject,
+    TransferConfirmParam, TransferListParam, TransferParam,
+};
 #[post("/{method}")]
 pub(crate) async fn base(
     jwt: JwtQuery,
@@ -65,6 +69,19 @@ pub(crate) async fn base(
             sub_request_list(&json_param.param::<SubRequestListParam>()?, &auth_dao).await
         }
         "delete" => delete(&json_param.param::<DeleteParam>()?, &auth_dao).await,
+        "transfer" => transfer(&json_param.param::<TransferParam>()?, &auth_dao).await,
+        "transfer_list" => {
+            transfer_list(&json_param.param::<TransferListParam>()?, &auth_dao).await
+        }
+        "transfer_accept" => {
+            transfer_accept(&json_param.param::<TransferConfirmParam>()?, &auth_dao).await
+        }
+        "transfer_reject" => {
+            transfer_reject(&json_param.param::<TransferConfirmParam>()?, &auth_dao).await
+        }
+        "transfer_cancel" => {
+            transfer_cancel(&json_param.param::<TransferConfirmParam>()?, &auth_dao).await
+        }
         "request_exter_feature" => {
             request_exter_feature(&json_param.param::<RequestExterFeatureParam>()?, &auth_dao).await
         }
@@ -5,12 +5,32 @@ use crate::common::handler::{
 use actix_web::post;
 
 use lsys_web::handler::api::user::account::delete;
+use lsys_web::handler::api::user::account::{account_merge, AccountMergeParam};
+use lsys_web::handler::api::user::account::{
+    account_export_add, account_export_list, AccountExportListParam,
+};
 use lsys_web::handler::api::user::account::info_check_username;
+use lsys_web::handler::api::user::account::{
+    account_invite_add, account_invite_disable, account_invite_list, AccountInviteAddParam,
+    AccountInviteDisableParam, AccountInviteListParam,
+};
 use lsys_web::handler::api::user::account::info_set_data;
 use lsys_web::handler::api::user::account::info_set_username;
 use lsys_web::handler::api::user::account::login_history;
 use lsys_web::handler::api::user::account::mapping_data;
 use lsys_web::handler::api::user::account::password_last_modify;
+use lsys_web::handler::api::user::account::{
+    login_device_list, security_event_confirm, security_event_list, security_event_secure,
+    SecurityEventListParam, SecurityEventParam,
+};
+use lsys_web::handler::api::user::account::{
+    personal_token_add, personal_token_del, personal_token_list, personal_token_scope,
+    PersonalTokenAddParam, PersonalTokenDelParam, PersonalTokenListParam,
+};
+use lsys_web::handler::api::user::account::{
+    session_list, session_logout, session_logout_other, session_reauth, SessionListParam,
+    SessionLogoutParam, SessionReauthParam,
+};
 use lsys_web::handler::api::user::account::DeleteParam;
 use lsys_web::handler::api::user::account::InfoCheckUserNameParam;
 use lsys_web::handler::api::user::account::InfoSetUserInfoParam;
@@ -34,8 +54,53 @@ pub async fn base(
         "login_history" => {
             login_history(&json_param.param::<LoginHistoryParam>()?, &auth_dao).await
         }
+        "session_list" => session_list(&json_param.param::<SessionListParam>()?, &auth_dao).await,
+        "session_logout" => {
+            session_logout(&json_param.param::<SessionLogoutParam>()?, &auth_dao).await
+        }
+        "session_logout_other" => session_logout_other(&auth_dao).await,
+        "session_reauth" => {
+            session_reauth(&json_param.param::<SessionReauthParam>()?, &auth_dao).await
+        }
+        "login_device_list" => login_device_list(&auth_dao).await,
+        "security_event_list" => {
+            security_event_list(&json_param.param::<SecurityEventListParam>()?, &auth_dao).await
+        }
+        "security_event_confirm" => {
+            security_event_confirm(&json_param.param::<SecurityEventParam>()?, &auth_dao).await
+        }
+        "security_event_secure" => {
+            security_event_secure(&json_param.param::<SecurityEventParam>()?, &auth_dao).await
+        }
+        "personal_token_scope" => personal_token_scope(&auth_dao).await,
+        "personal_token_add" => {
+            personal_token_add(&json_param.param::<PersonalTokenAddParam>()?, &auth_dao).await
+        }
+        "personal_token_list" => {
+            personal_token_list(&json_param.param::<PersonalTokenListParam>()?, &auth_dao).await
+        }
+        "personal_token_del" => {
+            personal_token_del(&json_param.param::<PersonalTokenDelParam>()?, &auth_dao).await
+        }
         "set_password" => set_password(&json_param.param::<SetPasswordParam>()?, &auth_dao).await,
         "delete" => delete(&json_param.param::<DeleteParam>()?, &auth_dao).await,
+        "account_merge" => {
+            account_merge(&json_param.param::<AccountMergeParam>()?, &auth_dao).await
+        }
+        "export_add" => account_export_add(&auth_dao).await,
+        "export_list" => {
+            account_export_list(&json_param.param::<AccountExportListParam>()?, &auth_dao).await
+        }
+        "invite_add" => {
+            account_invite_add(&json_param.param::<AccountInviteAddParam>()?, &auth_dao).await
+        }
+        "invite_list" => {
+            account_invite_list(&json_param.param::<AccountInviteListParam>()?, &auth_dao).await
+        }
+        "invite_disable" => {
+            account_invite_disable(&json_param.param::<AccountInviteDisableParam>()?, &auth_dao)
+                .await
+        }
 
         "set_username" => {
             info_set_username(&json_param.param::<InfoSetUserNameParam>()?, &auth_dao).await
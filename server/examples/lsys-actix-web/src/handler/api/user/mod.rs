@@ -1,5 +1,7 @@
 pub mod app;
 mod base;
+mod org;
 pub mod profile;
 pub mod rbac;
 pub use base::*;
+pub use org::*;
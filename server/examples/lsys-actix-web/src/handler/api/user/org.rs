@@ -0,0 +1,51 @@
+use crate::common::handler::{
+    JsonQuery, JwtQuery, ResponseJson, ResponseJsonResult, UserAuthQuery,
+};
+use actix_web::post;
+use lsys_web::handler::api::user::org::{
+    org_add, org_delete, org_edit, org_invite_accept, org_invite_add, org_invite_cancel,
+    org_invite_list, org_list, org_member_list, org_member_remove, org_member_role, OrgAddParam,
+    OrgDeleteParam, OrgEditParam, OrgInviteAcceptParam, OrgInviteAddParam, OrgInviteCancelParam,
+    OrgInviteListParam, OrgListParam, OrgMemberListParam, OrgMemberRemoveParam, OrgMemberRoleParam,
+};
+
+#[post("/{method}")]
+pub(crate) async fn org(
+    jwt: JwtQuery,
+    path: actix_web::web::Path<String>,
+    json_param: JsonQuery,
+    auth_dao: UserAuthQuery,
+) -> ResponseJsonResult<ResponseJson> {
+    auth_dao
+        .set_request_token(&jwt)
+        .await
+        .map_err(|e| auth_dao.fluent_error_json_response(&e))?;
+    Ok(match path.into_inner().as_str() {
+        "add" => org_add(&json_param.param::<OrgAddParam>()?, &auth_dao).await,
+        "edit" => org_edit(&json_param.param::<OrgEditParam>()?, &auth_dao).await,
+        "delete" => org_delete(&json_param.param::<OrgDeleteParam>()?, &auth_dao).await,
+        "list" => org_list(&json_param.param::<OrgListParam>()?, &auth_dao).await,
+        "member_list" => {
+            org_member_list(&json_param.param::<OrgMemberListParam>()?, &auth_dao).await
+        }
+        "member_role" => {
+            org_member_role(&json_param.param::<OrgMemberRoleParam>()?, &auth_dao).await
+        }
+        "member_remove" => {
+            org_member_remove(&json_param.param::<OrgMemberRemoveParam>()?, &auth_dao).await
+        }
+        "invite_add" => org_invite_add(&json_param.param::<OrgInviteAddParam>()?, &auth_dao).await,
+        "invite_list" => {
+            org_invite_list(&json_param.param::<OrgInviteListParam>()?, &auth_dao).await
+        }
+        "invite_cancel" => {
+            org_invite_cancel(&json_param.param::<OrgInviteCancelParam>()?, &auth_dao).await
+        }
+        "invite_accept" => {
+            org_invite_accept(&json_param.param::<OrgInviteAcceptParam>()?, &auth_dao).await
+        }
+        name => handler_not_found!(name),
+    }
+    .map_err(|e| auth_dao.fluent_error_json_response(&e))?
+    .into())
+}
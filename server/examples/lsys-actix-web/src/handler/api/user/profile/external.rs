@@ -13,7 +13,8 @@ use lsys_web::{
     },
 };
 use lsys_web_module_oauth::module::{
-    WeChatConfig, WechatLogin, WechatLoginParam, OAUTH_TYPE_WECHAT,
+    OidcCallbackParam, OidcLogin, OidcLoginParam, WeChatConfig, WechatLogin, WechatLoginParam,
+    OAUTH_TYPE_OIDC, OAUTH_TYPE_WECHAT,
 };
 use serde::Deserialize;
 use serde_json::json;
@@ -21,8 +22,11 @@ use serde_json::json;
 #[derive(Debug, Deserialize)]
 pub struct ExternalBindUrlParam {
     pub login_type: String,
+    #[serde(default)]
     pub login_state: String,
     pub callback_url: String,
+    #[serde(default, deserialize_with = "lsys_web::common::deserialize_option_u64")]
+    pub config_id: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -31,6 +35,15 @@ pub struct ExternalBindCheckParam {
     pub login_state: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ExternalBindCallbackParam {
+    pub login_type: String,
+    #[serde(deserialize_with = "lsys_web::common::deserialize_u64")]
+    pub config_id: u64,
+    pub code: String,
+    pub callback_state: String,
+}
+
 #[post("/exter/{method}")]
 pub(crate) async fn external(
     jwt: JwtQuery,
@@ -81,6 +94,29 @@ pub(crate) async fn external(
                 }
             }
         }
+        "bind_callback" => {
+            let login_param = json_param.param::<ExternalBindCallbackParam>()?;
+            match login_param.login_type.as_str() {
+                OAUTH_TYPE_OIDC => {
+                    let oidc = OidcLogin::load(auth_dao.web_dao.clone(), login_param.config_id)
+                        .await
+                        .map_err(|e| auth_dao.fluent_error_json_response(&e.into()))?;
+                    external_bind(
+                        &oidc,
+                        &OidcCallbackParam {
+                            code: login_param.code,
+                            state: login_param.callback_state,
+                        },
+                        &auth_dao,
+                    )
+                    .await
+                    .map_err(|e| auth_dao.fluent_error_json_response(&e))?
+                }
+                name => {
+                    handler_not_found!(name).map_err(|e| auth_dao.fluent_error_json_response(&e))?
+                }
+            }
+        }
         "bind_url" => {
             auth_dao
                 .user_session
@@ -116,6 +152,23 @@ pub(crate) async fn external(
                     .await
                     .map_err(|e| auth_dao.fluent_error_json_response(&e))?
                 }
+                OAUTH_TYPE_OIDC => {
+                    let oidc = OidcLogin::load(
+                        auth_dao.web_dao.clone(),
+                        param.config_id.unwrap_or_default(),
+                    )
+                    .await
+                    .map_err(|e| auth_dao.fluent_error_json_response(&e.into()))?;
+                    external_bind_url(
+                        &oidc,
+                        &OidcLoginParam {
+                            callback_url: param.callback_url,
+                        },
+                        &auth_dao,
+                    )
+                    .await
+                    .map_err(|e| auth_dao.fluent_error_json_response(&e))?
+                }
                 name => auth_dao.fluent_error_json_response(&JsonError::JsonResponse(
                     JsonData::default()
                         .set_sub_code("type_not_support")
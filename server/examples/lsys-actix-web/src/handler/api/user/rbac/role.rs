@@ -3,11 +3,17 @@ use crate::common::handler::{
 };
 use actix_web::post;
 use lsys_web::handler::api::user::rbac::{
-    system_role_add, system_role_data, system_role_del, system_role_edit, system_role_perm_add,
-    system_role_perm_data, system_role_perm_del, system_role_user_add, system_role_user_available,
-    system_role_user_data, system_role_user_del, SystemRoleAddParam, SystemRoleDataParam,
-    SystemRoleDelParam, SystemRoleEditParam, SystemRolePermAddParam, SystemRolePermDataParam,
-    SystemRolePermDelParam, SystemRoleUserAddParam, SystemRoleUserAvailableParam,
+    system_role_add, system_role_data, system_role_del, system_role_delegable,
+    system_role_delegate_add, system_role_delegate_data, system_role_delegate_del,
+    system_role_delegate_mine, system_role_edit, system_role_perm_add, system_role_perm_data,
+    system_role_perm_del, system_role_request_add, system_role_request_audit,
+    system_role_request_cancel, system_role_request_data, system_role_request_mine,
+    system_role_user_add, system_role_user_available, system_role_user_data, system_role_user_del,
+    SystemRoleAddParam, SystemRoleDataParam, SystemRoleDelParam, SystemRoleDelegableParam,
+    SystemRoleDelegateAddParam, SystemRoleDelegateDataParam, SystemRoleDelegateDelParam,
+    SystemRoleEditParam, SystemRolePermAddParam, SystemRolePermDataParam, SystemRolePermDelParam,
+    SystemRoleRequestAddParam, SystemRoleRequestAuditParam, SystemRoleRequestCancelParam,
+    SystemRoleRequestDataParam, SystemRoleUserAddParam, SystemRoleUserAvailableParam,
     SystemRoleUserDataParam, SystemRoleUserDelParam,
 };
 
@@ -52,6 +58,69 @@ pub async fn role(
             )
             .await
         }
+        "delegate_add" => {
+            system_role_delegate_add(
+                &json_param.param::<SystemRoleDelegateAddParam>()?,
+                &auth_dao,
+            )
+            .await
+        }
+        "delegate_delete" => {
+            system_role_delegate_del(
+                &json_param.param::<SystemRoleDelegateDelParam>()?,
+                &auth_dao,
+            )
+            .await
+        }
+        "delegate_data" => {
+            system_role_delegate_data(
+                &json_param.param::<SystemRoleDelegateDataParam>()?,
+                &auth_dao,
+            )
+            .await
+        }
+        "delegate_mine" => {
+            system_role_delegate_mine(
+                &json_param.param::<SystemRoleDelegateDataParam>()?,
+                &auth_dao,
+            )
+            .await
+        }
+        "delegable" => {
+            system_role_delegable(&json_param.param::<SystemRoleDelegableParam>()?, &auth_dao).await
+        }
+        "request_add" => {
+            system_role_request_add(&json_param.param::<SystemRoleRequestAddParam>()?, &auth_dao)
+                .await
+        }
+        "request_cancel" => {
+            system_role_request_cancel(
+                &json_param.param::<SystemRoleRequestCancelParam>()?,
+                &auth_dao,
+            )
+            .await
+        }
+        "request_mine" => {
+            system_role_request_mine(
+                &json_param.param::<SystemRoleRequestDataParam>()?,
+                &auth_dao,
+            )
+            .await
+        }
+        "request_data" => {
+            system_role_request_data(
+                &json_param.param::<SystemRoleRequestDataParam>()?,
+                &auth_dao,
+            )
+            .await
+        }
+        "request_audit" => {
+            system_role_request_audit(
+                &json_param.param::<SystemRoleRequestAuditParam>()?,
+                &auth_dao,
+            )
+            .await
+        }
         name => handler_not_found!(name),
     };
     Ok(data
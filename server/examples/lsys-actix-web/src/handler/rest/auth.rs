@@ -1,7 +1,8 @@
 use crate::common::handler::{ResponseJson, ResponseJsonResult, RestQuery};
 use actix_web::post;
 use lsys_web::handler::rest::auth::{
-    do_login, do_logout, login_info, DoLoginParam, DoLogoutParam, LoginInfoParam,
+    do_login, do_logout, do_refresh, login_info, DoLoginParam, DoLogoutParam, DoRefreshParam,
+    LoginInfoParam,
 };
 
 #[post("")]
@@ -11,6 +12,10 @@ pub(crate) async fn auth(rest: RestQuery) -> ResponseJsonResult<ResponseJson> {
             let param = rest.param::<DoLoginParam>()?;
             do_login(&param, &rest.get_app().await?, &rest).await
         }
+        "do_refresh" => {
+            let param = rest.param::<DoRefreshParam>()?;
+            do_refresh(&param, &rest.get_app().await?, &rest).await
+        }
         "do_logout" => {
             let param = rest.param::<DoLogoutParam>()?;
             do_logout(&param, &rest.get_app().await?, &rest).await
@@ -0,0 +1,238 @@
+//SCIM 2.0 用户及组同步接口
+use crate::common::handler::ReqQuery;
+use actix_service::ServiceFactory;
+use actix_web::dev::ServiceRequest;
+use actix_web::http::{header, StatusCode};
+use actix_web::web::{scope, Bytes, Path, Query};
+use actix_web::{delete, get, patch, post, put, App, Error, HttpRequest, HttpResponse};
+use lsys_web::common::JsonResult;
+use lsys_web::handler::scim::{
+    scim_auth, scim_group_create, scim_group_delete, scim_group_get, scim_group_list,
+    scim_group_patch, scim_group_replace, scim_parse_body, scim_resource_types,
+    scim_service_provider_config, scim_user_create, scim_user_delete, scim_user_get,
+    scim_user_list, scim_user_patch, scim_user_replace, ScimListParam, ScimResource, ScimResponse,
+    SCIM_CONTENT_TYPE,
+};
+
+fn scim_response(res: ScimResponse) -> HttpResponse {
+    let mut build = HttpResponse::build(
+        StatusCode::from_u16(res.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+    );
+    match res.body {
+        Some(body) => build.content_type(SCIM_CONTENT_TYPE).body(body.to_string()),
+        None => build.finish(),
+    }
+}
+
+fn scim_result(res: JsonResult<ScimResponse>, req_dao: &ReqQuery) -> HttpResponse {
+    scim_response(res.unwrap_or_else(|err| ScimResponse::error(&err, req_dao)))
+}
+
+fn scim_token(req: &HttpRequest) -> Option<&str> {
+    req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|e| e.to_str().ok())
+}
+
+#[get("/ServiceProviderConfig")]
+pub(crate) async fn service_provider_config() -> HttpResponse {
+    scim_response(scim_service_provider_config())
+}
+
+#[get("/ResourceTypes")]
+pub(crate) async fn resource_types() -> HttpResponse {
+    scim_response(scim_resource_types())
+}
+
+#[get("/Users")]
+pub(crate) async fn user_list(
+    req: HttpRequest,
+    param: Query<ScimListParam>,
+    req_dao: ReqQuery,
+) -> HttpResponse {
+    let res = async {
+        let auth = scim_auth(scim_token(&req), ScimResource::User, &req_dao).await?;
+        scim_user_list(&auth, &param, &req_dao).await
+    }
+    .await;
+    scim_result(res, &req_dao)
+}
+
+#[post("/Users")]
+pub(crate) async fn user_create(req: HttpRequest, body: Bytes, req_dao: ReqQuery) -> HttpResponse {
+    let res = async {
+        let auth = scim_auth(scim_token(&req), ScimResource::User, &req_dao).await?;
+        let body = scim_parse_body(&body)?;
+        scim_user_create(&auth, &body, &req_dao).await
+    }
+    .await;
+    scim_result(res, &req_dao)
+}
+
+#[get("/Users/{id}")]
+pub(crate) async fn user_get(
+    req: HttpRequest,
+    path: Path<String>,
+    req_dao: ReqQuery,
+) -> HttpResponse {
+    let res = async {
+        let auth = scim_auth(scim_token(&req), ScimResource::User, &req_dao).await?;
+        scim_user_get(&auth, &path, &req_dao).await
+    }
+    .await;
+    scim_result(res, &req_dao)
+}
+
+#[put("/Users/{id}")]
+pub(crate) async fn user_replace(
+    req: HttpRequest,
+    path: Path<String>,
+    body: Bytes,
+    req_dao: ReqQuery,
+) -> HttpResponse {
+    let res = async {
+        let auth = scim_auth(scim_token(&req), ScimResource::User, &req_dao).await?;
+        let body = scim_parse_body(&body)?;
+        scim_user_replace(&auth, &path, &body, &req_dao).await
+    }
+    .await;
+    scim_result(res, &req_dao)
+}
+
+#[patch("/Users/{id}")]
+pub(crate) async fn user_patch(
+    req: HttpRequest,
+    path: Path<String>,
+    body: Bytes,
+    req_dao: ReqQuery,
+) -> HttpResponse {
+    let res = async {
+        let auth = scim_auth(scim_token(&req), ScimResource::User, &req_dao).await?;
+        let body = scim_parse_body(&body)?;
+        scim_user_patch(&auth, &path, &body, &req_dao).await
+    }
+    .await;
+    scim_result(res, &req_dao)
+}
+
+#[delete("/Users/{id}")]
+pub(crate) async fn user_delete(
+    req: HttpRequest,
+    path: Path<String>,
+    req_dao: ReqQuery,
+) -> HttpResponse {
+    let res = async {
+        let auth = scim_auth(scim_token(&req), ScimResource::User, &req_dao).await?;
+        scim_user_delete(&auth, &path, &req_dao).await
+    }
+    .await;
+    scim_result(res, &req_dao)
+}
+
+#[get("/Groups")]
+pub(crate) async fn group_list(
+    req: HttpRequest,
+    param: Query<ScimListParam>,
+    req_dao: ReqQuery,
+) -> HttpResponse {
+    let res = async {
+        scim_auth(scim_token(&req), ScimResource::Group, &req_dao).await?;
+        scim_group_list(&param, &req_dao).await
+    }
+    .await;
+    scim_result(res, &req_dao)
+}
+
+#[post("/Groups")]
+pub(crate) async fn group_create(req: HttpRequest, body: Bytes, req_dao: ReqQuery) -> HttpResponse {
+    let res = async {
+        let auth = scim_auth(scim_token(&req), ScimResource::Group, &req_dao).await?;
+        let body = scim_parse_body(&body)?;
+        scim_group_create(&auth, &body, &req_dao).await
+    }
+    .await;
+    scim_result(res, &req_dao)
+}
+
+#[get("/Groups/{id}")]
+pub(crate) async fn group_get(
+    req: HttpRequest,
+    path: Path<String>,
+    req_dao: ReqQuery,
+) -> HttpResponse {
+    let res = async {
+        scim_auth(scim_token(&req), ScimResource::Group, &req_dao).await?;
+        scim_group_get(&path, &req_dao).await
+    }
+    .await;
+    scim_result(res, &req_dao)
+}
+
+#[put("/Groups/{id}")]
+pub(crate) async fn group_replace(
+    req: HttpRequest,
+    path: Path<String>,
+    body: Bytes,
+    req_dao: ReqQuery,
+) -> HttpResponse {
+    let res = async {
+        let auth = scim_auth(scim_token(&req), ScimResource::Group, &req_dao).await?;
+        let body = scim_parse_body(&body)?;
+        scim_group_replace(&auth, &path, &body, &req_dao).await
+    }
+    .await;
+    scim_result(res, &req_dao)
+}
+
+#[patch("/Groups/{id}")]
+pub(crate) async fn group_patch(
+    req: HttpRequest,
+    path: Path<String>,
+    body: Bytes,
+    req_dao: ReqQuery,
+) -> HttpResponse {
+    let res = async {
+        let auth = scim_auth(scim_token(&req), ScimResource::Group, &req_dao).await?;
+        let body = scim_parse_body(&body)?;
+        scim_group_patch(&auth, &path, &body, &req_dao).await
+    }
+    .await;
+    scim_result(res, &req_dao)
+}
+
+#[delete("/Groups/{id}")]
+pub(crate) async fn group_delete(
+    req: HttpRequest,
+    path: Path<String>,
+    req_dao: ReqQuery,
+) -> HttpResponse {
+    let res = async {
+        let auth = scim_auth(scim_token(&req), ScimResource::Group, &req_dao).await?;
+        scim_group_delete(&auth, &path, &req_dao).await
+    }
+    .await;
+    scim_result(res, &req_dao)
+}
+
+pub(crate) fn router<T>(app: App<T>) -> App<T>
+where
+    T: ServiceFactory<ServiceRequest, Config = (), Error = Error, InitError = ()>,
+{
+    app.service(
+        scope("/scim/v2")
+            .service(service_provider_config)
+            .service(resource_types)
+            .service(user_list)
+            .service(user_create)
+            .service(user_get)
+            .service(user_replace)
+            .service(user_patch)
+            .service(user_delete)
+            .service(group_list)
+            .service(group_create)
+            .service(group_get)
+            .service(group_replace)
+            .service(group_patch)
+            .service(group_delete),
+    )
+}
@@ -4,6 +4,7 @@ use actix_web::web::Data;
 use actix_web::Result;
 use actix_web::{get, HttpResponse};
 use lsys_web::dao::WebDao;
+use lsys_web::lsys_core::IntoFluentMessage;
 
 #[get("/dome")]
 pub(crate) async fn dome(web_dao: Data<WebDao>) -> Result<HttpResponse, WebHandError> {
@@ -13,3 +14,16 @@ pub(crate) async fn dome(web_dao: Data<WebDao>) -> Result<HttpResponse, WebHandE
     let body = web_dao.tera.render("index.html", &ctx)?;
     Ok(HttpResponse::Ok().content_type("text/html").body(body))
 }
+
+//JWT验证公钥,供其他服务离线验证
+#[get("/.well-known/jwks.json")]
+pub(crate) async fn jwks(web_dao: Data<WebDao>) -> Result<HttpResponse, WebHandError> {
+    let keys = web_dao
+        .web_app
+        .app_dao
+        .jwt_key
+        .jwks()
+        .await
+        .map_err(|e| WebHandError::string(e.to_fluent_message().default_format()))?;
+    Ok(HttpResponse::Ok().json(keys))
+}
@@ -63,6 +63,7 @@ where
     debug!("static dir is:{:?}", static_serve_from);
     app.service(actix_files::Files::new("/static", static_serve_from).show_files_listing())
         .service(index::dome)
+        .service(index::jwks)
 }
 
 pub(crate) fn router<T>(app: App<T>, app_dao: &Arc<WebDao>) -> App<T>
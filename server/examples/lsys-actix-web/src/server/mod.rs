@@ -6,7 +6,7 @@ use actix_web::{error, http, middleware as middlewares, HttpResponse, HttpServer
 
 use actix_web::App;
 use futures_util::TryFutureExt;
-use jsonwebtoken::{DecodingKey, Validation};
+use jsonwebtoken::Validation;
 use lsys_web::common::FluentFormat;
 use lsys_web::dao::WebDao;
 use lsys_web::lsys_core::{AppCore, AppCoreError};
@@ -28,12 +28,6 @@ pub async fn create_server(app_dir: &str) -> Result<Server, AppError> {
     let app_dao = Data::new(WebDao::new(app_core.clone()).await?);
     let bind_addr = app_dao.bind_addr();
     let bind_ssl_data = app_dao.bind_ssl_data();
-    let app_jwt_key = app_dao
-        .app_core
-        .config
-        .find(None)
-        .get_string("app_jwt_key")
-        .map_err(|err| AppCoreError::Config(lsys_web::lsys_core::ConfigError::Config(err)))?;
     let app_json_limit = app_dao
         .app_core
         .config
@@ -57,7 +51,7 @@ pub async fn create_server(app_dir: &str) -> Result<Server, AppError> {
     let is_use_ssl = bind_ssl_data.is_some();
     let mut server = HttpServer::new(move || {
         let jwt_config = JwtQueryConfig::new(
-            DecodingKey::from_secret(app_jwt_key.as_bytes()),
+            app_dao.web_app.app_dao.jwt_key.clone(),
             Validation::default(),
         );
         let json_config = JsonConfig::default()
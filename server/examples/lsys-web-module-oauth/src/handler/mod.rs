@@ -1,2 +1,4 @@
+mod oidc;
 mod wechat;
+pub use oidc::*;
 pub use wechat::*;
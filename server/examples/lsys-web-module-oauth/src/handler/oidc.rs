@@ -0,0 +1,219 @@
+use crate::module::{OidcClaimMap, OidcConfig, OidcSetting};
+use lsys_access::dao::AccessSession;
+use lsys_web::{
+    common::{JsonData, JsonResponse, JsonResult, RequestDao, UserAuthQueryDao},
+    dao::access::{api::system::admin::CheckAdminSiteSetting, RbacAccessCheckEnv},
+};
+use serde::Deserialize;
+use serde_json::json;
+
+fn oidc_setting(req_dao: &RequestDao) -> OidcSetting {
+    OidcSetting::new(req_dao.web_dao.web_setting.setting_dao.multiple.clone())
+}
+
+async fn oidc_check_admin(req_dao: &UserAuthQueryDao) -> JsonResult<u64> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminSiteSetting {},
+        )
+        .await?;
+    Ok(auth_data.user_id())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OidcConfigListParam {
+    #[serde(
+        default,
+        deserialize_with = "lsys_web::common::deserialize_option_vec_u64"
+    )]
+    pub ids: Option<Vec<u64>>,
+}
+
+pub async fn oidc_config_list(
+    param: &OidcConfigListParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    oidc_check_admin(req_dao).await?;
+    let row = oidc_setting(req_dao)
+        .list_config(param.ids.as_deref())
+        .await?;
+    let data = row
+        .into_iter()
+        .map(|e| {
+            json!({
+                "id": e.model().id,
+                "name": e.model().name,
+                "issuer": e.issuer,
+                "authorize_url": e.authorize_url,
+                "token_url": e.token_url,
+                "userinfo_url": e.userinfo_url,
+                "client_id": e.client_id,
+                "client_secret": e.client_secret,
+                "hide_client_secret": e.hide_client_secret(),
+                "scope": e.scope,
+                "use_pkce": e.use_pkce,
+                "claim": {
+                    "id": e.claim.id,
+                    "name": e.claim.name,
+                    "nikename": e.claim.nikename,
+                    "email": e.claim.email,
+                    "email_verified": e.claim.email_verified,
+                    "pic": e.claim.pic,
+                    "link": e.claim.link,
+                },
+                "change_user_id": e.model().change_user_id,
+                "change_time": e.model().change_time,
+            })
+        })
+        .collect::<Vec<_>>();
+    Ok(JsonResponse::data(JsonData::body(json!({ "data": data }))))
+}
+
+//登录页使用,仅返回可选的登录方式
+pub async fn oidc_provider_list(req_dao: &RequestDao) -> JsonResult<JsonResponse> {
+    let row = oidc_setting(req_dao).list_config(None).await?;
+    let data = row
+        .into_iter()
+        .map(|e| {
+            json!({
+                "id": e.model().id,
+                "name": e.model().name,
+            })
+        })
+        .collect::<Vec<_>>();
+    Ok(JsonResponse::data(JsonData::body(json!({ "data": data }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OidcClaimParam {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub nikename: Option<String>,
+    pub email: Option<String>,
+    pub email_verified: Option<String>,
+    pub pic: Option<String>,
+    pub link: Option<String>,
+}
+
+impl From<&OidcClaimParam> for OidcClaimMap {
+    fn from(value: &OidcClaimParam) -> Self {
+        let def = OidcClaimMap::default();
+        OidcClaimMap {
+            id: value.id.clone().filter(|e| !e.is_empty()).unwrap_or(def.id),
+            name: value.name.clone().unwrap_or(def.name),
+            nikename: value.nikename.clone().unwrap_or(def.nikename),
+            email: value.email.clone().unwrap_or(def.email),
+            email_verified: value.email_verified.clone().unwrap_or(def.email_verified),
+            pic: value.pic.clone().unwrap_or(def.pic),
+            link: value.link.clone().unwrap_or(def.link),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OidcConfigParam {
+    pub name: String,
+    #[serde(default)]
+    pub issuer: String,
+    #[serde(default)]
+    pub authorize_url: String,
+    #[serde(default)]
+    pub token_url: String,
+    #[serde(default)]
+    pub userinfo_url: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: String,
+    pub scope: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "lsys_web::common::deserialize_option_bool"
+    )]
+    pub use_pkce: Option<bool>,
+    pub claim: Option<OidcClaimParam>,
+}
+
+impl From<&OidcConfigParam> for OidcConfig {
+    fn from(value: &OidcConfigParam) -> Self {
+        OidcConfig {
+            issuer: value.issuer.trim().to_owned(),
+            authorize_url: value.authorize_url.trim().to_owned(),
+            token_url: value.token_url.trim().to_owned(),
+            userinfo_url: value.userinfo_url.trim().to_owned(),
+            client_id: value.client_id.to_owned(),
+            client_secret: value.client_secret.to_owned(),
+            scope: value
+                .scope
+                .clone()
+                .unwrap_or_else(|| "openid profile email".to_string()),
+            use_pkce: value.use_pkce.unwrap_or(true),
+            claim: value
+                .claim
+                .as_ref()
+                .map(OidcClaimMap::from)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+pub async fn oidc_config_add(
+    param: &OidcConfigParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let user_id = oidc_check_admin(req_dao).await?;
+    let id = oidc_setting(req_dao)
+        .add_config(
+            &param.name,
+            &OidcConfig::from(param),
+            user_id,
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(json!({ "id": id }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OidcConfigEditParam {
+    #[serde(deserialize_with = "lsys_web::common::deserialize_u64")]
+    pub id: u64,
+    #[serde(flatten)]
+    pub config: OidcConfigParam,
+}
+
+pub async fn oidc_config_edit(
+    param: &OidcConfigEditParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let user_id = oidc_check_admin(req_dao).await?;
+    let row = oidc_setting(req_dao)
+        .edit_config(
+            param.id,
+            &param.config.name,
+            &OidcConfig::from(&param.config),
+            user_id,
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(json!({ "num": row }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OidcConfigDelParam {
+    #[serde(deserialize_with = "lsys_web::common::deserialize_u64")]
+    pub id: u64,
+}
+
+pub async fn oidc_config_del(
+    param: &OidcConfigDelParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let user_id = oidc_check_admin(req_dao).await?;
+    let row = oidc_setting(req_dao)
+        .del_config(param.id, user_id, Some(&req_dao.req_env))
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(json!({ "num": row }))))
+}
@@ -4,3 +4,9 @@ pub use wechat_config::*;
 pub use wechat_lib::*;
 mod wechat_login;
 pub use wechat_login::*;
+mod oidc_config;
+mod oidc_lib;
+mod oidc_login;
+pub use oidc_config::*;
+pub use oidc_lib::*;
+pub use oidc_login::*;
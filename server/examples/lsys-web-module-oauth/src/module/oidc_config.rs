@@ -0,0 +1,163 @@
+use std::sync::Arc;
+
+use lsys_core::RequestEnv;
+use lsys_setting::dao::{
+    MultipleSetting, MultipleSettingData, SettingData, SettingDecode, SettingEncode, SettingError,
+    SettingJson, SettingKey, SettingResult,
+};
+use lsys_setting::model::SettingStatus;
+use serde::{Deserialize, Serialize};
+
+//config
+//通用 OpenID Connect / OAuth2 登录配置,可配置多个
+//issuer 不为空时通过 {issuer}/.well-known/openid-configuration 获取端点
+//手动填写的端点优先于发现结果
+
+#[derive(Deserialize, Serialize, Default, Clone)]
+pub struct OidcConfig {
+    pub issuer: String,
+    pub authorize_url: String,
+    pub token_url: String,
+    pub userinfo_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub scope: String,
+    pub use_pkce: bool,
+    pub claim: OidcClaimMap,
+}
+
+//用户信息字段映射,支持 a.b 形式的嵌套字段
+#[derive(Deserialize, Serialize, Clone)]
+pub struct OidcClaimMap {
+    pub id: String,
+    pub name: String,
+    pub nikename: String,
+    pub email: String,
+    pub email_verified: String,
+    pub pic: String,
+    pub link: String,
+}
+
+impl Default for OidcClaimMap {
+    fn default() -> Self {
+        Self {
+            id: "sub".to_string(),
+            name: "preferred_username".to_string(),
+            nikename: "name".to_string(),
+            email: "email".to_string(),
+            email_verified: "email_verified".to_string(),
+            pic: "picture".to_string(),
+            link: "profile".to_string(),
+        }
+    }
+}
+
+impl OidcConfig {
+    pub fn hide_client_secret(&self) -> String {
+        let len = self.client_secret.chars().count();
+        format!(
+            "{}**{}",
+            self.client_secret.chars().take(2).collect::<String>(),
+            self.client_secret
+                .chars()
+                .skip(if len > 2 { len - 2 } else { len })
+                .take(2)
+                .collect::<String>()
+        )
+    }
+}
+
+impl SettingKey for OidcConfig {
+    fn key<'t>() -> &'t str {
+        "oauth-oidc"
+    }
+}
+
+impl SettingDecode for OidcConfig {
+    fn decode(data: &str) -> SettingResult<Self> {
+        SettingJson::decode(data)
+    }
+}
+impl SettingEncode for OidcConfig {
+    fn encode(&self) -> String {
+        SettingJson::encode(self)
+    }
+}
+impl SettingJson<'_> for OidcConfig {}
+
+//OIDC登录配置管理
+pub struct OidcSetting {
+    setting: Arc<MultipleSetting>,
+}
+
+impl OidcSetting {
+    pub fn new(setting: Arc<MultipleSetting>) -> Self {
+        Self { setting }
+    }
+    //列出OIDC配置
+    pub async fn list_config(
+        &self,
+        config_ids: Option<&[u64]>,
+    ) -> SettingResult<Vec<SettingData<OidcConfig>>> {
+        self.setting
+            .list_data::<OidcConfig>(None, config_ids, None)
+            .await
+    }
+    //加载可用的OIDC配置
+    pub async fn find_config(&self, id: u64) -> SettingResult<SettingData<OidcConfig>> {
+        let data = self.setting.load::<OidcConfig>(None, id).await?;
+        if !SettingStatus::Enable.eq(data.model().status) {
+            return Err(SettingError::Sqlx(sqlx::Error::RowNotFound));
+        }
+        Ok(data)
+    }
+    //添加OIDC配置
+    pub async fn add_config(
+        &self,
+        name: &str,
+        config: &OidcConfig,
+        user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> SettingResult<u64> {
+        self.setting
+            .add(
+                None,
+                &MultipleSettingData { name, data: config },
+                user_id,
+                None,
+                env_data,
+            )
+            .await
+    }
+    //编辑OIDC配置
+    pub async fn edit_config(
+        &self,
+        id: u64,
+        name: &str,
+        config: &OidcConfig,
+        user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> SettingResult<u64> {
+        self.setting
+            .edit(
+                None,
+                id,
+                &MultipleSettingData { name, data: config },
+                user_id,
+                None,
+                env_data,
+            )
+            .await
+    }
+    //删除OIDC配置
+    pub async fn del_config(
+        &self,
+        id: u64,
+        user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> SettingResult<u64> {
+        self.setting
+            .del::<OidcConfig>(None, id, user_id, None, env_data)
+            .await
+    }
+}
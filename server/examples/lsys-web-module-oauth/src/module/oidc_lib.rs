@@ -0,0 +1,270 @@
+use std::time::Duration;
+
+use base64::Engine;
+use reqwest::{header, Method, RequestBuilder, StatusCode, Url};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+
+use super::OidcConfig;
+//lib
+
+//授权端点
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct OidcEndpoint {
+    #[serde(default)]
+    pub issuer: String,
+    #[serde(default)]
+    pub authorization_endpoint: String,
+    #[serde(default)]
+    pub token_endpoint: String,
+    #[serde(default)]
+    pub userinfo_endpoint: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OidcTokenResponse {
+    pub access_token: String,
+    pub token_type: Option<String>,
+    pub refresh_token: Option<String>,
+    pub expires_in: Option<u64>,
+    pub id_token: Option<String>,
+}
+
+pub struct OidcLib {
+    config: OidcConfig,
+    timeout: u64,
+}
+
+impl OidcLib {
+    pub fn new(config: OidcConfig) -> Self {
+        Self {
+            config,
+            timeout: 60,
+        }
+    }
+    pub fn config(&self) -> &OidcConfig {
+        &self.config
+    }
+    //scope 包含 openid 时为 OIDC 登录,需返回 id_token 并校验 nonce
+    pub fn is_openid(&self) -> bool {
+        self.config.scope.split(' ').any(|e| e == "openid")
+    }
+    //生成 PKCE code_challenge (S256)
+    pub fn pkce_challenge(code_verifier: &str) -> String {
+        base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .encode(Sha256::digest(code_verifier.as_bytes()))
+    }
+    //得到端点,未配置的端点通过issuer发现获取
+    pub async fn endpoint(&self) -> Result<OidcEndpoint, String> {
+        let mut endpoint = OidcEndpoint {
+            issuer: self.config.issuer.to_owned(),
+            authorization_endpoint: self.config.authorize_url.to_owned(),
+            token_endpoint: self.config.token_url.to_owned(),
+            userinfo_endpoint: self.config.userinfo_url.to_owned(),
+        };
+        let need_discovery = endpoint.authorization_endpoint.is_empty()
+            || endpoint.token_endpoint.is_empty()
+            || endpoint.userinfo_endpoint.is_empty();
+        if need_discovery && !self.config.issuer.is_empty() {
+            let discovery_url = format!(
+                "{}/.well-known/openid-configuration",
+                self.config.issuer.trim_end_matches('/')
+            );
+            let res = self
+                .send(self.client()?.request(Method::GET, discovery_url))
+                .await?;
+            let disc = serde_json::from_str::<OidcEndpoint>(&res).map_err(|e| e.to_string())?;
+            if endpoint.authorization_endpoint.is_empty() {
+                endpoint.authorization_endpoint = disc.authorization_endpoint;
+            }
+            if endpoint.token_endpoint.is_empty() {
+                endpoint.token_endpoint = disc.token_endpoint;
+            }
+            if endpoint.userinfo_endpoint.is_empty() {
+                endpoint.userinfo_endpoint = disc.userinfo_endpoint;
+            }
+        }
+        if endpoint.authorization_endpoint.is_empty() || endpoint.token_endpoint.is_empty() {
+            return Err("oidc authorize or token endpoint not config".to_string());
+        }
+        Ok(endpoint)
+    }
+    pub fn build_authorization_url(
+        &self,
+        endpoint: &OidcEndpoint,
+        redirect_uri: &str,
+        state: &str,
+        nonce: &str,
+        code_verifier: Option<&str>,
+    ) -> Result<String, String> {
+        let mut url = Url::parse(&endpoint.authorization_endpoint).map_err(|e| e.to_string())?;
+        {
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("response_type", "code")
+                .append_pair("client_id", &self.config.client_id)
+                .append_pair("redirect_uri", redirect_uri)
+                .append_pair("state", state);
+            if !self.config.scope.is_empty() {
+                query.append_pair("scope", &self.config.scope);
+            }
+            if self.is_openid() {
+                query.append_pair("nonce", nonce);
+            }
+            if let Some(verifier) = code_verifier {
+                query
+                    .append_pair("code_challenge", &Self::pkce_challenge(verifier))
+                    .append_pair("code_challenge_method", "S256");
+            }
+        }
+        Ok(url.to_string())
+    }
+    pub async fn oauth2_token(
+        &self,
+        endpoint: &OidcEndpoint,
+        code: &str,
+        redirect_uri: &str,
+        code_verifier: Option<&str>,
+    ) -> Result<OidcTokenResponse, String> {
+        let mut params = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("client_id", self.config.client_id.as_str()),
+        ];
+        if !self.config.client_secret.is_empty() {
+            params.push(("client_secret", self.config.client_secret.as_str()));
+        }
+        if let Some(verifier) = code_verifier {
+            params.push(("code_verifier", verifier));
+        }
+        let request = self
+            .client()?
+            .request(Method::POST, endpoint.token_endpoint.as_str())
+            .form(&params);
+        let res = self.send(request).await?;
+        let resp = serde_json::from_str::<OidcTokenResponse>(&res).map_err(|e| e.to_string())?;
+        Ok(resp)
+    }
+    //获取用户信息,无userinfo端点时使用已校验的id_token声明
+    //同时存在时userinfo的sub需与id_token一致
+    pub async fn oauth2_userinfo(
+        &self,
+        endpoint: &OidcEndpoint,
+        token: &OidcTokenResponse,
+        id_claims: Option<Value>,
+    ) -> Result<Value, String> {
+        if endpoint.userinfo_endpoint.is_empty() {
+            return id_claims
+                .ok_or_else(|| "oidc userinfo endpoint not config and miss id_token".to_string());
+        }
+        let request = self
+            .client()?
+            .request(Method::GET, endpoint.userinfo_endpoint.as_str())
+            .bearer_auth(&token.access_token);
+        let res = self.send(request).await?;
+        let claims = serde_json::from_str::<Value>(&res).map_err(|e| e.to_string())?;
+        if let Some(id_claims) = id_claims {
+            if claims.get("sub") != id_claims.get("sub") {
+                return Err("userinfo sub not match id_token".to_string());
+            }
+        }
+        Ok(claims)
+    }
+    /// 校验id_token声明: iss 与 issuer 一致,aud 包含 client_id,未过期,nonce 与发起登录时一致
+    ///
+    /// 此处不校验id_token的JWS签名(未从JWKS获取公钥).
+    /// id_token可信仅因为它是由本服务通过后端通道直接请求token端点,
+    /// 并携带client_secret/PKCE换取,经TLS返回的(OIDC Core 3.1.3.7).
+    /// 因此token端点必须配置为https,且不得将本方法用于从前端或其他渠道传入的id_token.
+    pub fn id_token_verify(
+        &self,
+        endpoint: &OidcEndpoint,
+        id_token: &str,
+        nonce: Option<&str>,
+        now_time: u64,
+    ) -> Result<Value, String> {
+        let claims = Self::id_token_claims(id_token)?;
+        if endpoint.issuer.is_empty() {
+            return Err("oidc issuer not config, can't verify id_token".to_string());
+        }
+        let issuer = endpoint.issuer.trim_end_matches('/');
+        match claims.get("iss").and_then(|e| e.as_str()) {
+            Some(iss) if iss.trim_end_matches('/') == issuer => {}
+            _ => return Err("id_token iss wrong".to_string()),
+        }
+        let client_id = self.config.client_id.as_str();
+        let aud_ok = match claims.get("aud") {
+            Some(Value::String(aud)) => aud == client_id,
+            Some(Value::Array(aud)) => {
+                aud.iter().any(|e| e.as_str() == Some(client_id))
+                    && (aud.len() == 1
+                        || claims.get("azp").and_then(|e| e.as_str()) == Some(client_id))
+            }
+            _ => false,
+        };
+        if !aud_ok {
+            return Err("id_token aud wrong".to_string());
+        }
+        match claims.get("exp").and_then(|e| e.as_u64()) {
+            Some(exp) if exp > now_time => {}
+            _ => return Err("id_token expired".to_string()),
+        }
+        if let Some(nonce) = nonce {
+            if claims.get("nonce").and_then(|e| e.as_str()) != Some(nonce) {
+                return Err("id_token nonce wrong".to_string());
+            }
+        }
+        Ok(claims)
+    }
+    //解析id_token中的声明
+    pub fn id_token_claims(id_token: &str) -> Result<Value, String> {
+        let payload = id_token
+            .split('.')
+            .nth(1)
+            .ok_or_else(|| "id_token format wrong".to_string())?;
+        let data = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(payload.trim_end_matches('='))
+            .map_err(|e| e.to_string())?;
+        serde_json::from_slice::<Value>(&data).map_err(|e| e.to_string())
+    }
+    //按映射路径取声明值,数字转为字符串
+    pub fn claim_value(claims: &Value, path: &str) -> Option<String> {
+        if path.is_empty() {
+            return None;
+        }
+        let mut val = claims;
+        for key in path.split('.') {
+            val = val.get(key)?;
+        }
+        match val {
+            Value::String(s) if !s.is_empty() => Some(s.to_owned()),
+            Value::Number(n) => Some(n.to_string()),
+            Value::Bool(b) => Some(b.to_string()),
+            _ => None,
+        }
+    }
+    fn client(&self) -> Result<reqwest::Client, String> {
+        reqwest::Client::builder()
+            .timeout(Duration::from_secs(self.timeout))
+            .build()
+            .map_err(|e| e.to_string())
+    }
+    async fn send(&self, request: RequestBuilder) -> Result<String, String> {
+        let request = request
+            .header(header::ACCEPT, "application/json")
+            .header(header::USER_AGENT, "lsys-oauth");
+        let result = request.send().await.map_err(|e| e.to_string())?;
+        let url = result.url().to_string();
+        let status = result.status();
+        let res = result.text().await.map_err(|e| e.to_string())?;
+        if status != StatusCode::OK {
+            warn!("oidc request {} fail response: {}", url, &res);
+            return Err(format!("oidc request fail:[{}] {}", status, res));
+        }
+        debug!("oidc request {} response: {}", url, &res);
+        Ok(res)
+    }
+}
@@ -0,0 +1,188 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use lsys_core::{now_time, rand_str, RandType};
+use lsys_setting::dao::SettingResult;
+use lsys_web::dao::{OauthCallbackParam, OauthLogin, OauthLoginData, OauthLoginParam, WebDao};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{OidcConfig, OidcLib, OidcSetting};
+
+pub const OAUTH_TYPE_OIDC: &str = "oidc";
+
+fn state_key(state: &str) -> String {
+    format!("oidc-{}", state)
+}
+
+//发起登录时保存的数据,回调时校验
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct OidcStateData {
+    config_id: u64,
+    callback_url: String,
+    nonce: String,
+    code_verifier: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OidcExternalData {
+    pub claims: Value,
+}
+
+pub struct OidcLoginParam {
+    pub callback_url: String,
+}
+impl OauthLoginParam for OidcLoginParam {}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OidcCallbackParam {
+    pub code: String,
+    pub state: String,
+}
+impl OauthCallbackParam for OidcCallbackParam {}
+
+pub struct OidcLogin {
+    config_id: u64,
+    timeout: u64, //state保存时间
+    lib: OidcLib,
+    web_dao: Arc<WebDao>,
+}
+
+impl OidcLogin {
+    pub fn new(web_dao: Arc<WebDao>, config_id: u64, config: OidcConfig) -> Self {
+        Self {
+            web_dao,
+            config_id,
+            lib: OidcLib::new(config),
+            timeout: 600,
+        }
+    }
+    //从配置加载
+    pub async fn load(web_dao: Arc<WebDao>, config_id: u64) -> SettingResult<Self> {
+        let config = OidcSetting::new(web_dao.web_setting.setting_dao.multiple.clone())
+            .find_config(config_id)
+            .await?;
+        let config = (*config).clone();
+        Ok(Self::new(web_dao, config_id, config))
+    }
+    //外部账号的配置名,同一个IdP的账号不随配置名称修改而变化
+    pub fn config_name(&self) -> String {
+        format!("{}-{}", OAUTH_TYPE_OIDC, self.config_id)
+    }
+    fn claim(&self, claims: &Value, path: &str) -> Option<String> {
+        OidcLib::claim_value(claims, path)
+    }
+}
+
+#[async_trait]
+impl OauthLogin<OidcLoginParam, OidcCallbackParam, OidcExternalData> for OidcLogin {
+    async fn login_url(&self, param: &OidcLoginParam) -> Result<String, String> {
+        let endpoint = self.lib.endpoint().await?;
+        let state = rand_str(RandType::LowerNumber, 32);
+        let state_data = OidcStateData {
+            config_id: self.config_id,
+            callback_url: param.callback_url.to_owned(),
+            nonce: rand_str(RandType::LowerNumber, 32),
+            code_verifier: if self.lib.config().use_pkce {
+                Some(rand_str(RandType::LowerNumber, 64))
+            } else {
+                None
+            },
+        };
+        let url = self.lib.build_authorization_url(
+            &endpoint,
+            &state_data.callback_url,
+            &state,
+            &state_data.nonce,
+            state_data.code_verifier.as_deref(),
+        )?;
+        let save_data = serde_json::to_string(&state_data).map_err(|e| e.to_string())?;
+        let mut redis = self.web_dao.redis.get().await.map_err(|e| e.to_string())?;
+        let _: () = redis
+            .set_ex(state_key(&state), save_data, self.timeout)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(url)
+    }
+    async fn login_callback(
+        &self,
+        param: &OidcCallbackParam,
+    ) -> Result<(OauthLoginData, OidcExternalData), String> {
+        if param.state.is_empty() {
+            return Err("state miss".to_string());
+        }
+        let key = state_key(&param.state);
+        let mut redis = self.web_dao.redis.get().await.map_err(|e| e.to_string())?;
+        //state仅能使用一次,使用GETDEL原子读取并删除,避免并发回调重复使用
+        let save_data: Option<String> = redis.get_del(&key).await.map_err(|e| e.to_string())?;
+        let state_data = save_data
+            .and_then(|e| serde_json::from_str::<OidcStateData>(&e).ok())
+            .ok_or_else(|| "state timeout or wrong".to_string())?;
+        if state_data.config_id != self.config_id {
+            return Err("state not match config".to_string());
+        }
+        let endpoint = self.lib.endpoint().await?;
+        let token = self
+            .lib
+            .oauth2_token(
+                &endpoint,
+                &param.code,
+                &state_data.callback_url,
+                state_data.code_verifier.as_deref(),
+            )
+            .await?;
+        //发起登录时带了nonce,必须返回id_token并校验
+        let nonce = if self.lib.is_openid() {
+            Some(state_data.nonce.as_str())
+        } else {
+            None
+        };
+        let id_claims = match token.id_token {
+            Some(ref id_token) => Some(self.lib.id_token_verify(
+                &endpoint,
+                id_token,
+                nonce,
+                now_time().map_err(|e| e.to_string())?,
+            )?),
+            None if nonce.is_some() => return Err("oidc token response miss id_token".to_string()),
+            None => None,
+        };
+        let claims = self
+            .lib
+            .oauth2_userinfo(&endpoint, &token, id_claims)
+            .await?;
+        let claim_map = &self.lib.config().claim;
+        let external_id = self
+            .claim(&claims, &claim_map.id)
+            .ok_or_else(|| format!("userinfo miss claim:{}", claim_map.id))?;
+        let external_name = self
+            .claim(&claims, &claim_map.name)
+            .unwrap_or_else(|| external_id.clone());
+        //仅使用IdP确认过的邮箱
+        let external_email = self.claim(&claims, &claim_map.email).filter(|_| {
+            self.claim(&claims, &claim_map.email_verified)
+                .map(|e| e == "true")
+                .unwrap_or(false)
+        });
+        Ok((
+            OauthLoginData {
+                config_name: self.config_name(),
+                external_type: OAUTH_TYPE_OIDC.to_string(),
+                external_nikename: self
+                    .claim(&claims, &claim_map.nikename)
+                    .unwrap_or_else(|| external_name.clone()),
+                external_id,
+                external_name,
+                external_gender: None,
+                external_link: self.claim(&claims, &claim_map.link),
+                external_pic: self.claim(&claims, &claim_map.pic),
+                external_email,
+                token_data: token.access_token,
+                token_timeout: token.expires_in.unwrap_or_default(),
+            },
+            OidcExternalData { claims },
+        ))
+    }
+}
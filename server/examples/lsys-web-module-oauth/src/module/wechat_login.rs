@@ -172,6 +172,7 @@ impl OauthLogin<WechatLoginParam, WechatCallbackParam, WechatExternalData> for W
                 external_link: None,
                 external_pic: Some(info.headimgurl),
                 external_nikename: info.nickname,
+                external_email: None,
                 token_data: resp.access_token,
                 token_timeout: resp.expires_in as u64,
             },
@@ -0,0 +1,331 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use base64::Engine;
+use lsys_web_module_oauth::module::{OidcConfig, OidcLib};
+use reqwest::Url;
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const CLIENT_ID: &str = "test-client";
+const CLIENT_SECRET: &str = "test-secret";
+const ACCESS_TOKEN: &str = "test-access-token";
+const REDIRECT_URI: &str = "http://127.0.0.1/callback";
+
+//模拟IdP: 授权码对应发起登录时的 nonce 及 code_challenge
+#[derive(Clone, Default)]
+struct MockIdp {
+    issuer: String,
+    codes: Arc<Mutex<HashMap<String, (String, String)>>>,
+}
+
+fn b64(data: &[u8]) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(data)
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+//生成未签名的id_token,库不校验签名
+fn id_token(claims: &Value) -> String {
+    format!(
+        "{}.{}.sig",
+        b64(br#"{"alg":"none","typ":"JWT"}"#),
+        b64(claims.to_string().as_bytes())
+    )
+}
+
+fn id_claims(issuer: &str, nonce: &str) -> Value {
+    json!({
+        "iss": issuer,
+        "sub": "user-1",
+        "aud": CLIENT_ID,
+        "exp": now() + 300,
+        "iat": now(),
+        "nonce": nonce,
+    })
+}
+
+fn query_map(data: &str) -> HashMap<String, String> {
+    Url::parse(&format!("http://localhost/?{}", data))
+        .unwrap()
+        .query_pairs()
+        .into_owned()
+        .collect()
+}
+
+impl MockIdp {
+    fn route(&self, method: &str, target: &str, headers: &str, body: &str) -> (u16, String) {
+        let url = Url::parse(&format!("{}{}", self.issuer, target)).unwrap();
+        match (method, url.path()) {
+            ("GET", "/.well-known/openid-configuration") => (
+                200,
+                json!({
+                    "issuer": self.issuer,
+                    "authorization_endpoint": format!("{}/authorize", self.issuer),
+                    "token_endpoint": format!("{}/token", self.issuer),
+                    "userinfo_endpoint": format!("{}/userinfo", self.issuer),
+                })
+                .to_string(),
+            ),
+            ("GET", "/authorize") => {
+                let query = query_map(url.query().unwrap_or_default());
+                let code = format!("code-{}", query["state"]);
+                self.codes.lock().unwrap().insert(
+                    code.clone(),
+                    (query["nonce"].clone(), query["code_challenge"].clone()),
+                );
+                (
+                    302,
+                    format!(
+                        "{}?code={}&state={}",
+                        query["redirect_uri"], code, query["state"]
+                    ),
+                )
+            }
+            ("POST", "/token") => {
+                let form = query_map(body);
+                let (nonce, challenge) = match self.codes.lock().unwrap().remove(&form["code"]) {
+                    Some(e) => e,
+                    None => return (400, r#"{"error":"invalid_grant"}"#.to_string()),
+                };
+                if form["client_id"] != CLIENT_ID
+                    || form["client_secret"] != CLIENT_SECRET
+                    || OidcLib::pkce_challenge(&form["code_verifier"]) != challenge
+                {
+                    return (400, r#"{"error":"invalid_grant"}"#.to_string());
+                }
+                (
+                    200,
+                    json!({
+                        "access_token": ACCESS_TOKEN,
+                        "token_type": "Bearer",
+                        "expires_in": 3600,
+                        "id_token": id_token(&id_claims(&self.issuer, &nonce)),
+                    })
+                    .to_string(),
+                )
+            }
+            ("GET", "/userinfo") => {
+                if !headers
+                    .to_ascii_lowercase()
+                    .contains(&format!("authorization: bearer {}", ACCESS_TOKEN))
+                {
+                    return (401, r#"{"error":"invalid_token"}"#.to_string());
+                }
+                (
+                    200,
+                    json!({
+                        "sub": "user-1",
+                        "preferred_username": "alice",
+                        "name": "Alice",
+                        "email": "alice@example.com",
+                        "email_verified": true,
+                        "org": {"id": 10},
+                    })
+                    .to_string(),
+                )
+            }
+            _ => (404, "{}".to_string()),
+        }
+    }
+    async fn handle(&self, mut stream: TcpStream) {
+        let mut buf = vec![];
+        let mut tmp = [0u8; 4096];
+        let (head, body) = loop {
+            let n = stream.read(&mut tmp).await.unwrap();
+            if n == 0 {
+                return;
+            }
+            buf.extend_from_slice(&tmp[..n]);
+            let data = String::from_utf8_lossy(&buf).to_string();
+            if let Some((head, body)) = data.split_once("\r\n\r\n") {
+                let len = head
+                    .lines()
+                    .find_map(|e| {
+                        let (k, v) = e.split_once(':')?;
+                        k.eq_ignore_ascii_case("content-length")
+                            .then(|| v.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or(0);
+                if body.len() >= len {
+                    break (head.to_string(), body.to_string());
+                }
+            }
+        };
+        let mut line = head.lines().next().unwrap_or_default().split(' ');
+        let method = line.next().unwrap_or_default();
+        let target = line.next().unwrap_or_default();
+        let (status, res) = self.route(method, target, &head, &body);
+        let out = if status == 302 {
+            format!(
+                "HTTP/1.1 302 Found\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                res
+            )
+        } else {
+            format!(
+                "HTTP/1.1 {} MOCK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                res.len(),
+                res
+            )
+        };
+        stream.write_all(out.as_bytes()).await.unwrap();
+    }
+}
+
+async fn mock_idp() -> MockIdp {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let idp = MockIdp {
+        issuer: format!("http://{}", listener.local_addr().unwrap()),
+        ..Default::default()
+    };
+    let server = idp.clone();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let server = server.clone();
+            tokio::spawn(async move { server.handle(stream).await });
+        }
+    });
+    idp
+}
+
+fn oidc_config(issuer: &str) -> OidcConfig {
+    OidcConfig {
+        issuer: issuer.to_string(),
+        client_id: CLIENT_ID.to_string(),
+        client_secret: CLIENT_SECRET.to_string(),
+        scope: "openid profile email".to_string(),
+        use_pkce: true,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_oidc_login_flow() {
+    let idp = mock_idp().await;
+    let lib = OidcLib::new(oidc_config(&idp.issuer));
+    let endpoint = lib.endpoint().await.unwrap();
+    assert_eq!(endpoint.token_endpoint, format!("{}/token", idp.issuer));
+
+    let verifier = "test-code-verifier-0123456789-0123456789-abc";
+    let auth_url = lib
+        .build_authorization_url(&endpoint, REDIRECT_URI, "state1", "nonce1", Some(verifier))
+        .unwrap();
+    let query = query_map(Url::parse(&auth_url).unwrap().query().unwrap());
+    assert_eq!(query["nonce"], "nonce1");
+    assert_eq!(query["code_challenge_method"], "S256");
+
+    //模拟用户在IdP完成授权后跳回
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+    let res = client.get(&auth_url).send().await.unwrap();
+    let location = Url::parse(res.headers()["location"].to_str().unwrap()).unwrap();
+    let callback = query_map(location.query().unwrap());
+    assert_eq!(callback["state"], "state1");
+
+    //错误的 code_verifier 不能换取令牌
+    assert!(lib
+        .oauth2_token(
+            &endpoint,
+            &callback["code"],
+            REDIRECT_URI,
+            Some("bad-verifier")
+        )
+        .await
+        .is_err());
+    let res = client.get(&auth_url).send().await.unwrap();
+    let location = Url::parse(res.headers()["location"].to_str().unwrap()).unwrap();
+    let callback = query_map(location.query().unwrap());
+    let token = lib
+        .oauth2_token(&endpoint, &callback["code"], REDIRECT_URI, Some(verifier))
+        .await
+        .unwrap();
+    assert_eq!(token.access_token, ACCESS_TOKEN);
+
+    let id_token = token.id_token.as_deref().unwrap();
+    assert!(lib
+        .id_token_verify(&endpoint, id_token, Some("nonce2"), now())
+        .is_err());
+    let id_claims = lib
+        .id_token_verify(&endpoint, id_token, Some("nonce1"), now())
+        .unwrap();
+
+    let claims = lib
+        .oauth2_userinfo(&endpoint, &token, Some(id_claims))
+        .await
+        .unwrap();
+    let claim = &lib.config().claim;
+    assert_eq!(OidcLib::claim_value(&claims, &claim.id).unwrap(), "user-1");
+    assert_eq!(OidcLib::claim_value(&claims, &claim.name).unwrap(), "alice");
+    assert_eq!(
+        OidcLib::claim_value(&claims, &claim.email_verified).unwrap(),
+        "true"
+    );
+    assert_eq!(OidcLib::claim_value(&claims, "org.id").unwrap(), "10");
+
+    //userinfo 的 sub 与 id_token 不一致
+    let mut other = OidcLib::id_token_claims(id_token).unwrap();
+    other["sub"] = json!("user-2");
+    assert!(lib
+        .oauth2_userinfo(&endpoint, &token, Some(other))
+        .await
+        .is_err());
+}
+
+#[test]
+fn test_id_token_verify() {
+    let issuer = "https://idp.example.com";
+    let lib = OidcLib::new(oidc_config(issuer));
+    let endpoint = lsys_web_module_oauth::module::OidcEndpoint {
+        issuer: issuer.to_string(),
+        ..Default::default()
+    };
+    let verify = |claims: &Value, nonce: Option<&str>| {
+        lib.id_token_verify(&endpoint, &id_token(claims), nonce, now())
+    };
+    let claims = id_claims(issuer, "n1");
+    assert!(verify(&claims, Some("n1")).is_ok());
+    //未发送nonce时不校验
+    assert!(verify(&claims, None).is_ok());
+
+    let mut tmp = claims.clone();
+    tmp.as_object_mut().unwrap().remove("nonce");
+    assert!(verify(&tmp, Some("n1")).is_err());
+
+    let mut tmp = claims.clone();
+    tmp["iss"] = json!("https://evil.example.com");
+    assert!(verify(&tmp, Some("n1")).is_err());
+
+    let mut tmp = claims.clone();
+    tmp["aud"] = json!("other-client");
+    assert!(verify(&tmp, Some("n1")).is_err());
+
+    let mut tmp = claims.clone();
+    tmp["aud"] = json!([CLIENT_ID, "other-client"]);
+    assert!(verify(&tmp, Some("n1")).is_err());
+    tmp["azp"] = json!(CLIENT_ID);
+    assert!(verify(&tmp, Some("n1")).is_ok());
+
+    let mut tmp = claims.clone();
+    tmp["exp"] = json!(now() - 1);
+    assert!(verify(&tmp, Some("n1")).is_err());
+
+    let mut tmp = claims.clone();
+    tmp.as_object_mut().unwrap().remove("exp");
+    assert!(verify(&tmp, Some("n1")).is_err());
+
+    //未配置issuer时无法校验id_token
+    let endpoint = lsys_web_module_oauth::module::OidcEndpoint::default();
+    assert!(lib
+        .id_token_verify(&endpoint, &id_token(&claims), Some("n1"), now())
+        .is_err());
+}
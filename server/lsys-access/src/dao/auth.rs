@@ -15,12 +15,13 @@ use lsys_core::db::{ModelTableName, SqlQuote, Update, WhereOption};
 use lsys_core::{model_option_set, sql_format};
 use serde::{Deserialize, Serialize};
 use sqlx::{MySql, Pool};
+use tracing::warn;
 
 use crate::model::{
     SessionDataModel, SessionDataModelRef, SessionModel, SessionModelRef, SessionStatus, UserModel,
 };
 
-use super::{AccessError, AccessResult, SessionBody};
+use super::{AccessError, AccessResult, AccessSessionTimeoutConfig, SessionBody};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct AccessAuthSessionCacheKey {
@@ -40,12 +41,16 @@ impl FromStr for AccessAuthSessionCacheKey {
     }
 }
 
+//最后活动时间更新间隔
+const SESSION_ACTIVE_INTERVAL: u64 = 300;
+
 pub struct AccessAuth {
-    db: Pool<MySql>,
+    pub(crate) db: Pool<MySql>,
     user: Arc<AccessUser>,
     pub(crate) session_cache: Arc<LocalCache<AccessAuthSessionCacheKey, SessionModel>>,
     pub(crate) session_data_cache:
         Arc<LocalCache<AccessAuthSessionCacheKey, Vec<(String, String)>>>,
+    pub(crate) timeout_config: AccessSessionTimeoutConfig,
 }
 
 impl AccessAuth {
@@ -54,6 +59,7 @@ impl AccessAuth {
         user: Arc<AccessUser>,
         remote_notify: Arc<RemoteNotify>,
         config: LocalCacheConfig,
+        timeout_config: AccessSessionTimeoutConfig,
     ) -> Self {
         Self {
             // user_cache: Arc::new(LocalCache::new(remote_notify.clone(), config)),
@@ -61,6 +67,7 @@ impl AccessAuth {
             session_data_cache: Arc::new(LocalCache::new(remote_notify, config)),
             db,
             user,
+            timeout_config,
         }
     }
     //通过ID获取用户
@@ -82,7 +89,7 @@ impl AccessAuth {
         session_body.valid()?;
         Ok(session_body)
     }
-    async fn load_session_body(&self, session: SessionModel) -> AccessResult<SessionBody> {
+    pub(crate) async fn load_session_body(&self, session: SessionModel) -> AccessResult<SessionBody> {
         let user = self.find_user_by_id(&session.user_id).await?;
         self.wrap_session_body(session, user)
     }
@@ -94,8 +101,10 @@ pub struct AccessLoginData<'t> {
     pub login_ip: Option<&'t str>,
     pub device_id: Option<&'t str>,
     pub device_name: Option<&'t str>,
+    pub user_agent: Option<&'t str>,
     pub expire_time: u64,
     pub session_data: Vec<(&'t str, &'t str)>,
+    pub impersonate_user_id: u64, //模拟登录时实际操作的用户ID
 }
 impl AccessAuth {
     //強制指定應用全部下線
@@ -152,6 +161,87 @@ impl AccessAuth {
     }
 }
 
+impl AccessAuth {
+    //强制指定登录下线
+    pub async fn logout_session(&self, user_id: u64, session_id: u64) -> AccessResult<()> {
+        let session = match sqlx::query_as::<_, SessionModel>(&sql_format!(
+            "select * from {} where id={} and user_id={} and status={}",
+            SessionModel::table_name(),
+            session_id,
+            user_id,
+            SessionStatus::Enable as i8,
+        ))
+        .fetch_one(&self.db)
+        .await
+        {
+            Ok(session) => session,
+            Err(sqlx::Error::RowNotFound) => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+        self.logout_session_vec(&[session]).await
+    }
+    //强制用户全部登录下线,可排除指定登录,返回下线数量
+    pub async fn logout_user_session(
+        &self,
+        user_id: u64,
+        user_app_id: Option<u64>,
+        exclude_session_id: Option<u64>,
+    ) -> AccessResult<u64> {
+        let mut where_sql = vec![
+            sql_format!("user_id={}", user_id),
+            sql_format!("status={}", SessionStatus::Enable as i8),
+        ];
+        if let Some(app_id) = user_app_id {
+            where_sql.push(sql_format!("user_app_id={}", app_id));
+        }
+        if let Some(session_id) = exclude_session_id {
+            where_sql.push(sql_format!("id!={}", session_id));
+        }
+        let mut total = 0;
+        loop {
+            let res = sqlx::query_as::<_, SessionModel>(&format!(
+                "select * from {} where {} order by id asc limit 100",
+                SessionModel::table_name(),
+                where_sql.join(" and ")
+            ))
+            .fetch_all(&self.db)
+            .await?;
+            if res.is_empty() {
+                break;
+            }
+            total += res.len() as u64;
+            self.logout_session_vec(&res).await?;
+        }
+        Ok(total)
+    }
+    pub(crate) async fn logout_session_vec(&self, session: &[SessionModel]) -> AccessResult<()> {
+        if session.is_empty() {
+            return Ok(());
+        }
+        let time = now_time()?;
+        let status = SessionStatus::Delete.to();
+        let change = lsys_core::model_option_set!(SessionModelRef,{
+            status:status,
+            logout_time:time,
+        });
+        Update::<SessionModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!(
+                    "id in ({})",
+                    session.iter().map(|e| e.id).collect::<Vec<_>>()
+                )),
+                &self.db,
+            )
+            .await?;
+        for tmp in session {
+            self.cache()
+                .del_session(tmp.user_app_id, tmp.oauth_app_id, &tmp.token_data)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
 pub struct AccessAuthLoginData<'t, TS: ToString> {
     pub app_id: u64,
     pub oauth_app_id: u64,
@@ -280,11 +370,25 @@ impl AccessAuth {
             .map(|e| e.device_id.unwrap_or_default().to_string())
             .unwrap_or_default();
 
-        let expire_time = login_param
+        let timeout = self.timeout_config.find(
+            login_param.app_id,
+            login_param.oauth_app_id,
+            login_param.login_type,
+        );
+        let idle_time = timeout.idle_time;
+        let absolute_time = if timeout.absolute_time > 0 {
+            time + timeout.absolute_time
+        } else {
+            0
+        };
+        let mut expire_time = login_param
             .login_data
             .as_ref()
             .map(|e| e.expire_time)
             .unwrap_or_default();
+        if absolute_time > 0 && expire_time > absolute_time {
+            expire_time = absolute_time;
+        }
         let login_ip = login_param
             .login_data
             .as_ref()
@@ -295,7 +399,23 @@ impl AccessAuth {
             .as_ref()
             .map(|e| e.device_name.unwrap_or_default().to_string())
             .unwrap_or_default();
+        let user_agent = login_param
+            .login_data
+            .as_ref()
+            .map(|e| {
+                string_clear(
+                    e.user_agent.unwrap_or_default(),
+                    StringClear::Option(STRING_CLEAR_FORMAT),
+                    Some(255),
+                )
+            })
+            .unwrap_or_default();
         let login_type = login_param.login_type.to_owned();
+        let impersonate_user_id = login_param
+            .login_data
+            .as_ref()
+            .map(|e| e.impersonate_user_id)
+            .unwrap_or_default();
         let session_data = login_param
             .login_data
             .as_ref()
@@ -325,7 +445,12 @@ impl AccessAuth {
                 let mut db = self.db.begin().await?;
                 let change = lsys_core::model_option_set!(SessionModelRef,{
                     device_name:device_name,
+                    user_agent:user_agent,
                     expire_time:expire_time,
+                    last_time:time,
+                    idle_time:idle_time,
+                    absolute_time:absolute_time,
+                    auth_time:time,
                 });
                 if let Err(err) = Update::<SessionModel, _>::new(change)
                     .execute_by_where(&WhereOption::Where(sql_format!("id={} ", sid)), &self.db)
@@ -373,10 +498,16 @@ impl AccessAuth {
                     login_ip:login_ip,
                     device_id:device_id,
                     device_name:device_name,
+                    user_agent:user_agent,
                     status:SessionStatus::Enable as i8,
                     add_time:time,
                     expire_time:expire_time,
                     logout_time:0,
+                    last_time:time,
+                    idle_time:idle_time,
+                    absolute_time:absolute_time,
+                    auth_time:time,
+                    impersonate_user_id:impersonate_user_id,
                 });
 
                 let sid = match Insert::<SessionModel, _>::new(vdata)
@@ -424,11 +555,13 @@ impl AccessAuth {
         add_time: u64,
     ) -> AccessResult<SessionBody> {
         session_body.valid()?;
+        //模拟登录不允许延长
+        session_body.impersonate_deny()?;
         let mut session = session_body.session().to_owned();
         if add_time == 0 || session.expire_time == 0 {
             return Ok(SessionBody::new(session_body.user().to_owned(), session));
         }
-        let expire_time = add_time + session.expire_time;
+        let expire_time = Self::limit_expire_time(&session, add_time + session.expire_time);
         let change = lsys_core::model_option_set!(SessionModelRef,{
             expire_time:expire_time,
         });
@@ -623,6 +756,45 @@ impl AccessAuth {
             .await?;
         Ok(())
     }
+    //更新最后活动时间,间隔时间内不重复更新
+    async fn touch_session(
+        &self,
+        cache_key: AccessAuthSessionCacheKey,
+        mut session: SessionModel,
+    ) -> SessionModel {
+        let time = now_time().unwrap_or_default();
+        //已空闲超时的登录不再更新
+        if session.idle_time > 0 && session.last_time + session.idle_time <= time {
+            return session;
+        }
+        let interval = if session.idle_time > 0 {
+            SESSION_ACTIVE_INTERVAL.min(session.idle_time / 2)
+        } else {
+            SESSION_ACTIVE_INTERVAL
+        };
+        if session.last_time + interval > time {
+            return session;
+        }
+        let change = lsys_core::model_option_set!(SessionModelRef,{
+            last_time:time,
+        });
+        match Update::<SessionModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("id={} ", session.id)),
+                &self.db,
+            )
+            .await
+        {
+            Ok(_) => {
+                session.last_time = time;
+                self.session_cache.set(cache_key, session.clone(), 0).await;
+            }
+            Err(err) => {
+                warn!("update session {} last time fail:{}", session.id, err);
+            }
+        }
+        session
+    }
     pub fn cache(&'_ self) -> AccessAuthCache<'_> {
         AccessAuthCache { dao: self }
     }
@@ -714,14 +886,15 @@ impl AccessAuthCache<'_> {
                     .await?;
                 self.dao
                     .session_cache
-                    .set(cache_key, session_model.clone(), 0)
+                    .set(cache_key.clone(), session_model.clone(), 0)
                     .await;
                 session_model
             }
         };
+        let session_model = self.dao.touch_session(cache_key, session_model).await;
         self.dao.load_session_body(session_model).await
     }
-    async fn del_session(
+    pub(crate) async fn del_session(
         &self,
         app_id: u64,
         oauth_app_id: u64,
@@ -0,0 +1,178 @@
+//登录刷新token
+//每次刷新都会生成新的刷新token,旧token标记为已轮换
+//同一登录下的刷新token为一组,已轮换的token被再次使用时视为泄露,注销整组token及对应登录
+use lsys_core::db::{Insert, ModelTableName, SqlQuote, Update, WhereOption};
+use lsys_core::{model_option_set, now_time, rand_str, sql_format, RandType};
+use tracing::warn;
+
+use crate::model::{
+    SessionModel, SessionModelRef, SessionRefreshModel, SessionRefreshModelRef,
+    SessionRefreshStatus, SessionStatus, UserModel,
+};
+
+use super::{AccessAuth, AccessError, AccessResult, SessionBody};
+
+impl AccessAuth {
+    /// 为登录创建刷新token
+    /// login_time 刷新后登录有效时长 refresh_time 刷新token有效时长
+    pub async fn create_refresh_token(
+        &self,
+        session_body: &SessionBody,
+        login_time: u64,
+        refresh_time: u64,
+    ) -> AccessResult<String> {
+        session_body.valid()?;
+        let session = session_body.session();
+        let token_data = rand_str(RandType::Lower, 32);
+        let add_time = now_time()?;
+        let time_out = add_time + refresh_time;
+        let status = SessionRefreshStatus::Enable as i8;
+        let vdata = model_option_set!(SessionRefreshModelRef,{
+            session_id:session.id,
+            user_app_id:session.user_app_id,
+            oauth_app_id:session.oauth_app_id,
+            token_data:token_data,
+            login_time:login_time,
+            status:status,
+            time_out:time_out,
+            add_time:add_time,
+            use_time:0,
+        });
+        Insert::<SessionRefreshModel, _>::new(vdata)
+            .execute(&self.db)
+            .await?;
+        Ok(token_data)
+    }
+    /// 使用刷新token延长登录
+    /// 返回延长后的登录数据及新的刷新token
+    pub async fn refresh_login(
+        &self,
+        app_id: u64,
+        oauth_app_id: u64,
+        refresh_token: &str,
+    ) -> AccessResult<(SessionBody, String)> {
+        let refresh = sqlx::query_as::<_, SessionRefreshModel>(&sql_format!(
+            "select * from {} where user_app_id={} and oauth_app_id={} and token_data={}",
+            SessionRefreshModel::table_name(),
+            app_id,
+            oauth_app_id,
+            refresh_token,
+        ))
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => AccessError::NotLogin,
+            _ => AccessError::Sqlx(e),
+        })?;
+        if SessionRefreshStatus::Rotated.eq(refresh.status) {
+            self.refresh_token_reuse(&refresh).await?;
+            return Err(AccessError::RefreshTokenReuse(refresh.session_id));
+        }
+        let time = now_time()?;
+        if !SessionRefreshStatus::Enable.eq(refresh.status) || refresh.time_out <= time {
+            return Err(AccessError::NotLogin);
+        }
+        let status = SessionRefreshStatus::Rotated as i8;
+        let change = model_option_set!(SessionRefreshModelRef,{
+            status:status,
+            use_time:time,
+        });
+        let res = Update::<SessionRefreshModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!(
+                    "id={} and status={}",
+                    refresh.id,
+                    SessionRefreshStatus::Enable as i8
+                )),
+                &self.db,
+            )
+            .await?;
+        if res.rows_affected() == 0 {
+            //同一token被并发使用
+            self.refresh_token_reuse(&refresh).await?;
+            return Err(AccessError::RefreshTokenReuse(refresh.session_id));
+        }
+        let mut session = sqlx::query_as::<_, SessionModel>(&sql_format!(
+            "select * from {} where id={} and status={}",
+            SessionModel::table_name(),
+            refresh.session_id,
+            SessionStatus::Enable as i8,
+        ))
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => AccessError::NotLogin,
+            _ => AccessError::Sqlx(e),
+        })?;
+        let expire_time = Self::limit_expire_time(&session, time + refresh.login_time);
+        let change = model_option_set!(SessionModelRef,{
+            expire_time:expire_time,
+            last_time:time,
+        });
+        Update::<SessionModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("id={} ", session.id)),
+                &self.db,
+            )
+            .await?;
+        self.cache()
+            .del_session(
+                session.user_app_id,
+                session.oauth_app_id,
+                &session.token_data,
+            )
+            .await?;
+        session.expire_time = expire_time;
+        session.last_time = time;
+        let session_body = self.load_session_body(session).await?;
+        let token_data = self
+            .create_refresh_token(
+                &session_body,
+                refresh.login_time,
+                refresh.time_out.saturating_sub(refresh.add_time),
+            )
+            .await?;
+        Ok((session_body, token_data))
+    }
+    /// 获取登录对应的用户,含已注销的登录,用于记录刷新token被再次使用
+    pub async fn refresh_session_user(&self, session_id: u64) -> AccessResult<UserModel> {
+        let user_id = sqlx::query_scalar::<_, u64>(&sql_format!(
+            "select user_id from {} where id={}",
+            SessionModel::table_name(),
+            session_id,
+        ))
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => AccessError::NotLogin,
+            _ => AccessError::Sqlx(e),
+        })?;
+        self.find_user_by_id(&user_id).await
+    }
+    //已轮换的刷新token被再次使用,注销同组刷新token及登录
+    async fn refresh_token_reuse(&self, refresh: &SessionRefreshModel) -> AccessResult<()> {
+        warn!(
+            "security: refresh token reuse detected, revoke session {} [app:{} oauth app:{} token id:{}]",
+            refresh.session_id, refresh.user_app_id, refresh.oauth_app_id, refresh.id
+        );
+        let status = SessionRefreshStatus::Delete as i8;
+        let change = model_option_set!(SessionRefreshModelRef,{
+            status:status,
+        });
+        Update::<SessionRefreshModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("session_id={}", refresh.session_id)),
+                &self.db,
+            )
+            .await?;
+        let session = sqlx::query_as::<_, SessionModel>(&sql_format!(
+            "select * from {} where id={} and status={}",
+            SessionModel::table_name(),
+            refresh.session_id,
+            SessionStatus::Enable as i8,
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        self.logout_session_vec(&session).await
+    }
+}
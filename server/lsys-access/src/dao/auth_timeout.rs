@@ -0,0 +1,128 @@
+//登录空闲超时及最长有效期,可按登录类型及应用配置
+//敏感操作需最近验证过身份(二次验证)
+use std::collections::HashMap;
+
+use lsys_core::db::{SqlQuote, Update, WhereOption};
+use lsys_core::{fluent_message, model_option_set, now_time, sql_format};
+
+use crate::model::{SessionModel, SessionModelRef};
+
+use super::{AccessAuth, AccessError, AccessResult, SessionBody};
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AccessSessionTimeout {
+    pub idle_time: u64,     //空闲超时时长,0 不限制
+    pub absolute_time: u64, //登录后最长有效时长,0 不限制
+}
+
+#[derive(Clone, Debug)]
+pub struct AccessSessionTimeoutConfig {
+    pub default: AccessSessionTimeout,
+    pub login_type: HashMap<String, AccessSessionTimeout>,
+    pub app: HashMap<u64, AccessSessionTimeout>,
+    pub step_up_time: u64, //敏感操作要求的最近验证时长,0 不检测
+    pub step_up_deny_login_type: Vec<String>, //不能执行敏感操作的登录类型,如个人访问令牌
+}
+
+impl Default for AccessSessionTimeoutConfig {
+    fn default() -> Self {
+        Self {
+            default: AccessSessionTimeout::default(),
+            login_type: HashMap::new(),
+            app: HashMap::new(),
+            step_up_time: 600,
+            step_up_deny_login_type: vec![],
+        }
+    }
+}
+
+impl AccessSessionTimeoutConfig {
+    //优先级: 应用 > 登录类型 > 默认
+    pub fn find(
+        &self,
+        user_app_id: u64,
+        oauth_app_id: u64,
+        login_type: &str,
+    ) -> AccessSessionTimeout {
+        if oauth_app_id > 0 {
+            if let Some(tmp) = self.app.get(&oauth_app_id) {
+                return *tmp;
+            }
+        }
+        if let Some(tmp) = self.app.get(&user_app_id) {
+            return *tmp;
+        }
+        if let Some(tmp) = self.login_type.get(login_type) {
+            return *tmp;
+        }
+        self.default
+    }
+}
+
+impl AccessAuth {
+    /// 检测登录是否在要求时间内验证过身份
+    /// 模拟登录及配置的登录类型不允许执行敏感操作
+    pub fn step_up_check(&self, session_body: &SessionBody) -> AccessResult<()> {
+        session_body.valid()?;
+        session_body.impersonate_deny()?;
+        self.step_up_login_type_check(session_body)?;
+        let step_up_time = self.timeout_config.step_up_time;
+        if step_up_time == 0 {
+            return Ok(());
+        }
+        if session_body.session().auth_time + step_up_time > now_time()? {
+            return Ok(());
+        }
+        Err(AccessError::NeedReauth(step_up_time))
+    }
+    /// 重新验证身份后更新登录的验证时间
+    pub async fn reauth(&self, session_body: &SessionBody) -> AccessResult<SessionBody> {
+        session_body.valid()?;
+        session_body.impersonate_deny()?;
+        self.step_up_login_type_check(session_body)?;
+        let mut session = session_body.session().to_owned();
+        let auth_time = now_time()?;
+        let change = model_option_set!(SessionModelRef,{
+            auth_time:auth_time,
+            last_time:auth_time,
+        });
+        Update::<SessionModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("id={} ", session.id)),
+                &self.db,
+            )
+            .await?;
+        self.cache()
+            .del_session(
+                session.user_app_id,
+                session.oauth_app_id,
+                &session.token_data,
+            )
+            .await?;
+        session.auth_time = auth_time;
+        session.last_time = auth_time;
+        self.load_session_body(session).await
+    }
+    fn step_up_login_type_check(&self, session_body: &SessionBody) -> AccessResult<()> {
+        let login_type = &session_body.session().login_type;
+        if self
+            .timeout_config
+            .step_up_deny_login_type
+            .contains(login_type)
+        {
+            return Err(AccessError::System(fluent_message!(
+                "access-step-up-deny",
+                {"login_type":login_type}
+            )));
+        }
+        Ok(())
+    }
+    //延长后的超时时间不超过最长有效期
+    pub(crate) fn limit_expire_time(session: &SessionModel, expire_time: u64) -> u64 {
+        if session.absolute_time > 0 && expire_time > session.absolute_time {
+            session.absolute_time
+        } else {
+            expire_time
+        }
+    }
+}
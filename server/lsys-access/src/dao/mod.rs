@@ -1,4 +1,6 @@
 mod auth;
+mod auth_refresh;
+mod auth_timeout;
 mod cache;
 mod result;
 mod session;
@@ -6,6 +8,7 @@ mod user;
 use std::sync::Arc;
 
 pub use auth::*;
+pub use auth_timeout::*;
 pub use cache::*;
 use lsys_core::{cache::LocalCacheConfig, RemoteNotify};
 pub use result::*;
@@ -23,6 +26,7 @@ pub struct AccessDao {
 pub struct AccessConfig {
     pub auth_cache: LocalCacheConfig,
     pub user_cache: LocalCacheConfig,
+    pub session_timeout: AccessSessionTimeoutConfig,
 }
 
 impl AccessConfig {
@@ -30,6 +34,7 @@ impl AccessConfig {
         Self {
             auth_cache: LocalCacheConfig::new("auth", if use_cache { None } else { Some(0) }, None),
             user_cache: LocalCacheConfig::new("user", if use_cache { None } else { Some(0) }, None),
+            session_timeout: AccessSessionTimeoutConfig::default(),
         }
     }
 }
@@ -50,6 +55,7 @@ impl AccessDao {
             user.clone(),
             remote_notify,
             config.user_cache,
+            config.session_timeout,
         ));
         Self { auth, user }
     }
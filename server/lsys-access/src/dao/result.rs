@@ -11,6 +11,9 @@ pub enum AccessError {
     NotLogin,
     IsLogout,
     LoginTokenDataExit(u64),
+    RefreshTokenReuse(u64),
+    NeedReauth(u64),
+    ImpersonateDeny(u64),
     System(FluentMessage),
     SerdeJson(serde_json::Error),
     BadAccount(FluentMessage),
@@ -23,6 +26,15 @@ impl IntoFluentMessage for AccessError {
             AccessError::LoginTokenDataExit(id) => {
                 fluent_message!("access-token-data-exits",{"id":id,})
             }
+            AccessError::RefreshTokenReuse(id) => {
+                fluent_message!("access-refresh-token-reuse",{"id":id,})
+            }
+            AccessError::NeedReauth(time) => {
+                fluent_message!("access-need-reauth",{"time":time,})
+            }
+            AccessError::ImpersonateDeny(user_id) => {
+                fluent_message!("access-impersonate-deny",{"user_id":user_id,})
+            }
             AccessError::NotLogin => fluent_message!("access-not-login"),
             AccessError::IsLogout => fluent_message!("access-not-login"),
             AccessError::Sqlx(err) => fluent_message!("sqlx-error", err),
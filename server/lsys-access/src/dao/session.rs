@@ -41,14 +41,37 @@ impl SessionBody {
     pub fn token_data(&self) -> &str {
         &self.session.token_data
     }
+    //返回登录记录id
+    pub fn session_id(&self) -> u64 {
+        self.session.id
+    }
     //返回用户id
     pub fn user_id(&self) -> u64 {
         self.user.id
     }
+    //模拟登录时返回实际操作的用户id
+    pub fn impersonate_user_id(&self) -> Option<u64> {
+        if self.session.impersonate_user_id > 0 {
+            Some(self.session.impersonate_user_id)
+        } else {
+            None
+        }
+    }
+    //模拟登录时返回错误,用于禁止敏感操作
+    pub fn impersonate_deny(&self) -> AccessResult<()> {
+        if let Some(user_id) = self.impersonate_user_id() {
+            return Err(AccessError::ImpersonateDeny(user_id));
+        }
+        Ok(())
+    }
     //当前session是否有效
     pub fn is_valid(&self) -> bool {
+        let time = now_time().unwrap_or_default();
         SessionStatus::Enable.eq(self.session.status)
-            && self.session.expire_time > now_time().unwrap_or_default()
+            && self.session.expire_time > time
+            && (self.session.absolute_time == 0 || self.session.absolute_time > time)
+            && (self.session.idle_time == 0
+                || self.session.last_time + self.session.idle_time > time)
     }
     //当前sesson是否有效，无效返回错误
     pub fn valid(&self) -> AccessResult<()> {
@@ -61,7 +84,12 @@ impl SessionBody {
 
 //登录标识数据，如cookie,sessionid等
 //需根据使用登陆自行实现该特征
-pub trait AccessSessionToken {}
+pub trait AccessSessionToken {
+    //是否为模拟登录会话的令牌,由令牌本身判断,不加载会话数据
+    fn is_impersonate(&self) -> bool {
+        false
+    }
+}
 
 //SESSION数据
 pub trait AccessSessionData {
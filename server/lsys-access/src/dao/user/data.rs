@@ -192,10 +192,12 @@ pub struct SessionDataRecord {
     pub login_ip: String,
     pub device_id: String,
     pub device_name: String,
+    pub user_agent: String,
     pub status: i8,
     pub add_time: u64,
     pub expire_time: u64,
     pub logout_time: u64,
+    pub last_time: u64,
 }
 
 impl AccessUser {
@@ -294,6 +296,7 @@ impl AccessUser {
                     login_ip: e.login_ip,
                     device_id: e.device_id,
                     device_name: e.device_name,
+                    user_agent: e.user_agent,
                     status: if SessionStatus::Enable.eq(e.status) {
                         if e.expire_time > ntime {
                             SessionStatus::Enable as i8
@@ -306,6 +309,7 @@ impl AccessUser {
                     add_time: e.add_time,
                     expire_time: e.expire_time,
                     logout_time: e.logout_time,
+                    last_time: e.last_time,
                 })
                 .collect::<Vec<_>>(),
             next,
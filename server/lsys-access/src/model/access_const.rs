@@ -7,3 +7,11 @@ pub enum SessionStatus {
     Enable = 1,
     Delete = 2,
 }
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[lsys_model_status(field_type = "i8")]
+pub enum SessionRefreshStatus {
+    Enable = 1,  //可用
+    Rotated = 2, //已使用并轮换
+    Delete = 3,  //已注销
+}
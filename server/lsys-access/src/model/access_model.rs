@@ -71,6 +71,10 @@ pub struct SessionModel {
     #[sqlx(default)]
     pub device_name: String,
 
+    ///客户端标识
+    #[sqlx(default)]
+    pub user_agent: String,
+
     ///状态
     #[sqlx(default)]
     pub status: i8,
@@ -86,6 +90,26 @@ pub struct SessionModel {
     /// 超时时间
     #[sqlx(default)]
     pub logout_time: u64,
+
+    /// 最后活动时间
+    #[sqlx(default)]
+    pub last_time: u64,
+
+    /// 空闲超时时长,0 不限制
+    #[sqlx(default)]
+    pub idle_time: u64,
+
+    /// 最长有效截止时间,0 不限制
+    #[sqlx(default)]
+    pub absolute_time: u64,
+
+    /// 最后身份验证时间
+    #[sqlx(default)]
+    pub auth_time: u64,
+
+    /// 模拟登录时实际操作的用户ID,0 非模拟登录
+    #[sqlx(default)]
+    pub impersonate_user_id: u64,
 }
 
 #[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
@@ -110,3 +134,46 @@ pub struct SessionDataModel {
     #[sqlx(default)]
     pub(crate) change_time: u64,
 }
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+#[lsys_model(table_name = "session_refresh")]
+pub struct SessionRefreshModel {
+    #[sqlx(default)]
+    pub(crate) id: u64,
+
+    /// 登录记录ID
+    #[sqlx(default)]
+    pub(crate) session_id: u64,
+
+    /// 冗余yaf_user的app_id
+    #[sqlx(default)]
+    pub user_app_id: u64,
+
+    /// OAUTH登录时的app_id
+    #[sqlx(default)]
+    pub oauth_app_id: u64,
+
+    ///刷新token
+    #[sqlx(default)]
+    pub token_data: String,
+
+    /// 刷新后登录有效时长
+    #[sqlx(default)]
+    pub login_time: u64,
+
+    ///状态
+    #[sqlx(default)]
+    pub status: i8,
+
+    /// 过期时间
+    #[sqlx(default)]
+    pub time_out: u64,
+
+    /// 添加时间
+    #[sqlx(default)]
+    pub add_time: u64,
+
+    /// 使用时间
+    #[sqlx(default)]
+    pub use_time: u64,
+}
@@ -20,10 +20,16 @@ CREATE TABLE `yaf_session` (
     `login_ip` varchar(46) NOT NULL DEFAULT '' COMMENT '登陆者IP',
     `device_id` varchar(64) NOT NULL DEFAULT '' COMMENT '设备ID',
     `device_name` varchar(255) NOT NULL DEFAULT '' COMMENT '设备名',
+    `user_agent` varchar(255) NOT NULL DEFAULT '' COMMENT '客户端标识',
     `status` tinyint NOT NULL COMMENT '状态',
     `add_time` bigint unsigned NOT NULL COMMENT '登录时间',
     `expire_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '超时时间',
     `logout_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '退出时间',
+    `last_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '最后活动时间',
+    `idle_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '空闲超时时长,0 不限制',
+    `absolute_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '最长有效截止时间,0 不限制',
+    `auth_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '最后身份验证时间',
+    `impersonate_user_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '模拟登录时实际操作的用户ID,0 非模拟登录',
     PRIMARY KEY (`id`),
     KEY `user_index` (`user_id`) USING BTREE,
     UNIQUE KEY `session_index` (
@@ -46,4 +52,19 @@ CREATE TABLE `yaf_session_data` (
     `change_time` int unsigned NOT NULL DEFAULT 0 COMMENT '最后更改时间',
     PRIMARY KEY (`id`),
     UNIQUE KEY `session_id_index` (`session_id`, `data_key`) USING BTREE
-) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '用户登录授权数据';
\ No newline at end of file
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '用户登录授权数据';
+CREATE TABLE `yaf_session_refresh` (
+    `id` bigint unsigned NOT NULL AUTO_INCREMENT,
+    `session_id` bigint unsigned NOT NULL COMMENT '登录记录ID,同一登录的刷新token为一组',
+    `user_app_id` bigint unsigned NOT NULL COMMENT '冗余yaf_user的app_id',
+    `oauth_app_id` bigint unsigned NOT NULL COMMENT 'OAUTH登录时的app_id',
+    `token_data` varchar(64) NOT NULL COMMENT '刷新token',
+    `login_time` bigint unsigned NOT NULL COMMENT '刷新后登录有效时长',
+    `status` tinyint NOT NULL COMMENT '状态',
+    `time_out` bigint unsigned NOT NULL COMMENT '过期时间',
+    `add_time` bigint unsigned NOT NULL COMMENT '添加时间',
+    `use_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '使用时间',
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `token_index` (`user_app_id`, `oauth_app_id`, `token_data`) USING BTREE,
+    KEY `session_index` (`session_id`) USING BTREE
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '用户登录刷新token';
@@ -233,6 +233,20 @@ impl MailRecord {
     pub async fn message_log_count(&self, message_id: u64) -> SenderResult<i64> {
         self.message_logs.list_count(message_id).await
     }
+    //清空指定收件邮箱的发送记录中的邮箱,用于账号注销,保留记录本身
+    pub async fn message_anonymize(&self, to_mail: &str) -> SenderResult<u64> {
+        let empty_mail = "".to_string();
+        let change = lsys_core::model_option_set!(SenderMailMessageModelRef,{
+            to_mail:empty_mail,
+        });
+        Ok(Update::<SenderMailMessageModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("to_mail={}", to_mail)),
+                &self.db,
+            )
+            .await?
+            .rows_affected())
+    }
     pub async fn message_log_list(
         &self,
         message_id: u64,
@@ -243,6 +243,21 @@ impl SmsRecord {
     pub async fn message_log_count(&self, message_id: u64) -> SenderResult<i64> {
         self.message_logs.list_count(message_id).await
     }
+    //清空指定手机号的发送记录中的号码,用于账号注销,保留记录本身
+    pub async fn message_anonymize(&self, area: &str, mobile: &str) -> SenderResult<u64> {
+        let empty_str = "".to_string();
+        let change = lsys_core::model_option_set!(SenderSmsMessageModelRef,{
+            area:empty_str,
+            mobile:empty_str,
+        });
+        Ok(Update::<SenderSmsMessageModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("area={} and mobile={}", area, mobile)),
+                &self.db,
+            )
+            .await?
+            .rows_affected())
+    }
     //消息日志列表
     pub async fn message_log_list(
         &self,
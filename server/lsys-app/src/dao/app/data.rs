@@ -565,11 +565,16 @@ pub struct UserAppDataParam<'t> {
     pub client_id: Option<&'t str>,
     pub like_client_id: Option<&'t str>,
     pub app_id: Option<u64>,
+    pub org_id: Option<u64>, //Some(0) 仅个人应用,Some(>0) 查询组织应用,不再限制用户
 }
 
 impl App {
     fn user_app_data_sql(&self, user_id: u64, app_where: &UserAppDataParam) -> Option<Vec<String>> {
-        let mut sql_vec = vec![sql_format!("user_id={}", user_id)];
+        let mut sql_vec = match app_where.org_id {
+            Some(org_id) if org_id > 0 => vec![sql_format!("org_id={}", org_id)],
+            Some(_) => vec![sql_format!("user_id={} and org_id=0", user_id)],
+            None => vec![sql_format!("user_id={}", user_id)],
+        };
         if let Some(ref rid) = app_where.parent_app_id {
             sql_vec.push(sql_format!("parent_app_id = {}", rid));
         };
@@ -0,0 +1,296 @@
+use super::AppOrg;
+use crate::dao::logger::AppOrgMemberLog;
+use crate::dao::{AppError, AppResult};
+use crate::model::{
+    AppOrgInviteModel, AppOrgInviteModelRef, AppOrgInviteStatus, AppOrgMemberRole, AppOrgModel,
+};
+use lsys_core::db::{Insert, ModelTableName, SqlExpr, SqlQuote, Update, WhereOption};
+use lsys_core::{
+    fluent_message, model_option_set, now_time, rand_str, sql_format, valid_key, PageParam,
+    RandType, RequestEnv, ValidEmail, ValidParam, ValidParamCheck,
+};
+
+impl AppOrg {
+    //邀请邮箱加入组织,同一邮箱未处理的邀请将被取消
+    pub async fn invite_add(
+        &self,
+        org: &AppOrgModel,
+        email: &str,
+        role: AppOrgMemberRole,
+        time_out: u64,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AppResult<AppOrgInviteModel> {
+        let email = email.trim().to_owned();
+        ValidParam::default()
+            .add(
+                valid_key!("email"),
+                &email,
+                &ValidParamCheck::default().add_rule(ValidEmail::default()),
+            )
+            .check()?;
+        //所有者需成员加入后由所有者设置
+        if role == AppOrgMemberRole::Owner {
+            return Err(AppError::System(fluent_message!("app-org-invite-bad-role")));
+        }
+        self.member_role_check(org, op_user_id, AppOrgMemberRole::Admin)
+            .await?;
+        let time = now_time()?;
+        let invite_code = rand_str(RandType::UpperHex, 32);
+        let pending = AppOrgInviteStatus::Pending as i8;
+        let cancel = AppOrgInviteStatus::Cancel as i8;
+        let mut db = self.db.begin().await?;
+        let change = model_option_set!(AppOrgInviteModelRef,{
+            status:cancel,
+            change_time:time,
+        });
+        if let Err(err) = Update::<AppOrgInviteModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!(
+                    "org_id={} and email={} and status={}",
+                    org.id,
+                    email,
+                    pending
+                )),
+                &mut *db,
+            )
+            .await
+        {
+            db.rollback().await?;
+            return Err(err.into());
+        }
+        let role = role as i8;
+        let invite_time_out = time + time_out;
+        let idata = model_option_set!(AppOrgInviteModelRef,{
+            org_id:org.id,
+            email:email,
+            role:role,
+            invite_code:invite_code,
+            status:pending,
+            time_out:invite_time_out,
+            user_id:op_user_id,
+            add_time:time,
+            change_time:time,
+        });
+        let id = match Insert::<AppOrgInviteModel, _>::new(idata)
+            .execute(&mut *db)
+            .await
+        {
+            Ok(res) => res.last_insert_id(),
+            Err(err) => {
+                db.rollback().await?;
+                return Err(err.into());
+            }
+        };
+        db.commit().await?;
+        self.logger
+            .add(
+                &AppOrgMemberLog {
+                    action: "invite",
+                    org_id: org.id,
+                    user_id: 0,
+                    role,
+                    email: Some(&email),
+                },
+                Some(org.id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(AppOrgInviteModel {
+            id,
+            org_id: org.id,
+            email,
+            role,
+            invite_code,
+            status: pending,
+            time_out: invite_time_out,
+            user_id: op_user_id,
+            accept_user_id: 0,
+            add_time: time,
+            change_time: time,
+        })
+    }
+    pub async fn invite_find_by_id(&self, id: u64) -> AppResult<AppOrgInviteModel> {
+        Ok(sqlx::query_as::<_, AppOrgInviteModel>(&sql_format!(
+            "select * from {} where id={}",
+            AppOrgInviteModel::table_name(),
+            id
+        ))
+        .fetch_one(&self.db)
+        .await?)
+    }
+    //查找可用的邀请
+    pub async fn invite_find_by_code(&self, invite_code: &str) -> AppResult<AppOrgInviteModel> {
+        let invite = sqlx::query_as::<_, AppOrgInviteModel>(&sql_format!(
+            "select * from {} where invite_code={}",
+            AppOrgInviteModel::table_name(),
+            invite_code
+        ))
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => AppError::System(fluent_message!("app-org-invite-invalid")),
+            _ => AppError::Sqlx(e),
+        })?;
+        if !AppOrgInviteStatus::Pending.eq(invite.status) || invite.time_out < now_time()? {
+            return Err(AppError::System(fluent_message!("app-org-invite-invalid")));
+        }
+        Ok(invite)
+    }
+    //接受邀请,邮箱归属由调用方校验
+    pub async fn invite_accept(
+        &self,
+        invite: &AppOrgInviteModel,
+        user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AppResult<()> {
+        if !AppOrgInviteStatus::Pending.eq(invite.status) || invite.time_out < now_time()? {
+            return Err(AppError::System(fluent_message!("app-org-invite-invalid")));
+        }
+        let org = self.find_by_id(invite.org_id).await?;
+        let role = AppOrgMemberRole::try_from(invite.role)?;
+        //已是成员时保留原有较高角色
+        let role = match self.member_role(org.id, user_id).await? {
+            Some(old_role) if old_role.contains(role) => old_role,
+            _ => role,
+        };
+        let time = now_time()?;
+        let mut db = self.db.begin().await?;
+        let status = AppOrgInviteStatus::Accepted as i8;
+        let change = model_option_set!(AppOrgInviteModelRef,{
+            status:status,
+            accept_user_id:user_id,
+            change_time:time,
+        });
+        match Update::<AppOrgInviteModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!(
+                    "id={} and status={}",
+                    invite.id,
+                    AppOrgInviteStatus::Pending as i8
+                )),
+                &mut *db,
+            )
+            .await
+        {
+            Ok(res) if res.rows_affected() > 0 => {}
+            Ok(_) => {
+                db.rollback().await?;
+                return Err(AppError::System(fluent_message!("app-org-invite-invalid")));
+            }
+            Err(err) => {
+                db.rollback().await?;
+                return Err(err.into());
+            }
+        }
+        if let Err(err) = self
+            .member_set_tran(org.id, user_id, role, invite.user_id, &mut db)
+            .await
+        {
+            db.rollback().await?;
+            return Err(err);
+        }
+        db.commit().await?;
+        self.logger
+            .add(
+                &AppOrgMemberLog {
+                    action: "accept",
+                    org_id: org.id,
+                    user_id,
+                    role: role as i8,
+                    email: Some(&invite.email),
+                },
+                Some(org.id),
+                Some(user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(())
+    }
+    //取消邀请
+    pub async fn invite_cancel(
+        &self,
+        org: &AppOrgModel,
+        invite: &AppOrgInviteModel,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AppResult<()> {
+        if invite.org_id != org.id {
+            return Err(AppError::System(fluent_message!("app-org-invite-invalid")));
+        }
+        self.member_role_check(org, op_user_id, AppOrgMemberRole::Admin)
+            .await?;
+        if !AppOrgInviteStatus::Pending.eq(invite.status) {
+            return Ok(());
+        }
+        let time = now_time()?;
+        let status = AppOrgInviteStatus::Cancel as i8;
+        let change = model_option_set!(AppOrgInviteModelRef,{
+            status:status,
+            change_time:time,
+        });
+        Update::<AppOrgInviteModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("id={}", invite.id)),
+                &self.db,
+            )
+            .await?;
+        self.logger
+            .add(
+                &AppOrgMemberLog {
+                    action: "invite-cancel",
+                    org_id: org.id,
+                    user_id: 0,
+                    role: invite.role,
+                    email: Some(&invite.email),
+                },
+                Some(org.id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(())
+    }
+    fn invite_where_sql(&self, org_id: u64, status: Option<AppOrgInviteStatus>) -> String {
+        let mut sql_vec = vec![sql_format!("org_id={}", org_id)];
+        if let Some(status) = status {
+            sql_vec.push(sql_format!("status={}", status as i8));
+        }
+        sql_vec.join(" and ")
+    }
+    pub async fn invite_data(
+        &self,
+        org_id: u64,
+        status: Option<AppOrgInviteStatus>,
+        page: Option<&PageParam>,
+    ) -> AppResult<Vec<AppOrgInviteModel>> {
+        Ok(sqlx::query_as::<_, AppOrgInviteModel>(&sql_format!(
+            "select * from {} where {} order by id desc {}",
+            AppOrgInviteModel::table_name(),
+            SqlExpr(self.invite_where_sql(org_id, status)),
+            SqlExpr(match page {
+                Some(page) => format!("limit {} offset {}", page.limit, page.offset),
+                None => "".to_string(),
+            })
+        ))
+        .fetch_all(&self.db)
+        .await?)
+    }
+    pub async fn invite_count(
+        &self,
+        org_id: u64,
+        status: Option<AppOrgInviteStatus>,
+    ) -> AppResult<i64> {
+        Ok(sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) as total from {} where {}",
+            AppOrgInviteModel::table_name(),
+            SqlExpr(self.invite_where_sql(org_id, status)),
+        ))
+        .fetch_one(&self.db)
+        .await?)
+    }
+}
@@ -0,0 +1,284 @@
+use super::AppOrg;
+use crate::dao::logger::AppOrgMemberLog;
+use crate::dao::{AppError, AppResult};
+use crate::model::{
+    AppOrgMemberModel, AppOrgMemberModelRef, AppOrgMemberRole, AppOrgMemberStatus, AppOrgModel,
+};
+use lsys_core::db::{ModelTableName, SqlExpr, SqlQuote, Update, WhereOption};
+use lsys_core::{fluent_message, model_option_set, now_time, sql_format, PageParam, RequestEnv};
+use sqlx::Transaction;
+
+impl AppOrg {
+    pub async fn member_data(
+        &self,
+        org_id: u64,
+        page: Option<&PageParam>,
+    ) -> AppResult<Vec<AppOrgMemberModel>> {
+        Ok(sqlx::query_as::<_, AppOrgMemberModel>(&sql_format!(
+            "select * from {} where org_id={} and status={} order by role asc,id asc {}",
+            AppOrgMemberModel::table_name(),
+            org_id,
+            AppOrgMemberStatus::Enable as i8,
+            SqlExpr(match page {
+                Some(page) => format!("limit {} offset {}", page.limit, page.offset),
+                None => "".to_string(),
+            })
+        ))
+        .fetch_all(&self.db)
+        .await?)
+    }
+    pub async fn member_count(&self, org_id: u64) -> AppResult<i64> {
+        Ok(sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) as total from {} where org_id={} and status={}",
+            AppOrgMemberModel::table_name(),
+            org_id,
+            AppOrgMemberStatus::Enable as i8,
+        ))
+        .fetch_one(&self.db)
+        .await?)
+    }
+    //组织中所有者数量,至少保留一个所有者
+    async fn owner_count(&self, org_id: u64) -> AppResult<i64> {
+        Ok(sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) as total from {} where org_id={} and role={} and status={}",
+            AppOrgMemberModel::table_name(),
+            org_id,
+            AppOrgMemberRole::Owner as i8,
+            AppOrgMemberStatus::Enable as i8,
+        ))
+        .fetch_one(&self.db)
+        .await?)
+    }
+    //检查操作用户可变更目标成员: 管理员可管理成员,涉及所有者时需所有者
+    async fn member_change_check(
+        &self,
+        org: &AppOrgModel,
+        member: &AppOrgMemberModel,
+        new_role: Option<AppOrgMemberRole>,
+        op_user_id: u64,
+    ) -> AppResult<()> {
+        let member_role = AppOrgMemberRole::try_from(member.role)?;
+        let need_role = if member_role == AppOrgMemberRole::Owner
+            || new_role == Some(AppOrgMemberRole::Owner)
+        {
+            AppOrgMemberRole::Owner
+        } else {
+            AppOrgMemberRole::Admin
+        };
+        self.member_role_check(org, op_user_id, need_role).await?;
+        if member_role == AppOrgMemberRole::Owner
+            && new_role != Some(AppOrgMemberRole::Owner)
+            && self.owner_count(org.id).await? <= 1
+        {
+            return Err(AppError::System(fluent_message!("app-org-last-owner",{
+                "name":&org.name,
+            })));
+        }
+        Ok(())
+    }
+    async fn find_member_check(
+        &self,
+        org: &AppOrgModel,
+        user_id: u64,
+    ) -> AppResult<AppOrgMemberModel> {
+        self.find_member(org.id, user_id).await?.ok_or_else(|| {
+            AppError::System(fluent_message!("app-org-not-member",{
+                "name":&org.name,
+                "user_id":user_id,
+            }))
+        })
+    }
+    //变更成员角色
+    pub async fn member_change_role(
+        &self,
+        org: &AppOrgModel,
+        user_id: u64,
+        role: AppOrgMemberRole,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AppResult<()> {
+        let member = self.find_member_check(org, user_id).await?;
+        if member.role == role as i8 {
+            return Ok(());
+        }
+        self.member_change_check(org, &member, Some(role), op_user_id)
+            .await?;
+        let time = now_time()?;
+        let role = role as i8;
+        let change = model_option_set!(AppOrgMemberModelRef,{
+            role:role,
+            change_user_id:op_user_id,
+            change_time:time,
+        });
+        Update::<AppOrgMemberModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("id={}", member.id)),
+                &self.db,
+            )
+            .await?;
+        self.logger
+            .add(
+                &AppOrgMemberLog {
+                    action: "role",
+                    org_id: org.id,
+                    user_id,
+                    role,
+                    email: None,
+                },
+                Some(org.id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(())
+    }
+    //移除成员,成员可自行退出
+    pub async fn member_remove(
+        &self,
+        org: &AppOrgModel,
+        user_id: u64,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AppResult<()> {
+        let member = self.find_member_check(org, user_id).await?;
+        if user_id == op_user_id {
+            if AppOrgMemberRole::Owner.eq(member.role) && self.owner_count(org.id).await? <= 1 {
+                return Err(AppError::System(fluent_message!("app-org-last-owner",{
+                    "name":&org.name,
+                })));
+            }
+        } else {
+            self.member_change_check(org, &member, None, op_user_id)
+                .await?;
+        }
+        let time = now_time()?;
+        let status = AppOrgMemberStatus::Delete as i8;
+        let change = model_option_set!(AppOrgMemberModelRef,{
+            status:status,
+            change_user_id:op_user_id,
+            change_time:time,
+        });
+        Update::<AppOrgMemberModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("id={}", member.id)),
+                &self.db,
+            )
+            .await?;
+        self.logger
+            .add(
+                &AppOrgMemberLog {
+                    action: "remove",
+                    org_id: org.id,
+                    user_id,
+                    role: member.role,
+                    email: None,
+                },
+                Some(org.id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(())
+    }
+    //把用户的组织成员身份移到另一用户,用于账号合并
+    //目标用户已是成员时保留两者中较高的角色
+    //在调用方事务中执行,事务提交后需调用 user_member_transfer_finish
+    pub async fn user_member_transfer(
+        &self,
+        from_user_id: u64,
+        to_user_id: u64,
+        op_user_id: u64,
+        db: &mut Transaction<'_, sqlx::MySql>,
+    ) -> AppResult<AppMemberTransfer> {
+        let member = sqlx::query_as::<_, AppOrgMemberModel>(&sql_format!(
+            "select * from {} where user_id in ({}) and status={}",
+            AppOrgMemberModel::table_name(),
+            [from_user_id, to_user_id],
+            AppOrgMemberStatus::Enable as i8,
+        ))
+        .fetch_all(&mut **db)
+        .await?;
+        let mut move_data = vec![];
+        for from in member.iter().filter(|e| e.user_id == from_user_id) {
+            let mut role = AppOrgMemberRole::try_from(from.role)?;
+            if let Some(to) = member
+                .iter()
+                .find(|e| e.user_id == to_user_id && e.org_id == from.org_id)
+            {
+                let to_role = AppOrgMemberRole::try_from(to.role)?;
+                if to_role.contains(role) {
+                    role = to_role;
+                }
+            }
+            move_data.push((from.id, from.org_id, role));
+        }
+        if move_data.is_empty() {
+            return Ok(AppMemberTransfer {
+                to_user_id,
+                move_data,
+            });
+        }
+        let time = now_time()?;
+        for (_, org_id, role) in move_data.iter() {
+            self.member_set_tran(*org_id, to_user_id, *role, op_user_id, db)
+                .await?;
+        }
+        let change = model_option_set!(AppOrgMemberModelRef,{
+            status:AppOrgMemberStatus::Delete as i8,
+            change_user_id:op_user_id,
+            change_time:time,
+        });
+        Update::<AppOrgMemberModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!(
+                    "id in ({})",
+                    move_data.iter().map(|e| e.0).collect::<Vec<_>>()
+                )),
+                &mut **db,
+            )
+            .await?;
+        Ok(AppMemberTransfer {
+            to_user_id,
+            move_data,
+        })
+    }
+    //账号合并事务提交后记录日志
+    pub async fn user_member_transfer_finish(
+        &self,
+        transfer: &AppMemberTransfer,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) {
+        for (id, org_id, role) in transfer.move_data.iter() {
+            self.logger
+                .add(
+                    &AppOrgMemberLog {
+                        action: "merge",
+                        org_id: *org_id,
+                        user_id: transfer.to_user_id,
+                        role: *role as i8,
+                        email: None,
+                    },
+                    Some(*id),
+                    Some(op_user_id),
+                    None,
+                    env_data,
+                )
+                .await;
+        }
+    }
+}
+
+//账号合并时转移的组织成员: 原成员记录ID,组织ID,转移后角色
+pub struct AppMemberTransfer {
+    to_user_id: u64,
+    move_data: Vec<(u64, u64, AppOrgMemberRole)>,
+}
+
+impl AppMemberTransfer {
+    pub fn total(&self) -> u64 {
+        self.move_data.len() as u64
+    }
+}
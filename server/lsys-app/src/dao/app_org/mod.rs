@@ -0,0 +1,96 @@
+//组织:多个用户共同拥有应用
+mod invite;
+mod member;
+mod org;
+mod transfer;
+
+pub use member::AppMemberTransfer;
+pub use org::*;
+pub use transfer::AppUserTransfer;
+
+use super::{App, AppError, AppResult};
+use crate::model::{
+    AppOrgMemberModel, AppOrgMemberRole, AppOrgMemberStatus, AppOrgModel, AppOrgStatus,
+};
+use lsys_core::db::{ModelTableName, SqlQuote};
+use lsys_core::{fluent_message, sql_format};
+use lsys_logger::dao::ChangeLoggerDao;
+use sqlx::{MySql, Pool};
+use std::sync::Arc;
+
+pub struct AppOrg {
+    db: Pool<MySql>,
+    app: Arc<App>,
+    logger: Arc<ChangeLoggerDao>,
+}
+
+impl AppOrg {
+    pub fn new(db: Pool<MySql>, app: Arc<App>, logger: Arc<ChangeLoggerDao>) -> Self {
+        Self { db, app, logger }
+    }
+    pub async fn find_by_id(&self, id: u64) -> AppResult<AppOrgModel> {
+        sqlx::query_as::<_, AppOrgModel>(&sql_format!(
+            "select * from {} where id={} and status={}",
+            AppOrgModel::table_name(),
+            id,
+            AppOrgStatus::Enable as i8
+        ))
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => {
+                AppError::System(fluent_message!("app-org-not-found", { "id": id }))
+            }
+            _ => AppError::Sqlx(e),
+        })
+    }
+    //用户在组织中的成员记录
+    pub async fn find_member(
+        &self,
+        org_id: u64,
+        user_id: u64,
+    ) -> AppResult<Option<AppOrgMemberModel>> {
+        match sqlx::query_as::<_, AppOrgMemberModel>(&sql_format!(
+            "select * from {} where org_id={} and user_id={} and status={}",
+            AppOrgMemberModel::table_name(),
+            org_id,
+            user_id,
+            AppOrgMemberStatus::Enable as i8
+        ))
+        .fetch_one(&self.db)
+        .await
+        {
+            Ok(member) => Ok(Some(member)),
+            Err(sqlx::Error::RowNotFound) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+    //用户在组织中的角色,非成员返回None
+    pub async fn member_role(
+        &self,
+        org_id: u64,
+        user_id: u64,
+    ) -> AppResult<Option<AppOrgMemberRole>> {
+        if org_id == 0 || user_id == 0 {
+            return Ok(None);
+        }
+        Ok(match self.find_member(org_id, user_id).await? {
+            Some(member) => Some(AppOrgMemberRole::try_from(member.role)?),
+            None => None,
+        })
+    }
+    //检查用户在组织中至少拥有指定角色
+    pub async fn member_role_check(
+        &self,
+        org: &AppOrgModel,
+        user_id: u64,
+        role: AppOrgMemberRole,
+    ) -> AppResult<AppOrgMemberRole> {
+        match self.member_role(org.id, user_id).await? {
+            Some(user_role) if user_role.contains(role) => Ok(user_role),
+            _ => Err(AppError::System(fluent_message!("app-org-no-perm",{
+                "name":&org.name,
+            }))),
+        }
+    }
+}
@@ -0,0 +1,314 @@
+use super::AppOrg;
+use crate::dao::logger::{AppOrgLog, AppOrgMemberLog};
+use crate::dao::{AppError, AppResult};
+use crate::model::{
+    AppModel, AppOrgMemberModel, AppOrgMemberModelRef, AppOrgMemberRole, AppOrgMemberStatus,
+    AppOrgModel, AppOrgModelRef, AppOrgStatus, AppStatus,
+};
+use lsys_core::db::{Insert, ModelTableName, SqlExpr, SqlQuote, Update, WhereOption};
+use lsys_core::{
+    fluent_message, model_option_set, now_time, sql_format, string_clear, valid_key, PageParam,
+    RequestEnv, StringClear, ValidParam, ValidParamCheck, ValidStrlen, STRING_CLEAR_FORMAT,
+};
+
+impl AppOrg {
+    fn org_name_param(&self, name: &str) -> AppResult<String> {
+        let name = string_clear(name, StringClear::Option(STRING_CLEAR_FORMAT), Some(33));
+        ValidParam::default()
+            .add(
+                valid_key!("org_name"),
+                &name,
+                &ValidParamCheck::default().add_rule(ValidStrlen::range(2, 32)),
+            )
+            .check()?;
+        Ok(name)
+    }
+    //创建组织,创建用户为所有者
+    pub async fn add_org(
+        &self,
+        name: &str,
+        user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AppResult<u64> {
+        let name = self.org_name_param(name)?;
+        let time = now_time()?;
+        let status = AppOrgStatus::Enable as i8;
+        let mut db = self.db.begin().await?;
+        let idata = model_option_set!(AppOrgModelRef,{
+            name:name,
+            user_id:user_id,
+            status:status,
+            change_user_id:user_id,
+            change_time:time,
+        });
+        let org_id = match Insert::<AppOrgModel, _>::new(idata).execute(&mut *db).await {
+            Ok(res) => res.last_insert_id(),
+            Err(err) => {
+                db.rollback().await?;
+                return Err(err.into());
+            }
+        };
+        if let Err(err) = self
+            .member_set_tran(org_id, user_id, AppOrgMemberRole::Owner, user_id, &mut db)
+            .await
+        {
+            db.rollback().await?;
+            return Err(err);
+        }
+        db.commit().await?;
+        self.logger
+            .add(
+                &AppOrgLog {
+                    action: "add",
+                    name: &name,
+                    user_id,
+                    status,
+                },
+                Some(org_id),
+                Some(user_id),
+                None,
+                env_data,
+            )
+            .await;
+        self.logger
+            .add(
+                &AppOrgMemberLog {
+                    action: "add",
+                    org_id,
+                    user_id,
+                    role: AppOrgMemberRole::Owner as i8,
+                    email: None,
+                },
+                Some(org_id),
+                Some(user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(org_id)
+    }
+    //修改组织名称,需管理员
+    pub async fn edit_org(
+        &self,
+        org: &AppOrgModel,
+        name: &str,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AppResult<()> {
+        self.member_role_check(org, op_user_id, AppOrgMemberRole::Admin)
+            .await?;
+        let name = self.org_name_param(name)?;
+        let time = now_time()?;
+        let change = model_option_set!(AppOrgModelRef,{
+            name:name,
+            change_user_id:op_user_id,
+            change_time:time,
+        });
+        Update::<AppOrgModel, _>::new(change)
+            .execute_by_where(&WhereOption::Where(sql_format!("id={}", org.id)), &self.db)
+            .await?;
+        self.logger
+            .add(
+                &AppOrgLog {
+                    action: "edit",
+                    name: &name,
+                    user_id: org.user_id,
+                    status: org.status,
+                },
+                Some(org.id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(())
+    }
+    //删除组织,需所有者且组织下无应用
+    pub async fn delete_org(
+        &self,
+        org: &AppOrgModel,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AppResult<()> {
+        self.member_role_check(org, op_user_id, AppOrgMemberRole::Owner)
+            .await?;
+        let app_total = sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) as total from {} where org_id={} and status in ({})",
+            AppModel::table_name(),
+            org.id,
+            &[
+                AppStatus::Enable as i8,
+                AppStatus::Init as i8,
+                AppStatus::Disable as i8
+            ],
+        ))
+        .fetch_one(&self.db)
+        .await?;
+        if app_total > 0 {
+            return Err(AppError::System(fluent_message!("app-org-exists-app",{
+                "name":&org.name,
+                "total":app_total,
+            })));
+        }
+        let time = now_time()?;
+        let mut db = self.db.begin().await?;
+        let status = AppOrgStatus::Delete as i8;
+        let change = model_option_set!(AppOrgModelRef,{
+            status:status,
+            change_user_id:op_user_id,
+            change_time:time,
+        });
+        if let Err(err) = Update::<AppOrgModel, _>::new(change)
+            .execute_by_where(&WhereOption::Where(sql_format!("id={}", org.id)), &mut *db)
+            .await
+        {
+            db.rollback().await?;
+            return Err(err.into());
+        }
+        let member_status = AppOrgMemberStatus::Delete as i8;
+        let change = model_option_set!(AppOrgMemberModelRef,{
+            status:member_status,
+            change_user_id:op_user_id,
+            change_time:time,
+        });
+        if let Err(err) = Update::<AppOrgMemberModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("org_id={}", org.id)),
+                &mut *db,
+            )
+            .await
+        {
+            db.rollback().await?;
+            return Err(err.into());
+        }
+        db.commit().await?;
+        self.logger
+            .add(
+                &AppOrgLog {
+                    action: "delete",
+                    name: &org.name,
+                    user_id: org.user_id,
+                    status: org.status,
+                },
+                Some(org.id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(())
+    }
+    //设置成员角色,已存在记录时更新
+    pub(crate) async fn member_set_tran(
+        &self,
+        org_id: u64,
+        user_id: u64,
+        role: AppOrgMemberRole,
+        op_user_id: u64,
+        db: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    ) -> AppResult<()> {
+        let time = now_time()?;
+        let role = role as i8;
+        let status = AppOrgMemberStatus::Enable as i8;
+        let idata = model_option_set!(AppOrgMemberModelRef,{
+            org_id:org_id,
+            user_id:user_id,
+            role:role,
+            status:status,
+            change_user_id:op_user_id,
+            change_time:time,
+        });
+        let change = model_option_set!(AppOrgMemberModelRef,{
+            role:role,
+            status:status,
+            change_user_id:op_user_id,
+            change_time:time,
+        });
+        Insert::<AppOrgMemberModel, _>::new(idata)
+            .execute_update(&Update::<AppOrgMemberModel, _>::new(change), &mut **db)
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct AppOrgAttrData {
+    pub member_count: Option<i64>, //成员数量
+    pub app_count: Option<i64>,    //应用数量
+}
+
+impl AppOrg {
+    //用户加入的组织
+    pub async fn user_org_data(
+        &self,
+        user_id: u64,
+        page: Option<&PageParam>,
+    ) -> AppResult<Vec<(AppOrgModel, AppOrgMemberModel)>> {
+        let member = sqlx::query_as::<_, AppOrgMemberModel>(&sql_format!(
+            "select m.* from {} as m join {} as o on m.org_id=o.id
+            where m.user_id={} and m.status={} and o.status={} order by m.id desc {}",
+            AppOrgMemberModel::table_name(),
+            AppOrgModel::table_name(),
+            user_id,
+            AppOrgMemberStatus::Enable as i8,
+            AppOrgStatus::Enable as i8,
+            SqlExpr(match page {
+                Some(page) => format!("limit {} offset {}", page.limit, page.offset),
+                None => "".to_string(),
+            })
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        if member.is_empty() {
+            return Ok(vec![]);
+        }
+        let org = sqlx::query_as::<_, AppOrgModel>(&sql_format!(
+            "select * from {} where id in ({})",
+            AppOrgModel::table_name(),
+            member.iter().map(|e| e.org_id).collect::<Vec<_>>()
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        Ok(member
+            .into_iter()
+            .filter_map(|m| {
+                org.iter()
+                    .find(|o| o.id == m.org_id)
+                    .map(|o| (o.to_owned(), m))
+            })
+            .collect())
+    }
+    pub async fn user_org_count(&self, user_id: u64) -> AppResult<i64> {
+        Ok(sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) as total from {} as m join {} as o on m.org_id=o.id
+            where m.user_id={} and m.status={} and o.status={}",
+            AppOrgMemberModel::table_name(),
+            AppOrgModel::table_name(),
+            user_id,
+            AppOrgMemberStatus::Enable as i8,
+            AppOrgStatus::Enable as i8,
+        ))
+        .fetch_one(&self.db)
+        .await?)
+    }
+    //组织附加信息
+    pub async fn org_attr(&self, org: &AppOrgModel) -> AppResult<AppOrgAttrData> {
+        let member_count = self.member_count(org.id).await?;
+        let app_count = sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) as total from {} where org_id={} and status in ({})",
+            AppModel::table_name(),
+            org.id,
+            &[
+                AppStatus::Enable as i8,
+                AppStatus::Init as i8,
+                AppStatus::Disable as i8
+            ],
+        ))
+        .fetch_one(&self.db)
+        .await?;
+        Ok(AppOrgAttrData {
+            member_count: Some(member_count),
+            app_count: Some(app_count),
+        })
+    }
+}
@@ -0,0 +1,458 @@
+use super::AppOrg;
+use crate::dao::logger::AppTransferLog;
+use crate::dao::{AppError, AppResult};
+use crate::model::{
+    AppModel, AppModelRef, AppOrgMemberRole, AppStatus, AppTransferModel, AppTransferModelRef,
+    AppTransferStatus,
+};
+use lsys_core::db::{Insert, ModelTableName, SqlExpr, SqlQuote, Update, WhereOption};
+use lsys_core::{fluent_message, model_option_set, now_time, sql_format, PageParam, RequestEnv};
+use sqlx::Transaction;
+
+impl AppOrg {
+    //转移应用所有权
+    //to_org_id>0 时转入组织,操作用户需为目标组织管理员,to_user_id 记录为负责用户
+    //转给其他用户时需对方接受,返回待确认的转移记录,其他情况直接转移返回None
+    //原所有者的权限校验由调用方完成
+    pub async fn app_transfer(
+        &self,
+        app: &AppModel,
+        to_user_id: u64,
+        to_org_id: u64,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AppResult<Option<AppTransferModel>> {
+        //子应用及外部账号的应用属于上级应用,不可转移
+        if app.parent_app_id > 0 || app.user_app_id > 0 {
+            return Err(AppError::System(
+                fluent_message!("app-transfer-not-support",{
+                    "name":&app.name,
+                }),
+            ));
+        }
+        if to_user_id == 0 {
+            return Err(AppError::System(fluent_message!("app-transfer-bad-user")));
+        }
+        if to_org_id > 0 {
+            let org = self.find_by_id(to_org_id).await?;
+            self.member_role_check(&org, op_user_id, AppOrgMemberRole::Admin)
+                .await?;
+            if to_user_id != op_user_id {
+                self.member_role_check(&org, to_user_id, AppOrgMemberRole::Member)
+                    .await?;
+            }
+        }
+        if app.user_id == to_user_id && app.org_id == to_org_id {
+            return Ok(None);
+        }
+        if to_org_id == 0 && to_user_id != op_user_id {
+            return Ok(Some(
+                self.transfer_request_add(app, to_user_id, op_user_id, env_data)
+                    .await?,
+            ));
+        }
+        let mut db = self.db.begin().await?;
+        if let Err(err) = self
+            .app_owner_set_tran(app, to_user_id, to_org_id, op_user_id, &mut db)
+            .await
+        {
+            db.rollback().await?;
+            return Err(err);
+        }
+        db.commit().await?;
+        self.app_transfer_finish(app, "transfer", to_user_id, to_org_id, op_user_id, env_data)
+            .await;
+        Ok(None)
+    }
+    async fn app_owner_set_tran(
+        &self,
+        app: &AppModel,
+        to_user_id: u64,
+        to_org_id: u64,
+        op_user_id: u64,
+        db: &mut Transaction<'_, sqlx::MySql>,
+    ) -> AppResult<()> {
+        let time = now_time()?;
+        let change = model_option_set!(AppModelRef,{
+            user_id:to_user_id,
+            org_id:to_org_id,
+            change_user_id:op_user_id,
+            change_time:time,
+        });
+        Update::<AppModel, _>::new(change)
+            .execute_by_where(&WhereOption::Where(sql_format!("id={}", app.id)), &mut **db)
+            .await?;
+        Ok(())
+    }
+    async fn app_transfer_finish(
+        &self,
+        app: &AppModel,
+        action: &str,
+        to_user_id: u64,
+        to_org_id: u64,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) {
+        self.app.id_cache.clear(&app.id).await;
+        self.app.client_id_cache.clear(&app.client_id).await;
+        self.logger
+            .add(
+                &AppTransferLog {
+                    action,
+                    client_id: &app.client_id,
+                    from_user_id: app.user_id,
+                    from_org_id: app.org_id,
+                    to_user_id,
+                    to_org_id,
+                },
+                Some(app.id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+    }
+    //发起转移给其他用户,同一应用未处理的转移将被取消
+    async fn transfer_request_add(
+        &self,
+        app: &AppModel,
+        to_user_id: u64,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AppResult<AppTransferModel> {
+        let time = now_time()?;
+        let pending = AppTransferStatus::Pending as i8;
+        let cancel = AppTransferStatus::Cancel as i8;
+        let mut db = self.db.begin().await?;
+        let change = model_option_set!(AppTransferModelRef,{
+            status:cancel,
+            change_time:time,
+        });
+        if let Err(err) = Update::<AppTransferModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("app_id={} and status={}", app.id, pending)),
+                &mut *db,
+            )
+            .await
+        {
+            db.rollback().await?;
+            return Err(err.into());
+        }
+        let idata = model_option_set!(AppTransferModelRef,{
+            app_id:app.id,
+            from_user_id:app.user_id,
+            from_org_id:app.org_id,
+            to_user_id:to_user_id,
+            status:pending,
+            user_id:op_user_id,
+            add_time:time,
+            change_time:time,
+        });
+        let id = match Insert::<AppTransferModel, _>::new(idata)
+            .execute(&mut *db)
+            .await
+        {
+            Ok(res) => res.last_insert_id(),
+            Err(err) => {
+                db.rollback().await?;
+                return Err(err.into());
+            }
+        };
+        db.commit().await?;
+        self.logger
+            .add(
+                &AppTransferLog {
+                    action: "request",
+                    client_id: &app.client_id,
+                    from_user_id: app.user_id,
+                    from_org_id: app.org_id,
+                    to_user_id,
+                    to_org_id: 0,
+                },
+                Some(app.id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(AppTransferModel {
+            id,
+            app_id: app.id,
+            from_user_id: app.user_id,
+            from_org_id: app.org_id,
+            to_user_id,
+            status: pending,
+            user_id: op_user_id,
+            add_time: time,
+            change_time: time,
+        })
+    }
+    pub async fn transfer_find_by_id(&self, id: u64) -> AppResult<AppTransferModel> {
+        sqlx::query_as::<_, AppTransferModel>(&sql_format!(
+            "select * from {} where id={}",
+            AppTransferModel::table_name(),
+            id
+        ))
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => AppError::System(fluent_message!("app-transfer-invalid")),
+            _ => AppError::Sqlx(e),
+        })
+    }
+    //更新待确认转移的状态,已处理时返回错误
+    async fn transfer_status_set_tran(
+        &self,
+        transfer: &AppTransferModel,
+        status: AppTransferStatus,
+        db: &mut Transaction<'_, sqlx::MySql>,
+    ) -> AppResult<()> {
+        let time = now_time()?;
+        let status = status as i8;
+        let change = model_option_set!(AppTransferModelRef,{
+            status:status,
+            change_time:time,
+        });
+        let res = Update::<AppTransferModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!(
+                    "id={} and status={}",
+                    transfer.id,
+                    AppTransferStatus::Pending as i8
+                )),
+                &mut **db,
+            )
+            .await?;
+        if res.rows_affected() == 0 {
+            return Err(AppError::System(fluent_message!("app-transfer-invalid")));
+        }
+        Ok(())
+    }
+    async fn transfer_status_set(
+        &self,
+        transfer: &AppTransferModel,
+        status: AppTransferStatus,
+    ) -> AppResult<()> {
+        let mut db = self.db.begin().await?;
+        if let Err(err) = self
+            .transfer_status_set_tran(transfer, status, &mut db)
+            .await
+        {
+            db.rollback().await?;
+            return Err(err);
+        }
+        db.commit().await?;
+        Ok(())
+    }
+    //接收用户接受转移,应用所有者在发起后已变化时转移作废
+    pub async fn transfer_accept(
+        &self,
+        transfer: &AppTransferModel,
+        user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AppResult<()> {
+        if !AppTransferStatus::Pending.eq(transfer.status) || transfer.to_user_id != user_id {
+            return Err(AppError::System(fluent_message!("app-transfer-invalid")));
+        }
+        let app = self.app.find_by_id(transfer.app_id).await?;
+        if app.user_id != transfer.from_user_id || app.org_id != transfer.from_org_id {
+            self.transfer_status_set(transfer, AppTransferStatus::Cancel)
+                .await?;
+            return Err(AppError::System(fluent_message!("app-transfer-invalid")));
+        }
+        let mut db = self.db.begin().await?;
+        if let Err(err) = self
+            .transfer_status_set_tran(transfer, AppTransferStatus::Accepted, &mut db)
+            .await
+        {
+            db.rollback().await?;
+            return Err(err);
+        }
+        if let Err(err) = self
+            .app_owner_set_tran(&app, user_id, 0, user_id, &mut db)
+            .await
+        {
+            db.rollback().await?;
+            return Err(err);
+        }
+        db.commit().await?;
+        self.app_transfer_finish(&app, "accept", user_id, 0, user_id, env_data)
+            .await;
+        Ok(())
+    }
+    //接收用户拒绝或发起方取消转移
+    pub async fn transfer_close(
+        &self,
+        transfer: &AppTransferModel,
+        status: AppTransferStatus,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AppResult<()> {
+        let action = match status {
+            AppTransferStatus::Reject if transfer.to_user_id == op_user_id => "reject",
+            AppTransferStatus::Cancel => "cancel",
+            _ => return Err(AppError::System(fluent_message!("app-transfer-invalid"))),
+        };
+        self.transfer_status_set(transfer, status).await?;
+        let app = self.app.find_by_id(transfer.app_id).await?;
+        self.logger
+            .add(
+                &AppTransferLog {
+                    action,
+                    client_id: &app.client_id,
+                    from_user_id: transfer.from_user_id,
+                    from_org_id: transfer.from_org_id,
+                    to_user_id: transfer.to_user_id,
+                    to_org_id: 0,
+                },
+                Some(app.id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(())
+    }
+    fn transfer_where_sql(
+        &self,
+        app_id: Option<u64>,
+        to_user_id: Option<u64>,
+        status: Option<AppTransferStatus>,
+    ) -> String {
+        let mut sql_vec = vec![];
+        if let Some(app_id) = app_id {
+            sql_vec.push(sql_format!("app_id={}", app_id));
+        }
+        if let Some(to_user_id) = to_user_id {
+            sql_vec.push(sql_format!("to_user_id={}", to_user_id));
+        }
+        if let Some(status) = status {
+            sql_vec.push(sql_format!("status={}", status as i8));
+        }
+        if sql_vec.is_empty() {
+            return "1=1".to_string();
+        }
+        sql_vec.join(" and ")
+    }
+    pub async fn transfer_data(
+        &self,
+        app_id: Option<u64>,
+        to_user_id: Option<u64>,
+        status: Option<AppTransferStatus>,
+        page: Option<&PageParam>,
+    ) -> AppResult<Vec<AppTransferModel>> {
+        Ok(sqlx::query_as::<_, AppTransferModel>(&sql_format!(
+            "select * from {} where {} order by id desc {}",
+            AppTransferModel::table_name(),
+            SqlExpr(self.transfer_where_sql(app_id, to_user_id, status)),
+            SqlExpr(match page {
+                Some(page) => format!("limit {} offset {}", page.limit, page.offset),
+                None => "".to_string(),
+            })
+        ))
+        .fetch_all(&self.db)
+        .await?)
+    }
+    pub async fn transfer_count(
+        &self,
+        app_id: Option<u64>,
+        to_user_id: Option<u64>,
+        status: Option<AppTransferStatus>,
+    ) -> AppResult<i64> {
+        Ok(sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) as total from {} where {}",
+            AppTransferModel::table_name(),
+            SqlExpr(self.transfer_where_sql(app_id, to_user_id, status)),
+        ))
+        .fetch_one(&self.db)
+        .await?)
+    }
+    //可被 user_app_transfer 转移的应用数量
+    pub async fn user_app_transfer_count(&self, user_id: u64) -> AppResult<i64> {
+        Ok(sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) as total from {} where user_id={} and status!={}",
+            AppModel::table_name(),
+            user_id,
+            AppStatus::Delete
+        ))
+        .fetch_one(&self.db)
+        .await?)
+    }
+    //把用户的全部应用移到另一用户名下,用于账号合并,所属组织不变
+    //在调用方事务中执行,事务提交后需调用 user_app_transfer_finish
+    pub async fn user_app_transfer(
+        &self,
+        from_user_id: u64,
+        to_user_id: u64,
+        op_user_id: u64,
+        db: &mut Transaction<'_, sqlx::MySql>,
+    ) -> AppResult<AppUserTransfer> {
+        if from_user_id == 0 || to_user_id == 0 || from_user_id == to_user_id {
+            return Err(AppError::System(fluent_message!("app-transfer-bad-user")));
+        }
+        let apps = sqlx::query_as::<_, AppModel>(&sql_format!(
+            "select * from {} where user_id={} and status!={}",
+            AppModel::table_name(),
+            from_user_id,
+            AppStatus::Delete
+        ))
+        .fetch_all(&mut **db)
+        .await?;
+        if !apps.is_empty() {
+            let time = now_time()?;
+            let change = model_option_set!(AppModelRef,{
+                user_id:to_user_id,
+                change_user_id:op_user_id,
+                change_time:time,
+            });
+            let ids = apps.iter().map(|e| e.id).collect::<Vec<_>>();
+            Update::<AppModel, _>::new(change)
+                .execute_by_where(
+                    &WhereOption::Where(sql_format!("id in ({})", ids)),
+                    &mut **db,
+                )
+                .await?;
+        }
+        Ok(AppUserTransfer { to_user_id, apps })
+    }
+    //账号合并事务提交后清理缓存并记录日志
+    pub async fn user_app_transfer_finish(
+        &self,
+        transfer: &AppUserTransfer,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) {
+        for app in transfer.apps.iter() {
+            self.app.id_cache.clear(&app.id).await;
+            self.app.client_id_cache.clear(&app.client_id).await;
+            self.logger
+                .add(
+                    &AppTransferLog {
+                        action: "merge",
+                        client_id: &app.client_id,
+                        from_user_id: app.user_id,
+                        from_org_id: app.org_id,
+                        to_user_id: transfer.to_user_id,
+                        to_org_id: app.org_id,
+                    },
+                    Some(app.id),
+                    Some(op_user_id),
+                    None,
+                    env_data,
+                )
+                .await;
+        }
+    }
+}
+
+//账号合并时转移的应用
+pub struct AppUserTransfer {
+    to_user_id: u64,
+    apps: Vec<AppModel>,
+}
+
+impl AppUserTransfer {
+    pub fn total(&self) -> u64 {
+        self.apps.len() as u64
+    }
+}
@@ -0,0 +1,123 @@
+//JWT签名密钥生成及私钥加密存储
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use jsonwebtoken::{Algorithm, EncodingKey};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::hkdf::{Salt, HKDF_SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{EcdsaKeyPair, Ed25519KeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING};
+use rsa::pkcs1::EncodeRsaPrivateKey;
+use rsa::traits::PublicKeyParts;
+use serde_json::{json, Value};
+
+pub(crate) struct JwtKeyPair {
+    pub private_der: Vec<u8>,
+    pub jwk: Value,
+}
+
+//生成签名密钥,返回私钥DER及公钥JWK
+pub(crate) fn generate_key(alg: Algorithm, kid: &str) -> Result<JwtKeyPair, String> {
+    let (private_der, mut jwk) = match alg {
+        Algorithm::RS256 => {
+            let key = rsa::RsaPrivateKey::new(&mut rsa::rand_core::OsRng, 2048)
+                .map_err(|e| e.to_string())?;
+            let der = key.to_pkcs1_der().map_err(|e| e.to_string())?;
+            (
+                der.as_bytes().to_vec(),
+                json!({
+                    "kty":"RSA",
+                    "n":URL_SAFE_NO_PAD.encode(key.n().to_bytes_be()),
+                    "e":URL_SAFE_NO_PAD.encode(key.e().to_bytes_be()),
+                }),
+            )
+        }
+        Algorithm::ES256 => {
+            let rng = SystemRandom::new();
+            let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng)
+                .map_err(|e| e.to_string())?;
+            let pair =
+                EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8.as_ref(), &rng)
+                    .map_err(|e| e.to_string())?;
+            //未压缩格式: 0x04 + x + y
+            let public_key = pair.public_key().as_ref();
+            (
+                pkcs8.as_ref().to_vec(),
+                json!({
+                    "kty":"EC",
+                    "crv":"P-256",
+                    "x":URL_SAFE_NO_PAD.encode(&public_key[1..33]),
+                    "y":URL_SAFE_NO_PAD.encode(&public_key[33..65]),
+                }),
+            )
+        }
+        Algorithm::EdDSA => {
+            let rng = SystemRandom::new();
+            let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).map_err(|e| e.to_string())?;
+            let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).map_err(|e| e.to_string())?;
+            (
+                pkcs8.as_ref().to_vec(),
+                json!({
+                    "kty":"OKP",
+                    "crv":"Ed25519",
+                    "x":URL_SAFE_NO_PAD.encode(pair.public_key().as_ref()),
+                }),
+            )
+        }
+        _ => return Err(format!("not support jwt algorithm: {:?}", alg)),
+    };
+    jwk["kid"] = json!(kid);
+    jwk["alg"] = json!(alg);
+    jwk["use"] = json!("sig");
+    Ok(JwtKeyPair { private_der, jwk })
+}
+
+pub(crate) fn encoding_key(alg: Algorithm, private_der: &[u8]) -> EncodingKey {
+    match alg {
+        Algorithm::RS256 => EncodingKey::from_rsa_der(private_der),
+        Algorithm::ES256 => EncodingKey::from_ec_der(private_der),
+        _ => EncodingKey::from_ed_der(private_der),
+    }
+}
+
+//HKDF派生用途标识,主密钥用于其他用途时派生出不同的密钥
+const CIPHER_KEY_SALT: &[u8] = b"lsys-app-jwt-key";
+const CIPHER_KEY_INFO: &[u8] = b"jwt private key encrypt aes-256-gcm";
+
+//私钥加密密钥由配置的主密钥通过HKDF-SHA256派生
+fn cipher_key(master_key: &str) -> Result<LessSafeKey, String> {
+    let prk = Salt::new(HKDF_SHA256, CIPHER_KEY_SALT).extract(master_key.as_bytes());
+    let okm = prk
+        .expand(&[CIPHER_KEY_INFO], &AES_256_GCM)
+        .map_err(|_| "derive jwt key cipher key fail".to_string())?;
+    Ok(LessSafeKey::new(UnboundKey::from(okm)))
+}
+
+//AES-256-GCM加密,输出 base64(nonce + 密文)
+pub(crate) fn encrypt_key(master_key: &str, data: &[u8]) -> Result<String, String> {
+    let key = cipher_key(master_key)?;
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|e| e.to_string())?;
+    let mut buf = data.to_vec();
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut buf)
+        .map_err(|e| e.to_string())?;
+    let mut out = nonce.to_vec();
+    out.extend(buf);
+    Ok(STANDARD.encode(out))
+}
+
+pub(crate) fn decrypt_key(master_key: &str, data: &str) -> Result<Vec<u8>, String> {
+    let key = cipher_key(master_key)?;
+    let data = STANDARD.decode(data).map_err(|e| e.to_string())?;
+    if data.len() <= NONCE_LEN {
+        return Err("bad private key data".to_string());
+    }
+    let (nonce, buf) = data.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|e| e.to_string())?;
+    let mut buf = buf.to_vec();
+    let out = key
+        .open_in_place(nonce, Aad::empty(), &mut buf)
+        .map_err(|e| e.to_string())?;
+    Ok(out.to_vec())
+}
@@ -0,0 +1,342 @@
+//JWT签名密钥管理
+//私钥加密存储,按周期轮换,轮换后旧密钥在保留期内仍可用于验证
+mod key_data;
+mod task;
+pub use task::*;
+
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::{
+    decode_header, Algorithm, DecodingKey, EncodingKey, Header, TokenData, Validation,
+};
+use lsys_core::db::{Insert, ModelTableName, SqlQuote, Update, WhereOption};
+use lsys_core::{
+    fluent_message, model_option_set, now_time, rand_str, sql_format, IntoFluentMessage, RandType,
+    TimeOutTaskConfig, TimeOutTaskNotify,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::{MySql, Pool};
+use tracing::{info, warn};
+
+use crate::model::{AppJwtKeyModel, AppJwtKeyModelRef, AppJwtKeyStatus};
+
+use super::{AppError, AppResult};
+
+//密钥数据刷新间隔,多节点时用于同步其他节点的轮换
+const KEY_RELOAD_TIME: u64 = 60;
+//验证时未找到密钥的最小重新加载间隔
+const KEY_MISS_RELOAD_TIME: u64 = 5;
+
+pub struct AppJwtKeyConfig {
+    pub timeout_task_config: TimeOutTaskConfig,
+    pub alg: Algorithm,     //签名算法 RS256 ES256 EdDSA
+    pub master_key: String, //私钥加密密钥
+    pub rotate_time: u64,   //轮换周期,0 不自动轮换
+    pub keep_time: u64,     //轮换后旧密钥保留验证时长
+}
+
+impl Default for AppJwtKeyConfig {
+    fn default() -> Self {
+        Self {
+            timeout_task_config: TimeOutTaskConfig::new("app_jwt_key_rotate", 300),
+            alg: Algorithm::RS256,
+            master_key: String::new(),
+            rotate_time: 30 * 86400,
+            keep_time: 30 * 86400,
+        }
+    }
+}
+
+struct JwtKeyItem {
+    id: u64,
+    kid: String,
+    alg: Algorithm,
+    encoding: Option<EncodingKey>,
+    decoding: DecodingKey,
+    jwk: Value,
+}
+
+pub struct AppJwtKey {
+    db: Pool<MySql>,
+    alg: Algorithm,
+    master_key: String,
+    rotate_time: u64,
+    keep_time: u64,
+    keys: RwLock<Vec<Arc<JwtKeyItem>>>,
+    load_time: AtomicU64,
+    rotate_notify: Arc<TimeOutTaskNotify>,
+}
+
+impl AppJwtKey {
+    pub fn new(db: Pool<MySql>, redis: deadpool_redis::Pool, config: AppJwtKeyConfig) -> Self {
+        Self {
+            db,
+            alg: config.alg,
+            master_key: config.master_key,
+            rotate_time: config.rotate_time,
+            keep_time: config.keep_time,
+            keys: RwLock::new(vec![]),
+            load_time: AtomicU64::new(0),
+            rotate_notify: Arc::new(TimeOutTaskNotify::new(redis, config.timeout_task_config)),
+        }
+    }
+    fn key_error(msg: impl ToString) -> AppError {
+        AppError::System(fluent_message!("app-jwt-key-error", msg.to_string()))
+    }
+    //从数据库加载可用密钥
+    pub async fn reload(&self) -> AppResult<()> {
+        let data = sqlx::query_as::<_, AppJwtKeyModel>(&sql_format!(
+            "select * from {} where status in ({}) order by id desc",
+            AppJwtKeyModel::table_name(),
+            vec![
+                AppJwtKeyStatus::Enable as i8,
+                AppJwtKeyStatus::Retired as i8
+            ]
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        let mut keys = Vec::with_capacity(data.len());
+        for tmp in data {
+            let alg = match Algorithm::from_str(&tmp.alg) {
+                Ok(alg) => alg,
+                Err(err) => {
+                    warn!("jwt key {} bad algorithm {}:{}", tmp.kid, tmp.alg, err);
+                    continue;
+                }
+            };
+            let decoding = match serde_json::from_str::<jsonwebtoken::jwk::Jwk>(&tmp.public_key)
+                .map_err(|e| e.to_string())
+                .and_then(|jwk| DecodingKey::from_jwk(&jwk).map_err(|e| e.to_string()))
+            {
+                Ok(key) => key,
+                Err(err) => {
+                    warn!("jwt key {} bad public key:{}", tmp.kid, err);
+                    continue;
+                }
+            };
+            let encoding = if AppJwtKeyStatus::Enable.eq(tmp.status) {
+                match key_data::decrypt_key(&self.master_key, &tmp.private_key) {
+                    Ok(der) => Some(key_data::encoding_key(alg, &der)),
+                    Err(err) => {
+                        //主密钥变更后无法解密,仅用于验证
+                        warn!("jwt key {} decrypt private key fail:{}", tmp.kid, err);
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+            keys.push(Arc::new(JwtKeyItem {
+                id: tmp.id,
+                kid: tmp.kid,
+                alg,
+                encoding,
+                decoding,
+                jwk: serde_json::from_str(&tmp.public_key)?,
+            }));
+        }
+        if let Ok(mut set_keys) = self.keys.write() {
+            *set_keys = keys;
+        }
+        self.load_time.store(now_time()?, Ordering::Relaxed);
+        Ok(())
+    }
+    //超过指定时长未加载时重新加载
+    async fn reload_timeout(&self, timeout: u64) -> AppResult<()> {
+        if self.load_time.load(Ordering::Relaxed) + timeout <= now_time()? {
+            self.reload().await?;
+        }
+        Ok(())
+    }
+    fn find_key(&self, kid: &str) -> Option<Arc<JwtKeyItem>> {
+        self.keys
+            .read()
+            .ok()?
+            .iter()
+            .find(|e| e.kid == kid)
+            .map(|e| e.to_owned())
+    }
+    fn find_sign_key(&self) -> Option<Arc<JwtKeyItem>> {
+        self.keys
+            .read()
+            .ok()?
+            .iter()
+            .filter(|e| e.encoding.is_some())
+            .max_by_key(|e| e.id)
+            .map(|e| e.to_owned())
+    }
+    //生成新签名密钥,原签名密钥转为仅验证
+    pub async fn rotate(&self) -> AppResult<String> {
+        match self.create_key(true).await? {
+            Some(kid) => Ok(kid),
+            //其他节点同时完成了轮换,使用其生成的密钥
+            None => self
+                .find_sign_key()
+                .map(|e| e.kid.to_owned())
+                .ok_or_else(|| Self::key_error("not find jwt sign key")),
+        }
+    }
+    //写入新签名密钥,retire 为真时先将原签名密钥转为仅验证
+    //签名中的密钥有唯一约束,多节点并发创建时仅一个成功,其余重新加载后返回 None
+    async fn create_key(&self, retire: bool) -> AppResult<Option<String>> {
+        if self.master_key.is_empty() {
+            return Err(Self::key_error("jwt key master key not set"));
+        }
+        let kid = rand_str(RandType::LowerNumber, 16);
+        let key_pair = key_data::generate_key(self.alg, &kid).map_err(Self::key_error)?;
+        let private_key = key_data::encrypt_key(&self.master_key, &key_pair.private_der)
+            .map_err(Self::key_error)?;
+        let public_key = key_pair.jwk.to_string();
+        let alg = format!("{:?}", self.alg);
+        let time = now_time()?;
+        let status = AppJwtKeyStatus::Enable as i8;
+        let vdata = model_option_set!(AppJwtKeyModelRef,{
+            kid:kid,
+            alg:alg,
+            private_key:private_key,
+            public_key:public_key,
+            status:status,
+            add_time:time,
+            change_time:time,
+        });
+        let mut db = self.db.begin().await?;
+        if retire {
+            let status = AppJwtKeyStatus::Retired as i8;
+            let change = model_option_set!(AppJwtKeyModelRef,{
+                status:status,
+                change_time:time,
+            });
+            if let Err(err) = Update::<AppJwtKeyModel, _>::new(change)
+                .execute_by_where(
+                    &WhereOption::Where(sql_format!("status={}", AppJwtKeyStatus::Enable as i8)),
+                    &mut *db,
+                )
+                .await
+            {
+                db.rollback().await?;
+                return Err(err.into());
+            }
+        }
+        if let Err(err) = Insert::<AppJwtKeyModel, _>::new(vdata)
+            .execute(&mut *db)
+            .await
+        {
+            db.rollback().await?;
+            if matches!(&err, sqlx::Error::Database(e) if e.is_unique_violation()) {
+                info!("jwt key create conflict, use key from other node");
+                self.reload().await?;
+                return Ok(None);
+            }
+            return Err(err.into());
+        }
+        db.commit().await?;
+        info!("jwt key rotate, new key:{} alg:{:?}", kid, self.alg);
+        self.reload().await?;
+        if self.rotate_time > 0 {
+            if let Err(err) = self
+                .rotate_notify
+                .notify_timeout(time + self.rotate_time)
+                .await
+            {
+                warn!(
+                    "jwt key rotate notify fail:{}",
+                    err.to_fluent_message().default_format()
+                );
+            }
+        }
+        Ok(Some(kid))
+    }
+    //删除超过保留期的已轮换密钥
+    pub(crate) async fn clear_retired(&self) -> AppResult<u64> {
+        let time = now_time()?;
+        let status = AppJwtKeyStatus::Delete as i8;
+        let change = model_option_set!(AppJwtKeyModelRef,{
+            status:status,
+            change_time:time,
+        });
+        let res = Update::<AppJwtKeyModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!(
+                    "status={} and change_time<={}",
+                    AppJwtKeyStatus::Retired as i8,
+                    time.saturating_sub(self.keep_time)
+                )),
+                &self.db,
+            )
+            .await?;
+        if res.rows_affected() > 0 {
+            self.reload().await?;
+        }
+        Ok(res.rows_affected())
+    }
+    /// 使用当前签名密钥生成JWT
+    pub async fn encode<T: Serialize>(&self, claims: &T) -> AppResult<String> {
+        self.reload_timeout(KEY_RELOAD_TIME).await?;
+        let key = match self.find_sign_key() {
+            Some(key) => key,
+            None => {
+                //可能为其他节点刚创建,重新加载后仍无签名密钥时创建
+                self.reload().await?;
+                if self.find_sign_key().is_none() {
+                    self.create_key(false).await?;
+                }
+                self.find_sign_key()
+                    .ok_or_else(|| Self::key_error("not find jwt sign key"))?
+            }
+        };
+        let mut header = Header::new(key.alg);
+        header.kid = Some(key.kid.to_owned());
+        match key.encoding {
+            Some(ref encoding) => {
+                jsonwebtoken::encode(&header, claims, encoding).map_err(Self::key_error)
+            }
+            None => Err(Self::key_error("not find jwt sign key")),
+        }
+    }
+    /// 验证JWT,接受当前及保留期内的已轮换密钥
+    pub async fn decode<T: DeserializeOwned>(
+        &self,
+        token: &str,
+        validation: &Validation,
+    ) -> jsonwebtoken::errors::Result<TokenData<T>> {
+        let header = decode_header(token)?;
+        let kid = header.kid.ok_or(ErrorKind::InvalidToken)?;
+        if let Err(err) = self.reload_timeout(KEY_RELOAD_TIME).await {
+            warn!(
+                "jwt key reload fail:{}",
+                err.to_fluent_message().default_format()
+            );
+        }
+        let key = match self.find_key(&kid) {
+            Some(key) => key,
+            None => {
+                //其他节点轮换的新密钥
+                if let Err(err) = self.reload_timeout(KEY_MISS_RELOAD_TIME).await {
+                    warn!(
+                        "jwt key reload fail:{}",
+                        err.to_fluent_message().default_format()
+                    );
+                }
+                self.find_key(&kid).ok_or(ErrorKind::InvalidToken)?
+            }
+        };
+        let mut validation = validation.to_owned();
+        validation.algorithms = vec![key.alg];
+        jsonwebtoken::decode::<T>(token, &key.decoding, &validation)
+    }
+    /// 可用于验证的公钥集合
+    pub async fn jwks(&self) -> AppResult<Value> {
+        self.reload_timeout(KEY_RELOAD_TIME).await?;
+        let keys = self
+            .keys
+            .read()
+            .map(|keys| keys.iter().map(|e| e.jwk.to_owned()).collect::<Vec<_>>())
+            .unwrap_or_default();
+        Ok(json!({ "keys": keys }))
+    }
+}
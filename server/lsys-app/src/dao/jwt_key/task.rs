@@ -0,0 +1,126 @@
+//JWT签名密钥定时轮换及过期清理
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use lsys_core::db::{ModelTableName, SqlQuote};
+use lsys_core::{
+    now_time, sql_format, AppCore, IntoFluentMessage, TimeOutTask, TimeOutTaskExec,
+    TimeOutTaskExecutor, TimeOutTaskNextTime,
+};
+
+use crate::model::{AppJwtKeyModel, AppJwtKeyStatus};
+
+use super::AppJwtKey;
+
+pub struct AppJwtKeyRotateTask {
+    jwt_key: Arc<AppJwtKey>,
+}
+
+impl AppJwtKeyRotateTask {
+    //下次需处理时间: 签名密钥轮换时间与已轮换密钥过期时间中较早者
+    async fn task_time(&self) -> Result<u64, String> {
+        let ntime = now_time().unwrap_or_default();
+        let add_time = sqlx::query_scalar::<_, u64>(&sql_format!(
+            "select add_time from {} where status={} order by id desc limit 1",
+            AppJwtKeyModel::table_name(),
+            AppJwtKeyStatus::Enable as i8
+        ))
+        .fetch_optional(&self.jwt_key.db)
+        .await
+        .map_err(|e| e.to_string())?;
+        let mut next_time = match add_time {
+            Some(add_time) if self.jwt_key.rotate_time > 0 => add_time + self.jwt_key.rotate_time,
+            Some(_) => u64::MAX,
+            None => ntime,
+        };
+        let change_time = sqlx::query_scalar::<_, u64>(&sql_format!(
+            "select change_time from {} where status={} order by change_time asc limit 1",
+            AppJwtKeyModel::table_name(),
+            AppJwtKeyStatus::Retired as i8
+        ))
+        .fetch_optional(&self.jwt_key.db)
+        .await
+        .map_err(|e| e.to_string())?;
+        if let Some(change_time) = change_time {
+            next_time = next_time.min(change_time + self.jwt_key.keep_time);
+        }
+        Ok(next_time)
+    }
+}
+
+#[async_trait::async_trait]
+impl TimeOutTaskExec for AppJwtKeyRotateTask {
+    async fn exec(
+        &self,
+        _max_lock_time: usize,
+        _expire_call: impl FnMut() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send,
+    ) -> Result<(), String> {
+        let ntime = now_time().unwrap_or_default();
+        let add_time = sqlx::query_scalar::<_, u64>(&sql_format!(
+            "select add_time from {} where status={} order by id desc limit 1",
+            AppJwtKeyModel::table_name(),
+            AppJwtKeyStatus::Enable as i8
+        ))
+        .fetch_optional(&self.jwt_key.db)
+        .await
+        .map_err(|e| e.to_string())?;
+        let need_rotate = match add_time {
+            Some(add_time) => {
+                self.jwt_key.rotate_time > 0 && add_time + self.jwt_key.rotate_time <= ntime
+            }
+            None => true,
+        };
+        if need_rotate {
+            self.jwt_key
+                .rotate()
+                .await
+                .map_err(|e| e.to_fluent_message().default_format())?;
+        }
+        self.jwt_key
+            .clear_retired()
+            .await
+            .map_err(|e| e.to_fluent_message().default_format())?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TimeOutTaskNextTime for AppJwtKeyRotateTask {
+    async fn next_time(&self, max_lock_time: usize) -> Result<Option<u64>, String> {
+        let ntime = now_time().unwrap_or_default();
+        let next_time = self.task_time().await?;
+        if next_time <= ntime + max_lock_time as u64 {
+            Ok(Some(next_time))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TimeOutTaskExecutor for AppJwtKeyRotateTask {
+    type Exec = Self;
+    type NextTime = Self;
+}
+
+impl AppJwtKey {
+    //监听密钥轮换,需在独立任务中运行
+    pub async fn listen_rotate(
+        self: &Arc<Self>,
+        app_core: Arc<AppCore>,
+        channel_buffer: Option<usize>,
+    ) {
+        let task = Arc::new(AppJwtKeyRotateTask {
+            jwt_key: self.clone(),
+        });
+        TimeOutTask::<AppJwtKeyRotateTask>::new(
+            app_core,
+            self.rotate_notify.clone(),
+            task.clone(),
+            task,
+        )
+        .listen(channel_buffer)
+        .await;
+    }
+}
@@ -166,3 +166,97 @@ impl ChangeLogData for AppNotifyDataDelLog<'_> {
         serde_json::to_string(&self).unwrap_or_default()
     }
 }
+
+#[derive(Serialize)]
+pub(crate) struct AppOAuthClientRefreshReuseLog<'t> {
+    pub parent_app_id: u64,
+    pub app_id: u64,
+    pub family_id: u64,
+    pub refresh_token: &'t str,
+}
+
+impl ChangeLogData for AppOAuthClientRefreshReuseLog<'_> {
+    fn log_type() -> &'static str {
+        "app-oauth-client-refresh-reuse"
+    }
+    fn message(&self) -> String {
+        format!("refresh token reuse,revoke family {}", self.family_id)
+    }
+    fn encode(&self) -> String {
+        serde_json::to_string(&self).unwrap_or_default()
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct AppOrgLog<'t> {
+    pub action: &'t str,
+    pub name: &'t str,
+    pub user_id: u64,
+    pub status: i8,
+}
+
+impl ChangeLogData for AppOrgLog<'_> {
+    fn log_type() -> &'static str {
+        "app-org"
+    }
+    fn message(&self) -> String {
+        format!("{}:{}", self.action, self.name)
+    }
+    fn encode(&self) -> String {
+        serde_json::to_string(&self).unwrap_or_default()
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct AppOrgMemberLog<'t> {
+    pub action: &'t str,
+    pub org_id: u64,
+    pub user_id: u64,
+    pub role: i8,
+    pub email: Option<&'t str>,
+}
+
+impl ChangeLogData for AppOrgMemberLog<'_> {
+    fn log_type() -> &'static str {
+        "app-org-member"
+    }
+    fn message(&self) -> String {
+        match self.email {
+            Some(email) => format!("{}:{} in org {}", self.action, email, self.org_id),
+            None => format!("{}:{} in org {}", self.action, self.user_id, self.org_id),
+        }
+    }
+    fn encode(&self) -> String {
+        serde_json::to_string(&self).unwrap_or_default()
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct AppTransferLog<'t> {
+    pub action: &'t str,
+    pub client_id: &'t str,
+    pub from_user_id: u64,
+    pub from_org_id: u64,
+    pub to_user_id: u64,
+    pub to_org_id: u64,
+}
+
+impl ChangeLogData for AppTransferLog<'_> {
+    fn log_type() -> &'static str {
+        "app-transfer"
+    }
+    fn message(&self) -> String {
+        format!(
+            "{} {}:user {} org {} => user {} org {}",
+            self.action,
+            self.client_id,
+            self.from_user_id,
+            self.from_org_id,
+            self.to_user_id,
+            self.to_org_id
+        )
+    }
+    fn encode(&self) -> String {
+        serde_json::to_string(&self).unwrap_or_default()
+    }
+}
@@ -1,8 +1,10 @@
 mod app;
 mod app_notify;
+mod app_org;
 mod app_secret;
 mod cache;
 mod exter_login;
+mod jwt_key;
 mod logger;
 mod oauth_client;
 mod oauth_server;
@@ -21,8 +23,10 @@ use crate::model::{AppModel, AppNotifyTryTimeMode, AppNotifyType};
 
 pub use app::*;
 pub use app_notify::*;
+pub use app_org::*;
 pub use app_secret::*;
 pub use cache::AppLocalCacheClear;
+pub use jwt_key::*;
 use lsys_logger::dao::ChangeLoggerDao;
 pub use oauth_client::*;
 pub use oauth_server::*;
@@ -40,6 +44,8 @@ pub struct AppDao {
     pub oauth_server: Arc<AppOAuthServer>,
     pub app_notify: Arc<AppNotify>,
     pub exter_login: Arc<AppExterLogin>,
+    pub jwt_key: Arc<AppJwtKey>,
+    pub org: Arc<AppOrg>,
 }
 
 pub struct SubAppNotifyConfig {
@@ -71,6 +77,7 @@ pub struct AppConfig {
     pub oauth_client_login_time: u64,
     pub oauth_client_refresh_time: u64,
     pub sub_app_notify_config: SubAppNotifyConfig,
+    pub jwt_key_config: AppJwtKeyConfig,
 }
 
 impl AppConfig {
@@ -101,6 +108,7 @@ impl AppConfig {
             oauth_client_code_time,
             oauth_client_login_time,
             oauth_client_refresh_time,
+            jwt_key_config: AppJwtKeyConfig::default(),
         }
     }
 }
@@ -168,7 +176,7 @@ impl AppDao {
         ));
         let oauth_client = Arc::from(AppOAuthClient::new(
             db.clone(),
-            redis,
+            redis.clone(),
             app.clone(),
             oauth_server.clone(),
             access.clone(),
@@ -184,6 +192,8 @@ impl AppDao {
         ));
 
         let exter_login = Arc::new(AppExterLogin::new(db.clone(), app.clone()));
+        let jwt_key = Arc::new(AppJwtKey::new(db.clone(), redis, config.jwt_key_config));
+        let org = Arc::new(AppOrg::new(db.clone(), app.clone(), logger.clone()));
 
         Ok(AppDao {
             app_core,
@@ -193,6 +203,8 @@ impl AppDao {
             app_secret,
             app_notify,
             exter_login,
+            jwt_key,
+            org,
         })
     }
     pub async fn listen_sub_app_change_notify(&self, channel_buffer: Option<usize>) {
@@ -248,10 +260,14 @@ impl AppDao {
             logger::AppRequestLog::log_type(),
             logger::AppOAuthClientSetDomainLog::log_type(),
             logger::AppOAuthClientSecretSetLog::log_type(),
+            logger::AppOAuthClientRefreshReuseLog::log_type(),
             logger::AppOAuthServerSetLog::log_type(),
             logger::AppViewSecretLog::log_type(),
             logger::AppNotifyConfigLog::log_type(),
             logger::AppNotifyDataDelLog::log_type(),
+            logger::AppOrgLog::log_type(),
+            logger::AppOrgMemberLog::log_type(),
+            logger::AppTransferLog::log_type(),
         ]
     }
 }
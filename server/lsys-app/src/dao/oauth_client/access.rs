@@ -216,7 +216,9 @@ impl AppOAuthClientAccess {
             login_ip: code_data.login_ip,
             device_id: code_data.device_id,
             device_name: code_data.device_name,
+            user_agent: None,
             session_data,
+            impersonate_user_id: 0,
         };
         Ok(self
             .auth
@@ -1,10 +1,12 @@
-use lsys_access::dao::AccessError;
+use lsys_access::dao::{AccessError, SessionBody};
 use lsys_core::db::{Insert, ModelTableName, Update, WhereOption};
 use lsys_core::{fluent_message, model_option_set, now_time, rand_str, sql_format};
 use serde::{Deserialize, Serialize};
 
+use crate::dao::logger::AppOAuthClientRefreshReuseLog;
 use crate::dao::oauth_client::access::AccessOAuthCodeData;
 use crate::dao::session::RestAuthData;
+use tracing::warn;
 
 use super::AppOAuthClient;
 use crate::dao::{AppError, AppResult};
@@ -116,28 +118,66 @@ impl AppOAuthClient {
                 &[(APP_OAUTH_CODE, code)],
             )
             .await?;
+        let refresh_token_data = self
+            .save_refresh_token(app, 0, code, &code_data_str, &session_data)
+            .await?;
+        Ok((
+            RestAuthData::new(app.to_owned(), session_data),
+            refresh_token_data,
+        ))
+    }
+    //保存刷新token及关联的access token
+    //family_id 为0时创建新的刷新token分组
+    async fn save_refresh_token(
+        &self,
+        app: &AppModel,
+        family_id: u64,
+        source_code: &str,
+        code_data: &str,
+        session_data: &SessionBody,
+    ) -> AppResult<String> {
         let mut db = self.db.begin().await?;
         let refresh_token_data = rand_str(lsys_core::RandType::Lower, 32);
         let status = AppOAuthClientRefreshTokenStatus::Init as i8;
         let add_time = now_time().unwrap_or_default();
         let time_out = add_time + self.refresh_time;
-        let source_code = code.to_owned();
+        let source_code = source_code.to_owned();
+        let code_data = code_data.to_owned();
         let add_data = model_option_set!(AppOAuthClientRefreshTokenModelRef,{
             app_id:app.id,
             time_out:time_out,
             refresh_token_data:refresh_token_data,
+            family_id:family_id,
             source_code:source_code,
-            code_data:code_data_str,
+            code_data:code_data,
             status:status,
             add_time:add_time,
         });
-        if let Err(e) = Insert::<AppOAuthClientRefreshTokenModel, _>::new(add_data)
+        let refresh_id = match Insert::<AppOAuthClientRefreshTokenModel, _>::new(add_data)
             .execute(&mut *db)
             .await
         {
-            db.rollback().await?;
-            return Err(e)?;
+            Ok(res) => res.last_insert_id(),
+            Err(e) => {
+                db.rollback().await?;
+                return Err(e)?;
+            }
         };
+        if family_id == 0 {
+            let change = model_option_set!(AppOAuthClientRefreshTokenModelRef,{
+                family_id:refresh_id,
+            });
+            if let Err(e) = Update::<AppOAuthClientRefreshTokenModel, _>::new(change)
+                .execute_by_where(
+                    &WhereOption::Where(sql_format!("id={}", refresh_id)),
+                    &mut *db,
+                )
+                .await
+            {
+                db.rollback().await?;
+                return Err(e)?;
+            }
+        }
         let add_data = model_option_set!(AppOAuthClientAccessModelRef,{
             app_id:app.id,
             access_token_data:session_data.session().token_data,
@@ -152,11 +192,7 @@ impl AppOAuthClient {
             return Err(e)?;
         };
         db.commit().await?;
-
-        Ok((
-            RestAuthData::new(app.to_owned(), session_data),
-            refresh_token_data,
-        ))
+        Ok(refresh_token_data)
     }
     pub async fn clear_refresh_token(&self, app: &AppModel, refresh_token: &str) -> AppResult<()> {
         let status = AppOAuthClientRefreshTokenStatus::Delete as i8;
@@ -176,6 +212,16 @@ impl AppOAuthClient {
                 &self.db,
             )
             .await?;
+        self.logout_refresh_access(app, refresh_token, true).await
+    }
+    //注销刷新token关联的access token
+    //destroy_code 为假时保留授权CODE,用于刷新后新登录仍使用该CODE的场景
+    async fn logout_refresh_access(
+        &self,
+        app: &AppModel,
+        refresh_token: &str,
+        destroy_code: bool,
+    ) -> AppResult<()> {
         let mut start_id = 0;
         loop {
             let tmp_vec=sqlx::query_as::<_,(u64,String)>(&sql_format!(
@@ -199,6 +245,10 @@ impl AppOAuthClient {
                     .await;
                 match login {
                     Ok(session) => {
+                        if !destroy_code {
+                            self.access.auth.do_logout(&session).await?;
+                            continue;
+                        }
                         if let Ok(Some(code)) = self
                             .access
                             .auth
@@ -258,19 +308,18 @@ impl AppOAuthClient {
         Ok(())
     }
     //刷新登陆session数据
+    //每次刷新返回新的刷新token,已刷新过的token再次使用时注销该组全部token及登录
     pub async fn refresh_session(
         &self,
         app: &AppModel,
         refresh_token: &str,
-    ) -> AppResult<RestAuthData> {
+    ) -> AppResult<(RestAuthData, String)> {
         self.check_access(app).await?;
-        let (code_data,source_code)=sqlx::query_as::<_,(String,String)>(&sql_format!(
-            "select code_data,source_code from {} where app_id={} and refresh_token_data={} and status={} and time_out>{}",
+        let refresh = sqlx::query_as::<_, AppOAuthClientRefreshTokenModel>(&sql_format!(
+            "select * from {} where app_id={} and refresh_token_data={} order by id desc limit 1",
             AppOAuthClientRefreshTokenModel::table_name(),
             app.id,
             refresh_token,
-            AppOAuthClientRefreshTokenStatus::Init as i8,
-            now_time().unwrap_or_default()
         ))
         .fetch_one(&self.db)
         .await
@@ -278,7 +327,41 @@ impl AppOAuthClient {
             sqlx::Error::RowNotFound => AppError::Access(AccessError::NotLogin),
             _ => AppError::Sqlx(e),
         })?;
-        let code_data = serde_json::from_str::<AccessOAuthCodeData>(code_data.as_str())
+        if AppOAuthClientRefreshTokenStatus::Rotated.eq(refresh.status) {
+            self.refresh_token_reuse(app, &refresh).await?;
+            return Err(AppError::Access(AccessError::RefreshTokenReuse(
+                refresh.family_id,
+            )));
+        }
+        let use_time = now_time().unwrap_or_default();
+        if !AppOAuthClientRefreshTokenStatus::Init.eq(refresh.status)
+            || refresh.time_out <= use_time
+        {
+            return Err(AppError::Access(AccessError::NotLogin));
+        }
+        let status = AppOAuthClientRefreshTokenStatus::Rotated as i8;
+        let change = model_option_set!(AppOAuthClientRefreshTokenModelRef,{
+            status:status,
+            use_time:use_time,
+        });
+        let res = Update::<AppOAuthClientRefreshTokenModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!(
+                    "id={} and status={}",
+                    refresh.id,
+                    AppOAuthClientRefreshTokenStatus::Init as i8
+                )),
+                &self.db,
+            )
+            .await?;
+        if res.rows_affected() == 0 {
+            //同一token被并发使用
+            self.refresh_token_reuse(app, &refresh).await?;
+            return Err(AppError::Access(AccessError::RefreshTokenReuse(
+                refresh.family_id,
+            )));
+        }
+        let code_data = serde_json::from_str::<AccessOAuthCodeData>(refresh.code_data.as_str())
             .map_err(|e| AppError::System(fluent_message!("access-bad-code", e)))?;
 
         let session_data = self
@@ -289,22 +372,91 @@ impl AppOAuthClient {
                 None,
                 self.login_time,
                 code_data,
-                &[(APP_OAUTH_CODE, &source_code)],
+                &[(APP_OAUTH_CODE, &refresh.source_code)],
             )
             .await?;
-        let refresh_token_data = refresh_token.to_owned();
-        let add_time = now_time().unwrap_or_default();
-        let add_data = model_option_set!(AppOAuthClientAccessModelRef,{
-            app_id:app.id,
-            access_token_data:session_data.session().token_data,
-            refresh_token_data:refresh_token_data,
-            add_time:add_time,
+        let family_id = if refresh.family_id > 0 {
+            refresh.family_id
+        } else {
+            refresh.id
+        };
+        let refresh_token_data = self
+            .save_refresh_token(
+                app,
+                family_id,
+                &refresh.source_code,
+                &refresh.code_data,
+                &session_data,
+            )
+            .await?;
+        //旧access_token随刷新失效
+        self.logout_refresh_access(app, refresh_token, false).await?;
+        Ok((
+            RestAuthData::new(app.to_owned(), session_data),
+            refresh_token_data,
+        ))
+    }
+    //已刷新的token被再次使用,注销同组全部刷新token及登录
+    async fn refresh_token_reuse(
+        &self,
+        app: &AppModel,
+        refresh: &AppOAuthClientRefreshTokenModel,
+    ) -> AppResult<()> {
+        let family_id = if refresh.family_id > 0 {
+            refresh.family_id
+        } else {
+            refresh.id
+        };
+        warn!(
+            "security: oauth refresh token reuse detected, revoke family {} [app:{}]",
+            family_id, app.id
+        );
+        let token_data = sqlx::query_scalar::<_, String>(&sql_format!(
+            "select refresh_token_data from {} where app_id={} and (family_id={} or id={}) and status!={}",
+            AppOAuthClientRefreshTokenModel::table_name(),
+            app.id,
+            family_id,
+            family_id,
+            AppOAuthClientRefreshTokenStatus::Delete as i8
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        for tmp in token_data.iter() {
+            self.clear_refresh_token(app, tmp).await?;
+        }
+        let status = AppOAuthClientRefreshTokenStatus::Delete as i8;
+        let delete_time = now_time().unwrap_or_default();
+        let change = model_option_set!(AppOAuthClientRefreshTokenModelRef,{
+            status:status,
+            delete_time:delete_time,
         });
-        Insert::<AppOAuthClientAccessModel, _>::new(add_data)
-            .execute(&self.db)
+        Update::<AppOAuthClientRefreshTokenModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!(
+                    "app_id={} and (family_id={} or id={}) and status!={}",
+                    app.id,
+                    family_id,
+                    family_id,
+                    AppOAuthClientRefreshTokenStatus::Delete as i8
+                )),
+                &self.db,
+            )
             .await?;
-        //@todo 缩短旧access_token的有效期。。。
-        Ok(RestAuthData::new(app.to_owned(), session_data))
+        self.logger
+            .add(
+                &AppOAuthClientRefreshReuseLog {
+                    parent_app_id: app.parent_app_id,
+                    app_id: app.id,
+                    family_id,
+                    refresh_token: &refresh.refresh_token_data,
+                },
+                Some(app.id),
+                None,
+                None,
+                None,
+            )
+            .await;
+        Ok(())
     }
     //根据rest token获取session数据
     pub async fn get_session_data(
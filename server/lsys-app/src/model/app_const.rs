@@ -110,8 +110,77 @@ pub enum AppNotifyType {
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
-#[lsys_model_status(field_type = "u8")]
+#[lsys_model_status(field_type = "i8")]
 pub enum AppOAuthClientRefreshTokenStatus {
     Init = 1,
+    Rotated = 2, //已刷新,再次使用视为泄露
+    Delete = -1,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[lsys_model_status(field_type = "i8")]
+pub enum AppJwtKeyStatus {
+    Enable = 1,  //用于签名
+    Retired = 2, //已轮换,仅用于验证
+    Delete = -1,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[lsys_model_status(field_type = "i8")]
+pub enum AppOrgStatus {
+    Enable = 1,
+    Delete = -1,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[lsys_model_status(field_type = "i8")]
+pub enum AppOrgMemberStatus {
+    Enable = 1,
     Delete = -1,
 }
+
+//组织角色,值越小权限越大
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[lsys_model_status(field_type = "i8")]
+pub enum AppOrgMemberRole {
+    Owner = 1,  //所有者
+    Admin = 2,  //管理员
+    Member = 3, //成员
+}
+
+impl AppOrgMemberRole {
+    //是否拥有指定角色的权限
+    pub fn contains(&self, role: AppOrgMemberRole) -> bool {
+        (*self as i8) <= (role as i8)
+    }
+    //成员身份对应的RBAC会话角色,高级角色包含低级角色
+    //角色由 lsys-web/tables.sql 初始化,授权需在系统角色中配置,参见 README
+    pub fn session_role_keys(&self) -> Vec<&'static str> {
+        [
+            (AppOrgMemberRole::Member, "org-member"),
+            (AppOrgMemberRole::Admin, "org-admin"),
+            (AppOrgMemberRole::Owner, "org-owner"),
+        ]
+        .into_iter()
+        .filter(|(r, _)| self.contains(*r))
+        .map(|(_, k)| k)
+        .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[lsys_model_status(field_type = "i8")]
+pub enum AppOrgInviteStatus {
+    Pending = 1,  //待接受
+    Accepted = 2, //已接受
+    Cancel = 3,   //已取消
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[lsys_model_status(field_type = "i8")]
+pub enum AppTransferStatus {
+    Pending = 1,  //待接收用户确认
+    Accepted = 2, //已接受
+    Cancel = 3,   //已取消
+    Reject = 4,   //已拒绝
+}
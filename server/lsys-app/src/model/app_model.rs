@@ -33,6 +33,10 @@ pub struct AppModel {
     #[sqlx(default)]
     pub user_app_id: u64,
 
+    /// 所属组织ID,0为个人应用
+    #[sqlx(default)]
+    pub org_id: u64,
+
     /// 最后更新用户,审核,禁用时用户
     #[sqlx(default)]
     pub change_user_id: u64,
@@ -384,6 +388,10 @@ pub struct AppOAuthClientRefreshTokenModel {
     #[sqlx(default)]
     pub refresh_token_data: String,
 
+    /// 刷新token分组,为该组首个token的ID
+    #[sqlx(default)]
+    pub family_id: u64,
+
     #[sqlx(default)]
     pub code_data: String,
 
@@ -398,6 +406,10 @@ pub struct AppOAuthClientRefreshTokenModel {
     #[sqlx(default)]
     pub time_out: u64,
 
+    /// 刷新使用时间
+    #[sqlx(default)]
+    pub use_time: u64,
+
     /// 时间
     #[sqlx(default)]
     pub delete_time: u64,
@@ -428,3 +440,182 @@ pub struct AppOAuthClientAccessModel {
     #[sqlx(default)]
     pub add_time: u64,
 }
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+#[lsys_model(table_name = "app_jwt_key")]
+pub struct AppJwtKeyModel {
+    #[sqlx(default)]
+    pub id: u64,
+
+    /// 密钥标识
+    #[sqlx(default)]
+    pub kid: String,
+
+    /// 签名算法
+    #[sqlx(default)]
+    pub alg: String,
+
+    /// 加密后的私钥
+    #[sqlx(default)]
+    pub private_key: String,
+
+    /// 公钥,JWK格式
+    #[sqlx(default)]
+    pub public_key: String,
+
+    /// 状态
+    #[sqlx(default)]
+    pub status: i8,
+
+    /// 创建时间
+    #[sqlx(default)]
+    pub add_time: u64,
+
+    /// 状态变更时间
+    #[sqlx(default)]
+    pub change_time: u64,
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+#[lsys_model(table_name = "app_org")]
+pub struct AppOrgModel {
+    #[sqlx(default)]
+    pub id: u64,
+
+    /// 组织名称
+    #[sqlx(default)]
+    pub name: String,
+
+    /// 创建用户ID
+    #[sqlx(default)]
+    pub user_id: u64,
+
+    /// 状态
+    #[sqlx(default)]
+    pub status: i8,
+
+    /// 最后更新用户
+    #[sqlx(default)]
+    pub change_user_id: u64,
+
+    /// 最后更新时间
+    #[sqlx(default)]
+    pub change_time: u64,
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+#[lsys_model(table_name = "app_org_member")]
+pub struct AppOrgMemberModel {
+    #[sqlx(default)]
+    pub id: u64,
+
+    /// 组织ID
+    #[sqlx(default)]
+    pub org_id: u64,
+
+    /// 成员用户ID
+    #[sqlx(default)]
+    pub user_id: u64,
+
+    /// 组织角色
+    #[sqlx(default)]
+    pub role: i8,
+
+    /// 状态
+    #[sqlx(default)]
+    pub status: i8,
+
+    /// 最后更新用户
+    #[sqlx(default)]
+    pub change_user_id: u64,
+
+    /// 最后更新时间
+    #[sqlx(default)]
+    pub change_time: u64,
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+#[lsys_model(table_name = "app_org_invite")]
+pub struct AppOrgInviteModel {
+    #[sqlx(default)]
+    pub id: u64,
+
+    /// 组织ID
+    #[sqlx(default)]
+    pub org_id: u64,
+
+    /// 受邀邮箱
+    #[sqlx(default)]
+    pub email: String,
+
+    /// 加入后的组织角色
+    #[sqlx(default)]
+    pub role: i8,
+
+    /// 邀请码
+    #[sqlx(default)]
+    pub invite_code: String,
+
+    /// 状态
+    #[sqlx(default)]
+    pub status: i8,
+
+    /// 过期时间
+    #[sqlx(default)]
+    pub time_out: u64,
+
+    /// 邀请用户ID
+    #[sqlx(default)]
+    pub user_id: u64,
+
+    /// 接受邀请的用户ID
+    #[sqlx(default)]
+    pub accept_user_id: u64,
+
+    /// 添加时间
+    #[sqlx(default)]
+    pub add_time: u64,
+
+    /// 状态变更时间
+    #[sqlx(default)]
+    pub change_time: u64,
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+#[lsys_model(table_name = "app_transfer")]
+pub struct AppTransferModel {
+    #[sqlx(default)]
+    pub id: u64,
+
+    /// 应用ID
+    #[sqlx(default)]
+    pub app_id: u64,
+
+    /// 转出用户ID
+    #[sqlx(default)]
+    pub from_user_id: u64,
+
+    /// 转出组织ID
+    #[sqlx(default)]
+    pub from_org_id: u64,
+
+    /// 接收用户ID
+    #[sqlx(default)]
+    pub to_user_id: u64,
+
+    /// 状态
+    #[sqlx(default)]
+    pub status: i8,
+
+    /// 发起用户ID
+    #[sqlx(default)]
+    pub user_id: u64,
+
+    /// 添加时间
+    #[sqlx(default)]
+    pub add_time: u64,
+
+    /// 状态变更时间
+    #[sqlx(default)]
+    pub change_time: u64,
+}
@@ -6,10 +6,12 @@ CREATE TABLE `yaf_app` (
     `status` tinyint NOT NULL COMMENT '状态 待审核 正常 已禁用',
     `user_id` bigint unsigned NOT NULL COMMENT '添加用户ID',
     `user_app_id` bigint unsigned NOT NULL COMMENT '冗余user表的app_id,>0时为外部账号',
+    `org_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '所属组织ID,0为个人应用',
     `change_user_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '更改用户',
     `change_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '更改时间',
     PRIMARY KEY (`id`),
-    UNIQUE KEY `app_client_id` (`client_id`) USING BTREE
+    UNIQUE KEY `app_client_id` (`client_id`) USING BTREE,
+    KEY `org_id` (`org_id`) USING BTREE
 ) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '应用数据';
 
 
@@ -39,14 +41,17 @@ CREATE TABLE `yaf_app_oauth_client_refresh_token` (
     `id` bigint unsigned NOT NULL AUTO_INCREMENT COMMENT 'ID',
     `app_id` bigint unsigned NOT NULL COMMENT 'app的id',
     `refresh_token_data` varchar(64) NOT NULL COMMENT '生成记录的CODE',
+    `family_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '刷新token分组,为该组首个token的ID',
     `time_out` bigint unsigned NOT NULL COMMENT '过期时间',
     `code_data` text NOT NULL COMMENT '登录数据',
     `source_code` varchar(64) NOT NULL  COMMENT '生成记录的CODE',
     `status` tinyint NOT NULL COMMENT '状态',
+    `use_time` bigint unsigned NOT NULL DEFAULT 0  COMMENT '刷新使用时间',
     `delete_time` bigint unsigned NOT NULL DEFAULT 0  COMMENT '删除时间',
     `add_time` bigint unsigned NOT NULL COMMENT '添加时间',
     PRIMARY KEY (`id`),
-    KEY `app_refresh_token_data` (`app_id`,`refresh_token_data`,`time_out`) USING BTREE
+    KEY `app_refresh_token_data` (`app_id`,`refresh_token_data`,`time_out`) USING BTREE,
+    KEY `app_family` (`app_id`,`family_id`) USING BTREE
 ) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = 'OAUTH登录刷新token';
 
 CREATE TABLE `yaf_app_oauth_client_access` (
@@ -163,4 +168,75 @@ CREATE TABLE `yaf_app_notify_data` (
     `result` varchar(512) NOT NULL DEFAULT '' COMMENT '错误信息',
     PRIMARY KEY (`id`),
     KEY idx_query ( `app_id`, `notify_method`,`notify_key`,`status`, `try_num`, `try_max`, `next_time`) USING BTREE
-) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '结果推送数据';
\ No newline at end of file
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '结果推送数据';
+CREATE TABLE `yaf_app_jwt_key` (
+    `id` bigint unsigned NOT NULL AUTO_INCREMENT,
+    `kid` varchar(32) NOT NULL COMMENT '密钥标识',
+    `alg` varchar(12) NOT NULL COMMENT '签名算法',
+    `private_key` text NOT NULL COMMENT '加密后的私钥',
+    `public_key` text NOT NULL COMMENT '公钥,JWK格式',
+    `status` tinyint NOT NULL COMMENT '状态:签名中,已轮换,已删除',
+    `add_time` bigint unsigned NOT NULL COMMENT '创建时间',
+    `change_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '状态变更时间',
+    `enable_key` tinyint GENERATED ALWAYS AS (if(`status` = 1, 1, NULL)) VIRTUAL COMMENT '签名中标记,保证仅一个签名中密钥',
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `kid` (`kid`) USING BTREE,
+    UNIQUE KEY `enable_key` (`enable_key`) USING BTREE,
+    KEY `status` (`status`, `add_time`) USING BTREE
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = 'JWT签名密钥';
+
+CREATE TABLE `yaf_app_org` (
+    `id` bigint unsigned NOT NULL AUTO_INCREMENT COMMENT 'ID',
+    `name` varchar(32) NOT NULL COMMENT '组织名称',
+    `user_id` bigint unsigned NOT NULL COMMENT '创建用户ID',
+    `status` tinyint NOT NULL COMMENT '状态',
+    `change_user_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '更改用户',
+    `change_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '更改时间',
+    PRIMARY KEY (`id`),
+    KEY `user_id` (`user_id`) USING BTREE
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '组织';
+
+CREATE TABLE `yaf_app_org_member` (
+    `id` bigint unsigned NOT NULL AUTO_INCREMENT COMMENT 'ID',
+    `org_id` bigint unsigned NOT NULL COMMENT '组织ID',
+    `user_id` bigint unsigned NOT NULL COMMENT '成员用户ID',
+    `role` tinyint NOT NULL COMMENT '组织角色 所有者 管理员 成员',
+    `status` tinyint NOT NULL COMMENT '状态',
+    `change_user_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '更改用户',
+    `change_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '更改时间',
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `org_user` (`org_id`, `user_id`) USING BTREE,
+    KEY `user_id` (`user_id`, `status`) USING BTREE
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '组织成员';
+
+CREATE TABLE `yaf_app_org_invite` (
+    `id` bigint unsigned NOT NULL AUTO_INCREMENT COMMENT 'ID',
+    `org_id` bigint unsigned NOT NULL COMMENT '组织ID',
+    `email` varchar(254) NOT NULL COMMENT '受邀邮箱',
+    `role` tinyint NOT NULL COMMENT '加入后的组织角色',
+    `invite_code` varchar(64) NOT NULL COMMENT '邀请码',
+    `status` tinyint NOT NULL COMMENT '状态 待接受 已接受 已取消',
+    `time_out` bigint unsigned NOT NULL COMMENT '过期时间',
+    `user_id` bigint unsigned NOT NULL COMMENT '邀请用户ID',
+    `accept_user_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '接受邀请的用户ID',
+    `add_time` bigint unsigned NOT NULL COMMENT '添加时间',
+    `change_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '更改时间',
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `invite_code` (`invite_code`) USING BTREE,
+    KEY `org_id` (`org_id`, `status`) USING BTREE
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '组织邀请';
+
+CREATE TABLE `yaf_app_transfer` (
+    `id` bigint unsigned NOT NULL AUTO_INCREMENT COMMENT 'ID',
+    `app_id` bigint unsigned NOT NULL COMMENT '应用ID',
+    `from_user_id` bigint unsigned NOT NULL COMMENT '转出用户ID',
+    `from_org_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '转出组织ID',
+    `to_user_id` bigint unsigned NOT NULL COMMENT '接收用户ID',
+    `status` tinyint NOT NULL COMMENT '状态 待确认 已接受 已取消 已拒绝',
+    `user_id` bigint unsigned NOT NULL COMMENT '发起用户ID',
+    `add_time` bigint unsigned NOT NULL COMMENT '添加时间',
+    `change_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '更改时间',
+    PRIMARY KEY (`id`),
+    KEY `app_id` (`app_id`, `status`) USING BTREE,
+    KEY `to_user_id` (`to_user_id`, `status`) USING BTREE
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '应用转移给其他用户的待确认记录';
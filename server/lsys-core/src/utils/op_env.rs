@@ -11,6 +11,8 @@ pub struct RequestEnv {
     pub request_id: Option<String>,
     pub request_user_agent: Option<String>,
     pub device_id: Option<String>,
+    pub personal_token: Option<String>, //使用个人访问令牌请求时为令牌HASH
+    pub request_path: Option<String>,   //请求路径,模拟登录审计用
 }
 
 impl RequestEnv {
@@ -80,6 +82,8 @@ impl RequestEnv {
             request_id: request_id.map(|e| e.to_string()),
             device_id: device_id.map(|e| e.to_string()),
             request_user_agent: request_user_agent.map(|e| e.to_string()),
+            personal_token: None,
+            request_path: None,
         })
     }
 }
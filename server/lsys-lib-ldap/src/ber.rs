@@ -0,0 +1,144 @@
+//LDAP 使用的 BER 编码,仅实现单字节 tag
+
+use crate::{LdapError, LdapResult};
+
+pub const TAG_BOOLEAN: u8 = 0x01;
+pub const TAG_INTEGER: u8 = 0x02;
+pub const TAG_OCTET_STRING: u8 = 0x04;
+pub const TAG_NULL: u8 = 0x05;
+pub const TAG_ENUMERATED: u8 = 0x0a;
+pub const TAG_SEQUENCE: u8 = 0x30;
+pub const TAG_SET: u8 = 0x31;
+
+//单个 TLV 节点
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tlv {
+    pub tag: u8,
+    pub value: Vec<u8>,
+}
+
+impl Tlv {
+    pub fn new(tag: u8, value: Vec<u8>) -> Self {
+        Self { tag, value }
+    }
+    pub fn constructed(tag: u8, children: Vec<Tlv>) -> Self {
+        let mut value = vec![];
+        for child in children {
+            child.encode_to(&mut value);
+        }
+        Self { tag, value }
+    }
+    pub fn sequence(children: Vec<Tlv>) -> Self {
+        Self::constructed(TAG_SEQUENCE, children)
+    }
+    pub fn octet_string(tag: u8, data: &[u8]) -> Self {
+        Self::new(tag, data.to_vec())
+    }
+    pub fn string(data: &str) -> Self {
+        Self::octet_string(TAG_OCTET_STRING, data.as_bytes())
+    }
+    pub fn boolean(data: bool) -> Self {
+        Self::new(TAG_BOOLEAN, vec![if data { 0xff } else { 0x00 }])
+    }
+    pub fn integer(tag: u8, data: i64) -> Self {
+        let bytes = data.to_be_bytes();
+        let mut start = 0;
+        //去掉多余的符号位字节
+        while start < bytes.len() - 1 {
+            let (cur, next) = (bytes[start], bytes[start + 1]);
+            if (cur == 0x00 && next & 0x80 == 0) || (cur == 0xff && next & 0x80 != 0) {
+                start += 1;
+            } else {
+                break;
+            }
+        }
+        Self::new(tag, bytes[start..].to_vec())
+    }
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = vec![];
+        self.encode_to(&mut out);
+        out
+    }
+    fn encode_to(&self, out: &mut Vec<u8>) {
+        out.push(self.tag);
+        let len = self.value.len();
+        if len < 0x80 {
+            out.push(len as u8);
+        } else {
+            let bytes = (len as u64).to_be_bytes();
+            let skip = bytes.iter().take_while(|e| **e == 0).count();
+            out.push(0x80 | (bytes.len() - skip) as u8);
+            out.extend_from_slice(&bytes[skip..]);
+        }
+        out.extend_from_slice(&self.value);
+    }
+    //从缓冲区解析一个节点,数据不完整时返回 None
+    pub fn decode(buf: &[u8]) -> LdapResult<Option<(Tlv, usize)>> {
+        if buf.len() < 2 {
+            return Ok(None);
+        }
+        let tag = buf[0];
+        let (len, head) = if buf[1] & 0x80 == 0 {
+            (buf[1] as usize, 2)
+        } else {
+            let num = (buf[1] & 0x7f) as usize;
+            if num == 0 || num > 8 {
+                return Err(LdapError::Ber(format!("unsupported length bytes:{}", num)));
+            }
+            if buf.len() < 2 + num {
+                return Ok(None);
+            }
+            let mut len = 0usize;
+            for b in &buf[2..2 + num] {
+                len = (len << 8) | *b as usize;
+            }
+            (len, 2 + num)
+        };
+        if buf.len() < head + len {
+            return Ok(None);
+        }
+        Ok(Some((
+            Tlv::new(tag, buf[head..head + len].to_vec()),
+            head + len,
+        )))
+    }
+    //解析子节点
+    pub fn children(&self) -> LdapResult<Vec<Tlv>> {
+        let mut out = vec![];
+        let mut pos = 0;
+        while pos < self.value.len() {
+            match Tlv::decode(&self.value[pos..])? {
+                Some((tlv, size)) => {
+                    out.push(tlv);
+                    pos += size;
+                }
+                None => {
+                    return Err(LdapError::Ber(format!(
+                        "truncated element in tag:{:#x}",
+                        self.tag
+                    )))
+                }
+            }
+        }
+        Ok(out)
+    }
+    pub fn as_integer(&self) -> LdapResult<i64> {
+        if self.value.is_empty() || self.value.len() > 8 {
+            return Err(LdapError::Ber(format!(
+                "bad integer length:{}",
+                self.value.len()
+            )));
+        }
+        let mut out: i64 = if self.value[0] & 0x80 != 0 { -1 } else { 0 };
+        for b in self.value.iter() {
+            out = (out << 8) | *b as i64;
+        }
+        Ok(out)
+    }
+    pub fn as_bool(&self) -> bool {
+        self.value.first().map(|e| *e != 0).unwrap_or(false)
+    }
+    pub fn as_string(&self) -> String {
+        String::from_utf8_lossy(&self.value).to_string()
+    }
+}
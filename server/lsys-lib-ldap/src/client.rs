@@ -0,0 +1,296 @@
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tracing::debug;
+
+use crate::ber::{Tlv, TAG_ENUMERATED, TAG_INTEGER, TAG_OCTET_STRING, TAG_SEQUENCE, TAG_SET};
+use crate::{parse_filter, LdapError, LdapResult};
+
+pub const OP_BIND_REQUEST: u8 = 0x60;
+pub const OP_BIND_RESPONSE: u8 = 0x61;
+pub const OP_UNBIND_REQUEST: u8 = 0x42;
+pub const OP_SEARCH_REQUEST: u8 = 0x63;
+pub const OP_SEARCH_ENTRY: u8 = 0x64;
+pub const OP_SEARCH_DONE: u8 = 0x65;
+pub const OP_SEARCH_REFERENCE: u8 = 0x73;
+pub const AUTH_SIMPLE: u8 = 0x80;
+
+trait LdapStream: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> LdapStream for T {}
+
+#[derive(Debug, Clone, Copy)]
+pub enum LdapScope {
+    Base = 0,
+    OneLevel = 1,
+    Subtree = 2,
+}
+
+#[derive(Debug, Clone)]
+pub struct LdapConnectOption {
+    pub timeout: u64,          //连接及请求超时,秒
+    pub tls_skip_verify: bool, //ldaps 时不校验证书,仅用于测试
+}
+
+impl Default for LdapConnectOption {
+    fn default() -> Self {
+        Self {
+            timeout: 10,
+            tls_skip_verify: false,
+        }
+    }
+}
+
+//搜索结果条目
+#[derive(Debug, Clone, Default)]
+pub struct LdapEntry {
+    pub dn: String,
+    pub attrs: Vec<(String, Vec<Vec<u8>>)>,
+}
+
+impl LdapEntry {
+    //属性名不区分大小写
+    pub fn attr_values(&self, name: &str) -> Vec<&[u8]> {
+        self.attrs
+            .iter()
+            .filter(|(key, _)| key.eq_ignore_ascii_case(name))
+            .flat_map(|(_, vals)| vals.iter().map(|e| e.as_slice()))
+            .collect()
+    }
+    //得到字符串值,非 UTF-8 数据(如 objectGUID)转为十六进制
+    pub fn attr_string(&self, name: &str) -> Option<String> {
+        if name.eq_ignore_ascii_case("dn") {
+            return Some(self.dn.clone());
+        }
+        self.attr_values(name)
+            .first()
+            .map(|e| match std::str::from_utf8(e) {
+                Ok(s) => s.to_string(),
+                Err(_) => e.iter().map(|b| format!("{:02x}", b)).collect(),
+            })
+            .filter(|e| !e.is_empty())
+    }
+    pub fn attr_strings(&self, name: &str) -> Vec<String> {
+        self.attr_values(name)
+            .into_iter()
+            .map(|e| String::from_utf8_lossy(e).to_string())
+            .collect()
+    }
+}
+
+//精简 LDAPv3 客户端,仅支持简单绑定及搜索
+pub struct LdapClient {
+    stream: Box<dyn LdapStream>,
+    buf: Vec<u8>,
+    msg_id: i64,
+    timeout: Duration,
+}
+
+impl LdapClient {
+    //url 格式: ldap://host:389 或 ldaps://host:636
+    pub async fn connect(url: &str, option: &LdapConnectOption) -> LdapResult<Self> {
+        let (tls, host, port) = parse_url(url)?;
+        let timeout = Duration::from_secs(option.timeout.max(1));
+        let tcp = tokio::time::timeout(timeout, TcpStream::connect((host.as_str(), port)))
+            .await
+            .map_err(|_| LdapError::Timeout)??;
+        let stream: Box<dyn LdapStream> = if tls {
+            let connector = native_tls::TlsConnector::builder()
+                .danger_accept_invalid_certs(option.tls_skip_verify)
+                .danger_accept_invalid_hostnames(option.tls_skip_verify)
+                .build()?;
+            let connector = tokio_native_tls::TlsConnector::from(connector);
+            let tls_stream = tokio::time::timeout(timeout, connector.connect(&host, tcp))
+                .await
+                .map_err(|_| LdapError::Timeout)??;
+            Box::new(tls_stream)
+        } else {
+            Box::new(tcp)
+        };
+        Ok(Self {
+            stream,
+            buf: vec![],
+            msg_id: 0,
+            timeout,
+        })
+    }
+    //简单绑定,密码为空时服务器会视为匿名绑定,调用方需自行校验
+    pub async fn simple_bind(&mut self, dn: &str, password: &str) -> LdapResult<()> {
+        let msg_id = self
+            .send(Tlv::constructed(
+                OP_BIND_REQUEST,
+                vec![
+                    Tlv::integer(TAG_INTEGER, 3),
+                    Tlv::string(dn),
+                    Tlv::octet_string(AUTH_SIMPLE, password.as_bytes()),
+                ],
+            ))
+            .await?;
+        let op = self.read_op(msg_id).await?;
+        if op.tag != OP_BIND_RESPONSE {
+            return Err(LdapError::Ber(format!(
+                "unexpected bind response:{:#x}",
+                op.tag
+            )));
+        }
+        check_result(&op)
+    }
+    pub async fn search(
+        &mut self,
+        base_dn: &str,
+        scope: LdapScope,
+        filter: &str,
+        attrs: &[&str],
+        size_limit: i64,
+    ) -> LdapResult<Vec<LdapEntry>> {
+        let filter = parse_filter(filter)?;
+        let msg_id = self
+            .send(Tlv::constructed(
+                OP_SEARCH_REQUEST,
+                vec![
+                    Tlv::string(base_dn),
+                    Tlv::integer(TAG_ENUMERATED, scope as i64),
+                    Tlv::integer(TAG_ENUMERATED, 0),
+                    Tlv::integer(TAG_INTEGER, size_limit),
+                    Tlv::integer(TAG_INTEGER, self.timeout.as_secs() as i64),
+                    Tlv::boolean(false),
+                    filter,
+                    Tlv::sequence(attrs.iter().map(|e| Tlv::string(e)).collect()),
+                ],
+            ))
+            .await?;
+        let mut out = vec![];
+        loop {
+            let op = self.read_op(msg_id).await?;
+            match op.tag {
+                OP_SEARCH_ENTRY => out.push(parse_entry(&op)?),
+                OP_SEARCH_REFERENCE => {}
+                OP_SEARCH_DONE => {
+                    check_result(&op)?;
+                    break;
+                }
+                tag => {
+                    return Err(LdapError::Ber(format!(
+                        "unexpected search response:{:#x}",
+                        tag
+                    )))
+                }
+            }
+        }
+        Ok(out)
+    }
+    pub async fn unbind(mut self) -> LdapResult<()> {
+        self.send(Tlv::new(OP_UNBIND_REQUEST, vec![])).await?;
+        self.stream.shutdown().await?;
+        Ok(())
+    }
+    async fn send(&mut self, op: Tlv) -> LdapResult<i64> {
+        self.msg_id += 1;
+        let data = Tlv::sequence(vec![Tlv::integer(TAG_INTEGER, self.msg_id), op]).encode();
+        tokio::time::timeout(self.timeout, self.stream.write_all(&data))
+            .await
+            .map_err(|_| LdapError::Timeout)??;
+        Ok(self.msg_id)
+    }
+    //读取指定消息编号的响应
+    async fn read_op(&mut self, msg_id: i64) -> LdapResult<Tlv> {
+        loop {
+            if let Some((msg, size)) = Tlv::decode(&self.buf)? {
+                self.buf.drain(..size);
+                let mut items = msg.children()?.into_iter();
+                let id = items
+                    .next()
+                    .ok_or_else(|| LdapError::Ber("miss message id".to_string()))?
+                    .as_integer()?;
+                let op = items
+                    .next()
+                    .ok_or_else(|| LdapError::Ber("miss protocol op".to_string()))?;
+                if id == msg_id {
+                    return Ok(op);
+                }
+                //服务器主动断开通知等
+                if id == 0 {
+                    check_result(&op)?;
+                }
+                debug!("ldap skip message:{} wait:{}", id, msg_id);
+                continue;
+            }
+            let mut tmp = [0u8; 4096];
+            let size = tokio::time::timeout(self.timeout, self.stream.read(&mut tmp))
+                .await
+                .map_err(|_| LdapError::Timeout)??;
+            if size == 0 {
+                return Err(LdapError::Io(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "ldap connect closed",
+                )));
+            }
+            self.buf.extend_from_slice(&tmp[..size]);
+        }
+    }
+}
+
+fn parse_url(url: &str) -> LdapResult<(bool, String, u16)> {
+    let (tls, addr) = if let Some(addr) = url.strip_prefix("ldaps://") {
+        (true, addr)
+    } else if let Some(addr) = url.strip_prefix("ldap://") {
+        (false, addr)
+    } else {
+        return Err(LdapError::Url(format!("unsupported scheme:{}", url)));
+    };
+    let addr = addr.trim_end_matches('/');
+    let default_port = if tls { 636 } else { 389 };
+    let (host, port) = match addr.rsplit_once(':') {
+        Some((host, port)) if !host.ends_with(']') || host.starts_with('[') => (
+            host,
+            port.parse::<u16>()
+                .map_err(|e| LdapError::Url(format!("bad port {}:{}", port, e)))?,
+        ),
+        _ => (addr, default_port),
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if host.is_empty() {
+        return Err(LdapError::Url(format!("miss host:{}", url)));
+    }
+    Ok((tls, host.to_string(), port))
+}
+
+//LDAPResult: resultCode matchedDN diagnosticMessage
+fn check_result(op: &Tlv) -> LdapResult<()> {
+    let items = op.children()?;
+    let code = items
+        .first()
+        .filter(|e| e.tag == TAG_ENUMERATED)
+        .ok_or_else(|| LdapError::Ber("miss result code".to_string()))?
+        .as_integer()?;
+    if code != 0 {
+        let msg = items.get(2).map(|e| e.as_string()).unwrap_or_default();
+        return Err(LdapError::Result(code, msg));
+    }
+    Ok(())
+}
+
+fn parse_entry(op: &Tlv) -> LdapResult<LdapEntry> {
+    let items = op.children()?;
+    let dn = items
+        .first()
+        .filter(|e| e.tag == TAG_OCTET_STRING)
+        .ok_or_else(|| LdapError::Ber("miss entry dn".to_string()))?
+        .as_string();
+    let mut attrs = vec![];
+    if let Some(attr_list) = items.get(1).filter(|e| e.tag == TAG_SEQUENCE) {
+        for attr in attr_list.children()? {
+            let mut parts = attr.children()?.into_iter();
+            let name = match parts.next() {
+                Some(e) => e.as_string(),
+                None => continue,
+            };
+            let vals = match parts.next().filter(|e| e.tag == TAG_SET) {
+                Some(e) => e.children()?.into_iter().map(|e| e.value).collect(),
+                None => vec![],
+            };
+            attrs.push((name, vals));
+        }
+    }
+    Ok(LdapEntry { dn, attrs })
+}
@@ -0,0 +1,167 @@
+//RFC 4515 字符串过滤条件转 BER
+//支持 & | ! = ~= >= <= 存在(=*) 及子串匹配
+
+use crate::ber::{Tlv, TAG_OCTET_STRING};
+use crate::{LdapError, LdapResult};
+
+const FILTER_AND: u8 = 0xa0;
+const FILTER_OR: u8 = 0xa1;
+const FILTER_NOT: u8 = 0xa2;
+const FILTER_EQUALITY: u8 = 0xa3;
+const FILTER_SUBSTRINGS: u8 = 0xa4;
+const FILTER_GE: u8 = 0xa5;
+const FILTER_LE: u8 = 0xa6;
+const FILTER_PRESENT: u8 = 0x87;
+const FILTER_APPROX: u8 = 0xa8;
+
+const SUB_INITIAL: u8 = 0x80;
+const SUB_ANY: u8 = 0x81;
+const SUB_FINAL: u8 = 0x82;
+
+//转义过滤条件中的值,拼接用户输入时必须使用
+pub fn ldap_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\5c"),
+            '*' => out.push_str("\\2a"),
+            '(' => out.push_str("\\28"),
+            ')' => out.push_str("\\29"),
+            '\0' => out.push_str("\\00"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+pub fn parse_filter(filter: &str) -> LdapResult<Tlv> {
+    let filter = filter.trim();
+    let filter = if filter.starts_with('(') {
+        filter.to_string()
+    } else {
+        format!("({})", filter)
+    };
+    let bytes = filter.as_bytes();
+    let (tlv, pos) = parse_item(bytes, 0)?;
+    if pos != bytes.len() {
+        return Err(LdapError::Filter(format!("unexpected data at:{}", pos)));
+    }
+    Ok(tlv)
+}
+
+fn parse_item(buf: &[u8], pos: usize) -> LdapResult<(Tlv, usize)> {
+    if buf.get(pos) != Some(&b'(') {
+        return Err(LdapError::Filter(format!("miss '(' at:{}", pos)));
+    }
+    let pos = pos + 1;
+    let (tlv, pos) = match buf.get(pos) {
+        Some(b'&') => parse_list(buf, pos + 1, FILTER_AND)?,
+        Some(b'|') => parse_list(buf, pos + 1, FILTER_OR)?,
+        Some(b'!') => {
+            let (item, pos) = parse_item(buf, pos + 1)?;
+            (Tlv::constructed(FILTER_NOT, vec![item]), pos)
+        }
+        Some(_) => parse_simple(buf, pos)?,
+        None => return Err(LdapError::Filter("unexpected end".to_string())),
+    };
+    if buf.get(pos) != Some(&b')') {
+        return Err(LdapError::Filter(format!("miss ')' at:{}", pos)));
+    }
+    Ok((tlv, pos + 1))
+}
+
+fn parse_list(buf: &[u8], mut pos: usize, tag: u8) -> LdapResult<(Tlv, usize)> {
+    let mut items = vec![];
+    while buf.get(pos) == Some(&b'(') {
+        let (item, next) = parse_item(buf, pos)?;
+        items.push(item);
+        pos = next;
+    }
+    if items.is_empty() {
+        return Err(LdapError::Filter(format!("empty filter list at:{}", pos)));
+    }
+    Ok((Tlv::constructed(tag, items), pos))
+}
+
+fn parse_simple(buf: &[u8], start: usize) -> LdapResult<(Tlv, usize)> {
+    let end = buf[start..]
+        .iter()
+        .position(|e| *e == b')')
+        .map(|e| start + e)
+        .ok_or_else(|| LdapError::Filter(format!("miss ')' after:{}", start)))?;
+    let item = &buf[start..end];
+    let eq = item
+        .iter()
+        .position(|e| *e == b'=')
+        .ok_or_else(|| LdapError::Filter(format!("miss '=' at:{}", start)))?;
+    if eq == 0 {
+        return Err(LdapError::Filter(format!("miss attribute at:{}", start)));
+    }
+    let (attr, tag) = match item[eq - 1] {
+        b'~' => (&item[..eq - 1], FILTER_APPROX),
+        b'>' => (&item[..eq - 1], FILTER_GE),
+        b'<' => (&item[..eq - 1], FILTER_LE),
+        _ => (&item[..eq], FILTER_EQUALITY),
+    };
+    if attr.is_empty() {
+        return Err(LdapError::Filter(format!("miss attribute at:{}", start)));
+    }
+    let value = &item[eq + 1..];
+    let tlv = if tag == FILTER_EQUALITY && value == b"*" {
+        Tlv::octet_string(FILTER_PRESENT, attr)
+    } else if tag == FILTER_EQUALITY && value.contains(&b'*') {
+        let parts = value.split(|e| *e == b'*').collect::<Vec<_>>();
+        let last = parts.len() - 1;
+        let mut subs = vec![];
+        for (i, part) in parts.into_iter().enumerate() {
+            if part.is_empty() {
+                continue;
+            }
+            let sub_tag = if i == 0 {
+                SUB_INITIAL
+            } else if i == last {
+                SUB_FINAL
+            } else {
+                SUB_ANY
+            };
+            subs.push(Tlv::new(sub_tag, unescape(part)?));
+        }
+        Tlv::constructed(
+            FILTER_SUBSTRINGS,
+            vec![
+                Tlv::string(&String::from_utf8_lossy(attr)),
+                Tlv::sequence(subs),
+            ],
+        )
+    } else {
+        Tlv::constructed(
+            tag,
+            vec![
+                Tlv::string(&String::from_utf8_lossy(attr)),
+                Tlv::octet_string(TAG_OCTET_STRING, &unescape(value)?),
+            ],
+        )
+    };
+    Ok((tlv, end))
+}
+
+//还原 \XX 转义
+fn unescape(value: &[u8]) -> LdapResult<Vec<u8>> {
+    let mut out = Vec::with_capacity(value.len());
+    let mut i = 0;
+    while i < value.len() {
+        if value[i] == b'\\' {
+            let hex = value
+                .get(i + 1..i + 3)
+                .and_then(|e| std::str::from_utf8(e).ok())
+                .and_then(|e| u8::from_str_radix(e, 16).ok())
+                .ok_or_else(|| LdapError::Filter("bad escape value".to_string()))?;
+            out.push(hex);
+            i += 3;
+        } else {
+            out.push(value[i]);
+            i += 1;
+        }
+    }
+    Ok(out)
+}
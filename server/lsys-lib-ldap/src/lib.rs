@@ -0,0 +1,59 @@
+pub mod ber;
+mod client;
+mod filter;
+
+pub use client::*;
+pub use filter::*;
+
+use std::fmt::{Display, Formatter};
+
+//LDAP 结果码:用户名或密码错误
+pub const RESULT_INVALID_CREDENTIALS: i64 = 49;
+
+#[derive(Debug)]
+pub enum LdapError {
+    Io(std::io::Error),
+    Tls(native_tls::Error),
+    Url(String),
+    Ber(String),
+    Filter(String),
+    Timeout,
+    //服务器返回的错误结果
+    Result(i64, String),
+}
+
+impl LdapError {
+    //是否为密码错误
+    pub fn is_invalid_credentials(&self) -> bool {
+        matches!(self, LdapError::Result(code, _) if *code == RESULT_INVALID_CREDENTIALS)
+    }
+}
+
+impl Display for LdapError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LdapError::Io(err) => write!(f, "ldap io error:{}", err),
+            LdapError::Tls(err) => write!(f, "ldap tls error:{}", err),
+            LdapError::Url(err) => write!(f, "ldap url error:{}", err),
+            LdapError::Ber(err) => write!(f, "ldap decode error:{}", err),
+            LdapError::Filter(err) => write!(f, "ldap filter error:{}", err),
+            LdapError::Timeout => write!(f, "ldap request timeout"),
+            LdapError::Result(code, msg) => write!(f, "ldap result [{}]:{}", code, msg),
+        }
+    }
+}
+
+impl std::error::Error for LdapError {}
+
+impl From<std::io::Error> for LdapError {
+    fn from(err: std::io::Error) -> Self {
+        LdapError::Io(err)
+    }
+}
+impl From<native_tls::Error> for LdapError {
+    fn from(err: native_tls::Error) -> Self {
+        LdapError::Tls(err)
+    }
+}
+
+pub type LdapResult<T> = Result<T, LdapError>;
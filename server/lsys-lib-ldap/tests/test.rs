@@ -0,0 +1,181 @@
+use lsys_lib_ldap::ber::{Tlv, TAG_ENUMERATED, TAG_INTEGER, TAG_OCTET_STRING, TAG_SET};
+use lsys_lib_ldap::{
+    ldap_escape, parse_filter, LdapClient, LdapConnectOption, LdapScope, OP_BIND_REQUEST,
+    OP_BIND_RESPONSE, OP_SEARCH_DONE, OP_SEARCH_ENTRY, OP_SEARCH_REQUEST, OP_UNBIND_REQUEST,
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const SERVICE_DN: &str = "cn=admin,dc=example,dc=com";
+const SERVICE_PW: &str = "admin";
+
+//测试用户: uid dn 密码 邮箱 组
+const USERS: &[(&str, &str, &str, &str, &str)] = &[(
+    "alice",
+    "uid=alice,ou=people,dc=example,dc=com",
+    "alice-pw",
+    "alice@example.com",
+    "cn=dev,ou=groups,dc=example,dc=com",
+)];
+
+fn ldap_result(tag: u8, code: i64, msg: &str) -> Tlv {
+    Tlv::constructed(
+        tag,
+        vec![
+            Tlv::integer(TAG_ENUMERATED, code),
+            Tlv::string(""),
+            Tlv::string(msg),
+        ],
+    )
+}
+
+fn attr(name: &str, val: &str) -> Tlv {
+    Tlv::sequence(vec![
+        Tlv::string(name),
+        Tlv::constructed(TAG_SET, vec![Tlv::string(val)]),
+    ])
+}
+
+//从过滤条件中找出 uid 等值条件的值
+fn find_uid(filter: &Tlv) -> Option<String> {
+    match filter.tag {
+        0xa0 | 0xa1 => filter.children().ok()?.iter().find_map(find_uid),
+        0xa3 => {
+            let items = filter.children().ok()?;
+            if items.first()?.as_string().eq_ignore_ascii_case("uid") {
+                Some(items.get(1)?.as_string())
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+async fn handle(mut stream: TcpStream) {
+    let mut buf = vec![];
+    let mut bound = false;
+    loop {
+        let (msg, size) = match Tlv::decode(&buf).unwrap() {
+            Some(e) => e,
+            None => {
+                let mut tmp = [0u8; 1024];
+                let size = stream.read(&mut tmp).await.unwrap();
+                if size == 0 {
+                    return;
+                }
+                buf.extend_from_slice(&tmp[..size]);
+                continue;
+            }
+        };
+        buf.drain(..size);
+        let items = msg.children().unwrap();
+        let msg_id = items[0].as_integer().unwrap();
+        let op = &items[1];
+        let mut out = vec![];
+        match op.tag {
+            OP_BIND_REQUEST => {
+                let parts = op.children().unwrap();
+                let dn = parts[1].as_string();
+                let pw = parts[2].as_string();
+                let ok = (dn == SERVICE_DN && pw == SERVICE_PW)
+                    || USERS
+                        .iter()
+                        .any(|u| u.1 == dn && u.2 == pw && !pw.is_empty());
+                bound = ok;
+                let code = if ok { 0 } else { 49 };
+                out.push(ldap_result(OP_BIND_RESPONSE, code, ""));
+            }
+            OP_SEARCH_REQUEST => {
+                if !bound {
+                    out.push(ldap_result(OP_SEARCH_DONE, 50, "insufficient access"));
+                } else {
+                    let parts = op.children().unwrap();
+                    let uid = find_uid(&parts[6]);
+                    for user in USERS.iter().filter(|u| Some(u.0.to_string()) == uid) {
+                        out.push(Tlv::constructed(
+                            OP_SEARCH_ENTRY,
+                            vec![
+                                Tlv::string(user.1),
+                                Tlv::sequence(vec![
+                                    attr("uid", user.0),
+                                    attr("mail", user.3),
+                                    attr("memberOf", user.4),
+                                ]),
+                            ],
+                        ));
+                    }
+                    out.push(ldap_result(OP_SEARCH_DONE, 0, ""));
+                }
+            }
+            OP_UNBIND_REQUEST => return,
+            _ => return,
+        }
+        for op in out {
+            let data = Tlv::sequence(vec![Tlv::integer(TAG_INTEGER, msg_id), op]).encode();
+            stream.write_all(&data).await.unwrap();
+        }
+    }
+}
+
+async fn mock_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(handle(stream));
+        }
+    });
+    format!("ldap://{}", addr)
+}
+
+#[tokio::test]
+async fn test_bind_and_search() {
+    let url = mock_server().await;
+    let opt = LdapConnectOption::default();
+    let mut client = LdapClient::connect(&url, &opt).await.unwrap();
+    client.simple_bind(SERVICE_DN, SERVICE_PW).await.unwrap();
+    let filter = format!("(&(objectClass=person)(uid={}))", ldap_escape("alice"));
+    let res = client
+        .search(
+            "dc=example,dc=com",
+            LdapScope::Subtree,
+            &filter,
+            &["uid", "mail", "memberOf"],
+            2,
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].dn, USERS[0].1);
+    assert_eq!(res[0].attr_string("MAIL").unwrap(), USERS[0].3);
+    assert_eq!(
+        res[0].attr_strings("memberof"),
+        vec![USERS[0].4.to_string()]
+    );
+    let res = client
+        .search("dc=example,dc=com", LdapScope::Subtree, "(uid=bob)", &[], 2)
+        .await
+        .unwrap();
+    assert!(res.is_empty());
+    client.simple_bind(USERS[0].1, USERS[0].2).await.unwrap();
+    let err = client.simple_bind(USERS[0].1, "bad").await.unwrap_err();
+    assert!(err.is_invalid_credentials());
+    client.unbind().await.unwrap();
+}
+
+#[test]
+fn test_filter() {
+    assert_eq!(ldap_escape("a*(b)\\"), "a\\2a\\28b\\29\\5c");
+    let tlv = parse_filter("uid=a\\2ab").unwrap();
+    assert_eq!(tlv.tag, 0xa3);
+    let items = tlv.children().unwrap();
+    assert_eq!(items[1], Tlv::octet_string(TAG_OCTET_STRING, b"a*b"));
+    let tlv = parse_filter("(&(objectClass=*)(|(cn=ab*c*d)(!(mail>=x))))").unwrap();
+    assert_eq!(tlv.tag, 0xa0);
+    let items = tlv.children().unwrap();
+    assert_eq!(items[0].tag, 0x87);
+    assert_eq!(items[1].tag, 0xa1);
+    assert!(parse_filter("(uid=a").is_err());
+    assert!(parse_filter("(&)").is_err());
+}
@@ -15,6 +15,12 @@ pub trait ChangeLogData {
     fn encode(&self) -> String; //更改是相关数据
 }
 
+/// 日志主体条件,用于查询与某主体相关的全部日志
+pub enum ChangeLogSubject<'t> {
+    Source(&'t str, u64),           //指定类型日志的相关记录ID
+    DataKey(&'t str, &'t str, u64), //指定类型日志数据中的字段值
+}
+
 pub struct ChangeLoggerDao {
     db: Pool<MySql>,
 }
@@ -86,6 +92,10 @@ impl ChangeLoggerDao {
             .chars()
             .take(64)
             .collect();
+        let personal_token = env_data
+            .as_ref()
+            .and_then(|e| e.personal_token.to_owned())
+            .unwrap_or_default();
 
         let new_data = model_option_set!(ChangeLogModelRef, {
             log_type: log_type,
@@ -98,6 +108,7 @@ impl ChangeLoggerDao {
             add_time:time,
             device_id:device_id,
             request_user_agent:request_user_agent,
+            personal_token:personal_token,
         });
 
         let res = db_option_executor!(
@@ -201,4 +212,65 @@ impl ChangeLoggerDao {
         .fetch_one(&self.db)
         .await?);
     }
+    fn subject_where(subject: &[ChangeLogSubject<'_>]) -> Vec<String> {
+        subject
+            .iter()
+            .filter_map(|e| match e {
+                ChangeLogSubject::Source(log_type, source_id) => Some(sql_format!(
+                    "(log_type={} and source_id={})",
+                    log_type,
+                    source_id
+                )),
+                ChangeLogSubject::DataKey(log_type, key, val) => {
+                    let key = string_clear(key, StringClear::Option(STRING_CLEAR_FORMAT), Some(33));
+                    if key.is_empty() {
+                        return None;
+                    }
+                    //日志数据为 serde_json 编码,字段后为逗号或结束括号,不依赖数据库JSON解析
+                    Some(sql_format!(
+                        "(log_type={} and (log_data like {} or log_data like {}))",
+                        log_type,
+                        format!("%\"{}\":{},%", key, val),
+                        format!("%\"{}\":{}}}%", key, val)
+                    ))
+                }
+            })
+            .collect()
+    }
+    /// 按主体查询日志,满足任一条件即返回
+    pub async fn subject_data(
+        &self,
+        subject: &[ChangeLogSubject<'_>],
+        limit: Option<&LimitParam>,
+    ) -> LoggerResult<(Vec<ChangeLogModel>, Option<u64>)> {
+        let sqlwhere = Self::subject_where(subject);
+        if sqlwhere.is_empty() {
+            return Ok((vec![], None));
+        }
+        let where_sql = format!("({})", sqlwhere.join(" or "));
+        let tmp = if let Some(page) = limit {
+            format!(
+                "{} {} order by {} {} ",
+                where_sql,
+                page.where_sql("id", Some("and")),
+                page.order_sql("id"),
+                page.limit_sql(),
+            )
+        } else {
+            format!("{} order by id desc", where_sql)
+        };
+        let mut data = sqlx::query_as::<_, ChangeLogModel>(&sql_format!(
+            "select * from {} where {}",
+            ChangeLogModel::table_name(),
+            SqlExpr(tmp)
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        let next = limit
+            .as_ref()
+            .map(|page| page.tidy(&mut data))
+            .unwrap_or_default()
+            .map(|e| e.id);
+        Ok((data, next))
+    }
 }
@@ -45,6 +45,10 @@ pub struct ChangeLogModel {
     #[sqlx(default)]
     pub device_id: String,
 
+    /// 个人访问令牌HASH
+    #[sqlx(default)]
+    pub personal_token: String,
+
     /// 时间
     #[sqlx(default)]
     pub add_time: u64,
@@ -9,6 +9,7 @@ CREATE TABLE `yaf_change_logs` (
     `request_id` varchar(64) NOT NULL DEFAULT '' COMMENT '请求id',
     `request_user_agent` varchar(254) NOT NULL DEFAULT '' COMMENT '请求UA',
     `device_id` varchar(64) NOT NULL DEFAULT '' COMMENT '设备标识',
+    `personal_token` varchar(64) NOT NULL DEFAULT '' COMMENT '个人访问令牌HASH',
     `add_time` bigint unsigned NOT NULL COMMENT '添加时间',
     PRIMARY KEY (`id`)
 ) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '操作日志';
\ No newline at end of file
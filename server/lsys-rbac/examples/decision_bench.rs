@@ -0,0 +1,85 @@
+//授权检查耗时对比
+//使用 config 目录下的数据库及REDIS配置
+//运行: cargo run -p lsys-rbac --example decision_bench --release -- [访问用户ID] [资源数量] [循环次数]
+//注意: check 及 batch_check 均会写入审计记录
+use lsys_core::{AppCore, RemoteNotify};
+use lsys_logger::dao::ChangeLoggerDao;
+use lsys_rbac::dao::{AccessCheckEnv, AccessCheckOp, AccessCheckRes, RbacConfig, RbacDao};
+use std::{sync::Arc, time::Instant};
+
+#[tokio::main]
+async fn main() {
+    let mut args = std::env::args().skip(1);
+    let user_id = args.next().and_then(|e| e.parse::<u64>().ok()).unwrap_or(1);
+    let res_num = args
+        .next()
+        .and_then(|e| e.parse::<usize>().ok())
+        .unwrap_or(200);
+    let loop_num = args
+        .next()
+        .and_then(|e| e.parse::<usize>().ok())
+        .unwrap_or(10);
+
+    let app_core = AppCore::new("", "config", None, None).await.unwrap();
+    let db = app_core.create_db().await.unwrap();
+    let redis = app_core.create_redis().await.unwrap();
+    let app_core = Arc::new(app_core);
+    let logger = Arc::new(ChangeLoggerDao::new(db.clone()));
+    let remote_notify =
+        Arc::new(RemoteNotify::new("lsys-remote-notify", app_core, redis.clone()).unwrap());
+    let rbac = RbacDao::new(db, redis, remote_notify, RbacConfig::new(true), logger)
+        .await
+        .unwrap();
+
+    let res_data = (0..res_num).map(|e| e.to_string()).collect::<Vec<_>>();
+    let check_res = res_data
+        .iter()
+        .map(|e| {
+            AccessCheckRes::system(
+                "bench",
+                e,
+                vec![
+                    AccessCheckOp::new("view", true),
+                    AccessCheckOp::new("edit", true),
+                ],
+            )
+        })
+        .collect::<Vec<_>>();
+    let env = AccessCheckEnv {
+        user_id,
+        ..Default::default()
+    };
+
+    let stime = Instant::now();
+    for _ in 0..loop_num {
+        for res in check_res.iter() {
+            let _ = rbac.access.check(&env, std::slice::from_ref(res)).await;
+        }
+    }
+    println!(
+        "check: {} res x {} loop, {:?} per loop",
+        res_num,
+        loop_num,
+        stime.elapsed() / loop_num as u32
+    );
+
+    let stime = Instant::now();
+    let first = rbac.access.batch_check(&env, &check_res).await.unwrap();
+    println!("batch_check first: {:?}", stime.elapsed());
+
+    let stime = Instant::now();
+    for _ in 0..loop_num {
+        rbac.access.batch_check(&env, &check_res).await.unwrap();
+    }
+    println!(
+        "batch_check cached: {} res x {} loop, {:?} per loop",
+        res_num,
+        loop_num,
+        stime.elapsed() / loop_num as u32
+    );
+    println!(
+        "allow: {}/{}",
+        first.iter().filter(|e| e.decision.result).count(),
+        first.len()
+    );
+}
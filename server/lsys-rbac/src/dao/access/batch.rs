@@ -0,0 +1,239 @@
+//批量授权检查
+//一次计算大量资源操作的授权结果并记录审计,用于界面渲染等大量检查的场景
+//结果按访问用户缓存,参见 AccessDecisionCache
+use super::{
+    check::{
+        audit_role_data, token_scope_allow, AccessCheckEnv, AccessCheckEval, AccessCheckItem,
+        AccessCheckOp, AccessCheckRes,
+    },
+    RbacAccess,
+};
+use crate::dao::{result::RbacResult, role::AccessDecision};
+
+//单项检查结果
+pub struct AccessCheckResult<'t> {
+    pub user_id: u64,
+    pub app_id: u64,
+    pub res_type: &'t str,
+    pub res_data: &'t str,
+    pub op_key: &'t str,
+    pub decision: AccessDecision,
+}
+
+//同一访问用户不同会话环境下的决策分开缓存
+fn decision_env_key(env_data: &AccessCheckEnv<'_>) -> String {
+    let mut role_key = env_data
+        .session_role
+        .iter()
+        .map(|e| format!("{}-{}-{}", e.user_id, e.app_id, e.role_key))
+        .collect::<Vec<_>>();
+    role_key.sort();
+    role_key.dedup();
+    format!(
+        "{}-{}-{}",
+        env_data.user_id,
+        env_data.user_app_id,
+        role_key.join(",")
+    )
+}
+
+fn decision_key(env_key: &str, res: &AccessCheckRes<'_>, op: &AccessCheckOp<'_>) -> String {
+    format!(
+        "{}|{}:{}:{}:{}:{}:{}",
+        env_key,
+        res.user_id,
+        res.app_id,
+        res.res_type,
+        op.op_key,
+        if op.req_auth { 1 } else { 0 },
+        res.res_data,
+    )
+}
+
+fn decision_from_item(item: &AccessCheckItem<'_>, eval: &AccessCheckEval<'_>) -> AccessDecision {
+    AccessDecision {
+        result: item.check_result,
+        block: item.is_role_excluce,
+        exclusive: item.is_role_exclusive,
+        res_id: item.res_detail.as_ref().map(|e| e.id).unwrap_or_default(),
+        op_id: item.op_detail.as_ref().map(|e| e.id).unwrap_or_default(),
+        res_name: item
+            .res_detail
+            .as_ref()
+            .map(|e| e.res_name.to_owned())
+            .unwrap_or_default(),
+        op_name: item
+            .op_detail
+            .as_ref()
+            .map(|e| e.op_name.to_owned())
+            .unwrap_or_default(),
+        timeout: eval.timeout,
+        res_auth: item.res_auth,
+        include: item.is_role_include,
+        all: item.is_role_all,
+        role_data: audit_role_data(&item.role_data),
+    }
+}
+
+impl RbacAccess {
+    //计算授权决策,优先使用缓存,返回顺序与传入资源及操作顺序一致
+    async fn decision_eval(
+        &self,
+        env_data: &AccessCheckEnv<'_>,
+        check_res_data: &[&AccessCheckRes<'_>],
+    ) -> RbacResult<Vec<AccessDecision>> {
+        let cache = &self.role.cache_decision;
+        let env_key = decision_env_key(env_data);
+        let keys = check_res_data
+            .iter()
+            .flat_map(|res| {
+                res.op_key_data
+                    .iter()
+                    .map(|op| decision_key(&env_key, res, op))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let epoch = cache.epoch();
+        let (mut decision_data, gen) = cache.find(env_data.user_id, &keys).await;
+        let mut miss_key = vec![];
+        let miss_res = check_res_data
+            .iter()
+            .filter_map(|res| {
+                let op_key_data = res
+                    .op_key_data
+                    .iter()
+                    .filter_map(|op| {
+                        let key = decision_key(&env_key, res, op);
+                        if decision_data.contains_key(&key) {
+                            return None;
+                        }
+                        miss_key.push(key);
+                        Some(AccessCheckOp::new(op.op_key, op.req_auth))
+                    })
+                    .collect::<Vec<_>>();
+                if op_key_data.is_empty() {
+                    return None;
+                }
+                Some(AccessCheckRes {
+                    user_id: res.user_id,
+                    app_id: res.app_id,
+                    res_type: res.res_type,
+                    res_data: res.res_data,
+                    op_key_data,
+                })
+            })
+            .collect::<Vec<_>>();
+        if !miss_res.is_empty() {
+            //check_eval 按传入顺序为每个操作生成一条结果
+            let eval = self.check_eval(env_data, &miss_res).await?;
+            let save_data = miss_key
+                .into_iter()
+                .zip(eval.items.iter())
+                .map(|(key, item)| (key, decision_from_item(item, &eval)))
+                .collect::<Vec<_>>();
+            decision_data.extend(save_data.iter().cloned());
+            cache.save(env_data.user_id, epoch, gen, save_data).await;
+        }
+        let mut out = Vec::with_capacity(keys.len());
+        let mut keys = keys.into_iter();
+        for res in check_res_data {
+            for op in res.op_key_data.iter() {
+                let mut decision = keys
+                    .next()
+                    .and_then(|key| decision_data.get(&key).cloned())
+                    .unwrap_or_default();
+                //个人访问令牌只能使用授权范围内的操作,缓存结果不区分令牌
+                if decision.result
+                    && !token_scope_allow(env_data.token_scope, res.res_type, op.op_key)
+                {
+                    decision.result = false;
+                }
+                out.push(decision);
+            }
+        }
+        Ok(out)
+    }
+    /// 批量检查授权,返回结果顺序与传入资源及操作顺序一致
+    pub async fn batch_check<'t>(
+        &self,
+        env_data: &AccessCheckEnv<'_>,
+        check_res_data: &'t [AccessCheckRes<'t>],
+    ) -> RbacResult<Vec<AccessCheckResult<'t>>> {
+        if check_res_data.is_empty() {
+            return Ok(vec![]);
+        }
+        self.check_param_valid(env_data, check_res_data).await?;
+        let res_list = check_res_data.iter().collect::<Vec<_>>();
+        let decision_data = self.decision_eval(env_data, &res_list).await?;
+        let mut out = Vec::with_capacity(decision_data.len());
+        let mut decision_iter = decision_data.into_iter();
+        for res in check_res_data {
+            for op in res.op_key_data.iter() {
+                out.push(AccessCheckResult {
+                    user_id: res.user_id,
+                    app_id: res.app_id,
+                    res_type: res.res_type,
+                    res_data: res.res_data,
+                    op_key: op.op_key,
+                    decision: decision_iter.next().unwrap_or_default(),
+                });
+            }
+        }
+        let audit_data = out
+            .iter()
+            .zip(
+                check_res_data
+                    .iter()
+                    .flat_map(|res| res.op_key_data.iter().map(move |_| res)),
+            )
+            .map(|(item, res)| (res, item.op_key, &item.decision))
+            .collect::<Vec<_>>();
+        self.decision_add_audit(env_data, out.iter().all(|e| e.decision.result), &audit_data)
+            .await;
+        Ok(out)
+    }
+    /// 批量检查多组授权,任一组全部授权时返回真
+    /// 与 list_check 判断方式相同,但所有组一次计算并只记录一条审计
+    pub async fn batch_list_check(
+        &self,
+        env_data: &AccessCheckEnv<'_>,
+        check_res_list: &[&[AccessCheckRes<'_>]],
+    ) -> RbacResult<bool> {
+        if check_res_list.iter().any(|e| e.is_empty()) {
+            return Ok(true);
+        }
+        for check_res_data in check_res_list {
+            self.check_param_valid(env_data, check_res_data).await?;
+        }
+        let res_list = check_res_list
+            .iter()
+            .flat_map(|e| e.iter())
+            .collect::<Vec<_>>();
+        if res_list.is_empty() {
+            return Ok(false);
+        }
+        let decision_data = self.decision_eval(env_data, &res_list).await?;
+        let mut result = false;
+        let mut start = 0;
+        for check_res_data in check_res_list {
+            let end = start
+                + check_res_data
+                    .iter()
+                    .map(|e| e.op_key_data.len())
+                    .sum::<usize>();
+            if decision_data[start..end].iter().all(|e| e.result) {
+                result = true;
+                break;
+            }
+            start = end;
+        }
+        let audit_data = res_list
+            .iter()
+            .flat_map(|res| res.op_key_data.iter().map(move |op| (*res, op.op_key)))
+            .zip(decision_data.iter())
+            .map(|((res, op_key), decision)| (res, op_key, decision))
+            .collect::<Vec<_>>();
+        self.decision_add_audit(env_data, result, &audit_data).await;
+        Ok(result)
+    }
+}
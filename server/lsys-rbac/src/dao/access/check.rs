@@ -12,7 +12,7 @@ use crate::{
         op::OpInfo,
         res::ResInfo,
         result::{RbacError, RbacResult},
-        role::{AccessResInfo,  AccessRoleInfo, AccessRoleRow},
+        role::{AccessDecision, AccessResInfo,  AccessRoleInfo, AccessRoleRow, AccessSystemRoleCheck},
     },
     model::{
         RbacAuditDetailModel, RbacAuditDetailModelRef, RbacAuditModel, RbacAuditModelRef,
@@ -128,6 +128,7 @@ pub struct AccessCheckEnv<'t> {
     pub user_app_id: u64, //用户关联app id
     pub user_req_env: Option<&'t RequestEnv>,
     pub user_login_token: Option<&'t str>,
+    pub token_scope: Option<&'t [String]>, //个人访问令牌授权范围,格式 资源类型:操作,None 不限制
 }
 
 //授权失败结果
@@ -145,18 +146,42 @@ pub struct AccessUnauthRes {
 }
 
 //权限检测结果
-struct AccessCheckItem<'t> {
-    check_res_item: &'t AccessCheckRes<'t>,
-    op_key: &'t str,
-    res_detail: Option<&'t RbacResModel>,
-    op_detail: Option<&'t RbacOpModel>,
-    role_data: Vec<&'t AccessRoleRow>,
-    res_auth: bool,
+pub(crate) struct AccessCheckItem<'t> {
+    pub(crate) check_res_item: &'t AccessCheckRes<'t>,
+    pub(crate) op_key: &'t str,
+    pub(crate) res_detail: Option<RbacResModel>,
+    pub(crate) op_detail: Option<RbacOpModel>,
+    pub(crate) role_data: Vec<AccessRoleRow>,
+    pub(crate) res_auth: bool,
     // is_root: bool,
-    is_role_excluce: bool,
-    is_role_include: bool,
-    is_role_all: bool,
-    check_result: bool,
+    pub(crate) is_role_excluce: bool,
+    pub(crate) is_role_include: bool,
+    pub(crate) is_role_all: bool,
+    pub(crate) is_role_exclusive: bool, //因互斥角色未授权
+    pub(crate) check_result: bool,
+}
+
+//授权计算结果
+pub(crate) struct AccessCheckEval<'t> {
+    pub(crate) items: Vec<AccessCheckItem<'t>>,
+    pub(crate) exclusive_role: Vec<u64>, //因互斥未授予权限的角色
+    pub(crate) timeout: u64,             //结果中最早失效的角色授权时间,0 为不失效
+}
+
+fn access_row_owned(rows: &[&AccessRoleRow]) -> Vec<AccessRoleRow> {
+    rows.iter().map(|e| (*e).to_owned()).collect()
+}
+
+fn in_token_scope(token_scope: Option<&[String]>, check_item: &AccessCheckItem<'_>) -> bool {
+    token_scope_allow(token_scope, check_item.check_res_item.res_type, check_item.op_key)
+}
+
+//个人访问令牌的授权范围,格式为 资源类型:操作
+pub(crate) fn token_scope_allow(token_scope: Option<&[String]>, res_type: &str, op_key: &str) -> bool {
+    match token_scope {
+        Some(scope) => scope.iter().any(|e| e.split_once(':') == Some((res_type, op_key))),
+        None => true,
+    }
 }
 
 // enum AccessRoleList {
@@ -165,7 +190,7 @@ struct AccessCheckItem<'t> {
 // }
 
 impl RbacAccess {
-    async fn check_param_valid(&self,
+    pub(crate) async fn check_param_valid(&self,
          env_data: &AccessCheckEnv<'_>,
         //待检测资源需要操作的列表
         check_res_data: &[AccessCheckRes<'_>],
@@ -205,6 +230,71 @@ impl RbacAccess {
             return Ok(());
         }
         self.check_param_valid(env_data,check_res_data).await?;
+        let AccessCheckEval {
+            items: mut check_data,
+            exclusive_role,
+            ..
+        } = self.check_eval(env_data, check_res_data).await?;
+        //个人访问令牌只能使用授权范围内的操作
+        for check_item in check_data.iter_mut() {
+            if check_item.check_result && !in_token_scope(env_data.token_scope, check_item) {
+                check_item.check_result = false;
+            }
+        }
+        let bad_item=check_data.iter().flat_map(|check_item|{
+            if check_item.check_result {
+                None                
+            }else{
+                Some(AccessUnauthRes{
+                    user_id: check_item.check_res_item.user_id,
+                    res_type:   check_item.check_res_item.res_type.to_owned(),
+                    res_data:  check_item.check_res_item.res_data.to_owned(),
+                    op_key: check_item.op_key.to_owned(),
+                    res_id: check_item.res_detail.as_ref().map(|e|e.id),
+                    op_id: check_item.op_detail.as_ref().map(|e|e.id),
+                    res_name:check_item.res_detail.as_ref().map(|e|e.res_name.to_owned()),
+                    op_name: check_item.op_detail.as_ref().map(|e|e.op_name.to_owned()),
+                    msg:if check_item.is_role_exclusive {
+                        fluent_message!( "rbac-access-exclusive",{//互斥角色
+                            "res_name":check_item.res_detail.as_ref().map(|e|e.res_name.as_str()).unwrap_or(check_item.check_res_item.res_type),
+                            "op_name":check_item.op_detail.as_ref().map(|e|e.op_name.as_str()).unwrap_or(check_item.op_key),
+                            "user_id":check_item.check_res_item.user_id,
+                            "role_id":exclusive_role.iter().map(|e|e.to_string()).collect::<Vec<_>>().join(","),
+                        })
+                    }else if check_item.is_role_excluce {
+                        fluent_message!( "rbac-access-block",{//主动禁止
+                            "res_name":check_item.res_detail.as_ref().map(|e|e.res_name.as_str()).unwrap_or(check_item.check_res_item.res_type),
+                            "op_name":check_item.op_detail.as_ref().map(|e|e.op_name.as_str()).unwrap_or(check_item.op_key),
+                            "user_id":check_item.check_res_item.user_id,
+                        })
+                        }else if !in_token_scope(env_data.token_scope, check_item) {
+                        fluent_message!( "rbac-access-scope",{//超出令牌授权范围
+                            "res_name":check_item.res_detail.as_ref().map(|e|e.res_name.as_str()).unwrap_or(check_item.check_res_item.res_type),
+                            "op_name":check_item.op_detail.as_ref().map(|e|e.op_name.as_str()).unwrap_or(check_item.op_key),
+                            "user_id":check_item.check_res_item.user_id,
+                        })
+                        }else{ fluent_message!( "rbac-access-unauth",{//未授权
+                            "res_name":check_item.res_detail.as_ref().map(|e|e.res_name.as_str()).unwrap_or(check_item.check_res_item.res_type),
+                            "op_name":check_item.op_detail.as_ref().map(|e|e.op_name.as_str()).unwrap_or(check_item.op_key),
+                            "user_id":check_item.check_res_item.user_id,
+                        })},
+                })
+            }
+        }).collect::<Vec<_>>();
+        self.check_add_audit(env_data, bad_item.is_empty(), &check_data)
+            .await;
+        if !bad_item.is_empty() {
+            return Err(RbacError::Check(bad_item));
+        }
+        Ok(())
+    }
+    //计算待检测资源的授权结果,不记录审计
+    pub(crate) async fn check_eval<'t>(
+        &self,
+        env_data: &AccessCheckEnv<'_>,
+        check_res_data: &'t [AccessCheckRes<'t>],
+    ) -> RbacResult<AccessCheckEval<'t>> {
+        let mut check_data = vec![];
         //把check_res_data转为数据库记录
         //user_id+res_type+res_data => yaf_rbac_res
         //user_id+op_key_data => yaf_rbac_op
@@ -232,9 +322,8 @@ impl RbacAccess {
             })
             .collect::<Vec<_>>();
         let op_list = self.op.cache().find_vec_by_info(&op_info).await?;
-        let mut check_data = vec![];
 
-        let access_role_list={
+        let mut access_role_list={
             let mut tmp_check = HashMap::new();
             for item in check_res_data {
                 tmp_check
@@ -296,7 +385,13 @@ impl RbacAccess {
                     .find_access_row(env_data.user_id, res_check, &role_check)
                     .await?
         };
-        let sys_role_all =  access_role_list.get_system_all_role();
+        let timeout = access_role_list.min_timeout();
+        //动态互斥:同时生效的互斥角色均不授予权限
+        let exclusive_role = self
+            .role
+            .exclusive_conflict_role(&access_role_list.grant_role_ids())
+            .await?;
+        let exclusive_list = access_role_list.take_grant_role(&exclusive_role);
         for res_item in check_res_data {
             let res_detail = res_list
                 .iter()
@@ -320,71 +415,32 @@ impl RbacAccess {
                     })
                     .and_then(|e| e.1.as_ref());
 
-                let sys_excluce =
-                    if let (Some(res_val), Some(op_val)) = (res_detail, op_detail) {
-                        access_role_list.get_system_exclude_role(res_val.id, op_val.id)
-                    } else {
-                        vec![]
-                    };
-                //系统屏蔽
-                if !sys_excluce.is_empty() {
-                    //bad
-                    check_data.push(AccessCheckItem {
-                        check_res_item: res_item,
-                        op_key: op_item.op_key,
-                        role_data: sys_excluce,
-                        res_detail,
-                        op_detail,
-                        res_auth: false,
-                        // is_root: false,
-                        is_role_all: false,
-                        is_role_excluce: true,
-                        is_role_include: false,
-                        check_result: false,
-                    });
-                    continue;
-                }
-                //系统允许全部
-                if !sys_role_all.is_empty() {
-                    //pass
-                    check_data.push(AccessCheckItem {
-                        check_res_item: res_item,
-                        op_key: op_item.op_key,
-                        role_data: sys_role_all.clone(),
-                        res_detail,
-                        op_detail,
-                        res_auth: false,
-                        is_role_all: true,
-                        is_role_excluce: false,
-                        is_role_include: false,
-                        check_result: true,
-                    });
-                    continue;
-                }
-                let sys_include =
-                    if let (Some(res_val), Some(op_val)) = (res_detail, op_detail) {
-                        access_role_list.get_system_exclude_role(res_val.id, op_val.id)
-                    } else {
-                        vec![]
+                let res_op = res_detail
+                    .zip(op_detail)
+                    .map(|(res_val, op_val)| (res_val.id, op_val.id));
+                //系统角色: 屏蔽 > 允许全部 > 允许部分
+                if let Some(sys_check) = access_role_list.system_role_check(res_op) {
+                    let (sys_role, is_role_all, is_role_excluce, is_role_include) = match sys_check
+                    {
+                        AccessSystemRoleCheck::Exclude(row) => (row, false, true, false),
+                        AccessSystemRoleCheck::All(row) => (row, true, false, false),
+                        AccessSystemRoleCheck::Include(row) => (row, false, false, true),
                     };
-                //系统允许部分
-                if !sys_include.is_empty() {
-                    //pass
                     check_data.push(AccessCheckItem {
                         check_res_item: res_item,
                         op_key: op_item.op_key,
-                        role_data: sys_include,
-                        res_detail,
-                        op_detail,
+                        role_data: access_row_owned(&sys_role),
+                        res_detail: res_detail.cloned(),
+                        op_detail: op_detail.cloned(),
                         res_auth: false,
-                        is_role_all: false,
-                        is_role_excluce: false,
-                        is_role_include: true,
-                        check_result: true,
+                        is_role_all,
+                        is_role_excluce,
+                        is_role_include,
+                        is_role_exclusive: false,
+                        check_result: !is_role_excluce,
                     });
                     continue;
                 }
-                      
                 //用户屏蔽
                 let user_excluce = if let (Some(res_val), Some(op_val)) =
                     (res_detail, op_detail)
@@ -399,13 +455,14 @@ impl RbacAccess {
                     check_data.push(AccessCheckItem {
                         check_res_item: res_item,
                         op_key: op_item.op_key,
-                        role_data: user_excluce,
-                        res_detail,
-                        op_detail,
+                        role_data: access_row_owned(&user_excluce),
+                        res_detail: res_detail.cloned(),
+                        op_detail: op_detail.cloned(),
                         res_auth: false,
                         is_role_all: false,
                         is_role_excluce: true,
                         is_role_include: false,
+                        is_role_exclusive: false,
                         check_result: false,
                     });
 
@@ -422,15 +479,15 @@ impl RbacAccess {
                         op_key: op_item.op_key,
                         role_data: user_all
                             .get()
-                            .map(|e| e.to_owned())
-                            .to_owned()
+                            .map(|e| access_row_owned(e))
                             .unwrap_or_default(),
-                        res_detail,
-                        op_detail,
+                        res_detail: res_detail.cloned(),
+                        op_detail: op_detail.cloned(),
                         res_auth: false,
                         is_role_all: true,
                         is_role_excluce: false,
                         is_role_include: false,
+                        is_role_exclusive: false,
                         check_result: true,
                     });
                     continue;
@@ -449,13 +506,14 @@ impl RbacAccess {
                     check_data.push(AccessCheckItem {
                         check_res_item: res_item,
                         op_key: op_item.op_key,
-                        role_data: user_include,
-                        res_detail,
-                        op_detail,
+                        role_data: access_row_owned(&user_include),
+                        res_detail: res_detail.cloned(),
+                        op_detail: op_detail.cloned(),
                         res_auth: false,
                         is_role_all: false,
                         is_role_excluce: false,
                         is_role_include: true,
+                        is_role_exclusive: false,
                         check_result: true,
                     });
                     continue;
@@ -466,66 +524,41 @@ impl RbacAccess {
                     check_data.push(AccessCheckItem {
                         check_res_item: res_item,
                         op_key: op_item.op_key,
-                        role_data: user_include,
-                        res_detail,
-                        op_detail,
+                        role_data: access_row_owned(&user_include),
+                        res_detail: res_detail.cloned(),
+                        op_detail: op_detail.cloned(),
                         res_auth: true,
                         is_role_all: false,
                         is_role_excluce: false,
                         is_role_include: true,
+                        is_role_exclusive: false,
                         check_result: true,
                     });
                     continue;
                 }
-                //无任何匹配的角色
+                //无任何匹配的角色,因互斥移除的角色可授权时标记为互斥导致
+                let is_role_exclusive = exclusive_list.is_grant(res_item.user_id, res_op);
                 //bad
                 check_data.push(AccessCheckItem {
                     check_res_item: res_item,
                     op_key: op_item.op_key,
                     role_data: vec![],
-                    res_detail,
-                    op_detail,
+                    res_detail: res_detail.cloned(),
+                    op_detail: op_detail.cloned(),
                     res_auth: false,
                     is_role_all: false,
                     is_role_excluce: false,
                     is_role_include: false,
+                    is_role_exclusive,
                     check_result: false,
                 });
             }   
         }
-        let bad_item=check_data.iter().flat_map(|check_item|{
-            if check_item.check_result {
-                None                
-            }else{
-                Some(AccessUnauthRes{
-                    user_id: check_item.check_res_item.user_id,
-                    res_type:   check_item.check_res_item.res_type.to_owned(),
-                    res_data:  check_item.check_res_item.res_data.to_owned(),
-                    op_key: check_item.op_key.to_owned(),
-                    res_id: check_item.res_detail.map(|e|e.id),
-                    op_id: check_item.op_detail.map(|e|e.id),
-                    res_name:check_item.res_detail.map(|e|e.res_name.to_owned()),
-                    op_name: check_item.op_detail.map(|e|e.op_name.to_owned()),
-                    msg:if check_item.is_role_excluce {
-                        fluent_message!( "rbac-access-block",{//主动禁止
-                            "res_name":check_item.res_detail.as_ref().map(|e|e.res_name.as_str()).unwrap_or(check_item.check_res_item.res_type),
-                            "op_name":check_item.op_detail.as_ref().map(|e|e.op_name.as_str()).unwrap_or(check_item.op_key),
-                            "user_id":check_item.check_res_item.user_id,
-                        })
-                        }else{ fluent_message!( "rbac-access-unauth",{//未授权
-                            "res_name":check_item.res_detail.as_ref().map(|e|e.res_name.as_str()).unwrap_or(check_item.check_res_item.res_type),
-                            "op_name":check_item.op_detail.as_ref().map(|e|e.op_name.as_str()).unwrap_or(check_item.op_key),
-                            "user_id":check_item.check_res_item.user_id,
-                        })},
-                })
-            }
-        }).collect::<Vec<_>>();
-        self.check_add_audit(env_data, bad_item.is_empty(), &check_data)
-            .await;
-        if !bad_item.is_empty() {
-            return Err(RbacError::Check(bad_item));
-        }
-        Ok(())
+        Ok(AccessCheckEval {
+            items: check_data,
+            exclusive_role,
+            timeout,
+        })
     }
     pub async fn list_check(
         &self,
@@ -561,6 +594,7 @@ pub(crate) struct AuditItem {
     user_ip: String,
     device_id: String,
     device_name: String,
+    personal_token: String,
     request_id: String,
     add_time: u64,
     detail: Vec<AuditItemDetail>,
@@ -583,6 +617,7 @@ struct AuditItemDetail {
     res_id: u64,
     op_id: u64,
     check_result: i8,
+    item_result: i8,
     res_auth: i8,
   //  is_root: i8,
     is_role_excluce: i8,
@@ -591,6 +626,85 @@ struct AuditItemDetail {
     role_data: String,
 }
 
+//角色临时授权申请各步骤的审计记录
+pub(crate) struct AuditRoleRequest<'t> {
+    pub op_user_id: u64,
+    pub action: &'t str,
+    pub request_id: u64,
+    pub request_user_id: u64,
+    pub role_id: u64,
+    pub role_user_id: u64,
+    pub role_name: &'t str,
+    pub role_key: &'t str,
+    pub expire_time: u64,
+}
+
+impl AuditItem {
+    pub(crate) fn role_request(
+        param: &AuditRoleRequest<'_>,
+        env_data: Option<&RequestEnv>,
+    ) -> Self {
+        let env_str = |val: Option<&String>, len: usize| -> String {
+            val.map(|e| e.chars().take(len).collect())
+                .unwrap_or_default()
+        };
+        Self {
+            user_id: param.op_user_id,
+            user_app_id: 0,
+            role_key_data: "[]".to_string(),
+            check_result: RbacAuditResult::Succ as i8,
+            token_data: "".to_string(),
+            user_ip: env_str(env_data.and_then(|e| e.request_ip.as_ref()), 40),
+            device_id: env_str(env_data.and_then(|e| e.device_id.as_ref()), 64),
+            device_name: env_str(env_data.and_then(|e| e.request_user_agent.as_ref()), 254),
+            personal_token: env_str(env_data.and_then(|e| e.personal_token.as_ref()), 64),
+            request_id: env_str(env_data.and_then(|e| e.request_id.as_ref()), 32),
+            add_time: now_time().unwrap_or_default(),
+            detail: vec![AuditItemDetail {
+                res_type: "rbac-role-request".to_string(),
+                res_data: param.request_id.to_string(),
+                res_user_id: param.role_user_id,
+                op_key: param.action.to_string(),
+                res_id: 0,
+                op_id: 0,
+                check_result: RbacAuditResult::Succ as i8,
+                item_result: RbacAuditResult::Succ as i8,
+                res_auth: 0,
+                is_role_excluce: 0,
+                is_role_include: 0,
+                is_role_all: 0,
+                role_data: json!([AuditItemRole {
+                    role_id: param.role_id,
+                    role_name: param.role_name.to_owned(),
+                    role_key: param.role_key.to_owned(),
+                    perm_id: 0,
+                    access_timeout: param.expire_time,
+                    access_user_id: param.request_user_id,
+                }])
+                .to_string(),
+            }],
+        }
+    }
+}
+
+//审计记录中的角色数据
+pub(crate) fn audit_role_data(role_data: &[AccessRoleRow]) -> String {
+    json!(role_data
+        .iter()
+        .map(|e| {
+            AuditItemRole {
+                role_id: e.role.id,
+                role_name: e.role.role_name.to_owned(),
+                role_key: e.role.role_key.to_owned(),
+                perm_id: e.perm_id,
+                access_timeout: e.access_timeout,
+                access_user_id: e.access_user_id,
+            }
+        })
+        .collect::<Vec<_>>())
+    .to_string()
+}
+
 // check audit
 impl RbacAccess {
     async fn check_add_audit(
@@ -599,6 +713,62 @@ impl RbacAccess {
         check_result: bool,
         // session_role_data: &[AccessSessionRole<'_>],
         detail_data: &[AccessCheckItem<'_>],
+    ) {
+        let detail = detail_data
+            .iter()
+            .map(|e| {
+                AuditItemDetail {
+                    res_type: e.check_res_item.res_type.to_owned(),
+                    res_data: e.check_res_item.res_data.to_owned(),
+                    res_user_id: e.check_res_item.user_id,
+                    op_key: e.op_key.to_owned(),
+                    res_id: e.res_detail.as_ref().map(|e| e.id).unwrap_or_default(),
+                    op_id: e.op_detail.as_ref().map(|e| e.id).unwrap_or_default(),
+                    check_result: if check_result { RbacAuditResult::Succ as i8 } else {  RbacAuditResult::Fail as i8 },
+                    item_result: if e.check_result { RbacAuditResult::Succ as i8 } else {  RbacAuditResult::Fail as i8 },
+                    res_auth: if e.res_auth { 1 } else { 0 },
+                  //  is_root: if e.is_root { 1 } else { 0 },
+                    is_role_excluce: if e.is_role_excluce { 1 } else { 0 },
+                    is_role_include: if e.is_role_include { 1 } else { 0 },
+                    is_role_all: if e.is_role_all { 1 } else { 0 },
+                    role_data: audit_role_data(&e.role_data),
+                }
+            })
+            .collect::<Vec<_>>();
+        self.audit_send(env_data, check_result, detail).await;
+    }
+    //批量检查的审计记录,检查结果可能来自决策缓存
+    pub(crate) async fn decision_add_audit(
+        &self,
+        env_data: &AccessCheckEnv<'_>,
+        check_result: bool,
+        detail_data: &[(&AccessCheckRes<'_>, &str, &AccessDecision)],
+    ) {
+        let detail = detail_data
+            .iter()
+            .map(|(res, op_key, e)| AuditItemDetail {
+                res_type: res.res_type.to_owned(),
+                res_data: res.res_data.to_owned(),
+                res_user_id: res.user_id,
+                op_key: op_key.to_string(),
+                res_id: e.res_id,
+                op_id: e.op_id,
+                check_result: if check_result { RbacAuditResult::Succ as i8 } else {  RbacAuditResult::Fail as i8 },
+                item_result: if e.result { RbacAuditResult::Succ as i8 } else {  RbacAuditResult::Fail as i8 },
+                res_auth: if e.res_auth { 1 } else { 0 },
+                is_role_excluce: if e.block { 1 } else { 0 },
+                is_role_include: if e.include { 1 } else { 0 },
+                is_role_all: if e.all { 1 } else { 0 },
+                role_data: e.role_data.to_owned(),
+            })
+            .collect::<Vec<_>>();
+        self.audit_send(env_data, check_result, detail).await;
+    }
+    async fn audit_send(
+        &self,
+        env_data: &AccessCheckEnv<'_>,
+        check_result: bool,
+        detail: Vec<AuditItemDetail>,
     ) {
         let role_key_data = json!(env_data
             .session_role
@@ -664,6 +834,11 @@ impl RbacAccess {
             .chars()
             .take(64)
             .collect();
+        let personal_token = env_data
+            .user_req_env
+            .as_ref()
+            .and_then(|e| e.personal_token.to_owned())
+            .unwrap_or_default();
         let send_data = AuditItem {
             user_id: env_data.user_id,
             user_app_id: env_data.user_app_id,
@@ -677,42 +852,10 @@ impl RbacAccess {
             user_ip,
             device_id,
             device_name,
+            personal_token,
             request_id,
             add_time,
-            detail: detail_data
-                .iter()
-                .map(|e| {
-                    AuditItemDetail {
-                        res_type: e.check_res_item.res_type.to_owned(),
-                        res_data: e.check_res_item.res_data.to_owned(),
-                        res_user_id: e.check_res_item.user_id,
-                        op_key: e.op_key.to_owned(),
-                        res_id: e.res_detail.map(|e| e.id).unwrap_or_default(),
-                        op_id: e.op_detail.map(|e| e.id).unwrap_or_default(),
-                        check_result: if check_result { RbacAuditResult::Succ as i8 } else {  RbacAuditResult::Fail as i8 },
-                        res_auth: if e.res_auth { 1 } else { 0 },
-                      //  is_root: if e.is_root { 1 } else { 0 },
-                        is_role_excluce: if e.is_role_excluce { 1 } else { 0 },
-                        is_role_include: if e.is_role_include { 1 } else { 0 },
-                        is_role_all: if e.is_role_all { 1 } else { 0 },
-                        role_data: json!(e
-                            .role_data
-                            .iter()
-                            .map(|e| {
-                                AuditItemRole {
-                                    role_id: e.role.id,
-                                    role_name: e.role.role_name.to_owned(),
-                                    role_key: e.role.role_key.to_owned(),
-                                    perm_id: e.perm_id,
-                                    access_timeout: e.access_timeout,
-                                    access_user_id: e.access_user_id,
-                                }
-                            })
-                            .collect::<Vec<_>>())
-                        .to_string(),
-                    }
-                })
-                .collect::<Vec<_>>(),
+            detail,
         };
         match self.audit_sender {
             Some(ref sender) => {
@@ -726,7 +869,7 @@ impl RbacAccess {
             }
         }
     }
-    async fn audit_add(db: &sqlx::Pool<sqlx::MySql>, msg: AuditItem) {
+    pub(crate) async fn audit_add(db: &sqlx::Pool<sqlx::MySql>, msg: AuditItem) {
         match db.begin().await {
             Ok(mut db_tran) => {
                 let vdata = lsys_core::model_option_set!(RbacAuditModelRef,{
@@ -738,6 +881,7 @@ impl RbacAccess {
                     user_ip:msg.user_ip,
                     device_id:msg.device_id,
                     device_name:msg.device_name,
+                    personal_token:msg.personal_token,
                     request_id:msg.request_id,
                     add_time:msg.add_time,
                 });
@@ -762,6 +906,7 @@ impl RbacAccess {
                             op_key:tmp.op_key,
                             rbac_audit_id:rbac_audit_id,
                             check_result:tmp.check_result,
+                            item_result:tmp.item_result,
                             add_time:msg.add_time,
                             res_id:tmp.res_id,
                             op_id:tmp.op_id,
@@ -0,0 +1,237 @@
+//授权审计记录保留及归档
+//超过保留天数的审计记录写入压缩的NDJSON文件后删除,未设置归档目录时直接删除
+use std::future::Future;
+use std::io::Write;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use flate2::{write::GzEncoder, Compression};
+use lsys_core::db::{ModelTableName, SqlQuote};
+use lsys_core::{
+    now_time, sql_format, AppCore, TimeOutTask, TimeOutTaskConfig, TimeOutTaskExec,
+    TimeOutTaskExecutor, TimeOutTaskNextTime,
+};
+use serde_json::json;
+use sqlx::{MySql, Pool};
+use tracing::info;
+
+use crate::model::{RbacAuditDetailModel, RbacAuditModel};
+
+use super::RbacAccess;
+
+pub struct RbacAuditArchiveConfig {
+    pub timeout_task_config: TimeOutTaskConfig,
+    pub keep_days: u64,               //审计记录保留天数,0 为不清理
+    pub archive_dir: Option<PathBuf>, //归档目录
+    pub batch_size: usize,            //单个归档文件记录数
+}
+
+impl Default for RbacAuditArchiveConfig {
+    fn default() -> Self {
+        Self {
+            timeout_task_config: TimeOutTaskConfig::new("rbac_audit_archive", 300),
+            keep_days: 0,
+            archive_dir: None,
+            batch_size: 1000,
+        }
+    }
+}
+
+pub struct RbacAuditArchiveTask {
+    db: Pool<MySql>,
+    keep_days: u64,
+    archive_dir: Option<PathBuf>,
+    batch_size: usize,
+}
+
+impl RbacAuditArchiveTask {
+    //写入归档文件,返回文件路径
+    async fn archive_write(
+        &self,
+        audit_data: &[RbacAuditModel],
+        detail_data: &[RbacAuditDetailModel],
+    ) -> Result<Option<PathBuf>, String> {
+        let archive_dir = match self.archive_dir {
+            Some(ref dir) => dir.to_owned(),
+            None => return Ok(None),
+        };
+        let mut body = String::new();
+        for audit in audit_data {
+            let detail = detail_data
+                .iter()
+                .filter(|e| e.rbac_audit_id == audit.id)
+                .collect::<Vec<_>>();
+            body += &json!({
+                "audit":audit,
+                "detail":detail,
+            })
+            .to_string();
+            body += "\n";
+        }
+        let file_path = archive_dir.join(format!(
+            "rbac-audit-{}-{}.ndjson.gz",
+            audit_data.first().map(|e| e.id).unwrap_or_default(),
+            audit_data.last().map(|e| e.id).unwrap_or_default(),
+        ));
+        tokio::task::spawn_blocking(move || {
+            std::fs::create_dir_all(&archive_dir)?;
+            let file = std::fs::File::create(&file_path)?;
+            let mut encoder = GzEncoder::new(file, Compression::default());
+            encoder.write_all(body.as_bytes())?;
+            encoder.finish()?.sync_all()?;
+            Ok::<_, std::io::Error>(file_path)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .map(Some)
+        .map_err(|e| e.to_string())
+    }
+    //归档并删除一批记录,返回处理数量
+    async fn archive_batch(&self, end_time: u64) -> Result<usize, String> {
+        let audit_data = sqlx::query_as::<_, RbacAuditModel>(&sql_format!(
+            "select * from {} where add_time<{} order by id asc limit {}",
+            RbacAuditModel::table_name(),
+            end_time,
+            self.batch_size
+        ))
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| e.to_string())?;
+        if audit_data.is_empty() {
+            return Ok(0);
+        }
+        let audit_ids = audit_data.iter().map(|e| e.id).collect::<Vec<_>>();
+        let detail_data = sqlx::query_as::<_, RbacAuditDetailModel>(&sql_format!(
+            "select * from {} where rbac_audit_id in ({})",
+            RbacAuditDetailModel::table_name(),
+            audit_ids
+        ))
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| e.to_string())?;
+        if let Some(file) = self.archive_write(&audit_data, &detail_data).await? {
+            info!(
+                "rbac audit archive {} records to {}",
+                audit_data.len(),
+                file.display()
+            );
+        }
+        let mut db = self.db.begin().await.map_err(|e| e.to_string())?;
+        for sql in [
+            sql_format!(
+                "delete from {} where rbac_audit_id in ({})",
+                RbacAuditDetailModel::table_name(),
+                audit_ids
+            ),
+            sql_format!(
+                "delete from {} where id in ({})",
+                RbacAuditModel::table_name(),
+                audit_ids
+            ),
+        ] {
+            if let Err(err) = sqlx::query(&sql).execute(&mut *db).await {
+                db.rollback().await.map_err(|e| e.to_string())?;
+                return Err(err.to_string());
+            }
+        }
+        db.commit().await.map_err(|e| e.to_string())?;
+        Ok(audit_data.len())
+    }
+}
+
+#[async_trait::async_trait]
+impl TimeOutTaskExec for RbacAuditArchiveTask {
+    async fn exec(
+        &self,
+        max_lock_time: usize,
+        mut expire_call: impl FnMut() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send,
+    ) -> Result<(), String> {
+        if self.keep_days == 0 {
+            return Ok(());
+        }
+        let ntime = now_time().unwrap_or_default();
+        let end_time = ntime.saturating_sub(self.keep_days * 86400);
+        let mut runtime = ntime;
+        loop {
+            if self.archive_batch(end_time).await? < self.batch_size {
+                break;
+            }
+            let last_now_time = now_time().unwrap_or_default();
+            if (last_now_time - runtime) > (max_lock_time as u64) {
+                return Err(format!(
+                    "rbac audit archive timeout[last run time:{},start time:{}]",
+                    last_now_time, runtime
+                ));
+            }
+            if (last_now_time - runtime) * 2 > (max_lock_time as u64) {
+                //时间小于一半延长一次有效期
+                expire_call().await;
+            }
+            runtime = last_now_time;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TimeOutTaskNextTime for RbacAuditArchiveTask {
+    async fn next_time(&self, max_lock_time: usize) -> Result<Option<u64>, String> {
+        if self.keep_days == 0 {
+            return Ok(None);
+        }
+        let ntime = now_time().unwrap_or_default();
+        let timeout_res = sqlx::query_scalar::<_, u64>(&sql_format!(
+            "select add_time from {} order by id asc limit 1",
+            RbacAuditModel::table_name(),
+        ))
+        .fetch_one(&self.db)
+        .await;
+        match timeout_res {
+            Ok(add_time) => {
+                let timeout = add_time + self.keep_days * 86400;
+                if timeout <= ntime + max_lock_time as u64 {
+                    Ok(Some(timeout))
+                } else {
+                    Ok(None)
+                }
+            }
+            Err(sqlx::Error::RowNotFound) => Ok(None),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TimeOutTaskExecutor for RbacAuditArchiveTask {
+    type Exec = Self;
+    type NextTime = Self;
+}
+
+impl RbacAccess {
+    //监听审计记录过期并归档,需在独立任务中运行
+    pub async fn listen_audit_archive(
+        &self,
+        app_core: Arc<AppCore>,
+        channel_buffer: Option<usize>,
+    ) {
+        if self.audit_keep_days == 0 {
+            info!("rbac audit keep days not set,skip archive");
+            return;
+        }
+        let task = Arc::new(RbacAuditArchiveTask {
+            db: self.db.clone(),
+            keep_days: self.audit_keep_days,
+            archive_dir: self.audit_archive_dir.clone(),
+            batch_size: self.audit_archive_batch.max(1),
+        });
+        TimeOutTask::<RbacAuditArchiveTask>::new(
+            app_core,
+            self.archive_notify.clone(),
+            task.clone(),
+            task,
+        )
+        .listen(channel_buffer)
+        .await;
+    }
+}
@@ -18,6 +18,7 @@ pub struct AuditDataParam<'t> {
     pub user_ip: Option<&'t str>,
     pub device_id: Option<&'t str>,
     pub request_id: Option<&'t str>,
+    pub personal_token: Option<bool>, //是否使用个人访问令牌请求
     pub res_data: Option<(u64, Option<u64>)>, //资源ID,资源关联操作ID
 }
 
@@ -45,6 +46,13 @@ impl RbacAccess {
 
             where_sql.push(sql_format!("  request_id = {}", val));
         }
+        if let Some(val) = audit_param.personal_token {
+            where_sql.push(if val {
+                "  personal_token != ''".to_string()
+            } else {
+                "  personal_token = ''".to_string()
+            });
+        }
         if let Some(val) = audit_param.res_data {
             where_sql.push(sql_format!(
                 "  id in (select rbac_audit_id from {} where res_id={} {})",
@@ -0,0 +1,181 @@
+//授权审计统计
+use lsys_core::db::{ModelTableName, SqlExpr, SqlQuote};
+use lsys_core::{now_time, sql_format, PageParam};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use crate::{
+    dao::result::RbacResult,
+    model::{
+        RbacAuditDetailModel, RbacAuditModel, RbacAuditResult, RbacPermModel, RbacPermStatus,
+        RbacRoleModel, RbacRoleStatus,
+    },
+};
+
+use super::RbacAccess;
+
+//统计分组
+pub enum AuditStatGroup {
+    User, //访问用户
+    Res,  //资源
+    Op,   //操作
+}
+
+//统计时间粒度
+pub enum AuditStatPeriod {
+    Hour,
+    Day,
+    Month,
+}
+
+impl AuditStatPeriod {
+    fn date_format(&self) -> &'static str {
+        match self {
+            AuditStatPeriod::Hour => "%Y-%m-%d %H",
+            AuditStatPeriod::Day => "%Y-%m-%d",
+            AuditStatPeriod::Month => "%Y-%m",
+        }
+    }
+}
+
+pub struct AuditStatParam {
+    pub user_app_id: Option<u64>,
+    pub res_user_id: Option<u64>,
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct AuditDenyStat {
+    pub date: String,
+    pub group_id: u64,     //用户ID,资源ID或操作ID
+    pub group_key: String, //资源或操作标识,按用户统计时为空
+    pub total: i64,
+}
+
+impl RbacAccess {
+    /// 按时间统计授权拒绝次数
+    pub async fn audit_deny_stat(
+        &self,
+        param: &AuditStatParam,
+        group: AuditStatGroup,
+        period: AuditStatPeriod,
+    ) -> RbacResult<Vec<AuditDenyStat>> {
+        let date_sql = format!(
+            "DATE_FORMAT(FROM_UNIXTIME(detail.add_time),'{}')",
+            period.date_format()
+        );
+        let (group_id, group_key, total) = match group {
+            AuditStatGroup::User => ("audit.user_id", "''", "COUNT(DISTINCT audit.id)"),
+            AuditStatGroup::Res => (
+                "detail.res_id",
+                "CONCAT(detail.res_type,':',detail.res_data)",
+                "COUNT(*)",
+            ),
+            AuditStatGroup::Op => ("detail.op_id", "detail.op_key", "COUNT(*)"),
+        };
+        let mut where_sql = vec![
+            sql_format!("detail.item_result={}", RbacAuditResult::Fail),
+            sql_format!("detail.add_time>={}", param.start_time),
+            sql_format!("detail.add_time<={}", param.end_time),
+        ];
+        if let Some(user_app_id) = param.user_app_id {
+            where_sql.push(sql_format!("audit.user_app_id={}", user_app_id));
+        }
+        if let Some(res_user_id) = param.res_user_id {
+            where_sql.push(sql_format!("detail.res_user_id={}", res_user_id));
+        }
+        let sql = sql_format!(
+            "select {date} as date,{group_id} as group_id,{group_key} as group_key,{total} as total
+            from {detail_table} as detail join {audit_table} as audit on detail.rbac_audit_id=audit.id
+            where {where_sql}
+            group by {date},{group_id},{group_key}
+            order by date asc,total desc",
+            date = SqlExpr(date_sql),
+            group_id = SqlExpr(group_id.to_string()),
+            group_key = SqlExpr(group_key.to_string()),
+            total = SqlExpr(total.to_string()),
+            detail_table = RbacAuditDetailModel::table_name(),
+            audit_table = RbacAuditModel::table_name(),
+            where_sql = SqlExpr(where_sql.join(" and ")),
+        );
+        Ok(sqlx::query_as::<_, AuditDenyStat>(&sql)
+            .fetch_all(&self.db)
+            .await?)
+    }
+}
+
+//已授权但未使用的权限
+pub struct AuditUnusedPermParam {
+    pub user_id: u64, //角色所属用户ID
+    pub app_id: Option<u64>,
+    pub days: u64, //多少天内未使用
+}
+
+impl RbacAccess {
+    fn audit_unused_perm_sql(&self, field: &str, param: &AuditUnusedPermParam) -> String {
+        let start_time = now_time()
+            .unwrap_or_default()
+            .saturating_sub(param.days * 86400);
+        let mut sql = sql_format!(
+            "select {} from {} as perm join {} as role on perm.role_id=role.id
+            where perm.status={} and role.status={} and role.user_id={} and perm.change_time<{}
+            and not exists (select 1 from {} as detail where detail.res_id=perm.res_id and detail.op_id=perm.op_id and detail.item_result={} and detail.add_time>={})",
+            SqlExpr(field.to_string()),
+            RbacPermModel::table_name(),
+            RbacRoleModel::table_name(),
+            RbacPermStatus::Enable,
+            RbacRoleStatus::Enable,
+            param.user_id,
+            start_time,
+            RbacAuditDetailModel::table_name(),
+            RbacAuditResult::Succ,
+            start_time,
+        );
+        if let Some(app_id) = param.app_id {
+            sql += &sql_format!(" and role.app_id={}", app_id);
+        }
+        sql
+    }
+    /// 指定天数内未被使用的权限数量
+    pub async fn audit_unused_perm_count(&self, param: &AuditUnusedPermParam) -> RbacResult<i64> {
+        let sql = self.audit_unused_perm_sql("count(*) as total", param);
+        Ok(sqlx::query_scalar::<_, i64>(&sql)
+            .fetch_one(&self.db)
+            .await?)
+    }
+    /// 指定天数内未被使用的权限,用于最小权限审查
+    pub async fn audit_unused_perm_data(
+        &self,
+        param: &AuditUnusedPermParam,
+        page: Option<&PageParam>,
+    ) -> RbacResult<Vec<(RbacPermModel, RbacRoleModel)>> {
+        let mut sql = self.audit_unused_perm_sql("perm.*", param);
+        sql += " order by perm.id desc";
+        if let Some(pdat) = page {
+            sql += format!(" limit {} offset {}", pdat.limit, pdat.offset).as_str();
+        }
+        let perm_data = sqlx::query_as::<_, RbacPermModel>(&sql)
+            .fetch_all(&self.db)
+            .await?;
+        if perm_data.is_empty() {
+            return Ok(vec![]);
+        }
+        let role_data = sqlx::query_as::<_, RbacRoleModel>(&sql_format!(
+            "select * from {} where id in ({})",
+            RbacRoleModel::table_name(),
+            perm_data.iter().map(|e| e.role_id).collect::<Vec<_>>()
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        Ok(perm_data
+            .into_iter()
+            .filter_map(|perm| {
+                role_data
+                    .iter()
+                    .find(|e| e.id == perm.role_id)
+                    .map(|role| (perm, role.to_owned()))
+            })
+            .collect())
+    }
+}
@@ -1,6 +1,9 @@
+mod batch;
 mod check;
+mod data_archive;
 mod data_audit;
 mod data_res;
+mod data_stat;
 mod data_user;
 
 pub use {
@@ -8,15 +11,19 @@ pub use {
     check::AccessUnauthRes,
 };
 
+pub use batch::AccessCheckResult;
+pub use data_archive::*;
 pub use data_audit::AuditDataParam;
 pub use data_res::*;
+pub use data_stat::*;
 pub use data_user::*;
 
 //授权检查实现
 
 use super::{op::RbacOp, res::RbacRes, role::RbacRole};
-use check::AuditItem;
-use std::sync::Arc;
+pub(crate) use check::{AuditItem, AuditRoleRequest};
+use lsys_core::TimeOutTaskNotify;
+use std::{path::PathBuf, sync::Arc};
 use tokio::sync::mpsc::Sender;
 
 pub struct RbacAccess {
@@ -25,18 +32,31 @@ pub struct RbacAccess {
     res: Arc<RbacRes>,
     op: Arc<RbacOp>,
     audit_sender: Option<Sender<AuditItem>>,
+    audit_keep_days: u64,
+    audit_archive_dir: Option<PathBuf>,
+    audit_archive_batch: usize,
+    archive_notify: Arc<TimeOutTaskNotify>,
 }
 
 impl RbacAccess {
     pub fn new(
         db: sqlx::Pool<sqlx::MySql>,
+        redis: deadpool_redis::Pool,
         role: Arc<RbacRole>,
         res: Arc<RbacRes>,
         op: Arc<RbacOp>,
         delay_audit_num: usize, //审计日志延迟插入队列数量
+        archive_config: RbacAuditArchiveConfig,
     ) -> Self {
         Self {
             audit_sender: Self::listen_audit(db.clone(), delay_audit_num),
+            audit_keep_days: archive_config.keep_days,
+            audit_archive_dir: archive_config.archive_dir,
+            audit_archive_batch: archive_config.batch_size,
+            archive_notify: Arc::new(TimeOutTaskNotify::new(
+                redis,
+                archive_config.timeout_task_config,
+            )),
             db,
             role,
             res,
@@ -8,13 +8,20 @@ use std::{str::FromStr, sync::Arc};
 
 use crate::model::{RbacOpModel, RbacResModel};
 
-use super::{op::OpCacheKey, res::ResCacheKey, role::AccessRoleRow, RbacDao};
+use super::{
+    op::OpCacheKey,
+    res::ResCacheKey,
+    role::{AccessDecisionCache, AccessRoleRow, RoleExclusiveSet},
+    RbacDao,
+};
 
 //RBAC 授权缓存
 pub enum RbacLocalCacheClear {
     OpCacheKey(Arc<LocalCache<OpCacheKey, Option<RbacOpModel>>>),
     ResCacheKey(Arc<LocalCache<ResCacheKey, Option<RbacResModel>>>),
     RbacRoleCache(Arc<LocalCache<String, Vec<AccessRoleRow>>>),
+    RbacRoleExclusiveCache(Arc<LocalCache<u64, Vec<RoleExclusiveSet>>>),
+    RbacDecisionCache(Arc<AccessDecisionCache>),
 }
 
 impl RbacLocalCacheClear {
@@ -23,6 +30,8 @@ impl RbacLocalCacheClear {
             RbacLocalCacheClear::OpCacheKey(rbac.op.cache_op_data.clone()),
             RbacLocalCacheClear::ResCacheKey(rbac.res.cache_res_data.clone()),
             RbacLocalCacheClear::RbacRoleCache(rbac.role.cache_access.clone()),
+            RbacLocalCacheClear::RbacRoleExclusiveCache(rbac.role.cache_exclusive.clone()),
+            RbacLocalCacheClear::RbacDecisionCache(rbac.role.cache_decision.clone()),
         ]
     }
 }
@@ -34,6 +43,8 @@ impl LocalCacheClearItem<'_> for RbacLocalCacheClear {
             RbacLocalCacheClear::OpCacheKey(cache) => cache.config().cache_name,
             RbacLocalCacheClear::ResCacheKey(cache) => cache.config().cache_name,
             RbacLocalCacheClear::RbacRoleCache(cache) => cache.config().cache_name,
+            RbacLocalCacheClear::RbacRoleExclusiveCache(cache) => cache.config().cache_name,
+            RbacLocalCacheClear::RbacDecisionCache(cache) => cache.config().cache_name,
         }
     }
     async fn clear_from_message(&self, msg: &str) -> Result<(), String> {
@@ -55,6 +66,12 @@ impl LocalCacheClearItem<'_> for RbacLocalCacheClear {
                     .await
             }
             RbacLocalCacheClear::RbacRoleCache(cache) => cache.del(&msg.to_owned()).await,
+            RbacLocalCacheClear::RbacRoleExclusiveCache(cache) => {
+                cache
+                    .del(&msg.parse::<u64>().map_err(|e| e.to_string())?)
+                    .await
+            }
+            RbacLocalCacheClear::RbacDecisionCache(cache) => cache.clear_from_message(msg).await?,
         };
         Ok(())
     }
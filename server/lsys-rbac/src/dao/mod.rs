@@ -26,6 +26,10 @@ pub struct RbacConfig {
     pub op_cache: LocalCacheConfig,
     pub role_relation_cache: LocalCacheConfig,
     pub role_access_cache: LocalCacheConfig,
+    pub role_exclusive_cache: LocalCacheConfig,
+    pub role_decision_cache: LocalCacheConfig, //访问用户授权决策缓存,同时缓存到REDIS
+    pub role_request: RbacRoleRequestConfig,
+    pub audit_archive: RbacAuditArchiveConfig, //审计记录保留及归档
     pub delay_audit_num: usize, //审计日志延迟插入队列最大数量,根据服务器内存大小适当设置,超高并发可加大此值提高并发响应速度
 }
 
@@ -42,6 +46,16 @@ impl RbacConfig {
                 if use_cache { None } else { Some(0) },
                 None,
             ),
+            role_exclusive_cache: LocalCacheConfig::new(
+                "rbac-role-exclusive",
+                if use_cache { None } else { Some(0) },
+                None,
+            ),
+            role_decision_cache: LocalCacheConfig::new(
+                "rbac-decision",
+                if use_cache { None } else { Some(0) },
+                None,
+            ),
             res_cache: LocalCacheConfig::new(
                 "rbac-res",
                 if use_cache { None } else { Some(0) },
@@ -52,6 +66,8 @@ impl RbacConfig {
                 if use_cache { None } else { Some(0) },
                 None,
             ),
+            role_request: RbacRoleRequestConfig::default(),
+            audit_archive: RbacAuditArchiveConfig::default(),
             delay_audit_num: 500,
         }
     }
@@ -67,14 +83,19 @@ pub struct RbacDao {
 impl RbacDao {
     pub async fn new(
         db: Pool<MySql>,
+        redis: deadpool_redis::Pool,
         remote_notify: Arc<RemoteNotify>,
         config: RbacConfig,
         logger: Arc<ChangeLoggerDao>,
     ) -> Result<RbacDao, AppCoreError> {
         let role = Arc::new(RbacRole::new(
             db.clone(),
+            redis.clone(),
             remote_notify.clone(),
             config.role_access_cache,
+            config.role_exclusive_cache,
+            config.role_decision_cache,
+            config.role_request,
             logger.clone(),
         ));
         let res = Arc::new(RbacRes::new(
@@ -94,10 +115,12 @@ impl RbacDao {
         Ok(Self {
             access: Arc::new(RbacAccess::new(
                 db,
+                redis,
                 role.clone(),
                 res.clone(),
                 op.clone(),
                 config.delay_audit_num,
+                config.audit_archive,
             )),
             res,
             role,
@@ -114,6 +137,9 @@ impl RbacDao {
             role::logger::LogRole::log_type(),
             role::logger::LogRoleUser::log_type(),
             role::logger::LogRolePerm::log_type(),
+            role::logger::LogRoleRequest::log_type(),
+            role::logger::LogRoleExclusive::log_type(),
+            role::logger::LogRoleDelegate::log_type(),
         ]
     }
 }
@@ -261,6 +261,8 @@ impl RbacOp {
             })
             .await;
 
+        //操作变动后授权决策中的操作信息需重新计算
+        self.res.role.cache_decision.clear_all().await;
         self.logger
             .add(
                 &LogOp {
@@ -325,6 +327,8 @@ impl RbacOp {
             })
             .await;
 
+        //操作变动后授权决策中的操作信息需重新计算
+        self.res.role.cache_decision.clear_all().await;
         self.logger
             .add(
                 &LogOp {
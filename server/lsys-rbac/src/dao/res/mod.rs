@@ -33,7 +33,7 @@ use sqlx::{Acquire, Transaction};
 pub struct RbacRes {
     db: Pool<MySql>,
     pub(crate) cache_res_data: Arc<LocalCache<ResCacheKey, Option<RbacResModel>>>, // res_key:res edit,res_op all
-    role: Arc<RbacRole>,
+    pub(crate) role: Arc<RbacRole>,
     logger: Arc<ChangeLoggerDao>,
 }
 
@@ -277,6 +277,8 @@ impl RbacRes {
             })
             .await;
 
+        //资源变动后授权决策中的资源信息需重新计算
+        self.role.cache_decision.clear_all().await;
         self.logger
             .add(
                 &LogRes {
@@ -339,6 +341,8 @@ impl RbacRes {
             })
             .await;
 
+        //资源变动后授权决策中的资源信息需重新计算
+        self.role.cache_decision.clear_all().await;
         self.logger
             .add(
                 &LogRes {
@@ -38,6 +38,7 @@ impl RbacRoleCache<'_>{
         role_perm:Option<&[(u64,u64)]>,//res id,op id 所有变动
         role_user:Option<&[u64]>,//user id 所有变动
     ){
+        self.clear_decision(role, role_user).await;
         //user_id res_range user_range 添加后不可修改
         if RbacRoleUserRange::Session.eq(role.user_range){
             if RbacRoleResRange::Any.eq(role.res_range){
@@ -382,7 +383,76 @@ pub struct AccessRoleData {
     role_data: Vec<AccessRoleRow>,
 }
 
+//系统角色对资源操作的检测结果
+pub(crate) enum AccessSystemRoleCheck<'t>{
+    Exclude(Vec<&'t AccessRoleRow>),//系统屏蔽
+    All(Vec<&'t AccessRoleRow>),//系统允许全部
+    Include(Vec<&'t AccessRoleRow>),//系统允许部分
+}
+
 impl AccessRoleData{
+    //当前生效的授予类角色ID,不含禁止类角色
+    pub(crate) fn grant_role_ids(&self)->Vec<u64>{
+        let ntime=now_time().unwrap_or_default();
+        let mut out=self.role_data.iter().filter(|e|{
+            !RbacRoleResRange::Exclude.eq(e.role.res_range)
+            && (e.access_timeout==0||e.access_timeout>ntime)
+        }).map(|e|e.role.id).collect::<Vec<_>>();
+        out.sort();
+        out.dedup();
+        out
+    }
+    //当前生效授权中最早的失效时间,0 为均不失效
+    pub(crate) fn min_timeout(&self)->u64{
+        let ntime=now_time().unwrap_or_default();
+        self.role_data.iter()
+            .map(|e|e.access_timeout)
+            .filter(|e|*e>ntime)
+            .min()
+            .unwrap_or(0)
+    }
+    //移除指定角色的授予数据并返回被移除的数据,禁止类角色保留
+    pub(crate) fn take_grant_role(&mut self,role_ids:&[u64])->AccessRoleData{
+        let (take,keep)=std::mem::take(&mut self.role_data).into_iter().partition(|e|{
+            !RbacRoleResRange::Exclude.eq(e.role.res_range) && role_ids.contains(&e.role.id)
+        });
+        self.role_data=keep;
+        AccessRoleData{role_data:take}
+    }
+    //是否有角色授予指定用户资源的操作,res_op 为 (资源ID,操作ID)
+    pub(crate) fn is_grant(&self,user_id:u64,res_op:Option<(u64,u64)>)->bool{
+        if !self.get_system_all_role().is_empty() || !self.get_user_all_role(user_id).is_empty() {
+            return true;
+        }
+        match res_op {
+            Some((res_id,op_id))=>{
+                !self.get_system_include_role(res_id,op_id).is_empty()
+                    || !self.get_user_include_role(user_id,res_id,op_id).is_empty()
+            }
+            None=>false
+        }
+    }
+    //按 系统屏蔽 > 系统允许全部 > 系统允许部分 检测系统角色,均未命中返回None,由用户角色决定
+    //res_op 为 (资源ID,操作ID),资源或操作不存在时为None
+    pub(crate) fn system_role_check(&self,res_op:Option<(u64,u64)>)->Option<AccessSystemRoleCheck<'_>>{
+        if let Some((res_id,op_id))=res_op{
+            let exclude=self.get_system_exclude_role(res_id,op_id);
+            if !exclude.is_empty(){
+                return Some(AccessSystemRoleCheck::Exclude(exclude));
+            }
+        }
+        let all=self.get_system_all_role();
+        if !all.is_empty(){
+            return Some(AccessSystemRoleCheck::All(all));
+        }
+        if let Some((res_id,op_id))=res_op{
+            let include=self.get_system_include_role(res_id,op_id);
+            if !include.is_empty(){
+                return Some(AccessSystemRoleCheck::Include(include));
+            }
+        }
+        None
+    }
     //获取系统禁止访问的角色列表
     pub fn get_system_exclude_role(&self,res_id:u64,op_id:u64)->Vec<&AccessRoleRow>{
         self.role_data.iter().filter(|e|{
@@ -790,4 +860,54 @@ impl<'t> RbacRoleFinder<'t>{
         }
         Ok(self.role_rows)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+fn test_role_row(role_id:u64,res_range:RbacRoleResRange,res_id:u64,op_id:u64)->AccessRoleRow{
+    AccessRoleRow{
+        role:RbacRoleModel{
+            id:role_id,
+            user_id:0,
+            app_id:0,
+            role_key:format!("role-{}",role_id),
+            user_range:RbacRoleUserRange::Custom as i8,
+            res_range:res_range as i8,
+            role_name:"".to_string(),
+            delegable:0,
+            status:RbacRoleStatus::Enable as i8,
+            change_user_id:0,
+            change_time:0,
+        },
+        op_id,
+        res_id,
+        perm_id:0,
+        access_timeout:0,
+        access_user_id:0,
+    }
+}
+
+#[test]
+fn test_system_role_check(){
+    //系统部分允许角色授予其关联的资源操作
+    let data=AccessRoleData{role_data:vec![test_role_row(1,RbacRoleResRange::Include,10,20)]};
+    assert!(matches!(
+        data.system_role_check(Some((10,20))),
+        Some(AccessSystemRoleCheck::Include(ref e)) if e.len()==1 && e[0].role.id==1
+    ));
+    assert!(data.system_role_check(Some((10,21))).is_none());
+    assert!(data.system_role_check(None).is_none());
+    //系统屏蔽优先于允许
+    let data=AccessRoleData{role_data:vec![
+        test_role_row(1,RbacRoleResRange::Include,10,20),
+        test_role_row(2,RbacRoleResRange::Any,0,0),
+        test_role_row(3,RbacRoleResRange::Exclude,10,20),
+    ]};
+    assert!(matches!(
+        data.system_role_check(Some((10,20))),
+        Some(AccessSystemRoleCheck::Exclude(ref e)) if e[0].role.id==3
+    ));
+    assert!(matches!(
+        data.system_role_check(Some((10,21))),
+        Some(AccessSystemRoleCheck::All(ref e)) if e[0].role.id==2
+    ));
+}
@@ -374,6 +374,23 @@ impl RbacRole {
             .fetch_all(&self.db)
             .await?)
     }
+    //指定用户是否在角色中
+    pub async fn role_user_exist(&self, role: &RbacRoleModel, user_id: u64) -> RbacResult<bool> {
+        if !RbacRoleUserRange::Custom.eq(role.user_range) {
+            return Ok(false);
+        }
+        let sql = sql_format!(
+            "select count(*) as total from {} where status={} and role_id={} and user_id={}",
+            RbacRoleUserModel::table_name(),
+            RbacRoleUserStatus::Enable,
+            role.id,
+            user_id
+        );
+        let total = sqlx::query_scalar::<_, i64>(&sql)
+            .fetch_one(&self.db)
+            .await?;
+        Ok(total > 0)
+    }
     pub async fn role_user_count(&self, role: &RbacRoleModel, all: bool) -> RbacResult<i64> {
         if !RbacRoleUserRange::Custom.eq(role.user_range) {
             return Ok(0);
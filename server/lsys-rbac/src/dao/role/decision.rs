@@ -0,0 +1,345 @@
+//授权决策缓存
+//按访问用户缓存已计算的授权结果,本地缓存未命中时查询REDIS
+//角色用户变动时仅清理相关访问用户,会话角色、互斥约束、资源及操作变动时整体失效
+//REDIS KEY 含整体失效版本号,整体失效后旧版本的KEY不再写入,到期后自动清理
+use std::collections::HashMap;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use lsys_core::cache::{LocalCache, LocalCacheConfig, LocalCacheMessage, REMOTE_NOTIFY_TYPE_CACHE};
+use lsys_core::{
+    db::{ModelTableName, SqlQuote},
+    sql_format,
+};
+use lsys_core::{now_time, IntoFluentMessage, LocalExecType, RemoteNotify};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use tracing::{error, warn};
+
+use super::cache::RbacRoleCache;
+use crate::model::{RbacRoleModel, RbacRoleUserModel, RbacRoleUserRange};
+
+const DECISION_FLUSH_ALL: &str = "*";
+
+//单项授权决策
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AccessDecision {
+    pub result: bool,    //是否授权
+    pub block: bool,     //是否被禁止类角色禁止
+    pub exclusive: bool, //是否因互斥角色未授权
+    pub res_id: u64,
+    pub op_id: u64,
+    pub res_name: String,
+    pub op_name: String,
+    pub timeout: u64, //决策失效时间,0 为不失效
+    //以下用于审计记录
+    pub res_auth: bool,    //资源是否需要授权
+    pub include: bool,     //是否由指定资源角色授权
+    pub all: bool,         //是否由全部资源角色授权
+    pub role_data: String, //相关角色数据
+}
+
+impl AccessDecision {
+    fn is_valid(&self, ntime: u64) -> bool {
+        self.timeout == 0 || self.timeout > ntime
+    }
+}
+
+//访问用户的授权决策集合
+#[derive(Clone, Debug, Default)]
+pub struct AccessDecisionSet {
+    flush: u64,
+    data: HashMap<String, AccessDecision>,
+}
+
+pub struct AccessDecisionCache {
+    cache: LocalCache<u64, AccessDecisionSet>,
+    redis: deadpool_redis::Pool,
+    remote_notify: Arc<RemoteNotify>,
+    flush: AtomicU64, //本机整体失效次数
+    epoch: AtomicU64, //本机任意失效次数,计算期间发生失效时不回写
+}
+
+impl AccessDecisionCache {
+    pub fn new(
+        redis: deadpool_redis::Pool,
+        remote_notify: Arc<RemoteNotify>,
+        config: LocalCacheConfig,
+    ) -> Self {
+        Self {
+            cache: LocalCache::new(remote_notify.clone(), config),
+            redis,
+            remote_notify,
+            flush: AtomicU64::new(0),
+            epoch: AtomicU64::new(0),
+        }
+    }
+    pub fn config(&self) -> &LocalCacheConfig {
+        self.cache.config()
+    }
+    fn is_enable(&self) -> bool {
+        self.cache.config().cache_size > 0
+    }
+    fn redis_key(&self, gen: u64, user_id: u64) -> String {
+        format!("{}-{}-{}", self.cache.config().cache_name, gen, user_id)
+    }
+    fn redis_gen_key(&self) -> String {
+        format!("{}-gen", self.cache.config().cache_name)
+    }
+    pub(crate) fn epoch(&self) -> u64 {
+        self.epoch.load(Ordering::SeqCst)
+    }
+    //REDIS中整体失效版本号,获取失败时返回None,此时不使用REDIS缓存
+    async fn redis_gen(&self, redis: &mut deadpool_redis::Connection) -> Option<u64> {
+        match redis.get::<_, Option<u64>>(self.redis_gen_key()).await {
+            Ok(gen) => Some(gen.unwrap_or(0)),
+            Err(err) => {
+                warn!("get rbac decision gen fail:{}", err);
+                None
+            }
+        }
+    }
+    /// 获取访问用户已缓存的决策
+    /// 返回命中的决策及REDIS中的失效版本号,版本号在回写时使用
+    pub async fn find(
+        &self,
+        user_id: u64,
+        keys: &[String],
+    ) -> (HashMap<String, AccessDecision>, Option<u64>) {
+        let mut out = HashMap::new();
+        if !self.is_enable() || keys.is_empty() {
+            return (out, None);
+        }
+        let ntime = now_time().unwrap_or_default();
+        let flush = self.flush.load(Ordering::SeqCst);
+        if let Some(set) = self.cache.get(&user_id).await {
+            if set.flush == flush {
+                for key in keys {
+                    if let Some(item) = set.data.get(key).filter(|e| e.is_valid(ntime)) {
+                        out.insert(key.to_owned(), item.to_owned());
+                    }
+                }
+            }
+        }
+        if out.len() == keys.len() {
+            return (out, None);
+        }
+        let mut redis = match self.redis.get().await {
+            Ok(redis) => redis,
+            Err(err) => {
+                warn!("get rbac decision redis fail:{}", err);
+                return (out, None);
+            }
+        };
+        let gen = match self.redis_gen(&mut redis).await {
+            Some(gen) => gen,
+            None => return (out, None),
+        };
+        let miss = keys
+            .iter()
+            .filter(|e| !out.contains_key(*e))
+            .collect::<Vec<_>>();
+        let res = redis::cmd("HMGET")
+            .arg(self.redis_key(gen, user_id))
+            .arg(&miss)
+            .query_async::<Vec<Option<String>>>(&mut *redis)
+            .await;
+        let redis_data = match res {
+            Ok(data) => data,
+            Err(err) => {
+                warn!("get rbac decision fail:{}", err);
+                return (out, Some(gen));
+            }
+        };
+        let mut redis_hit = vec![];
+        for (key, val) in miss.into_iter().zip(redis_data) {
+            let Some(val) = val else {
+                continue;
+            };
+            let Ok(item) = serde_json::from_str::<AccessDecision>(&val) else {
+                continue;
+            };
+            if !item.is_valid(ntime) {
+                continue;
+            }
+            redis_hit.push((key.to_owned(), item.clone()));
+            out.insert(key.to_owned(), item);
+        }
+        if !redis_hit.is_empty() {
+            self.local_save(user_id, flush, redis_hit).await;
+        }
+        (out, Some(gen))
+    }
+    async fn local_save(&self, user_id: u64, flush: u64, data: Vec<(String, AccessDecision)>) {
+        let mut set = match self.cache.get(&user_id).await {
+            Some(set) if set.flush == flush => set,
+            _ => AccessDecisionSet {
+                flush,
+                data: HashMap::new(),
+            },
+        };
+        set.data.extend(data);
+        self.cache.set(user_id, set, 0).await;
+    }
+    /// 回写新计算的决策
+    /// epoch 为计算前通过 epoch() 获取的值,计算期间有失效发生时放弃回写
+    pub async fn save(
+        &self,
+        user_id: u64,
+        epoch: u64,
+        gen: Option<u64>,
+        data: Vec<(String, AccessDecision)>,
+    ) {
+        if !self.is_enable() || data.is_empty() || self.epoch() != epoch {
+            return;
+        }
+        let flush = self.flush.load(Ordering::SeqCst);
+        if let Some(gen) = gen {
+            let fields = data
+                .iter()
+                .filter_map(|(k, v)| serde_json::to_string(v).ok().map(|v| (k.as_str(), v)))
+                .collect::<Vec<_>>();
+            match self.redis.get().await {
+                Ok(mut redis) => {
+                    let key = self.redis_key(gen, user_id);
+                    let res = redis::pipe()
+                        .cmd("HSET")
+                        .arg(&key)
+                        .arg(&fields)
+                        .ignore()
+                        .expire(&key, self.cache.config().cache_time as i64)
+                        .ignore()
+                        .query_async::<()>(&mut *redis)
+                        .await;
+                    if let Err(err) = res {
+                        warn!("save rbac decision fail:{}", err);
+                    }
+                }
+                Err(err) => {
+                    warn!("get rbac decision redis fail:{}", err);
+                }
+            }
+        }
+        self.local_save(user_id, flush, data).await;
+    }
+    /// 清理指定访问用户的决策
+    pub async fn clear_user(&self, user_ids: &[u64]) {
+        if !self.is_enable() || user_ids.is_empty() {
+            return;
+        }
+        self.epoch.fetch_add(1, Ordering::SeqCst);
+        match self.redis.get().await {
+            Ok(mut redis) => {
+                //旧版本的KEY不会再被读取,仅需清理当前版本
+                if let Some(gen) = self.redis_gen(&mut redis).await {
+                    let keys = user_ids
+                        .iter()
+                        .map(|e| self.redis_key(gen, *e))
+                        .collect::<Vec<_>>();
+                    if let Err(err) = redis.del::<_, ()>(keys).await {
+                        warn!("clear rbac decision fail:{}", err);
+                    }
+                }
+            }
+            Err(err) => {
+                warn!("get rbac decision redis fail:{}", err);
+            }
+        }
+        for user_id in user_ids {
+            self.cache.clear(user_id).await;
+        }
+    }
+    /// 全部决策失效
+    pub async fn clear_all(&self) {
+        if !self.is_enable() {
+            return;
+        }
+        self.local_flush();
+        match self.redis.get().await {
+            Ok(mut redis) => {
+                if let Err(err) = redis.incr::<_, _, ()>(self.redis_gen_key(), 1).await {
+                    warn!("flush rbac decision fail:{}", err);
+                }
+            }
+            Err(err) => {
+                warn!("get rbac decision redis fail:{}", err);
+            }
+        }
+        let send_msg = LocalCacheMessage::new(self.cache.config().cache_name, DECISION_FLUSH_ALL);
+        if let Err(err) = self
+            .remote_notify
+            .call(
+                REMOTE_NOTIFY_TYPE_CACHE,
+                send_msg,
+                None,
+                LocalExecType::RemoteExec,
+                None,
+            )
+            .await
+        {
+            warn!(
+                "notify flush rbac decision error:{}",
+                err.to_fluent_message().default_format()
+            );
+        }
+    }
+    fn local_flush(&self) {
+        self.epoch.fetch_add(1, Ordering::SeqCst);
+        self.flush.fetch_add(1, Ordering::SeqCst);
+    }
+    //其他节点通知清理
+    pub(crate) async fn clear_from_message(&self, msg: &str) -> Result<(), String> {
+        if msg == DECISION_FLUSH_ALL {
+            self.local_flush();
+            return Ok(());
+        }
+        let user_id = msg.parse::<u64>().map_err(|e| e.to_string())?;
+        self.epoch.fetch_add(1, Ordering::SeqCst);
+        self.cache.del(&user_id).await;
+        Ok(())
+    }
+}
+
+impl RbacRoleCache<'_> {
+    //角色授权或用户变动时清理决策缓存
+    pub(crate) async fn clear_decision(&self, role: &RbacRoleModel, role_user: Option<&[u64]>) {
+        let cache = &self.role.cache_decision;
+        if !RbacRoleUserRange::Custom.eq(role.user_range) {
+            //会话角色无法确定影响的访问用户
+            cache.clear_all().await;
+            return;
+        }
+        if let Some(user_ids) = role_user.filter(|e| !e.is_empty()) {
+            cache.clear_user(user_ids).await;
+            return;
+        }
+        let mut start_id = 0;
+        loop {
+            match sqlx::query_as::<_, (u64, u64)>(&sql_format!(
+                "select id,user_id from {} where role_id={} and id>{} order by id asc limit 100 ",
+                RbacRoleUserModel::table_name(),
+                role.id,
+                start_id
+            ))
+            .fetch_all(&self.role.db)
+            .await
+            {
+                Ok(data) => {
+                    if data.is_empty() {
+                        break;
+                    }
+                    start_id = data.last().map(|e| e.0).unwrap_or(start_id);
+                    let user_ids = data.into_iter().map(|e| e.1).collect::<Vec<_>>();
+                    cache.clear_user(&user_ids).await;
+                }
+                Err(err) => {
+                    error!("clear rbac decision fail on role user:{err}");
+                    cache.clear_all().await;
+                    break;
+                }
+            }
+        }
+    }
+}
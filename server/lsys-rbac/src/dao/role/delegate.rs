@@ -0,0 +1,379 @@
+//委托管理员
+//被委托用户只能分配自身拥有或允许委托的角色,且只能分配给范围内的用户
+//限制在角色用户增删时执行,未被委托的用户不受此限制(由调用方的权限检查决定)
+
+use lsys_core::db::{Insert, ModelTableName, SqlQuote, Update, WhereOption};
+use lsys_core::{
+    db_option_executor, fluent_message, impl_dao_fetch_one_by_one, model_option_set, now_time,
+    sql_format, PageParam, RequestEnv,
+};
+use sqlx::Transaction;
+
+use super::{logger::LogRoleDelegate, RbacRole};
+use crate::{
+    dao::result::{RbacError, RbacResult},
+    model::{
+        RbacRoleDelegateModel, RbacRoleDelegateModelRef, RbacRoleDelegateStatus, RbacRoleModel,
+        RbacRoleModelRef, RbacRoleUserModel, RbacRoleUserRange, RbacRoleUserStatus,
+    },
+};
+
+pub struct RoleDelegateAddData {
+    pub user_id: u64,
+    pub app_id: u64,
+    pub delegate_user_id: u64,
+    pub scope_role_id: u64,
+}
+
+impl RbacRole {
+    impl_dao_fetch_one_by_one!(
+        db,
+        find_delegate_by_id,
+        u64,
+        RbacRoleDelegateModel,
+        RbacResult<RbacRoleDelegateModel>,
+        id,
+        "id={id} and status = {status}",
+        status = RbacRoleDelegateStatus::Enable
+    );
+    /// 查找指定用户在某个用户或APP下的委托记录
+    pub async fn find_delegate(
+        &self,
+        user_id: u64,
+        app_id: u64,
+        delegate_user_id: u64,
+    ) -> RbacResult<Option<RbacRoleDelegateModel>> {
+        let res = sqlx::query_as::<_, RbacRoleDelegateModel>(&sql_format!(
+            "select * from {} where user_id={} and app_id={} and delegate_user_id={} and status={} limit 1",
+            RbacRoleDelegateModel::table_name(),
+            user_id,
+            app_id,
+            delegate_user_id,
+            RbacRoleDelegateStatus::Enable
+        ))
+        .fetch_one(&self.db)
+        .await;
+        match res {
+            Ok(row) => Ok(Some(row)),
+            Err(sqlx::Error::RowNotFound) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+    //用户是否有效拥有指定角色,返回有效的用户ID
+    async fn delegate_role_users(&self, role_id: u64, user_ids: &[u64]) -> RbacResult<Vec<u64>> {
+        if user_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        Ok(sqlx::query_scalar::<_, u64>(&sql_format!(
+            "select user_id from {} where role_id={} and user_id in ({}) and status={} and (timeout=0 or timeout>{})",
+            RbacRoleUserModel::table_name(),
+            role_id,
+            user_ids,
+            RbacRoleUserStatus::Enable,
+            now_time().unwrap_or_default()
+        ))
+        .fetch_all(&self.db)
+        .await?)
+    }
+    //校验范围角色,需为同一用户及APP下的自定义用户角色
+    async fn delegate_scope_valid(&self, param: &RoleDelegateAddData) -> RbacResult<()> {
+        if param.scope_role_id == 0 {
+            return Ok(());
+        }
+        let role = self.find_by_id(&param.scope_role_id).await?;
+        if role.user_id != param.user_id
+            || role.app_id != param.app_id
+            || !RbacRoleUserRange::Custom.eq(role.user_range)
+        {
+            return Err(RbacError::System(
+                fluent_message!("rbac-delegate-bad-scope",{
+                    "name":&role.role_name,
+                    "role_id":role.id,
+                }),
+            ));
+        }
+        Ok(())
+    }
+    /// 添加委托管理员,同一用户重复添加时更新其用户范围
+    pub async fn add_delegate(
+        &self,
+        param: &RoleDelegateAddData,
+        add_user_id: u64,
+        transaction: Option<&mut Transaction<'_, sqlx::MySql>>,
+        env_data: Option<&RequestEnv>,
+    ) -> RbacResult<u64> {
+        if param.delegate_user_id == 0 || param.delegate_user_id == param.user_id {
+            return Err(RbacError::System(fluent_message!(
+                "rbac-delegate-bad-user",
+                {
+                    "user_id":param.delegate_user_id
+                }
+            )));
+        }
+        self.delegate_scope_valid(param).await?;
+        let time = now_time().unwrap_or_default();
+        let old = self
+            .find_delegate(param.user_id, param.app_id, param.delegate_user_id)
+            .await?;
+        let db = &self.db;
+        let id = match old {
+            Some(old) => {
+                let change = model_option_set!(RbacRoleDelegateModelRef,{
+                    scope_role_id:param.scope_role_id,
+                    change_user_id:add_user_id,
+                    change_time:time,
+                });
+                db_option_executor!(
+                    db,
+                    {
+                        Update::<RbacRoleDelegateModel, _>::new(change)
+                            .execute_by_where(
+                                &WhereOption::Where(sql_format!("id={}", old.id)),
+                                db.as_executor(),
+                            )
+                            .await?;
+                    },
+                    transaction,
+                    db
+                );
+                old.id
+            }
+            None => {
+                let idata = model_option_set!(RbacRoleDelegateModelRef,{
+                    user_id:param.user_id,
+                    app_id:param.app_id,
+                    delegate_user_id:param.delegate_user_id,
+                    scope_role_id:param.scope_role_id,
+                    status:(RbacRoleDelegateStatus::Enable as i8),
+                    change_user_id:add_user_id,
+                    change_time:time,
+                });
+                db_option_executor!(
+                    db,
+                    {
+                        Insert::<RbacRoleDelegateModel, _>::new(idata)
+                            .execute(db.as_executor())
+                            .await?
+                            .last_insert_id()
+                    },
+                    transaction,
+                    db
+                )
+            }
+        };
+        self.logger
+            .add(
+                &LogRoleDelegate {
+                    action: "add",
+                    delegate_user_id: param.delegate_user_id,
+                    scope_role_id: param.scope_role_id,
+                    user_id: param.user_id,
+                },
+                Some(id),
+                Some(add_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(id)
+    }
+    /// 删除委托管理员
+    pub async fn del_delegate(
+        &self,
+        delegate: &RbacRoleDelegateModel,
+        delete_user_id: u64,
+        transaction: Option<&mut Transaction<'_, sqlx::MySql>>,
+        env_data: Option<&RequestEnv>,
+    ) -> RbacResult<()> {
+        let time = now_time().unwrap_or_default();
+        let change = model_option_set!(RbacRoleDelegateModelRef,{
+            status:(RbacRoleDelegateStatus::Delete as i8),
+            change_user_id:delete_user_id,
+            change_time:time,
+        });
+        let db = &self.db;
+        db_option_executor!(
+            db,
+            {
+                Update::<RbacRoleDelegateModel, _>::new(change)
+                    .execute_by_where(
+                        &WhereOption::Where(sql_format!("id={}", delegate.id)),
+                        db.as_executor(),
+                    )
+                    .await?;
+            },
+            transaction,
+            db
+        );
+        self.logger
+            .add(
+                &LogRoleDelegate {
+                    action: "del",
+                    delegate_user_id: delegate.delegate_user_id,
+                    scope_role_id: delegate.scope_role_id,
+                    user_id: delegate.user_id,
+                },
+                Some(delegate.id),
+                Some(delete_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(())
+    }
+    /// 设置角色是否允许委托管理员分配
+    pub async fn set_role_delegable(
+        &self,
+        role: &RbacRoleModel,
+        delegable: bool,
+        change_user_id: u64,
+        transaction: Option<&mut Transaction<'_, sqlx::MySql>>,
+        env_data: Option<&RequestEnv>,
+    ) -> RbacResult<()> {
+        let delegable: i8 = if delegable { 1 } else { 0 };
+        let time = now_time().unwrap_or_default();
+        let change = model_option_set!(RbacRoleModelRef,{
+            delegable:delegable,
+            change_user_id:change_user_id,
+            change_time:time,
+        });
+        let db = &self.db;
+        db_option_executor!(
+            db,
+            {
+                Update::<RbacRoleModel, _>::new(change)
+                    .execute_by_where(
+                        &WhereOption::Where(sql_format!("id={}", role.id)),
+                        db.as_executor(),
+                    )
+                    .await?;
+            },
+            transaction,
+            db
+        );
+        self.logger
+            .add(
+                &LogRoleDelegate {
+                    action: if delegable == 1 {
+                        "delegable"
+                    } else {
+                        "undelegable"
+                    },
+                    delegate_user_id: 0,
+                    scope_role_id: 0,
+                    user_id: role.user_id,
+                },
+                Some(role.id),
+                Some(change_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(())
+    }
+}
+
+impl RbacRole {
+    //委托管理员增删角色用户时的限制检查
+    pub(crate) async fn delegate_user_check(
+        &self,
+        role: &RbacRoleModel,
+        user_ids: &[u64],
+        op_user_id: u64,
+    ) -> RbacResult<()> {
+        if user_ids.is_empty() || op_user_id == 0 || op_user_id == role.user_id {
+            return Ok(());
+        }
+        let delegate = match self
+            .find_delegate(role.user_id, role.app_id, op_user_id)
+            .await?
+        {
+            Some(dat) => dat,
+            None => return Ok(()),
+        };
+        if user_ids.contains(&op_user_id) {
+            return Err(RbacError::System(fluent_message!("rbac-delegate-self",{
+                "name":&role.role_name,
+                "role_id":role.id,
+                "user_id":op_user_id,
+            })));
+        }
+        if role.delegable != 1
+            && self
+                .delegate_role_users(role.id, &[op_user_id])
+                .await?
+                .is_empty()
+        {
+            return Err(RbacError::System(
+                fluent_message!("rbac-delegate-role-denied",{
+                    "name":&role.role_name,
+                    "role_id":role.id,
+                    "user_id":op_user_id,
+                }),
+            ));
+        }
+        if delegate.scope_role_id > 0 {
+            let scope_user = self
+                .delegate_role_users(delegate.scope_role_id, user_ids)
+                .await?;
+            if let Some(user_id) = user_ids.iter().find(|e| !scope_user.contains(e)) {
+                return Err(RbacError::System(
+                    fluent_message!("rbac-delegate-user-out-scope",{
+                        "user_id":user_id,
+                        "role_id":delegate.scope_role_id,
+                    }),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct RoleDelegateDataParam {
+    pub user_id: Option<u64>,
+    pub app_id: Option<u64>,
+    pub delegate_user_id: Option<u64>,
+}
+
+impl RbacRole {
+    fn delegate_sql(&self, field: &str, param: &RoleDelegateDataParam) -> String {
+        let mut sql = sql_format!(
+            "select {} from {} where status={}",
+            lsys_core::db::SqlExpr(field),
+            RbacRoleDelegateModel::table_name(),
+            RbacRoleDelegateStatus::Enable
+        );
+        if let Some(user_id) = param.user_id {
+            sql += &sql_format!(" and user_id={}", user_id);
+        }
+        if let Some(app_id) = param.app_id {
+            sql += &sql_format!(" and app_id={}", app_id);
+        }
+        if let Some(delegate_user_id) = param.delegate_user_id {
+            sql += &sql_format!(" and delegate_user_id={}", delegate_user_id);
+        }
+        sql
+    }
+    /// 获取委托管理员数量
+    pub async fn delegate_count(&self, param: &RoleDelegateDataParam) -> RbacResult<i64> {
+        let sql = self.delegate_sql("count(*) as total", param);
+        Ok(sqlx::query_scalar::<_, i64>(&sql)
+            .fetch_one(&self.db)
+            .await?)
+    }
+    /// 获取委托管理员列表
+    pub async fn delegate_data(
+        &self,
+        param: &RoleDelegateDataParam,
+        page: Option<&PageParam>,
+    ) -> RbacResult<Vec<RbacRoleDelegateModel>> {
+        let mut sql = self.delegate_sql("*", param);
+        sql += " order by id desc";
+        if let Some(pdat) = page {
+            sql += format!(" limit {} offset {}", pdat.limit, pdat.offset).as_str();
+        }
+        Ok(sqlx::query_as::<_, RbacRoleDelegateModel>(&sql)
+            .fetch_all(&self.db)
+            .await?)
+    }
+}
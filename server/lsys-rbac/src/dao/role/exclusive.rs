@@ -0,0 +1,743 @@
+//角色互斥约束(职责分离)
+//静态:同一用户不能同时被加入同一约束下多个角色
+//动态:授权检查时,同时生效的同一约束下多个角色均不授予权限
+
+use std::collections::{HashMap, HashSet};
+
+use lsys_core::db::{Insert, ModelTableName, SqlQuote, Update, WhereOption};
+use lsys_core::{
+    fluent_message, impl_dao_fetch_one_by_one, model_option_set, now_time, sql_format, valid_key,
+    PageParam, RequestEnv, ValidParam, ValidParamCheck, ValidPattern, ValidStrlen,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{Acquire, Transaction};
+
+use super::{logger::LogRoleExclusive, RbacRole};
+use crate::{
+    dao::result::{RbacError, RbacResult},
+    model::{
+        RbacRoleExclusiveItemModel, RbacRoleExclusiveItemModelRef, RbacRoleExclusiveModel,
+        RbacRoleExclusiveModelRef, RbacRoleExclusiveStatus, RbacRoleModel, RbacRoleStatus,
+        RbacRoleUserModel, RbacRoleUserStatus,
+    },
+};
+
+//互斥约束及其包含角色
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RoleExclusiveSet {
+    pub exclusive_id: u64,
+    pub name: String,
+    pub role_ids: Vec<u64>,
+}
+
+pub struct RoleExclusiveAddData<'t> {
+    pub user_id: u64,
+    pub app_id: u64,
+    pub name: &'t str,
+    pub role_ids: &'t [u64],
+}
+
+impl RbacRole {
+    impl_dao_fetch_one_by_one!(
+        db,
+        find_exclusive_by_id,
+        u64,
+        RbacRoleExclusiveModel,
+        RbacResult<RbacRoleExclusiveModel>,
+        id,
+        "id={id} and status = {status}",
+        status = RbacRoleExclusiveStatus::Enable
+    );
+    //校验约束角色,需同属于约束所属用户及APP
+    async fn exclusive_param_valid(
+        &self,
+        user_id: u64,
+        app_id: u64,
+        name: &str,
+        role_ids: &[u64],
+    ) -> RbacResult<Vec<u64>> {
+        ValidParam::default()
+            .add(
+                valid_key!("exclusive_name"),
+                &name,
+                &ValidParamCheck::default()
+                    .add_rule(ValidPattern::NotFormat)
+                    .add_rule(ValidStrlen::range(1, 32)),
+            )
+            .check()?;
+        let mut role_ids = role_ids.to_vec();
+        role_ids.sort();
+        role_ids.dedup();
+        if role_ids.len() < 2 {
+            return Err(RbacError::System(fluent_message!(
+                "rbac-role-exclusive-min-role"
+            )));
+        }
+        let role_data = self.find_by_ids(&role_ids).await?;
+        for rid in role_ids.iter() {
+            match role_data.get(rid) {
+                Some(role) => {
+                    if role.user_id != user_id || role.app_id != app_id {
+                        return Err(RbacError::System(
+                            fluent_message!("rbac-role-exclusive-bad-role",{
+                                "name":&role.role_name,
+                                "role_id":role.id,
+                            }),
+                        ));
+                    }
+                }
+                None => {
+                    return Err(RbacError::System(
+                        fluent_message!("rbac-role-exclusive-bad-role",{
+                            "name":"",
+                            "role_id":rid,
+                        }),
+                    ));
+                }
+            }
+        }
+        Ok(role_ids)
+    }
+    async fn exclusive_add_item(
+        &self,
+        exclusive_id: u64,
+        role_ids: &[u64],
+        change_user_id: u64,
+        time: u64,
+        db: &mut Transaction<'_, sqlx::MySql>,
+    ) -> RbacResult<()> {
+        if role_ids.is_empty() {
+            return Ok(());
+        }
+        let mut idata = Vec::with_capacity(role_ids.len());
+        for role_id in role_ids {
+            idata.push(model_option_set!(RbacRoleExclusiveItemModelRef,{
+                exclusive_id:exclusive_id,
+                role_id:role_id,
+                status:(RbacRoleExclusiveStatus::Enable as i8),
+                change_user_id:change_user_id,
+                change_time:time,
+            }));
+        }
+        Insert::<RbacRoleExclusiveItemModel, _>::new_vec(idata)
+            .execute(&mut **db)
+            .await?;
+        Ok(())
+    }
+    async fn exclusive_clear_cache(&self, role_ids: &[u64]) {
+        for role_id in role_ids {
+            self.cache_exclusive.clear(role_id).await;
+        }
+        //已缓存的授权数据未包含互斥信息,无需清理
+        //授权决策包含互斥结果,整体失效
+        self.cache_decision.clear_all().await;
+    }
+    //添加角色互斥约束
+    pub async fn add_exclusive(
+        &self,
+        param: &RoleExclusiveAddData<'_>,
+        add_user_id: u64,
+        transaction: Option<&mut Transaction<'_, sqlx::MySql>>,
+        env_data: Option<&RequestEnv>,
+    ) -> RbacResult<u64> {
+        let role_ids = self
+            .exclusive_param_valid(param.user_id, param.app_id, param.name, param.role_ids)
+            .await?;
+        let time = now_time().unwrap_or_default();
+        let name = param.name.to_owned();
+        let idata = model_option_set!(RbacRoleExclusiveModelRef,{
+            user_id:param.user_id,
+            app_id:param.app_id,
+            name:name,
+            status:(RbacRoleExclusiveStatus::Enable as i8),
+            change_user_id:add_user_id,
+            change_time:time,
+        });
+        let mut db = match transaction {
+            Some(pb) => pb.begin().await?,
+            None => self.db.begin().await?,
+        };
+        let id = match Insert::<RbacRoleExclusiveModel, _>::new(idata)
+            .execute(&mut *db)
+            .await
+        {
+            Ok(res) => res.last_insert_id(),
+            Err(err) => {
+                db.rollback().await?;
+                return Err(err.into());
+            }
+        };
+        if let Err(err) = self
+            .exclusive_add_item(id, &role_ids, add_user_id, time, &mut db)
+            .await
+        {
+            db.rollback().await?;
+            return Err(err);
+        }
+        db.commit().await?;
+        self.exclusive_clear_cache(&role_ids).await;
+        self.logger
+            .add(
+                &LogRoleExclusive {
+                    action: "add",
+                    name: &name,
+                    role_ids: &role_ids,
+                    user_id: param.user_id,
+                },
+                Some(id),
+                Some(add_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(id)
+    }
+    //修改角色互斥约束
+    pub async fn edit_exclusive(
+        &self,
+        exclusive: &RbacRoleExclusiveModel,
+        name: &str,
+        role_ids: &[u64],
+        change_user_id: u64,
+        transaction: Option<&mut Transaction<'_, sqlx::MySql>>,
+        env_data: Option<&RequestEnv>,
+    ) -> RbacResult<()> {
+        let role_ids = self
+            .exclusive_param_valid(exclusive.user_id, exclusive.app_id, name, role_ids)
+            .await?;
+        let old_role_ids = self.exclusive_role_ids(&[exclusive.id]).await?;
+        let old_role_ids = old_role_ids.get(&exclusive.id).cloned().unwrap_or_default();
+        let time = now_time().unwrap_or_default();
+        let name = name.to_owned();
+        let change = model_option_set!(RbacRoleExclusiveModelRef,{
+            name:name,
+            change_user_id:change_user_id,
+            change_time:time,
+        });
+        let mut db = match transaction {
+            Some(pb) => pb.begin().await?,
+            None => self.db.begin().await?,
+        };
+        if let Err(err) = Update::<RbacRoleExclusiveModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("id={}", exclusive.id)),
+                &mut *db,
+            )
+            .await
+        {
+            db.rollback().await?;
+            return Err(err.into());
+        }
+        let del_ids = old_role_ids
+            .iter()
+            .filter(|e| !role_ids.contains(e))
+            .copied()
+            .collect::<Vec<_>>();
+        if !del_ids.is_empty() {
+            let change = model_option_set!(RbacRoleExclusiveItemModelRef,{
+                status:(RbacRoleExclusiveStatus::Delete as i8),
+                change_user_id:change_user_id,
+                change_time:time,
+            });
+            if let Err(err) = Update::<RbacRoleExclusiveItemModel, _>::new(change)
+                .execute_by_where(
+                    &WhereOption::Where(sql_format!(
+                        "exclusive_id={} and role_id in ({}) and status={}",
+                        exclusive.id,
+                        del_ids,
+                        RbacRoleExclusiveStatus::Enable
+                    )),
+                    &mut *db,
+                )
+                .await
+            {
+                db.rollback().await?;
+                return Err(err.into());
+            }
+        }
+        let add_ids = role_ids
+            .iter()
+            .filter(|e| !old_role_ids.contains(e))
+            .copied()
+            .collect::<Vec<_>>();
+        if let Err(err) = self
+            .exclusive_add_item(exclusive.id, &add_ids, change_user_id, time, &mut db)
+            .await
+        {
+            db.rollback().await?;
+            return Err(err);
+        }
+        db.commit().await?;
+        self.exclusive_clear_cache(&old_role_ids).await;
+        self.exclusive_clear_cache(&add_ids).await;
+        self.logger
+            .add(
+                &LogRoleExclusive {
+                    action: "edit",
+                    name: &name,
+                    role_ids: &role_ids,
+                    user_id: exclusive.user_id,
+                },
+                Some(exclusive.id),
+                Some(change_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(())
+    }
+    //删除角色互斥约束
+    pub async fn del_exclusive(
+        &self,
+        exclusive: &RbacRoleExclusiveModel,
+        delete_user_id: u64,
+        transaction: Option<&mut Transaction<'_, sqlx::MySql>>,
+        env_data: Option<&RequestEnv>,
+    ) -> RbacResult<()> {
+        let role_ids = self.exclusive_role_ids(&[exclusive.id]).await?;
+        let role_ids = role_ids.get(&exclusive.id).cloned().unwrap_or_default();
+        let time = now_time().unwrap_or_default();
+        let mut db = match transaction {
+            Some(pb) => pb.begin().await?,
+            None => self.db.begin().await?,
+        };
+        let change = model_option_set!(RbacRoleExclusiveModelRef,{
+            status:(RbacRoleExclusiveStatus::Delete as i8),
+            change_user_id:delete_user_id,
+            change_time:time,
+        });
+        if let Err(err) = Update::<RbacRoleExclusiveModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("id={}", exclusive.id)),
+                &mut *db,
+            )
+            .await
+        {
+            db.rollback().await?;
+            return Err(err.into());
+        }
+        let change = model_option_set!(RbacRoleExclusiveItemModelRef,{
+            status:(RbacRoleExclusiveStatus::Delete as i8),
+            change_user_id:delete_user_id,
+            change_time:time,
+        });
+        if let Err(err) = Update::<RbacRoleExclusiveItemModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("exclusive_id={}", exclusive.id)),
+                &mut *db,
+            )
+            .await
+        {
+            db.rollback().await?;
+            return Err(err.into());
+        }
+        db.commit().await?;
+        self.exclusive_clear_cache(&role_ids).await;
+        self.logger
+            .add(
+                &LogRoleExclusive {
+                    action: "del",
+                    name: &exclusive.name,
+                    role_ids: &role_ids,
+                    user_id: exclusive.user_id,
+                },
+                Some(exclusive.id),
+                Some(delete_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(())
+    }
+    //指定约束包含的角色
+    pub async fn exclusive_role_ids(
+        &self,
+        exclusive_ids: &[u64],
+    ) -> RbacResult<HashMap<u64, Vec<u64>>> {
+        let mut out = HashMap::with_capacity(exclusive_ids.len());
+        if exclusive_ids.is_empty() {
+            return Ok(out);
+        }
+        let rows = sqlx::query_as::<_, (u64, u64)>(&sql_format!(
+            "select exclusive_id,role_id from {} where exclusive_id in ({}) and status={}",
+            RbacRoleExclusiveItemModel::table_name(),
+            exclusive_ids,
+            RbacRoleExclusiveStatus::Enable
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        for (eid, rid) in rows {
+            out.entry(eid).or_insert_with(Vec::new).push(rid);
+        }
+        Ok(out)
+    }
+    async fn find_exclusive_set_by_role(&self, role_id: u64) -> RbacResult<Vec<RoleExclusiveSet>> {
+        let data = sqlx::query_as::<_, RbacRoleExclusiveModel>(&sql_format!(
+            "select ex.* from {} as ex join {} as item on ex.id=item.exclusive_id
+            where item.role_id={} and item.status={} and ex.status={}",
+            RbacRoleExclusiveModel::table_name(),
+            RbacRoleExclusiveItemModel::table_name(),
+            role_id,
+            RbacRoleExclusiveStatus::Enable,
+            RbacRoleExclusiveStatus::Enable
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        let mut role_ids = self
+            .exclusive_role_ids(&data.iter().map(|e| e.id).collect::<Vec<_>>())
+            .await?;
+        Ok(data
+            .into_iter()
+            .map(|e| RoleExclusiveSet {
+                role_ids: role_ids.remove(&e.id).unwrap_or_default(),
+                exclusive_id: e.id,
+                name: e.name,
+            })
+            .collect())
+    }
+}
+
+impl super::cache::RbacRoleCache<'_> {
+    //角色所在的互斥约束
+    pub async fn find_exclusive_by_roles(
+        &self,
+        role_ids: &[u64],
+    ) -> RbacResult<Vec<RoleExclusiveSet>> {
+        let mut out: Vec<RoleExclusiveSet> = vec![];
+        for role_id in role_ids {
+            let sets = match self.role.cache_exclusive.get(role_id).await {
+                Some(sets) => sets,
+                None => {
+                    let sets = self.role.find_exclusive_set_by_role(*role_id).await?;
+                    self.role
+                        .cache_exclusive
+                        .set(*role_id, sets.clone(), 0)
+                        .await;
+                    sets
+                }
+            };
+            for set in sets {
+                if !out.iter().any(|e| e.exclusive_id == set.exclusive_id) {
+                    out.push(set);
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl RbacRole {
+    //静态互斥检查:加入角色的用户不能已拥有同约束下其他角色
+    pub(crate) async fn exclusive_user_check(
+        &self,
+        role: &RbacRoleModel,
+        user_ids: &[u64],
+    ) -> RbacResult<()> {
+        if user_ids.is_empty() {
+            return Ok(());
+        }
+        let sets = self.cache().find_exclusive_by_roles(&[role.id]).await?;
+        let other_ids = sets
+            .iter()
+            .flat_map(|e| e.role_ids.iter().copied())
+            .filter(|e| *e != role.id)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        if other_ids.is_empty() {
+            return Ok(());
+        }
+        let res = sqlx::query_as::<_, (u64, u64)>(&sql_format!(
+            "select user_id,role_id from {} where role_id in ({}) and user_id in ({}) and status={} and (timeout=0 or timeout>{}) limit 1",
+            RbacRoleUserModel::table_name(),
+            other_ids,
+            user_ids,
+            RbacRoleUserStatus::Enable,
+            now_time().unwrap_or_default()
+        ))
+        .fetch_one(&self.db)
+        .await;
+        match res {
+            Ok((user_id, role_id)) => {
+                let name = sets
+                    .iter()
+                    .find(|e| e.role_ids.contains(&role_id))
+                    .map(|e| e.name.as_str())
+                    .unwrap_or_default();
+                Err(RbacError::System(
+                    fluent_message!("rbac-role-exclusive-conflict",{
+                        "name":name,
+                        "user_id":user_id,
+                        "role_name":&role.role_name,
+                        "role_id":role_id,
+                    }),
+                ))
+            }
+            Err(sqlx::Error::RowNotFound) => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+    //账号合并静态互斥检查:role_user 为两用户已关联的角色,返回合并后违反约束的角色
+    pub(crate) async fn exclusive_transfer_conflict(
+        &self,
+        role_user: &[RbacRoleUserModel],
+        from_user_id: u64,
+        to_user_id: u64,
+    ) -> RbacResult<Vec<RoleExclusiveViolation>> {
+        let ntime = now_time().unwrap_or_default();
+        let user_role_ids = |user_id: u64| {
+            role_user
+                .iter()
+                .filter(|e| {
+                    e.user_id == user_id
+                        && RbacRoleUserStatus::Enable.eq(e.status)
+                        && (e.timeout == 0 || e.timeout > ntime)
+                })
+                .map(|e| e.role_id)
+                .collect::<Vec<_>>()
+        };
+        let from_role_ids = user_role_ids(from_user_id);
+        let to_role_ids = user_role_ids(to_user_id);
+        if from_role_ids.is_empty() || to_role_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let sets = self.cache().find_exclusive_by_roles(&from_role_ids).await?;
+        let conflict = exclusive_merge_conflict(&sets, &from_role_ids, &to_role_ids);
+        Ok(conflict
+            .into_iter()
+            .map(|(set, role_ids)| RoleExclusiveViolation {
+                exclusive_id: set.exclusive_id,
+                name: set.name.to_owned(),
+                user_id: to_user_id,
+                role_ids,
+            })
+            .collect())
+    }
+    //动态互斥检查:返回同时生效且互斥的角色ID
+    pub(crate) async fn exclusive_conflict_role(&self, role_ids: &[u64]) -> RbacResult<Vec<u64>> {
+        if role_ids.len() < 2 {
+            return Ok(vec![]);
+        }
+        let mut out = vec![];
+        for set in self.cache().find_exclusive_by_roles(role_ids).await? {
+            let hit = set
+                .role_ids
+                .iter()
+                .filter(|e| role_ids.contains(e))
+                .copied()
+                .collect::<Vec<_>>();
+            if hit.len() > 1 {
+                out.extend(hit);
+            }
+        }
+        out.sort();
+        out.dedup();
+        Ok(out)
+    }
+}
+
+pub struct RoleExclusiveDataParam {
+    pub user_id: u64,
+    pub app_id: Option<u64>,
+    pub role_id: Option<u64>,
+}
+
+//已存在的违反互斥约束的用户
+#[derive(Clone, Debug, Serialize)]
+pub struct RoleExclusiveViolation {
+    pub exclusive_id: u64,
+    pub name: String,
+    pub user_id: u64,
+    pub role_ids: Vec<u64>,
+}
+
+impl RbacRole {
+    fn exclusive_sql(&self, field: &str, param: &RoleExclusiveDataParam) -> String {
+        let mut sql = sql_format!(
+            "select {} from {} where user_id={} and status={}",
+            lsys_core::db::SqlExpr(field),
+            RbacRoleExclusiveModel::table_name(),
+            param.user_id,
+            RbacRoleExclusiveStatus::Enable
+        );
+        if let Some(app_id) = param.app_id {
+            sql += &sql_format!(" and app_id={}", app_id);
+        }
+        if let Some(role_id) = param.role_id {
+            sql += &sql_format!(
+                " and id in (select exclusive_id from {} where role_id={} and status={})",
+                RbacRoleExclusiveItemModel::table_name(),
+                role_id,
+                RbacRoleExclusiveStatus::Enable
+            );
+        }
+        sql
+    }
+    /// 获取互斥约束数量
+    pub async fn exclusive_count(&self, param: &RoleExclusiveDataParam) -> RbacResult<i64> {
+        let sql = self.exclusive_sql("count(*) as total", param);
+        Ok(sqlx::query_scalar::<_, i64>(&sql)
+            .fetch_one(&self.db)
+            .await?)
+    }
+    /// 获取互斥约束列表及包含角色
+    pub async fn exclusive_data(
+        &self,
+        param: &RoleExclusiveDataParam,
+        page: Option<&PageParam>,
+    ) -> RbacResult<Vec<(RbacRoleExclusiveModel, Vec<RbacRoleModel>)>> {
+        let mut sql = self.exclusive_sql("*", param);
+        sql += " order by id desc";
+        if let Some(pdat) = page {
+            sql += format!(" limit {} offset {}", pdat.limit, pdat.offset).as_str();
+        }
+        let data = sqlx::query_as::<_, RbacRoleExclusiveModel>(&sql)
+            .fetch_all(&self.db)
+            .await?;
+        let role_ids = self
+            .exclusive_role_ids(&data.iter().map(|e| e.id).collect::<Vec<_>>())
+            .await?;
+        let role_data = self
+            .find_by_ids(&role_ids.values().flatten().copied().collect::<Vec<_>>())
+            .await?;
+        Ok(data
+            .into_iter()
+            .map(|e| {
+                let roles = role_ids
+                    .get(&e.id)
+                    .map(|ids| {
+                        ids.iter()
+                            .filter_map(|id| role_data.get(id).cloned())
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+                (e, roles)
+            })
+            .collect())
+    }
+    /// 违反互斥约束的用户报告,约束添加前已存在的角色用户不会被自动移除
+    pub async fn exclusive_violation_data(
+        &self,
+        param: &RoleExclusiveDataParam,
+    ) -> RbacResult<Vec<RoleExclusiveViolation>> {
+        let data = sqlx::query_as::<_, RbacRoleExclusiveModel>(&self.exclusive_sql("*", param))
+            .fetch_all(&self.db)
+            .await?;
+        let role_ids = self
+            .exclusive_role_ids(&data.iter().map(|e| e.id).collect::<Vec<_>>())
+            .await?;
+        let mut out = vec![];
+        let ntime = now_time().unwrap_or_default();
+        for item in data {
+            let rids = match role_ids.get(&item.id) {
+                Some(rids) if rids.len() > 1 => rids,
+                _ => continue,
+            };
+            let rows = sqlx::query_as::<_, (u64, u64)>(&sql_format!(
+                "select ru.user_id,ru.role_id from {} as ru join {} as role on ru.role_id=role.id
+                where ru.role_id in ({}) and ru.status={} and (ru.timeout=0 or ru.timeout>{}) and role.status={}
+                and ru.user_id in (
+                    select user_id from {} where role_id in ({}) and status={} and (timeout=0 or timeout>{})
+                    group by user_id having count(distinct role_id)>1
+                )
+                order by ru.user_id asc",
+                RbacRoleUserModel::table_name(),
+                RbacRoleModel::table_name(),
+                rids,
+                RbacRoleUserStatus::Enable,
+                ntime,
+                RbacRoleStatus::Enable,
+                RbacRoleUserModel::table_name(),
+                rids,
+                RbacRoleUserStatus::Enable,
+                ntime
+            ))
+            .fetch_all(&self.db)
+            .await?;
+            let mut user_map: Vec<(u64, Vec<u64>)> = vec![];
+            for (user_id, role_id) in rows {
+                match user_map.iter_mut().find(|e| e.0 == user_id) {
+                    Some(tmp) => {
+                        if !tmp.1.contains(&role_id) {
+                            tmp.1.push(role_id)
+                        }
+                    }
+                    None => user_map.push((user_id, vec![role_id])),
+                }
+            }
+            for (user_id, role_ids) in user_map {
+                if role_ids.len() < 2 {
+                    continue;
+                }
+                out.push(RoleExclusiveViolation {
+                    exclusive_id: item.id,
+                    name: item.name.to_owned(),
+                    user_id,
+                    role_ids,
+                });
+            }
+        }
+        Ok(out)
+    }
+}
+
+//合并两组角色时违反的互斥约束及涉及角色
+//仅计分别来自两组的不同角色,单组内已存在的冲突不因合并新增
+pub(crate) fn exclusive_merge_conflict<'t>(
+    sets: &'t [RoleExclusiveSet],
+    from_role_ids: &[u64],
+    to_role_ids: &[u64],
+) -> Vec<(&'t RoleExclusiveSet, Vec<u64>)> {
+    let mut out = vec![];
+    for set in sets {
+        let from_hit = set
+            .role_ids
+            .iter()
+            .filter(|e| from_role_ids.contains(e))
+            .copied()
+            .collect::<Vec<_>>();
+        let to_hit = set
+            .role_ids
+            .iter()
+            .filter(|e| to_role_ids.contains(e))
+            .copied()
+            .collect::<Vec<_>>();
+        if !from_hit.iter().any(|f| to_hit.iter().any(|t| t != f)) {
+            continue;
+        }
+        let mut role_ids = from_hit;
+        role_ids.extend(to_hit);
+        role_ids.sort();
+        role_ids.dedup();
+        out.push((set, role_ids));
+    }
+    out
+}
+
+#[test]
+fn test_exclusive_merge_conflict() {
+    let sets = vec![
+        RoleExclusiveSet {
+            exclusive_id: 1,
+            name: "pay".to_string(),
+            role_ids: vec![1, 2],
+        },
+        RoleExclusiveSet {
+            exclusive_id: 2,
+            name: "audit".to_string(),
+            role_ids: vec![3, 4, 5],
+        },
+    ];
+    //两用户拥有同一角色不冲突
+    assert!(exclusive_merge_conflict(&sets, &[1], &[1]).is_empty());
+    //来源用户单独已存在的冲突不因合并新增
+    assert!(exclusive_merge_conflict(&sets, &[1, 2], &[6]).is_empty());
+    let res = exclusive_merge_conflict(&sets, &[1, 3], &[2, 6]);
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].0.exclusive_id, 1);
+    assert_eq!(res[0].1, vec![1, 2]);
+    let res = exclusive_merge_conflict(&sets, &[4, 5], &[3, 4]);
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].0.exclusive_id, 2);
+    assert_eq!(res[0].1, vec![3, 4, 5]);
+}
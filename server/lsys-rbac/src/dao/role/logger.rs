@@ -68,3 +68,67 @@ impl ChangeLogData for LogRolePerm<'_> {
         serde_json::to_string(&self).unwrap_or_default()
     }
 }
+
+#[derive(Serialize)]
+pub(crate) struct LogRoleRequest<'t> {
+    pub action: &'t str,
+    pub role_id: u64,
+    pub name: &'t str,
+    pub request_user_id: u64,
+    pub duration: u64,
+    pub expire_time: u64,
+    pub note: &'t str,
+    pub user_id: u64,
+}
+
+impl ChangeLogData for LogRoleRequest<'_> {
+    fn log_type() -> &'static str {
+        "rbac-role-request"
+    }
+    fn message(&self) -> String {
+        format!("{} {}:{} ", self.action, self.role_id, self.request_user_id)
+    }
+    fn encode(&self) -> String {
+        serde_json::to_string(&self).unwrap_or_default()
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct LogRoleExclusive<'t> {
+    pub action: &'t str,
+    pub name: &'t str,
+    pub role_ids: &'t [u64],
+    pub user_id: u64,
+}
+
+impl ChangeLogData for LogRoleExclusive<'_> {
+    fn log_type() -> &'static str {
+        "rbac-role-exclusive"
+    }
+    fn message(&self) -> String {
+        format!("{} {} ", self.action, self.name)
+    }
+    fn encode(&self) -> String {
+        serde_json::to_string(&self).unwrap_or_default()
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct LogRoleDelegate<'t> {
+    pub action: &'t str,
+    pub delegate_user_id: u64,
+    pub scope_role_id: u64,
+    pub user_id: u64,
+}
+
+impl ChangeLogData for LogRoleDelegate<'_> {
+    fn log_type() -> &'static str {
+        "rbac-role-delegate"
+    }
+    fn message(&self) -> String {
+        format!("{} {} ", self.action, self.delegate_user_id)
+    }
+    fn encode(&self) -> String {
+        serde_json::to_string(&self).unwrap_or_default()
+    }
+}
@@ -1,16 +1,20 @@
 mod access;
 mod cache;
 mod data;
+mod decision;
+mod delegate;
+mod exclusive;
 pub(crate) mod logger;
 mod perm;
+mod request;
 mod user;
 //RBAC中角色相关实现
 use logger::LogRole;
 use lsys_core::{
     cache::{LocalCache, LocalCacheConfig},
     db::WhereOption,
-    fluent_message, now_time, valid_key, RemoteNotify, RequestEnv, ValidParam, ValidParamCheck,
-    ValidPattern, ValidStrlen,
+    fluent_message, now_time, valid_key, RemoteNotify, RequestEnv, TimeOutTaskNotify, ValidParam,
+    ValidParamCheck, ValidPattern, ValidStrlen,
 };
 use sqlx::Acquire;
 use std::{sync::Arc, vec};
@@ -31,8 +35,13 @@ pub use access::AccessResInfo;
 pub use access::AccessRoleData;
 pub use access::AccessRoleInfo;
 pub use access::AccessRoleRow;
+pub(crate) use access::AccessSystemRoleCheck;
 pub use data::*;
+pub use decision::*;
+pub use delegate::*;
+pub use exclusive::*;
 pub use perm::*;
+pub use request::*;
 pub use user::*;
 
 //角色相关操作的实现
@@ -41,20 +50,40 @@ pub use user::*;
 pub struct RbacRole {
     db: Pool<MySql>,
     pub(crate) cache_access: Arc<LocalCache<String, Vec<AccessRoleRow>>>,
+    pub(crate) cache_exclusive: Arc<LocalCache<u64, Vec<RoleExclusiveSet>>>, //role_id,所在互斥约束
+    pub(crate) cache_decision: Arc<AccessDecisionCache>, //访问用户的授权决策
     logger: Arc<ChangeLoggerDao>,
+    request_timeout_notify: Arc<TimeOutTaskNotify>,
+    request_max_duration: u64,
 }
 
 impl RbacRole {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         db: Pool<MySql>,
+        redis: deadpool_redis::Pool,
         remote_notify: Arc<RemoteNotify>,
         role_config: LocalCacheConfig,
+        exclusive_config: LocalCacheConfig,
+        decision_config: LocalCacheConfig,
+        request_config: RbacRoleRequestConfig,
         logger: Arc<ChangeLoggerDao>,
     ) -> Self {
         Self {
-            cache_access: Arc::from(LocalCache::new(remote_notify, role_config)),
+            cache_access: Arc::from(LocalCache::new(remote_notify.clone(), role_config)),
+            cache_exclusive: Arc::from(LocalCache::new(remote_notify.clone(), exclusive_config)),
+            cache_decision: Arc::from(AccessDecisionCache::new(
+                redis.clone(),
+                remote_notify,
+                decision_config,
+            )),
             db,
             logger,
+            request_timeout_notify: Arc::new(TimeOutTaskNotify::new(
+                redis,
+                request_config.timeout_task_config,
+            )),
+            request_max_duration: request_config.max_duration,
         }
     }
 }
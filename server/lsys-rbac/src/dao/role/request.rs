@@ -0,0 +1,757 @@
+//角色临时授权申请
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use lsys_core::db::{Insert, ModelTableName, SqlQuote, Update, WhereOption};
+use lsys_core::{
+    fluent_message, impl_dao_fetch_one_by_one, model_option_set, now_time, sql_format, valid_key,
+    AppCore, IntoFluentMessage, PageParam, RequestEnv, TimeOutTask, TimeOutTaskConfig,
+    TimeOutTaskExec, TimeOutTaskExecutor, TimeOutTaskNextTime, ValidNumber, ValidParam,
+    ValidParamCheck, ValidPattern, ValidStrlen,
+};
+use sqlx::{MySql, Pool};
+use tracing::warn;
+
+use super::{logger::LogRoleRequest, RbacRole, RoleAddUser};
+use crate::{
+    dao::access::{AuditItem, AuditRoleRequest, RbacAccess},
+    dao::result::{RbacError, RbacResult},
+    model::{
+        RbacRoleModel, RbacRoleRequestModel, RbacRoleRequestModelRef, RbacRoleRequestStatus,
+        RbacRoleStatus, RbacRoleUserModel, RbacRoleUserRange, RbacRoleUserStatus,
+    },
+};
+
+pub struct RbacRoleRequestConfig {
+    pub timeout_task_config: TimeOutTaskConfig,
+    pub max_duration: u64, //可申请的最大授权时长,秒
+}
+
+impl Default for RbacRoleRequestConfig {
+    fn default() -> Self {
+        Self {
+            timeout_task_config: TimeOutTaskConfig::new("rbac_role_request", 300),
+            max_duration: 30 * 24 * 3600,
+        }
+    }
+}
+
+pub struct RoleRequestAddData<'t> {
+    pub user_id: u64,
+    pub reason: &'t str,
+    pub duration: u64,
+}
+
+impl RbacRole {
+    impl_dao_fetch_one_by_one!(
+        db,
+        find_request_by_id,
+        u64,
+        RbacRoleRequestModel,
+        RbacResult<RbacRoleRequestModel>,
+        id,
+        "id={id}"
+    );
+    //申请临时加入角色
+    pub async fn add_request(
+        &self,
+        role: &RbacRoleModel,
+        param: &RoleRequestAddData<'_>,
+        env_data: Option<&RequestEnv>,
+    ) -> RbacResult<u64> {
+        if !RbacRoleUserRange::Custom.eq(role.user_range) {
+            return Err(RbacError::System(
+                fluent_message!("rbac-res-op-user-wrong",{
+                    "name":&role.role_name,
+                    "role_id":role.id,
+                    "range":role.user_range
+                }),
+            ));
+        }
+        ValidParam::default()
+            .add(
+                valid_key!("user_id"),
+                &param.user_id,
+                &ValidParamCheck::default().add_rule(ValidNumber::id()),
+            )
+            .add(
+                valid_key!("reason"),
+                &param.reason,
+                &ValidParamCheck::default()
+                    .add_rule(ValidPattern::NotFormat)
+                    .add_rule(ValidStrlen::range(1, 255)),
+            )
+            .add(
+                valid_key!("duration"),
+                &param.duration,
+                &ValidParamCheck::default()
+                    .add_rule(ValidNumber::range(60, self.request_max_duration)),
+            )
+            .check()?;
+
+        let pending = sqlx::query_scalar::<_, u64>(&sql_format!(
+            "select id from {} where role_id={} and user_id={} and status={} limit 1",
+            RbacRoleRequestModel::table_name(),
+            role.id,
+            param.user_id,
+            RbacRoleRequestStatus::Pending
+        ))
+        .fetch_one(&self.db)
+        .await;
+        match pending {
+            Ok(_) => {
+                return Err(RbacError::System(
+                    fluent_message!("rbac-role-request-exist",{
+                        "name":&role.role_name
+                    }),
+                ))
+            }
+            Err(sqlx::Error::RowNotFound) => {}
+            Err(err) => return Err(err.into()),
+        }
+
+        let member = sqlx::query_scalar::<_, u64>(&sql_format!(
+            "select id from {} where role_id={} and user_id={} and status={} and timeout=0 limit 1",
+            RbacRoleUserModel::table_name(),
+            role.id,
+            param.user_id,
+            RbacRoleUserStatus::Enable
+        ))
+        .fetch_one(&self.db)
+        .await;
+        match member {
+            Ok(_) => {
+                return Err(RbacError::System(
+                    fluent_message!("rbac-role-request-is-member",{
+                        "name":&role.role_name
+                    }),
+                ))
+            }
+            Err(sqlx::Error::RowNotFound) => {}
+            Err(err) => return Err(err.into()),
+        }
+
+        let time = now_time().unwrap_or_default();
+        let reason = param.reason.to_owned();
+        let request_ip = env_data
+            .and_then(|e| e.request_ip.to_owned())
+            .unwrap_or_default();
+        let idata = model_option_set!(RbacRoleRequestModelRef,{
+            role_id:role.id,
+            role_user_id:role.user_id,
+            role_app_id:role.app_id,
+            user_id:param.user_id,
+            reason:reason,
+            duration:param.duration,
+            status:(RbacRoleRequestStatus::Pending as i8),
+            request_ip:request_ip,
+            add_time:time,
+            change_time:time,
+        });
+        let id = Insert::<RbacRoleRequestModel, _>::new(idata)
+            .execute(&self.db)
+            .await?
+            .last_insert_id();
+
+        self.logger
+            .add(
+                &LogRoleRequest {
+                    action: "add",
+                    role_id: role.id,
+                    name: role.role_name.as_str(),
+                    request_user_id: param.user_id,
+                    duration: param.duration,
+                    expire_time: 0,
+                    note: param.reason,
+                    user_id: role.user_id,
+                },
+                Some(id),
+                Some(param.user_id),
+                None,
+                env_data,
+            )
+            .await;
+        RbacAccess::audit_add(
+            &self.db,
+            AuditItem::role_request(
+                &AuditRoleRequest {
+                    op_user_id: param.user_id,
+                    action: "add",
+                    request_id: id,
+                    request_user_id: param.user_id,
+                    role_id: role.id,
+                    role_user_id: role.user_id,
+                    role_name: &role.role_name,
+                    role_key: &role.role_key,
+                    expire_time: 0,
+                },
+                env_data,
+            ),
+        )
+        .await;
+        Ok(id)
+    }
+    fn request_status_check(
+        &self,
+        request: &RbacRoleRequestModel,
+        status: RbacRoleRequestStatus,
+    ) -> RbacResult<()> {
+        if !status.eq(request.status) {
+            return Err(RbacError::System(
+                fluent_message!("rbac-role-request-status-wrong",{
+                    "id":request.id,
+                    "status":request.status
+                }),
+            ));
+        }
+        Ok(())
+    }
+    fn request_note_valid(&self, note: &str) -> RbacResult<()> {
+        ValidParam::default()
+            .add(
+                valid_key!("audit_note"),
+                &note,
+                &ValidParamCheck::default()
+                    .add_rule(ValidPattern::NotFormat)
+                    .add_rule(ValidStrlen::range(0, 255)),
+            )
+            .check()?;
+        Ok(())
+    }
+    //审核通过,用户加入角色直到到期
+    pub async fn approve_request(
+        &self,
+        request: &RbacRoleRequestModel,
+        role: &RbacRoleModel,
+        audit_user_id: u64,
+        audit_note: &str,
+        env_data: Option<&RequestEnv>,
+    ) -> RbacResult<u64> {
+        self.request_status_check(request, RbacRoleRequestStatus::Pending)?;
+        if request.role_id != role.id {
+            return Err(RbacError::System(
+                fluent_message!("rbac-role-request-role-wrong",{
+                    "id":request.id,
+                    "role_id":role.id
+                }),
+            ));
+        }
+        self.request_note_valid(audit_note)?;
+
+        let time = now_time().unwrap_or_default();
+        let expire_time = time + request.duration;
+        let audit_note = audit_note.to_owned();
+        let change = model_option_set!(RbacRoleRequestModelRef,{
+            status:(RbacRoleRequestStatus::Approved as i8),
+            audit_user_id:audit_user_id,
+            audit_note:audit_note,
+            audit_time:time,
+            expire_time:expire_time,
+            change_time:time,
+        });
+        let mut db = self.db.begin().await?;
+        let res = Update::<RbacRoleRequestModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!(
+                    "id={} and status={}",
+                    request.id,
+                    RbacRoleRequestStatus::Pending
+                )),
+                &mut *db,
+            )
+            .await;
+        match res {
+            Ok(row) => {
+                if row.rows_affected() == 0 {
+                    db.rollback().await?;
+                    return Err(RbacError::System(
+                        fluent_message!("rbac-role-request-status-wrong",{
+                            "id":request.id,
+                            "status":request.status
+                        }),
+                    ));
+                }
+            }
+            Err(err) => {
+                db.rollback().await?;
+                return Err(err.into());
+            }
+        }
+        //已存在的角色用户同时更新到期时间
+        if let Err(err) = self
+            .add_user_data(
+                role,
+                &[RoleAddUser {
+                    user_id: request.user_id,
+                    timeout: expire_time,
+                }],
+                audit_user_id,
+                true,
+                true,
+                Some(&mut db),
+                env_data,
+            )
+            .await
+        {
+            db.rollback().await?;
+            return Err(err);
+        }
+        db.commit().await?;
+
+        if let Err(err) = self
+            .request_timeout_notify
+            .notify_timeout(expire_time)
+            .await
+        {
+            warn!(
+                "role request expire notify fail:{}",
+                err.to_fluent_message().default_format()
+            );
+        }
+
+        self.logger
+            .add(
+                &LogRoleRequest {
+                    action: "approve",
+                    role_id: role.id,
+                    name: role.role_name.as_str(),
+                    request_user_id: request.user_id,
+                    duration: request.duration,
+                    expire_time,
+                    note: &audit_note,
+                    user_id: role.user_id,
+                },
+                Some(request.id),
+                Some(audit_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        RbacAccess::audit_add(
+            &self.db,
+            AuditItem::role_request(
+                &AuditRoleRequest {
+                    op_user_id: audit_user_id,
+                    action: "approve",
+                    request_id: request.id,
+                    request_user_id: request.user_id,
+                    role_id: role.id,
+                    role_user_id: role.user_id,
+                    role_name: &role.role_name,
+                    role_key: &role.role_key,
+                    expire_time,
+                },
+                env_data,
+            ),
+        )
+        .await;
+        Ok(expire_time)
+    }
+    async fn request_close(
+        &self,
+        request: &RbacRoleRequestModel,
+        status: RbacRoleRequestStatus,
+        op_user_id: u64,
+        audit_note: &str,
+        env_data: Option<&RequestEnv>,
+    ) -> RbacResult<u64> {
+        self.request_status_check(request, RbacRoleRequestStatus::Pending)?;
+        self.request_note_valid(audit_note)?;
+        let time = now_time().unwrap_or_default();
+        let audit_note = audit_note.to_owned();
+        let mut change = model_option_set!(RbacRoleRequestModelRef,{
+            status:(status as i8),
+            change_time:time,
+        });
+        if RbacRoleRequestStatus::Rejected == status {
+            change.audit_user_id = Some(&op_user_id);
+            change.audit_note = Some(&audit_note);
+            change.audit_time = Some(&time);
+        }
+        let res = Update::<RbacRoleRequestModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!(
+                    "id={} and status={}",
+                    request.id,
+                    RbacRoleRequestStatus::Pending
+                )),
+                &self.db,
+            )
+            .await?;
+        if res.rows_affected() == 0 {
+            return Err(RbacError::System(
+                fluent_message!("rbac-role-request-status-wrong",{
+                    "id":request.id,
+                    "status":request.status
+                }),
+            ));
+        }
+        //角色可能已被删除,仅用于日志
+        let role = match self.find_by_id(&request.role_id).await {
+            Ok(role) => Some(role),
+            Err(RbacError::Sqlx(sqlx::Error::RowNotFound)) => None,
+            Err(err) => return Err(err),
+        };
+        let role_name = role.as_ref().map(|e| e.role_name.as_str()).unwrap_or("");
+        let action = if RbacRoleRequestStatus::Rejected == status {
+            "reject"
+        } else {
+            "cancel"
+        };
+        self.logger
+            .add(
+                &LogRoleRequest {
+                    action,
+                    role_id: request.role_id,
+                    name: role_name,
+                    request_user_id: request.user_id,
+                    duration: request.duration,
+                    expire_time: 0,
+                    note: &audit_note,
+                    user_id: request.role_user_id,
+                },
+                Some(request.id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        RbacAccess::audit_add(
+            &self.db,
+            AuditItem::role_request(
+                &AuditRoleRequest {
+                    op_user_id,
+                    action,
+                    request_id: request.id,
+                    request_user_id: request.user_id,
+                    role_id: request.role_id,
+                    role_user_id: request.role_user_id,
+                    role_name,
+                    role_key: role.as_ref().map(|e| e.role_key.as_str()).unwrap_or(""),
+                    expire_time: 0,
+                },
+                env_data,
+            ),
+        )
+        .await;
+        Ok(res.rows_affected())
+    }
+    //审核拒绝
+    pub async fn reject_request(
+        &self,
+        request: &RbacRoleRequestModel,
+        audit_user_id: u64,
+        audit_note: &str,
+        env_data: Option<&RequestEnv>,
+    ) -> RbacResult<u64> {
+        self.request_close(
+            request,
+            RbacRoleRequestStatus::Rejected,
+            audit_user_id,
+            audit_note,
+            env_data,
+        )
+        .await
+    }
+    //申请人取消申请
+    pub async fn cancel_request(
+        &self,
+        request: &RbacRoleRequestModel,
+        env_data: Option<&RequestEnv>,
+    ) -> RbacResult<u64> {
+        self.request_close(
+            request,
+            RbacRoleRequestStatus::Cancel,
+            request.user_id,
+            "",
+            env_data,
+        )
+        .await
+    }
+}
+
+pub struct RoleRequestDataParam<'t> {
+    pub role_user_id: Option<u64>,
+    pub role_app_id: Option<u64>,
+    pub role_id: Option<u64>,
+    pub user_id: Option<u64>,
+    pub status: Option<&'t [RbacRoleRequestStatus]>,
+}
+
+impl RbacRole {
+    fn request_sql(&self, field: &str, param: &RoleRequestDataParam<'_>) -> Option<String> {
+        let mut where_sql = vec![];
+        if let Some(val) = param.role_user_id {
+            where_sql.push(sql_format!("role_user_id = {}", val));
+        }
+        if let Some(val) = param.role_app_id {
+            where_sql.push(sql_format!("role_app_id = {}", val));
+        }
+        if let Some(val) = param.role_id {
+            where_sql.push(sql_format!("role_id = {}", val));
+        }
+        if let Some(val) = param.user_id {
+            where_sql.push(sql_format!("user_id = {}", val));
+        }
+        if let Some(val) = param.status {
+            if val.is_empty() {
+                return None;
+            }
+            where_sql.push(sql_format!(
+                "status in ({})",
+                val.iter().map(|e| *e as i8).collect::<Vec<_>>()
+            ));
+        }
+        Some(format!(
+            "select {} from {} {}",
+            field,
+            RbacRoleRequestModel::table_name(),
+            if where_sql.is_empty() {
+                "".to_string()
+            } else {
+                format!("where {}", where_sql.join(" and "))
+            }
+        ))
+    }
+    /// 获取申请数量
+    pub async fn request_count(&self, param: &RoleRequestDataParam<'_>) -> RbacResult<i64> {
+        match self.request_sql("count(*) as total", param) {
+            Some(sql) => Ok(sqlx::query_scalar::<_, i64>(&sql)
+                .fetch_one(&self.db)
+                .await?),
+            None => Ok(0),
+        }
+    }
+    /// 获取申请列表
+    pub async fn request_data(
+        &self,
+        param: &RoleRequestDataParam<'_>,
+        page: Option<&PageParam>,
+    ) -> RbacResult<Vec<RbacRoleRequestModel>> {
+        match self.request_sql("*", param) {
+            Some(mut sql) => {
+                sql += " order by id desc";
+                if let Some(pdat) = page {
+                    sql += format!(" limit {} offset {}", pdat.limit, pdat.offset).as_str();
+                }
+                Ok(sqlx::query_as::<_, RbacRoleRequestModel>(&sql)
+                    .fetch_all(&self.db)
+                    .await?)
+            }
+            None => Ok(vec![]),
+        }
+    }
+}
+
+//授权到期后的通知
+#[async_trait::async_trait]
+pub trait RbacRoleRequestExpireNotify: Send + Sync + 'static {
+    async fn expire_notify(&self, request: &RbacRoleRequestModel, role_name: &str);
+}
+
+//到期任务,到期后从角色中移除用户
+pub struct RbacRoleRequestExpireTask {
+    db: Pool<MySql>,
+    role: Arc<RbacRole>,
+    notify: Option<Box<dyn RbacRoleRequestExpireNotify>>,
+}
+
+impl RbacRoleRequestExpireTask {
+    async fn request_expire(&self, request: &RbacRoleRequestModel, ntime: u64) -> RbacResult<()> {
+        let change = model_option_set!(RbacRoleRequestModelRef,{
+            status:(RbacRoleRequestStatus::Expired as i8),
+            change_time:ntime,
+        });
+        let res = Update::<RbacRoleRequestModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!(
+                    "id={} and status={}",
+                    request.id,
+                    RbacRoleRequestStatus::Approved
+                )),
+                &self.db,
+            )
+            .await?;
+        if res.rows_affected() == 0 {
+            return Ok(());
+        }
+        let role = match sqlx::query_as::<_, RbacRoleModel>(&sql_format!(
+            "select * from {} where id={} and status={}",
+            RbacRoleModel::table_name(),
+            request.role_id,
+            RbacRoleStatus::Enable
+        ))
+        .fetch_one(&self.db)
+        .await
+        {
+            Ok(role) => Some(role),
+            Err(sqlx::Error::RowNotFound) => None,
+            Err(err) => return Err(err.into()),
+        };
+        if let Some(ref role) = role {
+            //期间被重新授权(永久或更长时间)时不移除
+            let timeout_user = sqlx::query_scalar::<_, u64>(&sql_format!(
+                "select user_id from {} where role_id={} and user_id={} and status={} and timeout>0 and timeout<={}",
+                RbacRoleUserModel::table_name(),
+                role.id,
+                request.user_id,
+                RbacRoleUserStatus::Enable,
+                ntime
+            ))
+            .fetch_all(&self.db)
+            .await?;
+            self.role
+                .del_user(role, &timeout_user, 0, None, None)
+                .await?;
+        }
+        self.role
+            .logger
+            .add(
+                &LogRoleRequest {
+                    action: "expire",
+                    role_id: request.role_id,
+                    name: role.as_ref().map(|e| e.role_name.as_str()).unwrap_or(""),
+                    request_user_id: request.user_id,
+                    duration: request.duration,
+                    expire_time: request.expire_time,
+                    note: "",
+                    user_id: request.role_user_id,
+                },
+                Some(request.id),
+                Some(0),
+                None,
+                None,
+            )
+            .await;
+        RbacAccess::audit_add(
+            &self.db,
+            AuditItem::role_request(
+                &AuditRoleRequest {
+                    op_user_id: 0,
+                    action: "expire",
+                    request_id: request.id,
+                    request_user_id: request.user_id,
+                    role_id: request.role_id,
+                    role_user_id: request.role_user_id,
+                    role_name: role.as_ref().map(|e| e.role_name.as_str()).unwrap_or(""),
+                    role_key: role.as_ref().map(|e| e.role_key.as_str()).unwrap_or(""),
+                    expire_time: request.expire_time,
+                },
+                None,
+            ),
+        )
+        .await;
+        if let Some(ref notify) = self.notify {
+            notify
+                .expire_notify(
+                    request,
+                    role.as_ref().map(|e| e.role_name.as_str()).unwrap_or(""),
+                )
+                .await;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TimeOutTaskExec for RbacRoleRequestExpireTask {
+    async fn exec(
+        &self,
+        max_lock_time: usize,
+        mut expire_call: impl FnMut() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send,
+    ) -> Result<(), String> {
+        let ntime = now_time().unwrap_or_default();
+        let mut runtime = ntime;
+        let mut start_id = 0;
+        loop {
+            let res = sqlx::query_as::<_, RbacRoleRequestModel>(&sql_format!(
+                "select * from {} where status={} and expire_time>0 and expire_time<={} and id>{} order by id asc limit 100",
+                RbacRoleRequestModel::table_name(),
+                RbacRoleRequestStatus::Approved,
+                ntime,
+                start_id
+            ))
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| e.to_string())?;
+            if res.is_empty() {
+                break;
+            }
+            for item in res {
+                start_id = item.id;
+                if let Err(err) = self.request_expire(&item, ntime).await {
+                    warn!(
+                        "role request expire fail[{}]:{}",
+                        item.id,
+                        err.to_fluent_message().default_format()
+                    );
+                }
+            }
+            let last_now_time = now_time().unwrap_or_default();
+            if (last_now_time - runtime) > (max_lock_time as u64) {
+                return Err(format!(
+                    "role request expire timeout[last run time:{},start time:{}]",
+                    last_now_time, runtime
+                ));
+            }
+            if (last_now_time - runtime) * 2 > (max_lock_time as u64) {
+                //时间小于一半延长一次有效期
+                expire_call().await;
+            }
+            runtime = last_now_time;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TimeOutTaskNextTime for RbacRoleRequestExpireTask {
+    async fn next_time(&self, max_lock_time: usize) -> Result<Option<u64>, String> {
+        let ntime = now_time().unwrap_or_default();
+        let timeout_res = sqlx::query_scalar::<_, u64>(&sql_format!(
+            "select expire_time from {} where status={} and expire_time>0 and expire_time<={} order by expire_time asc limit 1",
+            RbacRoleRequestModel::table_name(),
+            RbacRoleRequestStatus::Approved,
+            (ntime + max_lock_time as u64)
+        ))
+        .fetch_one(&self.db)
+        .await;
+        match timeout_res {
+            Ok(dat) => Ok(Some(dat)),
+            Err(sqlx::Error::RowNotFound) => Ok(None),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TimeOutTaskExecutor for RbacRoleRequestExpireTask {
+    type Exec = Self;
+    type NextTime = Self;
+}
+
+impl RbacRole {
+    //监听申请到期并移除角色用户,需在独立任务中运行
+    pub async fn listen_request_expire(
+        self: &Arc<Self>,
+        app_core: Arc<AppCore>,
+        notify: Option<Box<dyn RbacRoleRequestExpireNotify>>,
+        channel_buffer: Option<usize>,
+    ) {
+        let task = Arc::new(RbacRoleRequestExpireTask {
+            db: self.db.clone(),
+            role: self.clone(),
+            notify,
+        });
+        TimeOutTask::<RbacRoleRequestExpireTask>::new(
+            app_core,
+            self.request_timeout_notify.clone(),
+            task.clone(),
+            task,
+        )
+        .listen(channel_buffer)
+        .await;
+    }
+}
@@ -8,7 +8,7 @@ use lsys_core::db::{Insert, ModelTableName, SqlQuote, Update, WhereOption};
 use lsys_core::{db_option_executor, model_option_set, sql_format};
 use sqlx::Transaction;
 
-use super::{logger::LogRoleUser, RbacRole};
+use super::{logger::LogRoleUser, RbacRole, RoleExclusiveViolation};
 use crate::dao::role::fluent_message;
 use crate::{
     dao::result::{RbacError, RbacResult},
@@ -34,6 +34,50 @@ impl RbacRole {
         add_user_id: u64,
         transaction: Option<&mut Transaction<'_, sqlx::MySql>>,
         env_data: Option<&RequestEnv>,
+    ) -> RbacResult<()> {
+        self.add_user_data(
+            role,
+            user_vec,
+            add_user_id,
+            true,
+            false,
+            transaction,
+            env_data,
+        )
+        .await
+    }
+    /// 已通过管理权限检查的角色添加用户,不受委托管理限制
+    pub async fn admin_add_user(
+        &self,
+        role: &RbacRoleModel,
+        user_vec: &[RoleAddUser],
+        add_user_id: u64,
+        transaction: Option<&mut Transaction<'_, sqlx::MySql>>,
+        env_data: Option<&RequestEnv>,
+    ) -> RbacResult<()> {
+        self.add_user_data(
+            role,
+            user_vec,
+            add_user_id,
+            false,
+            false,
+            transaction,
+            env_data,
+        )
+        .await
+    }
+    //delegate_check 为 true 时按委托管理记录限制操作用户
+    //update_timeout 为 true 时已存在的角色用户同时更新到期时间
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn add_user_data(
+        &self,
+        role: &RbacRoleModel,
+        user_vec: &[RoleAddUser],
+        add_user_id: u64,
+        delegate_check: bool,
+        update_timeout: bool,
+        transaction: Option<&mut Transaction<'_, sqlx::MySql>>,
+        env_data: Option<&RequestEnv>,
     ) -> RbacResult<()> {
         if user_vec.is_empty() {
             return Ok(());
@@ -50,6 +94,12 @@ impl RbacRole {
 
         let user_id_vec = user_vec.iter().map(|e| e.user_id).collect::<Vec<_>>();
 
+        if delegate_check {
+            self.delegate_user_check(role, &user_id_vec, add_user_id)
+                .await?;
+        }
+        self.exclusive_user_check(role, &user_id_vec).await?;
+
         let user_res = sqlx::query_as::<_, (u64, u64)>(&sql_format!(
             "select id,user_id from {} where user_id in ({}) and role_id={} ",
             RbacRoleUserModel::table_name(),
@@ -70,12 +120,15 @@ impl RbacRole {
             let mut is_updata = false;
             for (itemid, uid) in user_res.iter() {
                 if uid == user_id {
-                    let item = model_option_set!(RbacRoleUserModelRef,{
+                    let mut item = model_option_set!(RbacRoleUserModelRef,{
                         role_id:role.id,
                         change_time:nowtime,
                         change_user_id:add_user_id,
                         status:(RbacRoleUserStatus::Enable as i8),
                     });
+                    if update_timeout {
+                        item.timeout = Some(timeout);
+                    }
                     if let Err(err) = Update::< RbacRoleUserModel, _>::new(item)
                         .execute_by_where(
                             &WhereOption::Where(sql_format!("id={}", *itemid)),
@@ -141,6 +194,20 @@ impl RbacRole {
         del_user_id: u64,
         transaction: Option<&mut Transaction<'_, sqlx::MySql>>,
         env_data: Option<&RequestEnv>,
+    ) -> RbacResult<u64> {
+        self.delegate_user_check(role, user_id_vec, del_user_id)
+            .await?;
+        self.admin_del_user(role, user_id_vec, del_user_id, transaction, env_data)
+            .await
+    }
+    /// 已通过管理权限检查的角色删除用户,不受委托管理限制
+    pub async fn admin_del_user(
+        &self,
+        role: &RbacRoleModel,
+        user_id_vec: &[u64],
+        del_user_id: u64,
+        transaction: Option<&mut Transaction<'_, sqlx::MySql>>,
+        env_data: Option<&RequestEnv>,
     ) -> RbacResult<u64> {
         if user_id_vec.is_empty() {
             return Ok(0);
@@ -191,4 +258,175 @@ impl RbacRole {
             .await;
         Ok(res.rows_affected())
     }
+    /// 用户关联的有效角色数量
+    pub async fn user_role_count(&self, user_id: u64) -> RbacResult<i64> {
+        Ok(sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) as total from {} where user_id={} and status={}",
+            RbacRoleUserModel::table_name(),
+            user_id,
+            RbacRoleUserStatus::Enable as i8,
+        ))
+        .fetch_one(&self.db)
+        .await?)
+    }
+    fn user_transfer_sql(from_user_id: u64, to_user_id: u64) -> String {
+        sql_format!(
+            "select * from {} where user_id in ({}) and status={}",
+            RbacRoleUserModel::table_name(),
+            [from_user_id, to_user_id],
+            RbacRoleUserStatus::Enable as i8,
+        )
+    }
+    /// 账号合并后违反互斥约束的角色,用于合并预览
+    pub async fn user_transfer_conflict(
+        &self,
+        from_user_id: u64,
+        to_user_id: u64,
+    ) -> RbacResult<Vec<RoleExclusiveViolation>> {
+        let role_user = sqlx::query_as::<_, RbacRoleUserModel>(&Self::user_transfer_sql(
+            from_user_id,
+            to_user_id,
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        self.exclusive_transfer_conflict(&role_user, from_user_id, to_user_id)
+            .await
+    }
+    /// 把用户关联的角色移到另一用户,用于账号合并
+    /// 目标用户已关联相同角色时保留较长的超时时间
+    /// 合并后违反互斥约束时返回错误
+    /// 在调用方事务中执行,事务提交后需调用 user_transfer_finish
+    pub async fn user_transfer(
+        &self,
+        from_user_id: u64,
+        to_user_id: u64,
+        op_user_id: u64,
+        db: &mut Transaction<'_, sqlx::MySql>,
+    ) -> RbacResult<RoleUserTransfer> {
+        let role_user = sqlx::query_as::<_, RbacRoleUserModel>(&Self::user_transfer_sql(
+            from_user_id,
+            to_user_id,
+        ))
+        .fetch_all(&mut **db)
+        .await?;
+        let conflict = self
+            .exclusive_transfer_conflict(&role_user, from_user_id, to_user_id)
+            .await?;
+        if let Some(item) = conflict.first() {
+            return Err(RbacError::System(
+                fluent_message!("rbac-role-merge-exclusive-conflict",{
+                    "name":&item.name,
+                    "from_user_id":from_user_id,
+                    "to_user_id":to_user_id,
+                    "role_ids":item.role_ids.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(","),
+                }),
+            ));
+        }
+        let from_data = role_user
+            .iter()
+            .filter(|e| e.user_id == from_user_id)
+            .collect::<Vec<_>>();
+        let time = now_time().unwrap_or_default();
+        for from in from_data.iter() {
+            let to = role_user
+                .iter()
+                .find(|e| e.user_id == to_user_id && e.role_id == from.role_id);
+            match to {
+                Some(to) => {
+                    //超时为0表示不超时
+                    let timeout = if from.timeout == 0 || to.timeout == 0 {
+                        0
+                    } else {
+                        from.timeout.max(to.timeout)
+                    };
+                    let item = model_option_set!(RbacRoleUserModelRef,{
+                        timeout:timeout,
+                        change_time:time,
+                        change_user_id:op_user_id,
+                    });
+                    Update::<RbacRoleUserModel, _>::new(item)
+                        .execute_by_where(
+                            &WhereOption::Where(sql_format!("id={}", to.id)),
+                            &mut **db,
+                        )
+                        .await?;
+                    let item = model_option_set!(RbacRoleUserModelRef,{
+                        status:(RbacRoleUserStatus::Delete as i8),
+                        change_time:time,
+                        change_user_id:op_user_id,
+                    });
+                    Update::<RbacRoleUserModel, _>::new(item)
+                        .execute_by_where(
+                            &WhereOption::Where(sql_format!("id={}", from.id)),
+                            &mut **db,
+                        )
+                        .await?;
+                }
+                None => {
+                    let item = model_option_set!(RbacRoleUserModelRef,{
+                        user_id:to_user_id,
+                        change_time:time,
+                        change_user_id:op_user_id,
+                    });
+                    Update::<RbacRoleUserModel, _>::new(item)
+                        .execute_by_where(
+                            &WhereOption::Where(sql_format!("id={}", from.id)),
+                            &mut **db,
+                        )
+                        .await?;
+                }
+            };
+        }
+        Ok(RoleUserTransfer {
+            from_user_id,
+            to_user_id,
+            role_ids: from_data.iter().map(|e| e.role_id).collect(),
+        })
+    }
+    /// 账号合并事务提交后清理缓存并记录日志
+    pub async fn user_transfer_finish(
+        &self,
+        transfer: &RoleUserTransfer,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> RbacResult<()> {
+        if transfer.role_ids.is_empty() {
+            return Ok(());
+        }
+        let user_ids = [transfer.from_user_id, transfer.to_user_id];
+        for role in self.find_by_ids(&transfer.role_ids).await?.values() {
+            self.cache()
+                .clear_access(role, Some(&[]), Some(&user_ids))
+                .await;
+            self.logger
+                .add(
+                    &LogRoleUser {
+                        action: "merge",
+                        name: role.role_name.as_str(),
+                        add_user: None,
+                        user_id: role.user_id,
+                        del_user: Some(vec![transfer.from_user_id]),
+                    },
+                    Some(role.id),
+                    Some(op_user_id),
+                    None,
+                    env_data,
+                )
+                .await;
+        }
+        Ok(())
+    }
+}
+
+/// 账号合并时转移的角色
+pub struct RoleUserTransfer {
+    from_user_id: u64,
+    to_user_id: u64,
+    role_ids: Vec<u64>,
+}
+
+impl RoleUserTransfer {
+    pub fn total(&self) -> u64 {
+        self.role_ids.len() as u64
+    }
 }
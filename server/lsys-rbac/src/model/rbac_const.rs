@@ -66,3 +66,27 @@ pub enum RbacAuditResult {
     Succ = 1,
     Fail = 0,
 }
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[lsys_model_status(field_type = "i8")]
+pub enum RbacRoleRequestStatus {
+    Pending = 1,  //待审核
+    Approved = 2, //已通过,关联用户有效中
+    Rejected = 3, //已拒绝
+    Cancel = 4,   //申请人取消
+    Expired = 5,  //已到期,关联用户已移除
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[lsys_model_status(field_type = "i8")]
+pub enum RbacRoleExclusiveStatus {
+    Enable = 1,
+    Delete = -1,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[lsys_model_status(field_type = "i8")]
+pub enum RbacRoleDelegateStatus {
+    Enable = 1,
+    Delete = -1,
+}
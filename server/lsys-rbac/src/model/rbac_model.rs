@@ -32,6 +32,10 @@ pub struct RbacRoleModel {
     #[sqlx(default)]
     pub role_name: String,
 
+    /// 是否允许委托管理员分配 1 允许 0 不允许
+    #[sqlx(default)]
+    pub delegable: i8,
+
     /// 状态 1 启用 -1 删除
     #[sqlx(default)]
     pub status: i8,
@@ -262,6 +266,10 @@ pub struct RbacAuditModel {
     #[sqlx(default)]
     pub device_name: String,
 
+    /// 个人访问令牌HASH
+    #[sqlx(default)]
+    pub personal_token: String,
+
     /// 请求ID
     #[sqlx(default)]
     pub request_id: String,
@@ -310,6 +318,10 @@ pub struct RbacAuditDetailModel {
     #[sqlx(default)]
     pub check_result: i8,
 
+    /// 该资源操作的授权结果 0 失败 1 成功
+    #[sqlx(default)]
+    pub item_result: i8,
+
     /// 资源是否要授权访问
     #[sqlx(default)]
     pub res_auth: i8,
@@ -334,3 +346,161 @@ pub struct RbacAuditDetailModel {
     #[sqlx(default)]
     pub add_time: u64,
 }
+
+///角色临时授权申请
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+#[lsys_model(table_name = "rbac_role_request")]
+pub struct RbacRoleRequestModel {
+    #[sqlx(default)]
+    pub id: u64,
+
+    /// 申请角色ID
+    #[sqlx(default)]
+    pub role_id: u64,
+
+    /// 冗余 角色的 user_id
+    #[sqlx(default)]
+    pub role_user_id: u64,
+
+    /// 冗余 角色的 app_id
+    #[sqlx(default)]
+    pub role_app_id: u64,
+
+    /// 申请用户ID
+    #[sqlx(default)]
+    pub user_id: u64,
+
+    /// 申请原因
+    #[sqlx(default)]
+    pub reason: String,
+
+    /// 申请授权时长,秒
+    #[sqlx(default)]
+    pub duration: u64,
+
+    /// 状态
+    #[sqlx(default)]
+    pub status: i8,
+
+    /// 申请IP
+    #[sqlx(default)]
+    pub request_ip: String,
+
+    /// 审核用户ID
+    #[sqlx(default)]
+    pub audit_user_id: u64,
+
+    /// 审核备注
+    #[sqlx(default)]
+    pub audit_note: String,
+
+    /// 审核时间
+    #[sqlx(default)]
+    pub audit_time: u64,
+
+    /// 授权到期时间,审核通过后设置
+    #[sqlx(default)]
+    pub expire_time: u64,
+
+    /// 申请时间
+    #[sqlx(default)]
+    pub add_time: u64,
+
+    /// 最后修改时间
+    #[sqlx(default)]
+    pub change_time: u64,
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+#[lsys_model(table_name = "rbac_role_exclusive")]
+pub struct RbacRoleExclusiveModel {
+    #[sqlx(default)]
+    pub id: u64,
+
+    /// 约束所属用户ID,0 为系统
+    #[sqlx(default)]
+    pub user_id: u64,
+
+    /// 约束所属APP
+    #[sqlx(default)]
+    pub app_id: u64,
+
+    /// 约束名称
+    #[sqlx(default)]
+    pub name: String,
+
+    /// 状态
+    #[sqlx(default)]
+    pub status: i8,
+
+    /// 最后修改用户
+    #[sqlx(default)]
+    pub change_user_id: u64,
+
+    /// 最后修改时间
+    #[sqlx(default)]
+    pub change_time: u64,
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+#[lsys_model(table_name = "rbac_role_exclusive_item")]
+pub struct RbacRoleExclusiveItemModel {
+    #[sqlx(default)]
+    pub id: u64,
+
+    /// 互斥约束ID
+    #[sqlx(default)]
+    pub exclusive_id: u64,
+
+    /// 角色ID
+    #[sqlx(default)]
+    pub role_id: u64,
+
+    /// 状态
+    #[sqlx(default)]
+    pub status: i8,
+
+    /// 最后修改用户
+    #[sqlx(default)]
+    pub change_user_id: u64,
+
+    /// 最后修改时间
+    #[sqlx(default)]
+    pub change_time: u64,
+}
+
+//委托管理员
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+#[lsys_model(table_name = "rbac_role_delegate")]
+pub struct RbacRoleDelegateModel {
+    #[sqlx(default)]
+    pub id: u64,
+
+    /// 委托所属用户ID,0 为系统
+    #[sqlx(default)]
+    pub user_id: u64,
+
+    /// 委托所属APP
+    #[sqlx(default)]
+    pub app_id: u64,
+
+    /// 被委托的管理用户ID
+    #[sqlx(default)]
+    pub delegate_user_id: u64,
+
+    /// 可分配用户范围,该角色下的用户,0 不限
+    #[sqlx(default)]
+    pub scope_role_id: u64,
+
+    /// 状态 1 启用 -1 删除
+    #[sqlx(default)]
+    pub status: i8,
+
+    /// 添加用户
+    #[sqlx(default)]
+    pub change_user_id: u64,
+
+    /// 最后更改时间
+    #[sqlx(default)]
+    pub change_time: u64,
+}
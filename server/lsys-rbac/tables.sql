@@ -6,6 +6,7 @@ CREATE TABLE `yaf_rbac_role` (
     `user_range` tinyint NOT NULL COMMENT '角色包含用户范围',
     `res_range` tinyint NOT NULL COMMENT '角色可操作资源范围',
     `role_name` varchar(32) NOT NULL COMMENT '角色名,可为空',
+    `delegable` tinyint NOT NULL DEFAULT 0 COMMENT '是否允许委托管理员分配',
     `status` tinyint NOT NULL COMMENT '状态',
     `change_user_id` bigint unsigned NOT NULL COMMENT '最后更新用户',
     `change_time` bigint unsigned NOT NULL COMMENT '最后更改时间',
@@ -132,13 +133,15 @@ CREATE TABLE `yaf_rbac_audit` (
     `token_data` varchar(64) NOT NULL DEFAULT '' COMMENT '授权token',
     `user_ip` varchar(46) NOT NULL DEFAULT '' COMMENT '登陆者IP',
     `device_id` varchar(64) NOT NULL DEFAULT '' COMMENT '设备ID',
+    `personal_token` varchar(64) NOT NULL DEFAULT '' COMMENT '个人访问令牌HASH',
     `device_name` varchar(255) NOT NULL DEFAULT '' COMMENT '设备名',
     `request_id` varchar(64) NOT NULL DEFAULT '' COMMENT '请求id',
     `add_time` bigint unsigned NOT NULL COMMENT '检测时间',
     PRIMARY KEY (`id`),
     KEY `rbac_log_IDX` (
         `user_id`
-    ) USING BTREE
+    ) USING BTREE,
+    KEY `rbac_log_time_IDX` (`add_time`) USING BTREE
 ) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '授权审计记录';
 
 
@@ -152,6 +155,7 @@ CREATE TABLE `yaf_rbac_audit_detail` (
     `res_id` bigint unsigned NOT NULL COMMENT '资源ID',
     `op_id` bigint unsigned NOT NULL COMMENT '操作ID',
     `check_result` tinyint NOT NULL  COMMENT '授权检查结果',
+    `item_result` tinyint NOT NULL DEFAULT 0 COMMENT '该资源操作的授权检查结果',
     `res_auth` tinyint NOT NULL  COMMENT '默认是否需要授权',
     `is_role_excluce` tinyint NOT NULL  COMMENT '是否被屏蔽',
     `is_role_include` tinyint NOT NULL  COMMENT '是否单独授权',
@@ -161,5 +165,67 @@ CREATE TABLE `yaf_rbac_audit_detail` (
     PRIMARY KEY (`id`),
     KEY `rbac_log_IDX` (
         `rbac_audit_id`
-    ) USING BTREE
+    ) USING BTREE,
+    KEY `rbac_log_res_IDX` (`res_id`, `op_id`, `add_time`) USING BTREE,
+    KEY `rbac_log_time_IDX` (`add_time`) USING BTREE
 ) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '授权审计详细';
+
+
+CREATE TABLE `yaf_rbac_role_request` (
+    `id` bigint unsigned NOT NULL AUTO_INCREMENT,
+    `role_id` bigint unsigned NOT NULL COMMENT '申请角色ID',
+    `role_user_id` bigint unsigned NOT NULL COMMENT '冗余角色的用户ID',
+    `role_app_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '冗余角色的应用ID',
+    `user_id` bigint unsigned NOT NULL COMMENT '申请用户ID',
+    `reason` varchar(255) NOT NULL COMMENT '申请原因',
+    `duration` bigint unsigned NOT NULL COMMENT '申请授权时长,秒',
+    `status` tinyint NOT NULL COMMENT '状态',
+    `request_ip` varchar(46) NOT NULL DEFAULT '' COMMENT '申请IP',
+    `audit_user_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '审核用户ID',
+    `audit_note` varchar(255) NOT NULL DEFAULT '' COMMENT '审核备注',
+    `audit_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '审核时间',
+    `expire_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '授权到期时间',
+    `add_time` bigint unsigned NOT NULL COMMENT '申请时间',
+    `change_time` bigint unsigned NOT NULL COMMENT '最后更改时间',
+    PRIMARY KEY (`id`),
+    KEY `rbac_role_request_role_IDX` (`role_user_id`, `role_app_id`, `status`) USING BTREE,
+    KEY `rbac_role_request_user_IDX` (`user_id`, `role_id`, `status`) USING BTREE,
+    KEY `rbac_role_request_expire_IDX` (`status`, `expire_time`) USING BTREE
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '角色临时授权申请';
+CREATE TABLE `yaf_rbac_role_exclusive` (
+    `id` bigint unsigned NOT NULL AUTO_INCREMENT,
+    `user_id` bigint unsigned NOT NULL COMMENT '约束所属用户ID,0 为系统',
+    `app_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '约束所属APP',
+    `name` varchar(32) NOT NULL COMMENT '约束名称',
+    `status` tinyint NOT NULL COMMENT '状态',
+    `change_user_id` bigint unsigned NOT NULL COMMENT '最后更新用户',
+    `change_time` bigint unsigned NOT NULL COMMENT '最后更改时间',
+    PRIMARY KEY (`id`),
+    KEY `rbac_role_exclusive_IDX` (`user_id`, `app_id`, `status`) USING BTREE
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '角色互斥约束,同一用户只能拥有其中一个角色';
+
+CREATE TABLE `yaf_rbac_role_exclusive_item` (
+    `id` bigint unsigned NOT NULL AUTO_INCREMENT,
+    `exclusive_id` bigint unsigned NOT NULL COMMENT '互斥约束ID',
+    `role_id` bigint unsigned NOT NULL COMMENT '角色ID',
+    `status` tinyint NOT NULL COMMENT '状态',
+    `change_user_id` bigint unsigned NOT NULL COMMENT '最后更新用户',
+    `change_time` bigint unsigned NOT NULL COMMENT '最后更改时间',
+    PRIMARY KEY (`id`),
+    KEY `rbac_role_exclusive_item_IDX` (`exclusive_id`, `status`) USING BTREE,
+    KEY `rbac_role_exclusive_item_role_IDX` (`role_id`, `status`) USING BTREE
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '角色互斥约束包含的角色';
+
+CREATE TABLE `yaf_rbac_role_delegate` (
+    `id` bigint unsigned NOT NULL AUTO_INCREMENT,
+    `user_id` bigint unsigned NOT NULL COMMENT '委托所属用户ID,0 为系统',
+    `app_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '委托所属APP',
+    `delegate_user_id` bigint unsigned NOT NULL COMMENT '被委托的管理用户ID',
+    `scope_role_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '可分配用户范围,该角色下的用户,0 不限',
+    `status` tinyint NOT NULL COMMENT '状态',
+    `change_user_id` bigint unsigned NOT NULL COMMENT '最后更新用户',
+    `change_time` bigint unsigned NOT NULL COMMENT '最后更改时间',
+    PRIMARY KEY (`id`),
+    KEY `rbac_role_delegate_IDX` (`user_id`, `app_id`, `status`) USING BTREE,
+    KEY `rbac_role_delegate_user_IDX` (`delegate_user_id`, `status`) USING BTREE
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '委托管理员,仅能分配自身拥有或允许委托的角色给范围内用户';
@@ -7,8 +7,8 @@ use crate::model::{AccountIndexCat, AccountModel, AccountModelRef, AccountStatus
 use lsys_access::dao::{AccessDao, UserInfo};
 use lsys_core::cache::{LocalCache, LocalCacheConfig};
 use lsys_core::{
-    fluent_message, now_time, valid_key, LimitParam, RemoteNotify, RequestEnv, ValidParam,
-    ValidParamCheck, ValidPattern, ValidStrlen,
+    fluent_message, now_time, valid_key, LimitParam, PageParam, RemoteNotify, RequestEnv,
+    ValidParam, ValidParamCheck, ValidPattern, ValidStrlen,
 };
 use lsys_logger::dao::ChangeLoggerDao;
 use tracing::warn;
@@ -48,7 +48,7 @@ impl Account {
             logger,
         }
     }
-    async fn nickname_param_valid(&self, nickname: &str) -> AccountResult<()> {
+    pub(crate) async fn nickname_param_valid(&self, nickname: &str) -> AccountResult<()> {
         ValidParam::default()
             .add(
                 valid_key!("nickname"),
@@ -81,6 +81,7 @@ impl Account {
             status:u_status,
         });
 
+        let own_tran = transaction.is_none();
         let mut db = match transaction {
             Some(pb) => pb.begin().await?,
             None => self.db.begin().await?,
@@ -126,6 +127,9 @@ impl Account {
             return Err(ie);
         }
         db.commit().await?;
+        if own_tran {
+            self.index.change_notify(account.id);
+        }
 
         //此过程必须,同步过去好查数据
         if let Err(err) = self
@@ -178,6 +182,7 @@ impl Account {
             confirm_time:time,
             status:AccountStatus::Enable as i8,
         });
+        let own_tran = transaction.is_none();
         let mut db = match transaction {
             Some(pb) => pb.begin().await?,
             None => self.db.begin().await?,
@@ -206,6 +211,9 @@ impl Account {
             return Err(ie);
         }
         db.commit().await?;
+        if own_tran {
+            self.index.change_notify(account.id);
+        }
 
         self.logger
             .add(
@@ -232,34 +240,52 @@ impl Account {
         transaction: Option<&mut Transaction<'_, sqlx::MySql>>,
         env_data: Option<&RequestEnv>,
     ) -> AccountResult<()> {
-        let time = now_time()?;
+        let own_tran = transaction.is_none();
         let mut db = match transaction {
             Some(pb) => pb.begin().await?,
             None => self.db.begin().await?,
         };
-
-        //delete account data
+        if let Err(err) = self.del_tran(account, del_name, &mut db).await {
+            db.rollback().await?;
+            return Err(err);
+        }
+        db.commit().await?;
+        if own_tran {
+            self.index.change_notify(account.id);
+        }
+        self.del_finish(account, op_user_id, env_data).await;
+        Ok(())
+    }
+    //删除账号数据,由调用方提交事务
+    pub(crate) async fn del_tran(
+        &self,
+        account: &AccountModel,
+        del_name: Option<&str>,
+        db: &mut Transaction<'_, sqlx::MySql>,
+    ) -> AccountResult<()> {
+        let time = now_time()?;
         let mut change = lsys_core::model_option_set!(AccountModelRef,{
             status:AccountStatus::Delete as i8,
             change_time:time
         });
         let del_name_ow = del_name.map(|e| e.to_string());
         change.nickname = del_name_ow.as_ref();
-        let tmp = Update::<AccountModel, _>::new(change)
+        Update::<AccountModel, _>::new(change)
             .execute_by_where(
                 &WhereOption::Where(sql_format!("id={}", account.id)),
-                &mut *db,
+                &mut **db,
             )
-            .await;
-        if let Err(e) = tmp {
-            db.rollback().await?;
-            return Err(e.into());
-        }
-        if let Err(ie) = self.index.account_del(account.id, Some(&mut db)).await {
-            db.rollback().await?;
-            return Err(ie);
-        }
-        db.commit().await?;
+            .await?;
+        self.index.account_del(account.id, Some(db)).await?;
+        Ok(())
+    }
+    //删除事务提交后清理缓存并记录日志
+    pub(crate) async fn del_finish(
+        &self,
+        account: &AccountModel,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) {
         self.cache.clear(&account.id).await;
         self.logger
             .add(
@@ -274,6 +300,23 @@ impl Account {
                 env_data,
             )
             .await;
+    }
+    /// 通知更新账号搜索索引
+    /// 传入外部事务修改账号数据时,由调用方在外部事务提交后调用
+    pub fn search_notify(&self, account_id: u64) {
+        self.index.change_notify(account_id);
+    }
+    //同步昵称到访问用户,用于匿名化等直接修改账号数据的场景
+    pub(crate) async fn access_nickname_sync(
+        &self,
+        account_id: u64,
+        nickname: &str,
+    ) -> AccountResult<()> {
+        self.access
+            .user
+            .cache()
+            .sync_user(0, account_id, Some(nickname), None)
+            .await?;
         Ok(())
     }
     pub async fn set_nikename(
@@ -291,6 +334,7 @@ impl Account {
             change_time:time,
             nickname:nikename,
         });
+        let own_tran = transaction.is_none();
         let mut db = match transaction {
             Some(pb) => pb.begin().await?,
             None => self.db.begin().await?,
@@ -322,6 +366,9 @@ impl Account {
                 }
 
                 db.commit().await?;
+                if own_tran {
+                    self.index.change_notify(account.id);
+                }
                 self.cache.clear(&account.id).await;
                 Ok(mr.last_insert_id())
             }
@@ -401,6 +448,30 @@ impl Account {
             )
             .await
     }
+    //按添加顺序分页列出正常及未激活账号
+    pub async fn list_data(&self, page: Option<&PageParam>) -> AccountResult<Vec<AccountModel>> {
+        let mut sql = sql_format!(
+            "select * from {} where status in ({}) order by id asc",
+            AccountModel::table_name(),
+            [AccountStatus::Enable as i8, AccountStatus::Init as i8]
+        );
+        if let Some(pdat) = page {
+            sql += format!(" limit {} offset {}", pdat.limit, pdat.offset).as_str();
+        }
+        Ok(sqlx::query_as::<_, AccountModel>(&sql)
+            .fetch_all(&self.db)
+            .await?)
+    }
+    pub async fn list_count(&self) -> AccountResult<i64> {
+        let sql = sql_format!(
+            "select count(*) as total from {} where status in ({})",
+            AccountModel::table_name(),
+            [AccountStatus::Enable as i8, AccountStatus::Init as i8]
+        );
+        Ok(sqlx::query_scalar::<_, i64>(&sql)
+            .fetch_one(&self.db)
+            .await?)
+    }
 
     pub fn cache(&'_ self) -> AccountCache<'_> {
         AccountCache { dao: self }
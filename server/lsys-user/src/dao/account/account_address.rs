@@ -74,7 +74,7 @@ impl AccountAddress {
             &address_data.name,
             &ValidParamCheck::default()
                 .add_rule(ValidPattern::NotFormat)
-                .add_rule(ValidStrlen::range(4, 16)),
+                .add_rule(ValidStrlen::range(2, 16)),
         );
         valid_param.add(
             valid_key!("address_country_code"),
@@ -109,7 +109,7 @@ impl AccountAddress {
                 .add_rule(ValidPattern::NotFormat)
                 .add_rule(ValidStrlen::range(1, 128)),
         );
-
+        valid_param.check()?;
         Ok(())
     }
 
@@ -143,6 +143,7 @@ impl AccountAddress {
             account_id:address.account_id
         });
 
+        let own_tran = transaction.is_none();
         let mut db = match transaction {
             Some(pb) => pb.begin().await?,
             None => self.db.begin().await?,
@@ -186,6 +187,9 @@ impl AccountAddress {
                     return Err(ie);
                 }
                 db.commit().await?;
+                if own_tran {
+                    self.index.change_notify(address.account_id);
+                }
                 self.cache.clear(&address.account_id).await;
 
                 self.logger
@@ -258,6 +262,7 @@ impl AccountAddress {
             return Ok(address.id);
         }
 
+        let own_tran = transaction.is_none();
         let mut db = match transaction {
             Some(pb) => pb.begin().await?,
             None => self.db.begin().await?,
@@ -303,6 +308,9 @@ impl AccountAddress {
                         }
 
                         db.commit().await?;
+                        if own_tran {
+                            self.index.change_notify(account.id);
+                        }
                         self.cache.clear(&account.id).await;
 
                         let aid = mr.last_insert_id();
@@ -344,6 +352,7 @@ impl AccountAddress {
             status:AccountAddressStatus::Delete as i8,
             change_time:time,
         });
+        let own_tran = transaction.is_none();
         let mut db = match transaction {
             Some(pb) => pb.begin().await?,
             None => self.db.begin().await?,
@@ -391,6 +400,9 @@ impl AccountAddress {
                         }
 
                         db.commit().await?;
+                        if own_tran {
+                            self.index.change_notify(address.account_id);
+                        }
                         self.cache.clear(&address.account_id).await;
 
                         self.logger
@@ -417,6 +429,101 @@ impl AccountAddress {
             }
         }
     }
+    /// 区域数据升级后修正地址区域编码及名称
+    pub async fn normalize_address(
+        &self,
+        address: &AccountAddressModel,
+        address_code: &str,
+        address_info: &str,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<()> {
+        if address.address_code == address_code && address.address_info == address_info {
+            return Ok(());
+        }
+        let time = now_time()?;
+        let address_code = address_code.to_owned();
+        let address_info = address_info.to_owned();
+        let change = lsys_core::model_option_set!(AccountAddressModelRef,{
+            address_code:address_code,
+            address_info:address_info,
+            change_time:time,
+        });
+        let mut db = self.db.begin().await?;
+        if let Err(e) = Update::<AccountAddressModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("id={}", address.id)),
+                &mut *db,
+            )
+            .await
+        {
+            db.rollback().await?;
+            return Err(e.into());
+        }
+        if let Err(ie) = self
+            .index
+            .del(
+                crate::model::AccountIndexCat::Address,
+                address.account_id,
+                &[&address.address_info],
+                Some(&mut db),
+            )
+            .await
+        {
+            db.rollback().await?;
+            return Err(ie);
+        }
+        if let Err(ie) = self
+            .index
+            .add(
+                crate::model::AccountIndexCat::Address,
+                address.account_id,
+                &[&address_info],
+                Some(&mut db),
+            )
+            .await
+        {
+            db.rollback().await?;
+            return Err(ie);
+        }
+        db.commit().await?;
+        self.index.change_notify(address.account_id);
+        self.cache.clear(&address.account_id).await;
+        self.logger
+            .add(
+                &LogAccountAddress {
+                    action: "normalize",
+                    address_code: &address_code,
+                    address_info: &address_info,
+                    address_detail: &address.address_detail,
+                    name: &address.name,
+                    mobile: &address.mobile,
+                    account_id: address.account_id,
+                },
+                Some(address.id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(())
+    }
+    /// 按ID顺序分批获取可用地址
+    pub async fn list_batch(
+        &self,
+        start_id: u64,
+        limit: u64,
+    ) -> AccountResult<Vec<AccountAddressModel>> {
+        Ok(sqlx::query_as::<_, AccountAddressModel>(&sql_format!(
+            "select * from {} where id>{} and status={} order by id asc limit {}",
+            AccountAddressModel::table_name(),
+            start_id,
+            AccountAddressStatus::Enable,
+            limit
+        ))
+        .fetch_all(&self.db)
+        .await?)
+    }
     lsys_core::impl_dao_fetch_one_by_one!(
         db,
         find_by_id,
@@ -0,0 +1,549 @@
+//账号注销
+//申请后进入宽限期,宽限期内账号登录或管理员恢复可撤销注销
+//宽限期结束由注销任务在单一节点执行,账号删除由 AccountDeleteExecutor 实现,之后匿名化本模块中的个人信息
+//匿名化只清空个人信息字段,保留记录及关联ID,便于审计
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use lsys_core::db::{Insert, ModelTableName, SqlExpr, SqlQuote, Update, WhereOption};
+use lsys_core::{
+    fluent_message, model_option_set, now_time, sql_format, AppCore, IntoFluentMessage, LimitParam,
+    RequestEnv, TimeOutTask, TimeOutTaskConfig, TimeOutTaskExec, TimeOutTaskExecutor,
+    TimeOutTaskNextTime, TimeOutTaskNotify,
+};
+use lsys_logger::dao::ChangeLoggerDao;
+use sqlx::{MySql, Pool};
+use tracing::{info, warn};
+
+use crate::model::{
+    AccountAddressModel, AccountDeleteModel, AccountDeleteModelRef, AccountDeleteStatus,
+    AccountEmailModel, AccountExternalModel, AccountIndexModel, AccountInfoModel,
+    AccountLoginDeviceModel, AccountLoginModel, AccountMobileModel, AccountModel,
+    AccountSecurityEventModel, AccountStatus, AccountTokenModel,
+};
+
+use super::{
+    logger::LogAccountDelete, Account, AccountAddress, AccountError, AccountInfo, AccountResult,
+};
+
+//匿名化后的账号昵称
+const ANONYMIZE_NICKNAME: &str = "delete user";
+
+pub struct AccountDeleteConfig {
+    pub timeout_task_config: TimeOutTaskConfig,
+    pub grace_time: u64, //申请后到执行删除的宽限期,秒
+}
+
+impl Default for AccountDeleteConfig {
+    fn default() -> Self {
+        Self {
+            timeout_task_config: TimeOutTaskConfig::new("account_delete", 600),
+            grace_time: 30 * 86400,
+        }
+    }
+}
+
+/// 账号绑定过的收件邮箱及手机号,含已解绑的,用于清理其他模块中的发送记录
+pub struct AccountDeleteRecipient {
+    pub email: Vec<String>,
+    pub mobile: Vec<(String, String)>,
+}
+
+/// 宽限期结束后删除账号,由上层模块实现
+#[async_trait::async_trait]
+pub trait AccountDeleteExecutor: Send + Sync + 'static {
+    //删除账号及关联数据,并清理其他模块中该账号的个人信息
+    async fn delete(
+        &self,
+        record: &AccountDeleteModel,
+        account: &AccountModel,
+        recipient: &AccountDeleteRecipient,
+    ) -> Result<(), String>;
+}
+
+pub struct AccountDelete {
+    db: Pool<MySql>,
+    account: Arc<Account>,
+    info: Arc<AccountInfo>,
+    address: Arc<AccountAddress>,
+    logger: Arc<ChangeLoggerDao>,
+    timeout_notify: Arc<TimeOutTaskNotify>,
+    grace_time: u64,
+}
+
+impl AccountDelete {
+    pub fn new(
+        db: Pool<MySql>,
+        redis: deadpool_redis::Pool,
+        account: Arc<Account>,
+        info: Arc<AccountInfo>,
+        address: Arc<AccountAddress>,
+        config: AccountDeleteConfig,
+        logger: Arc<ChangeLoggerDao>,
+    ) -> Self {
+        Self {
+            db,
+            account,
+            info,
+            address,
+            logger,
+            timeout_notify: Arc::new(TimeOutTaskNotify::new(redis, config.timeout_task_config)),
+            grace_time: config.grace_time,
+        }
+    }
+    pub async fn find_by_id(&self, id: &u64) -> AccountResult<AccountDeleteModel> {
+        Ok(sqlx::query_as::<_, AccountDeleteModel>(&sql_format!(
+            "select * from {} where id={}",
+            AccountDeleteModel::table_name(),
+            id
+        ))
+        .fetch_one(&self.db)
+        .await?)
+    }
+    /// 账号等待删除的注销申请
+    pub async fn find_pending_by_account_id(
+        &self,
+        account_id: u64,
+    ) -> AccountResult<Option<AccountDeleteModel>> {
+        let res = sqlx::query_as::<_, AccountDeleteModel>(&sql_format!(
+            "select * from {} where account_id={} and status={} order by id desc limit 1",
+            AccountDeleteModel::table_name(),
+            account_id,
+            AccountDeleteStatus::Pending
+        ))
+        .fetch_one(&self.db)
+        .await;
+        match res {
+            Ok(data) => Ok(Some(data)),
+            Err(sqlx::Error::RowNotFound) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+    /// 申请注销,已有等待中的申请时直接返回该申请
+    pub async fn delete_apply(
+        &self,
+        account: &AccountModel,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<AccountDeleteModel> {
+        if AccountStatus::Delete.eq(account.status) {
+            return Err(AccountError::System(fluent_message!(
+                "account-is-delete",
+                {
+                    "user":account.id
+                }
+            )));
+        }
+        if let Some(data) = self.find_pending_by_account_id(account.id).await? {
+            return Ok(data);
+        }
+        let time = now_time()?;
+        let delete_time = time + self.grace_time;
+        let status = AccountDeleteStatus::Pending as i8;
+        let vdata = model_option_set!(AccountDeleteModelRef,{
+            account_id:account.id,
+            status:status,
+            delete_time:delete_time,
+            add_user_id:op_user_id,
+            add_time:time,
+        });
+        let id = Insert::<AccountDeleteModel, _>::new(vdata)
+            .execute(&self.db)
+            .await?
+            .last_insert_id();
+        self.logger
+            .add(
+                &LogAccountDelete {
+                    action: "apply",
+                    message: "",
+                    account_id: account.id,
+                    delete_time,
+                },
+                Some(id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        if let Err(err) = self.timeout_notify.notify_timeout(delete_time).await {
+            warn!(
+                "account delete notify fail:{}",
+                err.to_fluent_message().default_format()
+            );
+        }
+        Ok(AccountDeleteModel {
+            id,
+            account_id: account.id,
+            status,
+            delete_time,
+            message: "".to_string(),
+            add_user_id: op_user_id,
+            add_time: time,
+            change_user_id: 0,
+            change_time: 0,
+        })
+    }
+    /// 撤销注销,返回是否存在等待中的申请
+    pub async fn delete_restore(
+        &self,
+        account_id: u64,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<bool> {
+        let data = match self.find_pending_by_account_id(account_id).await? {
+            Some(data) => data,
+            None => return Ok(false),
+        };
+        let time = now_time()?;
+        let status = AccountDeleteStatus::Restore as i8;
+        let change = model_option_set!(AccountDeleteModelRef,{
+            status:status,
+            change_user_id:op_user_id,
+            change_time:time,
+        });
+        let res = Update::<AccountDeleteModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!(
+                    "id={} and status={}",
+                    data.id,
+                    AccountDeleteStatus::Pending
+                )),
+                &self.db,
+            )
+            .await?;
+        if res.rows_affected() == 0 {
+            return Ok(false);
+        }
+        self.logger
+            .add(
+                &LogAccountDelete {
+                    action: "restore",
+                    message: "",
+                    account_id,
+                    delete_time: data.delete_time,
+                },
+                Some(data.id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(true)
+    }
+    fn list_where(&self, account_id: Option<u64>, status: Option<AccountDeleteStatus>) -> String {
+        let mut where_sql = vec![];
+        if let Some(aid) = account_id {
+            where_sql.push(sql_format!("account_id={}", aid));
+        }
+        if let Some(s) = status {
+            where_sql.push(sql_format!("status={}", s));
+        }
+        if where_sql.is_empty() {
+            "1".to_string()
+        } else {
+            where_sql.join(" and ")
+        }
+    }
+    /// 注销申请列表
+    pub async fn list_data(
+        &self,
+        account_id: Option<u64>,
+        status: Option<AccountDeleteStatus>,
+        limit: Option<&LimitParam>,
+    ) -> AccountResult<(Vec<AccountDeleteModel>, Option<u64>)> {
+        let where_sql = self.list_where(account_id, status);
+        let sql = if let Some(page) = limit {
+            format!(
+                "{} {} order by {} {}",
+                where_sql,
+                page.where_sql("id", Some("and")),
+                page.order_sql("id"),
+                page.limit_sql(),
+            )
+        } else {
+            format!("{} order by id desc", where_sql)
+        };
+        let mut data = sqlx::query_as::<_, AccountDeleteModel>(&sql_format!(
+            "select * from {} where {}",
+            AccountDeleteModel::table_name(),
+            SqlExpr(sql)
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        let next = limit
+            .as_ref()
+            .map(|page| page.tidy(&mut data))
+            .unwrap_or_default()
+            .map(|e| e.id);
+        Ok((data, next))
+    }
+    /// 注销申请数量
+    pub async fn list_count(
+        &self,
+        account_id: Option<u64>,
+        status: Option<AccountDeleteStatus>,
+    ) -> AccountResult<i64> {
+        Ok(sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) as total from {} where {}",
+            AccountDeleteModel::table_name(),
+            SqlExpr(self.list_where(account_id, status))
+        ))
+        .fetch_one(&self.db)
+        .await?)
+    }
+    //账号绑定过的邮箱及手机号
+    async fn recipient(&self, account_id: u64) -> AccountResult<AccountDeleteRecipient> {
+        let email = sqlx::query_scalar::<_, String>(&sql_format!(
+            "select distinct email from {} where account_id={} and email!=''",
+            AccountEmailModel::table_name(),
+            account_id
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        let mobile = sqlx::query_as::<_, (String, String)>(&sql_format!(
+            "select distinct area_code,mobile from {} where account_id={} and mobile!=''",
+            AccountMobileModel::table_name(),
+            account_id
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        Ok(AccountDeleteRecipient { email, mobile })
+    }
+    //清空账号相关个人信息
+    async fn anonymize(&self, account_id: u64) -> AccountResult<()> {
+        let sqls = [
+            sql_format!(
+                "update {} set nickname={} where id={}",
+                AccountModel::table_name(),
+                ANONYMIZE_NICKNAME,
+                account_id
+            ),
+            sql_format!(
+                "update {} set email='' where account_id={}",
+                AccountEmailModel::table_name(),
+                account_id
+            ),
+            sql_format!(
+                "update {} set area_code='',mobile='' where account_id={}",
+                AccountMobileModel::table_name(),
+                account_id
+            ),
+            sql_format!(
+                "update {} set address_code='',address_info='',address_detail='',name='',mobile='' where account_id={}",
+                AccountAddressModel::table_name(),
+                account_id
+            ),
+            sql_format!(
+                "update {} set external_id='',external_name='',external_gender='',external_link='',external_pic='',external_nikename='',token_data='' where account_id={}",
+                AccountExternalModel::table_name(),
+                account_id
+            ),
+            sql_format!(
+                "update {} set gender=0,headimg='',birthday='',reg_ip='' where account_id={}",
+                AccountInfoModel::table_name(),
+                account_id
+            ),
+            sql_format!(
+                "update {} set login_account='',login_ip='',login_city='' where account_id={}",
+                AccountLoginModel::table_name(),
+                account_id
+            ),
+            sql_format!(
+                "update {} set device_name='',login_ip='',login_city='',login_lat=0,login_lng=0 where account_id={}",
+                AccountLoginDeviceModel::table_name(),
+                account_id
+            ),
+            sql_format!(
+                "update {} set device_name='',login_ip='',login_city='',event_data='' where account_id={}",
+                AccountSecurityEventModel::table_name(),
+                account_id
+            ),
+            sql_format!(
+                "update {} set last_used_ip='' where account_id={}",
+                AccountTokenModel::table_name(),
+                account_id
+            ),
+            sql_format!(
+                "delete from {} where account_id={}",
+                AccountIndexModel::table_name(),
+                account_id
+            ),
+        ];
+        let mut db = self.db.begin().await?;
+        for sql in sqls {
+            if let Err(err) = sqlx::query(&sql).execute(&mut *db).await {
+                db.rollback().await?;
+                return Err(err.into());
+            }
+        }
+        db.commit().await?;
+        self.account.search_notify(account_id);
+        self.account.cache.clear(&account_id).await;
+        self.info.cache.clear(&account_id).await;
+        self.address.cache.clear(&account_id).await;
+        //访问用户中保存的昵称同步替换,并清理其缓存
+        self.account
+            .access_nickname_sync(account_id, ANONYMIZE_NICKNAME)
+            .await
+    }
+    //执行单个注销
+    async fn delete_exec(
+        &self,
+        record: &AccountDeleteModel,
+        executor: &dyn AccountDeleteExecutor,
+    ) -> AccountResult<()> {
+        let exec_res = match self.account.find_by_id(&record.account_id).await {
+            Ok(account) => match self.recipient(account.id).await {
+                Ok(recipient) => executor.delete(record, &account, &recipient).await,
+                Err(err) => Err(err.to_fluent_message().default_format()),
+            },
+            Err(err) => Err(err.to_fluent_message().default_format()),
+        };
+        let exec_res = match exec_res {
+            Ok(()) => self
+                .anonymize(record.account_id)
+                .await
+                .map_err(|e| e.to_fluent_message().default_format()),
+            Err(err) => Err(err),
+        };
+        let time = now_time()?;
+        let (action, status, message) = match exec_res {
+            Ok(()) => ("finish", AccountDeleteStatus::Finish, "".to_string()),
+            Err(err) => {
+                warn!("account delete fail[{}]:{}", record.id, err);
+                (
+                    "fail",
+                    AccountDeleteStatus::Fail,
+                    err.chars().take(255).collect::<String>(),
+                )
+            }
+        };
+        let status = status as i8;
+        let change = model_option_set!(AccountDeleteModelRef,{
+            status:status,
+            message:message,
+            change_time:time,
+        });
+        Update::<AccountDeleteModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("id={}", record.id)),
+                &self.db,
+            )
+            .await?;
+        self.logger
+            .add(
+                &LogAccountDelete {
+                    action,
+                    message: &message,
+                    account_id: record.account_id,
+                    delete_time: record.delete_time,
+                },
+                Some(record.id),
+                Some(0),
+                None,
+                None,
+            )
+            .await;
+        Ok(())
+    }
+}
+
+pub struct AccountDeleteTask {
+    delete: Arc<AccountDelete>,
+    executor: Box<dyn AccountDeleteExecutor>,
+}
+
+#[async_trait::async_trait]
+impl TimeOutTaskExec for AccountDeleteTask {
+    async fn exec(
+        &self,
+        max_lock_time: usize,
+        mut expire_call: impl FnMut() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send,
+    ) -> Result<(), String> {
+        let mut runtime = now_time().unwrap_or_default();
+        let ntime = runtime;
+        let mut start_id = 0;
+        loop {
+            let res = sqlx::query_as::<_, AccountDeleteModel>(&sql_format!(
+                "select * from {} where status={} and delete_time<={} and id>{} order by id asc limit 10",
+                AccountDeleteModel::table_name(),
+                AccountDeleteStatus::Pending,
+                ntime,
+                start_id
+            ))
+            .fetch_all(&self.delete.db)
+            .await
+            .map_err(|e| e.to_string())?;
+            if res.is_empty() {
+                break;
+            }
+            for item in res {
+                start_id = item.id;
+                if let Err(err) = self.delete.delete_exec(&item, self.executor.as_ref()).await {
+                    warn!(
+                        "account delete exec fail[{}]:{}",
+                        item.id,
+                        err.to_fluent_message().default_format()
+                    );
+                }
+                let last_now_time = now_time().unwrap_or_default();
+                if (last_now_time - runtime) * 2 > (max_lock_time as u64) {
+                    //执行耗时较长,超过一半时间延长一次有效期
+                    expire_call().await;
+                    runtime = last_now_time;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TimeOutTaskNextTime for AccountDeleteTask {
+    async fn next_time(&self, max_lock_time: usize) -> Result<Option<u64>, String> {
+        let timeout_res = sqlx::query_scalar::<_, u64>(&sql_format!(
+            "select delete_time from {} where status={} and delete_time<={} order by delete_time asc limit 1",
+            AccountDeleteModel::table_name(),
+            AccountDeleteStatus::Pending,
+            now_time().unwrap_or_default() + max_lock_time as u64
+        ))
+        .fetch_one(&self.delete.db)
+        .await;
+        match timeout_res {
+            Ok(dat) => Ok(Some(dat)),
+            Err(sqlx::Error::RowNotFound) => Ok(None),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TimeOutTaskExecutor for AccountDeleteTask {
+    type Exec = Self;
+    type NextTime = Self;
+}
+
+impl AccountDelete {
+    //监听到期的注销申请并执行删除,需在独立任务中运行
+    pub async fn listen_delete(
+        self: &Arc<Self>,
+        app_core: Arc<AppCore>,
+        executor: Box<dyn AccountDeleteExecutor>,
+        channel_buffer: Option<usize>,
+    ) {
+        info!("account delete task start");
+        let task = Arc::new(AccountDeleteTask {
+            delete: self.clone(),
+            executor,
+        });
+        TimeOutTask::<AccountDeleteTask>::new(
+            app_core,
+            self.timeout_notify.clone(),
+            task.clone(),
+            task,
+        )
+        .listen(channel_buffer)
+        .await;
+    }
+}
@@ -69,7 +69,7 @@ impl AccountEmail {
         .await?;
         Ok(useremal)
     }
-    async fn email_param_valid(&self, email: &str) -> AccountResult<()> {
+    pub(crate) async fn email_param_valid(&self, email: &str) -> AccountResult<()> {
         ValidParam::default()
             .add(
                 valid_key!("email"),
@@ -134,6 +134,7 @@ impl AccountEmail {
             status:_status,
         });
 
+        let own_tran = transaction.is_none();
         let mut db = match transaction {
             Some(pb) => pb.begin().await?,
             None => self.db.begin().await?,
@@ -181,6 +182,9 @@ impl AccountEmail {
                         }
 
                         db.commit().await?;
+                        if own_tran {
+                            self.index.change_notify(account.id);
+                        }
                         self.account_cache.clear(&account.id).await;
 
                         let aid = mr.last_insert_id();
@@ -356,6 +360,7 @@ impl AccountEmail {
         }
 
         db.commit().await?;
+        self.index.change_notify(email.account_id);
         self.cache.clear(&email.id).await;
         self.account_cache.clear(&email.account_id).await;
 
@@ -392,6 +397,7 @@ impl AccountEmail {
             status:AccountEmailStatus::Delete as i8,
             change_time:time,
         });
+        let own_tran = transaction.is_none();
         let mut db = match transaction {
             Some(pb) => pb.begin().await?,
             None => self.db.begin().await?,
@@ -440,6 +446,9 @@ impl AccountEmail {
                         }
 
                         db.commit().await?;
+                        if own_tran {
+                            self.index.change_notify(email.account_id);
+                        }
                         self.cache.clear(&email.id).await;
                         self.account_cache.clear(&email.account_id).await;
 
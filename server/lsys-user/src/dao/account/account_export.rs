@@ -0,0 +1,467 @@
+//个人数据导出
+//申请后写入导出记录并通知导出任务,任务在单一节点执行,数据收集及打包由 AccountExportBuilder 实现
+//导出文件到期后由导出任务删除
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use lsys_core::db::{Insert, ModelTableName, SqlExpr, SqlQuote, Update, WhereOption};
+use lsys_core::{
+    fluent_message, model_option_set, now_time, rand_str, sql_format, AppCore, IntoFluentMessage,
+    LimitParam, RandType, RequestEnv, TimeOutTask, TimeOutTaskConfig, TimeOutTaskExec,
+    TimeOutTaskExecutor, TimeOutTaskNextTime, TimeOutTaskNotify,
+};
+use lsys_logger::dao::ChangeLoggerDao;
+use sqlx::{MySql, Pool};
+use tracing::{info, warn};
+
+use crate::model::{AccountExportModel, AccountExportModelRef, AccountExportStatus, AccountModel};
+
+use super::{logger::LogAccountExport, Account, AccountError, AccountResult};
+
+pub struct AccountExportConfig {
+    pub timeout_task_config: TimeOutTaskConfig,
+    pub download_time: u64, //导出文件下载有效期,秒
+    pub interval_time: u64, //同一账号两次导出最小间隔,秒
+}
+
+impl Default for AccountExportConfig {
+    fn default() -> Self {
+        Self {
+            timeout_task_config: TimeOutTaskConfig::new("account_export", 600),
+            download_time: 3 * 86400,
+            interval_time: 86400,
+        }
+    }
+}
+
+/// 导出生成的文件
+pub struct AccountExportFile {
+    pub file_path: String,
+    pub file_size: u64,
+}
+
+/// 导出数据的收集打包及完成通知,由上层模块实现
+#[async_trait::async_trait]
+pub trait AccountExportBuilder: Send + Sync + 'static {
+    //收集账号相关数据并写入导出文件
+    async fn build(
+        &self,
+        export: &AccountExportModel,
+        account: &AccountModel,
+    ) -> Result<AccountExportFile, String>;
+    //导出完成后通知用户
+    async fn finish_notify(&self, export: &AccountExportModel, account: &AccountModel);
+}
+
+pub struct AccountExport {
+    db: Pool<MySql>,
+    account: Arc<Account>,
+    logger: Arc<ChangeLoggerDao>,
+    timeout_notify: Arc<TimeOutTaskNotify>,
+    download_time: u64,
+    interval_time: u64,
+}
+
+impl AccountExport {
+    pub fn new(
+        db: Pool<MySql>,
+        redis: deadpool_redis::Pool,
+        account: Arc<Account>,
+        config: AccountExportConfig,
+        logger: Arc<ChangeLoggerDao>,
+    ) -> Self {
+        Self {
+            db,
+            account,
+            logger,
+            timeout_notify: Arc::new(TimeOutTaskNotify::new(redis, config.timeout_task_config)),
+            download_time: config.download_time,
+            interval_time: config.interval_time,
+        }
+    }
+    pub async fn find_by_id(&self, id: &u64) -> AccountResult<AccountExportModel> {
+        Ok(sqlx::query_as::<_, AccountExportModel>(&sql_format!(
+            "select * from {} where id={}",
+            AccountExportModel::table_name(),
+            id
+        ))
+        .fetch_one(&self.db)
+        .await?)
+    }
+    /// 申请导出,已有进行中的导出或间隔过短时返回错误
+    pub async fn export_add(
+        &self,
+        account: &AccountModel,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<AccountExportModel> {
+        let time = now_time()?;
+        let last_time = sqlx::query_scalar::<_, Option<u64>>(&sql_format!(
+            "select max(add_time) from {} where account_id={} and status in ({},{})",
+            AccountExportModel::table_name(),
+            account.id,
+            AccountExportStatus::Init,
+            AccountExportStatus::Finish
+        ))
+        .fetch_one(&self.db)
+        .await?
+        .unwrap_or_default();
+        if last_time + self.interval_time > time {
+            return Err(AccountError::System(fluent_message!(
+                "account-export-interval",
+                {
+                    "time":last_time + self.interval_time
+                }
+            )));
+        }
+        let status = AccountExportStatus::Init as i8;
+        let vdata = model_option_set!(AccountExportModelRef,{
+            account_id:account.id,
+            status:status,
+            add_user_id:op_user_id,
+            add_time:time,
+        });
+        let id = Insert::<AccountExportModel, _>::new(vdata)
+            .execute(&self.db)
+            .await?
+            .last_insert_id();
+        self.logger
+            .add(
+                &LogAccountExport {
+                    action: "add",
+                    message: "",
+                    account_id: account.id,
+                },
+                Some(id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        if let Err(err) = self.timeout_notify.notify_timeout(time).await {
+            warn!(
+                "account export notify fail:{}",
+                err.to_fluent_message().default_format()
+            );
+        }
+        Ok(AccountExportModel {
+            id,
+            account_id: account.id,
+            status,
+            file_path: "".to_string(),
+            file_size: 0,
+            download_token: "".to_string(),
+            expire_time: 0,
+            message: "".to_string(),
+            add_user_id: op_user_id,
+            add_time: time,
+            finish_time: 0,
+        })
+    }
+    /// 通过下载令牌查找可下载的导出记录
+    pub async fn find_by_download_token(&self, token: &str) -> AccountResult<AccountExportModel> {
+        let res = sqlx::query_as::<_, AccountExportModel>(&sql_format!(
+            "select * from {} where download_token={} and status={} and expire_time>{}",
+            AccountExportModel::table_name(),
+            token,
+            AccountExportStatus::Finish,
+            now_time()?
+        ))
+        .fetch_one(&self.db)
+        .await;
+        match res {
+            Ok(export) => Ok(export),
+            Err(sqlx::Error::RowNotFound) => Err(AccountError::System(fluent_message!(
+                "account-export-expired"
+            ))),
+            Err(err) => Err(err.into()),
+        }
+    }
+    /// 用户导出记录
+    pub async fn list_data(
+        &self,
+        account_id: u64,
+        limit: Option<&LimitParam>,
+    ) -> AccountResult<(Vec<AccountExportModel>, Option<u64>)> {
+        let where_sql = sql_format!("account_id={}", account_id);
+        let sql = if let Some(page) = limit {
+            format!(
+                "{} {} order by {} {}",
+                where_sql,
+                page.where_sql("id", Some("and")),
+                page.order_sql("id"),
+                page.limit_sql(),
+            )
+        } else {
+            format!("{} order by id desc", where_sql)
+        };
+        let mut data = sqlx::query_as::<_, AccountExportModel>(&sql_format!(
+            "select * from {} where {}",
+            AccountExportModel::table_name(),
+            SqlExpr(sql)
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        let next = limit
+            .as_ref()
+            .map(|page| page.tidy(&mut data))
+            .unwrap_or_default()
+            .map(|e| e.id);
+        Ok((data, next))
+    }
+    /// 用户导出记录数量
+    pub async fn list_count(&self, account_id: u64) -> AccountResult<i64> {
+        Ok(sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) as total from {} where account_id={}",
+            AccountExportModel::table_name(),
+            account_id
+        ))
+        .fetch_one(&self.db)
+        .await?)
+    }
+    //执行单个导出
+    async fn export_exec(
+        &self,
+        export: &AccountExportModel,
+        builder: &dyn AccountExportBuilder,
+    ) -> AccountResult<()> {
+        let build_res = match self.account.find_by_id(&export.account_id).await {
+            Ok(account) => builder
+                .build(export, &account)
+                .await
+                .map(|file| (account, file)),
+            Err(err) => Err(err.to_fluent_message().default_format()),
+        };
+        let time = now_time()?;
+        match build_res {
+            Ok((account, file)) => {
+                let status = AccountExportStatus::Finish as i8;
+                let download_token = rand_str(RandType::LowerHex, 64);
+                let expire_time = time + self.download_time;
+                let change = model_option_set!(AccountExportModelRef,{
+                    status:status,
+                    file_path:file.file_path,
+                    file_size:file.file_size,
+                    download_token:download_token,
+                    expire_time:expire_time,
+                    finish_time:time,
+                });
+                Update::<AccountExportModel, _>::new(change)
+                    .execute_by_where(
+                        &WhereOption::Where(sql_format!("id={}", export.id)),
+                        &self.db,
+                    )
+                    .await?;
+                self.logger
+                    .add(
+                        &LogAccountExport {
+                            action: "finish",
+                            message: "",
+                            account_id: export.account_id,
+                        },
+                        Some(export.id),
+                        Some(0),
+                        None,
+                        None,
+                    )
+                    .await;
+                let export = AccountExportModel {
+                    status,
+                    file_path: file.file_path,
+                    file_size: file.file_size,
+                    download_token,
+                    expire_time,
+                    finish_time: time,
+                    ..export.to_owned()
+                };
+                builder.finish_notify(&export, &account).await;
+            }
+            Err(err) => {
+                warn!("account export fail[{}]:{}", export.id, err);
+                let status = AccountExportStatus::Fail as i8;
+                let message = err.chars().take(255).collect::<String>();
+                let change = model_option_set!(AccountExportModelRef,{
+                    status:status,
+                    message:message,
+                    finish_time:time,
+                });
+                Update::<AccountExportModel, _>::new(change)
+                    .execute_by_where(
+                        &WhereOption::Where(sql_format!("id={}", export.id)),
+                        &self.db,
+                    )
+                    .await?;
+                self.logger
+                    .add(
+                        &LogAccountExport {
+                            action: "fail",
+                            message: &message,
+                            account_id: export.account_id,
+                        },
+                        Some(export.id),
+                        Some(0),
+                        None,
+                        None,
+                    )
+                    .await;
+            }
+        }
+        Ok(())
+    }
+    //删除过期的导出文件
+    async fn export_clear(&self, export: &AccountExportModel) -> AccountResult<()> {
+        if !export.file_path.is_empty() {
+            if let Err(err) = std::fs::remove_file(&export.file_path) {
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    warn!(
+                        "account export remove file fail[{}]:{}",
+                        export.file_path, err
+                    );
+                }
+            }
+        }
+        let status = AccountExportStatus::Delete as i8;
+        let change = model_option_set!(AccountExportModelRef,{
+            status:status,
+        });
+        Update::<AccountExportModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("id={}", export.id)),
+                &self.db,
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+pub struct AccountExportTask {
+    export: Arc<AccountExport>,
+    builder: Box<dyn AccountExportBuilder>,
+}
+
+impl AccountExportTask {
+    async fn find_export(&self, where_sql: String) -> Result<Vec<AccountExportModel>, String> {
+        sqlx::query_as::<_, AccountExportModel>(&sql_format!(
+            "select * from {} where {} order by id asc limit 10",
+            AccountExportModel::table_name(),
+            SqlExpr(where_sql)
+        ))
+        .fetch_all(&self.export.db)
+        .await
+        .map_err(|e| e.to_string())
+    }
+}
+
+#[async_trait::async_trait]
+impl TimeOutTaskExec for AccountExportTask {
+    async fn exec(
+        &self,
+        max_lock_time: usize,
+        mut expire_call: impl FnMut() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send,
+    ) -> Result<(), String> {
+        let mut runtime = now_time().unwrap_or_default();
+        let mut start_id = 0;
+        loop {
+            let res = self
+                .find_export(sql_format!(
+                    "status={} and id>{}",
+                    AccountExportStatus::Init,
+                    start_id
+                ))
+                .await?;
+            if res.is_empty() {
+                break;
+            }
+            for item in res {
+                start_id = item.id;
+                if let Err(err) = self.export.export_exec(&item, self.builder.as_ref()).await {
+                    warn!(
+                        "account export exec fail[{}]:{}",
+                        item.id,
+                        err.to_fluent_message().default_format()
+                    );
+                }
+                let last_now_time = now_time().unwrap_or_default();
+                if (last_now_time - runtime) * 2 > (max_lock_time as u64) {
+                    //单个导出耗时较长,超过一半时间延长一次有效期
+                    expire_call().await;
+                    runtime = last_now_time;
+                }
+            }
+        }
+        let ntime = now_time().unwrap_or_default();
+        loop {
+            let res = self
+                .find_export(sql_format!(
+                    "status={} and expire_time<={}",
+                    AccountExportStatus::Finish,
+                    ntime
+                ))
+                .await?;
+            if res.is_empty() {
+                break;
+            }
+            for item in res {
+                self.export
+                    .export_clear(&item)
+                    .await
+                    .map_err(|e| e.to_fluent_message().default_format())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TimeOutTaskNextTime for AccountExportTask {
+    async fn next_time(&self, max_lock_time: usize) -> Result<Option<u64>, String> {
+        let ntime = now_time().unwrap_or_default();
+        let timeout_res = sqlx::query_scalar::<_, u64>(&sql_format!(
+            "select if(status={},{},expire_time) as next_time from {} where status={} or (status={} and expire_time<={}) order by next_time asc limit 1",
+            AccountExportStatus::Init,
+            ntime,
+            AccountExportModel::table_name(),
+            AccountExportStatus::Init,
+            AccountExportStatus::Finish,
+            ntime + max_lock_time as u64
+        ))
+        .fetch_one(&self.export.db)
+        .await;
+        match timeout_res {
+            Ok(dat) => Ok(Some(dat)),
+            Err(sqlx::Error::RowNotFound) => Ok(None),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TimeOutTaskExecutor for AccountExportTask {
+    type Exec = Self;
+    type NextTime = Self;
+}
+
+impl AccountExport {
+    //监听导出申请并执行导出,需在独立任务中运行
+    pub async fn listen_export(
+        self: &Arc<Self>,
+        app_core: Arc<AppCore>,
+        builder: Box<dyn AccountExportBuilder>,
+        channel_buffer: Option<usize>,
+    ) {
+        info!("account export task start");
+        let task = Arc::new(AccountExportTask {
+            export: self.clone(),
+            builder,
+        });
+        TimeOutTask::<AccountExportTask>::new(
+            app_core,
+            self.timeout_notify.clone(),
+            task.clone(),
+            task,
+        )
+        .listen(channel_buffer)
+        .await;
+    }
+}
@@ -233,6 +233,7 @@ impl AccountExternal {
                     change_time:time,
                 });
 
+                let own_tran = transaction.is_none();
                 let mut db = match transaction {
                     Some(pb) => pb.begin().await?,
                     None => db.begin().await?,
@@ -277,6 +278,9 @@ impl AccountExternal {
                                 }
 
                                 db.commit().await?;
+                                if own_tran {
+                                    self.index.change_notify(account.id);
+                                }
                                 self.account_cache.clear(&account.id).await;
                                 mr.last_insert_id()
                             }
@@ -469,6 +473,7 @@ impl AccountExternal {
             status:AccountExternalStatus::Delete as i8,
             change_time:time
         });
+        let own_tran = transaction.is_none();
         let mut db = match transaction {
             Some(pb) => pb.begin().await?,
             None => self.db.begin().await?,
@@ -512,6 +517,9 @@ impl AccountExternal {
                         }
 
                         db.commit().await?;
+                        if own_tran {
+                            self.index.change_notify(account_ext.account_id);
+                        }
                         self.cache.clear(&account_ext.id).await;
                         self.account_cache.clear(&account_ext.account_id).await;
 
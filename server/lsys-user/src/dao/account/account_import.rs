@@ -0,0 +1,795 @@
+//账号批量导入及导出
+//导入数据先逐行校验,校验结果及待导入数据写入导入明细,再按批次在事务中导入
+//导入中断后再次执行从未处理的行继续,已处理的行不会重复导入
+//密码可为明文或其他系统的HASH(md5,bcrypt),其他系统的HASH在用户首次登录后转为当前方式
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use lsys_core::db::{Insert, ModelTableName, SqlExpr, SqlQuote, Update, WhereOption};
+use lsys_core::{
+    fluent_message, model_option_set, now_time, sql_format, IntoFluentMessage, LimitParam,
+    RequestEnv,
+};
+use lsys_logger::dao::ChangeLoggerDao;
+use serde::{Deserialize, Serialize};
+use sqlx::{Acquire, MySql, Pool, Transaction};
+
+use crate::model::{
+    AccountEmailStatus, AccountImportModel, AccountImportModelRef, AccountImportRowModel,
+    AccountImportRowModelRef, AccountImportRowStatus, AccountImportStatus, AccountMobileStatus,
+    AccountModel, AccountPasswordModel, AccountStatus,
+};
+
+use super::{
+    logger::LogAccountBulkExport, logger::LogAccountImport, Account, AccountEmail, AccountError, AccountMobile, AccountName,
+    AccountPassword, AccountPasswordHash, AccountPasswordLegacy, AccountResult,
+};
+
+//每批导入行数,每批一个事务
+pub const ACCOUNT_IMPORT_BATCH: usize = 100;
+
+/// 导入及导出的账号数据
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct AccountImportData {
+    pub ref_id: String, //原系统用户标识
+    pub nickname: String,
+    pub username: String,
+    pub email: Vec<String>,
+    pub mobile: Vec<String>,     //区号-手机号,如 86-13800138000
+    pub password: String,        //明文密码
+    pub password_format: String, //password_hash 的格式: native(本系统导出) md5 bcrypt
+    pub password_hash: String,
+}
+
+//校验通过后写入导入明细的数据
+#[derive(Serialize, Deserialize)]
+struct AccountImportRowData {
+    nickname: String,
+    username: String,
+    email: Vec<String>,
+    mobile: Vec<(String, String)>,
+    password: String, //存储用的密码HASH,为空不设置密码
+}
+
+//同一导入中登录名,邮箱,手机号不能重复
+#[derive(Default)]
+struct AccountImportUnique {
+    username: HashSet<String>,
+    email: HashSet<String>,
+    mobile: HashSet<String>,
+}
+
+impl AccountImportUnique {
+    fn check(set: &mut HashSet<String>, cat: &str, val: &str) -> AccountResult<()> {
+        if !set.insert(val.to_owned()) {
+            return Err(AccountError::System(
+                fluent_message!("account-import-duplicate",{
+                    "type":cat,
+                    "value":val
+                }),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 导出的账号
+pub struct AccountImportExportItem {
+    pub account: AccountModel,
+    pub data: AccountImportData,
+}
+
+pub struct AccountImport {
+    db: Pool<MySql>,
+    account: Arc<Account>,
+    account_name: Arc<AccountName>,
+    account_email: Arc<AccountEmail>,
+    account_mobile: Arc<AccountMobile>,
+    account_password: Arc<AccountPassword>,
+    password_hash: Arc<AccountPasswordHash>,
+    logger: Arc<ChangeLoggerDao>,
+}
+
+impl AccountImport {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        db: Pool<MySql>,
+        account: Arc<Account>,
+        account_name: Arc<AccountName>,
+        account_email: Arc<AccountEmail>,
+        account_mobile: Arc<AccountMobile>,
+        account_password: Arc<AccountPassword>,
+        password_hash: Arc<AccountPasswordHash>,
+        logger: Arc<ChangeLoggerDao>,
+    ) -> Self {
+        Self {
+            db,
+            account,
+            account_name,
+            account_email,
+            account_mobile,
+            account_password,
+            password_hash,
+            logger,
+        }
+    }
+    lsys_core::impl_dao_fetch_one_by_one!(
+        db,
+        find_by_id,
+        u64,
+        AccountImportModel,
+        AccountResult<AccountImportModel>,
+        id,
+        "id = {id}"
+    );
+    //校验单行数据,返回待导入数据
+    async fn import_check(
+        &self,
+        item: &AccountImportData,
+        unique: &mut AccountImportUnique,
+    ) -> AccountResult<AccountImportRowData> {
+        let username = item.username.trim().to_owned();
+        if !username.is_empty() {
+            self.account_name.name_param_valid(&username).await?;
+            AccountImportUnique::check(&mut unique.username, "username", &username)?;
+            if let Ok(tmp) = self.account_name.find_by_name(&username).await {
+                return Err(AccountError::System(
+                    fluent_message!("account-import-exists",{
+                        "type":"username",
+                        "value":&username,
+                        "id":tmp.account_id
+                    }),
+                ));
+            }
+        }
+        let mut email = Vec::with_capacity(item.email.len());
+        for tmp in item
+            .email
+            .iter()
+            .map(|e| e.trim())
+            .filter(|e| !e.is_empty())
+        {
+            self.account_email.email_param_valid(tmp).await?;
+            AccountImportUnique::check(&mut unique.email, "email", &tmp.to_lowercase())?;
+            if let Ok(res) = self.account_email.find_by_last_email(tmp).await {
+                return Err(AccountError::System(
+                    fluent_message!("account-import-exists",{
+                        "type":"email",
+                        "value":tmp,
+                        "id":res.account_id
+                    }),
+                ));
+            }
+            email.push(tmp.to_owned());
+        }
+        let mut mobile = Vec::with_capacity(item.mobile.len());
+        for tmp in item
+            .mobile
+            .iter()
+            .map(|e| e.trim())
+            .filter(|e| !e.is_empty())
+        {
+            let (area_code, mobile_num) = match tmp.split_once('-') {
+                Some((a, m)) => (a.trim().trim_start_matches('+'), m.trim()),
+                None => {
+                    return Err(AccountError::System(
+                        fluent_message!("account-import-bad-mobile",{
+                            "mobile":tmp
+                        }),
+                    ))
+                }
+            };
+            self.account_mobile
+                .mobile_param_valid(area_code, mobile_num)
+                .await?;
+            AccountImportUnique::check(
+                &mut unique.mobile,
+                "mobile",
+                &format!("{}-{}", area_code, mobile_num),
+            )?;
+            if let Ok(res) = self
+                .account_mobile
+                .find_by_last_mobile(area_code, mobile_num)
+                .await
+            {
+                return Err(AccountError::System(
+                    fluent_message!("account-import-exists",{
+                        "type":"mobile",
+                        "value":tmp,
+                        "id":res.account_id
+                    }),
+                ));
+            }
+            mobile.push((area_code.to_owned(), mobile_num.to_owned()));
+        }
+        //未提供昵称时使用登录名或邮箱前缀
+        let mut nickname = item.nickname.trim().to_owned();
+        if nickname.is_empty() {
+            nickname = if !username.is_empty() {
+                username.to_owned()
+            } else {
+                email
+                    .first()
+                    .and_then(|e| e.split_once('@'))
+                    .map(|e| e.0.to_owned())
+                    .unwrap_or_default()
+            };
+        }
+        self.account.nickname_param_valid(&nickname).await?;
+        //导入的密码不做强度检测,保证用户可继续使用原密码
+        let password = if !item.password.is_empty() {
+            self.password_hash.hash_password(&item.password).await
+        } else if !item.password_hash.is_empty() {
+            match item.password_format.as_str() {
+                "native" | "" => item.password_hash.to_owned(),
+                format => AccountPasswordLegacy::parse(format, &item.password_hash)
+                    .map(|e| e.encode())
+                    .ok_or_else(|| {
+                        AccountError::System(fluent_message!("account-import-bad-password",{
+                            "format":format
+                        }))
+                    })?,
+            }
+        } else {
+            "".to_string()
+        };
+        if password.len() > 150 {
+            return Err(AccountError::System(
+                fluent_message!("account-import-bad-password",{
+                    "format":&item.password_format
+                }),
+            ));
+        }
+        Ok(AccountImportRowData {
+            nickname,
+            username,
+            email,
+            mobile,
+            password,
+        })
+    }
+    /// 添加导入,校验全部数据并写入导入明细
+    /// 校验失败的行直接标记为失败,不影响其他行导入
+    pub async fn import_add(
+        &self,
+        name: &str,
+        data: &[AccountImportData],
+        confirm: bool,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<AccountImportModel> {
+        if data.is_empty() {
+            return Err(AccountError::System(fluent_message!(
+                "account-import-empty"
+            )));
+        }
+        let time = now_time()?;
+        let mut unique = AccountImportUnique::default();
+        let mut rows = Vec::with_capacity(data.len());
+        for (i, item) in data.iter().enumerate() {
+            let (row_data, status, message) = match self.import_check(item, &mut unique).await {
+                Ok(row) => (
+                    serde_json::to_string(&row)?,
+                    AccountImportRowStatus::Init as i8,
+                    "".to_string(),
+                ),
+                Err(err) => (
+                    "".to_string(),
+                    AccountImportRowStatus::Fail as i8,
+                    err.to_fluent_message().default_format(),
+                ),
+            };
+            let ref_id = item.ref_id.chars().take(64).collect::<String>();
+            let message = message.chars().take(255).collect::<String>();
+            rows.push((i as u32 + 1, ref_id, row_data, status, message));
+        }
+        let name = name.chars().take(64).collect::<String>();
+        let confirm = if confirm { 1 } else { 0 };
+        let total_num = rows.len() as u32;
+        let fail_num = rows
+            .iter()
+            .filter(|e| AccountImportRowStatus::Fail.eq(e.3))
+            .count() as u32;
+        let status = if fail_num == total_num {
+            AccountImportStatus::Finish as i8
+        } else {
+            AccountImportStatus::Init as i8
+        };
+        let mut db = self.db.begin().await?;
+        let vdata = model_option_set!(AccountImportModelRef,{
+            name:name,
+            confirm:confirm,
+            status:status,
+            total_num:total_num,
+            fail_num:fail_num,
+            add_user_id:op_user_id,
+            add_time:time,
+        });
+        let import_id = match Insert::<AccountImportModel, _>::new(vdata)
+            .execute(&mut *db)
+            .await
+        {
+            Ok(res) => res.last_insert_id(),
+            Err(err) => {
+                db.rollback().await?;
+                return Err(err.into());
+            }
+        };
+        for chunk in rows.chunks(ACCOUNT_IMPORT_BATCH) {
+            let vdata = chunk
+                .iter()
+                .map(|e| {
+                    model_option_set!(AccountImportRowModelRef,{
+                        import_id:import_id,
+                        row_num:e.0,
+                        ref_id:e.1,
+                        data:e.2,
+                        status:e.3,
+                        message:e.4,
+                        change_time:time,
+                    })
+                })
+                .collect::<Vec<_>>();
+            if let Err(err) = Insert::<AccountImportRowModel, _>::new_vec(vdata)
+                .execute(&mut *db)
+                .await
+            {
+                db.rollback().await?;
+                return Err(err.into());
+            }
+        }
+        db.commit().await?;
+        self.logger
+            .add(
+                &LogAccountImport {
+                    action: "add",
+                    name: &name,
+                    total_num,
+                    succ_num: 0,
+                    fail_num,
+                },
+                Some(import_id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        self.find_by_id(&import_id).await
+    }
+    //导入单行,在传入的事务中执行
+    async fn import_row(
+        &self,
+        data: &AccountImportRowData,
+        confirm: bool,
+        op_user_id: u64,
+        tran: &mut Transaction<'_, sqlx::MySql>,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<u64> {
+        let account = self
+            .account
+            .add(&data.nickname, op_user_id, Some(&mut *tran), env_data)
+            .await?;
+        self.account
+            .enable(&account, op_user_id, Some(&mut *tran), env_data)
+            .await?;
+        if !data.username.is_empty() {
+            self.account_name
+                .change_account_name(
+                    &account,
+                    &data.username,
+                    op_user_id,
+                    Some(&mut *tran),
+                    env_data,
+                )
+                .await?;
+        }
+        for email in data.email.iter() {
+            self.account_email
+                .add_email(
+                    &account,
+                    email,
+                    if confirm {
+                        AccountEmailStatus::Valid
+                    } else {
+                        AccountEmailStatus::Init
+                    },
+                    op_user_id,
+                    Some(&mut *tran),
+                    env_data,
+                )
+                .await?;
+        }
+        for (area_code, mobile) in data.mobile.iter() {
+            self.account_mobile
+                .add_mobile(
+                    &account,
+                    area_code,
+                    mobile,
+                    if confirm {
+                        AccountMobileStatus::Valid
+                    } else {
+                        AccountMobileStatus::Init
+                    },
+                    op_user_id,
+                    Some(&mut *tran),
+                    env_data,
+                )
+                .await?;
+        }
+        if !data.password.is_empty() {
+            self.account_password
+                .set_passwrod_hash(&account, &data.password, op_user_id, tran, env_data)
+                .await?;
+        }
+        Ok(account.id)
+    }
+    //导入一批待导入的行,无待导入行时返回false
+    async fn import_batch(
+        &self,
+        import: &AccountImportModel,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<bool> {
+        let mut db = self.db.begin().await?;
+        //锁定导入记录,同一导入同时只有一个批次在执行
+        sqlx::query(&sql_format!(
+            "select id from {} where id={} for update",
+            AccountImportModel::table_name(),
+            import.id
+        ))
+        .execute(&mut *db)
+        .await?;
+        let rows = sqlx::query_as::<_, AccountImportRowModel>(&sql_format!(
+            "select * from {} where import_id={} and status={} order by row_num asc limit {}",
+            AccountImportRowModel::table_name(),
+            import.id,
+            AccountImportRowStatus::Init,
+            ACCOUNT_IMPORT_BATCH
+        ))
+        .fetch_all(&mut *db)
+        .await?;
+        if rows.is_empty() {
+            db.commit().await?;
+            return Ok(false);
+        }
+        let confirm = import.confirm == 1;
+        let mut account_ids = vec![];
+        for row in rows {
+            let res = match serde_json::from_str::<AccountImportRowData>(&row.data) {
+                Ok(data) => {
+                    //每行使用独立的保存点,失败仅回滚当前行
+                    let mut row_db = Acquire::begin(&mut db).await?;
+                    match self
+                        .import_row(&data, confirm, op_user_id, &mut row_db, env_data)
+                        .await
+                    {
+                        Ok(account_id) => {
+                            row_db.commit().await?;
+                            account_ids.push(account_id);
+                            Ok(account_id)
+                        }
+                        Err(err) => {
+                            row_db.rollback().await?;
+                            Err(err)
+                        }
+                    }
+                }
+                Err(err) => Err(err.into()),
+            };
+            let time = now_time()?;
+            //导入成功后清除明细中的待导入数据,避免密码HASH等数据长期保留
+            let (status, account_id, message, data) = match res {
+                Ok(account_id) => (
+                    AccountImportRowStatus::Succ as i8,
+                    account_id,
+                    "".to_string(),
+                    "".to_string(),
+                ),
+                Err(err) => (
+                    AccountImportRowStatus::Fail as i8,
+                    0,
+                    err.to_fluent_message()
+                        .default_format()
+                        .chars()
+                        .take(255)
+                        .collect::<String>(),
+                    row.data,
+                ),
+            };
+            let change = model_option_set!(AccountImportRowModelRef,{
+                status:status,
+                account_id:account_id,
+                message:message,
+                data:data,
+                change_time:time,
+            });
+            Update::<AccountImportRowModel, _>::new(change)
+                .execute_by_where(&WhereOption::Where(sql_format!("id={}", row.id)), &mut *db)
+                .await?;
+        }
+        db.commit().await?;
+        for account_id in account_ids {
+            self.account.search_notify(account_id);
+        }
+        Ok(true)
+    }
+    /// 执行导入,最多执行 max_batch 批
+    /// 可重复调用直到导入完成,中断后调用会继续导入未处理的行
+    pub async fn import_run(
+        &self,
+        import: &AccountImportModel,
+        max_batch: u64,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<AccountImportModel> {
+        if AccountImportStatus::Finish.eq(import.status) {
+            return Ok(import.to_owned());
+        }
+        let mut is_finish = false;
+        for _ in 0..max_batch.max(1) {
+            if !self.import_batch(import, op_user_id, env_data).await? {
+                is_finish = true;
+                break;
+            }
+        }
+        let count = sqlx::query_as::<_, (i8, i64)>(&sql_format!(
+            "select status,count(*) as total from {} where import_id={} group by status",
+            AccountImportRowModel::table_name(),
+            import.id
+        ))
+        .fetch_all(&self.db)
+        .await?
+        .into_iter()
+        .collect::<HashMap<i8, i64>>();
+        let succ_num = count
+            .get(&(AccountImportRowStatus::Succ as i8))
+            .copied()
+            .unwrap_or(0) as u32;
+        let fail_num = count
+            .get(&(AccountImportRowStatus::Fail as i8))
+            .copied()
+            .unwrap_or(0) as u32;
+        if !count.contains_key(&(AccountImportRowStatus::Init as i8)) {
+            is_finish = true;
+        }
+        let status = if is_finish {
+            AccountImportStatus::Finish as i8
+        } else {
+            AccountImportStatus::Init as i8
+        };
+        let time = now_time()?;
+        let change = model_option_set!(AccountImportModelRef,{
+            status:status,
+            succ_num:succ_num,
+            fail_num:fail_num,
+            change_time:time,
+        });
+        Update::<AccountImportModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("id={}", import.id)),
+                &self.db,
+            )
+            .await?;
+        if is_finish {
+            self.logger
+                .add(
+                    &LogAccountImport {
+                        action: "finish",
+                        name: &import.name,
+                        total_num: import.total_num,
+                        succ_num,
+                        fail_num,
+                    },
+                    Some(import.id),
+                    Some(op_user_id),
+                    None,
+                    env_data,
+                )
+                .await;
+        }
+        self.find_by_id(&import.id).await
+    }
+    /// 导入列表
+    pub async fn import_list_data(
+        &self,
+        limit: Option<&LimitParam>,
+    ) -> AccountResult<(Vec<AccountImportModel>, Option<u64>)> {
+        let sql = if let Some(page) = limit {
+            format!(
+                "{} order by {} {}",
+                page.where_sql("id", None),
+                page.order_sql("id"),
+                page.limit_sql(),
+            )
+        } else {
+            "1 order by id desc".to_string()
+        };
+        let mut data = sqlx::query_as::<_, AccountImportModel>(&sql_format!(
+            "select * from {} where {}",
+            AccountImportModel::table_name(),
+            SqlExpr(sql)
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        let next = limit
+            .as_ref()
+            .map(|page| page.tidy(&mut data))
+            .unwrap_or_default()
+            .map(|e| e.id);
+        Ok((data, next))
+    }
+    /// 导入数量
+    pub async fn import_list_count(&self) -> AccountResult<i64> {
+        Ok(sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) as total from {}",
+            AccountImportModel::table_name(),
+        ))
+        .fetch_one(&self.db)
+        .await?)
+    }
+    fn row_where(&self, import_id: u64, status: Option<AccountImportRowStatus>) -> String {
+        match status {
+            Some(s) => sql_format!("import_id={} and status={}", import_id, s),
+            None => sql_format!("import_id={}", import_id),
+        }
+    }
+    /// 导入明细列表,可用于获取失败行及原系统用户对应的新用户ID
+    pub async fn row_list_data(
+        &self,
+        import_id: u64,
+        status: Option<AccountImportRowStatus>,
+        limit: Option<&LimitParam>,
+    ) -> AccountResult<(Vec<AccountImportRowModel>, Option<u64>)> {
+        let where_sql = self.row_where(import_id, status);
+        let sql = if let Some(page) = limit {
+            format!(
+                "{} {} order by {} {}",
+                where_sql,
+                page.where_sql("id", Some("and")),
+                page.order_sql("id"),
+                page.limit_sql(),
+            )
+        } else {
+            format!("{} order by id asc", where_sql)
+        };
+        let mut data = sqlx::query_as::<_, AccountImportRowModel>(&sql_format!(
+            "select * from {} where {}",
+            AccountImportRowModel::table_name(),
+            SqlExpr(sql)
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        let next = limit
+            .as_ref()
+            .map(|page| page.tidy(&mut data))
+            .unwrap_or_default()
+            .map(|e| e.id);
+        Ok((data, next))
+    }
+    /// 导入明细数量
+    pub async fn row_list_count(
+        &self,
+        import_id: u64,
+        status: Option<AccountImportRowStatus>,
+    ) -> AccountResult<i64> {
+        Ok(sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) as total from {} where {}",
+            AccountImportRowModel::table_name(),
+            SqlExpr(self.row_where(import_id, status))
+        ))
+        .fetch_one(&self.db)
+        .await?)
+    }
+    /// 按ID顺序导出账号,格式与导入一致
+    /// with_password 为真时导出密码HASH,可用于备份后导入
+    /// 每次导出记录日志
+    pub async fn export_data(
+        &self,
+        start_id: u64,
+        limit: u64,
+        with_password: bool,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<(Vec<AccountImportExportItem>, Option<u64>)> {
+        let limit = limit.clamp(1, 1000);
+        let account = sqlx::query_as::<_, AccountModel>(&sql_format!(
+            "select * from {} where id>{} and status in ({}) order by id asc limit {}",
+            AccountModel::table_name(),
+            start_id,
+            [AccountStatus::Enable as i8, AccountStatus::Init as i8],
+            limit
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        if account.is_empty() {
+            return Ok((vec![], None));
+        }
+        let ids = account.iter().map(|e| e.id).collect::<Vec<_>>();
+        let mut name = self.account_name.find_by_account_ids(&ids).await?;
+        let mut email = self
+            .account_email
+            .cache()
+            .find_by_account_ids_vec(&ids)
+            .await?;
+        let mut mobile = self
+            .account_mobile
+            .cache()
+            .find_by_account_ids_vec(&ids)
+            .await?;
+        let mut password = if with_password {
+            let pids = account
+                .iter()
+                .filter(|e| e.password_id > 0)
+                .map(|e| e.password_id)
+                .collect::<Vec<_>>();
+            if pids.is_empty() {
+                HashMap::new()
+            } else {
+                sqlx::query_as::<_, AccountPasswordModel>(&sql_format!(
+                    "select * from {} where id in ({})",
+                    AccountPasswordModel::table_name(),
+                    pids
+                ))
+                .fetch_all(&self.db)
+                .await?
+                .into_iter()
+                .map(|e| (e.account_id, e.password))
+                .collect::<HashMap<_, _>>()
+            }
+        } else {
+            HashMap::new()
+        };
+        let next = if account.len() as u64 >= limit {
+            account.last().map(|e| e.id)
+        } else {
+            None
+        };
+        self.logger
+            .add(
+                &LogAccountBulkExport {
+                    start_id,
+                    end_id: account.last().map(|e| e.id).unwrap_or_default(),
+                    total_num: account.len() as u32,
+                    with_password,
+                },
+                None,
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        let mut out = Vec::with_capacity(account.len());
+        for tmp in account {
+            let (password_format, password_hash) = match password.remove(&tmp.id) {
+                Some(pw) => match AccountPasswordLegacy::decode(&pw) {
+                    Some(legacy) => (legacy.format().to_string(), legacy.hash()),
+                    None => ("native".to_string(), pw),
+                },
+                None => ("".to_string(), "".to_string()),
+            };
+            let data = AccountImportData {
+                ref_id: tmp.id.to_string(),
+                nickname: tmp.nickname.to_owned(),
+                username: name.remove(&tmp.id).map(|e| e.username).unwrap_or_default(),
+                email: email
+                    .remove(&tmp.id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|e| e.email)
+                    .collect(),
+                mobile: mobile
+                    .remove(&tmp.id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|e| format!("{}-{}", e.area_code, e.mobile))
+                    .collect(),
+                password: "".to_string(),
+                password_format,
+                password_hash,
+            };
+            out.push(AccountImportExportItem { account: tmp, data });
+        }
+        Ok((out, next))
+    }
+}
@@ -8,13 +8,20 @@ use super::AccountResult;
 use lsys_core::db::{Insert, ModelTableName, SqlExpr, SqlQuote, Update};
 use lsys_core::{db_option_executor, model_option_set, sql_format};
 use sqlx::{Acquire, MySql, Pool, Transaction};
+use tokio::sync::mpsc::UnboundedSender;
 pub struct AccountIndex {
     db: Pool<MySql>,
+    change_tx: UnboundedSender<u64>, //索引变动通知,用于更新全文搜索索引
 }
 
 impl AccountIndex {
-    pub fn new(db: Pool<MySql>) -> Self {
-        Self { db }
+    pub fn new(db: Pool<MySql>, change_tx: UnboundedSender<u64>) -> Self {
+        Self { db, change_tx }
+    }
+    //索引变动通知,需在修改数据的事务提交后调用,避免读取到未提交的数据
+    pub(crate) fn change_notify(&self, account_id: u64) {
+        //接收端未启动时忽略
+        let _ = self.change_tx.send(account_id);
     }
     //一个用户一个类型只能有一个记录
     pub async fn cat_one_add(
@@ -115,6 +115,7 @@ impl AccountInfo {
         .fetch_one(&self.db)
         .await;
 
+        let own_tran = transaction.is_none();
         let mut db = match transaction {
             Some(pb) => pb.begin().await?,
             None => self.db.begin().await?,
@@ -161,6 +162,9 @@ impl AccountInfo {
             return Err(ie.into());
         };
         db.commit().await?;
+        if own_tran {
+            self.index.change_notify(account.id);
+        }
         self.cache.clear(&account.id).await;
 
         let reg_from = info.reg_from.map(|e| e.to_string()).unwrap_or_default();
@@ -140,6 +140,7 @@ impl AccountLoginHistory {
         login_type: &str,
         login_ip: &str,
         login_city: &str,
+        login_country: &str,
     ) -> AccountResult<u64> {
         let time = now_time()?;
         let login_account = string_clear(
@@ -158,6 +159,11 @@ impl AccountLoginHistory {
             StringClear::Option(STRING_CLEAR_FORMAT),
             Some(100),
         );
+        let login_country = string_clear(
+            login_country,
+            StringClear::Option(STRING_CLEAR_FORMAT),
+            Some(8),
+        );
         let new_data = model_option_set!(AccountLoginModelRef,{
             login_type:login_type,
             login_account:login_account,
@@ -165,6 +171,7 @@ impl AccountLoginHistory {
             account_id: 0,
             is_login: 0,
             login_city:login_city,
+            login_country:login_country,
             add_time: time,
         });
         let login_res = Insert::<AccountLoginModel, _>::new(new_data)
@@ -0,0 +1,641 @@
+//重复账号合并
+//把来源账号的邮箱、手机、外部账号、收货地址、用户名、密码及登录记录移到保留账号,并删除来源账号
+//冲突规则:
+//  邮箱、手机、外部账号: 保留账号已存在相同数据时,删除来源账号上的该条记录
+//  用户名、密码: 保留账号已存在时保留不变,否则使用来源账号的
+//  昵称、资料: 保持保留账号的不变
+//  个人访问令牌: 来源账号的令牌全部作废
+use std::sync::Arc;
+
+use lsys_core::db::{ModelTableName, SqlQuote, Update, WhereOption};
+use lsys_core::{fluent_message, model_option_set, now_time, sql_format, RequestEnv};
+use lsys_logger::dao::ChangeLoggerDao;
+use serde::Serialize;
+use sqlx::{MySql, MySqlConnection, Pool, Transaction};
+
+use crate::model::{
+    AccountAddressModel, AccountAddressModelRef, AccountAddressStatus, AccountEmailModel,
+    AccountEmailModelRef, AccountEmailStatus, AccountExternalModel, AccountExternalModelRef,
+    AccountExternalStatus, AccountIndexCat, AccountLoginModel, AccountMobileModel,
+    AccountMobileModelRef, AccountMobileStatus, AccountModel, AccountModelRef, AccountNameModel,
+    AccountNameModelRef, AccountNameStatus, AccountPasswordModel, AccountPasswordModelRef,
+    AccountStatus, AccountTokenModel, AccountTokenModelRef, AccountTokenStatus,
+};
+
+use super::logger::LogAccountMerge;
+use super::{
+    Account, AccountAddress, AccountEmail, AccountError, AccountExternal, AccountIndex,
+    AccountMobile, AccountName, AccountResult, AccountToken,
+};
+
+/// 合并预览,dry-run 及合并结果均返回此数据
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct AccountMergePreview {
+    pub email_move: Vec<String>,
+    pub email_conflict: Vec<String>,
+    pub mobile_move: Vec<String>,
+    pub mobile_conflict: Vec<String>,
+    pub external_move: Vec<String>,
+    pub external_conflict: Vec<String>,
+    pub address_move: u64,
+    pub username_move: Option<String>,
+    pub username_drop: Option<String>,
+    pub password_move: bool,
+    pub login_history_move: i64,
+    pub token_revoke: i64,
+}
+
+//合并计划,记录需处理的数据
+struct AccountMergePlan {
+    email_move: Vec<AccountEmailModel>,
+    email_conflict: Vec<AccountEmailModel>,
+    mobile_move: Vec<AccountMobileModel>,
+    mobile_conflict: Vec<AccountMobileModel>,
+    external_move: Vec<AccountExternalModel>,
+    external_conflict: Vec<AccountExternalModel>,
+    address_move: Vec<AccountAddressModel>,
+    name_move: Option<AccountNameModel>,
+    name_drop: Option<AccountNameModel>,
+    password_move: bool,
+    login_history_move: i64,
+    token_revoke: Vec<AccountTokenModel>,
+}
+
+/// 合并结果,调用方事务提交后传入 AccountMerge::merge_finish
+pub struct AccountMergeResult {
+    target_account_id: u64,
+    source: AccountModel,
+    plan: AccountMergePlan,
+}
+
+impl AccountMergeResult {
+    pub fn preview(&self) -> AccountMergePreview {
+        self.plan.preview()
+    }
+}
+
+impl AccountMergePlan {
+    fn preview(&self) -> AccountMergePreview {
+        AccountMergePreview {
+            email_move: self.email_move.iter().map(|e| e.email.clone()).collect(),
+            email_conflict: self
+                .email_conflict
+                .iter()
+                .map(|e| e.email.clone())
+                .collect(),
+            mobile_move: self
+                .mobile_move
+                .iter()
+                .map(|e| format!("{}-{}", e.area_code, e.mobile))
+                .collect(),
+            mobile_conflict: self
+                .mobile_conflict
+                .iter()
+                .map(|e| format!("{}-{}", e.area_code, e.mobile))
+                .collect(),
+            external_move: self
+                .external_move
+                .iter()
+                .map(|e| format!("{}:{}", e.external_type, e.external_name))
+                .collect(),
+            external_conflict: self
+                .external_conflict
+                .iter()
+                .map(|e| format!("{}:{}", e.external_type, e.external_name))
+                .collect(),
+            address_move: self.address_move.len() as u64,
+            username_move: self.name_move.as_ref().map(|e| e.username.clone()),
+            username_drop: self.name_drop.as_ref().map(|e| e.username.clone()),
+            password_move: self.password_move,
+            login_history_move: self.login_history_move,
+            token_revoke: self.token_revoke.len() as i64,
+        }
+    }
+}
+
+pub struct AccountMerge {
+    db: Pool<MySql>,
+    account: Arc<Account>,
+    index: Arc<AccountIndex>,
+    email: Arc<AccountEmail>,
+    mobile: Arc<AccountMobile>,
+    external: Arc<AccountExternal>,
+    address: Arc<AccountAddress>,
+    name: Arc<AccountName>,
+    token: Arc<AccountToken>,
+    logger: Arc<ChangeLoggerDao>,
+}
+
+impl AccountMerge {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        db: Pool<MySql>,
+        account: Arc<Account>,
+        index: Arc<AccountIndex>,
+        email: Arc<AccountEmail>,
+        mobile: Arc<AccountMobile>,
+        external: Arc<AccountExternal>,
+        address: Arc<AccountAddress>,
+        name: Arc<AccountName>,
+        token: Arc<AccountToken>,
+        logger: Arc<ChangeLoggerDao>,
+    ) -> Self {
+        Self {
+            db,
+            account,
+            index,
+            email,
+            mobile,
+            external,
+            address,
+            name,
+            token,
+            logger,
+        }
+    }
+    fn merge_check(&self, target: &AccountModel, source: &AccountModel) -> AccountResult<()> {
+        if target.id == source.id {
+            return Err(AccountError::System(fluent_message!("account-merge-same")));
+        }
+        for account in [target, source] {
+            if AccountStatus::Delete.eq(account.status) {
+                return Err(AccountError::System(fluent_message!("account-is-delete",{
+                    "user":&account.nickname
+                })));
+            }
+        }
+        Ok(())
+    }
+    //读取需处理的数据,合并时在同一事务中读取
+    async fn merge_plan(
+        &self,
+        target: &AccountModel,
+        source: &AccountModel,
+        db: &mut MySqlConnection,
+    ) -> AccountResult<AccountMergePlan> {
+        let account_ids = [target.id, source.id];
+        let (target_email, source_email): (Vec<_>, Vec<_>) =
+            sqlx::query_as::<_, AccountEmailModel>(&sql_format!(
+                "select * from {} where account_id in ({}) and status in ({})",
+                AccountEmailModel::table_name(),
+                account_ids,
+                [
+                    AccountEmailStatus::Init as i8,
+                    AccountEmailStatus::Valid as i8
+                ]
+            ))
+            .fetch_all(&mut *db)
+            .await?
+            .into_iter()
+            .partition(|e| e.account_id == target.id);
+        let (email_conflict, email_move) = source_email.into_iter().partition(|e| {
+            target_email
+                .iter()
+                .any(|t| t.email.eq_ignore_ascii_case(&e.email))
+        });
+        let (target_mobile, source_mobile): (Vec<_>, Vec<_>) =
+            sqlx::query_as::<_, AccountMobileModel>(&sql_format!(
+                "select * from {} where account_id in ({}) and status in ({})",
+                AccountMobileModel::table_name(),
+                account_ids,
+                [
+                    AccountMobileStatus::Init as i8,
+                    AccountMobileStatus::Valid as i8
+                ]
+            ))
+            .fetch_all(&mut *db)
+            .await?
+            .into_iter()
+            .partition(|e| e.account_id == target.id);
+        let (mobile_conflict, mobile_move) = source_mobile.into_iter().partition(|e| {
+            target_mobile
+                .iter()
+                .any(|t| t.area_code == e.area_code && t.mobile == e.mobile)
+        });
+        let (target_external, source_external): (Vec<_>, Vec<_>) =
+            sqlx::query_as::<_, AccountExternalModel>(&sql_format!(
+                "select * from {} where account_id in ({}) and status={}",
+                AccountExternalModel::table_name(),
+                account_ids,
+                AccountExternalStatus::Enable as i8
+            ))
+            .fetch_all(&mut *db)
+            .await?
+            .into_iter()
+            .partition(|e| e.account_id == target.id);
+        let (external_conflict, external_move) = source_external.into_iter().partition(|e| {
+            target_external.iter().any(|t| {
+                t.config_name == e.config_name
+                    && t.external_type == e.external_type
+                    && t.external_id == e.external_id
+            })
+        });
+        let address_move = sqlx::query_as::<_, AccountAddressModel>(&sql_format!(
+            "select * from {} where account_id={} and status={}",
+            AccountAddressModel::table_name(),
+            source.id,
+            AccountAddressStatus::Enable
+        ))
+        .fetch_all(&mut *db)
+        .await?;
+
+        let name_data = sqlx::query_as::<_, AccountNameModel>(&sql_format!(
+            "select * from {} where account_id in ({}) order by id desc",
+            AccountNameModel::table_name(),
+            account_ids,
+        ))
+        .fetch_all(&mut *db)
+        .await?;
+        //用户名表 account_id 唯一,保留账号存在记录时不转移
+        let target_has_name = name_data.iter().any(|e| e.account_id == target.id);
+        let source_name = name_data
+            .into_iter()
+            .find(|e| e.account_id == source.id)
+            .filter(|e| AccountNameStatus::Enable.eq(e.status));
+        let (name_move, name_drop) = if target_has_name {
+            (None, source_name)
+        } else {
+            (source_name, None)
+        };
+
+        let login_history_move = sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) from {} where account_id={}",
+            AccountLoginModel::table_name(),
+            source.id
+        ))
+        .fetch_one(&mut *db)
+        .await?;
+        let token_revoke = sqlx::query_as::<_, AccountTokenModel>(&sql_format!(
+            "select * from {} where account_id={} and status={}",
+            AccountTokenModel::table_name(),
+            source.id,
+            AccountTokenStatus::Enable
+        ))
+        .fetch_all(&mut *db)
+        .await?;
+        Ok(AccountMergePlan {
+            email_move,
+            email_conflict,
+            mobile_move,
+            mobile_conflict,
+            external_move,
+            external_conflict,
+            address_move,
+            name_move,
+            name_drop,
+            password_move: target.password_id == 0 && source.password_id > 0,
+            login_history_move,
+            token_revoke,
+        })
+    }
+    /// 合并预览,不修改数据
+    pub async fn merge_preview(
+        &self,
+        target: &AccountModel,
+        source: &AccountModel,
+    ) -> AccountResult<AccountMergePreview> {
+        self.merge_check(target, source)?;
+        let mut db = self.db.acquire().await?;
+        Ok(self.merge_plan(target, source, &mut db).await?.preview())
+    }
+    /// 合并账号,source 合并到 target 后删除 source
+    /// 在调用方事务中执行,事务提交后需调用 merge_finish 清理缓存并记录日志
+    pub async fn merge(
+        &self,
+        target: &AccountModel,
+        source: &AccountModel,
+        db: &mut Transaction<'_, sqlx::MySql>,
+    ) -> AccountResult<AccountMergeResult> {
+        self.merge_check(target, source)?;
+        let plan = self.merge_plan(target, source, db).await?;
+        self.merge_tran(target, source, &plan, db).await?;
+        self.account.del_tran(source, None, db).await?;
+        Ok(AccountMergeResult {
+            target_account_id: target.id,
+            source: source.to_owned(),
+            plan,
+        })
+    }
+    /// 合并事务提交后清理缓存并记录日志
+    pub async fn merge_finish(
+        &self,
+        result: &AccountMergeResult,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) {
+        let plan = &result.plan;
+        self.account
+            .del_finish(&result.source, op_user_id, env_data)
+            .await;
+        for account_id in [result.target_account_id, result.source.id] {
+            self.index.change_notify(account_id);
+            self.account.cache.clear(&account_id).await;
+            self.email.account_cache.clear(&account_id).await;
+            self.mobile.account_cache.clear(&account_id).await;
+            self.external.account_cache.clear(&account_id).await;
+            self.address.cache.clear(&account_id).await;
+            self.name.cache.clear(&account_id).await;
+        }
+        for tmp in plan.email_move.iter().chain(plan.email_conflict.iter()) {
+            self.email.cache.clear(&tmp.id).await;
+        }
+        for tmp in plan.mobile_move.iter().chain(plan.mobile_conflict.iter()) {
+            self.mobile.cache.clear(&tmp.id).await;
+        }
+        for tmp in plan
+            .external_move
+            .iter()
+            .chain(plan.external_conflict.iter())
+        {
+            self.external.cache.clear(&tmp.id).await;
+        }
+        for tmp in plan.token_revoke.iter() {
+            self.token.cache.clear(&tmp.session_id).await;
+        }
+        self.logger
+            .add(
+                &LogAccountMerge {
+                    action: "merge",
+                    target_account_id: result.target_account_id,
+                    source_account_id: result.source.id,
+                    preview: &plan.preview(),
+                },
+                Some(result.target_account_id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+    }
+    async fn merge_tran(
+        &self,
+        target: &AccountModel,
+        source: &AccountModel,
+        plan: &AccountMergePlan,
+        db: &mut Transaction<'_, sqlx::MySql>,
+    ) -> AccountResult<()> {
+        let time = now_time()?;
+
+        let ids = plan.email_conflict.iter().map(|e| e.id).collect::<Vec<_>>();
+        if !ids.is_empty() {
+            let change = model_option_set!(AccountEmailModelRef,{
+                status:AccountEmailStatus::Delete as i8,
+                change_time:time,
+            });
+            Update::<AccountEmailModel, _>::new(change)
+                .execute_by_where(
+                    &WhereOption::Where(sql_format!("id in ({})", ids)),
+                    &mut **db,
+                )
+                .await?;
+        }
+        let ids = plan.email_move.iter().map(|e| e.id).collect::<Vec<_>>();
+        if !ids.is_empty() {
+            let change = model_option_set!(AccountEmailModelRef,{
+                account_id:target.id,
+                change_time:time,
+            });
+            Update::<AccountEmailModel, _>::new(change)
+                .execute_by_where(
+                    &WhereOption::Where(sql_format!("id in ({})", ids)),
+                    &mut **db,
+                )
+                .await?;
+            let data = plan
+                .email_move
+                .iter()
+                .map(|e| e.email.as_str())
+                .collect::<Vec<_>>();
+            self.index
+                .add(AccountIndexCat::Email, target.id, &data, Some(&mut *db))
+                .await?;
+        }
+
+        let ids = plan
+            .mobile_conflict
+            .iter()
+            .map(|e| e.id)
+            .collect::<Vec<_>>();
+        if !ids.is_empty() {
+            let change = model_option_set!(AccountMobileModelRef,{
+                status:AccountMobileStatus::Delete as i8,
+                change_time:time,
+            });
+            Update::<AccountMobileModel, _>::new(change)
+                .execute_by_where(
+                    &WhereOption::Where(sql_format!("id in ({})", ids)),
+                    &mut **db,
+                )
+                .await?;
+        }
+        let ids = plan.mobile_move.iter().map(|e| e.id).collect::<Vec<_>>();
+        if !ids.is_empty() {
+            let change = model_option_set!(AccountMobileModelRef,{
+                account_id:target.id,
+                change_time:time,
+            });
+            Update::<AccountMobileModel, _>::new(change)
+                .execute_by_where(
+                    &WhereOption::Where(sql_format!("id in ({})", ids)),
+                    &mut **db,
+                )
+                .await?;
+            let data = plan
+                .mobile_move
+                .iter()
+                .map(|e| e.mobile.as_str())
+                .collect::<Vec<_>>();
+            self.index
+                .add(AccountIndexCat::Mobile, target.id, &data, Some(&mut *db))
+                .await?;
+        }
+
+        let ids = plan
+            .external_conflict
+            .iter()
+            .map(|e| e.id)
+            .collect::<Vec<_>>();
+        if !ids.is_empty() {
+            let change = model_option_set!(AccountExternalModelRef,{
+                status:AccountExternalStatus::Delete as i8,
+                change_time:time,
+            });
+            Update::<AccountExternalModel, _>::new(change)
+                .execute_by_where(
+                    &WhereOption::Where(sql_format!("id in ({})", ids)),
+                    &mut **db,
+                )
+                .await?;
+        }
+        let ids = plan.external_move.iter().map(|e| e.id).collect::<Vec<_>>();
+        if !ids.is_empty() {
+            let change = model_option_set!(AccountExternalModelRef,{
+                account_id:target.id,
+                change_time:time,
+            });
+            Update::<AccountExternalModel, _>::new(change)
+                .execute_by_where(
+                    &WhereOption::Where(sql_format!("id in ({})", ids)),
+                    &mut **db,
+                )
+                .await?;
+            let data = plan
+                .external_move
+                .iter()
+                .map(|e| e.external_type.as_str())
+                .collect::<Vec<_>>();
+            self.index
+                .add(
+                    AccountIndexCat::ExternalType,
+                    target.id,
+                    &data,
+                    Some(&mut *db),
+                )
+                .await?;
+        }
+
+        let ids = plan.address_move.iter().map(|e| e.id).collect::<Vec<_>>();
+        if !ids.is_empty() {
+            let change = model_option_set!(AccountAddressModelRef,{
+                account_id:target.id,
+                change_time:time,
+            });
+            Update::<AccountAddressModel, _>::new(change)
+                .execute_by_where(
+                    &WhereOption::Where(sql_format!(
+                        "id in ({}) and status={}",
+                        ids,
+                        AccountAddressStatus::Enable
+                    )),
+                    &mut **db,
+                )
+                .await?;
+            let data = plan
+                .address_move
+                .iter()
+                .map(|e| e.address_info.as_str())
+                .collect::<Vec<_>>();
+            self.index
+                .add(AccountIndexCat::Address, target.id, &data, Some(&mut *db))
+                .await?;
+        }
+
+        if let Some(name) = &plan.name_move {
+            let change = model_option_set!(AccountNameModelRef,{
+                account_id:target.id,
+                change_time:time,
+            });
+            Update::<AccountNameModel, _>::new(change)
+                .execute_by_where(
+                    &WhereOption::Where(sql_format!("id={}", name.id)),
+                    &mut **db,
+                )
+                .await?;
+            self.index
+                .cat_one_add(
+                    AccountIndexCat::AccountName,
+                    target.id,
+                    &name.username,
+                    Some(&mut *db),
+                )
+                .await?;
+        }
+        if let Some(name) = &plan.name_drop {
+            let username = format!("del__{}", source.id);
+            let change = model_option_set!(AccountNameModelRef,{
+                username:username,
+                status:AccountNameStatus::Delete as i8,
+                change_time:time,
+            });
+            Update::<AccountNameModel, _>::new(change)
+                .execute_by_where(
+                    &WhereOption::Where(sql_format!("id={}", name.id)),
+                    &mut **db,
+                )
+                .await?;
+        }
+
+        if plan.password_move {
+            let change = model_option_set!(AccountPasswordModelRef,{
+                account_id:target.id,
+            });
+            Update::<AccountPasswordModel, _>::new(change)
+                .execute_by_where(
+                    &WhereOption::Where(sql_format!("id={}", source.password_id)),
+                    &mut **db,
+                )
+                .await?;
+        }
+
+        sqlx::query(&sql_format!(
+            "update {} set account_id={} where account_id={}",
+            AccountLoginModel::table_name(),
+            target.id,
+            source.id
+        ))
+        .execute(&mut **db)
+        .await?;
+
+        let ids = plan.token_revoke.iter().map(|e| e.id).collect::<Vec<_>>();
+        if !ids.is_empty() {
+            let change = model_option_set!(AccountTokenModelRef,{
+                status:AccountTokenStatus::Delete as i8,
+                change_time:time,
+            });
+            Update::<AccountTokenModel, _>::new(change)
+                .execute_by_where(
+                    &WhereOption::Where(sql_format!("id in ({})", ids)),
+                    &mut **db,
+                )
+                .await?;
+        }
+
+        let mut change = model_option_set!(AccountModelRef,{
+            change_time:time,
+        });
+        if plan.password_move {
+            change.password_id = Some(&source.password_id);
+        }
+        let use_name = 1;
+        if plan.name_move.is_some() {
+            change.use_name = Some(&use_name);
+        }
+        Update::<AccountModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("id={}", target.id)),
+                &mut **db,
+            )
+            .await?;
+        //按合并后数据重算保留账号的绑定数量
+        sqlx::query(&sql_format!(
+            "update {} set email_count=(select count(*) from {} where account_id={} and status in ({})),
+                mobile_count=(select count(*) from {} where account_id={} and status in ({})),
+                external_count=(select count(*) from {} where account_id={} and status={}),
+                address_count=(select count(*) from {} where account_id={} and status={})
+                where id={}",
+            AccountModel::table_name(),
+            AccountEmailModel::table_name(),
+            target.id,
+            [
+                AccountEmailStatus::Init as i8,
+                AccountEmailStatus::Valid as i8
+            ],
+            AccountMobileModel::table_name(),
+            target.id,
+            [
+                AccountMobileStatus::Init as i8,
+                AccountMobileStatus::Valid as i8
+            ],
+            AccountExternalModel::table_name(),
+            target.id,
+            AccountExternalStatus::Enable,
+            AccountAddressModel::table_name(),
+            target.id,
+            AccountAddressStatus::Enable,
+            target.id
+        ))
+        .execute(&mut **db)
+        .await?;
+        Ok(())
+    }
+}
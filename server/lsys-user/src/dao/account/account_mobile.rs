@@ -80,7 +80,11 @@ impl AccountMobile {
 
         Ok(res)
     }
-    async fn mobile_param_valid(&self, area_code: &str, mobile: &str) -> AccountResult<()> {
+    pub(crate) async fn mobile_param_valid(
+        &self,
+        area_code: &str,
+        mobile: &str,
+    ) -> AccountResult<()> {
         ValidParam::default()
             .add(
                 valid_key!("user_mobile"),
@@ -150,6 +154,7 @@ impl AccountMobile {
             change_time:time,
         });
 
+        let own_tran = transaction.is_none();
         let mut db = match transaction {
             Some(pb) => pb.begin().await?,
             None => self.db.begin().await?,
@@ -197,6 +202,9 @@ impl AccountMobile {
                         }
 
                         db.commit().await?;
+                        if own_tran {
+                            self.index.change_notify(account.id);
+                        }
                         self.account_cache.clear(&account.id).await;
                         mr.last_insert_id()
                     }
@@ -374,6 +382,7 @@ impl AccountMobile {
             return Err(ie);
         }
         db.commit().await?;
+        self.index.change_notify(account_mobile.account_id);
         self.account_cache.clear(&account_mobile.account_id).await;
         self.cache.clear(&account_mobile.id).await;
 
@@ -411,6 +420,7 @@ impl AccountMobile {
             status:AccountMobileStatus::Delete as i8,
             change_time:time
         });
+        let own_tran = transaction.is_none();
         let mut db = match transaction {
             Some(pb) => pb.begin().await?,
             None => self.db.begin().await?,
@@ -454,6 +464,9 @@ impl AccountMobile {
                         }
 
                         db.commit().await?;
+                        if own_tran {
+                            self.index.change_notify(account_mobile.account_id);
+                        }
                         self.account_cache.clear(&account_mobile.account_id).await;
                         self.cache.clear(&account_mobile.id).await;
                         Ok(mr.rows_affected())
@@ -79,6 +79,7 @@ impl AccountName {
             status:status
         });
 
+        let own_tran = transaction.is_none();
         let mut db = match transaction {
             Some(pb) => pb.begin().await?,
             None => self.db.begin().await?,
@@ -106,6 +107,9 @@ impl AccountName {
             return Err(ie);
         }
         db.commit().await?;
+        if own_tran {
+            self.index.change_notify(account.id);
+        }
 
         self.logger
             .add(
@@ -121,7 +125,7 @@ impl AccountName {
             .await;
         Ok(())
     }
-    async fn name_param_valid(&self, username: &str) -> AccountResult<()> {
+    pub(crate) async fn name_param_valid(&self, username: &str) -> AccountResult<()> {
         ValidParam::default()
             .add(
                 valid_key!("username"),
@@ -176,6 +180,7 @@ impl AccountName {
                             status:status,
                             change_time: time,
                         });
+                        let own_tran = transaction.is_none();
                         let mut db = match transaction {
                             Some(pb) => pb.begin().await?,
                             None => self.db.begin().await?,
@@ -215,6 +220,9 @@ impl AccountName {
                             return Err(ie);
                         }
                         db.commit().await?;
+                        if own_tran {
+                            self.index.change_notify(account.id);
+                        }
                         self.cache.clear(&account.id).await;
                         Ok(())
                     }
@@ -225,6 +233,7 @@ impl AccountName {
                             username:username,
                             change_time:time
                         });
+                        let own_tran = transaction.is_none();
                         let mut db = match transaction {
                             Some(pb) => pb.begin().await?,
                             None => self.db.begin().await?,
@@ -253,6 +262,9 @@ impl AccountName {
                             return Err(ie);
                         }
                         db.commit().await?;
+                        if own_tran {
+                            self.index.change_notify(account_name.account_id);
+                        }
                         self.cache.clear(&account.id).await;
                         Ok(())
                     }
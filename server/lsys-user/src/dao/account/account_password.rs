@@ -1,8 +1,12 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::dao::AccountResult;
 
-use crate::model::{AccountModel, AccountModelRef, AccountPasswordModel, AccountPasswordModelRef};
+use crate::model::{
+    AccountEmailModel, AccountEmailStatus, AccountModel, AccountModelRef, AccountNameModel,
+    AccountNameStatus, AccountPasswordModel, AccountPasswordModelRef,
+};
 use lsys_core::{
     fluent_message, now_time, valid_key, IntoFluentMessage, RequestEnv, ValidParam,
     ValidParamCheck, ValidPassword,
@@ -13,11 +17,12 @@ use lsys_core::db::{ModelTableName, SqlQuote};
 use lsys_core::{model_option_set, sql_format};
 use lsys_logger::dao::ChangeLoggerDao;
 use lsys_setting::dao::{NotFoundResult, SingleSetting};
+use sha1::{Digest, Sha1};
 use sqlx::{Acquire, MySql, Pool, Transaction};
 use tracing::warn;
 
 use super::logger::LogAccountPassWrod;
-use super::{AccountError, AccountPasswordConfig, AccountPasswordHash};
+use super::{AccountError, AccountPasswordConfig, AccountPasswordHash, AccountPasswordLegacy};
 
 pub struct AccountPassword {
     db: Pool<MySql>,
@@ -26,6 +31,7 @@ pub struct AccountPassword {
     account_passwrd_hash: Arc<AccountPasswordHash>,
     setting: Arc<SingleSetting>,
     logger: Arc<ChangeLoggerDao>,
+    breached_dir: Option<PathBuf>,
 }
 
 impl AccountPassword {
@@ -36,6 +42,7 @@ impl AccountPassword {
         redis: deadpool_redis::Pool,
         logger: Arc<ChangeLoggerDao>,
         account_passwrd_hash: Arc<AccountPasswordHash>,
+        breached_dir: Option<PathBuf>,
     ) -> Self {
         Self {
             db,
@@ -44,6 +51,7 @@ impl AccountPassword {
             account_passwrd_hash,
             setting,
             logger,
+            breached_dir,
         }
     }
 }
@@ -106,6 +114,170 @@ impl AccountPassword {
 
         Ok(())
     }
+    //已泄露密码库为 k-anonymity 格式:按SHA1前5位分文件,每行为 剩余35位:出现次数
+    async fn breached_count(&self, password: &str) -> Option<u64> {
+        let dir = self.breached_dir.as_ref()?;
+        let hash: String = Sha1::digest(password.as_bytes())
+            .iter()
+            .map(|e| format!("{:02X}", e))
+            .collect();
+        let (prefix, suffix) = hash.split_at(5);
+        let mut body = None;
+        for file_name in [format!("{}.txt", prefix), prefix.to_string()] {
+            match tokio::fs::read_to_string(dir.join(file_name)).await {
+                Ok(data) => {
+                    body = Some(data);
+                    break;
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => {
+                    warn!("read breached password file {} fail:{}", prefix, err);
+                    return None;
+                }
+            }
+        }
+        body?.lines().find_map(|line| {
+            let (hash_suffix, count) = line.trim().split_once(':')?;
+            if hash_suffix.eq_ignore_ascii_case(suffix) {
+                Some(count.trim().parse::<u64>().unwrap_or(1))
+            } else {
+                None
+            }
+        })
+    }
+    //账号已绑定的登录名及邮箱
+    async fn account_names(&self, account_id: u64) -> AccountResult<Vec<String>> {
+        let mut names = sqlx::query_scalar::<_, String>(&sql_format!(
+            "select username from {} where account_id={} and status={}",
+            AccountNameModel::table_name(),
+            account_id,
+            AccountNameStatus::Enable as i8
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        let emails = sqlx::query_scalar::<_, String>(&sql_format!(
+            "select email from {} where account_id={} and status in ({})",
+            AccountEmailModel::table_name(),
+            account_id,
+            &[
+                AccountEmailStatus::Init as i8,
+                AccountEmailStatus::Valid as i8
+            ]
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        names.extend(emails);
+        Ok(names)
+    }
+    async fn policy_check_config(
+        &self,
+        config: &AccountPasswordConfig,
+        account_id: u64,
+        password: &str,
+        account_names: &[&str],
+    ) -> AccountResult<()> {
+        if config.min_length > 0 && (password.chars().count() as u64) < config.min_length {
+            return Err(AccountError::System(fluent_message!(
+                "account-password-min-length",
+                {"len":config.min_length}
+            )));
+        }
+        let class_check = [
+            (
+                config.require_lower,
+                password.chars().any(|c| c.is_ascii_lowercase()),
+                fluent_message!("account-password-need-lower"),
+            ),
+            (
+                config.require_upper,
+                password.chars().any(|c| c.is_ascii_uppercase()),
+                fluent_message!("account-password-need-upper"),
+            ),
+            (
+                config.require_number,
+                password.chars().any(|c| c.is_ascii_digit()),
+                fluent_message!("account-password-need-number"),
+            ),
+            (
+                config.require_symbol,
+                password.chars().any(|c| c.is_ascii_punctuation()),
+                fluent_message!("account-password-need-symbol"),
+            ),
+        ];
+        for (require, has, message) in class_check {
+            if require && !has {
+                return Err(AccountError::System(message));
+            }
+        }
+        if config.disable_account_name {
+            let mut names = account_names
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>();
+            if account_id > 0 {
+                names.extend(self.account_names(account_id).await?);
+            }
+            let check_password = password.to_lowercase();
+            for name in names {
+                let name = name.trim().to_lowercase();
+                //邮箱同时检测@前部分
+                let local_name = name.split('@').next().unwrap_or_default().to_string();
+                if [name, local_name]
+                    .iter()
+                    .any(|e| e.chars().count() >= 3 && check_password.contains(e.as_str()))
+                {
+                    return Err(AccountError::System(fluent_message!(
+                        "account-password-contain-account"
+                    )));
+                }
+            }
+        }
+        if config.history_num > 0 && account_id > 0 {
+            let hash_password = self.account_passwrd_hash.hash_password(password).await;
+            let history = sqlx::query_scalar::<_, String>(&sql_format!(
+                "select password from {} where account_id={} order by id desc limit {}",
+                AccountPasswordModel::table_name(),
+                account_id,
+                config.history_num
+            ))
+            .fetch_all(&self.db)
+            .await?;
+            if history.contains(&hash_password) {
+                return Err(AccountError::System(fluent_message!(
+                    "account-password-history",
+                    {"num":config.history_num}
+                )));
+            }
+        }
+        if config.breached_check {
+            if self.breached_dir.is_none() {
+                warn!("password breached check is enable,but breached dir not set");
+            } else if let Some(count) = self.breached_count(password).await {
+                return Err(AccountError::System(fluent_message!(
+                    "account-password-breached",
+                    {"count":count}
+                )));
+            }
+        }
+        Ok(())
+    }
+    /// 按站点配置的密码策略检测密码
+    /// account_names 为尚未绑定到账号的登录名及邮箱,如注册时提交的数据
+    pub async fn policy_check(
+        &self,
+        account_id: u64,
+        password: &str,
+        account_names: &[&str],
+    ) -> AccountResult<()> {
+        self.passwrod_param_valid(password).await?;
+        let config = self
+            .setting
+            .load::<AccountPasswordConfig>(None)
+            .await
+            .notfound_default()?;
+        self.policy_check_config(&config, account_id, password, account_names)
+            .await
+    }
     /// 校验验证码并设置新密码
     #[allow(clippy::too_many_arguments)]
     pub async fn set_passwrod_from_code(
@@ -155,6 +327,8 @@ impl AccountPassword {
             .load::<AccountPasswordConfig>(None)
             .await
             .notfound_default()?;
+        self.policy_check_config(&config, account.id, &new_password, &[])
+            .await?;
         if account.password_id > 0 {
             let account_pass_res = sqlx::query_as::<_, AccountPasswordModel>(&sql_format!(
                 "select * from {} where account_id={} and id={}",
@@ -294,12 +468,84 @@ impl AccountPassword {
                 _ => return Err(err),
             },
         };
+        if let Some(legacy) = AccountPasswordLegacy::decode(&account_password.password) {
+            //bcrypt 计算耗时,不占用异步线程
+            let tmp_password = check_password.to_owned();
+            let is_match = tokio::task::spawn_blocking(move || legacy.verify(&tmp_password))
+                .await
+                .unwrap_or(false);
+            if is_match {
+                //导入的密码校验通过后转为当前HASH方式
+                let nh_passwrod = self
+                    .account_passwrd_hash
+                    .hash_password(check_password)
+                    .await;
+                let change = lsys_core::model_option_set!(AccountPasswordModelRef, {
+                    password: nh_passwrod
+                });
+                if let Err(err) = Update::<AccountPasswordModel, _>::new(change)
+                    .execute_by_where(
+                        &WhereOption::Where(sql_format!("id={}", account_password.id)),
+                        &self.db,
+                    )
+                    .await
+                {
+                    warn!("password {} rehash fail:{}", account_password.id, err);
+                }
+            }
+            return Ok(is_match);
+        }
         Ok(self
             .account_passwrd_hash
             .hash_password(check_password)
             .await
             == account_password.password)
     }
+    /// 直接设置已HASH的密码,用于批量导入
+    /// password 为当前HASH方式的结果或 AccountPasswordLegacy::encode 的结果
+    pub(crate) async fn set_passwrod_hash(
+        &self,
+        account: &AccountModel,
+        password: &str,
+        op_user_id: u64,
+        transaction: &mut Transaction<'_, sqlx::MySql>,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<u64> {
+        let time = now_time()?;
+        let password = password.to_owned();
+        let new_data = model_option_set!(AccountPasswordModelRef,{
+            account_id:account.id,
+            password:password,
+            disable_time: 0,
+            add_time: time,
+        });
+        let pid = Insert::<AccountPasswordModel, _>::new(new_data)
+            .execute(&mut **transaction)
+            .await?
+            .last_insert_id();
+        let change = lsys_core::model_option_set!(AccountModelRef,{
+            password_id:pid,
+            change_time:time,
+        });
+        Update::<AccountModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("id={}", account.id)),
+                &mut **transaction,
+            )
+            .await?;
+        self.logger
+            .add(
+                &LogAccountPassWrod {
+                    account_id: account.id,
+                },
+                Some(pid),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(pid)
+    }
     /// 检测指定ID密码是否超时
     /// 返回 (是否超时, 密码有效期配置)
     pub async fn password_timeout(&self, account_id: u64) -> AccountResult<(bool, u64)> {
@@ -0,0 +1,568 @@
+//注册控制
+//邀请码:管理员或用户发放,限制使用次数及过期时间
+//仅邀请注册时,未持有有效邀请码只允许指定域名邮箱自助注册
+//开启审核时,新注册账号待管理员审核通过后启用
+use std::sync::Arc;
+
+use lsys_core::db::{Insert, ModelTableName, SqlExpr, SqlQuote, Update, WhereOption};
+use lsys_core::{
+    fluent_message, model_option_set, now_time, rand_str, sql_format, LimitParam, RandType,
+    RequestEnv,
+};
+use lsys_logger::dao::ChangeLoggerDao;
+use lsys_setting::dao::{
+    NotFoundResult, SettingData, SettingDecode, SettingEncode, SettingJson, SettingKey,
+    SettingResult, SingleSetting,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{MySql, Pool, Transaction};
+
+use crate::model::{
+    AccountInviteModel, AccountInviteModelRef, AccountInviteStatus, AccountModel,
+    AccountRegisterModel, AccountRegisterModelRef, AccountRegisterStatus,
+};
+
+use super::{
+    logger::{LogAccountInvite, LogAccountRegister},
+    Account, AccountError, AccountResult,
+};
+
+#[derive(Deserialize, Serialize, Default)]
+#[serde(default)]
+pub struct AccountRegisterConfig {
+    pub invite_only: bool,         //仅邀请注册
+    pub email_domain: Vec<String>, //允许自助注册的邮箱域名,为空不限制
+    pub need_approve: bool,        //注册后需管理员审核
+    pub user_invite: bool,         //允许普通用户发放邀请码
+    pub user_invite_max_num: u32,  //用户发放的邀请码最大使用次数
+}
+
+impl SettingKey for AccountRegisterConfig {
+    fn key<'t>() -> &'t str {
+        "account-register"
+    }
+}
+impl SettingDecode for AccountRegisterConfig {
+    fn decode(data: &str) -> SettingResult<Self> {
+        SettingJson::decode(data)
+    }
+}
+impl SettingEncode for AccountRegisterConfig {
+    fn encode(&self) -> String {
+        SettingJson::encode(self)
+    }
+}
+impl SettingJson<'_> for AccountRegisterConfig {}
+
+//注册检测结果,注册时传入 register_add
+pub struct AccountRegisterPermit {
+    pub invite: Option<AccountInviteModel>,
+    pub need_approve: bool,
+}
+
+pub struct AccountRegister {
+    db: Pool<MySql>,
+    account: Arc<Account>,
+    setting: Arc<SingleSetting>,
+    logger: Arc<ChangeLoggerDao>,
+}
+
+impl AccountRegister {
+    pub fn new(
+        db: Pool<MySql>,
+        account: Arc<Account>,
+        setting: Arc<SingleSetting>,
+        logger: Arc<ChangeLoggerDao>,
+    ) -> Self {
+        Self {
+            db,
+            account,
+            setting,
+            logger,
+        }
+    }
+    pub async fn config(&self) -> AccountResult<SettingData<AccountRegisterConfig>> {
+        Ok(self
+            .setting
+            .load::<AccountRegisterConfig>(None)
+            .await
+            .notfound_default()?)
+    }
+    /// 发放邀请码,expire_time 为0时不过期
+    pub async fn invite_add(
+        &self,
+        max_num: u32,
+        expire_time: u64,
+        note: &str,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<AccountInviteModel> {
+        let time = now_time()?;
+        if max_num == 0 || (expire_time > 0 && expire_time <= time) {
+            return Err(AccountError::System(fluent_message!(
+                "account-invite-bad-param"
+            )));
+        }
+        let invite_code = rand_str(RandType::UpperNumber, 12);
+        let note = note.trim().chars().take(255).collect::<String>();
+        let status = AccountInviteStatus::Enable as i8;
+        let idata = model_option_set!(AccountInviteModelRef,{
+            invite_code:invite_code,
+            max_num:max_num,
+            use_num:0,
+            expire_time:expire_time,
+            note:note,
+            status:status,
+            add_user_id:op_user_id,
+            add_time:time,
+        });
+        let id = Insert::<AccountInviteModel, _>::new(idata)
+            .execute(&self.db)
+            .await?
+            .last_insert_id();
+        self.logger
+            .add(
+                &LogAccountInvite {
+                    action: "add",
+                    max_num,
+                    expire_time,
+                    note: &note,
+                },
+                Some(id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(AccountInviteModel {
+            id,
+            invite_code,
+            max_num,
+            use_num: 0,
+            expire_time,
+            note,
+            status,
+            add_user_id: op_user_id,
+            add_time: time,
+            change_user_id: 0,
+            change_time: 0,
+        })
+    }
+    /// 停用邀请码
+    pub async fn invite_disable(
+        &self,
+        invite: &AccountInviteModel,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<()> {
+        if AccountInviteStatus::Disable.eq(invite.status) {
+            return Ok(());
+        }
+        let time = now_time()?;
+        let change = model_option_set!(AccountInviteModelRef,{
+            status:AccountInviteStatus::Disable as i8,
+            change_user_id:op_user_id,
+            change_time:time,
+        });
+        Update::<AccountInviteModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("id={}", invite.id)),
+                &self.db,
+            )
+            .await?;
+        self.logger
+            .add(
+                &LogAccountInvite {
+                    action: "disable",
+                    max_num: invite.max_num,
+                    expire_time: invite.expire_time,
+                    note: &invite.note,
+                },
+                Some(invite.id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(())
+    }
+    lsys_core::impl_dao_fetch_one_by_one!(
+        db,
+        invite_find_by_id,
+        u64,
+        AccountInviteModel,
+        AccountResult<AccountInviteModel>,
+        id,
+        "id = {id} "
+    );
+    /// 查找可用的邀请码
+    pub async fn invite_find_by_code(
+        &self,
+        invite_code: &str,
+    ) -> AccountResult<AccountInviteModel> {
+        let invite_code = invite_code.trim().to_uppercase();
+        let invite = sqlx::query_as::<_, AccountInviteModel>(&sql_format!(
+            "select * from {} where invite_code={}",
+            AccountInviteModel::table_name(),
+            invite_code
+        ))
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => {
+                AccountError::System(fluent_message!("account-invite-invalid"))
+            }
+            _ => AccountError::Sqlx(e),
+        })?;
+        if !AccountInviteStatus::Enable.eq(invite.status)
+            || invite.use_num >= invite.max_num
+            || (invite.expire_time > 0 && invite.expire_time < now_time()?)
+        {
+            return Err(AccountError::System(fluent_message!(
+                "account-invite-invalid"
+            )));
+        }
+        Ok(invite)
+    }
+    fn invite_where(
+        &self,
+        add_user_id: Option<u64>,
+        status: Option<AccountInviteStatus>,
+    ) -> String {
+        let mut where_sql = vec![];
+        if let Some(uid) = add_user_id {
+            where_sql.push(sql_format!("add_user_id={}", uid));
+        }
+        if let Some(s) = status {
+            where_sql.push(sql_format!("status={}", s));
+        }
+        if where_sql.is_empty() {
+            "1".to_string()
+        } else {
+            where_sql.join(" and ")
+        }
+    }
+    /// 邀请码列表
+    pub async fn invite_list_data(
+        &self,
+        add_user_id: Option<u64>,
+        status: Option<AccountInviteStatus>,
+        limit: Option<&LimitParam>,
+    ) -> AccountResult<(Vec<AccountInviteModel>, Option<u64>)> {
+        let where_sql = self.invite_where(add_user_id, status);
+        let sql = if let Some(page) = limit {
+            format!(
+                "{} {} order by {} {}",
+                where_sql,
+                page.where_sql("id", Some("and")),
+                page.order_sql("id"),
+                page.limit_sql(),
+            )
+        } else {
+            format!("{} order by id desc", where_sql)
+        };
+        let mut data = sqlx::query_as::<_, AccountInviteModel>(&sql_format!(
+            "select * from {} where {}",
+            AccountInviteModel::table_name(),
+            SqlExpr(sql)
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        let next = limit
+            .as_ref()
+            .map(|page| page.tidy(&mut data))
+            .unwrap_or_default()
+            .map(|e| e.id);
+        Ok((data, next))
+    }
+    /// 邀请码数量
+    pub async fn invite_list_count(
+        &self,
+        add_user_id: Option<u64>,
+        status: Option<AccountInviteStatus>,
+    ) -> AccountResult<i64> {
+        Ok(sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) as total from {} where {}",
+            AccountInviteModel::table_name(),
+            SqlExpr(self.invite_where(add_user_id, status))
+        ))
+        .fetch_one(&self.db)
+        .await?)
+    }
+    //邮箱是否属于允许自助注册的域名
+    fn email_domain_allow(config: &AccountRegisterConfig, email: Option<&str>) -> bool {
+        let domain = match email.and_then(|e| e.trim().rsplit_once('@')) {
+            Some((_, domain)) => domain.to_lowercase(),
+            None => return false,
+        };
+        config.email_domain.iter().any(|e| {
+            e.trim()
+                .trim_start_matches('@')
+                .eq_ignore_ascii_case(&domain)
+        })
+    }
+    /// 自助注册前检测,持有有效邀请码时不限制邮箱域名
+    pub async fn register_check(
+        &self,
+        invite_code: Option<&str>,
+        email: Option<&str>,
+    ) -> AccountResult<AccountRegisterPermit> {
+        let config = self.config().await?;
+        let invite = match invite_code.map(|e| e.trim()).filter(|e| !e.is_empty()) {
+            Some(code) => Some(self.invite_find_by_code(code).await?),
+            None => None,
+        };
+        if invite.is_none() {
+            if config.invite_only {
+                if config.email_domain.is_empty() || !Self::email_domain_allow(&config, email) {
+                    return Err(AccountError::System(fluent_message!(
+                        "account-register-need-invite"
+                    )));
+                }
+            } else if !config.email_domain.is_empty() && !Self::email_domain_allow(&config, email) {
+                return Err(AccountError::System(fluent_message!(
+                    "account-register-email-domain",
+                    {"domain":config.email_domain.join(",")}
+                )));
+            }
+        }
+        Ok(AccountRegisterPermit {
+            invite,
+            need_approve: config.need_approve,
+        })
+    }
+    /// 注册账号创建后在同一事务中使用邀请码并记录注册信息
+    pub async fn register_add(
+        &self,
+        account: &AccountModel,
+        permit: &AccountRegisterPermit,
+        reg_from: &str,
+        transaction: &mut Transaction<'_, sqlx::MySql>,
+    ) -> AccountResult<()> {
+        if permit.invite.is_none() && !permit.need_approve {
+            return Ok(());
+        }
+        let time = now_time()?;
+        let invite_id = match permit.invite {
+            Some(ref invite) => {
+                //并发注册时按剩余次数扣减
+                let res = sqlx::query(&sql_format!(
+                    "update {} set use_num=use_num+1,change_time={} where id={} and status={} and use_num<max_num and (expire_time=0 or expire_time>={})",
+                    AccountInviteModel::table_name(),
+                    time,
+                    invite.id,
+                    AccountInviteStatus::Enable as i8,
+                    time
+                ))
+                .execute(&mut **transaction)
+                .await?;
+                if res.rows_affected() == 0 {
+                    return Err(AccountError::System(fluent_message!(
+                        "account-invite-invalid"
+                    )));
+                }
+                invite.id
+            }
+            None => 0,
+        };
+        let status = if permit.need_approve {
+            AccountRegisterStatus::Pending
+        } else {
+            AccountRegisterStatus::Approved
+        } as i8;
+        let reg_from = reg_from.to_owned();
+        let idata = model_option_set!(AccountRegisterModelRef,{
+            account_id:account.id,
+            invite_id:invite_id,
+            reg_from:reg_from,
+            status:status,
+            add_time:time,
+        });
+        Insert::<AccountRegisterModel, _>::new(idata)
+            .execute(&mut **transaction)
+            .await?;
+        Ok(())
+    }
+    lsys_core::impl_dao_fetch_one_by_one!(
+        db,
+        find_by_id,
+        u64,
+        AccountRegisterModel,
+        AccountResult<AccountRegisterModel>,
+        id,
+        "id = {id} "
+    );
+    //审核状态变更,仅处理待审核记录
+    async fn register_change(
+        &self,
+        register: &AccountRegisterModel,
+        status: AccountRegisterStatus,
+        message: &str,
+        op_user_id: u64,
+        transaction: &mut Transaction<'_, sqlx::MySql>,
+    ) -> AccountResult<()> {
+        let time = now_time()?;
+        let message = message.trim().chars().take(255).collect::<String>();
+        let change = model_option_set!(AccountRegisterModelRef,{
+            status:status as i8,
+            message:message,
+            change_user_id:op_user_id,
+            change_time:time,
+        });
+        let res = Update::<AccountRegisterModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!(
+                    "id={} and status={}",
+                    register.id,
+                    AccountRegisterStatus::Pending as i8
+                )),
+                &mut **transaction,
+            )
+            .await?;
+        if res.rows_affected() == 0 {
+            return Err(AccountError::System(fluent_message!(
+                "account-register-not-pending"
+            )));
+        }
+        Ok(())
+    }
+    /// 审核通过并启用账号
+    pub async fn register_approve(
+        &self,
+        register: &AccountRegisterModel,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<()> {
+        let account = self.account.find_by_id(&register.account_id).await?;
+        let mut db = self.db.begin().await?;
+        if let Err(err) = self
+            .register_change(
+                register,
+                AccountRegisterStatus::Approved,
+                "",
+                op_user_id,
+                &mut db,
+            )
+            .await
+        {
+            db.rollback().await?;
+            return Err(err);
+        }
+        if let Err(err) = self
+            .account
+            .enable(&account, op_user_id, Some(&mut db), env_data)
+            .await
+        {
+            db.rollback().await?;
+            return Err(err);
+        }
+        db.commit().await?;
+        self.account.search_notify(account.id);
+        self.account.cache.clear(&account.id).await;
+        self.logger
+            .add(
+                &LogAccountRegister {
+                    action: "approve",
+                    message: "",
+                    account_id: register.account_id,
+                    invite_id: register.invite_id,
+                },
+                Some(register.id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(())
+    }
+    /// 拒绝注册,账号数据由调用方删除
+    pub async fn register_reject(
+        &self,
+        register: &AccountRegisterModel,
+        message: &str,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<()> {
+        let mut db = self.db.begin().await?;
+        if let Err(err) = self
+            .register_change(
+                register,
+                AccountRegisterStatus::Reject,
+                message,
+                op_user_id,
+                &mut db,
+            )
+            .await
+        {
+            db.rollback().await?;
+            return Err(err);
+        }
+        db.commit().await?;
+        self.logger
+            .add(
+                &LogAccountRegister {
+                    action: "reject",
+                    message,
+                    account_id: register.account_id,
+                    invite_id: register.invite_id,
+                },
+                Some(register.id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(())
+    }
+    fn register_where(&self, status: Option<AccountRegisterStatus>) -> String {
+        match status {
+            Some(s) => sql_format!("status={}", s),
+            None => "1".to_string(),
+        }
+    }
+    /// 注册记录列表
+    pub async fn register_list_data(
+        &self,
+        status: Option<AccountRegisterStatus>,
+        limit: Option<&LimitParam>,
+    ) -> AccountResult<(Vec<AccountRegisterModel>, Option<u64>)> {
+        let where_sql = self.register_where(status);
+        let sql = if let Some(page) = limit {
+            format!(
+                "{} {} order by {} {}",
+                where_sql,
+                page.where_sql("id", Some("and")),
+                page.order_sql("id"),
+                page.limit_sql(),
+            )
+        } else {
+            format!("{} order by id desc", where_sql)
+        };
+        let mut data = sqlx::query_as::<_, AccountRegisterModel>(&sql_format!(
+            "select * from {} where {}",
+            AccountRegisterModel::table_name(),
+            SqlExpr(sql)
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        let next = limit
+            .as_ref()
+            .map(|page| page.tidy(&mut data))
+            .unwrap_or_default()
+            .map(|e| e.id);
+        Ok((data, next))
+    }
+    /// 注册记录数量
+    pub async fn register_list_count(
+        &self,
+        status: Option<AccountRegisterStatus>,
+    ) -> AccountResult<i64> {
+        Ok(sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) as total from {} where {}",
+            AccountRegisterModel::table_name(),
+            SqlExpr(self.register_where(status))
+        ))
+        .fetch_one(&self.db)
+        .await?)
+    }
+}
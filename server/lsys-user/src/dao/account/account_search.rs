@@ -0,0 +1,639 @@
+// 账号全文搜索索引
+// 基于tantivy,汇总昵称,登录名,邮箱,手机号,外部账号等信息到同一文档
+// 账号索引变动时(AccountIndex)通知更新,可从MYSQL全量重建
+use std::collections::HashSet;
+use std::ops::Bound;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike};
+use lsys_core::{
+    fluent_message, IntoFluentMessage, LocalExecType, MsgSendBody, PageParam, RemoteNotify,
+    RemoteTask,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tantivy::collector::{Count, FacetCollector, FacetCounts, TopDocs};
+use tantivy::directory::MmapDirectory;
+use tantivy::query::{
+    AllQuery, BooleanQuery, BoostQuery, FuzzyTermQuery, Occur, Query, RangeQuery, RegexQuery,
+    TermQuery,
+};
+use tantivy::schema::{
+    Facet, FacetOptions, Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, FAST,
+    INDEXED, STORED,
+};
+use tantivy::tokenizer::{LowerCaser, RawTokenizer, TextAnalyzer};
+use tantivy::{
+    DocAddress, Index, IndexBuilder, IndexReader, IndexSettings, IndexWriter, Order, ReloadPolicy,
+    TantivyDocument, Term,
+};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::model::{AccountModel, AccountStatus};
+
+use super::{
+    Account, AccountEmail, AccountError, AccountExternal, AccountMobile, AccountName, AccountResult,
+};
+
+pub const REMOTE_NOTIFY_TYPE_ACCOUNT_SEARCH: u8 = 102;
+
+//可用的分面根节点
+pub const ACCOUNT_SEARCH_FACET: &[&str] = &["/status", "/login", "/reg"];
+
+const ACCOUNT_SEARCH_TOKENIZER: &str = "account_raw";
+const ACCOUNT_SEARCH_BATCH: u64 = 500;
+
+fn search_error(err: impl ToString) -> AccountError {
+    AccountError::System(fluent_message!("account-search-index-error",{
+        "msg":err.to_string()
+    }))
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum AccountSearchMessage {
+    Change(Vec<u64>),
+    Rebuild,
+}
+
+pub struct AccountSearchResult {
+    pub account_ids: Vec<u64>,
+    pub total: usize,
+    pub facets: Vec<(String, u64)>,
+}
+
+struct AccountSearchIndex {
+    reader: IndexReader,
+    writer: Mutex<IndexWriter>,
+    account_id: Field,
+    keyword: Field,
+    facet: Field,
+    reg_time: Field,
+}
+
+impl AccountSearchIndex {
+    fn open(path: &PathBuf) -> tantivy::Result<Self> {
+        std::fs::create_dir_all(path)?;
+        let mut schema_builder = Schema::builder();
+        let account_id = schema_builder.add_u64_field("account_id", INDEXED | STORED | FAST);
+        let keyword_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer(ACCOUNT_SEARCH_TOKENIZER)
+                .set_index_option(IndexRecordOption::WithFreqs),
+        );
+        let keyword = schema_builder.add_text_field("keyword", keyword_options);
+        let facet = schema_builder.add_facet_field("facet", FacetOptions::default());
+        let reg_time = schema_builder.add_u64_field("reg_time", INDEXED | FAST);
+        let index: Index = IndexBuilder::new()
+            .schema(schema_builder.build())
+            .settings(IndexSettings::default())
+            .open_or_create(MmapDirectory::open(path)?)?;
+        //整个值作为一个词,搜索时使用前缀及模糊匹配
+        index.tokenizers().register(
+            ACCOUNT_SEARCH_TOKENIZER,
+            TextAnalyzer::builder(RawTokenizer::default())
+                .filter(LowerCaser)
+                .build(),
+        );
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        let writer = index.writer_with_num_threads(1, 50_000_000)?;
+        Ok(Self {
+            reader,
+            writer: Mutex::new(writer),
+            account_id,
+            keyword,
+            facet,
+            reg_time,
+        })
+    }
+}
+
+//索引中使用的账号数据
+struct AccountSearchDoc {
+    account: AccountModel,
+    keyword: Vec<String>,
+    login: Vec<Vec<String>>,
+}
+
+pub struct AccountSearch {
+    account: Arc<Account>,
+    account_name: Arc<AccountName>,
+    account_email: Arc<AccountEmail>,
+    account_mobile: Arc<AccountMobile>,
+    account_external: Arc<AccountExternal>,
+    remote_notify: Arc<RemoteNotify>,
+    change_rx: Mutex<Option<UnboundedReceiver<u64>>>,
+    index: Option<AccountSearchIndex>,
+}
+
+impl AccountSearch {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        account: Arc<Account>,
+        account_name: Arc<AccountName>,
+        account_email: Arc<AccountEmail>,
+        account_mobile: Arc<AccountMobile>,
+        account_external: Arc<AccountExternal>,
+        remote_notify: Arc<RemoteNotify>,
+        index_dir: PathBuf,
+        change_rx: UnboundedReceiver<u64>,
+    ) -> Self {
+        //索引打开失败不影响其他功能,仅搜索不可用
+        let index = match AccountSearchIndex::open(&index_dir) {
+            Ok(index) => Some(index),
+            Err(err) => {
+                warn!(
+                    "account search index open fail [{}]:{}",
+                    index_dir.display(),
+                    err
+                );
+                None
+            }
+        };
+        Self {
+            account,
+            account_name,
+            account_email,
+            account_mobile,
+            account_external,
+            remote_notify,
+            change_rx: Mutex::new(Some(change_rx)),
+            index,
+        }
+    }
+    fn index(&self) -> AccountResult<&AccountSearchIndex> {
+        self.index
+            .as_ref()
+            .ok_or_else(|| AccountError::System(fluent_message!("account-search-not-enable")))
+    }
+    fn keyword_query(index: &AccountSearchIndex, word: &str) -> AccountResult<Box<dyn Query>> {
+        let term = Term::from_field_text(index.keyword, word);
+        let mut sub_query: Vec<(Occur, Box<dyn Query>)> = vec![
+            (
+                Occur::Should,
+                Box::new(BoostQuery::new(
+                    Box::new(TermQuery::new(term.clone(), IndexRecordOption::Basic)),
+                    4.0,
+                )),
+            ),
+            (
+                Occur::Should,
+                Box::new(BoostQuery::new(
+                    Box::new(
+                        RegexQuery::from_pattern(
+                            &format!("{}.*", regex_syntax_escape(word)),
+                            index.keyword,
+                        )
+                        .map_err(search_error)?,
+                    ),
+                    2.0,
+                )),
+            ),
+        ];
+        //过短的词模糊匹配结果过多
+        if word.chars().count() >= 4 {
+            sub_query.push((Occur::Should, Box::new(FuzzyTermQuery::new(term, 1, true))));
+        }
+        if let Ok(account_id) = word.parse::<u64>() {
+            sub_query.push((
+                Occur::Should,
+                Box::new(BoostQuery::new(
+                    Box::new(TermQuery::new(
+                        Term::from_field_u64(index.account_id, account_id),
+                        IndexRecordOption::Basic,
+                    )),
+                    8.0,
+                )),
+            ));
+        }
+        Ok(Box::new(BooleanQuery::new(sub_query)))
+    }
+    fn parse_facet(facet: &[String]) -> AccountResult<Vec<Facet>> {
+        let mut out = Vec::with_capacity(facet.len());
+        for tmp in facet {
+            let tfacet = Facet::from_text(tmp).map_err(search_error)?;
+            if !ACCOUNT_SEARCH_FACET
+                .iter()
+                .any(|e| Facet::from(*e).is_prefix_of(&tfacet))
+            {
+                return Err(AccountError::System(
+                    fluent_message!("account-search-bad-facet",{
+                        "facet":tmp
+                    }),
+                ));
+            }
+            out.push(tfacet);
+        }
+        Ok(out)
+    }
+    /// 搜索账号
+    /// key_word 空格分隔,每个词需匹配
+    /// facet 如 /status/enable /login/external/github /reg/2024/05
+    /// 同一根节点多个值为或关系,不同根节点为且关系
+    pub async fn search(
+        &self,
+        key_word: &str,
+        facet: &[String],
+        reg_time: (Option<u64>, Option<u64>),
+        page: Option<&PageParam>,
+    ) -> AccountResult<AccountSearchResult> {
+        let index = self.index()?;
+        let facet = Self::parse_facet(facet)?;
+        let mut query: Vec<(Occur, Box<dyn Query>)> = vec![];
+        let words = key_word
+            .split_whitespace()
+            .map(|e| e.to_lowercase())
+            .collect::<HashSet<_>>();
+        for word in words.iter() {
+            query.push((Occur::Must, Self::keyword_query(index, word)?));
+        }
+        for root in ACCOUNT_SEARCH_FACET {
+            let root = Facet::from(*root);
+            let sub_query = facet
+                .iter()
+                .filter(|e| root.is_prefix_of(e))
+                .map(|e| {
+                    let tmp: Box<dyn Query> = Box::new(TermQuery::new(
+                        Term::from_facet(index.facet, e),
+                        IndexRecordOption::Basic,
+                    ));
+                    (Occur::Should, tmp)
+                })
+                .collect::<Vec<_>>();
+            if !sub_query.is_empty() {
+                query.push((Occur::Must, Box::new(BooleanQuery::new(sub_query))));
+            }
+        }
+        if reg_time.0.is_some() || reg_time.1.is_some() {
+            query.push((
+                Occur::Must,
+                Box::new(RangeQuery::new_u64_bounds(
+                    "reg_time".to_string(),
+                    reg_time.0.map(Bound::Included).unwrap_or(Bound::Unbounded),
+                    reg_time.1.map(Bound::Included).unwrap_or(Bound::Unbounded),
+                )),
+            ));
+        }
+        let query: Box<dyn Query> = if query.is_empty() {
+            Box::new(AllQuery)
+        } else {
+            Box::new(BooleanQuery::new(query))
+        };
+
+        let mut root_facet = FacetCollector::for_field("facet");
+        for root in ACCOUNT_SEARCH_FACET {
+            root_facet.add_facet(Facet::from(*root));
+        }
+        //下钻分面:外部账号类型及已筛选年份的月份
+        let mut sub_facet_list = vec![Facet::from("/login/external")];
+        for tmp in facet.iter() {
+            if Facet::from("/reg").is_prefix_of(tmp) {
+                let year = tmp.to_path().into_iter().take(2).collect::<Vec<_>>();
+                let year = Facet::from_path(year);
+                if !sub_facet_list.contains(&year) {
+                    sub_facet_list.push(year);
+                }
+            }
+        }
+        let mut sub_facet = FacetCollector::for_field("facet");
+        for tmp in sub_facet_list.iter() {
+            sub_facet.add_facet(tmp.to_owned());
+        }
+
+        let (offset, limit) = page
+            .map(|e| (e.offset as usize, e.limit.max(1) as usize))
+            .unwrap_or((0, 20));
+        let searcher = index.reader.searcher();
+        let top = TopDocs::with_limit(limit).and_offset(offset);
+        let (docs, total, root_count, sub_count): (Vec<DocAddress>, _, _, _) = if words.is_empty() {
+            //无关键词时按账号ID倒序
+            let (docs, total, root_count, sub_count) = searcher
+                .search(
+                    &query,
+                    &(
+                        top.order_by_u64_field("account_id", Order::Desc),
+                        Count,
+                        root_facet,
+                        sub_facet,
+                    ),
+                )
+                .map_err(search_error)?;
+            (
+                docs.into_iter().map(|e| e.1).collect(),
+                total,
+                root_count,
+                sub_count,
+            )
+        } else {
+            let (docs, total, root_count, sub_count) = searcher
+                .search(&query, &(top, Count, root_facet, sub_facet))
+                .map_err(search_error)?;
+            (
+                docs.into_iter().map(|e| e.1).collect(),
+                total,
+                root_count,
+                sub_count,
+            )
+        };
+        let mut account_ids = Vec::with_capacity(docs.len());
+        for addr in docs {
+            let column = searcher
+                .segment_reader(addr.segment_ord)
+                .fast_fields()
+                .u64("account_id")
+                .map_err(search_error)?;
+            if let Some(account_id) = column.first(addr.doc_id) {
+                account_ids.push(account_id);
+            }
+        }
+        let mut facets = vec![];
+        let mut facet_push = |counts: &FacetCounts, list: &[Facet]| {
+            for tmp in list {
+                for (name, num) in counts.get(tmp.to_owned()) {
+                    facets.push((name.to_string(), num));
+                }
+            }
+        };
+        facet_push(
+            &root_count,
+            &ACCOUNT_SEARCH_FACET
+                .iter()
+                .map(|e| Facet::from(*e))
+                .collect::<Vec<_>>(),
+        );
+        facet_push(&sub_count, &sub_facet_list);
+        Ok(AccountSearchResult {
+            account_ids,
+            total,
+            facets,
+        })
+    }
+    async fn load_doc(&self, account_ids: &[u64]) -> AccountResult<Vec<AccountSearchDoc>> {
+        if account_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let account = self.account.find_by_ids(account_ids).await?;
+        let ids = account.keys().copied().collect::<Vec<_>>();
+        let mut name = self.account_name.find_by_account_ids(&ids).await?;
+        let mut email = self.account_email.find_by_account_ids_vec(&ids).await?;
+        let mut mobile = self.account_mobile.find_by_account_ids_vec(&ids).await?;
+        let mut external = self.account_external.find_by_account_ids_vec(&ids).await?;
+        let mut out = Vec::with_capacity(account.len());
+        for (account_id, account) in account {
+            let mut keyword = vec![account.nickname.to_owned()];
+            if account.nickname.split_whitespace().count() > 1 {
+                keyword.extend(account.nickname.split_whitespace().map(|e| e.to_owned()));
+            }
+            let mut login = vec![];
+            if let Some(name) = name.remove(&account_id) {
+                if !name.username.is_empty() {
+                    keyword.push(name.username);
+                    login.push(vec!["name".to_string()]);
+                }
+            }
+            let email = email.remove(&account_id).unwrap_or_default();
+            if !email.is_empty() {
+                login.push(vec!["email".to_string()]);
+            }
+            for tmp in email {
+                if let Some((_, domain)) = tmp.email.split_once('@') {
+                    keyword.push(domain.to_owned());
+                }
+                keyword.push(tmp.email);
+            }
+            let mobile = mobile.remove(&account_id).unwrap_or_default();
+            if !mobile.is_empty() {
+                login.push(vec!["mobile".to_string()]);
+            }
+            for tmp in mobile {
+                keyword.push(format!("{}{}", tmp.area_code, tmp.mobile));
+                keyword.push(tmp.mobile);
+            }
+            for tmp in external.remove(&account_id).unwrap_or_default() {
+                let login_type = vec!["external".to_string(), tmp.external_type];
+                if !login.contains(&login_type) {
+                    login.push(login_type);
+                }
+                if !tmp.external_name.is_empty() {
+                    keyword.push(tmp.external_name);
+                }
+                keyword.push(tmp.external_id);
+            }
+            keyword.retain(|e| !e.is_empty());
+            out.push(AccountSearchDoc {
+                account,
+                keyword,
+                login,
+            });
+        }
+        Ok(out)
+    }
+    fn create_doc(index: &AccountSearchIndex, data: AccountSearchDoc) -> TantivyDocument {
+        let mut doc = TantivyDocument::default();
+        doc.add_u64(index.account_id, data.account.id);
+        doc.add_u64(index.reg_time, data.account.add_time);
+        for tmp in data.keyword {
+            doc.add_text(index.keyword, tmp);
+        }
+        let status = match AccountStatus::try_from(data.account.status) {
+            Ok(AccountStatus::Enable) => "enable",
+            Ok(AccountStatus::Init) => "init",
+            Ok(AccountStatus::Delete) => "delete",
+            Err(_) => "unknown",
+        };
+        doc.add_facet(index.facet, Facet::from_path(["status", status]));
+        for tmp in data.login {
+            doc.add_facet(
+                index.facet,
+                Facet::from_path(std::iter::once("login".to_string()).chain(tmp)),
+            );
+        }
+        if let Some(reg) = DateTime::from_timestamp(data.account.add_time as i64, 0) {
+            doc.add_facet(
+                index.facet,
+                Facet::from_path([
+                    "reg".to_string(),
+                    reg.year().to_string(),
+                    format!("{:02}", reg.month()),
+                ]),
+            );
+        }
+        doc
+    }
+    //更新指定账号索引,已删除账号从索引移除
+    async fn reindex(&self, account_ids: &[u64]) -> AccountResult<()> {
+        let index = self.index()?;
+        let docs = self.load_doc(account_ids).await?;
+        let mut writer = index.writer.lock().await;
+        for account_id in account_ids {
+            writer.delete_term(Term::from_field_u64(index.account_id, *account_id));
+        }
+        for tmp in docs {
+            writer
+                .add_document(Self::create_doc(index, tmp))
+                .map_err(search_error)?;
+        }
+        writer.commit().map_err(search_error)?;
+        index.reader.reload().map_err(search_error)?;
+        Ok(())
+    }
+    //从MYSQL全量重建本机索引
+    async fn rebuild_index(&self) -> AccountResult<u64> {
+        let index = self.index()?;
+        let mut writer = index.writer.lock().await;
+        writer.delete_all_documents().map_err(search_error)?;
+        let mut offset = 0;
+        let mut total = 0;
+        loop {
+            let account = self
+                .account
+                .list_data(Some(&PageParam::new(offset, ACCOUNT_SEARCH_BATCH)))
+                .await?;
+            if account.is_empty() {
+                break;
+            }
+            offset += account.len() as u64;
+            let docs = self
+                .load_doc(&account.iter().map(|e| e.id).collect::<Vec<_>>())
+                .await?;
+            for tmp in docs {
+                writer
+                    .add_document(Self::create_doc(index, tmp))
+                    .map_err(search_error)?;
+                total += 1;
+            }
+        }
+        writer.commit().map_err(search_error)?;
+        index.reader.reload().map_err(search_error)?;
+        Ok(total)
+    }
+    /// 重建索引,并通知其他节点重建
+    pub async fn rebuild(&self) -> AccountResult<u64> {
+        let total = self.rebuild_index().await?;
+        if let Err(err) = self
+            .remote_notify
+            .call(
+                REMOTE_NOTIFY_TYPE_ACCOUNT_SEARCH,
+                AccountSearchMessage::Rebuild,
+                None,
+                LocalExecType::IgnoreLocal,
+                None,
+            )
+            .await
+        {
+            warn!(
+                "account search rebuild notify fail:{}",
+                err.to_fluent_message().default_format()
+            );
+        }
+        Ok(total)
+    }
+    /// 后台监听账号变动并更新索引
+    /// 本机索引为空时从MYSQL重建
+    pub async fn listen(&self) {
+        let mut change_rx = match self.change_rx.lock().await.take() {
+            Some(rx) => rx,
+            None => {
+                warn!("account search is listening");
+                return;
+            }
+        };
+        let index = match self.index() {
+            Ok(index) => index,
+            Err(_) => return,
+        };
+        if index.reader.searcher().num_docs() == 0 {
+            match self.rebuild_index().await {
+                Ok(total) => info!("account search index rebuild:{}", total),
+                Err(err) => warn!(
+                    "account search index rebuild fail:{}",
+                    err.to_fluent_message().default_format()
+                ),
+            }
+        }
+        //变动通知在事务提交后发送,收到后合并已到达的通知再读取
+        while let Some(account_id) = change_rx.recv().await {
+            let mut account_ids = HashSet::from([account_id]);
+            while let Ok(account_id) = change_rx.try_recv() {
+                account_ids.insert(account_id);
+            }
+            let account_ids = account_ids.into_iter().collect::<Vec<_>>();
+            if let Err(err) = self.reindex(&account_ids).await {
+                warn!(
+                    "account search index update fail:{}",
+                    err.to_fluent_message().default_format()
+                );
+                continue;
+            }
+            if let Err(err) = self
+                .remote_notify
+                .call(
+                    REMOTE_NOTIFY_TYPE_ACCOUNT_SEARCH,
+                    AccountSearchMessage::Change(account_ids),
+                    None,
+                    LocalExecType::IgnoreLocal,
+                    None,
+                )
+                .await
+            {
+                warn!(
+                    "account search change notify fail:{}",
+                    err.to_fluent_message().default_format()
+                );
+            }
+        }
+    }
+}
+
+//tantivy 正则语法转义
+fn regex_syntax_escape(word: &str) -> String {
+    let mut out = String::with_capacity(word.len());
+    for c in word.chars() {
+        if "\\.+*?()|[]{}^$#&-~\"<>@".contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// 订阅其他节点的账号变动,更新本机索引
+pub struct AccountSearchNotify {
+    search: Arc<AccountSearch>,
+}
+impl AccountSearchNotify {
+    pub fn new(search: Arc<AccountSearch>) -> Self {
+        Self { search }
+    }
+}
+
+#[async_trait]
+impl RemoteTask for AccountSearchNotify {
+    fn msg_type(&self) -> u8 {
+        REMOTE_NOTIFY_TYPE_ACCOUNT_SEARCH
+    }
+    async fn run(&self, msg: MsgSendBody) -> Result<Option<Value>, String> {
+        let msg =
+            serde_json::from_value::<AccountSearchMessage>(msg.data).map_err(|e| e.to_string())?;
+        match msg {
+            AccountSearchMessage::Change(account_ids) => self
+                .search
+                .reindex(&account_ids)
+                .await
+                .map_err(|e| e.to_fluent_message().default_format())?,
+            AccountSearchMessage::Rebuild => {
+                self.search
+                    .rebuild_index()
+                    .await
+                    .map_err(|e| e.to_fluent_message().default_format())?;
+            }
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,516 @@
+//登录安全检测
+//记录用户登录设备,检测新设备、新国家及不可能的行程,产生待用户确认的安全事件
+use lsys_access::dao::{AccessDao, SessionBody};
+use lsys_core::db::{Insert, ModelTableName, SqlExpr, SqlQuote, Update, WhereOption};
+use lsys_core::{
+    fluent_message, model_option_set, now_time, sql_format, string_clear, LimitParam, RequestEnv,
+    StringClear, STRING_CLEAR_FORMAT,
+};
+use lsys_logger::dao::ChangeLoggerDao;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use sqlx::{MySql, Pool};
+use std::sync::Arc;
+
+use crate::model::{
+    AccountLoginDeviceModel, AccountLoginDeviceModelRef, AccountLoginModel,
+    AccountSecurityEventModel, AccountSecurityEventModelRef, AccountSecurityEventStatus,
+};
+
+use super::{logger::LogAccountSecurity, AccountError, AccountResult};
+
+pub const ACCOUNT_RISK_NEW_DEVICE: &str = "new-device";
+pub const ACCOUNT_RISK_NEW_COUNTRY: &str = "new-country";
+pub const ACCOUNT_RISK_IMPOSSIBLE_TRAVEL: &str = "impossible-travel";
+pub const ACCOUNT_RISK_REFRESH_REUSE: &str = "refresh-token-reuse";
+
+//两次登录距离小于此值(公里)时不检测行程,避免IP定位误差
+const TRAVEL_MIN_DISTANCE: f64 = 100.0;
+//坐标存储精度,万分之一度
+const LOCATION_SCALE: f64 = 10000.0;
+
+/// IP对应的地理信息
+#[derive(Clone, Debug, Default)]
+pub struct AccountLoginGeo {
+    pub country: String,
+    pub city: String,
+    pub location: Option<(f64, f64)>, //纬度,经度
+}
+
+pub struct AccountSecurityCheckParam<'t> {
+    pub account_id: u64,
+    pub session_id: u64,
+    pub login_id: u64,
+    pub login_ip: &'t str,
+    pub device_id: Option<&'t str>,
+    pub user_agent: Option<&'t str>,
+    pub geo: Option<&'t AccountLoginGeo>,
+    pub travel_speed: u64, //正常出行最大速度,公里每小时,0 不检测
+}
+
+pub struct AccountSecurity {
+    db: Pool<MySql>,
+    access: Arc<AccessDao>,
+    logger: Arc<ChangeLoggerDao>,
+}
+
+impl AccountSecurity {
+    pub fn new(db: Pool<MySql>, access: Arc<AccessDao>, logger: Arc<ChangeLoggerDao>) -> Self {
+        Self { db, access, logger }
+    }
+    /// 设备指纹,优先使用客户端提交的设备ID,否则使用UA
+    pub fn device_hash(device_id: Option<&str>, user_agent: Option<&str>) -> String {
+        let device = match device_id.filter(|e| !e.is_empty()) {
+            Some(id) => format!("id:{}", id),
+            None => format!("ua:{}", user_agent.unwrap_or_default()),
+        };
+        Sha256::digest(device.as_bytes())
+            .iter()
+            .map(|e| format!("{:02x}", e))
+            .collect()
+    }
+    //两坐标间距离,公里
+    fn distance(from: (f64, f64), to: (f64, f64)) -> f64 {
+        let (lat1, lng1) = (from.0.to_radians(), from.1.to_radians());
+        let (lat2, lng2) = (to.0.to_radians(), to.1.to_radians());
+        let a = ((lat2 - lat1) / 2.0).sin().powi(2)
+            + lat1.cos() * lat2.cos() * ((lng2 - lng1) / 2.0).sin().powi(2);
+        6371.0 * 2.0 * a.sqrt().asin()
+    }
+    //是否首次在该国家登录,无国家登录记录时不检测
+    async fn is_new_country(
+        &self,
+        account_id: u64,
+        login_id: u64,
+        country: &str,
+    ) -> AccountResult<bool> {
+        let where_sql = sql_format!(
+            "account_id={} and is_login=1 and id!={}",
+            account_id,
+            login_id
+        );
+        let total = sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) as total from {} where {} and login_country!=''",
+            AccountLoginModel::table_name(),
+            SqlExpr(where_sql.as_str())
+        ))
+        .fetch_one(&self.db)
+        .await?;
+        if total == 0 {
+            return Ok(false);
+        }
+        let same = sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) as total from {} where {} and login_country={}",
+            AccountLoginModel::table_name(),
+            SqlExpr(where_sql.as_str()),
+            country
+        ))
+        .fetch_one(&self.db)
+        .await?;
+        Ok(same == 0)
+    }
+    /// 登录成功后检测,返回产生的安全事件
+    /// 用户首次记录登录设备时仅记录设备不检测
+    pub async fn login_check(
+        &self,
+        param: &AccountSecurityCheckParam<'_>,
+    ) -> AccountResult<Option<AccountSecurityEventModel>> {
+        let time = now_time()?;
+        let device_hash = Self::device_hash(param.device_id, param.user_agent);
+        let device_name = string_clear(
+            param.user_agent.unwrap_or_default(),
+            StringClear::Option(STRING_CLEAR_FORMAT),
+            Some(255),
+        );
+        let login_ip = string_clear(
+            param.login_ip,
+            StringClear::Option(STRING_CLEAR_FORMAT),
+            Some(46),
+        );
+        let last_device = sqlx::query_as::<_, AccountLoginDeviceModel>(&sql_format!(
+            "select * from {} where account_id={} order by last_time desc limit 1",
+            AccountLoginDeviceModel::table_name(),
+            param.account_id
+        ))
+        .fetch_optional(&self.db)
+        .await?;
+        let device = sqlx::query_as::<_, AccountLoginDeviceModel>(&sql_format!(
+            "select * from {} where account_id={} and device_hash={}",
+            AccountLoginDeviceModel::table_name(),
+            param.account_id,
+            device_hash
+        ))
+        .fetch_optional(&self.db)
+        .await?;
+        let mut event_type = vec![];
+        let mut event_data = Map::new();
+        if let Some(ref last_device) = last_device {
+            if device.is_none() {
+                event_type.push(ACCOUNT_RISK_NEW_DEVICE);
+            }
+            if let Some(geo) = param.geo.filter(|e| !e.country.is_empty()) {
+                if self
+                    .is_new_country(param.account_id, param.login_id, &geo.country)
+                    .await?
+                {
+                    event_type.push(ACCOUNT_RISK_NEW_COUNTRY);
+                    event_data.insert("country".to_string(), json!(geo.country));
+                }
+                let last_location = (
+                    last_device.login_lat as f64 / LOCATION_SCALE,
+                    last_device.login_lng as f64 / LOCATION_SCALE,
+                );
+                if let Some(location) = geo.location.filter(|_| {
+                    param.travel_speed > 0
+                        && (last_device.login_lat != 0 || last_device.login_lng != 0)
+                }) {
+                    let distance = Self::distance(last_location, location);
+                    let hours = time.saturating_sub(last_device.last_time).max(60) as f64 / 3600.0;
+                    if distance > TRAVEL_MIN_DISTANCE
+                        && distance / hours > param.travel_speed as f64
+                    {
+                        event_type.push(ACCOUNT_RISK_IMPOSSIBLE_TRAVEL);
+                        event_data.insert("prev_city".to_string(), json!(last_device.login_city));
+                        event_data.insert("prev_time".to_string(), json!(last_device.last_time));
+                        event_data.insert("distance".to_string(), json!(distance.round() as u64));
+                    }
+                }
+            }
+        }
+        let login_country = param.geo.map(|e| e.country.to_owned()).unwrap_or_default();
+        let login_city = param.geo.map(|e| e.city.to_owned()).unwrap_or_default();
+        let (login_lat, login_lng) = param
+            .geo
+            .and_then(|e| e.location)
+            .map(|(lat, lng)| {
+                (
+                    (lat * LOCATION_SCALE).round() as i32,
+                    (lng * LOCATION_SCALE).round() as i32,
+                )
+            })
+            .unwrap_or_default();
+        match device {
+            Some(device) => {
+                let login_num = device.login_num.saturating_add(1);
+                let change = model_option_set!(AccountLoginDeviceModelRef,{
+                    device_name:device_name,
+                    login_ip:login_ip,
+                    login_country:login_country,
+                    login_city:login_city,
+                    login_lat:login_lat,
+                    login_lng:login_lng,
+                    login_num:login_num,
+                    last_time:time,
+                });
+                Update::<AccountLoginDeviceModel, _>::new(change)
+                    .execute_by_where(
+                        &WhereOption::Where(sql_format!("id={}", device.id)),
+                        &self.db,
+                    )
+                    .await?;
+            }
+            None => {
+                let login_num = 1;
+                let vdata = model_option_set!(AccountLoginDeviceModelRef,{
+                    account_id:param.account_id,
+                    device_hash:device_hash,
+                    device_name:device_name,
+                    login_ip:login_ip,
+                    login_country:login_country,
+                    login_city:login_city,
+                    login_lat:login_lat,
+                    login_lng:login_lng,
+                    login_num:login_num,
+                    last_time:time,
+                    add_time:time,
+                });
+                let change = model_option_set!(AccountLoginDeviceModelRef,{
+                    last_time:time,
+                });
+                Insert::<AccountLoginDeviceModel, _>::new(vdata)
+                    .execute_update(&Update::<AccountLoginDeviceModel, _>::new(change), &self.db)
+                    .await?;
+            }
+        }
+        if event_type.is_empty() {
+            return Ok(None);
+        }
+        let event_type = event_type.join(",");
+        let event_data = Value::Object(event_data).to_string();
+        let status = AccountSecurityEventStatus::Pending as i8;
+        let vdata = model_option_set!(AccountSecurityEventModelRef,{
+            account_id:param.account_id,
+            session_id:param.session_id,
+            event_type:event_type,
+            device_hash:device_hash,
+            device_name:device_name,
+            login_ip:login_ip,
+            login_city:login_city,
+            event_data:event_data,
+            status:status,
+            add_time:time,
+        });
+        let id = Insert::<AccountSecurityEventModel, _>::new(vdata)
+            .execute(&self.db)
+            .await?
+            .last_insert_id();
+        Ok(Some(AccountSecurityEventModel {
+            id,
+            account_id: param.account_id,
+            session_id: param.session_id,
+            event_type,
+            device_hash,
+            device_name,
+            login_ip,
+            login_city,
+            event_data,
+            status,
+            add_time: time,
+            change_time: 0,
+        }))
+    }
+    /// 已轮换的刷新token被再次使用,相关登录已注销,产生待用户确认的安全事件
+    pub async fn refresh_reuse_event(
+        &self,
+        account_id: u64,
+        session_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<u64> {
+        let time = now_time()?;
+        let device_hash = Self::device_hash(
+            env_data.and_then(|e| e.device_id.as_deref()),
+            env_data.and_then(|e| e.request_user_agent.as_deref()),
+        );
+        let device_name = string_clear(
+            env_data
+                .and_then(|e| e.request_user_agent.as_deref())
+                .unwrap_or_default(),
+            StringClear::Option(STRING_CLEAR_FORMAT),
+            Some(255),
+        );
+        let login_ip = string_clear(
+            env_data
+                .and_then(|e| e.request_ip.as_deref())
+                .unwrap_or_default(),
+            StringClear::Option(STRING_CLEAR_FORMAT),
+            Some(46),
+        );
+        let event_type = ACCOUNT_RISK_REFRESH_REUSE.to_string();
+        let event_data = json!({ "session_id": session_id }).to_string();
+        let status = AccountSecurityEventStatus::Pending as i8;
+        let vdata = model_option_set!(AccountSecurityEventModelRef,{
+            account_id:account_id,
+            session_id:session_id,
+            event_type:event_type,
+            device_hash:device_hash,
+            device_name:device_name,
+            login_ip:login_ip,
+            event_data:event_data,
+            status:status,
+            add_time:time,
+        });
+        let id = Insert::<AccountSecurityEventModel, _>::new(vdata)
+            .execute(&self.db)
+            .await?
+            .last_insert_id();
+        self.logger
+            .add(
+                &LogAccountSecurity {
+                    action: "add",
+                    event_type: &event_type,
+                    login_ip: &login_ip,
+                    account_id,
+                },
+                Some(id),
+                None,
+                None,
+                env_data,
+            )
+            .await;
+        Ok(id)
+    }
+    async fn find_pending_event(
+        &self,
+        account_id: u64,
+        event_id: u64,
+    ) -> AccountResult<AccountSecurityEventModel> {
+        match sqlx::query_as::<_, AccountSecurityEventModel>(&sql_format!(
+            "select * from {} where id={} and account_id={} and status={}",
+            AccountSecurityEventModel::table_name(),
+            event_id,
+            account_id,
+            AccountSecurityEventStatus::Pending,
+        ))
+        .fetch_one(&self.db)
+        .await
+        {
+            Ok(event) => Ok(event),
+            Err(sqlx::Error::RowNotFound) => Err(AccountError::Param(fluent_message!(
+                "account-security-event-not-find",
+                { "id": event_id }
+            ))),
+            Err(err) => Err(err.into()),
+        }
+    }
+    async fn set_event_status(
+        &self,
+        session_body: &SessionBody,
+        event: &AccountSecurityEventModel,
+        status: AccountSecurityEventStatus,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<()> {
+        let time = now_time()?;
+        let status = status as i8;
+        let change = model_option_set!(AccountSecurityEventModelRef,{
+            status:status,
+            change_time:time,
+        });
+        Update::<AccountSecurityEventModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("id={}", event.id)),
+                &self.db,
+            )
+            .await?;
+        self.logger
+            .add(
+                &LogAccountSecurity {
+                    action: if AccountSecurityEventStatus::Secure.eq(status) {
+                        "secure"
+                    } else {
+                        "confirm"
+                    },
+                    event_type: &event.event_type,
+                    login_ip: &event.login_ip,
+                    account_id: event.account_id,
+                },
+                Some(event.id),
+                Some(session_body.user_id()),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(())
+    }
+    /// 用户确认为本人登录
+    pub async fn event_confirm(
+        &self,
+        session_body: &SessionBody,
+        event_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<()> {
+        let event = self
+            .find_pending_event(session_body.account_id()?, event_id)
+            .await?;
+        self.set_event_status(
+            session_body,
+            &event,
+            AccountSecurityEventStatus::Confirm,
+            env_data,
+        )
+        .await
+    }
+    /// 用户确认非本人登录,下线除当前外的全部登录并移除该登录设备
+    pub async fn event_secure(
+        &self,
+        session_body: &SessionBody,
+        event_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<u64> {
+        let event = self
+            .find_pending_event(session_body.account_id()?, event_id)
+            .await?;
+        self.set_event_status(
+            session_body,
+            &event,
+            AccountSecurityEventStatus::Secure,
+            env_data,
+        )
+        .await?;
+        sqlx::query(&sql_format!(
+            "delete from {} where account_id={} and device_hash={}",
+            AccountLoginDeviceModel::table_name(),
+            event.account_id,
+            event.device_hash
+        ))
+        .execute(&self.db)
+        .await?;
+        let total = self
+            .access
+            .auth
+            .logout_user_session(
+                session_body.user_id(),
+                None,
+                Some(session_body.session_id()),
+            )
+            .await?;
+        Ok(total)
+    }
+    /// 安全事件列表
+    pub async fn event_data(
+        &self,
+        account_id: u64,
+        status: Option<AccountSecurityEventStatus>,
+        limit: Option<&LimitParam>,
+    ) -> AccountResult<(Vec<AccountSecurityEventModel>, Option<u64>)> {
+        let mut where_sql = sql_format!("account_id={}", account_id);
+        if let Some(status) = status {
+            where_sql += &sql_format!(" and status={}", status);
+        }
+        let sql = if let Some(page) = limit {
+            format!(
+                "{} {} order by {} {}",
+                where_sql,
+                page.where_sql("id", Some("and")),
+                page.order_sql("id"),
+                page.limit_sql(),
+            )
+        } else {
+            format!("{} order by id desc", where_sql)
+        };
+        let mut data = sqlx::query_as::<_, AccountSecurityEventModel>(&sql_format!(
+            "select * from {} where {}",
+            AccountSecurityEventModel::table_name(),
+            SqlExpr(sql)
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        let next = limit
+            .as_ref()
+            .map(|page| page.tidy(&mut data))
+            .unwrap_or_default()
+            .map(|e| e.id);
+        Ok((data, next))
+    }
+    /// 安全事件数量
+    pub async fn event_count(
+        &self,
+        account_id: u64,
+        status: Option<AccountSecurityEventStatus>,
+    ) -> AccountResult<i64> {
+        let mut where_sql = sql_format!("account_id={}", account_id);
+        if let Some(status) = status {
+            where_sql += &sql_format!(" and status={}", status);
+        }
+        let res = sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) as total from {} where {}",
+            AccountSecurityEventModel::table_name(),
+            SqlExpr(where_sql)
+        ))
+        .fetch_one(&self.db)
+        .await?;
+        Ok(res)
+    }
+    /// 用户登录设备列表
+    pub async fn device_data(
+        &self,
+        account_id: u64,
+    ) -> AccountResult<Vec<AccountLoginDeviceModel>> {
+        Ok(sqlx::query_as::<_, AccountLoginDeviceModel>(&sql_format!(
+            "select * from {} where account_id={} order by last_time desc",
+            AccountLoginDeviceModel::table_name(),
+            account_id
+        ))
+        .fetch_all(&self.db)
+        .await?)
+    }
+}
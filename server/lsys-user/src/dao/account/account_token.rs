@@ -0,0 +1,382 @@
+//个人访问令牌
+//令牌以登录记录方式保存,只存储令牌HASH,授权范围为创建时用户拥有的RBAC操作
+use lsys_access::dao::{AccessAuthLoginData, AccessDao, AccessLoginData, SessionBody};
+use lsys_core::cache::{LocalCache, LocalCacheConfig};
+use lsys_core::db::{Insert, ModelTableName, SqlExpr, SqlQuote, Update, WhereOption};
+use lsys_core::{
+    fluent_message, model_option_set, now_time, rand_str, sql_format, valid_key, LimitParam,
+    RandType, RemoteNotify, RequestEnv, ValidParam, ValidParamCheck, ValidPattern, ValidStrlen,
+};
+use lsys_logger::dao::ChangeLoggerDao;
+use sha2::{Digest, Sha256};
+use sqlx::{MySql, Pool};
+use std::sync::Arc;
+
+use crate::model::{AccountTokenModel, AccountTokenModelRef, AccountTokenStatus};
+
+use super::{logger::LogAccountToken, AccountError, AccountResult};
+
+pub const ACCOUNT_TOKEN_LOGIN_TYPE: &str = "token";
+//令牌前缀,用于区分普通登录token
+const ACCOUNT_TOKEN_PREFIX: &str = "pat_";
+//令牌最长有效期
+const ACCOUNT_TOKEN_MAX_TIME: u64 = 366 * 86400;
+//最后使用时间更新间隔
+const ACCOUNT_TOKEN_USED_INTERVAL: u64 = 60;
+
+pub struct AccountToken {
+    db: Pool<MySql>,
+    access: Arc<AccessDao>,
+    pub(crate) cache: Arc<LocalCache<u64, AccountTokenModel>>,
+    logger: Arc<ChangeLoggerDao>,
+}
+
+pub struct AccountTokenAddParam<'t> {
+    pub token_name: &'t str,
+    pub token_scope: &'t [String],
+    pub expire_time: u64,
+}
+
+impl AccountToken {
+    pub fn new(
+        db: Pool<MySql>,
+        access: Arc<AccessDao>,
+        remote_notify: Arc<RemoteNotify>,
+        config: LocalCacheConfig,
+        logger: Arc<ChangeLoggerDao>,
+    ) -> Self {
+        Self {
+            cache: Arc::new(LocalCache::new(remote_notify, config)),
+            db,
+            access,
+            logger,
+        }
+    }
+    /// 是否为个人访问令牌
+    pub fn is_token(token: &str) -> bool {
+        token.starts_with(ACCOUNT_TOKEN_PREFIX)
+    }
+    /// 令牌存储的HASH,同时为对应登录记录的token_data
+    pub fn token_hash(token: &str) -> String {
+        Sha256::digest(token.as_bytes())
+            .iter()
+            .map(|e| format!("{:02x}", e))
+            .collect()
+    }
+    /// 授权范围列表
+    pub fn token_scope(token: &AccountTokenModel) -> Vec<String> {
+        token
+            .token_scope
+            .split(',')
+            .filter(|e| !e.is_empty())
+            .map(|e| e.to_string())
+            .collect()
+    }
+    /// 个人访问令牌登录不能访问授权范围以外的接口
+    pub fn session_deny(session_body: &SessionBody) -> AccountResult<()> {
+        if session_body.session().login_type == ACCOUNT_TOKEN_LOGIN_TYPE {
+            return Err(AccountError::System(fluent_message!(
+                "account-token-not-allow"
+            )));
+        }
+        Ok(())
+    }
+    async fn add_param_valid(&self, param: &AccountTokenAddParam<'_>) -> AccountResult<()> {
+        let time = now_time()?;
+        let mut valid_param = ValidParam::default();
+        valid_param.add(
+            valid_key!("token_name"),
+            &param.token_name,
+            &ValidParamCheck::default()
+                .add_rule(ValidPattern::NotFormat)
+                .add_rule(ValidStrlen::range(1, 64)),
+        );
+        for scope in param.token_scope {
+            let (res_type, op_key) = scope.split_once(':').unwrap_or((scope, ""));
+            valid_param
+                .add(
+                    valid_key!("token_scope"),
+                    &res_type,
+                    &ValidParamCheck::default()
+                        .add_rule(ValidPattern::Ident)
+                        .add_rule(ValidStrlen::range(1, 32)),
+                )
+                .add(
+                    valid_key!("token_scope"),
+                    &op_key,
+                    &ValidParamCheck::default()
+                        .add_rule(ValidPattern::Ident)
+                        .add_rule(ValidStrlen::range(1, 32)),
+                );
+        }
+        valid_param.check()?;
+        if param.token_scope.is_empty() {
+            return Err(AccountError::Param(fluent_message!(
+                "account-token-scope-empty"
+            )));
+        }
+        if param.token_scope.join(",").len() > 2000 {
+            return Err(AccountError::Param(fluent_message!(
+                "account-token-scope-long"
+            )));
+        }
+        if param.expire_time <= time || param.expire_time > time + ACCOUNT_TOKEN_MAX_TIME {
+            return Err(AccountError::Param(fluent_message!(
+                "account-token-bad-expire",
+                {
+                    "max_day":ACCOUNT_TOKEN_MAX_TIME/86400
+                }
+            )));
+        }
+        Ok(())
+    }
+    /// 为当前登录用户创建令牌,返回令牌记录及令牌明文,明文仅在创建时返回
+    pub async fn add_token(
+        &self,
+        session_body: &SessionBody,
+        param: &AccountTokenAddParam<'_>,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<(AccountTokenModel, String)> {
+        self.add_param_valid(param).await?;
+        let account_id = session_body.account_id()?;
+        let token = format!(
+            "{}{}",
+            ACCOUNT_TOKEN_PREFIX,
+            rand_str(RandType::LowerNumber, 40)
+        );
+        let token_hash = Self::token_hash(&token);
+        let user = session_body.user();
+        let token_session = self
+            .access
+            .auth
+            .do_login(&AccessAuthLoginData {
+                app_id: user.app_id,
+                oauth_app_id: 0,
+                user_data: &user.user_data,
+                user_nickname: &user.user_nickname,
+                token_data: Some(&token_hash),
+                login_type: ACCOUNT_TOKEN_LOGIN_TYPE,
+                login_data: Some(&AccessLoginData {
+                    login_ip: env_data.and_then(|e| e.request_ip.as_deref()),
+                    user_agent: env_data.and_then(|e| e.request_user_agent.as_deref()),
+                    device_name: Some(param.token_name),
+                    expire_time: param.expire_time,
+                    ..Default::default()
+                }),
+            })
+            .await?;
+        let time = now_time()?;
+        let token_name = param.token_name.to_owned();
+        let token_scope = param.token_scope.join(",");
+        let session_id = token_session.session_id();
+        let status = AccountTokenStatus::Enable as i8;
+        let vdata = model_option_set!(AccountTokenModelRef,{
+            account_id:account_id,
+            session_id:session_id,
+            token_name:token_name,
+            token_hash:token_hash,
+            token_scope:token_scope,
+            expire_time:param.expire_time,
+            status:status,
+            add_time:time,
+            change_time:time,
+        });
+        let id = match Insert::<AccountTokenModel, _>::new(vdata)
+            .execute(&self.db)
+            .await
+        {
+            Ok(res) => res.last_insert_id(),
+            Err(err) => {
+                self.access.auth.do_logout(&token_session).await?;
+                return Err(err.into());
+            }
+        };
+        self.logger
+            .add(
+                &LogAccountToken {
+                    action: "add",
+                    token_name: &token_name,
+                    token_scope: &token_scope,
+                    expire_time: param.expire_time,
+                    account_id,
+                },
+                Some(id),
+                Some(session_body.user_id()),
+                None,
+                env_data,
+            )
+            .await;
+        Ok((
+            AccountTokenModel {
+                id,
+                account_id,
+                session_id,
+                token_name,
+                token_hash,
+                token_scope,
+                expire_time: param.expire_time,
+                last_used_time: 0,
+                last_used_ip: "".to_string(),
+                status,
+                add_time: time,
+                change_time: time,
+            },
+            token,
+        ))
+    }
+    /// 撤销令牌,同时下线对应登录
+    pub async fn del_token(
+        &self,
+        session_body: &SessionBody,
+        token_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<()> {
+        let account_id = session_body.account_id()?;
+        let token = match sqlx::query_as::<_, AccountTokenModel>(&sql_format!(
+            "select * from {} where id={} and account_id={} and status={}",
+            AccountTokenModel::table_name(),
+            token_id,
+            account_id,
+            AccountTokenStatus::Enable,
+        ))
+        .fetch_one(&self.db)
+        .await
+        {
+            Ok(token) => token,
+            Err(sqlx::Error::RowNotFound) => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+        let time = now_time()?;
+        let status = AccountTokenStatus::Delete as i8;
+        let change = model_option_set!(AccountTokenModelRef,{
+            status:status,
+            change_time:time,
+        });
+        Update::<AccountTokenModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!("id={}", token.id)),
+                &self.db,
+            )
+            .await?;
+        self.cache.clear(&token.session_id).await;
+        self.access
+            .auth
+            .logout_session(session_body.user_id(), token.session_id)
+            .await?;
+        self.logger
+            .add(
+                &LogAccountToken {
+                    action: "del",
+                    token_name: &token.token_name,
+                    token_scope: &token.token_scope,
+                    expire_time: token.expire_time,
+                    account_id,
+                },
+                Some(token.id),
+                Some(session_body.user_id()),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(())
+    }
+    /// 记录令牌使用时间及IP
+    pub async fn record_used(&self, token_hash: &str, used_ip: Option<&str>) -> AccountResult<()> {
+        let time = now_time()?;
+        let used_ip = used_ip
+            .unwrap_or_default()
+            .chars()
+            .take(46)
+            .collect::<String>();
+        let change = model_option_set!(AccountTokenModelRef,{
+            last_used_time:time,
+            last_used_ip:used_ip,
+        });
+        Update::<AccountTokenModel, _>::new(change)
+            .execute_by_where(
+                &WhereOption::Where(sql_format!(
+                    "token_hash={} and status={} and last_used_time<{}",
+                    token_hash,
+                    AccountTokenStatus::Enable,
+                    time.saturating_sub(ACCOUNT_TOKEN_USED_INTERVAL)
+                )),
+                &self.db,
+            )
+            .await?;
+        Ok(())
+    }
+    /// 用户令牌列表
+    pub async fn list_data(
+        &self,
+        account_id: u64,
+        limit: Option<&LimitParam>,
+    ) -> AccountResult<(Vec<AccountTokenModel>, Option<u64>)> {
+        let where_sql = sql_format!(
+            "account_id={} and status={}",
+            account_id,
+            AccountTokenStatus::Enable
+        );
+        let sql = if let Some(page) = limit {
+            format!(
+                "{} {} order by {} {}",
+                where_sql,
+                page.where_sql("id", Some("and")),
+                page.order_sql("id"),
+                page.limit_sql(),
+            )
+        } else {
+            format!("{} order by id desc", where_sql)
+        };
+        let mut data = sqlx::query_as::<_, AccountTokenModel>(&sql_format!(
+            "select * from {} where {}",
+            AccountTokenModel::table_name(),
+            SqlExpr(sql)
+        ))
+        .fetch_all(&self.db)
+        .await?;
+        let next = limit
+            .as_ref()
+            .map(|page| page.tidy(&mut data))
+            .unwrap_or_default()
+            .map(|e| e.id);
+        Ok((data, next))
+    }
+    /// 用户令牌数量
+    pub async fn list_count(&self, account_id: u64) -> AccountResult<i64> {
+        let res = sqlx::query_scalar::<_, i64>(&sql_format!(
+            "select count(*) as total from {} where account_id={} and status={}",
+            AccountTokenModel::table_name(),
+            account_id,
+            AccountTokenStatus::Enable
+        ))
+        .fetch_one(&self.db)
+        .await?;
+        Ok(res)
+    }
+    lsys_core::impl_dao_fetch_one_by_one!(
+        db,
+        find_by_session_id,
+        u64,
+        AccountTokenModel,
+        AccountResult<AccountTokenModel>,
+        session_id,
+        "session_id={session_id} and status={status}",
+        status = AccountTokenStatus::Enable
+    );
+    pub fn cache(&'_ self) -> AccountTokenCache<'_> {
+        AccountTokenCache { dao: self }
+    }
+}
+
+pub struct AccountTokenCache<'t> {
+    pub dao: &'t AccountToken,
+}
+impl AccountTokenCache<'_> {
+    lsys_core::impl_cache_fetch_one!(
+        find_by_session_id,
+        dao,
+        cache,
+        u64,
+        AccountResult<AccountTokenModel>
+    );
+}
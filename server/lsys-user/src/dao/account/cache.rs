@@ -5,7 +5,7 @@ use lsys_core::cache::{LocalCache, LocalCacheClearItem};
 
 use crate::model::{
     AccountAddressModel, AccountEmailModel, AccountExternalModel, AccountInfoModel,
-    AccountMobileModel, AccountModel, AccountNameModel,
+    AccountMobileModel, AccountModel, AccountNameModel, AccountTokenModel,
 };
 
 use super::AccountDao;
@@ -21,6 +21,7 @@ pub enum AccountLocalCacheClear {
     Mobile(Arc<LocalCache<u64, AccountMobileModel>>),
     UserMobile(Arc<LocalCache<u64, Vec<u64>>>),
     Name(Arc<LocalCache<u64, AccountNameModel>>),
+    Token(Arc<LocalCache<u64, AccountTokenModel>>),
 }
 
 impl AccountLocalCacheClear {
@@ -36,6 +37,7 @@ impl AccountLocalCacheClear {
             Self::Mobile(account.account_mobile.cache.clone()),
             Self::UserMobile(account.account_email.account_cache.clone()),
             Self::Name(account.account_name.cache.clone()),
+            Self::Token(account.account_token.cache.clone()),
         ]
     }
 }
@@ -51,6 +53,7 @@ impl LocalCacheClearItem<'_> for AccountLocalCacheClear {
             Self::Info(cache) => cache.config().cache_name,
             Self::Mobile(cache) => cache.config().cache_name,
             Self::Name(cache) => cache.config().cache_name,
+            Self::Token(cache) => cache.config().cache_name,
             Self::UserMobile(cache) => cache.config().cache_name,
             Self::UserExternal(cache) => cache.config().cache_name,
             Self::UserEmail(cache) => cache.config().cache_name,
@@ -66,6 +69,7 @@ impl LocalCacheClearItem<'_> for AccountLocalCacheClear {
             Self::Info(cache) => cache.del(key).await,
             Self::Mobile(cache) => cache.del(key).await,
             Self::Name(cache) => cache.del(key).await,
+            Self::Token(cache) => cache.del(key).await,
             Self::UserMobile(cache) => cache.del(key).await,
             Self::UserExternal(cache) => cache.del(key).await,
             Self::UserEmail(cache) => cache.del(key).await,
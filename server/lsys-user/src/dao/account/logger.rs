@@ -168,3 +168,244 @@ impl ChangeLogData for LogAccountPassWrod {
         serde_json::to_string(&self).unwrap_or_default()
     }
 }
+
+#[derive(Serialize)]
+pub(crate) struct LogAccountToken<'t> {
+    pub action: &'t str,
+    pub token_name: &'t str,
+    pub token_scope: &'t str,
+    pub expire_time: u64,
+    pub account_id: u64,
+}
+
+impl ChangeLogData for LogAccountToken<'_> {
+    fn log_type() -> &'static str {
+        "account-token"
+    }
+    fn message(&self) -> String {
+        format!("{} personal token {}", self.action, self.token_name)
+    }
+    fn encode(&self) -> String {
+        serde_json::to_string(&self).unwrap_or_default()
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct LogAccountSecurity<'t> {
+    pub action: &'t str,
+    pub event_type: &'t str,
+    pub login_ip: &'t str,
+    pub account_id: u64,
+}
+
+impl ChangeLogData for LogAccountSecurity<'_> {
+    fn log_type() -> &'static str {
+        "account-security"
+    }
+    fn message(&self) -> String {
+        format!("{} security event {}", self.action, self.event_type)
+    }
+    fn encode(&self) -> String {
+        serde_json::to_string(&self).unwrap_or_default()
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct LogAccountMerge<'t> {
+    pub action: &'t str,
+    pub target_account_id: u64,
+    pub source_account_id: u64,
+    pub preview: &'t super::AccountMergePreview,
+}
+
+impl ChangeLogData for LogAccountMerge<'_> {
+    fn log_type() -> &'static str {
+        "account-merge"
+    }
+    fn message(&self) -> String {
+        format!(
+            "{} account {} into {}",
+            self.action, self.source_account_id, self.target_account_id
+        )
+    }
+    fn encode(&self) -> String {
+        serde_json::to_string(&self).unwrap_or_default()
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct LogAccountExport<'t> {
+    pub action: &'t str,
+    pub message: &'t str,
+    pub account_id: u64,
+}
+
+impl ChangeLogData for LogAccountExport<'_> {
+    fn log_type() -> &'static str {
+        "account-export"
+    }
+    fn message(&self) -> String {
+        format!("{} data export {}", self.action, self.message)
+    }
+    fn encode(&self) -> String {
+        serde_json::to_string(&self).unwrap_or_default()
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct LogAccountDelete<'t> {
+    pub action: &'t str,
+    pub message: &'t str,
+    pub account_id: u64,
+    pub delete_time: u64,
+}
+
+impl ChangeLogData for LogAccountDelete<'_> {
+    fn log_type() -> &'static str {
+        "account-delete"
+    }
+    fn message(&self) -> String {
+        format!("{} account delete {}", self.action, self.message)
+    }
+    fn encode(&self) -> String {
+        serde_json::to_string(&self).unwrap_or_default()
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct LogAccountInvite<'t> {
+    pub action: &'t str,
+    pub max_num: u32,
+    pub expire_time: u64,
+    pub note: &'t str,
+}
+
+impl ChangeLogData for LogAccountInvite<'_> {
+    fn log_type() -> &'static str {
+        "account-invite"
+    }
+    fn message(&self) -> String {
+        format!("{} invite code {}", self.action, self.note)
+    }
+    fn encode(&self) -> String {
+        serde_json::to_string(&self).unwrap_or_default()
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct LogAccountRegister<'t> {
+    pub action: &'t str,
+    pub message: &'t str,
+    pub account_id: u64,
+    pub invite_id: u64,
+}
+
+impl ChangeLogData for LogAccountRegister<'_> {
+    fn log_type() -> &'static str {
+        "account-register"
+    }
+    fn message(&self) -> String {
+        format!("{} register {}", self.action, self.message)
+    }
+    fn encode(&self) -> String {
+        serde_json::to_string(&self).unwrap_or_default()
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct LogAccountImport<'t> {
+    pub action: &'t str,
+    pub name: &'t str,
+    pub total_num: u32,
+    pub succ_num: u32,
+    pub fail_num: u32,
+}
+
+impl ChangeLogData for LogAccountImport<'_> {
+    fn log_type() -> &'static str {
+        "account-import"
+    }
+    fn message(&self) -> String {
+        format!("{} account import {}", self.action, self.name)
+    }
+    fn encode(&self) -> String {
+        serde_json::to_string(&self).unwrap_or_default()
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct LogAccountBulkExport {
+    pub start_id: u64,
+    pub end_id: u64,
+    pub total_num: u32,
+    pub with_password: bool,
+}
+
+impl ChangeLogData for LogAccountBulkExport {
+    fn log_type() -> &'static str {
+        "account-bulk-export"
+    }
+    fn message(&self) -> String {
+        if self.with_password {
+            format!(
+                "export {} account with password [{}-{}]",
+                self.total_num, self.start_id, self.end_id
+            )
+        } else {
+            format!(
+                "export {} account [{}-{}]",
+                self.total_num, self.start_id, self.end_id
+            )
+        }
+    }
+    fn encode(&self) -> String {
+        serde_json::to_string(&self).unwrap_or_default()
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct LogAccountImpersonate<'t> {
+    pub action: &'t str, //login 登录 request 请求
+    pub account_id: u64,
+    pub user_id: u64,
+    pub impersonate_user_id: u64,
+    pub session_id: u64,
+    pub request_path: &'t str,
+}
+
+impl ChangeLogData for LogAccountImpersonate<'_> {
+    fn log_type() -> &'static str {
+        "account-impersonate"
+    }
+    fn message(&self) -> String {
+        format!(
+            "{} impersonate account {} by user {}",
+            self.action, self.account_id, self.impersonate_user_id
+        )
+    }
+    fn encode(&self) -> String {
+        serde_json::to_string(&self).unwrap_or_default()
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct LogAuthRefreshReuse {
+    pub app_id: u64,
+    pub session_id: u64,
+    pub user_id: u64,
+}
+
+impl ChangeLogData for LogAuthRefreshReuse {
+    fn log_type() -> &'static str {
+        "auth-refresh-reuse"
+    }
+    fn message(&self) -> String {
+        format!(
+            "refresh token reuse,revoke session {} of app {}",
+            self.session_id, self.app_id
+        )
+    }
+    fn encode(&self) -> String {
+        serde_json::to_string(&self).unwrap_or_default()
+    }
+}
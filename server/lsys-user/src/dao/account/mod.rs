@@ -11,14 +11,22 @@ use std::sync::Arc;
 #[allow(clippy::module_inception)]
 mod account;
 mod account_address;
+mod account_delete;
 mod account_email;
+mod account_export;
 mod account_external;
+mod account_import;
 mod account_index;
 mod account_info;
 mod account_login_history;
+mod account_merge;
 mod account_mobile;
 mod account_name;
 mod account_password;
+mod account_register;
+mod account_search;
+mod account_security;
+mod account_token;
 mod cache;
 pub(crate) mod logger;
 mod password;
@@ -26,14 +34,22 @@ mod result;
 
 use account::*;
 pub use account_address::*;
+pub use account_delete::*;
 use account_email::*;
+pub use account_export::*;
 use account_external::*;
+pub use account_import::*;
 use account_index::*;
 use account_info::*;
 pub use account_login_history::*;
+pub use account_merge::*;
 use account_mobile::*;
 use account_name::*;
 use account_password::*;
+pub use account_register::*;
+pub use account_search::*;
+pub use account_security::*;
+pub use account_token::*;
 pub use cache::*;
 pub use password::*;
 pub use result::*;
@@ -50,6 +66,11 @@ pub struct AccountConfig {
     pub info_cache: LocalCacheConfig,
     pub address_cache: LocalCacheConfig,
     pub external_cache: LocalCacheConfig,
+    pub token_cache: LocalCacheConfig,
+    pub export: AccountExportConfig, //个人数据导出
+    pub delete: AccountDeleteConfig, //账号注销
+    pub password_breached_dir: Option<std::path::PathBuf>, //已泄露密码库目录
+    pub search_index_dir: Option<std::path::PathBuf>, //账号搜索索引目录
 }
 
 impl AccountConfig {
@@ -90,11 +111,21 @@ impl AccountConfig {
                 if use_cache { None } else { Some(0) },
                 None,
             ),
+            token_cache: LocalCacheConfig::new(
+                "account-token",
+                if use_cache { None } else { Some(0) },
+                None,
+            ),
+            export: AccountExportConfig::default(),
+            delete: AccountDeleteConfig::default(),
+            password_breached_dir: None,
+            search_index_dir: None,
         }
     }
 }
 
 pub struct AccountDao {
+    pub(crate) db: Pool<MySql>,
     pub account: Arc<Account>,
     pub account_email: Arc<AccountEmail>,
     pub account_external: Arc<AccountExternal>,
@@ -105,6 +136,14 @@ pub struct AccountDao {
     pub account_password: Arc<AccountPassword>,
     pub account_login_hostory: Arc<AccountLoginHistory>,
     pub account_passwrod_hash: Arc<AccountPasswordHash>,
+    pub account_token: Arc<AccountToken>,
+    pub account_security: Arc<AccountSecurity>,
+    pub account_merge: Arc<AccountMerge>,
+    pub account_export: Arc<AccountExport>,
+    pub account_delete: Arc<AccountDelete>,
+    pub account_register: Arc<AccountRegister>,
+    pub account_search: Arc<AccountSearch>,
+    pub account_import: Arc<AccountImport>,
 }
 
 impl AccountDao {
@@ -117,7 +156,8 @@ impl AccountDao {
         remote_notify: Arc<RemoteNotify>,
         logger: Arc<ChangeLoggerDao>,
     ) -> Self {
-        let account_index = Arc::from(AccountIndex::new(db.clone()));
+        let (change_tx, change_rx) = tokio::sync::mpsc::unbounded_channel();
+        let account_index = Arc::from(AccountIndex::new(db.clone(), change_tx));
         let password_hash = Arc::from(AccountPasswordHash::default());
         let account = Arc::from(Account::new(
             db.clone(),
@@ -128,61 +168,140 @@ impl AccountDao {
             logger.clone(),
         ));
         let account_login_hostory = Arc::from(AccountLoginHistory::new(db.clone()));
+        let account_email = Arc::from(AccountEmail::new(
+            db.clone(),
+            redis.clone(),
+            account_index.clone(),
+            remote_notify.clone(),
+            config.email_cache,
+            logger.clone(),
+        ));
+        let account_external = Arc::from(AccountExternal::new(
+            db.clone(),
+            account_index.clone(),
+            remote_notify.clone(),
+            config.external_cache,
+            logger.clone(),
+        ));
+        let account_mobile = Arc::from(AccountMobile::new(
+            db.clone(),
+            redis.clone(),
+            account_index.clone(),
+            remote_notify.clone(),
+            config.mobile_cache,
+            logger.clone(),
+        ));
+        let account_name = Arc::from(AccountName::new(
+            db.clone(),
+            account_index.clone(),
+            access.clone(),
+            remote_notify.clone(),
+            config.name_cache,
+            logger.clone(),
+        ));
+        let account_address = Arc::from(AccountAddress::new(
+            db.clone(),
+            account_index.clone(),
+            remote_notify.clone(),
+            config.address_cache,
+            logger.clone(),
+        ));
+        let account_token = Arc::from(AccountToken::new(
+            db.clone(),
+            access.clone(),
+            remote_notify.clone(),
+            config.token_cache,
+            logger.clone(),
+        ));
+        let account_merge = Arc::from(AccountMerge::new(
+            db.clone(),
+            account.clone(),
+            account_index.clone(),
+            account_email.clone(),
+            account_mobile.clone(),
+            account_external.clone(),
+            account_address.clone(),
+            account_name.clone(),
+            account_token.clone(),
+            logger.clone(),
+        ));
+        let account_export = Arc::from(AccountExport::new(
+            db.clone(),
+            redis.clone(),
+            account.clone(),
+            config.export,
+            logger.clone(),
+        ));
+        let account_search = Arc::from(AccountSearch::new(
+            account.clone(),
+            account_name.clone(),
+            account_email.clone(),
+            account_mobile.clone(),
+            account_external.clone(),
+            remote_notify.clone(),
+            config
+                .search_index_dir
+                .unwrap_or_else(|| std::env::temp_dir().join("lsys_account_index")),
+            change_rx,
+        ));
+        let account_info = Arc::from(AccountInfo::new(
+            db.clone(),
+            account_index,
+            remote_notify,
+            config.info_cache,
+            logger.clone(),
+        ));
+        let account_delete = Arc::from(AccountDelete::new(
+            db.clone(),
+            redis.clone(),
+            account.clone(),
+            account_info.clone(),
+            account_address.clone(),
+            config.delete,
+            logger.clone(),
+        ));
+        let account_password = Arc::from(AccountPassword::new(
+            db.clone(),
+            setting.clone(),
+            // fluent,
+            redis,
+            logger.clone(),
+            password_hash.clone(),
+            config.password_breached_dir,
+        ));
+        let account_import = Arc::from(AccountImport::new(
+            db.clone(),
+            account.clone(),
+            account_name.clone(),
+            account_email.clone(),
+            account_mobile.clone(),
+            account_password.clone(),
+            password_hash.clone(),
+            logger.clone(),
+        ));
         AccountDao {
-            account,
-            account_email: Arc::from(AccountEmail::new(
+            db: db.clone(),
+            account: account.clone(),
+            account_email,
+            account_external,
+            account_mobile,
+            account_name,
+            account_info,
+            account_address,
+            account_security: Arc::from(AccountSecurity::new(db.clone(), access, logger.clone())),
+            account_token,
+            account_merge,
+            account_export,
+            account_delete,
+            account_register: Arc::from(AccountRegister::new(
                 db.clone(),
-                redis.clone(),
-                account_index.clone(),
-                remote_notify.clone(),
-                config.email_cache,
+                account,
+                setting.clone(),
                 logger.clone(),
             )),
-            account_external: Arc::from(AccountExternal::new(
-                db.clone(),
-                account_index.clone(),
-                remote_notify.clone(),
-                config.external_cache,
-                logger.clone(),
-            )),
-            account_mobile: Arc::from(AccountMobile::new(
-                db.clone(),
-                redis.clone(),
-                account_index.clone(),
-                remote_notify.clone(),
-                config.mobile_cache,
-                logger.clone(),
-            )),
-            account_name: Arc::from(AccountName::new(
-                db.clone(),
-                account_index.clone(),
-                access,
-                remote_notify.clone(),
-                config.name_cache,
-                logger.clone(),
-            )),
-            account_info: Arc::from(AccountInfo::new(
-                db.clone(),
-                account_index.clone(),
-                remote_notify.clone(),
-                config.info_cache,
-                logger.clone(),
-            )),
-            account_address: Arc::from(AccountAddress::new(
-                db.clone(),
-                account_index,
-                remote_notify.clone(),
-                config.address_cache,
-                logger.clone(),
-            )),
-            account_password: Arc::from(AccountPassword::new(
-                db,
-                setting,
-                // fluent,
-                redis,
-                logger,
-                password_hash.clone(),
-            )),
+            account_search,
+            account_import,
+            account_password,
             account_passwrod_hash: password_hash,
             account_login_hostory,
         }
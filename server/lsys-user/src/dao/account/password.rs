@@ -41,10 +41,105 @@ impl AccountPasswordHash {
     }
 }
 
+/// 从其他系统导入的密码HASH
+/// 存储时加格式前缀,登录校验成功后转为当前HASH方式
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccountPasswordLegacy {
+    Md5 { hash: String, salt: String }, //md5(密码+salt),salt可为空
+    Bcrypt(String),                     //$2a$ $2b$ $2y$
+}
+
+impl AccountPasswordLegacy {
+    const MD5_PREFIX: &'static str = "{md5}";
+    const BCRYPT_PREFIX: &'static str = "{bcrypt}";
+    /// 解析导入数据
+    /// md5 格式为 hash 或 hash:salt
+    pub fn parse(format: &str, hash: &str) -> Option<Self> {
+        match format {
+            "md5" => {
+                let (hash, salt) = hash.split_once(':').unwrap_or((hash, ""));
+                if hash.len() != 32 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return None;
+                }
+                Some(Self::Md5 {
+                    hash: hash.to_ascii_lowercase(),
+                    salt: salt.to_owned(),
+                })
+            }
+            "bcrypt" => {
+                //支持 $2a$ $2b$ $2x$ $2y$ 格式
+                if hash.parse::<bcrypt::HashParts>().is_err() {
+                    return None;
+                }
+                Some(Self::Bcrypt(hash.to_owned()))
+            }
+            _ => None,
+        }
+    }
+    /// 从存储的密码中解析,非导入密码返回None
+    pub fn decode(password: &str) -> Option<Self> {
+        if let Some(tmp) = password.strip_prefix(Self::MD5_PREFIX) {
+            return Self::parse("md5", tmp);
+        }
+        if let Some(tmp) = password.strip_prefix(Self::BCRYPT_PREFIX) {
+            return Self::parse("bcrypt", tmp);
+        }
+        None
+    }
+    pub fn encode(&self) -> String {
+        match self {
+            Self::Md5 { hash, salt } => {
+                if salt.is_empty() {
+                    format!("{}{}", Self::MD5_PREFIX, hash)
+                } else {
+                    format!("{}{}:{}", Self::MD5_PREFIX, hash, salt)
+                }
+            }
+            Self::Bcrypt(hash) => format!("{}{}", Self::BCRYPT_PREFIX, hash),
+        }
+    }
+    /// 导出时使用的格式名称及HASH,与 parse 参数对应
+    pub fn format(&self) -> &'static str {
+        match self {
+            Self::Md5 { .. } => "md5",
+            Self::Bcrypt(_) => "bcrypt",
+        }
+    }
+    pub fn hash(&self) -> String {
+        match self {
+            Self::Md5 { hash, salt } => {
+                if salt.is_empty() {
+                    hash.to_owned()
+                } else {
+                    format!("{}:{}", hash, salt)
+                }
+            }
+            Self::Bcrypt(hash) => hash.to_owned(),
+        }
+    }
+    pub fn verify(&self, password: &str) -> bool {
+        match self {
+            Self::Md5 { hash, salt } => {
+                format!("{:x}", md5::compute(format!("{}{}", password, salt))) == *hash
+            }
+            Self::Bcrypt(hash) => bcrypt::verify(password, hash).unwrap_or(false),
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Default)]
+#[serde(default)]
 pub struct AccountPasswordConfig {
     pub timeout: u64,
     pub disable_old_password: bool,
+    pub min_length: u64,            //最小长度,0为不限制
+    pub require_lower: bool,        //必须包含小写字母
+    pub require_upper: bool,        //必须包含大写字母
+    pub require_number: bool,       //必须包含数字
+    pub require_symbol: bool,       //必须包含符号
+    pub disable_account_name: bool, //不能包含登录名及邮箱
+    pub history_num: u64,           //不能与最近N次密码相同,0为不限制
+    pub breached_check: bool,       //检测是否为已泄露密码
 }
 
 impl SettingKey for AccountPasswordConfig {
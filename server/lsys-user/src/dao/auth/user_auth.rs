@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use base64::Engine;
 
-use lsys_access::dao::{AccessDao, AccessSessionData, AccessSessionToken, SessionBody};
+use lsys_access::dao::{AccessDao, AccessError, AccessSessionData, AccessSessionToken, SessionBody};
 use lsys_core::fluent_message;
 use lsys_core::now_time;
 
@@ -14,6 +14,9 @@ use base64::{
 };
 
 use super::{UserAuthError, UserAuthResult};
+use crate::dao::{
+    AccountToken, AuthAccount, ACCOUNT_IMPERSONATE_LOGIN_TYPE, ACCOUNT_TOKEN_LOGIN_TYPE,
+};
 
 const CUSTOM_ENGINE: engine::GeneralPurpose =
     engine::GeneralPurpose::new(&alphabet::URL_SAFE, general_purpose::NO_PAD);
@@ -86,7 +89,11 @@ impl FromStr for UserAuthToken {
         Ok(Self::new(app_id, &token, user_id, time_out))
     }
 }
-impl AccessSessionToken for UserAuthToken {}
+impl AccessSessionToken for UserAuthToken {
+    fn is_impersonate(&self) -> bool {
+        AuthAccount::is_impersonate_token(&self.token)
+    }
+}
 
 impl From<&UserAuthData> for UserAuthToken {
     fn from(value: &UserAuthData) -> Self {
@@ -159,18 +166,39 @@ impl UserAuthDao {
             reload_type,
         }
     }
+    //加载登录记录
+    //个人访问令牌登录只保存令牌HASH,必须使用令牌明文访问
+    async fn load_session(&self, user_token: &UserAuthToken) -> UserAuthResult<SessionBody> {
+        let is_token = AccountToken::is_token(&user_token.token);
+        let token_data = if is_token {
+            AccountToken::token_hash(&user_token.token)
+        } else {
+            user_token.token.to_owned()
+        };
+        let session = self
+            .access
+            .auth
+            .cache()
+            .login_data(user_token.app_id, 0, &token_data)
+            .await?;
+        if is_token != (session.session().login_type == ACCOUNT_TOKEN_LOGIN_TYPE) {
+            return Err(AccessError::NotLogin.into());
+        }
+        //模拟登录令牌需与会话类型一致,保证按令牌记录模拟登录请求不会遗漏
+        if user_token.is_impersonate()
+            != (session.session().login_type == ACCOUNT_IMPERSONATE_LOGIN_TYPE)
+        {
+            return Err(AccessError::NotLogin.into());
+        }
+        Ok(session)
+    }
     //得到当前登陆用户
     //@todo 多加个一个参数，用于是否决定是否实时从数据库拿记录,目标是实现jwt验证通过时，可以不加载数据库记录
     pub async fn get_session_data(
         &self,
         user_token: &UserAuthToken,
     ) -> UserAuthResult<UserAuthData> {
-        let session = self
-            .access
-            .auth
-            .cache()
-            .login_data(user_token.app_id, 0, &user_token.token)
-            .await?;
+        let session = self.load_session(user_token).await?;
         let login_data = self
             .access
             .auth
@@ -239,12 +267,7 @@ impl UserAuthDao {
     }
     //获取当前登录状态
     pub async fn is_login(&self, user_token: &UserAuthToken) -> UserAuthResult<bool> {
-        let session = self
-            .access
-            .auth
-            .cache()
-            .login_data(user_token.app_id, 0, &user_token.token)
-            .await?;
+        let session = self.load_session(user_token).await?;
         Ok(session.is_valid())
     }
 }
@@ -0,0 +1,487 @@
+use super::super::AccountLoginEnv;
+use super::{reload_match_wrap, AccountLoginMeta, AccountLoginParam};
+
+use crate::dao::account::AccountError;
+
+use crate::dao::{AccountDao, AccountResult, UserAuthData, UserAuthResult, UserLoginReload};
+use crate::model::{AccountEmailModel, AccountModel};
+use async_trait::async_trait;
+use lsys_access::dao::SessionBody;
+use lsys_core::{
+    fluent_message, rand_str, valid_key, AppCore, IntoFluentMessage, RandType, ValidEmail,
+    ValidParam, ValidParamCheck, ValidPattern, ValidRuleKey, ValidStrlen, WaitItem, WaitNotify,
+    WaitNotifyError,
+};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+use tracing::warn;
+
+//邮件链接登录,链接中的令牌一次有效
+//可选在原设备确认:在任意设备打开链接后,由发起登录的设备完成登录
+
+pub struct EmailLinkLoginData {
+    data: AccountEmailModel,
+}
+
+impl EmailLinkLoginData {
+    pub fn new(data: AccountEmailModel) -> Self {
+        EmailLinkLoginData { data }
+    }
+    pub fn to_json(&self) -> Value {
+        json!(self.data)
+    }
+    pub async fn from(account_dao: &AccountDao, auth_data: &UserAuthData) -> AccountResult<Self> {
+        match auth_data.login_data().parse::<u64>() {
+            Err(err) => Err(AccountError::System(fluent_message!(
+               "account-bad-session",{
+                    "msg":err,
+                    "data":auth_data.login_data()
+                }
+            ))),
+            Ok(id) => {
+                let data = account_dao.account_email.cache().find_by_id(&id).await?;
+                data.is_enable()?;
+                Ok(EmailLinkLoginData::new(data))
+            }
+        }
+    }
+}
+
+impl std::ops::Deref for EmailLinkLoginData {
+    type Target = AccountEmailModel;
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+pub struct EmailLinkLoginReload {
+    account_dao: Arc<AccountDao>,
+}
+
+impl EmailLinkLoginReload {
+    pub fn new(account_dao: Arc<AccountDao>) -> Self {
+        Self { account_dao }
+    }
+}
+
+#[async_trait]
+impl UserLoginReload for EmailLinkLoginReload {
+    async fn reload(
+        &self,
+        session: &SessionBody,
+        data: &str,
+    ) -> Option<UserAuthResult<(String, u64)>> {
+        reload_match_wrap::<EmailLinkLoginMeta>(session, async {
+            let id = data
+                .parse::<u64>()
+                .map_err(|err| AccountError::System(fluent_message!("auth-bad-session", err)))?;
+            let dat = self.account_dao.account_email.find_by_id(&id).await?;
+            dat.is_enable()?;
+            Ok(dat.id.to_string())
+        })
+        .await
+    }
+}
+
+pub struct EmailLinkLoginMeta {}
+impl AccountLoginMeta for EmailLinkLoginMeta {
+    fn login_type() -> String {
+        "email-link".to_string()
+    }
+}
+
+pub struct EmailLinkConfig {
+    pub link_time: u64,     //链接有效时间,同验证码登录
+    pub send_interval: u64, //同一邮箱发送间隔
+    pub wait_timeout: u8,   //原设备单次等待确认时间
+}
+
+impl Default for EmailLinkConfig {
+    fn default() -> Self {
+        Self {
+            link_time: 300,
+            send_interval: 30,
+            wait_timeout: 60,
+        }
+    }
+}
+
+//链接令牌对应数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailLinkData {
+    pub email: String,
+    pub wait_id: String, //不为空时需在原设备完成登录
+    pub confirm: bool,   //链接是否已被打开确认
+}
+
+//原设备等待数据
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct EmailLinkWaitData {
+    host: String,  //等待中的主机
+    token: String, //确认后的链接令牌
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmailLinkWaitItem {
+    wait_id: String,
+}
+
+impl WaitItem for EmailLinkWaitItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.wait_id == other.wait_id
+    }
+}
+
+pub struct EmailLinkDao {
+    redis: deadpool_redis::Pool,
+    wait_notify: WaitNotify<EmailLinkWaitItem>,
+    pub config: EmailLinkConfig,
+}
+
+impl EmailLinkDao {
+    pub fn new(
+        redis: deadpool_redis::Pool,
+        app_core: Arc<AppCore>,
+        config: EmailLinkConfig,
+    ) -> Self {
+        let wait_notify = WaitNotify::new(
+            "email-link-wait",
+            redis.clone(),
+            app_core,
+            config.wait_timeout,
+        );
+        Self {
+            redis,
+            wait_notify,
+            config,
+        }
+    }
+    //只保存令牌HASH
+    fn link_key(token: &str) -> String {
+        let hash: String = Sha256::digest(token.as_bytes())
+            .iter()
+            .map(|e| format!("{:02x}", e))
+            .collect();
+        format!("email-link-login-{}", hash)
+    }
+    fn wait_key(wait_id: &str) -> String {
+        format!("email-link-wait-{}", wait_id)
+    }
+    fn send_key(email: &str) -> String {
+        format!("email-link-send-{}", email)
+    }
+    fn host_name() -> String {
+        hostname::get()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    }
+    async fn email_param_valid(email: &str) -> AccountResult<()> {
+        ValidParam::default()
+            .add(
+                valid_key!("login_email"),
+                &email,
+                &ValidParamCheck::default()
+                    .add_rule(ValidEmail::default())
+                    .add_rule(ValidStrlen::range(3, 150)),
+            )
+            .check()?;
+        Ok(())
+    }
+    async fn token_param_valid(key: ValidRuleKey, token: &str) -> AccountResult<()> {
+        ValidParam::default()
+            .add(
+                key,
+                &token,
+                &ValidParamCheck::default()
+                    .add_rule(ValidPattern::Hex)
+                    .add_rule(ValidStrlen::eq(32)),
+            )
+            .check()?;
+        Ok(())
+    }
+    /// 创建登录链接令牌,返回 (令牌,原设备等待ID,有效时间)
+    pub async fn link_create(
+        &self,
+        email: &str,
+        wait_confirm: bool,
+    ) -> AccountResult<(String, Option<String>, u64)> {
+        Self::email_param_valid(email).await?;
+        let mut redis = self.redis.get().await?;
+        let send_key = Self::send_key(email);
+        let is_set: bool = redis.set_nx(&send_key, 1).await?;
+        if !is_set {
+            let ttl: i64 = redis.ttl(&send_key).await?;
+            return Err(AccountError::System(fluent_message!(
+                "login-email-link-interval",
+                {"time":ttl.max(1)}
+            )));
+        }
+        let _: () = redis
+            .expire(&send_key, self.config.send_interval as i64)
+            .await?;
+
+        let token = rand_str(RandType::LowerHex, 32);
+        let wait_id = if wait_confirm {
+            let wait_id = rand_str(RandType::LowerHex, 32);
+            let _: () = redis
+                .set_ex(
+                    Self::wait_key(&wait_id),
+                    json!(EmailLinkWaitData::default()).to_string(),
+                    self.config.link_time,
+                )
+                .await?;
+            Some(wait_id)
+        } else {
+            None
+        };
+        let data = EmailLinkData {
+            email: email.to_owned(),
+            wait_id: wait_id.to_owned().unwrap_or_default(),
+            confirm: false,
+        };
+        let _: () = redis
+            .set_ex(
+                Self::link_key(&token),
+                json!(data).to_string(),
+                self.config.link_time,
+            )
+            .await?;
+        Ok((token, wait_id, self.config.link_time))
+    }
+    /// 获取链接令牌数据
+    pub async fn link_data(&self, token: &str) -> AccountResult<EmailLinkData> {
+        Self::token_param_valid(valid_key!("login_token"), token).await?;
+        let mut redis = self.redis.get().await?;
+        let data: Option<String> = redis.get(Self::link_key(token)).await?;
+        match data {
+            Some(data) => Ok(serde_json::from_str::<EmailLinkData>(&data)?),
+            None => Err(AccountError::System(fluent_message!(
+                "login-email-link-expired"
+            ))),
+        }
+    }
+    /// 使用链接令牌,仅第一次使用成功
+    /// 需原设备完成的链接只能在确认后通过对应的 wait_id 使用
+    pub async fn link_take(
+        &self,
+        token: &str,
+        wait_id: Option<&str>,
+    ) -> AccountResult<EmailLinkData> {
+        let data = self.link_data(token).await?;
+        let wait_match = match wait_id {
+            Some(wait_id) => data.confirm && data.wait_id == wait_id,
+            None => data.wait_id.is_empty(),
+        };
+        if !wait_match {
+            return Err(AccountError::System(fluent_message!(
+                "login-email-link-need-confirm"
+            )));
+        }
+        let mut redis = self.redis.get().await?;
+        let del_num: u64 = redis.del(Self::link_key(token)).await?;
+        if del_num == 0 {
+            return Err(AccountError::System(fluent_message!(
+                "login-email-link-expired"
+            )));
+        }
+        Ok(data)
+    }
+    /// 在任意设备打开链接,确认由原设备完成登录
+    pub async fn link_confirm(&self, token: &str) -> AccountResult<EmailLinkData> {
+        let mut data = self.link_data(token).await?;
+        if data.wait_id.is_empty() {
+            return Err(AccountError::System(fluent_message!(
+                "login-email-link-not-confirm"
+            )));
+        }
+        let mut redis = self.redis.get().await?;
+        let wait_key = Self::wait_key(&data.wait_id);
+        let wait_str: Option<String> = redis.get(&wait_key).await?;
+        let mut wait_data = match wait_str {
+            Some(tmp) => serde_json::from_str::<EmailLinkWaitData>(&tmp)?,
+            None => {
+                return Err(AccountError::System(fluent_message!(
+                    "login-email-link-expired"
+                )))
+            }
+        };
+        if !data.confirm {
+            data.confirm = true;
+            let ttl: i64 = redis.ttl(Self::link_key(token)).await?;
+            let _: () = redis
+                .set_ex(
+                    Self::link_key(token),
+                    json!(data).to_string(),
+                    ttl.max(1) as u64,
+                )
+                .await?;
+            wait_data.token = token.to_owned();
+            let ttl: i64 = redis.ttl(&wait_key).await?;
+            let _: () = redis
+                .set_ex(&wait_key, json!(wait_data).to_string(), ttl.max(1) as u64)
+                .await?;
+        }
+        //先写确认数据再读等待主机,原设备先写主机再读确认数据,保证不会漏掉通知
+        let wait_str: Option<String> = redis.get(&wait_key).await?;
+        let host = wait_str
+            .and_then(|e| serde_json::from_str::<EmailLinkWaitData>(&e).ok())
+            .map(|e| e.host)
+            .unwrap_or_default();
+        if !host.is_empty() {
+            if let Err(err) = self
+                .wait_notify
+                .notify(
+                    &host,
+                    EmailLinkWaitItem {
+                        wait_id: data.wait_id.to_owned(),
+                    },
+                    Ok(true),
+                )
+                .await
+            {
+                warn!(
+                    "email link notify {} fail:{}",
+                    data.wait_id,
+                    err.to_fluent_message().default_format()
+                );
+            }
+        }
+        Ok(data)
+    }
+    async fn wait_token(&self, wait_id: &str) -> AccountResult<Option<String>> {
+        let mut redis = self.redis.get().await?;
+        let wait_str: Option<String> = redis.get(Self::wait_key(wait_id)).await?;
+        match wait_str {
+            Some(tmp) => {
+                let wait_data = serde_json::from_str::<EmailLinkWaitData>(&tmp)?;
+                if wait_data.token.is_empty() {
+                    Ok(None)
+                } else {
+                    Ok(Some(wait_data.token))
+                }
+            }
+            None => Err(AccountError::System(fluent_message!(
+                "login-email-link-expired"
+            ))),
+        }
+    }
+    /// 原设备等待链接被确认,确认后返回链接令牌,等待超时返回None
+    pub async fn wait_confirm(&self, wait_id: &str) -> AccountResult<Option<String>> {
+        Self::token_param_valid(valid_key!("wait_id"), wait_id).await?;
+        if let Some(token) = self.wait_token(wait_id).await? {
+            return Ok(Some(token));
+        }
+        let receiver = self
+            .wait_notify
+            .wait(EmailLinkWaitItem {
+                wait_id: wait_id.to_owned(),
+            })
+            .await;
+        {
+            let wait_key = Self::wait_key(wait_id);
+            let mut redis = self.redis.get().await?;
+            let ttl: i64 = redis.ttl(&wait_key).await?;
+            if ttl > 0 {
+                let _: () = redis
+                    .set_ex(
+                        &wait_key,
+                        json!(EmailLinkWaitData {
+                            host: Self::host_name(),
+                            token: "".to_string(),
+                        })
+                        .to_string(),
+                        ttl as u64,
+                    )
+                    .await?;
+            }
+        }
+        if let Some(token) = self.wait_token(wait_id).await? {
+            return Ok(Some(token));
+        }
+        match self.wait_notify.wait_timeout(receiver).await {
+            Ok(_) | Err(WaitNotifyError::TimeOut) => {}
+            Err(err) => {
+                warn!(
+                    "email link wait {} fail:{}",
+                    wait_id,
+                    err.to_fluent_message().default_format()
+                );
+            }
+        }
+        self.wait_token(wait_id).await
+    }
+    /// 原设备完成登录后清理等待数据
+    pub async fn wait_clear(&self, wait_id: &str) -> AccountResult<()> {
+        let mut redis = self.redis.get().await?;
+        let _: () = redis.del(Self::wait_key(wait_id)).await?;
+        Ok(())
+    }
+    pub async fn listen(&self) {
+        self.wait_notify.listen().await
+    }
+}
+
+pub struct EmailLinkLogin {
+    account: Arc<AccountDao>,
+    link: Arc<EmailLinkDao>,
+    pub token: String,
+    pub wait_id: Option<String>, //在原设备完成登录时的等待ID
+    pub email: String,
+}
+
+impl EmailLinkLogin {
+    pub async fn new(
+        account: Arc<AccountDao>,
+        link: Arc<EmailLinkDao>,
+        token: &str,
+        wait_id: Option<&str>,
+    ) -> AccountResult<Self> {
+        let data = link.link_data(token).await?;
+        Ok(Self {
+            account,
+            link,
+            token: token.to_string(),
+            wait_id: wait_id.map(|e| e.to_string()),
+            email: data.email,
+        })
+    }
+}
+
+#[async_trait]
+impl AccountLoginParam for EmailLinkLogin {
+    type Meta = EmailLinkLoginMeta;
+    fn account_name(&self) -> String {
+        self.email.clone()
+    }
+    async fn get_account(&self, _: &AccountLoginEnv) -> AccountResult<(String, AccountModel)> {
+        let email = self
+            .account
+            .account_email
+            .find_by_last_email(&self.email)
+            .await
+            .map_err(auth_user_not_found_map!(self.account_name(), "email link"))?;
+        email.is_enable()?;
+
+        let user = self
+            .account
+            .account
+            .find_by_id(&email.account_id)
+            .await
+            .map_err(auth_user_not_found_map!(
+                self.account_name(),
+                "email link [user id]"
+            ))?;
+        user.is_enable()?;
+
+        self.link
+            .link_take(&self.token, self.wait_id.as_deref())
+            .await?;
+
+        Ok((email.id.to_string(), user))
+    }
+}
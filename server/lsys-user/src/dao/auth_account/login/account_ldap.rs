@@ -0,0 +1,502 @@
+use crate::dao::{
+    AccountDao, AccountError, AccountResult, UserAuthData, UserAuthResult, UserLoginReload,
+};
+use crate::model::{AccountEmailStatus, AccountExternalModel, AccountInfoModelRef, AccountModel};
+use async_trait::async_trait;
+use lsys_access::dao::SessionBody;
+use lsys_core::{
+    fluent_message, model_option_set, valid_key, ValidParam, ValidParamCheck, ValidStrlen,
+};
+use lsys_lib_ldap::{ldap_escape, LdapClient, LdapConnectOption, LdapEntry, LdapError, LdapScope};
+use lsys_setting::dao::{SettingDecode, SettingEncode, SettingJson, SettingKey, SettingResult};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+use super::super::AccountLoginEnv;
+use super::{reload_match_wrap, AccountLoginMeta, AccountLoginParam};
+
+//外部账号记录中使用的配置名及类型
+pub const LDAP_EXTERNAL_CONFIG: &str = "ldap";
+pub const LDAP_EXTERNAL_TYPE: &str = "ldap";
+
+//LDAP 组到 RBAC 角色的映射
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct LdapGroupRole {
+    pub group: String, //组DN或组名,不区分大小写
+    pub role_id: u64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct LdapConfig {
+    pub enable: bool,
+    pub url: String, //ldap://host:389 或 ldaps://host:636
+    #[serde(default)]
+    pub tls_skip_verify: bool,
+    #[serde(default)]
+    pub timeout: u64,
+    pub bind_dn: String, //查询用账号,为空时匿名查询
+    #[serde(default)]
+    pub bind_password: String,
+    pub base_dn: String,
+    pub user_filter: String, //{name} 替换为转义后的登录名
+    pub attr_id: String,     //唯一标识属性,AD 可用 objectGUID
+    pub attr_nickname: String,
+    pub attr_email: String,
+    pub attr_group: String,
+    #[serde(default)]
+    pub group_role: Vec<LdapGroupRole>,
+}
+
+impl Default for LdapConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            url: "ldap://127.0.0.1:389".to_string(),
+            tls_skip_verify: false,
+            timeout: 10,
+            bind_dn: "".to_string(),
+            bind_password: "".to_string(),
+            base_dn: "".to_string(),
+            user_filter: "(&(objectClass=person)(uid={name}))".to_string(),
+            attr_id: "uid".to_string(),
+            attr_nickname: "cn".to_string(),
+            attr_email: "mail".to_string(),
+            attr_group: "memberOf".to_string(),
+            group_role: vec![],
+        }
+    }
+}
+
+impl LdapConfig {
+    pub fn hide_bind_password(&mut self) {
+        if !self.bind_password.is_empty() {
+            self.bind_password = "******".to_string();
+        }
+    }
+}
+
+impl SettingKey for LdapConfig {
+    fn key<'t>() -> &'t str {
+        "user-ldap"
+    }
+}
+impl SettingDecode for LdapConfig {
+    fn decode(data: &str) -> SettingResult<Self> {
+        SettingJson::decode(data)
+    }
+}
+impl SettingEncode for LdapConfig {
+    fn encode(&self) -> String {
+        SettingJson::encode(self)
+    }
+}
+impl SettingJson<'_> for LdapConfig {}
+
+pub struct LdapLoginData {
+    data: AccountExternalModel,
+}
+
+impl LdapLoginData {
+    pub fn new(data: AccountExternalModel) -> Self {
+        LdapLoginData { data }
+    }
+    pub fn to_json(&self) -> Value {
+        json!(self.data)
+    }
+    pub async fn from(account_dao: &AccountDao, auth_data: &UserAuthData) -> AccountResult<Self> {
+        match auth_data.login_data().parse::<u64>() {
+            Err(err) => Err(AccountError::System(fluent_message!(
+                "account-bad-session",{
+                    "msg":err,
+                    "data":auth_data.login_data()
+                }
+            ))),
+            Ok(id) => {
+                let data = account_dao.account_external.cache().find_by_id(&id).await?;
+                Ok(LdapLoginData::new(data))
+            }
+        }
+    }
+}
+
+impl std::ops::Deref for LdapLoginData {
+    type Target = AccountExternalModel;
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+pub struct LdapLoginReload {
+    account_dao: Arc<AccountDao>,
+}
+
+impl LdapLoginReload {
+    pub fn new(account_dao: Arc<AccountDao>) -> Self {
+        Self { account_dao }
+    }
+}
+
+#[async_trait]
+impl UserLoginReload for LdapLoginReload {
+    async fn reload(
+        &self,
+        session: &SessionBody,
+        data: &str,
+    ) -> Option<UserAuthResult<(String, u64)>> {
+        reload_match_wrap::<LdapLoginMeta>(session, async {
+            let id = data
+                .parse::<u64>()
+                .map_err(|err| AccountError::System(fluent_message!("auth-bad-session", err)))?;
+            let dat = self.account_dao.account_external.find_by_id(&id).await?;
+            dat.is_enable()?;
+            Ok(dat.id.to_string())
+        })
+        .await
+    }
+}
+
+pub struct LdapLoginMeta {}
+impl AccountLoginMeta for LdapLoginMeta {
+    fn login_type() -> String {
+        "ldap".to_string()
+    }
+}
+
+//目录中找到的用户信息
+struct LdapUser {
+    dn: String,
+    id: String,
+    nickname: String,
+    email: Option<String>,
+}
+
+enum LdapAuthResult {
+    NotFound,
+    BadPassword(String), //目录中的用户标识
+    Ok(LdapUser, Vec<String>),
+}
+
+pub struct LdapLogin {
+    account_dao: Arc<AccountDao>,
+    config: LdapConfig,
+    pub name: String,
+    pub password: String,
+    groups: Mutex<Vec<String>>,
+}
+
+impl LdapLogin {
+    async fn new_param_valid(name: &str, password: &str) -> AccountResult<()> {
+        ValidParam::default()
+            .add(
+                valid_key!("login_name"),
+                &name,
+                &ValidParamCheck::default().add_rule(ValidStrlen::range(1, 64)),
+            )
+            .add(
+                valid_key!("login_password"),
+                &password,
+                &ValidParamCheck::default().add_rule(ValidStrlen::range(1, 128)),
+            )
+            .check()?;
+        Ok(())
+    }
+    pub async fn new(
+        account_dao: Arc<AccountDao>,
+        config: LdapConfig,
+        name: &str,
+        password: &str,
+    ) -> AccountResult<Self> {
+        Self::new_param_valid(name, password).await?;
+        Ok(Self {
+            account_dao,
+            config,
+            name: name.trim().to_string(),
+            password: password.to_string(),
+            groups: Mutex::new(vec![]),
+        })
+    }
+    //登录成功后用户所属的组,用于同步角色
+    pub fn groups(&self) -> Vec<String> {
+        self.groups.lock().map(|e| e.clone()).unwrap_or_default()
+    }
+    pub fn config(&self) -> &LdapConfig {
+        &self.config
+    }
+    fn ldap_err(&self, err: LdapError) -> AccountError {
+        warn!("ldap login {} fail:{}", self.name, err);
+        AccountError::System(fluent_message!("auth-ldap-error", {
+            "name": self.name.as_str(),
+            "msg": err.to_string()
+        }))
+    }
+    //查找并验证目录用户
+    async fn ldap_auth(&self) -> Result<LdapAuthResult, LdapError> {
+        let option = LdapConnectOption {
+            timeout: if self.config.timeout == 0 {
+                LdapConnectOption::default().timeout
+            } else {
+                self.config.timeout
+            },
+            tls_skip_verify: self.config.tls_skip_verify,
+        };
+        let mut client = LdapClient::connect(&self.config.url, &option).await?;
+        if !self.config.bind_dn.is_empty() {
+            client
+                .simple_bind(&self.config.bind_dn, &self.config.bind_password)
+                .await?;
+        }
+        let filter = self
+            .config
+            .user_filter
+            .replace("{name}", &ldap_escape(&self.name));
+        let attrs = [
+            self.config.attr_id.as_str(),
+            self.config.attr_nickname.as_str(),
+            self.config.attr_email.as_str(),
+            self.config.attr_group.as_str(),
+        ];
+        //多取一条用于判断过滤条件是否唯一
+        let mut entries = client
+            .search(&self.config.base_dn, LdapScope::Subtree, &filter, &attrs, 2)
+            .await?;
+        if entries.len() != 1 {
+            let _ = client.unbind().await;
+            if entries.len() > 1 {
+                return Err(LdapError::Filter(format!(
+                    "filter {} match multiple entries",
+                    filter
+                )));
+            }
+            return Ok(LdapAuthResult::NotFound);
+        }
+        let entry: LdapEntry = entries.remove(0);
+        let id = match entry.attr_string(&self.config.attr_id) {
+            Some(id) => id,
+            None => {
+                let _ = client.unbind().await;
+                return Err(LdapError::Ber(format!(
+                    "miss attribute {}",
+                    self.config.attr_id
+                )));
+            }
+        };
+        //以用户身份绑定验证密码
+        let bind_res = client.simple_bind(&entry.dn, &self.password).await;
+        let _ = client.unbind().await;
+        match bind_res {
+            Ok(()) => {}
+            Err(err) if err.is_invalid_credentials() => return Ok(LdapAuthResult::BadPassword(id)),
+            Err(err) => return Err(err),
+        }
+        let nickname = entry
+            .attr_string(&self.config.attr_nickname)
+            .unwrap_or_else(|| self.name.clone())
+            .chars()
+            .take(32)
+            .collect::<String>();
+        let user = LdapUser {
+            dn: entry.dn.clone(),
+            id,
+            nickname,
+            email: entry.attr_string(&self.config.attr_email),
+        };
+        Ok(LdapAuthResult::Ok(
+            user,
+            entry.attr_strings(&self.config.attr_group),
+        ))
+    }
+    //首次登录时创建账号,同样受仅邀请、邮箱域名及审核限制
+    async fn provision(
+        &self,
+        ldap_user: &LdapUser,
+        login_env: &AccountLoginEnv,
+    ) -> AccountResult<AccountModel> {
+        let permit = self
+            .account_dao
+            .account_register
+            .register_check(None, ldap_user.email.as_deref())
+            .await?;
+        let email = match ldap_user.email {
+            Some(ref email) => match self
+                .account_dao
+                .account_email
+                .find_by_last_email(email)
+                .await
+            {
+                Ok(_) => None,
+                Err(AccountError::Sqlx(sqlx::Error::RowNotFound)) => Some(email.as_str()),
+                Err(err) => return Err(err),
+            },
+            None => None,
+        };
+        let reg_ip = login_env
+            .login_ip
+            .map(|e| e.to_string())
+            .unwrap_or_default();
+        let reg_from = LdapLoginMeta::login_type();
+        let info = model_option_set!(AccountInfoModelRef,{
+            reg_ip:reg_ip,
+            reg_from:reg_from,
+        });
+        let mut tran = self.account_dao.db.begin().await?;
+        let user = match self
+            .account_dao
+            .account
+            .add(&ldap_user.nickname, 0, Some(&mut tran), None)
+            .await
+        {
+            Ok(u) => u,
+            Err(err) => {
+                tran.rollback().await?;
+                return Err(err);
+            }
+        };
+        if !permit.need_approve {
+            if let Err(err) = self
+                .account_dao
+                .account
+                .enable(&user, 0, Some(&mut tran), None)
+                .await
+            {
+                tran.rollback().await?;
+                return Err(err);
+            }
+        }
+        if let Err(err) = self
+            .account_dao
+            .account_register
+            .register_add(&user, &permit, &reg_from, &mut tran)
+            .await
+        {
+            tran.rollback().await?;
+            return Err(err);
+        }
+        if let Err(err) = self
+            .account_dao
+            .account_external
+            .add_external(
+                &user,
+                LDAP_EXTERNAL_CONFIG,
+                LDAP_EXTERNAL_TYPE,
+                &ldap_user.id,
+                &self.name,
+                0,
+                Some(&mut tran),
+                None,
+            )
+            .await
+        {
+            tran.rollback().await?;
+            return Err(err);
+        }
+        if let Some(email) = email {
+            if let Err(err) = self
+                .account_dao
+                .account_email
+                .add_email(
+                    &user,
+                    email,
+                    AccountEmailStatus::Valid,
+                    0,
+                    Some(&mut tran),
+                    None,
+                )
+                .await
+            {
+                tran.rollback().await?;
+                return Err(err);
+            }
+        }
+        if let Err(err) = self
+            .account_dao
+            .account_info
+            .set_info(&user, &info, 0, Some(&mut tran), None)
+            .await
+        {
+            tran.rollback().await?;
+            return Err(err);
+        }
+        tran.commit().await?;
+        self.account_dao.account.search_notify(user.id);
+        //enable 后需重新读取状态
+        self.account_dao.account.find_by_id(&user.id).await
+    }
+}
+
+#[async_trait]
+impl AccountLoginParam for LdapLogin {
+    type Meta = LdapLoginMeta;
+    fn account_name(&self) -> String {
+        self.name.clone()
+    }
+    async fn get_account(
+        &self,
+        login_env: &AccountLoginEnv,
+    ) -> AccountResult<(String, AccountModel)> {
+        if !self.config.enable {
+            return Err(AccountError::System(fluent_message!("auth-ldap-disable")));
+        }
+        let (ldap_user, groups) = match self.ldap_auth().await {
+            Ok(LdapAuthResult::Ok(user, groups)) => (user, groups),
+            Ok(LdapAuthResult::NotFound) => {
+                return Err(AccountError::UserNotFind(fluent_message!(
+                    "auth-not-user",
+                    {"name":self.account_name()}
+                )))
+            }
+            Ok(LdapAuthResult::BadPassword(id)) => {
+                //账号已存在时记录到对应用户下
+                let account_id = self
+                    .account_dao
+                    .account_external
+                    .find_by_external(LDAP_EXTERNAL_CONFIG, LDAP_EXTERNAL_TYPE, &id)
+                    .await
+                    .map(|e| e.account_id)
+                    .unwrap_or(0);
+                return Err(AccountError::PasswordNotMatch((
+                    account_id,
+                    fluent_message!("auth-bad-password"),
+                )));
+            }
+            Err(err) => return Err(self.ldap_err(err)),
+        };
+        let external = match self
+            .account_dao
+            .account_external
+            .find_by_external(LDAP_EXTERNAL_CONFIG, LDAP_EXTERNAL_TYPE, &ldap_user.id)
+            .await
+        {
+            Ok(e) => e,
+            Err(AccountError::Sqlx(sqlx::Error::RowNotFound)) => {
+                let user = self.provision(&ldap_user, login_env).await?;
+                self.account_dao
+                    .account_external
+                    .find_by_account_external(
+                        &user,
+                        LDAP_EXTERNAL_CONFIG,
+                        LDAP_EXTERNAL_TYPE,
+                        &ldap_user.id,
+                    )
+                    .await?
+            }
+            Err(err) => return Err(err),
+        };
+        let user = self
+            .account_dao
+            .account
+            .find_by_id(&external.account_id)
+            .await
+            .map_err(auth_user_not_found_map!(
+                self.account_name(),
+                "ldap [user id]"
+            ))?;
+        user.is_enable()?;
+        tracing::debug!("ldap login {} as {}", ldap_user.dn, user.id);
+        if let Ok(mut tmp) = self.groups.lock() {
+            *tmp = groups;
+        }
+        Ok((external.id.to_string(), user))
+    }
+}
@@ -0,0 +1,59 @@
+use crate::dao::{AccountDao, AccountResult};
+
+use crate::model::AccountModel;
+use async_trait::async_trait;
+
+use lsys_core::{valid_key, ValidParam, ValidParamCheck, ValidStrlen};
+
+use std::sync::Arc;
+
+use super::super::AccountLoginEnv;
+use super::{auth_check_account_password, AccountLoginMeta, AccountLoginParam};
+
+//已登录账号通过密码重新验证身份,仅用于 AuthAccount::reauth
+pub struct PasswordReauthMeta {}
+impl AccountLoginMeta for PasswordReauthMeta {
+    fn login_type() -> String {
+        "reauth-password".to_string()
+    }
+}
+pub struct PasswordReauth {
+    account_dao: Arc<AccountDao>,
+    account: AccountModel,
+    password: String,
+}
+impl PasswordReauth {
+    pub async fn new(
+        account_dao: Arc<AccountDao>,
+        account: AccountModel,
+        password: &str,
+    ) -> AccountResult<Self> {
+        ValidParam::default()
+            .add(
+                valid_key!("login_password"),
+                &password,
+                &ValidParamCheck::default().add_rule(ValidStrlen::range(1, 128)),
+            )
+            .check()?;
+        Ok(Self {
+            account_dao,
+            account,
+            password: password.to_string(),
+        })
+    }
+}
+#[async_trait]
+impl AccountLoginParam for PasswordReauth {
+    type Meta = PasswordReauthMeta;
+    //登录历史按账号ID记录,用于失败次数限制
+    fn account_name(&self) -> String {
+        format!("account-{}", self.account.id)
+    }
+    async fn get_account(&self, _: &AccountLoginEnv) -> AccountResult<(String, AccountModel)> {
+        self.account.is_enable()?;
+        let user =
+            auth_check_account_password(&self.account_dao, self.account.clone(), &self.password)
+                .await?;
+        Ok(("".to_string(), user))
+    }
+}
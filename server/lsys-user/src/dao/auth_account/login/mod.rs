@@ -14,6 +14,8 @@ use std::sync::Arc;
 
 pub struct AccountLoginEnv {
     pub login_ip: Option<IpAddr>,
+    pub device_id: Option<String>,
+    pub user_agent: Option<String>,
 }
 
 pub trait AccountLoginMeta {
@@ -104,13 +106,19 @@ async fn auth_check_account_password(
 mod macros;
 mod account_email;
 mod account_email_code;
+mod account_email_link;
 mod account_external;
+mod account_ldap;
 mod account_mobile;
 mod account_mobile_code;
 mod account_name;
+mod account_reauth;
 pub use self::account_email::*;
 pub use self::account_email_code::*;
+pub use self::account_email_link::*;
 pub use self::account_external::*;
+pub use self::account_ldap::*;
 pub use self::account_mobile::*;
 pub use self::account_mobile_code::*;
 pub use self::account_name::*;
+pub use self::account_reauth::*;
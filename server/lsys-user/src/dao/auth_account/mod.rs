@@ -1,11 +1,16 @@
 //内部账号关联登陆验证实现
-use crate::dao::{AccountResult, UserAuthError, UserAuthResult};
-use crate::model::{AccountModel, AccountStatus};
+use crate::dao::{
+    AccountDelete, AccountLoginGeo, AccountResult, AccountSecurity, AccountSecurityCheckParam,
+    UserAuthError, UserAuthResult,
+};
+use crate::model::{AccountModel, AccountSecurityEventModel, AccountStatus};
 use ip2location::Record;
 use login::{AccountLoginEnv, AccountLoginParam};
 use lsys_access::dao::{AccessAuthLoginData, AccessDao, AccessLoginData, SessionBody};
-use lsys_core::now_time;
-use lsys_core::{fluent_message, IntoFluentMessage, LimitParam};
+use lsys_core::{
+    fluent_message, now_time, rand_str, IntoFluentMessage, LimitParam, RandType, RequestEnv,
+};
+use lsys_logger::dao::ChangeLoggerDao;
 use tokio::sync::Mutex;
 
 use std::net::{IpAddr, Ipv4Addr};
@@ -14,16 +19,24 @@ use std::sync::Arc;
 
 use tracing::{debug, warn};
 
+use super::account::logger::LogAccountImpersonate;
 use super::{AccountError, AccountLoginHistory};
 use login::AccountLoginMeta;
 pub mod login;
 
 const ACCESS_LOGIN_DATA: &str = "login-data";
 
+//模拟登录的登录类型
+pub const ACCOUNT_IMPERSONATE_LOGIN_TYPE: &str = "impersonate";
+//模拟登录的令牌前缀,用于在解析令牌时识别模拟登录会话
+const ACCOUNT_IMPERSONATE_TOKEN_PREFIX: &str = "imp_";
+
 pub struct AuthAccountConfig {
     pub login_limit_captcha: u32,
     pub login_limit_lock: u32,
     pub login_limit_time: u64,
+    pub login_travel_speed: u64, //异地登录检测时正常出行最大速度,公里每小时,0 不检测
+    pub impersonate_time: u64,   //模拟登录有效时间,秒
     pub ip_db: Option<Mutex<ip2location::DB>>,
 }
 
@@ -33,25 +46,36 @@ impl AuthAccountConfig {
             login_limit_captcha: 3,
             login_limit_lock: 8,
             login_limit_time: 300,
+            login_travel_speed: 1000,
+            impersonate_time: 1800,
             ip_db: ip_db.map(|e| Mutex::new(e)),
         }
     }
 }
 pub struct AuthAccount {
     account_history: Arc<AccountLoginHistory>,
+    account_security: Arc<AccountSecurity>,
+    account_delete: Arc<AccountDelete>,
     access: Arc<AccessDao>,
+    logger: Arc<ChangeLoggerDao>,
     login_config: AuthAccountConfig,
 }
 impl AuthAccount {
     /// 对外对象创建
     pub fn new(
         account_history: Arc<AccountLoginHistory>,
+        account_security: Arc<AccountSecurity>,
+        account_delete: Arc<AccountDelete>,
         access: Arc<AccessDao>,
+        logger: Arc<ChangeLoggerDao>,
         login_config: AuthAccountConfig,
     ) -> Self {
         Self {
             account_history,
+            account_security,
+            account_delete,
             access,
+            logger,
             login_config,
         }
     }
@@ -134,6 +158,10 @@ impl AuthAccount {
     }
     //IP 转成城市
     async fn env_to_city(&self, login_env: &AccountLoginEnv) -> Option<String> {
+        self.env_to_geo(login_env).await.map(|e| e.city)
+    }
+    //IP 转成地理信息
+    async fn env_to_geo(&self, login_env: &AccountLoginEnv) -> Option<AccountLoginGeo> {
         let login_ip = login_env.login_ip?;
         if let Some(ref lock_db) = self.login_config.ip_db {
             #[allow(unused_mut)]
@@ -144,6 +172,18 @@ impl AuthAccount {
                     match rec {
                         Record::LocationDb(record) => {
                             debug!("parse city: {:?} on ip: {:?}", record, login_ip);
+                            let country = record
+                                .country
+                                .as_ref()
+                                .map(|e| e.short_name.to_string())
+                                .filter(|e| e != "-")
+                                .unwrap_or_default();
+                            let location = match (record.latitude, record.longitude) {
+                                (Some(lat), Some(lng)) if lat != 0.0 || lng != 0.0 => {
+                                    Some((lat as f64, lng as f64))
+                                }
+                                _ => None,
+                            };
                             let city = [
                                 record
                                     .country
@@ -156,7 +196,11 @@ impl AuthAccount {
                             .filter(|e| !e.is_empty() && *e != "-")
                             .collect::<Vec<String>>()
                             .join("-");
-                            return Some(city);
+                            return Some(AccountLoginGeo {
+                                country,
+                                city,
+                                location,
+                            });
                         }
                         Record::ProxyDb(_) => {}
                     }
@@ -165,17 +209,17 @@ impl AuthAccount {
         }
         None
     }
-    //执行登录
+    //执行登录,成功时返回登录及异常登录产生的安全事件
     pub async fn login<TO: AccountLoginParam>(
         &self,
         login_param: &TO,
         login_env: AccountLoginEnv,
-    ) -> AccountResult<SessionBody> {
+    ) -> AccountResult<(SessionBody, Option<AccountSecurityEventModel>)> {
         let login_ip = login_env
             .login_ip
             .unwrap_or(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)))
             .to_string();
-        let city = self.env_to_city(&login_env).await.unwrap_or_default();
+        let geo = self.env_to_geo(&login_env).await;
         let login_account = login_param.account_name();
         let login_id = self
             .account_history
@@ -183,17 +227,55 @@ impl AuthAccount {
                 &login_account,
                 &<TO as AccountLoginParam>::Meta::login_type(),
                 &login_ip,
-                &city,
+                geo.as_ref().map(|e| e.city.as_str()).unwrap_or_default(),
+                geo.as_ref().map(|e| e.country.as_str()).unwrap_or_default(),
             )
             .await?;
-        let res = self.login_user(login_param, login_env).await;
+        let res = self.login_user(login_param, &login_env).await;
         match res {
             Ok((account, session)) => {
                 let is_login = i8::from(session.is_valid());
                 self.account_history
                     .finish_history(login_id, is_login, account.id, "")
                     .await?;
-                Ok(session)
+                //宽限期内登录时撤销注销申请
+                if let Err(err) = self
+                    .account_delete
+                    .delete_restore(account.id, account.id, None)
+                    .await
+                {
+                    warn!(
+                        "login restore account delete fail: {} in account:{}",
+                        err.to_fluent_message().default_format(),
+                        account.id
+                    );
+                }
+                //检测失败不影响登录
+                let event = match self
+                    .account_security
+                    .login_check(&AccountSecurityCheckParam {
+                        account_id: account.id,
+                        session_id: session.session_id(),
+                        login_id,
+                        login_ip: &login_ip,
+                        device_id: login_env.device_id.as_deref(),
+                        user_agent: login_env.user_agent.as_deref(),
+                        geo: geo.as_ref(),
+                        travel_speed: self.login_config.login_travel_speed,
+                    })
+                    .await
+                {
+                    Ok(event) => event,
+                    Err(err) => {
+                        warn!(
+                            "login security check fail: {} in account:{}",
+                            err.to_fluent_message().default_format(),
+                            account.id
+                        );
+                        None
+                    }
+                };
+                Ok((session, event))
             }
             Err(err) => {
                 let account_id = match err {
@@ -214,12 +296,64 @@ impl AuthAccount {
             }
         }
     }
+    /// 重新验证当前登录账号的身份,用于敏感操作前
+    /// 验证结果计入登录历史,与登录共用失败次数限制
+    pub async fn reauth<TO: AccountLoginParam>(
+        &self,
+        login_param: &TO,
+        login_env: &AccountLoginEnv,
+        session_body: &SessionBody,
+    ) -> AccountResult<SessionBody> {
+        let account_id = session_body.account_id()?;
+        let login_ip = login_env
+            .login_ip
+            .unwrap_or(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)))
+            .to_string();
+        let login_city = self.env_to_city(login_env).await.unwrap_or_default();
+        let login_id = self
+            .account_history
+            .create_history(
+                &login_param.account_name(),
+                &<TO as AccountLoginParam>::Meta::login_type(),
+                &login_ip,
+                &login_city,
+                "",
+            )
+            .await?;
+        let res = match login_param.get_account(login_env).await {
+            Ok((_, account)) if account.id != account_id => Err(AccountError::PasswordNotMatch((
+                account_id,
+                fluent_message!("auth-reauth-not-match"),
+            ))),
+            Ok(_) => Ok(()),
+            Err(err) => Err(err),
+        };
+        match res {
+            Ok(()) => {
+                self.account_history
+                    .finish_history(login_id, 1, account_id, "reauth")
+                    .await?;
+                Ok(self.access.auth.reauth(session_body).await?)
+            }
+            Err(err) => {
+                self.account_history
+                    .finish_history(
+                        login_id,
+                        0,
+                        account_id,
+                        err.to_fluent_message().default_format(),
+                    )
+                    .await?;
+                Err(err)
+            }
+        }
+    }
     async fn login_user<TO: AccountLoginParam>(
         &self,
         login_param: &TO,
-        login_env: AccountLoginEnv,
+        login_env: &AccountLoginEnv,
     ) -> AccountResult<(AccountModel, SessionBody)> {
-        let (login_type_data, account) = login_param.get_account(&login_env).await?;
+        let (login_type_data, account) = login_param.get_account(login_env).await?;
         if AccountStatus::Delete.eq(account.status) {
             return Err(AccountError::AuthStatusError((
                 account.id,
@@ -240,10 +374,12 @@ impl AuthAccount {
         let login_data = AccessLoginData {
             user_account: Some(&login_account),
             login_ip: Some(&login_ip),
-            device_id: None,
+            device_id: login_env.device_id.as_deref(),
             device_name: None,
+            user_agent: login_env.user_agent.as_deref(),
             expire_time: time + <TO as AccountLoginParam>::Meta::login_timeout(),
             session_data,
+            impersonate_user_id: 0,
         };
         let session = self
             .access
@@ -260,4 +396,105 @@ impl AuthAccount {
             .await?;
         Ok((account, session))
     }
+    /// 是否为模拟登录会话的令牌
+    pub fn is_impersonate_token(token: &str) -> bool {
+        token.starts_with(ACCOUNT_IMPERSONATE_TOKEN_PREFIX)
+    }
+    //模拟指定账号登录,登录会话记录实际操作的用户ID
+    //不记录登录历史及安全检测,会话不可延长
+    pub async fn impersonate(
+        &self,
+        account: &AccountModel,
+        op_user_id: u64,
+        login_env: &AccountLoginEnv,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<SessionBody> {
+        if !AccountStatus::Enable.eq(account.status) {
+            return Err(AccountError::System(fluent_message!(
+                "auth-impersonate-status-bad",{
+                    "account_id":account.id
+                }
+            )));
+        }
+        let login_ip = login_env
+            .login_ip
+            .unwrap_or(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)))
+            .to_string();
+        let login_data = AccessLoginData {
+            user_account: None,
+            login_ip: Some(&login_ip),
+            device_id: login_env.device_id.as_deref(),
+            device_name: None,
+            user_agent: login_env.user_agent.as_deref(),
+            expire_time: now_time()? + self.login_config.impersonate_time,
+            session_data: vec![],
+            impersonate_user_id: op_user_id,
+        };
+        let token_data = format!(
+            "{}{}",
+            ACCOUNT_IMPERSONATE_TOKEN_PREFIX,
+            rand_str(RandType::Lower, 32)
+        );
+        let session = self
+            .access
+            .auth
+            .do_login(&AccessAuthLoginData {
+                app_id: 0,
+                oauth_app_id: 0,
+                user_data: account.id,
+                user_nickname: &account.nickname,
+                token_data: Some(&token_data),
+                login_type: ACCOUNT_IMPERSONATE_LOGIN_TYPE,
+                login_data: Some(&login_data),
+            })
+            .await?;
+        self.logger
+            .add(
+                &LogAccountImpersonate {
+                    action: "login",
+                    account_id: account.id,
+                    user_id: session.user_id(),
+                    impersonate_user_id: op_user_id,
+                    session_id: session.session_id(),
+                    request_path: "",
+                },
+                Some(account.id),
+                Some(op_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(session)
+    }
+    //记录模拟登录会话的请求,非模拟登录会话忽略
+    pub async fn impersonate_record(
+        &self,
+        session: &SessionBody,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<()> {
+        let impersonate_user_id = match session.impersonate_user_id() {
+            Some(user_id) => user_id,
+            None => return Ok(()),
+        };
+        let account_id = session.account_id()?;
+        self.logger
+            .add(
+                &LogAccountImpersonate {
+                    action: "request",
+                    account_id,
+                    user_id: session.user_id(),
+                    impersonate_user_id,
+                    session_id: session.session_id(),
+                    request_path: env_data
+                        .and_then(|e| e.request_path.as_deref())
+                        .unwrap_or_default(),
+                },
+                Some(account_id),
+                Some(impersonate_user_id),
+                None,
+                env_data,
+            )
+            .await;
+        Ok(())
+    }
 }
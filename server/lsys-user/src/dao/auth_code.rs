@@ -1,22 +1,37 @@
 //使用rest接口登陆后，根据生成的登陆code，完成在系统后台登陆的实现
-use lsys_access::dao::{AccessAuthLoginData, AccessDao, AccessLoginData, SessionBody};
+use lsys_access::dao::{AccessAuthLoginData, AccessDao, AccessError, AccessLoginData, SessionBody};
 use lsys_core::{
-    valid_key, AppCore, ValidNumber, ValidParam, ValidParamCheck, ValidPattern, ValidStrlen,
+    valid_key, AppCore, IntoFluentMessage, RequestEnv, ValidNumber, ValidParam, ValidParamCheck,
+    ValidPattern, ValidStrlen,
 };
+use lsys_logger::dao::ChangeLoggerDao;
+use tracing::warn;
 
 use std::sync::Arc;
 
-use super::{AccountResult, UserAuthToken};
+use super::{logger::LogAuthRefreshReuse, AccountResult, AccountSecurity, UserAuthToken};
 
 pub struct AuthCode {
     access: Arc<AccessDao>,
+    account_security: Arc<AccountSecurity>,
+    logger: Arc<ChangeLoggerDao>,
     app_core: Arc<AppCore>,
 }
 pub const CODE_LOGIN_TYPE: &str = "code";
 
 impl AuthCode {
-    pub fn new(access: Arc<AccessDao>, app_core: Arc<AppCore>) -> Self {
-        Self { access, app_core }
+    pub fn new(
+        access: Arc<AccessDao>,
+        account_security: Arc<AccountSecurity>,
+        logger: Arc<ChangeLoggerDao>,
+        app_core: Arc<AppCore>,
+    ) -> Self {
+        Self {
+            access,
+            account_security,
+            logger,
+            app_core,
+        }
     }
     pub async fn code_login(
         &self,
@@ -69,6 +84,89 @@ impl AuthCode {
         let session = self.access.auth.login_data(app_id, 0, token_data).await?;
         Ok(self.access.auth.do_logout(&session).await?)
     }
+    //为登录创建刷新token
+    pub async fn code_refresh_token(
+        &self,
+        session: &SessionBody,
+        login_time: u64,
+        refresh_time: u64,
+    ) -> AccountResult<String> {
+        Ok(self
+            .access
+            .auth
+            .create_refresh_token(session, login_time, refresh_time)
+            .await?)
+    }
+    //通过刷新token延长登录,返回新的刷新token
+    pub async fn code_refresh(
+        &self,
+        app_id: u64,
+        refresh_token: &str,
+        env_data: Option<&RequestEnv>,
+    ) -> AccountResult<(SessionBody, String)> {
+        match self
+            .access
+            .auth
+            .refresh_login(app_id, 0, refresh_token)
+            .await
+        {
+            Ok(data) => Ok(data),
+            Err(AccessError::RefreshTokenReuse(session_id)) => {
+                self.refresh_reuse_record(app_id, session_id, env_data)
+                    .await;
+                Err(AccessError::RefreshTokenReuse(session_id).into())
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+    //刷新token被再次使用,记录变更日志,登录用户为系统账号时同时产生安全事件
+    async fn refresh_reuse_record(
+        &self,
+        app_id: u64,
+        session_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) {
+        let user = match self.access.auth.refresh_session_user(session_id).await {
+            Ok(user) => user,
+            Err(err) => {
+                warn!(
+                    "find refresh reuse session {} fail:{}",
+                    session_id,
+                    err.to_fluent_message().default_format()
+                );
+                return;
+            }
+        };
+        self.logger
+            .add(
+                &LogAuthRefreshReuse {
+                    app_id,
+                    session_id,
+                    user_id: user.id,
+                },
+                Some(session_id),
+                Some(user.id),
+                None,
+                env_data,
+            )
+            .await;
+        if user.app_id != 0 {
+            return;
+        }
+        let Ok(account_id) = user.user_data.parse::<u64>() else {
+            return;
+        };
+        if let Err(err) = self
+            .account_security
+            .refresh_reuse_event(account_id, session_id, env_data)
+            .await
+        {
+            warn!(
+                "add refresh reuse security event fail:{}",
+                err.to_fluent_message().default_format()
+            );
+        }
+    }
     pub async fn login_data(&self, app_id: u64, token_data: &str) -> AccountResult<SessionBody> {
         Ok(self
             .access
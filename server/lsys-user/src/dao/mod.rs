@@ -14,6 +14,7 @@ pub struct UserDao {
     pub auth_dao: Arc<UserAuthDao>,
     pub auth_account_dao: Arc<AuthAccount>,
     pub auth_code_dao: Arc<AuthCode>,
+    pub email_link_dao: Arc<login::EmailLinkDao>,
 }
 
 impl UserDao {
@@ -22,12 +23,14 @@ impl UserDao {
         auth_dao: Arc<UserAuthDao>,
         auth_account_dao: Arc<AuthAccount>,
         auth_code_dao: Arc<AuthCode>,
+        email_link_dao: Arc<login::EmailLinkDao>,
     ) -> Self {
         Self {
             account_dao,
             auth_dao,
             auth_code_dao,
             auth_account_dao,
+            email_link_dao,
         }
     }
 
@@ -42,6 +45,47 @@ impl UserDao {
             account::logger::LogAccountName::log_type(),
             account::logger::LogAccount::log_type(),
             account::logger::LogAccountPassWrod::log_type(),
+            account::logger::LogAccountToken::log_type(),
+            account::logger::LogAccountSecurity::log_type(),
+            account::logger::LogAccountMerge::log_type(),
+            account::logger::LogAccountExport::log_type(),
+            account::logger::LogAccountDelete::log_type(),
+            account::logger::LogAccountInvite::log_type(),
+            account::logger::LogAccountRegister::log_type(),
+            account::logger::LogAccountImport::log_type(),
+            account::logger::LogAccountImpersonate::log_type(),
+            account::logger::LogAuthRefreshReuse::log_type(),
+        ]
+    }
+    //以指定账号为主体的日志条件
+    pub fn log_subjects(account_id: u64) -> Vec<lsys_logger::dao::ChangeLogSubject<'static>> {
+        use account::logger::*;
+        use lsys_logger::dao::{ChangeLogData, ChangeLogSubject};
+        vec![
+            ChangeLogSubject::Source(LogAccount::log_type(), account_id),
+            ChangeLogSubject::Source(LogAccountInfo::log_type(), account_id),
+            ChangeLogSubject::Source(LogAccountName::log_type(), account_id),
+            ChangeLogSubject::DataKey(LogAccountAddress::log_type(), "account_id", account_id),
+            ChangeLogSubject::DataKey(LogAccountEmail::log_type(), "account_id", account_id),
+            ChangeLogSubject::DataKey(LogAccountExternal::log_type(), "account_id", account_id),
+            ChangeLogSubject::DataKey(LogAccountMobile::log_type(), "account_id", account_id),
+            ChangeLogSubject::DataKey(LogAccountPassWrod::log_type(), "account_id", account_id),
+            ChangeLogSubject::DataKey(LogAccountToken::log_type(), "account_id", account_id),
+            ChangeLogSubject::DataKey(LogAccountSecurity::log_type(), "account_id", account_id),
+            ChangeLogSubject::DataKey(LogAccountExport::log_type(), "account_id", account_id),
+            ChangeLogSubject::DataKey(LogAccountDelete::log_type(), "account_id", account_id),
+            ChangeLogSubject::DataKey(LogAccountRegister::log_type(), "account_id", account_id),
+            ChangeLogSubject::DataKey(LogAccountImpersonate::log_type(), "account_id", account_id),
+            ChangeLogSubject::DataKey(
+                LogAccountMerge::log_type(),
+                "target_account_id",
+                account_id,
+            ),
+            ChangeLogSubject::DataKey(
+                LogAccountMerge::log_type(),
+                "source_account_id",
+                account_id,
+            ),
         ]
     }
 }
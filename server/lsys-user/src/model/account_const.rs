@@ -65,3 +65,66 @@ pub enum AccountIndexCat {
     AccountStatus = 7,   //只有 enable 跟 init, 且只存在其中一个
     RegFrom = 8,      //如果存在其中一个
 }
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[lsys_model_status(field_type = "i8")]
+pub enum AccountTokenStatus {
+    Enable = 1,
+    Delete = -1,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[lsys_model_status(field_type = "i8")]
+pub enum AccountSecurityEventStatus {
+    Pending = 1, //待用户确认
+    Confirm = 2, //用户确认为本人操作
+    Secure = 3,  //用户确认非本人,已下线全部登录
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[lsys_model_status(field_type = "i8")]
+pub enum AccountExportStatus {
+    Init = 1,    //待导出
+    Finish = 2,  //导出完成,可下载
+    Fail = 3,    //导出失败
+    Delete = -1, //已过期,文件已删除
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[lsys_model_status(field_type = "i8")]
+pub enum AccountDeleteStatus {
+    Pending = 1, //等待删除,可恢复
+    Restore = 2, //已恢复
+    Finish = 3,  //已删除并匿名化
+    Fail = 4,    //删除失败
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[lsys_model_status(field_type = "i8")]
+pub enum AccountInviteStatus {
+    Enable = 1,  //可用
+    Disable = 2, //已停用
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[lsys_model_status(field_type = "i8")]
+pub enum AccountRegisterStatus {
+    Pending = 1,  //等待审核
+    Approved = 2, //已通过
+    Reject = 3,   //已拒绝
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[lsys_model_status(field_type = "i8")]
+pub enum AccountImportStatus {
+    Init = 1,   //待导入或导入中
+    Finish = 2, //全部行已处理
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[lsys_model_status(field_type = "i8")]
+pub enum AccountImportRowStatus {
+    Init = 1, //待导入
+    Succ = 2, //已导入
+    Fail = 3, //校验或导入失败
+}
@@ -240,6 +240,10 @@ pub struct AccountLoginModel {
     #[sqlx(default)]
     pub login_city: String,
 
+    /// IP对应国家代码
+    #[sqlx(default)]
+    pub login_country: String,
+
     /// 尝试登录账号对应用户ID  default:  0
     #[sqlx(default)]
     pub account_id: u64,
@@ -360,3 +364,410 @@ pub struct AccountIndexModel {
     #[sqlx(default)]
     pub change_time: u64,
 }
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+#[lsys_model(table_name = "account_token")]
+pub struct AccountTokenModel {
+    #[sqlx(default)]
+    pub id: u64,
+
+    /// 用户ID
+    #[sqlx(default)]
+    pub account_id: u64,
+
+    /// 对应登录记录ID
+    #[sqlx(default)]
+    pub session_id: u64,
+
+    /// 令牌名称
+    #[sqlx(default)]
+    pub token_name: String,
+
+    /// 令牌HASH
+    #[sqlx(default)]
+    pub token_hash: String,
+
+    /// 授权范围,资源类型:操作,逗号分隔
+    #[sqlx(default)]
+    pub token_scope: String,
+
+    /// 过期时间
+    #[sqlx(default)]
+    pub expire_time: u64,
+
+    /// 最后使用时间
+    #[sqlx(default)]
+    pub last_used_time: u64,
+
+    /// 最后使用IP
+    #[sqlx(default)]
+    pub last_used_ip: String,
+
+    /// 状态
+    #[sqlx(default)]
+    pub status: i8,
+
+    /// 添加时间
+    #[sqlx(default)]
+    pub add_time: u64,
+
+    /// 最后更改时间
+    #[sqlx(default)]
+    pub change_time: u64,
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+#[lsys_model(table_name = "account_login_device")]
+pub struct AccountLoginDeviceModel {
+    #[sqlx(default)]
+    pub id: u64,
+
+    /// 用户ID
+    #[sqlx(default)]
+    pub account_id: u64,
+
+    /// 设备指纹
+    #[sqlx(default)]
+    pub device_hash: String,
+
+    /// 设备名,登录时的UA
+    #[sqlx(default)]
+    pub device_name: String,
+
+    /// 最后登录IP
+    #[sqlx(default)]
+    pub login_ip: String,
+
+    /// 最后登录国家代码
+    #[sqlx(default)]
+    pub login_country: String,
+
+    /// 最后登录城市
+    #[sqlx(default)]
+    pub login_city: String,
+
+    /// 最后登录纬度,单位万分之一度
+    #[sqlx(default)]
+    pub login_lat: i32,
+
+    /// 最后登录经度,单位万分之一度
+    #[sqlx(default)]
+    pub login_lng: i32,
+
+    /// 登录次数
+    #[sqlx(default)]
+    pub login_num: u32,
+
+    /// 最后登录时间
+    #[sqlx(default)]
+    pub last_time: u64,
+
+    /// 首次登录时间
+    #[sqlx(default)]
+    pub add_time: u64,
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+#[lsys_model(table_name = "account_security_event")]
+pub struct AccountSecurityEventModel {
+    #[sqlx(default)]
+    pub id: u64,
+
+    /// 用户ID
+    #[sqlx(default)]
+    pub account_id: u64,
+
+    /// 触发的登录记录ID
+    #[sqlx(default)]
+    pub session_id: u64,
+
+    /// 事件类型,逗号分隔
+    #[sqlx(default)]
+    pub event_type: String,
+
+    /// 设备指纹
+    #[sqlx(default)]
+    pub device_hash: String,
+
+    /// 设备名
+    #[sqlx(default)]
+    pub device_name: String,
+
+    /// 登录IP
+    #[sqlx(default)]
+    pub login_ip: String,
+
+    /// 登录城市
+    #[sqlx(default)]
+    pub login_city: String,
+
+    /// 附加数据,JSON
+    #[sqlx(default)]
+    pub event_data: String,
+
+    /// 状态
+    #[sqlx(default)]
+    pub status: i8,
+
+    /// 添加时间
+    #[sqlx(default)]
+    pub add_time: u64,
+
+    /// 处理时间
+    #[sqlx(default)]
+    pub change_time: u64,
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+#[lsys_model(table_name = "account_export")]
+pub struct AccountExportModel {
+    #[sqlx(default)]
+    pub id: u64,
+
+    /// 用户ID
+    #[sqlx(default)]
+    pub account_id: u64,
+
+    /// 状态
+    #[sqlx(default)]
+    pub status: i8,
+
+    /// 导出文件路径
+    #[sqlx(default)]
+    pub file_path: String,
+
+    /// 导出文件大小
+    #[sqlx(default)]
+    pub file_size: u64,
+
+    /// 下载令牌
+    #[sqlx(default)]
+    pub download_token: String,
+
+    /// 下载过期时间
+    #[sqlx(default)]
+    pub expire_time: u64,
+
+    /// 失败原因
+    #[sqlx(default)]
+    pub message: String,
+
+    /// 申请用户
+    #[sqlx(default)]
+    pub add_user_id: u64,
+
+    /// 申请时间
+    #[sqlx(default)]
+    pub add_time: u64,
+
+    /// 完成时间
+    #[sqlx(default)]
+    pub finish_time: u64,
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+#[lsys_model(table_name = "account_delete")]
+pub struct AccountDeleteModel {
+    #[sqlx(default)]
+    pub id: u64,
+
+    /// 用户ID
+    #[sqlx(default)]
+    pub account_id: u64,
+
+    /// 状态
+    #[sqlx(default)]
+    pub status: i8,
+
+    /// 计划删除时间
+    #[sqlx(default)]
+    pub delete_time: u64,
+
+    /// 失败原因
+    #[sqlx(default)]
+    pub message: String,
+
+    /// 申请用户
+    #[sqlx(default)]
+    pub add_user_id: u64,
+
+    /// 申请时间
+    #[sqlx(default)]
+    pub add_time: u64,
+
+    /// 恢复或处理用户
+    #[sqlx(default)]
+    pub change_user_id: u64,
+
+    /// 恢复或处理时间
+    #[sqlx(default)]
+    pub change_time: u64,
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+#[lsys_model(table_name = "account_invite")]
+pub struct AccountInviteModel {
+    #[sqlx(default)]
+    pub id: u64,
+
+    /// 邀请码
+    #[sqlx(default)]
+    pub invite_code: String,
+
+    /// 最大使用次数
+    #[sqlx(default)]
+    pub max_num: u32,
+
+    /// 已使用次数
+    #[sqlx(default)]
+    pub use_num: u32,
+
+    /// 过期时间,0为不过期
+    #[sqlx(default)]
+    pub expire_time: u64,
+
+    /// 备注
+    #[sqlx(default)]
+    pub note: String,
+
+    /// 状态
+    #[sqlx(default)]
+    pub status: i8,
+
+    /// 发放用户
+    #[sqlx(default)]
+    pub add_user_id: u64,
+
+    /// 发放时间
+    #[sqlx(default)]
+    pub add_time: u64,
+
+    /// 更改用户
+    #[sqlx(default)]
+    pub change_user_id: u64,
+
+    /// 更改时间
+    #[sqlx(default)]
+    pub change_time: u64,
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+#[lsys_model(table_name = "account_register")]
+pub struct AccountRegisterModel {
+    #[sqlx(default)]
+    pub id: u64,
+
+    /// 用户ID
+    #[sqlx(default)]
+    pub account_id: u64,
+
+    /// 使用的邀请码ID
+    #[sqlx(default)]
+    pub invite_id: u64,
+
+    /// 注册方式
+    #[sqlx(default)]
+    pub reg_from: String,
+
+    /// 状态
+    #[sqlx(default)]
+    pub status: i8,
+
+    /// 审核说明
+    #[sqlx(default)]
+    pub message: String,
+
+    /// 注册时间
+    #[sqlx(default)]
+    pub add_time: u64,
+
+    /// 审核用户
+    #[sqlx(default)]
+    pub change_user_id: u64,
+
+    /// 审核时间
+    #[sqlx(default)]
+    pub change_time: u64,
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+#[lsys_model(table_name = "account_import")]
+pub struct AccountImportModel {
+    #[sqlx(default)]
+    pub id: u64,
+
+    /// 导入名称
+    #[sqlx(default)]
+    pub name: String,
+
+    /// 导入邮箱及手机号是否标记为已验证
+    #[sqlx(default)]
+    pub confirm: i8,
+
+    /// 状态
+    #[sqlx(default)]
+    pub status: i8,
+
+    /// 总行数
+    #[sqlx(default)]
+    pub total_num: u32,
+
+    /// 导入成功行数
+    #[sqlx(default)]
+    pub succ_num: u32,
+
+    /// 校验或导入失败行数
+    #[sqlx(default)]
+    pub fail_num: u32,
+
+    /// 添加用户
+    #[sqlx(default)]
+    pub add_user_id: u64,
+
+    /// 添加时间
+    #[sqlx(default)]
+    pub add_time: u64,
+
+    /// 最后导入时间
+    #[sqlx(default)]
+    pub change_time: u64,
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+#[lsys_model(table_name = "account_import_row")]
+pub struct AccountImportRowModel {
+    #[sqlx(default)]
+    pub id: u64,
+
+    /// 导入ID
+    #[sqlx(default)]
+    pub import_id: u64,
+
+    /// 源数据行号
+    #[sqlx(default)]
+    pub row_num: u32,
+
+    /// 原系统用户标识
+    #[sqlx(default)]
+    pub ref_id: String,
+
+    /// 待导入数据,密码已转为HASH
+    #[sqlx(default)]
+    pub data: String,
+
+    /// 状态
+    #[sqlx(default)]
+    pub status: i8,
+
+    /// 导入后的用户ID
+    #[sqlx(default)]
+    pub account_id: u64,
+
+    /// 失败原因
+    #[sqlx(default)]
+    pub message: String,
+
+    /// 处理时间
+    #[sqlx(default)]
+    pub change_time: u64,
+}
@@ -112,7 +112,148 @@ CREATE TABLE `yaf_account_login` (
     `account_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '尝试登录账号对应用户ID',
     `login_ip` varchar(46) NOT NULL DEFAULT '' COMMENT '登陆者IP',
     `login_city` varchar(100) DEFAULT '' NOT NULL COMMENT '登陆城市',
+    `login_country` varchar(8) DEFAULT '' NOT NULL COMMENT '登陆国家代码',
     `login_msg` varchar(100) DEFAULT '' NOT NULL COMMENT '登陆消息',
     `add_time` bigint unsigned NOT NULL COMMENT '登录时间',
-    PRIMARY KEY (`id`)
+    PRIMARY KEY (`id`),
+    KEY `account_login_country_IDX` (`account_id`, `login_country`) USING BTREE
 ) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '用户登录记录';
+CREATE TABLE `yaf_account_token` (
+    `id` bigint unsigned NOT NULL AUTO_INCREMENT,
+    `account_id` bigint unsigned NOT NULL COMMENT '用户ID',
+    `session_id` bigint unsigned NOT NULL COMMENT '对应登录记录ID',
+    `token_name` varchar(64) NOT NULL COMMENT '令牌名称',
+    `token_hash` varchar(64) NOT NULL COMMENT '令牌HASH',
+    `token_scope` varchar(2000) NOT NULL DEFAULT '' COMMENT '授权范围',
+    `expire_time` bigint unsigned NOT NULL COMMENT '过期时间',
+    `last_used_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '最后使用时间',
+    `last_used_ip` varchar(46) NOT NULL DEFAULT '' COMMENT '最后使用IP',
+    `status` tinyint NOT NULL DEFAULT 1 COMMENT '状态',
+    `add_time` bigint unsigned NOT NULL COMMENT '添加时间',
+    `change_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '最后更改时间',
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `account_token_hash_IDX` (`token_hash`) USING BTREE,
+    KEY `account_token_session_id_IDX` (`session_id`) USING BTREE,
+    KEY `account_token_account_id_IDX` (`account_id`, `status`) USING BTREE
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '用户个人访问令牌';
+CREATE TABLE `yaf_account_login_device` (
+    `id` bigint unsigned NOT NULL AUTO_INCREMENT,
+    `account_id` bigint unsigned NOT NULL COMMENT '用户ID',
+    `device_hash` varchar(64) NOT NULL COMMENT '设备指纹',
+    `device_name` varchar(255) NOT NULL DEFAULT '' COMMENT '设备名',
+    `login_ip` varchar(46) NOT NULL DEFAULT '' COMMENT '最后登录IP',
+    `login_country` varchar(8) NOT NULL DEFAULT '' COMMENT '最后登录国家代码',
+    `login_city` varchar(100) NOT NULL DEFAULT '' COMMENT '最后登录城市',
+    `login_lat` int NOT NULL DEFAULT 0 COMMENT '最后登录纬度,万分之一度',
+    `login_lng` int NOT NULL DEFAULT 0 COMMENT '最后登录经度,万分之一度',
+    `login_num` int unsigned NOT NULL DEFAULT 0 COMMENT '登录次数',
+    `last_time` bigint unsigned NOT NULL COMMENT '最后登录时间',
+    `add_time` bigint unsigned NOT NULL COMMENT '首次登录时间',
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `account_login_device_IDX` (`account_id`, `device_hash`) USING BTREE
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '用户登录设备';
+CREATE TABLE `yaf_account_security_event` (
+    `id` bigint unsigned NOT NULL AUTO_INCREMENT,
+    `account_id` bigint unsigned NOT NULL COMMENT '用户ID',
+    `session_id` bigint unsigned NOT NULL COMMENT '触发的登录记录ID',
+    `event_type` varchar(64) NOT NULL COMMENT '事件类型',
+    `device_hash` varchar(64) NOT NULL DEFAULT '' COMMENT '设备指纹',
+    `device_name` varchar(255) NOT NULL DEFAULT '' COMMENT '设备名',
+    `login_ip` varchar(46) NOT NULL DEFAULT '' COMMENT '登录IP',
+    `login_city` varchar(100) NOT NULL DEFAULT '' COMMENT '登录城市',
+    `event_data` varchar(512) NOT NULL DEFAULT '' COMMENT '附加数据',
+    `status` tinyint NOT NULL DEFAULT 1 COMMENT '状态',
+    `add_time` bigint unsigned NOT NULL COMMENT '添加时间',
+    `change_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '处理时间',
+    PRIMARY KEY (`id`),
+    KEY `account_security_event_IDX` (`account_id`, `status`) USING BTREE
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '用户安全事件';
+CREATE TABLE `yaf_account_export` (
+    `id` bigint unsigned NOT NULL AUTO_INCREMENT,
+    `account_id` bigint unsigned NOT NULL COMMENT '用户ID',
+    `status` tinyint NOT NULL DEFAULT 1 COMMENT '状态',
+    `file_path` varchar(255) NOT NULL DEFAULT '' COMMENT '导出文件路径',
+    `file_size` bigint unsigned NOT NULL DEFAULT 0 COMMENT '导出文件大小',
+    `download_token` varchar(64) NOT NULL DEFAULT '' COMMENT '下载令牌',
+    `expire_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '下载过期时间',
+    `message` varchar(255) NOT NULL DEFAULT '' COMMENT '失败原因',
+    `add_user_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '申请用户',
+    `add_time` bigint unsigned NOT NULL COMMENT '申请时间',
+    `finish_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '完成时间',
+    PRIMARY KEY (`id`),
+    KEY `account_export_account_IDX` (`account_id`, `status`) USING BTREE,
+    KEY `account_export_status_IDX` (`status`, `expire_time`) USING BTREE,
+    KEY `account_export_token_IDX` (`download_token`) USING BTREE
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '用户个人数据导出';
+CREATE TABLE `yaf_account_delete` (
+    `id` bigint unsigned NOT NULL AUTO_INCREMENT,
+    `account_id` bigint unsigned NOT NULL COMMENT '用户ID',
+    `status` tinyint NOT NULL DEFAULT 1 COMMENT '状态',
+    `delete_time` bigint unsigned NOT NULL COMMENT '计划删除时间',
+    `message` varchar(255) NOT NULL DEFAULT '' COMMENT '失败原因',
+    `add_user_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '申请用户',
+    `add_time` bigint unsigned NOT NULL COMMENT '申请时间',
+    `change_user_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '恢复或处理用户',
+    `change_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '恢复或处理时间',
+    PRIMARY KEY (`id`),
+    KEY `account_delete_account_IDX` (`account_id`, `status`) USING BTREE,
+    KEY `account_delete_status_IDX` (`status`, `delete_time`) USING BTREE
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '用户注销申请';
+CREATE TABLE `yaf_account_invite` (
+    `id` bigint unsigned NOT NULL AUTO_INCREMENT,
+    `invite_code` varchar(64) NOT NULL COMMENT '邀请码',
+    `max_num` int unsigned NOT NULL COMMENT '最大使用次数',
+    `use_num` int unsigned NOT NULL DEFAULT 0 COMMENT '已使用次数',
+    `expire_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '过期时间,0为不过期',
+    `note` varchar(255) NOT NULL DEFAULT '' COMMENT '备注',
+    `status` tinyint NOT NULL DEFAULT 1 COMMENT '状态',
+    `add_user_id` bigint unsigned NOT NULL COMMENT '发放用户',
+    `add_time` bigint unsigned NOT NULL COMMENT '发放时间',
+    `change_user_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '更改用户',
+    `change_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '更改时间',
+    PRIMARY KEY (`id`),
+    UNIQUE KEY `account_invite_code_IDX` (`invite_code`) USING BTREE,
+    KEY `account_invite_user_IDX` (`add_user_id`, `status`) USING BTREE
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '注册邀请码';
+CREATE TABLE `yaf_account_register` (
+    `id` bigint unsigned NOT NULL AUTO_INCREMENT,
+    `account_id` bigint unsigned NOT NULL COMMENT '用户ID',
+    `invite_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '使用的邀请码ID',
+    `reg_from` varchar(32) NOT NULL DEFAULT '' COMMENT '注册方式',
+    `status` tinyint NOT NULL COMMENT '状态',
+    `message` varchar(255) NOT NULL DEFAULT '' COMMENT '审核说明',
+    `add_time` bigint unsigned NOT NULL COMMENT '注册时间',
+    `change_user_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '审核用户',
+    `change_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '审核时间',
+    PRIMARY KEY (`id`),
+    KEY `account_register_account_IDX` (`account_id`) USING BTREE,
+    KEY `account_register_status_IDX` (`status`) USING BTREE,
+    KEY `account_register_invite_IDX` (`invite_id`) USING BTREE
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '用户注册记录及审核';
+CREATE TABLE `yaf_account_import` (
+    `id` bigint unsigned NOT NULL AUTO_INCREMENT,
+    `name` varchar(64) NOT NULL DEFAULT '' COMMENT '导入名称',
+    `confirm` tinyint NOT NULL DEFAULT 0 COMMENT '导入邮箱及手机号是否标记为已验证',
+    `status` tinyint NOT NULL DEFAULT 1 COMMENT '状态',
+    `total_num` int unsigned NOT NULL DEFAULT 0 COMMENT '总行数',
+    `succ_num` int unsigned NOT NULL DEFAULT 0 COMMENT '导入成功行数',
+    `fail_num` int unsigned NOT NULL DEFAULT 0 COMMENT '校验或导入失败行数',
+    `add_user_id` bigint unsigned NOT NULL COMMENT '添加用户',
+    `add_time` bigint unsigned NOT NULL COMMENT '添加时间',
+    `change_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '最后导入时间',
+    PRIMARY KEY (`id`),
+    KEY `account_import_status_IDX` (`status`) USING BTREE
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '账号批量导入';
+CREATE TABLE `yaf_account_import_row` (
+    `id` bigint unsigned NOT NULL AUTO_INCREMENT,
+    `import_id` bigint unsigned NOT NULL COMMENT '导入ID',
+    `row_num` int unsigned NOT NULL COMMENT '源数据行号',
+    `ref_id` varchar(64) NOT NULL DEFAULT '' COMMENT '原系统用户标识',
+    `data` text NOT NULL COMMENT '待导入数据,密码已转为HASH,导入成功后清空',
+    `status` tinyint NOT NULL DEFAULT 1 COMMENT '状态',
+    `account_id` bigint unsigned NOT NULL DEFAULT 0 COMMENT '导入后的用户ID',
+    `message` varchar(255) NOT NULL DEFAULT '' COMMENT '失败原因',
+    `change_time` bigint unsigned NOT NULL DEFAULT 0 COMMENT '处理时间',
+    PRIMARY KEY (`id`),
+    KEY `account_import_row_IDX` (`import_id`, `status`, `row_num`) USING BTREE
+) ENGINE = InnoDB CHARSET = utf8mb4 COMMENT = '账号批量导入明细';
@@ -131,6 +131,13 @@ impl JsonFluent for AccessError {
                 json_data.set_code(400).set_sub_code("token_data")
             }
             AccessError::Sqlx(err) => err.to_json_data(fluent),
+            AccessError::RefreshTokenReuse(_) => {
+                json_data.set_code(403).set_sub_code("refresh_token_reuse")
+            }
+            AccessError::NeedReauth(_) => json_data.set_code(403).set_sub_code("need_reauth"),
+            AccessError::ImpersonateDeny(_) => {
+                json_data.set_code(403).set_sub_code("impersonate_deny")
+            }
             AccessError::NotLogin => json_data.set_code(403).set_sub_code("not_login"),
             AccessError::IsLogout => json_data.set_code(403).set_sub_code("not_login"),
             AccessError::System(_) => json_data,
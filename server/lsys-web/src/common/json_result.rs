@@ -34,6 +34,13 @@ impl JsonError {
             }
         }
     }
+    pub fn to_fluent_string(&self, fluent: &FluentBundle) -> String {
+        match self {
+            JsonError::Error(fluent_error_json_data) => fluent_error_json_data.fluent_format(fluent),
+            JsonError::Message(message) => fluent.format_message(message),
+            JsonError::JsonResponse(_, message) => fluent.format_message(message),
+        }
+    }
 }
 
 pub type JsonResult<T> = Result<T, JsonError>;
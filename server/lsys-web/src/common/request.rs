@@ -5,7 +5,7 @@ use std::{ops::Deref, sync::Arc};
 use async_trait::async_trait;
 use lsys_access::dao::{AccessSession, AccessSessionData, AccessSessionToken};
 use lsys_app::dao::{RestAuthData, RestAuthSession, RestAuthToken};
-use lsys_core::{FluentBundle, RequestEnv};
+use lsys_core::{FluentBundle, IntoFluentMessage, RequestEnv};
 
 use lsys_user::dao::{UserAuthData, UserAuthSession, UserAuthToken};
 
@@ -14,6 +14,7 @@ use crate::{
     dao::WebDao,
 };
 use tokio::sync::RwLock;
+use tracing::warn;
 
 pub struct RequestDao {
     pub web_dao: Arc<WebDao>,
@@ -35,15 +36,7 @@ impl RequestDao {
     }
     // 将 JsonError 转为本地化字符串
     pub fn fluent_error_string(&self, data: &JsonError) -> String {
-        match data {
-            JsonError::Error(fluent_error_json_data) => {
-                fluent_error_json_data.fluent_format(&self.fluent)
-            }
-            JsonError::Message(fluent_message) => self.fluent.format_message(fluent_message),
-            JsonError::JsonResponse(_, fluent_message) => {
-                self.fluent.format_message(fluent_message)
-            }
-        }
+        data.to_fluent_string(&self.fluent)
     }
 }
 
@@ -94,10 +87,48 @@ impl<T: AccessSessionToken, D: AccessSessionData, S: AccessSession<T, D>> Reques
         if let Some(token_data) = token.get_token_data() {
             let user_token = token.get_paser().parse_user_token(token_data).await?;
             token.finish_user_token(&user_token);
+            let is_impersonate = user_token.is_impersonate();
             self.user_session
                 .write()
                 .await
                 .set_session_token(user_token);
+            //个人访问令牌请求记录最后使用时间及IP
+            if let Some(ref token_hash) = self.req_env.personal_token {
+                if let Err(err) = self
+                    .web_dao
+                    .web_user
+                    .user_dao
+                    .account_dao
+                    .account_token
+                    .record_used(token_hash, self.req_env.request_ip.as_deref())
+                    .await
+                {
+                    warn!(
+                        "record personal token used fail:{}",
+                        err.to_fluent_message().default_format()
+                    );
+                }
+            }
+            //模拟登录会话的请求写入变更日志,登录失效等错误由具体接口处理
+            //通过令牌判断是否为模拟登录,普通请求无需额外加载会话
+            if !is_impersonate {
+                return Ok(());
+            }
+            if let Ok(session_data) = self.user_session.read().await.get_session_data().await {
+                if let Err(err) = self
+                    .web_dao
+                    .web_user
+                    .user_dao
+                    .auth_account_dao
+                    .impersonate_record(session_data.session_body(), Some(&self.req_env))
+                    .await
+                {
+                    warn!(
+                        "record impersonate request fail:{}",
+                        err.to_fluent_message().default_format()
+                    );
+                }
+            }
         }
         Ok(())
     }
@@ -1,3 +1,4 @@
+use crate::common::JsonData;
 use crate::common::JsonError;
 use crate::common::JsonResult;
 use lsys_core::{
@@ -125,3 +126,186 @@ impl AppArea {
         Ok(get_area!(self.area).geo_search(lat, lng)?)
     }
 }
+
+//区域名称简称,用于匹配省略后缀的地址文本
+fn area_short_name(name: &str) -> &str {
+    const AREA_SUFFIX: [&str; 14] = [
+        "特别行政区",
+        "维吾尔自治区",
+        "壮族自治区",
+        "回族自治区",
+        "自治区",
+        "自治州",
+        "自治县",
+        "地区",
+        "省",
+        "市",
+        "区",
+        "县",
+        "盟",
+        "旗",
+    ];
+    for suffix in AREA_SUFFIX {
+        if let Some(tmp) = name.strip_suffix(suffix) {
+            if tmp.chars().count() >= 2 {
+                return tmp;
+            }
+        }
+    }
+    name
+}
+
+//地址文本是否以区域名称开头,返回匹配的字节长度
+fn area_prefix_len(name: &str, text: &str) -> Option<usize> {
+    if text.starts_with(name) {
+        return Some(name.len());
+    }
+    let short = area_short_name(name);
+    if short.len() < name.len() && text.starts_with(short) {
+        return Some(short.len());
+    }
+    None
+}
+
+//从同级区域中找出匹配地址文本开头最长的区域
+fn area_prefix_match(items: Vec<AreaCodeItem>, text: &str) -> Option<(AreaCodeItem, usize)> {
+    items
+        .into_iter()
+        .filter_map(|e| area_prefix_len(&e.name, text).map(|len| (e, len)))
+        .max_by_key(|e| e.1)
+}
+
+fn area_code_error(msg: lsys_core::FluentMessage) -> JsonError {
+    JsonError::JsonResponse(
+        JsonData::default().set_code(500).set_sub_code("bad_code"),
+        msg,
+    )
+}
+
+//地址文本解析结果
+pub struct AreaAddressParse {
+    pub item: Vec<AreaCodeItem>, //解析出的区域,从省级开始
+    pub detail: String,          //区域后剩余的详细地址
+    pub related: Vec<Vec<AreaCodeRelatedItem>>,
+}
+
+#[allow(clippy::result_large_err)]
+impl AppArea {
+    /// 区域名称路径,与前端选择器显示一致
+    pub fn code_info(items: &[AreaCodeItem]) -> String {
+        items
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+    /// 校验区域编码存在且为末级区域,传入区域名称时校验名称路径一致
+    pub fn code_verify(&self, code: &str, info: &str) -> JsonResult<Vec<AreaCodeItem>> {
+        let items = self.code_find(code)?;
+        match items.last() {
+            Some(last) if last.code == code.trim() => {
+                if !last.leaf {
+                    return Err(area_code_error(fluent_message!("address-miss-city")));
+                }
+            }
+            _ => return Err(area_code_error(fluent_message!("address-bad-area"))),
+        }
+        let info = info
+            .chars()
+            .filter(|e| !e.is_whitespace() && *e != '/')
+            .collect::<String>();
+        if !info.is_empty() && info != items.iter().map(|e| e.name.as_str()).collect::<String>() {
+            return Err(area_code_error(fluent_message!("address-info-mismatch",{
+                "info":Self::code_info(&items)
+            })));
+        }
+        Ok(items)
+    }
+    //省级区域未匹配时,通过搜索定位地址开头的区域
+    fn address_search_prefix(&self, text: &str) -> JsonResult<Option<(Vec<AreaCodeItem>, usize)>> {
+        let chars = text.char_indices().map(|e| e.0).collect::<Vec<_>>();
+        for num in (2..=chars.len().min(10)).rev() {
+            let end = chars.get(num).copied().unwrap_or(text.len());
+            for tmp in self.code_search(&text[0..end], 5)? {
+                let len = match tmp.item.last() {
+                    Some(last) => area_prefix_len(&last.name, text),
+                    None => None,
+                };
+                if let Some(len) = len {
+                    return Ok(Some((tmp.item, len)));
+                }
+            }
+        }
+        Ok(None)
+    }
+    /// 解析中文地址文本中的省市区及详细地址,用于预填地址表单
+    /// 忽略空白及 code_info 输出的 "/" 分隔符
+    pub fn address_parse(&self, address: &str) -> JsonResult<AreaAddressParse> {
+        let text = address
+            .chars()
+            .filter(|e| !e.is_whitespace() && *e != '/')
+            .collect::<String>();
+        let mut rest = text.as_str();
+        let mut item = vec![];
+        let mut childs = self.code_childs("")?;
+        let top = childs
+            .iter()
+            .any(|e| area_prefix_len(&e.name, rest).is_some());
+        if !top {
+            if let Some((path, len)) = self.address_search_prefix(rest)? {
+                rest = &rest[len..];
+                item = path;
+                childs = match item.last() {
+                    Some(last) if !last.leaf => self.code_childs(&last.code)?,
+                    _ => vec![],
+                };
+            }
+        }
+        while !childs.is_empty() {
+            //仅有一个下级区域时(如直辖市辖区),允许地址中省略该级
+            let single = if childs.len() == 1 {
+                Some(childs[0].code.clone())
+            } else {
+                None
+            };
+            match area_prefix_match(childs, rest) {
+                Some((tmp, len)) => {
+                    rest = &rest[len..];
+                    childs = if tmp.leaf {
+                        vec![]
+                    } else {
+                        self.code_childs(&tmp.code)?
+                    };
+                    item.push(tmp);
+                }
+                None => {
+                    let Some(code) = single else {
+                        break;
+                    };
+                    match area_prefix_match(self.code_childs(&code)?, rest) {
+                        Some((tmp, len)) => {
+                            rest = &rest[len..];
+                            item.extend(self.code_find(&code)?.pop());
+                            childs = if tmp.leaf {
+                                vec![]
+                            } else {
+                                self.code_childs(&tmp.code)?
+                            };
+                            item.push(tmp);
+                        }
+                        None => break,
+                    }
+                }
+            }
+        }
+        let related = match item.last() {
+            Some(last) => self.code_related(&last.code)?,
+            None => vec![],
+        };
+        Ok(AreaAddressParse {
+            item,
+            detail: rest.trim_start_matches([',', '，']).to_string(),
+            related,
+        })
+    }
+}
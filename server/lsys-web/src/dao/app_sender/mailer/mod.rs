@@ -75,6 +75,118 @@ impl SenderMailer {
         .await
         .map(|_| ())
     }
+    pub async fn send_role_request_expire(
+        &self,
+        to: &str,
+        role_name: &str,
+        expire_time: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> JsonResult<()> {
+        let mut context = Context::new();
+        context.insert("role_name", role_name);
+        context.insert("expire_time", &expire_time);
+        self.send(
+            "rbac_role_request_expire",
+            to,
+            &context.into_json().to_string(),
+            None,
+            env_data,
+        )
+        .await
+        .map(|_| ())
+    }
+    //异常登录通知
+    pub async fn send_login_risk(
+        &self,
+        to: &str,
+        event_type: &str,
+        login_ip: &str,
+        login_city: &str,
+        login_time: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> JsonResult<()> {
+        let mut context = Context::new();
+        context.insert("event_type", event_type);
+        context.insert("login_ip", login_ip);
+        context.insert("login_city", login_city);
+        context.insert("login_time", &login_time);
+        self.send(
+            "account_login_risk_email",
+            to,
+            &context.into_json().to_string(),
+            None,
+            env_data,
+        )
+        .await
+        .map(|_| ())
+    }
+    //组织邀请
+    pub async fn send_org_invite(
+        &self,
+        to: &str,
+        org_name: &str,
+        invite_code: &str,
+        time_out: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> JsonResult<()> {
+        let mut context = Context::new();
+        context.insert("org_name", org_name);
+        context.insert("invite_code", invite_code);
+        context.insert("time_out", &time_out);
+        self.send(
+            "app_org_invite",
+            to,
+            &context.into_json().to_string(),
+            None,
+            env_data,
+        )
+        .await
+        .map(|_| ())
+    }
+    //邮件链接登录
+    pub async fn send_login_link(
+        &self,
+        to: &str,
+        login_token: &str,
+        wait_confirm: bool,
+        time_out: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> JsonResult<()> {
+        let mut context = Context::new();
+        context.insert("login_token", login_token);
+        context.insert("wait_confirm", &wait_confirm);
+        context.insert("time_out", &time_out);
+        self.send(
+            "valid_link_login_email",
+            to,
+            &context.into_json().to_string(),
+            None,
+            env_data,
+        )
+        .await
+        .map(|_| ())
+    }
+    //个人数据导出完成
+    pub async fn send_account_export(
+        &self,
+        to: &str,
+        download_token: &str,
+        expire_time: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> JsonResult<()> {
+        let mut context = Context::new();
+        context.insert("download_token", download_token);
+        context.insert("expire_time", &expire_time);
+        self.send(
+            "account_export_email",
+            to,
+            &context.into_json().to_string(),
+            None,
+            env_data,
+        )
+        .await
+        .map(|_| ())
+    }
     // 发送接口
     async fn send(
         &self,
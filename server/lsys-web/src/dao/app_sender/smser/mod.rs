@@ -183,6 +183,30 @@ impl SenderSmser {
         )
         .await
     }
+    //异常登录通知
+    pub async fn send_login_risk(
+        &self,
+        area: &str,
+        mobile: &str,
+        event_type: &str,
+        login_city: &str,
+        login_time: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> JsonResult<u64> {
+        let mut context = HashMap::new();
+        context.insert("event_type", event_type.to_owned());
+        context.insert("login_city", login_city.to_owned());
+        context.insert("login_time", login_time.to_string());
+        self.send(
+            "account_login_risk_mobile",
+            area,
+            mobile,
+            &json!(context).to_string(),
+            None,
+            env_data,
+        )
+        .await
+    }
     //记录短信查看日志
     pub async fn smser_message_body(
         &self,
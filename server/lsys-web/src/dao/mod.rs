@@ -17,8 +17,9 @@ pub use app_captcha::*;
 pub use app_sender::*;
 
 use lsys_user::dao::login::{
-    EmailCodeLoginReload, EmailLoginReload, ExternalLoginReload, MobileCodeLoginReload,
-    MobileLoginReload, NameLoginReload,
+    EmailCodeLoginReload, EmailLinkConfig, EmailLinkDao, EmailLinkLoginReload, EmailLoginReload,
+    ExternalLoginReload, LdapLoginReload, MobileCodeLoginReload, MobileLoginReload,
+    NameLoginReload,
 };
 pub use web_access::*;
 pub use web_account::*;
@@ -27,7 +28,10 @@ pub use web_app::*;
 pub use web_rbac::*;
 pub use web_setting::*;
 
-use lsys_access::dao::{AccessConfig, AccessDao, AccessLocalCacheClear};
+use lsys_access::dao::{
+    AccessConfig, AccessDao, AccessLocalCacheClear, AccessSessionTimeout,
+    AccessSessionTimeoutConfig,
+};
 use lsys_app::dao::{AppConfig, AppLocalCacheClear};
 // use lsys_app_notify::dao::{NotifyConfig, NotifyDao};
 use lsys_core::cache::{LocalCacheClear, LocalCacheClearItem};
@@ -38,13 +42,14 @@ use lsys_rbac::dao::RbacLocalCacheClear;
 use lsys_rbac::dao::{RbacConfig, RbacDao};
 use lsys_setting::dao::{SettingConfig, SettingDao, SettingLocalCacheClear};
 use lsys_user::dao::{
-    AccountConfig, AccountDao, AccountLocalCacheClear, AuthAccount, AuthAccountConfig, AuthCode,
-    UserAuthDao, UserDao,
+    AccountConfig, AccountDao, AccountLocalCacheClear, AccountSearchNotify, AuthAccount,
+    AuthAccountConfig, AuthCode, UserAuthDao, UserDao, ACCOUNT_TOKEN_LOGIN_TYPE,
 };
 
 use sqlx::{MySql, Pool};
 use std::sync::Arc;
 use std::vec;
+use tracing::warn;
 use tera::Tera;
 
 pub struct WebDao {
@@ -94,46 +99,126 @@ impl WebDao {
             )
             .await?,
         );
+        let mut access_config = AccessConfig::new(use_cache);
+        access_config.session_timeout = session_timeout_config(app_core.config.find(None));
         let access_dao = Arc::new(AccessDao::new(
             db.clone(),
             remote_notify.clone(),
-            AccessConfig::new(use_cache),
+            access_config,
         ));
 
+        let mut account_config = AccountConfig::new(use_cache);
+        if let Ok(hours) = app_core.config.find(None).get_int("user_export_download_hours") {
+            account_config.export.download_time = hours.max(1) as u64 * 3600;
+        }
+        if let Ok(days) = app_core.config.find(None).get_int("user_delete_grace_days") {
+            account_config.delete.grace_time = days.max(0) as u64 * 86400;
+        }
+        account_config.password_breached_dir = app_core
+            .config_path(app_core.config.find(None), "user_password_breached_dir")
+            .ok();
+        account_config.search_index_dir = app_core
+            .config_path(app_core.config.find(None), "user_search_index_dir")
+            .ok();
         let account_dao = Arc::new(AccountDao::new(
             db.clone(),
             redis.clone(),
             setting_dao.single.clone(),
             access_dao.clone(),
-            AccountConfig::new(use_cache),
+            account_config,
             remote_notify.clone(),
             change_logger.clone(),
         ));
+        tokio::spawn({
+            let account_search = account_dao.account_search.clone();
+            async move {
+                //账号变动时更新搜索索引
+                account_search.listen().await;
+            }
+        });
         let auth_dao = Arc::new(UserAuthDao::new(
             access_dao.clone(),
             vec![
                 Box::new(NameLoginReload::new(account_dao.clone())),
                 Box::new(EmailLoginReload::new(account_dao.clone())),
                 Box::new(EmailCodeLoginReload::new(account_dao.clone())),
+                Box::new(EmailLinkLoginReload::new(account_dao.clone())),
                 Box::new(MobileCodeLoginReload::new(account_dao.clone())),
                 Box::new(MobileLoginReload::new(account_dao.clone())),
                 Box::new(ExternalLoginReload::new(account_dao.clone())),
+                Box::new(LdapLoginReload::new(account_dao.clone())),
             ],
         ));
+        let mut auth_account_config = AuthAccountConfig::new(ip_city_db(&app_core));
+        if let Ok(speed) = app_core.config.find(None).get_int("login_travel_speed") {
+            auth_account_config.login_travel_speed = speed.max(0) as u64;
+        }
+        if let Ok(minutes) = app_core
+            .config
+            .find(None)
+            .get_int("login_impersonate_minutes")
+        {
+            auth_account_config.impersonate_time = minutes.max(1) as u64 * 60;
+        }
         let auth_account_dao = Arc::new(AuthAccount::new(
             account_dao.account_login_hostory.clone(),
+            account_dao.account_security.clone(),
+            account_dao.account_delete.clone(),
             access_dao.clone(),
-            AuthAccountConfig::new(None),
+            change_logger.clone(),
+            auth_account_config,
         ));
-        let auth_code_dao = Arc::new(AuthCode::new(access_dao.clone(), app_core.clone()));
+        let auth_code_dao = Arc::new(AuthCode::new(
+            access_dao.clone(),
+            account_dao.account_security.clone(),
+            change_logger.clone(),
+            app_core.clone(),
+        ));
+        let email_link_dao = Arc::new(EmailLinkDao::new(
+            redis.clone(),
+            app_core.clone(),
+            EmailLinkConfig::default(),
+        ));
+        tokio::spawn({
+            let email_link_dao = email_link_dao.clone();
+            async move {
+                //原设备等待邮件链接确认
+                email_link_dao.listen().await;
+            }
+        });
 
         let user_dao = Arc::new(UserDao::new(
             account_dao.clone(),
             auth_dao,
             auth_account_dao,
             auth_code_dao,
+            email_link_dao,
         ));
 
+        let mut app_config = AppConfig::new(
+            use_cache,
+            120,             //oauth Code有效期120秒
+            7 * 24 * 3600,   //TOKEN有效期7天
+            180 * 24 * 3600, //TOKEN有效期180天
+        );
+        //jwt 签名私钥使用 app_jwt_key 加密存储
+        app_config.jwt_key_config.master_key = app_core
+            .config
+            .find(None)
+            .get_string("app_jwt_key")
+            .unwrap_or_default();
+        if let Ok(alg) = app_core.config.find(None).get_string("app_jwt_alg") {
+            match alg.parse() {
+                Ok(alg) => app_config.jwt_key_config.alg = alg,
+                Err(err) => warn!("app_jwt_alg {} is invalid:{}", alg, err),
+            }
+        }
+        if let Ok(days) = app_core.config.find(None).get_int("app_jwt_rotate_days") {
+            app_config.jwt_key_config.rotate_time = days.max(0) as u64 * 86400;
+        }
+        if let Ok(days) = app_core.config.find(None).get_int("app_jwt_keep_days") {
+            app_config.jwt_key_config.keep_time = days.max(0) as u64 * 86400;
+        }
         let web_app = Arc::new(
             WebApp::new(
                 db.clone(),
@@ -142,12 +227,7 @@ impl WebDao {
                 access_dao.clone(),
                 remote_notify.clone(),
                 change_logger.clone(),
-                AppConfig::new(
-                    use_cache,
-                    120,             //oauth Code有效期120秒
-                    7 * 24 * 3600,   //TOKEN有效期7天
-                    180 * 24 * 3600, //TOKEN有效期180天
-                ),
+                app_config,
             )
             .await?,
         );
@@ -182,29 +262,99 @@ impl WebDao {
             .iter()
             .filter_map(|e| e.to_owned().into_int().map(|e| e as u64).ok())
             .collect::<Vec<u64>>();
+        let mut rbac_config = RbacConfig::new(use_cache);
+        rbac_config.audit_archive.keep_days = app_core
+            .config
+            .find(None)
+            .get_int("rbac_audit_keep_days")
+            .map(|e| e.max(0) as u64)
+            .unwrap_or(0);
+        rbac_config.audit_archive.archive_dir = app_core
+            .config_path(app_core.config.find(None), "rbac_audit_archive_dir")
+            .ok();
         let web_rbac = Arc::new(WebRbac::new(
             Arc::new(
                 RbacDao::new(
                     db.clone(),
+                    redis.clone(),
                     remote_notify.clone(),
-                    RbacConfig::new(use_cache),
+                    rbac_config,
                     change_logger.clone(),
                 )
                 .await?,
             ),
             root_user_id,
+            account_dao.account_token.clone(),
         ));
 
+        tokio::spawn({
+            let account_export = account_dao.account_export.clone();
+            let export_dir = app_core
+                .config_path(app_core.config.find(None), "user_export_dir")
+                .unwrap_or_else(|_| std::env::temp_dir().join("lsys-user-export"));
+            let export_builder = WebAccountExportBuilder::new(
+                access_dao.clone(),
+                user_dao.clone(),
+                web_app.app_dao.clone(),
+                change_logger.clone(),
+                app_sender.clone(),
+                export_dir,
+            );
+            let task_app_core = app_core.clone();
+            async move {
+                account_export
+                    .listen_export(task_app_core, Box::new(export_builder), None)
+                    .await
+            }
+        });
+
         let web_user = Arc::new(WebUser::new(
             db.clone(),
             user_dao,
             web_app.app_dao.clone(),
+            web_rbac.rbac_dao.clone(),
             app_sender.clone(),
             app_captcha.clone(),
             app_area.clone(),
             change_logger.clone(),
         ));
 
+        tokio::spawn({
+            let account_delete = account_dao.account_delete.clone();
+            let delete_executor =
+                WebAccountDeleteExecutor::new(web_user.account.clone(), fluent.locale(None));
+            let task_app_core = app_core.clone();
+            async move {
+                account_delete
+                    .listen_delete(task_app_core, Box::new(delete_executor), None)
+                    .await
+            }
+        });
+        tokio::spawn({
+            let role_dao = web_rbac.rbac_dao.role.clone();
+            let role_request_notify = WebRbacRoleRequestNotify::new(
+                access_dao.clone(),
+                account_dao.clone(),
+                app_sender.mailer.clone(),
+            );
+            let task_app_core = app_core.clone();
+            async move {
+                role_dao
+                    .listen_request_expire(task_app_core, Some(Box::new(role_request_notify)), None)
+                    .await
+            }
+        });
+        tokio::spawn({
+            let rbac_access = web_rbac.rbac_dao.access.clone();
+            let task_app_core = app_core.clone();
+            async move { rbac_access.listen_audit_archive(task_app_core, None).await }
+        });
+        tokio::spawn({
+            let jwt_key = web_app.app_dao.jwt_key.clone();
+            let task_app_core = app_core.clone();
+            async move { jwt_key.listen_rotate(task_app_core, None).await }
+        });
+
         let web_access = Arc::new(WebAccess::new(access_dao.clone()));
 
         let web_setting = Arc::new(WebSetting::new(setting_dao.clone(), db.clone()));
@@ -237,6 +387,11 @@ impl WebDao {
         remote_notify
             .push_run(Box::new(LocalCacheClear::new(cache_item)))
             .await;
+        remote_notify
+            .push_run(Box::new(AccountSearchNotify::new(
+                account_dao.account_search.clone(),
+            )))
+            .await;
 
         //远程任务后台任务
         tokio::spawn(async move {
@@ -305,3 +460,61 @@ impl WebDao {
         Some((format!("{}:{}", host, port), cert, key))
     }
 }
+
+//IP城市数据,未配置或加载失败时不解析登录城市
+fn ip_city_db(app_core: &AppCore) -> Option<ip2location::DB> {
+    let path = app_core
+        .config_path(app_core.config.find(None), "ip_city_db")
+        .ok()?;
+    match ip2location::DB::from_file(&path) {
+        Ok(db) => Some(db),
+        Err(err) => {
+            warn!("load ip city db {:?} fail:{:?}", path, err);
+            None
+        }
+    }
+}
+
+//登录超时配置,按登录类型及应用配置格式为 [空闲超时,最长有效时长]
+fn session_timeout_config(config: &config::Config) -> AccessSessionTimeoutConfig {
+    let get_time = |key: &str| config.get_int(key).map(|e| e.max(0) as u64).unwrap_or(0);
+    let parse_timeout = |val: config::Value| -> Option<AccessSessionTimeout> {
+        let tmp = val.into_array().ok()?;
+        let mut time = tmp
+            .into_iter()
+            .map(|e| e.into_int().unwrap_or(0).max(0) as u64);
+        Some(AccessSessionTimeout {
+            idle_time: time.next().unwrap_or(0),
+            absolute_time: time.next().unwrap_or(0),
+        })
+    };
+    let mut timeout_config = AccessSessionTimeoutConfig {
+        default: AccessSessionTimeout {
+            idle_time: get_time("session_idle_time"),
+            absolute_time: get_time("session_absolute_time"),
+        },
+        ..Default::default()
+    };
+    if let Ok(step_up_time) = config.get_int("session_step_up_time") {
+        timeout_config.step_up_time = step_up_time.max(0) as u64;
+    }
+    //个人访问令牌不能执行敏感操作
+    timeout_config
+        .step_up_deny_login_type
+        .push(ACCOUNT_TOKEN_LOGIN_TYPE.to_string());
+    if let Ok(table) = config.get_table("session_timeout_login_type") {
+        for (login_type, val) in table {
+            if let Some(timeout) = parse_timeout(val) {
+                timeout_config.login_type.insert(login_type, timeout);
+            }
+        }
+    }
+    if let Ok(table) = config.get_table("session_timeout_app") {
+        for (app_id, val) in table {
+            if let (Ok(app_id), Some(timeout)) = (app_id.parse::<u64>(), parse_timeout(val)) {
+                timeout_config.app.insert(app_id, timeout);
+            }
+        }
+    }
+    timeout_config
+}
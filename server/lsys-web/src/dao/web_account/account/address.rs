@@ -1,6 +1,7 @@
-use crate::common::{JsonData, JsonError, JsonResult};
+use crate::common::JsonResult;
+use crate::dao::AppArea;
 use lsys_access::dao::SessionBody;
-use lsys_core::{fluent_message, RequestEnv};
+use lsys_core::RequestEnv;
 use lsys_user::dao::AccountAddressParam;
 use lsys_user::model::AccountAddressModel;
 
@@ -26,19 +27,8 @@ impl WebUserAccount {
             .account_dao
             .session_account(session_body)
             .await?;
-        if param.code.trim().len() < 6 {
-            return Err(JsonError::JsonResponse(
-                JsonData::default().set_code(500).set_sub_code("bad_code"),
-                fluent_message!("address-miss-city"), // JsonResponse::message("your submit area miss city").set_code("bad_code")
-            ));
-        }
-        let area = self.area.code_related(param.code)?;
-        if area.is_empty() {
-            return Err(JsonError::JsonResponse(
-                JsonData::default().set_code(500).set_sub_code("bad_code"),
-                fluent_message!("address-bad-area"), // JsonResponse::message("your submit area miss city").set_code("bad_code")
-            ));
-        }
+        let area = self.area.code_verify(param.code, param.info)?;
+        let info = AppArea::code_info(&area);
 
         let id = self
             .user_dao
@@ -49,7 +39,7 @@ impl WebUserAccount {
                 &AccountAddressParam {
                     country_code: "CHN",
                     address_code: param.code,
-                    address_info: param.info,
+                    address_info: &info,
                     address_detail: param.detail,
                     name: param.name,
                     mobile: param.mobile,
@@ -69,19 +59,8 @@ impl WebUserAccount {
         session_body: &SessionBody,
         env_data: Option<&RequestEnv>,
     ) -> JsonResult<()> {
-        if param.code.trim().len() < 6 {
-            return Err(JsonError::JsonResponse(
-                JsonData::default().set_code(500).set_sub_code("bad_code"),
-                fluent_message!("address-miss-city"),
-            ));
-        }
-        let area = self.area.code_find(param.code)?;
-        if area.is_empty() {
-            return Err(JsonError::JsonResponse(
-                JsonData::default().set_code(500).set_sub_code("bad_code"),
-                fluent_message!("address-bad-area"),
-            ));
-        }
+        let area = self.area.code_verify(param.code, param.info)?;
+        let info = AppArea::code_info(&area);
 
         self.user_dao
             .account_dao
@@ -91,7 +70,7 @@ impl WebUserAccount {
                 &AccountAddressParam {
                     country_code: "CHN",
                     address_code: param.code,
-                    address_info: param.info,
+                    address_info: &info,
                     address_detail: param.detail,
                     name: param.name,
                     mobile: param.mobile,
@@ -104,3 +83,58 @@ impl WebUserAccount {
         Ok(())
     }
 }
+
+//地址区域修正结果
+#[derive(Default)]
+pub struct AddressNormalizeResult {
+    pub total: u64,   //检查地址数
+    pub change: u64,  //已修正地址数
+    pub invalid: u64, //区域编码失效且无法通过名称匹配的地址数
+}
+
+impl WebUserAccount {
+    //区域数据升级后修正已存储地址
+    //编码仍有效时按新数据更新名称路径,编码失效时按原名称路径重新解析编码
+    pub async fn user_address_normalize(
+        &self,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> JsonResult<AddressNormalizeResult> {
+        let address_dao = &self.user_dao.account_dao.account_address;
+        let mut out = AddressNormalizeResult::default();
+        let mut start_id = 0;
+        loop {
+            let list = address_dao.list_batch(start_id, 100).await?;
+            let Some(last) = list.last() else {
+                break;
+            };
+            start_id = last.id;
+            for address in list {
+                out.total += 1;
+                let (code, info) = match self.area.code_verify(&address.address_code, "") {
+                    Ok(item) => (address.address_code.clone(), AppArea::code_info(&item)),
+                    Err(_) => {
+                        let parse = self.area.address_parse(&address.address_info)?;
+                        match parse.item.last() {
+                            Some(last) if last.leaf && parse.detail.is_empty() => {
+                                (last.code.clone(), AppArea::code_info(&parse.item))
+                            }
+                            _ => {
+                                out.invalid += 1;
+                                continue;
+                            }
+                        }
+                    }
+                };
+                if code == address.address_code && info == address.address_info {
+                    continue;
+                }
+                address_dao
+                    .normalize_address(&address, &code, &info, op_user_id, env_data)
+                    .await?;
+                out.change += 1;
+            }
+        }
+        Ok(out)
+    }
+}
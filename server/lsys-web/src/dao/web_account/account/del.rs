@@ -1,29 +1,35 @@
 use crate::common::JsonResult;
 use lsys_access::dao::SessionBody;
-use lsys_core::RequestEnv;
-use lsys_user::model::{AccountInfoModelRef, AccountModel, AccountStatus};
+use lsys_core::{FluentBundle, IntoFluentMessage, RequestEnv};
+use lsys_user::dao::{AccountDeleteExecutor, AccountDeleteRecipient};
+use lsys_user::model::{AccountDeleteModel, AccountInfoModelRef, AccountModel, AccountStatus};
+use std::sync::Arc;
 
 use super::WebUserAccount;
 use lsys_core::model_option_set;
 
 impl WebUserAccount {
-    //删除当前登录用户
+    //当前登录用户申请注销,宽限期后删除
     pub async fn user_delete_from_session(
         &self,
         session: &SessionBody,
         env_data: Option<&RequestEnv>,
-    ) -> JsonResult<()> {
+    ) -> JsonResult<AccountDeleteModel> {
         let account = self.user_dao.account_dao.session_account(session).await?;
-        self.user_delete(&account, session, env_data).await?;
+        let data = self
+            .user_dao
+            .account_dao
+            .account_delete
+            .delete_apply(&account, session.user_id(), env_data)
+            .await?;
         self.user_dao.auth_dao.logout(session).await?;
-
-        Ok(())
+        Ok(data)
     }
     //删除用户
     pub async fn user_delete(
         &self,
         user: &AccountModel,
-        session_body: &SessionBody,
+        op_user_id: u64,
         env_data: Option<&RequestEnv>,
     ) -> JsonResult<()> {
         if AccountStatus::Delete.eq(user.status) {
@@ -41,7 +47,7 @@ impl WebUserAccount {
                 .user_dao
                 .account_dao
                 .account_email
-                .del_email(&email, session_body.user_id(), Some(&mut tran), env_data)
+                .del_email(&email, op_user_id, Some(&mut tran), env_data)
                 .await;
             if let Err(err) = res {
                 tran.rollback().await?;
@@ -59,7 +65,7 @@ impl WebUserAccount {
                 .user_dao
                 .account_dao
                 .account_mobile
-                .del_mobile(&mobile, session_body.user_id(), Some(&mut tran), env_data)
+                .del_mobile(&mobile, op_user_id, Some(&mut tran), env_data)
                 .await;
             if let Err(err) = res {
                 tran.rollback().await?;
@@ -77,7 +83,7 @@ impl WebUserAccount {
                 .user_dao
                 .account_dao
                 .account_external
-                .del_external(&external, session_body.user_id(), Some(&mut tran), env_data)
+                .del_external(&external, op_user_id, Some(&mut tran), env_data)
                 .await;
             if let Err(err) = res {
                 tran.rollback().await?;
@@ -92,13 +98,7 @@ impl WebUserAccount {
             .user_dao
             .account_dao
             .account_info
-            .set_info(
-                user,
-                &info_ref,
-                session_body.user_id(),
-                Some(&mut tran),
-                env_data,
-            )
+            .set_info(user, &info_ref, op_user_id, Some(&mut tran), env_data)
             .await;
         if let Err(err) = res {
             tran.rollback().await?;
@@ -108,7 +108,7 @@ impl WebUserAccount {
             .user_dao
             .account_dao
             .account_name
-            .remove_account_name(user, session_body.user_id(), Some(&mut tran), env_data)
+            .remove_account_name(user, op_user_id, Some(&mut tran), env_data)
             .await;
         if let Err(err) = res {
             tran.rollback().await?;
@@ -121,7 +121,7 @@ impl WebUserAccount {
             .del(
                 user,
                 Some("delete user"),
-                session_body.user_id(),
+                op_user_id,
                 Some(&mut tran),
                 env_data,
             )
@@ -131,6 +131,55 @@ impl WebUserAccount {
             return Err(err.into());
         }
         tran.commit().await?;
+        self.user_dao.account_dao.account.search_notify(user.id);
+        Ok(())
+    }
+}
+
+//宽限期结束后删除账号,并清空发送记录中的收件人
+pub struct WebAccountDeleteExecutor {
+    account: Arc<WebUserAccount>,
+    fluent: Arc<FluentBundle>,
+}
+
+impl WebAccountDeleteExecutor {
+    pub fn new(account: Arc<WebUserAccount>, fluent: Arc<FluentBundle>) -> Self {
+        Self { account, fluent }
+    }
+}
+
+#[async_trait::async_trait]
+impl AccountDeleteExecutor for WebAccountDeleteExecutor {
+    async fn delete(
+        &self,
+        record: &AccountDeleteModel,
+        account: &AccountModel,
+        recipient: &AccountDeleteRecipient,
+    ) -> Result<(), String> {
+        self.account
+            .user_delete(account, record.add_user_id, None)
+            .await
+            .map_err(|e| e.to_fluent_string(&self.fluent))?;
+        for email in recipient.email.iter() {
+            self.account
+                .sender
+                .mailer
+                .mailer_dao
+                .mail_record
+                .message_anonymize(email)
+                .await
+                .map_err(|e| e.to_fluent_message().default_format())?;
+        }
+        for (area, mobile) in recipient.mobile.iter() {
+            self.account
+                .sender
+                .smser
+                .smser_dao
+                .sms_record
+                .message_anonymize(area, mobile)
+                .await
+                .map_err(|e| e.to_fluent_message().default_format())?;
+        }
         Ok(())
     }
 }
@@ -112,6 +112,7 @@ impl WebUserAccount {
             return Err(err.into());
         }
         db.commit().await?;
+        self.user_dao.account_dao.account.search_notify(account.id);
         // let token = self
         //     .user_dao
         //     .auth_dao
@@ -0,0 +1,146 @@
+use crate::common::JsonResult;
+use lsys_core::RequestEnv;
+use lsys_rbac::dao::RoleExclusiveViolation;
+use lsys_user::dao::AccountMergePreview;
+use lsys_user::model::AccountModel;
+use serde::Serialize;
+
+use super::WebUserAccount;
+
+//账号合并数据,除账号自身数据外包含应用、组织成员及角色的转移数量
+#[derive(Serialize)]
+pub struct AccountMergeData {
+    #[serde(flatten)]
+    pub account: AccountMergePreview,
+    pub app_move: i64,
+    pub org_member_move: i64,
+    pub role_user_move: i64,
+    //合并后违反角色互斥约束的角色,存在时无法合并
+    pub role_exclusive_conflict: Vec<RoleExclusiveViolation>,
+}
+
+impl WebUserAccount {
+    //合并预览,不修改数据
+    pub async fn account_merge_preview(
+        &self,
+        target: &AccountModel,
+        source: &AccountModel,
+    ) -> JsonResult<AccountMergeData> {
+        let account = self
+            .user_dao
+            .account_dao
+            .account_merge
+            .merge_preview(target, source)
+            .await?;
+        let account_cache = self.user_dao.account_dao.account.cache();
+        let target_user = account_cache.get_user(target).await?;
+        let source_user = account_cache.get_user(source).await?;
+        Ok(AccountMergeData {
+            account,
+            app_move: self
+                .app_dao
+                .org
+                .user_app_transfer_count(source_user.id)
+                .await?,
+            org_member_move: self.app_dao.org.user_org_count(source_user.id).await?,
+            role_user_move: self.rbac_dao.role.user_role_count(source_user.id).await?,
+            role_exclusive_conflict: self
+                .rbac_dao
+                .role
+                .user_transfer_conflict(source_user.id, target_user.id)
+                .await?,
+        })
+    }
+    //合并账号: source 的数据移到 target 后删除 source
+    //返回被合并的访问用户ID,调用方需下线该用户的登录
+    pub async fn account_merge(
+        &self,
+        target: &AccountModel,
+        source: &AccountModel,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> JsonResult<(u64, AccountMergeData)> {
+        let account_cache = self.user_dao.account_dao.account.cache();
+        let target_user = account_cache.get_user(target).await?;
+        let source_user = account_cache.get_user(source).await?;
+        let mut tran = self.db.begin().await?;
+        let account = match self
+            .user_dao
+            .account_dao
+            .account_merge
+            .merge(target, source, &mut tran)
+            .await
+        {
+            Ok(data) => data,
+            Err(err) => {
+                tran.rollback().await?;
+                return Err(err.into());
+            }
+        };
+        let app_move = match self
+            .app_dao
+            .org
+            .user_app_transfer(source_user.id, target_user.id, op_user_id, &mut tran)
+            .await
+        {
+            Ok(data) => data,
+            Err(err) => {
+                tran.rollback().await?;
+                return Err(err.into());
+            }
+        };
+        let org_member_move = match self
+            .app_dao
+            .org
+            .user_member_transfer(source_user.id, target_user.id, op_user_id, &mut tran)
+            .await
+        {
+            Ok(data) => data,
+            Err(err) => {
+                tran.rollback().await?;
+                return Err(err.into());
+            }
+        };
+        let role_user_move = match self
+            .rbac_dao
+            .role
+            .user_transfer(source_user.id, target_user.id, op_user_id, &mut tran)
+            .await
+        {
+            Ok(data) => data,
+            Err(err) => {
+                tran.rollback().await?;
+                return Err(err.into());
+            }
+        };
+        tran.commit().await?;
+        //事务提交后再清理缓存及记录日志
+        self.user_dao
+            .account_dao
+            .account_merge
+            .merge_finish(&account, op_user_id, env_data)
+            .await;
+        self.app_dao
+            .org
+            .user_app_transfer_finish(&app_move, op_user_id, env_data)
+            .await;
+        self.app_dao
+            .org
+            .user_member_transfer_finish(&org_member_move, op_user_id, env_data)
+            .await;
+        self.rbac_dao
+            .role
+            .user_transfer_finish(&role_user_move, op_user_id, env_data)
+            .await?;
+        Ok((
+            source_user.id,
+            AccountMergeData {
+                account: account.preview(),
+                app_move: app_move.total() as i64,
+                org_member_move: org_member_move.total() as i64,
+                role_user_move: role_user_move.total() as i64,
+                role_exclusive_conflict: vec![],
+            },
+        ))
+    }
+}
@@ -5,12 +5,17 @@ mod detail;
 mod email;
 mod info;
 mod list;
+mod merge;
 mod mobile;
 
 pub use address::*;
+pub use del::*;
 pub use detail::*;
 pub use info::*;
+pub use merge::*;
 use lsys_access::dao::UserInfo;
+use lsys_app::dao::AppDao;
+use lsys_rbac::dao::RbacDao;
 use lsys_user::dao::UserDao;
 use sqlx::Pool;
 use std::sync::Arc;
@@ -22,6 +27,8 @@ use crate::{
 
 pub struct WebUserAccount {
     user_dao: Arc<UserDao>,
+    app_dao: Arc<AppDao>,
+    rbac_dao: Arc<RbacDao>,
     captcha: Arc<AppCaptcha>,
     sender: Arc<AppSender>,
     area: Arc<AppArea>,
@@ -31,6 +38,8 @@ pub struct WebUserAccount {
 impl WebUserAccount {
     pub fn new(
         user_dao: Arc<UserDao>,
+        app_dao: Arc<AppDao>,
+        rbac_dao: Arc<RbacDao>,
         captcha: Arc<AppCaptcha>,
         sender: Arc<AppSender>,
         area: Arc<AppArea>,
@@ -38,6 +47,8 @@ impl WebUserAccount {
     ) -> Self {
         WebUserAccount {
             user_dao,
+            app_dao,
+            rbac_dao,
             captcha,
             sender,
             area,
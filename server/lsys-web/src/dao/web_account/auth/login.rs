@@ -12,11 +12,15 @@ use lsys_user::dao::{
 };
 use lsys_user::dao::{
     login::{
-        EmailCodeLoginData, EmailCodeLoginMeta, EmailLoginData, EmailLoginMeta, ExternalLoginData,
-        ExternalLoginMeta, MobileCodeLoginData, MobileCodeLoginMeta, MobileLoginData,
-        MobileLoginMeta, NameLoginData, NameLoginMeta,
+        EmailCodeLoginData, EmailCodeLoginMeta, EmailLinkLoginData, EmailLinkLoginMeta,
+        EmailLoginData, EmailLoginMeta, ExternalLoginData, ExternalLoginMeta, LdapLoginData,
+        LdapLoginMeta, MobileCodeLoginData, MobileCodeLoginMeta, MobileLoginData, MobileLoginMeta,
+        NameLoginData, NameLoginMeta,
     },
-    CODE_LOGIN_TYPE,
+    ACCOUNT_IMPERSONATE_LOGIN_TYPE, CODE_LOGIN_TYPE,
+};
+use lsys_user::model::{
+    AccountEmailStatus, AccountMobileStatus, AccountModel, AccountSecurityEventModel,
 };
 #[derive(Debug, Clone, Serialize)]
 pub struct ShowUserAppData {
@@ -41,6 +45,7 @@ pub struct ShowUserAuthData {
     pub account_id: u64,
     pub time_out: u64,
     pub login_time: u64,
+    pub impersonate_user_id: u64, //模拟登录时为实际操作的用户ID
 }
 impl WebUserAuth {
     //成功登录，统一返回用户登录信息
@@ -58,6 +63,12 @@ impl WebUserAuth {
                     .await?
                     .to_json()
             )
+        } else if auth_data.session().login_type == EmailLinkLoginMeta::login_type() {
+            json!(
+                EmailLinkLoginData::from(&self.user_dao.account_dao, auth_data)
+                    .await?
+                    .to_json()
+            )
         } else if auth_data.session().login_type == EmailLoginMeta::login_type() {
             json!(EmailLoginData::from(&self.user_dao.account_dao, auth_data)
                 .await?
@@ -78,7 +89,13 @@ impl WebUserAuth {
                     .await?
                     .to_json()
             )
-        } else if auth_data.session().login_type == CODE_LOGIN_TYPE {
+        } else if auth_data.session().login_type == LdapLoginMeta::login_type() {
+            json!(LdapLoginData::from(&self.user_dao.account_dao, auth_data)
+                .await?
+                .to_json())
+        } else if auth_data.session().login_type == CODE_LOGIN_TYPE
+            || auth_data.session().login_type == ACCOUNT_IMPERSONATE_LOGIN_TYPE
+        {
             json!({})
         } else {
             return Err(JsonError::Message(fluent_message!("bad-session-data")));
@@ -119,25 +136,30 @@ impl WebUserAuth {
                 .unwrap_or(true),
             time_out,
             login_time,
+            impersonate_user_id: auth_data.impersonate_user_id().unwrap_or_default(),
         })
     }
 }
 
 impl WebUserAuth {
-    //内部账号登录
-    pub async fn user_login<TO: AccountLoginParam>(
-        &self,
-        param: &TO,
-        code: Option<&CaptchaParam>,
-        env_data: Option<&RequestEnv>,
-    ) -> JsonResult<SessionBody> {
-        let lenv = AccountLoginEnv {
+    fn login_env(env_data: Option<&RequestEnv>) -> AccountLoginEnv {
+        AccountLoginEnv {
             login_ip: env_data
                 .map(|e| e.request_ip.as_ref().map(|e| e.parse::<IpAddr>().ok()))
                 .unwrap_or_default()
                 .unwrap_or_default(),
-        };
-        let res = self.user_dao.auth_account_dao.check(param, &lenv).await;
+            device_id: env_data.and_then(|e| e.device_id.to_owned()),
+            user_agent: env_data.and_then(|e| e.request_user_agent.to_owned()),
+        }
+    }
+    //登录失败锁定及验证码检测
+    async fn login_limit_check<TO: AccountLoginParam>(
+        &self,
+        param: &TO,
+        code: Option<&CaptchaParam>,
+        lenv: &AccountLoginEnv,
+    ) -> JsonResult<()> {
+        let res = self.user_dao.auth_account_dao.check(param, lenv).await;
         if let Err(UserAuthError::CheckCaptchaNeed(_)) = &res {
             if let Some(captcha_code) = code {
                 let tres = self
@@ -164,9 +186,137 @@ impl WebUserAuth {
         } else {
             res?
         }
-        let token = self.user_dao.auth_account_dao.login(param, lenv).await?;
+        Ok(())
+    }
+    //内部账号登录
+    pub async fn user_login<TO: AccountLoginParam>(
+        &self,
+        param: &TO,
+        code: Option<&CaptchaParam>,
+        env_data: Option<&RequestEnv>,
+    ) -> JsonResult<SessionBody> {
+        let lenv = Self::login_env(env_data);
+        self.login_limit_check(param, code, &lenv).await?;
+        let (token, event) = self.user_dao.auth_account_dao.login(param, lenv).await?;
+        if let Some(event) = event {
+            self.login_risk_notify(&event, env_data).await;
+        }
         Ok(token)
     }
+    //已登录账号重新验证身份,与登录使用相同的失败锁定及验证码限制
+    pub async fn user_reauth<TO: AccountLoginParam>(
+        &self,
+        param: &TO,
+        code: Option<&CaptchaParam>,
+        session_body: &SessionBody,
+        env_data: Option<&RequestEnv>,
+    ) -> JsonResult<SessionBody> {
+        let lenv = Self::login_env(env_data);
+        self.login_limit_check(param, code, &lenv).await?;
+        Ok(self
+            .user_dao
+            .auth_account_dao
+            .reauth(param, &lenv, session_body)
+            .await?)
+    }
+    //管理员模拟指定账号登录
+    pub async fn user_impersonate(
+        &self,
+        account: &AccountModel,
+        op_user_id: u64,
+        env_data: Option<&RequestEnv>,
+    ) -> JsonResult<SessionBody> {
+        let lenv = Self::login_env(env_data);
+        Ok(self
+            .user_dao
+            .auth_account_dao
+            .impersonate(account, op_user_id, &lenv, env_data)
+            .await?)
+    }
+    //异常登录时通知用户已验证的邮箱及手机
+    async fn login_risk_notify(
+        &self,
+        event: &AccountSecurityEventModel,
+        env_data: Option<&RequestEnv>,
+    ) {
+        let account_dao = &self.user_dao.account_dao;
+        match account_dao
+            .account_email
+            .cache()
+            .find_by_account_id_vec(event.account_id)
+            .await
+        {
+            Ok(emails) => {
+                for email in emails
+                    .iter()
+                    .filter(|e| AccountEmailStatus::Valid.eq(e.status))
+                {
+                    if self
+                        .sender
+                        .mailer
+                        .send_login_risk(
+                            &email.email,
+                            &event.event_type,
+                            &event.login_ip,
+                            &event.login_city,
+                            event.add_time,
+                            env_data,
+                        )
+                        .await
+                        .is_err()
+                    {
+                        tracing::warn!(
+                            "login risk notify,send mail fail:{} to {}",
+                            event.id,
+                            email.email
+                        );
+                    }
+                }
+            }
+            Err(err) => tracing::warn!(
+                "login risk notify,find email fail:{}",
+                err.to_fluent_message().default_format()
+            ),
+        }
+        match account_dao
+            .account_mobile
+            .cache()
+            .find_by_account_id_vec(event.account_id)
+            .await
+        {
+            Ok(mobiles) => {
+                for mobile in mobiles
+                    .iter()
+                    .filter(|e| AccountMobileStatus::Valid.eq(e.status))
+                {
+                    if self
+                        .sender
+                        .smser
+                        .send_login_risk(
+                            &mobile.area_code,
+                            &mobile.mobile,
+                            &event.event_type,
+                            &event.login_city,
+                            event.add_time,
+                            env_data,
+                        )
+                        .await
+                        .is_err()
+                    {
+                        tracing::warn!(
+                            "login risk notify,send sms fail:{} to {}",
+                            event.id,
+                            mobile.mobile
+                        );
+                    }
+                }
+            }
+            Err(err) => tracing::warn!(
+                "login risk notify,find mobile fail:{}",
+                err.to_fluent_message().default_format()
+            ),
+        }
+    }
     //通过APP code登录
     // 由  self.user_dao.auth_code_dao.code_login 产生 login_code
     pub async fn app_code_login(
@@ -247,8 +397,10 @@ impl WebUserAuth {
                 .map(|e| e.request_ip.as_ref().map(|e| e.parse::<IpAddr>().ok()))
                 .unwrap_or_default()
                 .unwrap_or_default(),
+            device_id: env_data.and_then(|e| e.device_id.to_owned()),
+            user_agent: env_data.and_then(|e| e.request_user_agent.to_owned()),
         };
-        let session_body = self
+        let (session_body, event) = self
             .user_dao
             .auth_account_dao
             .login(
@@ -260,6 +412,9 @@ impl WebUserAuth {
                 login_env,
             )
             .await?;
+        if let Some(event) = event {
+            self.login_risk_notify(&event, env_data).await;
+        }
         Ok(session_body)
     }
 }
@@ -5,7 +5,9 @@ use crate::dao::{OauthCallbackParam, OauthLogin, OauthLoginData, OauthLoginParam
 use lsys_core::model_option_set;
 use lsys_core::{fluent_message, RequestEnv};
 use lsys_user::dao::AccountError;
-use lsys_user::model::{AccountExternalModel, AccountExternalStatus, AccountInfoModelRef};
+use lsys_user::model::{
+    AccountEmailStatus, AccountExternalModel, AccountExternalStatus, AccountInfoModelRef,
+};
 use serde::Serialize;
 
 impl WebUserAuth {
@@ -68,6 +70,13 @@ impl WebUserAuth {
         };
         let user_ext = match ext_op {
             None => {
+                //首次登录自动注册,同样受仅邀请、邮箱域名及审核限制
+                let permit = self
+                    .user_dao
+                    .account_dao
+                    .account_register
+                    .register_check(None, data.external_email.as_deref())
+                    .await?;
                 let reg_from = format!("oauth-{}", data.external_type);
                 let reg_op = env_data
                     .map(|e| e.request_id.as_ref().map(|e| e.to_string()))
@@ -80,14 +89,31 @@ impl WebUserAuth {
                 if let Some(ref img) = data.external_pic {
                     info.headimg = Some(img);
                 }
+                //外部确认过的邮箱未被使用时随注册绑定
+                let email = match data.external_email {
+                    Some(ref email) => match self
+                        .user_dao
+                        .account_dao
+                        .account_email
+                        .find_by_last_email(email)
+                        .await
+                    {
+                        Ok(_) => None,
+                        Err(AccountError::Sqlx(sqlx::Error::RowNotFound)) => {
+                            Some((email.as_str(), AccountEmailStatus::Valid))
+                        }
+                        Err(err) => return Err(err.into()),
+                    },
+                    None => None,
+                };
                 let user = self
                     .reg_user(
                         &AccountRegData {
-                            status_enable: true,
+                            status_enable: !permit.need_approve,
                             nikename: data.external_nikename.as_str(),
                             passwrod: None,
                             name: None,
-                            email: None,
+                            email,
                             mobile: None,
                             external: Some((
                                 data.config_name.as_str(),
@@ -96,6 +122,7 @@ impl WebUserAuth {
                                 data.external_name.as_str(),
                             )),
                             info: Some(info),
+                            register: Some((&permit, &reg_from)),
                         },
                         op_user_id,
                         env_data,
@@ -1,7 +1,7 @@
 //用户注册的封装
 use lsys_core::RequestEnv;
 use lsys_user::{
-    dao::AccountResult,
+    dao::{AccountRegisterPermit, AccountResult},
     model::{AccountEmailStatus, AccountInfoModelRef, AccountMobileStatus, AccountModel},
 };
 
@@ -21,6 +21,7 @@ pub struct AccountRegData<'t> {
     pub mobile: Option<(&'t str, &'t str, AccountMobileStatus)>,
     pub external: Option<(&'t str, &'t str, &'t str, &'t str)>,
     pub info: Option<AccountInfoModelRef<'t>>,
+    pub register: Option<(&'t AccountRegisterPermit, &'t str)>, //自助注册检测结果及注册方式
 }
 
 impl WebUserAuth {
@@ -31,6 +32,21 @@ impl WebUserAuth {
         op_user_id: u64,
         env_data: Option<&RequestEnv>,
     ) -> AccountResult<AccountModel> {
+        if let Some(pw) = reg_data.passwrod {
+            //登录名及邮箱此时尚未绑定,需单独传入检测
+            let mut account_names = vec![];
+            if let Some(name) = reg_data.name {
+                account_names.push(name);
+            }
+            if let Some((email, _)) = reg_data.email {
+                account_names.push(email);
+            }
+            self.user_dao
+                .account_dao
+                .account_password
+                .policy_check(0, pw, &account_names)
+                .await?;
+        }
         let mut tran = self.db.begin().await?;
         let user = match self
             .user_dao
@@ -126,6 +142,18 @@ impl WebUserAuth {
                 return Err(err);
             }
         }
+        if let Some((permit, reg_from)) = reg_data.register {
+            let res = self
+                .user_dao
+                .account_dao
+                .account_register
+                .register_add(&user, permit, reg_from, &mut tran)
+                .await;
+            if let Err(err) = res {
+                tran.rollback().await?;
+                return Err(err);
+            }
+        }
         if let Some(ref info_ref) = reg_data.info {
             let res = self
                 .user_dao
@@ -139,6 +167,7 @@ impl WebUserAuth {
             }
         }
         tran.commit().await?;
+        self.user_dao.account_dao.account.search_notify(user.id);
         Ok(user)
     }
 }
@@ -147,6 +176,7 @@ pub struct RegFromNameData<'t> {
     pub nikename: Option<&'t str>,
     pub name: &'t str,
     pub password: &'t str,
+    pub invite_code: Option<&'t str>,
 }
 impl WebUserAuth {
     // 通过用户名注册
@@ -155,7 +185,13 @@ impl WebUserAuth {
         param: &RegFromNameData<'_>,
         op_user_id: u64,
         env_data: Option<&RequestEnv>,
-    ) -> JsonResult<u64> {
+    ) -> JsonResult<(u64, bool)> {
+        let permit = self
+            .user_dao
+            .account_dao
+            .account_register
+            .register_check(param.invite_code, None)
+            .await?;
         let reg_ip = env_data
             .map(|e| e.request_ip.clone().unwrap_or_default())
             .unwrap_or_default();
@@ -165,7 +201,7 @@ impl WebUserAuth {
         let user = self
             .reg_user(
                 &AccountRegData {
-                    status_enable: true,
+                    status_enable: !permit.need_approve,
                     nikename: param.nikename.unwrap_or(param.name),
                     passwrod: Some(param.password),
                     name: Some(param.name),
@@ -173,12 +209,13 @@ impl WebUserAuth {
                     mobile: None,
                     external: None,
                     info: Some(info),
+                    register: Some((&permit, "name")),
                 },
                 op_user_id,
                 env_data,
             )
             .await?;
-        Ok(user.id)
+        Ok((user.id, permit.need_approve))
     }
 }
 pub struct RegSendCodeFromMobileData<'t> {
@@ -297,6 +334,7 @@ pub struct RegFromEmailData<'t> {
     pub code: &'t str,
     pub password: &'t str,
     pub nikename: &'t str,
+    pub invite_code: Option<&'t str>,
 }
 impl WebUserAuth {
     // 通过邮件验证码注册
@@ -305,12 +343,18 @@ impl WebUserAuth {
         param: &RegFromEmailData<'_>,
         op_user_id: u64,
         env_data: Option<&RequestEnv>,
-    ) -> JsonResult<u64> {
+    ) -> JsonResult<(u64, bool)> {
         self.user_dao
             .account_dao
             .account_email
             .valid_code_check(param.code, 0, param.email)
             .await?;
+        let permit = self
+            .user_dao
+            .account_dao
+            .account_register
+            .register_check(param.invite_code, Some(param.email))
+            .await?;
         let reg_ip = env_data
             .map(|e| e.request_ip.clone().unwrap_or_default())
             .unwrap_or_default();
@@ -320,7 +364,7 @@ impl WebUserAuth {
         let user = self
             .reg_user(
                 &AccountRegData {
-                    status_enable: true,
+                    status_enable: !permit.need_approve,
                     nikename: param.nikename,
                     passwrod: Some(param.password),
                     name: None,
@@ -328,6 +372,7 @@ impl WebUserAuth {
                     mobile: None,
                     external: None,
                     info: Some(info),
+                    register: Some((&permit, "email")),
                 },
                 op_user_id,
                 env_data,
@@ -339,7 +384,7 @@ impl WebUserAuth {
             .account_email
             .valid_code_clear(0, param.email)
             .await;
-        Ok(user.id)
+        Ok((user.id, permit.need_approve))
     }
 }
 pub struct RegFromMobileData<'t> {
@@ -348,6 +393,7 @@ pub struct RegFromMobileData<'t> {
     pub code: &'t str,
     pub password: &'t str,
     pub nikename: &'t str,
+    pub invite_code: Option<&'t str>,
 }
 impl WebUserAuth {
     // 通过短信验证码注册
@@ -356,12 +402,18 @@ impl WebUserAuth {
         param: &RegFromMobileData<'_>,
         op_user_id: u64,
         env_data: Option<&RequestEnv>,
-    ) -> JsonResult<u64> {
+    ) -> JsonResult<(u64, bool)> {
         self.user_dao
             .account_dao
             .account_mobile
             .valid_code_check(param.code, param.area_code, param.mobile)
             .await?;
+        let permit = self
+            .user_dao
+            .account_dao
+            .account_register
+            .register_check(param.invite_code, None)
+            .await?;
         let reg_ip = env_data
             .map(|e| e.request_ip.clone().unwrap_or_default())
             .unwrap_or_default();
@@ -371,7 +423,7 @@ impl WebUserAuth {
         let user = self
             .reg_user(
                 &AccountRegData {
-                    status_enable: true,
+                    status_enable: !permit.need_approve,
                     nikename: param.nikename,
                     passwrod: Some(param.password),
                     name: None,
@@ -379,6 +431,7 @@ impl WebUserAuth {
                     mobile: Some((param.area_code, param.mobile, AccountMobileStatus::Valid)),
                     external: None,
                     info: Some(info),
+                    register: Some((&permit, "mobile")),
                 },
                 op_user_id,
                 env_data,
@@ -390,6 +443,6 @@ impl WebUserAuth {
             .account_mobile
             .valid_code_clear(param.area_code, param.mobile)
             .await;
-        Ok(user.id)
+        Ok((user.id, permit.need_approve))
     }
 }
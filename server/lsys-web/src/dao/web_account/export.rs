@@ -0,0 +1,420 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use lsys_access::dao::{AccessDao, SessionDataParam};
+use lsys_app::dao::AppDao;
+use lsys_core::IntoFluentMessage;
+use lsys_logger::dao::ChangeLoggerDao;
+use lsys_user::dao::{AccountError, AccountExportBuilder, AccountExportFile, UserDao};
+use lsys_user::model::{AccountEmailStatus, AccountExportModel, AccountModel};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tracing::warn;
+
+use crate::dao::AppSender;
+
+fn export_err(err: impl IntoFluentMessage) -> String {
+    err.to_fluent_message().default_format()
+}
+
+//不存在的记录导出为空
+fn export_option<T>(res: Result<T, AccountError>) -> Result<Option<T>, String> {
+    match res {
+        Ok(data) => Ok(Some(data)),
+        Err(AccountError::Sqlx(sqlx::Error::RowNotFound)) => Ok(None),
+        Err(err) => Err(export_err(err)),
+    }
+}
+
+fn export_json(data: &Value) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(data).map_err(|e| e.to_string())
+}
+
+fn export_csv<T: Serialize>(data: &[T]) -> Result<Vec<u8>, String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for item in data {
+        writer.serialize(item).map_err(|e| e.to_string())?;
+    }
+    writer.into_inner().map_err(|e| e.to_string())
+}
+
+//个人数据导出: 收集账号在各模块的数据,打包为 zip 文件
+//敏感凭据(密码、令牌、第三方授权token、消息模板变量)不导出
+pub struct WebAccountExportBuilder {
+    access_dao: Arc<AccessDao>,
+    user_dao: Arc<UserDao>,
+    app_dao: Arc<AppDao>,
+    logger: Arc<ChangeLoggerDao>,
+    sender: Arc<AppSender>,
+    export_dir: PathBuf,
+}
+
+impl WebAccountExportBuilder {
+    pub fn new(
+        access_dao: Arc<AccessDao>,
+        user_dao: Arc<UserDao>,
+        app_dao: Arc<AppDao>,
+        logger: Arc<ChangeLoggerDao>,
+        sender: Arc<AppSender>,
+        export_dir: PathBuf,
+    ) -> Self {
+        Self {
+            access_dao,
+            user_dao,
+            app_dao,
+            logger,
+            sender,
+            export_dir,
+        }
+    }
+    //账号资料
+    async fn account_data(&self, account: &AccountModel) -> Result<Vec<(String, Vec<u8>)>, String> {
+        let account_dao = &self.user_dao.account_dao;
+        let info = export_option(
+            account_dao
+                .account_info
+                .cache()
+                .find_by_account_id(&account.id)
+                .await,
+        )?;
+        let name = export_option(
+            account_dao
+                .account_name
+                .cache()
+                .find_by_account_id(&account.id)
+                .await,
+        )?;
+        let email = account_dao
+            .account_email
+            .cache()
+            .find_by_account_id_vec(account.id)
+            .await
+            .map_err(export_err)?;
+        let mobile = account_dao
+            .account_mobile
+            .cache()
+            .find_by_account_id_vec(account.id)
+            .await
+            .map_err(export_err)?;
+        let address = account_dao
+            .account_address
+            .cache()
+            .find_by_account_id_vec(&account.id)
+            .await
+            .map_err(export_err)?;
+        let external = account_dao
+            .account_external
+            .cache()
+            .find_by_account_id_vec(account.id)
+            .await
+            .map_err(export_err)?
+            .into_iter()
+            .map(|e| {
+                json!({
+                    "id": e.id,
+                    "config_name": e.config_name,
+                    "external_type": e.external_type,
+                    "external_id": e.external_id,
+                    "external_name": e.external_name,
+                    "external_nikename": e.external_nikename,
+                    "external_gender": e.external_gender,
+                    "external_link": e.external_link,
+                    "external_pic": e.external_pic,
+                    "status": e.status,
+                    "change_time": e.change_time,
+                })
+            })
+            .collect::<Vec<_>>();
+        let device = account_dao
+            .account_security
+            .device_data(account.id)
+            .await
+            .map_err(export_err)?;
+        let security_event = account_dao
+            .account_security
+            .event_data(account.id, None, None)
+            .await
+            .map_err(export_err)?
+            .0;
+        let token = account_dao
+            .account_token
+            .list_data(account.id, None)
+            .await
+            .map_err(export_err)?
+            .0
+            .into_iter()
+            .map(|e| {
+                json!({
+                    "id": e.id,
+                    "token_name": e.token_name,
+                    "token_scope": e.token_scope,
+                    "expire_time": e.expire_time,
+                    "last_used_time": e.last_used_time,
+                    "last_used_ip": e.last_used_ip,
+                    "add_time": e.add_time,
+                })
+            })
+            .collect::<Vec<_>>();
+        Ok(vec![
+            (
+                "account.json".to_string(),
+                export_json(&json!({
+                    "account": {
+                        "id": account.id,
+                        "nickname": account.nickname,
+                        "status": account.status,
+                        "add_time": account.add_time,
+                        "confirm_time": account.confirm_time,
+                        "change_time": account.change_time,
+                    },
+                    "username": name.map(|e| e.username),
+                    "info": info,
+                }))?,
+            ),
+            ("email.json".to_string(), export_json(&json!(email))?),
+            ("mobile.json".to_string(), export_json(&json!(mobile))?),
+            ("address.json".to_string(), export_json(&json!(address))?),
+            ("external.json".to_string(), export_json(&json!(external))?),
+            (
+                "login_device.json".to_string(),
+                export_json(&json!(device))?,
+            ),
+            (
+                "security_event.json".to_string(),
+                export_json(&json!(security_event))?,
+            ),
+            (
+                "personal_token.json".to_string(),
+                export_json(&json!(token))?,
+            ),
+        ])
+    }
+    //登录及操作记录
+    async fn log_data(&self, account: &AccountModel) -> Result<Vec<(String, Vec<u8>)>, String> {
+        let login_history = self
+            .user_dao
+            .account_dao
+            .account_login_hostory
+            .history_data(Some(account.id), None, None, None, None, None)
+            .await
+            .map_err(export_err)?
+            .0;
+        let change_log = self
+            .logger
+            .subject_data(&UserDao::log_subjects(account.id), None)
+            .await
+            .map_err(export_err)?
+            .0;
+        Ok(vec![
+            ("login_history.csv".to_string(), export_csv(&login_history)?),
+            ("change_log.csv".to_string(), export_csv(&change_log)?),
+        ])
+    }
+    //OAUTH 授权登录
+    async fn oauth_data(&self, account: &AccountModel) -> Result<Vec<(String, Vec<u8>)>, String> {
+        let user = self
+            .user_dao
+            .account_dao
+            .account
+            .cache()
+            .get_user(account)
+            .await
+            .map_err(export_err)?;
+        let session = self
+            .access_dao
+            .user
+            .session_data(
+                &SessionDataParam {
+                    app_id: Some(user.app_id),
+                    oauth_app_id: None,
+                    user_id: Some(user.id),
+                    is_enable: None,
+                },
+                None,
+            )
+            .await
+            .map_err(export_err)?
+            .0;
+        let mut grant = vec![];
+        for item in session.into_iter().filter(|e| e.oauth_app_id > 0) {
+            let app = self
+                .app_dao
+                .app
+                .cache()
+                .find_by_id(item.oauth_app_id)
+                .await
+                .ok();
+            grant.push(json!({
+                "app_id": item.oauth_app_id,
+                "app_name": app.as_ref().map(|e| e.name.as_str()),
+                "client_id": app.as_ref().map(|e| e.client_id.as_str()),
+                "login_ip": item.login_ip,
+                "device_name": item.device_name,
+                "status": item.status,
+                "add_time": item.add_time,
+                "expire_time": item.expire_time,
+                "logout_time": item.logout_time,
+                "last_time": item.last_time,
+            }));
+        }
+        Ok(vec![(
+            "oauth_grant.json".to_string(),
+            export_json(&json!(grant))?,
+        )])
+    }
+    //发送到该账号邮箱及手机号的消息记录
+    async fn message_data(&self, account: &AccountModel) -> Result<Vec<(String, Vec<u8>)>, String> {
+        let account_dao = &self.user_dao.account_dao;
+        let mut mail_data = vec![];
+        for email in account_dao
+            .account_email
+            .cache()
+            .find_by_account_id_vec(account.id)
+            .await
+            .map_err(export_err)?
+        {
+            let (message, _) = self
+                .sender
+                .mailer
+                .mailer_dao
+                .mail_record
+                .message_list(None, None, None, None, None, None, Some(&email.email), None)
+                .await
+                .map_err(export_err)?;
+            for (msg, body) in message {
+                mail_data.push(json!({
+                    "to_mail": msg.to_mail,
+                    "tpl_key": body.as_ref().map(|e| e.tpl_key.as_str()),
+                    "status": msg.status,
+                    "try_num": msg.try_num,
+                    "add_time": msg.add_time,
+                    "send_time": msg.send_time,
+                    "receive_time": msg.receive_time,
+                }));
+            }
+        }
+        let mut sms_data = vec![];
+        for mobile in account_dao
+            .account_mobile
+            .cache()
+            .find_by_account_id_vec(account.id)
+            .await
+            .map_err(export_err)?
+        {
+            let (message, _) = self
+                .sender
+                .smser
+                .smser_dao
+                .sms_record
+                .message_list(
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    Some(&mobile.mobile),
+                    None,
+                )
+                .await
+                .map_err(export_err)?;
+            for (msg, body) in message.into_iter().filter(|e| e.0.area == mobile.area_code) {
+                sms_data.push(json!({
+                    "area": msg.area,
+                    "mobile": msg.mobile,
+                    "tpl_key": body.as_ref().map(|e| e.tpl_key.as_str()),
+                    "status": msg.status,
+                    "try_num": msg.try_num,
+                    "add_time": msg.add_time,
+                    "send_time": msg.send_time,
+                    "receive_time": msg.receive_time,
+                }));
+            }
+        }
+        Ok(vec![
+            (
+                "mail_message.json".to_string(),
+                export_json(&json!(mail_data))?,
+            ),
+            (
+                "sms_message.json".to_string(),
+                export_json(&json!(sms_data))?,
+            ),
+        ])
+    }
+}
+
+#[async_trait::async_trait]
+impl AccountExportBuilder for WebAccountExportBuilder {
+    async fn build(
+        &self,
+        export: &AccountExportModel,
+        account: &AccountModel,
+    ) -> Result<AccountExportFile, String> {
+        let mut files = self.account_data(account).await?;
+        files.extend(self.log_data(account).await?);
+        files.extend(self.oauth_data(account).await?);
+        files.extend(self.message_data(account).await?);
+        let export_dir = self.export_dir.clone();
+        let file_path = export_dir.join(format!("account-export-{}-{}.zip", account.id, export.id));
+        tokio::task::spawn_blocking(move || {
+            std::fs::create_dir_all(&export_dir).map_err(|e| e.to_string())?;
+            let file = std::fs::File::create(&file_path).map_err(|e| e.to_string())?;
+            let mut zip = zip::ZipWriter::new(file);
+            let options = zip::write::FileOptions::default()
+                .compression_method(zip::CompressionMethod::Deflated);
+            for (name, body) in files {
+                zip.start_file(name, options).map_err(|e| e.to_string())?;
+                zip.write_all(&body).map_err(|e| e.to_string())?;
+            }
+            let file = zip.finish().map_err(|e| e.to_string())?;
+            file.sync_all().map_err(|e| e.to_string())?;
+            let file_size = file.metadata().map_err(|e| e.to_string())?.len();
+            Ok(AccountExportFile {
+                file_path: file_path.to_string_lossy().to_string(),
+                file_size,
+            })
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+    async fn finish_notify(&self, export: &AccountExportModel, account: &AccountModel) {
+        let emails = match self
+            .user_dao
+            .account_dao
+            .account_email
+            .cache()
+            .find_by_account_id_vec(account.id)
+            .await
+        {
+            Ok(emails) => emails,
+            Err(err) => {
+                warn!("account export notify,find email fail:{}", export_err(err));
+                return;
+            }
+        };
+        for email in emails
+            .iter()
+            .filter(|e| AccountEmailStatus::Valid.eq(e.status))
+        {
+            if self
+                .sender
+                .mailer
+                .send_account_export(
+                    &email.email,
+                    &export.download_token,
+                    export.expire_time,
+                    None,
+                )
+                .await
+                .is_err()
+            {
+                warn!(
+                    "account export notify,send mail fail:{} to {}",
+                    export.id, email.email
+                );
+            }
+        }
+    }
+}
@@ -1,11 +1,14 @@
 mod account;
 mod auth;
+mod export;
 mod oauth;
 
 pub use account::*;
 pub use auth::*;
+pub use export::*;
 use lsys_app::dao::AppDao;
 use lsys_logger::dao::ChangeLoggerDao;
+use lsys_rbac::dao::RbacDao;
 use lsys_user::dao::UserDao;
 pub use oauth::*;
 
@@ -20,10 +23,12 @@ pub struct WebUser {
 }
 
 impl WebUser {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         db: Pool<MySql>,
         user_dao: Arc<UserDao>,
         app_dao: Arc<AppDao>,
+        rbac_dao: Arc<RbacDao>,
         sender: Arc<AppSender>,
         captcha: Arc<AppCaptcha>,
         area: Arc<AppArea>,
@@ -32,6 +37,8 @@ impl WebUser {
         WebUser {
             account: Arc::new(WebUserAccount::new(
                 user_dao.clone(),
+                app_dao.clone(),
+                rbac_dao,
                 captcha.clone(),
                 sender.clone(),
                 area.clone(),
@@ -10,6 +10,7 @@ pub struct OauthLoginData {
     pub external_link: Option<String>,
     pub external_pic: Option<String>,
     pub external_nikename: String,
+    pub external_email: Option<String>, //外部平台已确认的邮箱
     pub token_data: String,
     pub token_timeout: u64,
 }
@@ -1,11 +1,16 @@
 mod oauth_server;
 mod stat;
+use crate::dao::access::RbacAccessCheckEnv;
 use lsys_access::dao::AccessDao;
+use lsys_access::dao::SessionBody;
 use lsys_app::dao::AppConfig;
 use lsys_app::dao::AppDao;
+use lsys_app::dao::AppResult;
+use lsys_app::model::AppModel;
 use lsys_core::AppCore;
 use lsys_core::AppCoreError;
 use lsys_core::RemoteNotify;
+use lsys_core::RequestEnv;
 use lsys_logger::dao::ChangeLoggerDao;
 use sqlx::MySql;
 use std::sync::Arc;
@@ -51,3 +56,26 @@ impl WebApp {
         Ok(Self { app_dao, db })
     }
 }
+
+impl WebApp {
+    //应用权限检测环境,组织应用附加当前用户成员身份对应的会话角色
+    pub async fn app_check_env<'t>(
+        &self,
+        app: &AppModel,
+        session_body: &'t SessionBody,
+        req_env: &'t RequestEnv,
+    ) -> AppResult<RbacAccessCheckEnv<'t>> {
+        let check_env = RbacAccessCheckEnv::session_body(session_body, req_env);
+        Ok(
+            match self
+                .app_dao
+                .org
+                .member_role(app.org_id, session_body.user_id())
+                .await?
+            {
+                Some(role) => check_env.add_session_role(&role.session_role_keys()),
+                None => check_env,
+            },
+        )
+    }
+}
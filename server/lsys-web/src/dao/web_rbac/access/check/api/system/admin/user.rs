@@ -62,3 +62,61 @@ impl RbacCheckResTpl for CheckAdminChangeLogsView {
         }]
     }
 }
+
+pub struct CheckAdminUserImpersonate {}
+#[async_trait::async_trait]
+impl RbacCheckAccess for CheckAdminUserImpersonate {
+    async fn check(&self, access: &RbacAccess, check_env: &AccessCheckEnv<'_>) -> RbacResult<()> {
+        access
+            .check(
+                check_env,
+                &[AccessCheckRes::system_empty_data(
+                    "global-system",
+                    vec![AccessCheckOp::new("impersonate-user", true)],
+                )],
+            )
+            .await
+    }
+    fn depends(&self) -> Vec<Box<RbacCheckAccessDepend>> {
+        vec![Box::new(CheckAdminBase {})]
+    }
+}
+impl RbacCheckResTpl for CheckAdminUserImpersonate {
+    fn tpl_data() -> Vec<CheckResTpl> {
+        vec![CheckResTpl {
+            user: false,
+            data: false,
+            key: "global-system",
+            ops: vec!["impersonate-user"],
+        }]
+    }
+}
+
+pub struct CheckAdminUserPasswordExport {}
+#[async_trait::async_trait]
+impl RbacCheckAccess for CheckAdminUserPasswordExport {
+    async fn check(&self, access: &RbacAccess, check_env: &AccessCheckEnv<'_>) -> RbacResult<()> {
+        access
+            .check(
+                check_env,
+                &[AccessCheckRes::system_empty_data(
+                    "global-system",
+                    vec![AccessCheckOp::new("export-user-password", true)],
+                )],
+            )
+            .await
+    }
+    fn depends(&self) -> Vec<Box<RbacCheckAccessDepend>> {
+        vec![Box::new(CheckAdminUserManage {})]
+    }
+}
+impl RbacCheckResTpl for CheckAdminUserPasswordExport {
+    fn tpl_data() -> Vec<CheckResTpl> {
+        vec![CheckResTpl {
+            user: false,
+            data: false,
+            key: "global-system",
+            ops: vec!["export-user-password"],
+        }]
+    }
+}
@@ -6,14 +6,18 @@ use crate::dao::RbacCheckAccessDepend;
 use crate::dao::WebRbac;
 use lsys_access::dao::SessionBody;
 use lsys_access::model::UserModel;
+use lsys_core::IntoFluentMessage;
 use lsys_core::RequestEnv;
 use lsys_rbac::dao::AccessCheckEnv;
 use lsys_rbac::dao::AccessSessionRole;
+use lsys_rbac::dao::RbacError;
 use lsys_rbac::dao::RbacResult;
+use lsys_user::dao::{AccountToken, ACCOUNT_TOKEN_LOGIN_TYPE};
 use std::ops::Deref;
 
 pub struct RbacAccessCheckEnv<'t> {
     check_env: AccessCheckEnv<'t>,
+    token_session_id: Option<u64>, //个人访问令牌登录时的登录记录ID
 }
 
 impl<'t> Deref for RbacAccessCheckEnv<'t> {
@@ -35,6 +39,7 @@ impl<'t> RbacAccessCheckEnv<'t> {
                 }],
                 ..Default::default()
             },
+            token_session_id: None,
         }
     }
     pub fn user(user: &UserModel, req_env: &'t RequestEnv) -> Self {
@@ -56,7 +61,9 @@ impl<'t> RbacAccessCheckEnv<'t> {
                 user_id: user.id,
                 user_app_id: user.app_id,
                 user_login_token: None,
+                token_scope: None,
             },
+            token_session_id: None,
         }
     }
     pub fn session_body(session_body: &'t SessionBody, req_env: &'t RequestEnv) -> Self {
@@ -77,8 +84,29 @@ impl<'t> RbacAccessCheckEnv<'t> {
             user_id: session_body.user_id(),
             user_app_id: session_body.session().user_app_id,
             user_login_token: Some(session_body.token_data()),
+            token_scope: None,
         };
-        Self { check_env }
+        let token_session_id =
+            if session_body.session().login_type == ACCOUNT_TOKEN_LOGIN_TYPE {
+                Some(session_body.session_id())
+            } else {
+                None
+            };
+        Self {
+            check_env,
+            token_session_id,
+        }
+    }
+    //附加会话角色,如访问组织应用时成员身份对应的角色
+    pub fn add_session_role(mut self, role_keys: &[&'t str]) -> Self {
+        for role_key in role_keys {
+            self.check_env.session_role.push(AccessSessionRole {
+                role_key,
+                user_id: 0,
+                app_id: 0,
+            });
+        }
+        self
     }
 }
 
@@ -113,17 +141,37 @@ impl WebRbac {
     //     self.inner_check(check_env, check_dep).await
     // }
     // 统一权限检测
-    #[async_recursion::async_recursion]
     pub async fn check(
         &self,
         check_env: &RbacAccessCheckEnv<'_>,
         check_dep: &RbacCheckAccessDepend,
     ) -> RbacResult<()> {
+        //个人访问令牌请求限定在令牌授权范围内,且不使用root豁免
+        if let Some(session_id) = check_env.token_session_id {
+            let token = self
+                .account_token
+                .cache()
+                .find_by_session_id(&session_id)
+                .await
+                .map_err(|e| RbacError::System(e.to_fluent_message()))?;
+            let token_scope = AccountToken::token_scope(&token);
+            let mut token_env = check_env.check_env.clone();
+            token_env.token_scope = Some(&token_scope);
+            return self.check_depend(&token_env, check_dep).await;
+        }
         if check_env.user_app_id == 0 && check_env.user_id > 0 && self.is_root(check_env.user_id) {
             return Ok(());
         }
+        self.check_depend(check_env, check_dep).await
+    }
+    #[async_recursion::async_recursion]
+    async fn check_depend(
+        &self,
+        check_env: &AccessCheckEnv<'_>,
+        check_dep: &RbacCheckAccessDepend,
+    ) -> RbacResult<()> {
         for pr in check_dep.depends() {
-            self.check(check_env, pr.as_ref()).await?
+            self.check_depend(check_env, pr.as_ref()).await?
         }
         check_dep.check(&self.rbac_dao.access, check_env).await
     }
@@ -3,21 +3,32 @@ pub mod access;
 mod common;
 pub mod res_op;
 pub mod res_tpl;
+mod role_delegate;
+mod role_request;
+mod token_scope;
 
 pub use common::*;
+pub use role_request::*;
 use std::sync::Arc;
 
 use lsys_rbac::dao::RbacDao;
+use lsys_user::dao::AccountToken;
 pub struct WebRbac {
     root_id_data: Vec<u64>,
     pub rbac_dao: Arc<RbacDao>,
+    account_token: Arc<AccountToken>,
 }
 
 impl WebRbac {
-    pub fn new(rbac_dao: Arc<RbacDao>, root_id_data: Vec<u64>) -> Self {
+    pub fn new(
+        rbac_dao: Arc<RbacDao>,
+        root_id_data: Vec<u64>,
+        account_token: Arc<AccountToken>,
+    ) -> Self {
         Self {
             rbac_dao,
             root_id_data,
+            account_token,
         }
     }
     pub fn is_root(&self, user_id: u64) -> bool {
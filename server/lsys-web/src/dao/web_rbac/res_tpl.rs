@@ -1,7 +1,8 @@
 use crate::dao::access::api::system::admin::{
     CheckAdminApp, CheckAdminBase, CheckAdminChangeLogsView, CheckAdminMailConfig,
     CheckAdminMailMgr, CheckAdminRbacEdit, CheckAdminRbacView, CheckAdminSiteSetting,
-    CheckAdminSmsConfig, CheckAdminSmsMgr, CheckAdminUserManage,
+    CheckAdminSmsConfig, CheckAdminSmsMgr, CheckAdminUserImpersonate, CheckAdminUserManage,
+    CheckAdminUserPasswordExport,
 };
 use crate::dao::access::api::system::auth::{CheckSystemLogin, CheckSystemRegister};
 use crate::dao::access::api::system::user::{
@@ -28,6 +29,8 @@ impl WebRbac {
             CheckAdminApp,
             CheckAdminSiteSetting,
             CheckAdminUserManage,
+            CheckAdminUserImpersonate,
+            CheckAdminUserPasswordExport,
             CheckAdminChangeLogsView,
             CheckUserAddressEdit,
             CheckUserEmailEdit,
@@ -0,0 +1,33 @@
+use lsys_rbac::{dao::RbacResult, model::RbacRoleModel};
+
+use super::{access::RbacAccessCheckEnv, RbacCheckAccessDepend, WebRbac};
+
+impl WebRbac {
+    //角色用户增删的权限检查,res_user_id 为当前接口管理的角色所属用户,0 为系统
+    //优先检查管理权限,通过时返回 false,增删时不受委托限制
+    //无管理权限时,若为该角色所属用户的委托管理员也可操作,返回 true,增删时按委托记录限制
+    pub async fn check_role_user_edit(
+        &self,
+        check_env: &RbacAccessCheckEnv<'_>,
+        check_dep: &RbacCheckAccessDepend,
+        role: &RbacRoleModel,
+        res_user_id: u64,
+    ) -> RbacResult<bool> {
+        let err = match self.check(check_env, check_dep).await {
+            Ok(()) => return Ok(false),
+            Err(err) => err,
+        };
+        if check_env.user_id == 0 || role.user_id != res_user_id || role.app_id != 0 {
+            return Err(err);
+        }
+        match self
+            .rbac_dao
+            .role
+            .find_delegate(role.user_id, role.app_id, check_env.user_id)
+            .await?
+        {
+            Some(_) => Ok(true),
+            None => Err(err),
+        }
+    }
+}
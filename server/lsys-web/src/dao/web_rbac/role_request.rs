@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use lsys_access::dao::AccessDao;
+use lsys_core::IntoFluentMessage;
+use lsys_rbac::{dao::RbacRoleRequestExpireNotify, model::RbacRoleRequestModel};
+use lsys_user::{dao::AccountDao, model::AccountEmailStatus};
+use tracing::warn;
+
+use crate::dao::SenderMailer;
+
+//角色临时授权到期后邮件通知申请用户
+pub struct WebRbacRoleRequestNotify {
+    access_dao: Arc<AccessDao>,
+    account_dao: Arc<AccountDao>,
+    mailer: Arc<SenderMailer>,
+}
+
+impl WebRbacRoleRequestNotify {
+    pub fn new(
+        access_dao: Arc<AccessDao>,
+        account_dao: Arc<AccountDao>,
+        mailer: Arc<SenderMailer>,
+    ) -> Self {
+        Self {
+            access_dao,
+            account_dao,
+            mailer,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl RbacRoleRequestExpireNotify for WebRbacRoleRequestNotify {
+    async fn expire_notify(&self, request: &RbacRoleRequestModel, role_name: &str) {
+        let user = match self
+            .access_dao
+            .user
+            .cache()
+            .find_by_id(&request.user_id)
+            .await
+        {
+            Ok(user) => user,
+            Err(err) => {
+                warn!(
+                    "role request expire notify,find user fail:{}",
+                    err.to_fluent_message().default_format()
+                );
+                return;
+            }
+        };
+        //仅系统账号可找到邮箱
+        if user.app_id > 0 {
+            return;
+        }
+        let account_id = match user.user_data.parse::<u64>() {
+            Ok(id) => id,
+            Err(_) => return,
+        };
+        let emails = match self
+            .account_dao
+            .account_email
+            .cache()
+            .find_by_account_id_vec(account_id)
+            .await
+        {
+            Ok(emails) => emails,
+            Err(err) => {
+                warn!(
+                    "role request expire notify,find email fail:{}",
+                    err.to_fluent_message().default_format()
+                );
+                return;
+            }
+        };
+        for email in emails
+            .iter()
+            .filter(|e| AccountEmailStatus::Valid.eq(e.status))
+        {
+            if self
+                .mailer
+                .send_role_request_expire(&email.email, role_name, request.expire_time, None)
+                .await
+                .is_err()
+            {
+                warn!(
+                    "role request expire notify,send mail fail:{} to {}",
+                    request.id, email.email
+                );
+            }
+        }
+    }
+}
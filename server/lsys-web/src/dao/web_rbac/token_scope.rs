@@ -0,0 +1,71 @@
+//个人访问令牌授权范围
+//范围格式为 资源类型:操作,只允许系统资源模板中的操作
+use lsys_core::fluent_message;
+use lsys_rbac::dao::{AccessCheckOp, AccessCheckRes, RbacError, RbacResult};
+
+use super::{access::RbacAccessCheckEnv, WebRbac};
+
+impl WebRbac {
+    fn token_scope_tpl(&self, data: bool) -> Vec<String> {
+        let mut out = vec![];
+        for tpl in self.res_tpl_data(false, data) {
+            for op in tpl.ops {
+                let scope = format!("{}:{}", tpl.key, op);
+                if !out.contains(&scope) {
+                    out.push(scope)
+                }
+            }
+        }
+        out
+    }
+    /// 可用于个人访问令牌的授权范围
+    pub fn token_scope_data(&self) -> Vec<String> {
+        let mut out = self.token_scope_tpl(false);
+        for scope in self.token_scope_tpl(true) {
+            if !out.contains(&scope) {
+                out.push(scope)
+            }
+        }
+        out
+    }
+    /// 检测授权范围内的操作为当前用户已拥有的操作
+    pub async fn token_scope_check(
+        &self,
+        check_env: &RbacAccessCheckEnv<'_>,
+        token_scope: &[String],
+    ) -> RbacResult<()> {
+        let scope_data = self.token_scope_data();
+        let user_scope_data = self.token_scope_tpl(true);
+        let user_id = check_env.user_id.to_string();
+        for scope in token_scope {
+            if !scope_data.contains(scope) {
+                return Err(RbacError::System(fluent_message!(
+                    "rbac-token-scope-bad",
+                    {
+                        "scope":scope
+                    }
+                )));
+            }
+            let (res_type, op_key) = scope.split_once(':').unwrap_or((scope, ""));
+            let system_res = [AccessCheckRes::system_empty_data(
+                res_type,
+                vec![AccessCheckOp::new(op_key, true)],
+            )];
+            if user_scope_data.contains(scope) {
+                //用户自身数据的操作
+                let user_res = [AccessCheckRes::system(
+                    res_type,
+                    &user_id,
+                    vec![AccessCheckOp::new(op_key, false)],
+                )];
+                self.rbac_dao
+                    .access
+                    .list_check(check_env, &[&system_res, &user_res])
+                    .await?;
+            } else {
+                self.rbac_dao.access.check(check_env, &system_res).await?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 //站点配置
 use lsys_access::dao::SessionBody;
 use lsys_core::RequestEnv;
-use lsys_user::dao::AccountPasswordConfig;
+use lsys_user::dao::{AccountPasswordConfig, AccountRegisterConfig};
 use serde::Deserialize;
 
 use lsys_setting::dao::{
@@ -37,8 +37,8 @@ impl SettingJson<'_> for SiteConfig {}
 
 pub struct SiteConfigData<'t> {
     pub site_tips: &'t str,
-    pub password_timeout: u64,
-    pub disable_old_password: bool,
+    pub password: &'t AccountPasswordConfig, //密码有效期及密码策略
+    pub register: &'t AccountRegisterConfig, //邀请注册及注册审核
 }
 impl WebSetting {
     pub async fn save_site_setting_data(
@@ -55,10 +55,25 @@ impl WebSetting {
                 None,
                 &SingleSettingData {
                     name: AccountPasswordConfig::key(),
-                    data: &AccountPasswordConfig {
-                        timeout: param.password_timeout,
-                        disable_old_password: param.disable_old_password,
-                    },
+                    data: param.password,
+                },
+                session_body.user_id(),
+                Some(&mut transaction),
+                env_data,
+            )
+            .await
+        {
+            transaction.rollback().await?;
+            return Err(e.into());
+        };
+        if let Err(e) = self
+            .setting_dao
+            .single
+            .save::<AccountRegisterConfig>(
+                None,
+                &SingleSettingData {
+                    name: AccountRegisterConfig::key(),
+                    data: param.register,
                 },
                 session_body.user_id(),
                 Some(&mut transaction),
@@ -0,0 +1,132 @@
+use crate::{
+    common::{CaptchaParam, JsonResult, UserAuthQueryDao},
+    dao::ShowUserAuthData,
+};
+use lsys_access::dao::SessionBody;
+use lsys_core::IntoFluentMessage;
+use lsys_rbac::dao::RoleAddUser;
+use lsys_setting::dao::NotFoundResult;
+use lsys_user::dao::{
+    login::{LdapConfig, LdapGroupRole, LdapLogin},
+    UserAuthToken,
+};
+use serde::Deserialize;
+use tracing::warn;
+
+use super::user_login_finish;
+
+#[derive(Deserialize)]
+pub struct LdapLoginParam {
+    name: String,
+    password: String,
+    captcha: Option<CaptchaParam>,
+}
+
+pub async fn user_login_from_ldap(
+    param: &LdapLoginParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<(UserAuthToken, ShowUserAuthData)> {
+    let config = req_dao
+        .web_dao
+        .web_setting
+        .setting_dao
+        .single
+        .load::<LdapConfig>(None)
+        .await
+        .notfound_default()?;
+    let login = LdapLogin::new(
+        req_dao.web_dao.web_user.user_dao.account_dao.clone(),
+        (*config).clone(),
+        &param.name,
+        &param.password,
+    )
+    .await?;
+    let session_body = req_dao
+        .web_dao
+        .web_user
+        .auth
+        .user_login(&login, param.captcha.as_ref(), Some(&req_dao.req_env))
+        .await?;
+    ldap_role_sync(&session_body, &config.group_role, &login.groups(), req_dao).await;
+    user_login_finish(session_body, req_dao).await
+}
+
+//组名可配置为完整DN或CN值
+fn ldap_group_match(group: &str, user_groups: &[String]) -> bool {
+    let group = group.trim();
+    !group.is_empty()
+        && user_groups.iter().any(|dn| {
+            dn.eq_ignore_ascii_case(group)
+                || dn
+                    .split(',')
+                    .next()
+                    .and_then(|e| e.split_once('='))
+                    .map(|(_, cn)| cn.trim().eq_ignore_ascii_case(group))
+                    .unwrap_or(false)
+        })
+}
+
+//按目录中的组同步角色,同步失败不影响登录
+async fn ldap_role_sync(
+    session_body: &SessionBody,
+    group_role: &[LdapGroupRole],
+    user_groups: &[String],
+    req_dao: &UserAuthQueryDao,
+) {
+    let role_dao = &req_dao.web_dao.web_rbac.rbac_dao.role;
+    let user_id = session_body.user_id();
+    for item in group_role {
+        let role = match role_dao.find_by_id(&item.role_id).await {
+            Ok(role) => role,
+            Err(err) => {
+                warn!(
+                    "ldap sync role {} fail:{}",
+                    item.role_id,
+                    err.to_fluent_message().default_format()
+                );
+                continue;
+            }
+        };
+        let in_group = ldap_group_match(&item.group, user_groups);
+        //角色状态未变化时不重复写入
+        match role_dao.role_user_exist(&role, user_id).await {
+            Ok(exist) if exist == in_group => continue,
+            Ok(_) => {}
+            Err(err) => {
+                warn!(
+                    "ldap sync role {} fail:{}",
+                    role.id,
+                    err.to_fluent_message().default_format()
+                );
+                continue;
+            }
+        }
+        let res = if in_group {
+            role_dao
+                .add_user(
+                    &role,
+                    &[RoleAddUser {
+                        user_id,
+                        timeout: 0,
+                    }],
+                    0,
+                    None,
+                    Some(&req_dao.req_env),
+                )
+                .await
+        } else {
+            role_dao
+                .del_user(&role, &[user_id], 0, None, Some(&req_dao.req_env))
+                .await
+                .map(|_| ())
+        };
+        if let Err(err) = res {
+            warn!(
+                "ldap sync role {} user {} fail:{}",
+                role.id,
+                user_id,
+                err.to_fluent_message().default_format()
+            );
+        }
+    }
+}
@@ -6,8 +6,9 @@ use crate::{
     dao::{access::api::system::auth::CheckSystemLogin, ShowUserAuthData},
 };
 use lsys_access::dao::{AccessSession, SessionBody};
+use lsys_core::IntoFluentMessage;
 use lsys_user::dao::{
-    login::{EmailCodeLogin, EmailLogin, MobileCodeLogin, MobileLogin, NameLogin},
+    login::{EmailCodeLogin, EmailLinkLogin, EmailLogin, MobileCodeLogin, MobileLogin, NameLogin},
     UserAuthToken,
 };
 use serde::Deserialize;
@@ -293,3 +294,149 @@ pub async fn user_login_email_send_code(
         .await;
     Ok(JsonResponse::data(JsonData::body(json!({ "ttl": data.1 }))))
 }
+
+#[derive(Deserialize)]
+pub struct EmailSendLinkLoginParam {
+    email: String,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    wait_confirm: Option<bool>,
+    captcha: CaptchaParam,
+}
+
+//发送登录链接邮件,wait_confirm 为真时打开链接后由当前设备完成登录
+pub async fn user_login_email_send_link(
+    param: &EmailSendLinkLoginParam,
+    req_dao: &RequestDao,
+) -> JsonResult<JsonResponse> {
+    let valid_code = req_dao
+        .web_dao
+        .app_captcha
+        .valid_code(&crate::dao::CaptchaKey::LoginEmailCode);
+    valid_code
+        .check_code(&param.captcha.borrow().into())
+        .await?;
+    let (token, wait_id, ttl) = req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .email_link_dao
+        .link_create(&param.email, param.wait_confirm.unwrap_or(false))
+        .await?;
+    req_dao
+        .web_dao
+        .app_sender
+        .mailer
+        .send_login_link(
+            &param.email,
+            &token,
+            wait_id.is_some(),
+            ttl,
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    let _ = valid_code
+        .destroy_code(
+            &param.captcha.key,
+            &mut req_dao.web_dao.app_captcha.valid_code_builder(),
+        )
+        .await;
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "ttl": ttl,
+        "wait_id": wait_id,
+    }))))
+}
+
+#[derive(Deserialize)]
+pub struct EmailLinkLoginParam {
+    token: String,
+    captcha: Option<CaptchaParam>,
+}
+
+//打开邮件中的链接直接登录
+pub async fn user_login_from_email_link(
+    param: &EmailLinkLoginParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<(UserAuthToken, ShowUserAuthData)> {
+    let session_body = req_dao
+        .web_dao
+        .web_user
+        .auth
+        .user_login(
+            &EmailLinkLogin::new(
+                req_dao.web_dao.web_user.user_dao.account_dao.clone(),
+                req_dao.web_dao.web_user.user_dao.email_link_dao.clone(),
+                &param.token,
+                None,
+            )
+            .await?,
+            param.captcha.as_ref(),
+            Some(&req_dao.req_env),
+        )
+        .await?;
+
+    user_login_finish(session_body, req_dao).await
+}
+
+#[derive(Deserialize)]
+pub struct EmailLinkConfirmParam {
+    token: String,
+}
+
+//在任意设备打开链接,确认由原设备完成登录
+pub async fn user_login_email_link_confirm(
+    param: &EmailLinkConfirmParam,
+    req_dao: &RequestDao,
+) -> JsonResult<JsonResponse> {
+    let data = req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .email_link_dao
+        .link_confirm(&param.token)
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(
+        json!({ "email": data.email }),
+    )))
+}
+
+#[derive(Deserialize)]
+pub struct EmailLinkWaitParam {
+    wait_id: String,
+    captcha: Option<CaptchaParam>,
+}
+
+//原设备等待链接确认,确认后完成登录,等待超时返回None
+pub async fn user_login_email_link_wait(
+    param: &EmailLinkWaitParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<Option<(UserAuthToken, ShowUserAuthData)>> {
+    let link_dao = &req_dao.web_dao.web_user.user_dao.email_link_dao;
+    let token = match link_dao.wait_confirm(&param.wait_id).await? {
+        Some(token) => token,
+        None => return Ok(None),
+    };
+    let session_body = req_dao
+        .web_dao
+        .web_user
+        .auth
+        .user_login(
+            &EmailLinkLogin::new(
+                req_dao.web_dao.web_user.user_dao.account_dao.clone(),
+                link_dao.clone(),
+                &token,
+                Some(&param.wait_id),
+            )
+            .await?,
+            param.captcha.as_ref(),
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    if let Err(err) = link_dao.wait_clear(&param.wait_id).await {
+        tracing::warn!(
+            "clear email link wait fail:{} in [{}]",
+            err.to_fluent_message().default_format(),
+            param.wait_id
+        );
+    }
+    Ok(Some(user_login_finish(session_body, req_dao).await?))
+}
@@ -1,8 +1,10 @@
 mod app_code_login;
 mod external_login;
+mod ldap_login;
 mod local_login;
 mod login_data;
 pub use app_code_login::*;
 pub use external_login::*;
+pub use ldap_login::*;
 pub use local_login::*;
 pub use login_data::*;
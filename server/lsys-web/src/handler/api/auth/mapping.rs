@@ -5,8 +5,10 @@ use crate::common::RequestDao;
 use lsys_core::FluentMessage;
 use lsys_user::dao::login::AccountLoginMeta;
 use lsys_user::dao::login::EmailCodeLoginMeta;
+use lsys_user::dao::login::EmailLinkLoginMeta;
 use lsys_user::dao::login::EmailLoginMeta;
 use lsys_user::dao::login::ExternalLoginMeta;
+use lsys_user::dao::login::LdapLoginMeta;
 use lsys_user::dao::login::MobileCodeLoginMeta;
 use lsys_user::dao::login::MobileLoginMeta;
 use lsys_user::dao::login::NameLoginMeta;
@@ -23,6 +25,10 @@ pub async fn mapping_data(req_dao: &RequestDao, exter: Value) -> JsonResult<Json
             EmailCodeLoginMeta::login_type(),
             EmailCodeLoginMeta::login_timeout(),
         ),
+        (
+            EmailLinkLoginMeta::login_type(),
+            EmailLinkLoginMeta::login_timeout(),
+        ),
         (NameLoginMeta::login_type(), NameLoginMeta::login_timeout()),
         (
             MobileLoginMeta::login_type(),
@@ -35,7 +41,8 @@ pub async fn mapping_data(req_dao: &RequestDao, exter: Value) -> JsonResult<Json
         (
             ExternalLoginMeta::login_type(),
             ExternalLoginMeta::login_timeout(),
-        )]).iter()
+        ),
+        (LdapLoginMeta::login_type(), LdapLoginMeta::login_timeout())]).iter()
     .map(|e| {
         json!({
             "key":&e.0,
@@ -14,6 +14,7 @@ pub struct RegFromNameParam {
     pub nikename: Option<String>,
     pub name: String,
     pub password: String,
+    pub invite_code: Option<String>,
 }
 
 pub async fn user_reg_from_name(
@@ -28,7 +29,7 @@ pub async fn user_reg_from_name(
             &CheckSystemRegister {},
         )
         .await?;
-    let user = req_dao
+    let (user, need_approve) = req_dao
         .web_dao
         .web_user
         .auth
@@ -37,6 +38,7 @@ pub async fn user_reg_from_name(
                 nikename: param.nikename.as_deref(),
                 name: &param.name,
                 password: &param.password,
+                invite_code: param.invite_code.as_deref(),
             },
             0,
             Some(&req_dao.req_env),
@@ -44,6 +46,7 @@ pub async fn user_reg_from_name(
         .await?;
     Ok(JsonResponse::data(JsonData::body(json!({
         "id":user,
+        "need_approve":need_approve,
     }))))
 }
 #[derive(Debug, Deserialize)]
@@ -102,6 +105,7 @@ pub struct RegFromEmailParam {
     pub code: String,
     pub password: String,
     pub nikename: String,
+    pub invite_code: Option<String>,
 }
 
 pub async fn user_reg_from_email(
@@ -116,7 +120,7 @@ pub async fn user_reg_from_email(
             &CheckSystemRegister {},
         )
         .await?;
-    let id = req_dao
+    let (id, need_approve) = req_dao
         .web_dao
         .web_user
         .auth
@@ -126,12 +130,13 @@ pub async fn user_reg_from_email(
                 code: &param.code,
                 password: &param.password,
                 nikename: &param.nikename,
+                invite_code: param.invite_code.as_deref(),
             },
             0,
             Some(&req_dao.req_env),
         )
         .await?;
-    Ok(JsonResponse::data(JsonData::body(json!({ "id": id}))))
+    Ok(JsonResponse::data(JsonData::body(json!({ "id": id, "need_approve": need_approve }))))
 }
 
 #[derive(Deserialize)]
@@ -141,6 +146,7 @@ pub struct RegFromMobileParam {
     pub code: String,
     pub password: String,
     pub nikename: String,
+    pub invite_code: Option<String>,
 }
 
 pub async fn user_reg_from_mobile(
@@ -155,7 +161,7 @@ pub async fn user_reg_from_mobile(
             &CheckSystemRegister {},
         )
         .await?;
-    let id = req_dao
+    let (id, need_approve) = req_dao
         .web_dao
         .web_user
         .auth
@@ -166,10 +172,11 @@ pub async fn user_reg_from_mobile(
                 code: &param.code,
                 password: &param.password,
                 nikename: &param.nikename,
+                invite_code: param.invite_code.as_deref(),
             },
             0,
             Some(&req_dao.req_env),
         )
         .await?;
-    Ok(JsonResponse::data(JsonData::body(json!({ "id": id}))))
+    Ok(JsonResponse::data(JsonData::body(json!({ "id": id, "need_approve": need_approve }))))
 }
@@ -123,3 +123,48 @@ pub fn geo_find(param: &GeoParam, req_dao: &RequestDao) -> JsonResult<JsonRespon
         .collect::<Vec<_>>();
     Ok(JsonResponse::data(JsonData::body(json!({ "area": data }))))
 }
+#[derive(Debug, Deserialize)]
+pub struct AddressParseParam {
+    pub address: String,
+}
+//解析地址文本,返回匹配的省市区及剩余详细地址
+#[allow(clippy::result_large_err)]
+pub fn address_parse(param: &AddressParseParam, req_dao: &RequestDao) -> JsonResult<JsonResponse> {
+    let data = req_dao.web_dao.app_area.address_parse(&param.address)?;
+    let code = data.item.last().map(|e| e.code.clone()).unwrap_or_default();
+    let leaf = data.item.last().map(|e| e.leaf).unwrap_or(false);
+    let item = data
+        .item
+        .into_iter()
+        .map(|e| {
+            json!({
+                "name":e.name,
+                "code":e.code,
+                "leaf":e.leaf,
+            })
+        })
+        .collect::<Vec<_>>();
+    let related = data
+        .related
+        .into_iter()
+        .map(|e| {
+            e.into_iter()
+                .map(|e| {
+                    json!({
+                        "name":e.item.name,
+                        "code":e.item.code,
+                        "leaf":e.item.leaf,
+                        "selected":e.selected,
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "code": code,
+        "leaf": leaf,
+        "area": item,
+        "detail": data.detail,
+        "related": related,
+    }))))
+}
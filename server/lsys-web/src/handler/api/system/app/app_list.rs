@@ -41,6 +41,7 @@ pub struct ShowAppRecord {
     pub client_id: String,
     pub status: i8,
     pub user_id: u64,
+    pub org_id: u64,
     pub change_time: u64,
     pub change_user_id: u64,
     pub exter_login: bool,                                       //请求数量
@@ -122,6 +123,7 @@ pub async fn app_list(param: &ListParam, req_dao: &UserAuthQueryDao) -> JsonResu
             client_id: e.0.client_id,
             status: e.0.status,
             user_id: e.0.user_id,
+            org_id: e.0.org_id,
             change_time: e.0.change_time,
             change_user_id: e.0.change_user_id,
             exter_login: e.1.exter_login.unwrap_or(false),
@@ -5,6 +5,7 @@ use crate::common::UserAuthQueryDao;
 use crate::handler::APP_FEATURE_BARCODE;
 use crate::handler::APP_FEATURE_MAIL;
 use crate::handler::APP_FEATURE_RBAC;
+use crate::handler::APP_FEATURE_SCIM;
 use crate::handler::APP_FEATURE_SMS;
 use lsys_app::model::AppRequestStatus;
 use lsys_app::model::AppRequestType;
@@ -33,6 +34,7 @@ pub async fn mapping_data(req_dao: &UserAuthQueryDao) -> JsonResult<JsonResponse
             const_json_format!(req_dao, APP_FEATURE_MAIL),
             const_json_format!(req_dao, APP_FEATURE_BARCODE),
             const_json_format!(req_dao, APP_FEATURE_RBAC),
+            const_json_format!(req_dao, APP_FEATURE_SCIM),
         ],
          "request_type":vec![
             status_json_format!(req_dao, AppRequestType::AppReq),
@@ -1,12 +1,16 @@
 use crate::common::JsonData;
+use crate::common::JsonError;
 use crate::common::JsonResponse;
 use crate::common::JsonResult;
 use crate::common::LimitParam;
+use crate::common::PageParam;
 use crate::common::UserAuthQueryDao;
 use crate::dao::access::api::system::admin::CheckAdminRbacView;
 use crate::dao::access::RbacAccessCheckEnv;
 use lsys_access::dao::AccessSession;
-use lsys_rbac::dao::AuditDataParam;
+use lsys_core::{fluent_message, now_time};
+use lsys_rbac::dao::{AuditDataParam, AuditStatGroup, AuditStatParam, AuditStatPeriod};
+use lsys_rbac::model::{RbacAuditDetailModel, RbacAuditModel};
 use serde::Deserialize;
 use serde_json::{json, Value};
 
@@ -29,6 +33,8 @@ pub struct AuditParam {
     pub user_ip: Option<String>,
     pub device_id: Option<String>,
     pub request_id: Option<String>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub personal_token: Option<bool>,
     pub res_data: Option<AuditResParam>,
     pub limit: Option<LimitParam>,
     #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
@@ -61,6 +67,7 @@ pub async fn audit_data(
                 user_ip: param.user_ip.as_deref(),
                 device_id: param.device_id.as_deref(),
                 request_id: param.request_id.as_deref(),
+                personal_token: param.personal_token,
                 res_data: param.res_data.as_ref().map(|e| (e.res_id, e.op_id)),
             },
             param.limit.as_ref().map(|e| e.into()).as_ref(),
@@ -79,6 +86,7 @@ pub async fn audit_data(
                     user_ip: param.user_ip.as_deref(),
                     device_id: param.device_id.as_deref(),
                     request_id: param.request_id.as_deref(),
+                    personal_token: param.personal_token,
                     res_data: param.res_data.as_ref().map(|e| (e.res_id, e.op_id)),
                 })
                 .await?,
@@ -111,3 +119,267 @@ pub async fn audit_data(
         "total": count,
     }))))
 }
+
+//授权拒绝统计
+
+#[derive(Debug, Deserialize)]
+pub struct AuditDenyStatParam {
+    pub group: String,          //user res op
+    pub period: Option<String>, //hour day month
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub user_app_id: Option<u64>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub res_user_id: Option<u64>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub start_time: Option<u64>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub end_time: Option<u64>,
+}
+
+pub async fn audit_deny_stat(
+    param: &AuditDenyStatParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminRbacView {},
+        )
+        .await?;
+    let group = match param.group.as_str() {
+        "user" => AuditStatGroup::User,
+        "res" => AuditStatGroup::Res,
+        "op" => AuditStatGroup::Op,
+        _ => {
+            return Err(JsonError::Message(fluent_message!(
+                "rbac-audit-stat-bad-group",{
+                    "group":param.group.as_str()
+                }
+            )))
+        }
+    };
+    let period = match param.period.as_deref().unwrap_or("day") {
+        "hour" => AuditStatPeriod::Hour,
+        "day" => AuditStatPeriod::Day,
+        "month" => AuditStatPeriod::Month,
+        period => {
+            return Err(JsonError::Message(fluent_message!(
+                "rbac-audit-stat-bad-period",{
+                    "period":period
+                }
+            )))
+        }
+    };
+    let end_time = param
+        .end_time
+        .unwrap_or_else(|| now_time().unwrap_or_default());
+    let start_time = param
+        .start_time
+        .unwrap_or_else(|| end_time.saturating_sub(7 * 86400));
+    let data = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .access
+        .audit_deny_stat(
+            &AuditStatParam {
+                user_app_id: param.user_app_id,
+                res_user_id: param.res_user_id,
+                start_time,
+                end_time,
+            },
+            group,
+            period,
+        )
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(json!({ "data": data }))))
+}
+
+//长期未使用的权限
+
+#[derive(Debug, Deserialize)]
+pub struct AuditUnusedPermParam {
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub user_id: Option<u64>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub app_id: Option<u64>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub days: Option<u64>,
+    pub page: Option<PageParam>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+}
+
+pub async fn audit_unused_perm(
+    param: &AuditUnusedPermParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminRbacView {},
+        )
+        .await?;
+    let rbac_dao = &req_dao.web_dao.web_rbac.rbac_dao;
+    let unused_param = lsys_rbac::dao::AuditUnusedPermParam {
+        user_id: param.user_id.unwrap_or(0),
+        app_id: param.app_id,
+        days: param.days.unwrap_or(90),
+    };
+    let data = rbac_dao
+        .access
+        .audit_unused_perm_data(
+            &unused_param,
+            param.page.as_ref().map(|e| e.into()).as_ref(),
+        )
+        .await?;
+    let count = if param.count_num.unwrap_or(false) {
+        Some(
+            rbac_dao
+                .access
+                .audit_unused_perm_count(&unused_param)
+                .await?,
+        )
+    } else {
+        None
+    };
+    let res_data = rbac_dao
+        .res
+        .find_by_ids(&data.iter().map(|e| e.0.res_id).collect::<Vec<_>>())
+        .await?;
+    let op_data = rbac_dao
+        .op
+        .find_by_ids(&data.iter().map(|e| e.0.op_id).collect::<Vec<_>>())
+        .await?;
+    let out_data = data
+        .iter()
+        .map(|(perm, role)| {
+            json!({
+                "perm":perm,
+                "role":role,
+                "res":res_data.get(&perm.res_id),
+                "op":op_data.get(&perm.op_id),
+            })
+        })
+        .collect::<Vec<Value>>();
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "data": out_data,
+        "total": count,
+    }))))
+}
+
+//导出审计记录为CSV
+
+#[allow(clippy::result_large_err)]
+fn audit_export_csv(data: &[(RbacAuditModel, Vec<RbacAuditDetailModel>)]) -> JsonResult<String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    let mut rows = vec![vec![
+        "audit_id",
+        "user_id",
+        "user_app_id",
+        "check_result",
+        "user_ip",
+        "device_id",
+        "personal_token",
+        "request_id",
+        "add_time",
+        "res_type",
+        "res_data",
+        "res_user_id",
+        "op_key",
+        "res_id",
+        "op_id",
+        "detail_check_result",
+        "detail_item_result",
+        "is_role_excluce",
+        "is_role_include",
+        "is_role_all",
+    ]
+    .into_iter()
+    .map(|e| e.to_string())
+    .collect::<Vec<_>>()];
+    for (audit, detail) in data {
+        for item in detail {
+            rows.push(vec![
+                audit.id.to_string(),
+                audit.user_id.to_string(),
+                audit.user_app_id.to_string(),
+                audit.check_result.to_string(),
+                audit.user_ip.to_owned(),
+                audit.device_id.to_owned(),
+                audit.personal_token.to_owned(),
+                audit.request_id.to_owned(),
+                audit.add_time.to_string(),
+                item.res_type.to_owned(),
+                item.res_data.to_owned(),
+                item.res_user_id.to_string(),
+                item.op_key.to_owned(),
+                item.res_id.to_string(),
+                item.op_id.to_string(),
+                item.check_result.to_string(),
+                item.item_result.to_string(),
+                item.is_role_excluce.to_string(),
+                item.is_role_include.to_string(),
+                item.is_role_all.to_string(),
+            ]);
+        }
+    }
+    for row in rows {
+        writer.write_record(&row).map_err(|e| {
+            JsonError::Message(fluent_message!("rbac-audit-export-error", e.to_string()))
+        })?;
+    }
+    let body = writer.into_inner().map_err(|e| {
+        JsonError::Message(fluent_message!("rbac-audit-export-error", e.to_string()))
+    })?;
+    Ok(String::from_utf8_lossy(&body).to_string())
+}
+
+/// 导出审计记录,返回 (文件名,CSV内容,下一页起始ID)
+pub async fn audit_export(
+    param: &AuditParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<(String, String, Option<u64>)> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminRbacView {},
+        )
+        .await?;
+    let res = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .access
+        .audit_data(
+            &AuditDataParam {
+                user_id: param.user_id,
+                user_app_id: param.user_app_id,
+                user_ip: param.user_ip.as_deref(),
+                device_id: param.device_id.as_deref(),
+                request_id: param.request_id.as_deref(),
+                personal_token: param.personal_token,
+                res_data: param.res_data.as_ref().map(|e| (e.res_id, e.op_id)),
+            },
+            param.limit.as_ref().map(|e| e.into()).as_ref(),
+        )
+        .await?;
+    let body = audit_export_csv(&res.0)?;
+    Ok((
+        format!("rbac-audit-{}.csv", now_time().unwrap_or_default()),
+        body,
+        res.1,
+    ))
+}
@@ -3,6 +3,7 @@ use crate::common::JsonResponse;
 use crate::common::JsonResult;
 use crate::common::UserAuthQueryDao;
 use lsys_rbac::model::RbacAuditResult;
+use lsys_rbac::model::RbacRoleRequestStatus;
 use lsys_rbac::model::RbacRoleResRange;
 use lsys_rbac::model::RbacRoleUserRange;
 use serde_json::json;
@@ -21,5 +22,12 @@ pub async fn mapping_data(req_dao: &UserAuthQueryDao) -> JsonResult<JsonResponse
             status_json_format!(req_dao, RbacAuditResult::Succ),
             status_json_format!(req_dao, RbacAuditResult::Fail),
         ],
+        "role_request_status":vec![
+            status_json_format!(req_dao, RbacRoleRequestStatus::Pending),
+            status_json_format!(req_dao, RbacRoleRequestStatus::Approved),
+            status_json_format!(req_dao, RbacRoleRequestStatus::Rejected),
+            status_json_format!(req_dao, RbacRoleRequestStatus::Cancel),
+            status_json_format!(req_dao, RbacRoleRequestStatus::Expired),
+        ],
     }))))
 }
@@ -5,7 +5,10 @@ mod res;
 mod res_tpl;
 mod res_type;
 mod role;
+mod role_delegate;
+mod role_exclusive;
 mod role_perm;
+mod role_request;
 mod role_user;
 
 pub use audit::*;
@@ -15,7 +18,10 @@ pub use res::*;
 pub use res_tpl::*;
 pub use res_type::*;
 pub use role::*;
+pub use role_delegate::*;
+pub use role_exclusive::*;
 pub use role_perm::*;
+pub use role_request::*;
 pub use role_user::*;
 mod mapping;
 pub use mapping::*;
@@ -0,0 +1,203 @@
+use crate::dao::access::RbacAccessCheckEnv;
+use crate::{
+    common::{JsonData, JsonError, JsonResponse, JsonResult, PageParam, UserAuthQueryDao},
+    dao::access::api::system::admin::{CheckAdminRbacEdit, CheckAdminRbacView},
+};
+use lsys_access::dao::AccessSession;
+use lsys_core::fluent_message;
+use lsys_rbac::dao::{RoleDelegateAddData, RoleDelegateDataParam};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, Deserialize)]
+pub struct RoleDelegateAddParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub delegate_user_id: u64,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub scope_role_id: Option<u64>,
+}
+
+//添加系统角色的委托管理员
+pub async fn role_delegate_add(
+    param: &RoleDelegateAddParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminRbacEdit {},
+        )
+        .await?;
+    let id = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .add_delegate(
+            &RoleDelegateAddData {
+                user_id: 0,
+                app_id: 0,
+                delegate_user_id: param.delegate_user_id,
+                scope_role_id: param.scope_role_id.unwrap_or_default(),
+            },
+            auth_data.user_id(),
+            None,
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(json!({ "id": id }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RoleDelegateDelParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub id: u64,
+}
+
+pub async fn role_delegate_del(
+    param: &RoleDelegateDelParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminRbacEdit {},
+        )
+        .await?;
+    let delegate = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .find_delegate_by_id(&param.id)
+        .await?;
+    if delegate.user_id != 0 {
+        return Err(JsonError::Message(fluent_message!(
+            "role-delegate-not-found",
+            {
+                "id": param.id
+            }
+        )));
+    }
+    req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .del_delegate(&delegate, auth_data.user_id(), None, Some(&req_dao.req_env))
+        .await?;
+    Ok(JsonResponse::default())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RoleDelegateListParam {
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub delegate_user_id: Option<u64>,
+    pub page: Option<PageParam>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+}
+
+pub async fn role_delegate_data(
+    param: &RoleDelegateListParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminRbacView {},
+        )
+        .await?;
+    let data_param = RoleDelegateDataParam {
+        user_id: Some(0),
+        app_id: Some(0),
+        delegate_user_id: param.delegate_user_id,
+    };
+    let res = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .delegate_data(&data_param, param.page.as_ref().map(|e| e.into()).as_ref())
+        .await?;
+    let res = bind_vec_user_info_from_req!(req_dao, res, delegate_user_id, false);
+    let count = if param.count_num.unwrap_or(false) {
+        Some(
+            req_dao
+                .web_dao
+                .web_rbac
+                .rbac_dao
+                .role
+                .delegate_count(&data_param)
+                .await?,
+        )
+    } else {
+        None
+    };
+    Ok(JsonResponse::data(JsonData::body(
+        json!({ "data": res,"total":count}),
+    )))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RoleDelegableParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub role_id: u64,
+    #[serde(deserialize_with = "crate::common::deserialize_bool")]
+    pub delegable: bool,
+}
+
+//设置系统角色是否允许委托管理员分配
+pub async fn role_delegable(
+    param: &RoleDelegableParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminRbacEdit {},
+        )
+        .await?;
+    let role = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .find_by_id(&param.role_id)
+        .await?;
+    if role.user_id != 0 || role.app_id != 0 {
+        return Err(JsonError::Message(fluent_message!(
+            "role-delegable-not-system",
+            {
+                "name": role.role_name,
+                "role_id": role.id
+            }
+        )));
+    }
+    req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .set_role_delegable(
+            &role,
+            param.delegable,
+            auth_data.user_id(),
+            None,
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::default())
+}
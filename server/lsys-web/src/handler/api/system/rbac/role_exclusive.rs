@@ -0,0 +1,227 @@
+use crate::dao::access::RbacAccessCheckEnv;
+use crate::{
+    common::{JsonData, JsonResponse, JsonResult, PageParam, UserAuthQueryDao},
+    dao::access::api::system::admin::{CheckAdminRbacEdit, CheckAdminRbacView},
+};
+use lsys_access::dao::AccessSession;
+use lsys_rbac::dao::{RoleExclusiveAddData, RoleExclusiveDataParam};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, Deserialize)]
+pub struct RoleExclusiveAddParam {
+    pub name: String,
+    #[serde(deserialize_with = "crate::common::deserialize_vec_u64")]
+    pub role_ids: Vec<u64>,
+}
+
+//添加系统角色互斥约束
+pub async fn role_exclusive_add(
+    param: &RoleExclusiveAddParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminRbacEdit {},
+        )
+        .await?;
+    let id = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .add_exclusive(
+            &RoleExclusiveAddData {
+                user_id: 0,
+                app_id: 0,
+                name: &param.name,
+                role_ids: &param.role_ids,
+            },
+            auth_data.user_id(),
+            None,
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(json!({ "id": id }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RoleExclusiveEditParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub exclusive_id: u64,
+    pub name: String,
+    #[serde(deserialize_with = "crate::common::deserialize_vec_u64")]
+    pub role_ids: Vec<u64>,
+}
+
+pub async fn role_exclusive_edit(
+    param: &RoleExclusiveEditParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminRbacEdit {},
+        )
+        .await?;
+    let exclusive = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .find_exclusive_by_id(&param.exclusive_id)
+        .await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .edit_exclusive(
+            &exclusive,
+            &param.name,
+            &param.role_ids,
+            auth_data.user_id(),
+            None,
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::default())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RoleExclusiveDelParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub exclusive_id: u64,
+}
+
+pub async fn role_exclusive_del(
+    param: &RoleExclusiveDelParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminRbacEdit {},
+        )
+        .await?;
+    let exclusive = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .find_exclusive_by_id(&param.exclusive_id)
+        .await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .del_exclusive(
+            &exclusive,
+            auth_data.user_id(),
+            None,
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::default())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RoleExclusiveListParam {
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub role_id: Option<u64>,
+    pub page: Option<PageParam>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+}
+
+pub async fn role_exclusive_data(
+    param: &RoleExclusiveListParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminRbacView {},
+        )
+        .await?;
+    let data_param = RoleExclusiveDataParam {
+        user_id: 0,
+        app_id: Some(0),
+        role_id: param.role_id,
+    };
+    let res = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .exclusive_data(&data_param, param.page.as_ref().map(|e| e.into()).as_ref())
+        .await?
+        .into_iter()
+        .map(|(exclusive, role)| json!({ "exclusive": exclusive, "role": role }))
+        .collect::<Vec<_>>();
+    let count = if param.count_num.unwrap_or(false) {
+        Some(
+            req_dao
+                .web_dao
+                .web_rbac
+                .rbac_dao
+                .role
+                .exclusive_count(&data_param)
+                .await?,
+        )
+    } else {
+        None
+    };
+    Ok(JsonResponse::data(JsonData::body(
+        json!({ "data": res,"total":count}),
+    )))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RoleExclusiveViolationParam {
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub role_id: Option<u64>,
+}
+
+//已存在的违反互斥约束的用户
+pub async fn role_exclusive_violation(
+    param: &RoleExclusiveViolationParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminRbacView {},
+        )
+        .await?;
+    let res = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .exclusive_violation_data(&RoleExclusiveDataParam {
+            user_id: 0,
+            app_id: Some(0),
+            role_id: param.role_id,
+        })
+        .await?;
+    let res = bind_vec_user_info_from_req!(req_dao, res, user_id, false);
+    Ok(JsonResponse::data(JsonData::body(json!({ "data": res }))))
+}
@@ -0,0 +1,158 @@
+use crate::dao::access::RbacAccessCheckEnv;
+use crate::{
+    common::{JsonData, JsonError, JsonResponse, JsonResult, PageParam, UserAuthQueryDao},
+    dao::access::api::system::admin::{CheckAdminRbacEdit, CheckAdminRbacView},
+};
+use lsys_access::dao::AccessSession;
+use lsys_core::fluent_message;
+use lsys_rbac::dao::RoleRequestDataParam;
+use lsys_rbac::model::RbacRoleRequestStatus;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, Deserialize)]
+pub struct RoleRequestListParam {
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub role_id: Option<u64>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub user_id: Option<u64>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_vec_i8")]
+    pub status: Option<Vec<i8>>,
+    pub page: Option<PageParam>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+}
+
+//系统角色的临时授权申请
+pub async fn role_request_data(
+    param: &RoleRequestListParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminRbacView {},
+        )
+        .await?;
+    let status = match param.status {
+        Some(ref tmp) => Some(
+            tmp.iter()
+                .map(|e| RbacRoleRequestStatus::try_from(*e))
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        None => None,
+    };
+    let data_param = RoleRequestDataParam {
+        role_user_id: Some(0),
+        role_app_id: Some(0),
+        role_id: param.role_id,
+        user_id: param.user_id,
+        status: status.as_deref(),
+    };
+    let res = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .request_data(&data_param, param.page.as_ref().map(|e| e.into()).as_ref())
+        .await?;
+    let res = bind_vec_user_info_from_req!(
+        req_dao,
+        res,
+        [user_id: "user_data", audit_user_id: "audit_user_data"],
+        false
+    );
+    let count = if param.count_num.unwrap_or(false) {
+        Some(
+            req_dao
+                .web_dao
+                .web_rbac
+                .rbac_dao
+                .role
+                .request_count(&data_param)
+                .await?,
+        )
+    } else {
+        None
+    };
+    Ok(JsonResponse::data(JsonData::body(
+        json!({ "data": res,"total":count}),
+    )))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RoleRequestAuditParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub id: u64,
+    #[serde(deserialize_with = "crate::common::deserialize_bool")]
+    pub approve: bool,
+    pub note: Option<String>,
+}
+
+pub async fn role_request_audit(
+    param: &RoleRequestAuditParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminRbacEdit {},
+        )
+        .await?;
+    let request = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .find_request_by_id(&param.id)
+        .await?;
+    if request.role_user_id != 0 {
+        return Err(JsonError::Message(fluent_message!(
+            "role-request-not-found",
+            {
+                "id": param.id
+            }
+        )));
+    }
+    let note = param.note.as_deref().unwrap_or_default();
+    if param.approve {
+        let role = req_dao
+            .web_dao
+            .web_rbac
+            .rbac_dao
+            .role
+            .find_by_id(&request.role_id)
+            .await?;
+        let expire_time = req_dao
+            .web_dao
+            .web_rbac
+            .rbac_dao
+            .role
+            .approve_request(
+                &request,
+                &role,
+                auth_data.user_id(),
+                note,
+                Some(&req_dao.req_env),
+            )
+            .await?;
+        Ok(JsonResponse::data(JsonData::body(
+            json!({ "expire_time": expire_time }),
+        )))
+    } else {
+        req_dao
+            .web_dao
+            .web_rbac
+            .rbac_dao
+            .role
+            .reject_request(&request, auth_data.user_id(), note, Some(&req_dao.req_env))
+            .await?;
+        Ok(JsonResponse::default())
+    }
+}
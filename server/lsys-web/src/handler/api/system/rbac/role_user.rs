@@ -32,12 +32,6 @@ pub async fn role_user_add(
 ) -> JsonResult<JsonResponse> {
     let auth_data = req_dao.user_session.read().await.get_session_data().await?;
 
-    req_dao
-        .web_dao
-        .web_rbac
-        .check(&RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env), &CheckAdminRbacEdit {})
-        .await?;
-
     let role = req_dao
         .web_dao
         .web_rbac
@@ -46,6 +40,17 @@ pub async fn role_user_add(
         .find_by_id(&param.role_id)
         .await?;
 
+    let is_delegate = req_dao
+        .web_dao
+        .web_rbac
+        .check_role_user_edit(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminRbacEdit {},
+            &role,
+            0,
+        )
+        .await?;
+
     let user_data = req_dao
         .web_dao
         .web_access
@@ -89,19 +94,28 @@ pub async fn role_user_add(
             }
         }
     }
-    req_dao
-        .web_dao
-        .web_rbac
-        .rbac_dao
-        .role
-        .add_user(
-            &role,
-            &add_user_data,
-            auth_data.user_id(),
-            None,
-            Some(&req_dao.req_env),
-        )
-        .await?;
+    let role_dao = &req_dao.web_dao.web_rbac.rbac_dao.role;
+    if is_delegate {
+        role_dao
+            .add_user(
+                &role,
+                &add_user_data,
+                auth_data.user_id(),
+                None,
+                Some(&req_dao.req_env),
+            )
+            .await?;
+    } else {
+        role_dao
+            .admin_add_user(
+                &role,
+                &add_user_data,
+                auth_data.user_id(),
+                None,
+                Some(&req_dao.req_env),
+            )
+            .await?;
+    }
     Ok(JsonResponse::default())
 }
 
@@ -119,11 +133,6 @@ pub async fn role_user_del(
 ) -> JsonResult<JsonResponse> {
     let auth_data = req_dao.user_session.read().await.get_session_data().await?;
 
-    req_dao
-        .web_dao
-        .web_rbac
-        .check(&RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env), &CheckAdminRbacEdit {})
-        .await?;
     let role = req_dao
         .web_dao
         .web_rbac
@@ -131,19 +140,38 @@ pub async fn role_user_del(
         .role
         .find_by_id(&param.role_id)
         .await?;
-    req_dao
+    let is_delegate = req_dao
         .web_dao
         .web_rbac
-        .rbac_dao
-        .role
-        .del_user(
+        .check_role_user_edit(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminRbacEdit {},
             &role,
-            &param.user_data,
-            auth_data.user_id(),
-            None,
-            Some(&req_dao.req_env),
+            0,
         )
         .await?;
+    let role_dao = &req_dao.web_dao.web_rbac.rbac_dao.role;
+    if is_delegate {
+        role_dao
+            .del_user(
+                &role,
+                &param.user_data,
+                auth_data.user_id(),
+                None,
+                Some(&req_dao.req_env),
+            )
+            .await?;
+    } else {
+        role_dao
+            .admin_del_user(
+                &role,
+                &param.user_data,
+                auth_data.user_id(),
+                None,
+                Some(&req_dao.req_env),
+            )
+            .await?;
+    }
     Ok(JsonResponse::default())
 }
 
@@ -0,0 +1,136 @@
+use crate::common::JsonData;
+use crate::common::JsonResponse;
+use crate::common::JsonResult;
+use crate::common::UserAuthQueryDao;
+use crate::dao::access::api::system::admin::CheckAdminSiteSetting;
+use crate::dao::access::RbacAccessCheckEnv;
+use lsys_access::dao::AccessSession;
+use lsys_setting::dao::NotFoundResult;
+use lsys_setting::dao::SettingKey;
+use lsys_setting::dao::SingleSettingData;
+use lsys_user::dao::login::{LdapConfig, LdapGroupRole};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, Deserialize)]
+pub struct LdapGroupRoleParam {
+    pub group: String,
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub role_id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LdapConfigParam {
+    #[serde(deserialize_with = "crate::common::deserialize_bool")]
+    pub enable: bool,
+    pub url: String,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub tls_skip_verify: Option<bool>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub timeout: Option<u64>,
+    pub bind_dn: String,
+    //为空时保留原密码
+    pub bind_password: Option<String>,
+    pub base_dn: String,
+    pub user_filter: String,
+    pub attr_id: String,
+    pub attr_nickname: String,
+    pub attr_email: String,
+    pub attr_group: String,
+    pub group_role: Option<Vec<LdapGroupRoleParam>>,
+}
+
+pub async fn ldap_config_set(
+    param: &LdapConfigParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminSiteSetting {},
+        )
+        .await?;
+    let old_config = req_dao
+        .web_dao
+        .web_setting
+        .setting_dao
+        .single
+        .load::<LdapConfig>(None)
+        .await
+        .notfound_default()?;
+    let bind_password = match param.bind_password.as_deref() {
+        Some(pw) if !pw.is_empty() => pw.to_string(),
+        _ => old_config.bind_password.clone(),
+    };
+    let config = LdapConfig {
+        enable: param.enable,
+        url: param.url.trim().to_string(),
+        tls_skip_verify: param.tls_skip_verify.unwrap_or(false),
+        timeout: param.timeout.unwrap_or(0),
+        bind_dn: param.bind_dn.trim().to_string(),
+        bind_password,
+        base_dn: param.base_dn.trim().to_string(),
+        user_filter: param.user_filter.trim().to_string(),
+        attr_id: param.attr_id.trim().to_string(),
+        attr_nickname: param.attr_nickname.trim().to_string(),
+        attr_email: param.attr_email.trim().to_string(),
+        attr_group: param.attr_group.trim().to_string(),
+        group_role: param
+            .group_role
+            .as_ref()
+            .map(|e| {
+                e.iter()
+                    .map(|t| LdapGroupRole {
+                        group: t.group.trim().to_string(),
+                        role_id: t.role_id,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default(),
+    };
+    req_dao
+        .web_dao
+        .web_setting
+        .setting_dao
+        .single
+        .save::<LdapConfig>(
+            None,
+            &SingleSettingData {
+                name: LdapConfig::key(),
+                data: &config,
+            },
+            auth_data.user_id(),
+            None,
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::default())
+}
+
+pub async fn ldap_config_get(req_dao: &UserAuthQueryDao) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminSiteSetting {},
+        )
+        .await?;
+    let config = req_dao
+        .web_dao
+        .web_setting
+        .setting_dao
+        .single
+        .load::<LdapConfig>(None)
+        .await
+        .notfound_default()?;
+    let mut config = (*config).clone();
+    config.hide_bind_password();
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "config": config,
+    }))))
+}
@@ -1,3 +1,4 @@
+mod ldap_setting;
 mod site_setting;
 use crate::common::JsonData;
 use crate::common::JsonResponse;
@@ -10,6 +11,7 @@ use lsys_setting::dao::SingleSettingData;
 use lsys_setting::dao::{SettingDecode, SettingEncode, SettingKey};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+pub use ldap_setting::*;
 pub use site_setting::*;
 use crate::dao::access::RbacAccessCheckEnv;
 
@@ -6,7 +6,7 @@ use crate::dao::SiteConfig;
 use crate::dao::SiteConfigData;
 use lsys_access::dao::AccessSession;
 use lsys_setting::dao::NotFoundResult;
-use lsys_user::dao::AccountPasswordConfig;
+use lsys_user::dao::{AccountPasswordConfig, AccountRegisterConfig};
 use serde::Deserialize;
 use serde_json::json;
 use crate::dao::access::RbacAccessCheckEnv;
@@ -19,6 +19,31 @@ pub struct SiteConfigParam {
     pub password_timeout: u64,
     #[serde(deserialize_with = "crate::common::deserialize_bool")]
     pub disable_old_password: bool,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub password_min_length: Option<u64>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub password_require_lower: Option<bool>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub password_require_upper: Option<bool>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub password_require_number: Option<bool>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub password_require_symbol: Option<bool>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub password_disable_account_name: Option<bool>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub password_history_num: Option<u64>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub password_breached_check: Option<bool>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub register_invite_only: Option<bool>,
+    pub register_email_domain: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub register_need_approve: Option<bool>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub register_user_invite: Option<bool>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub register_user_invite_max_num: Option<u64>,
 }
 
 pub async fn site_config_set(
@@ -35,6 +60,18 @@ pub async fn site_config_set(
             &CheckAdminSiteSetting {},
         )
         .await?;
+    //未传入的可选项保留已保存的值
+    let setting_dao = &req_dao.web_dao.web_setting.setting_dao;
+    let password = setting_dao
+        .single
+        .load::<AccountPasswordConfig>(None)
+        .await
+        .notfound_default()?;
+    let register = setting_dao
+        .single
+        .load::<AccountRegisterConfig>(None)
+        .await
+        .notfound_default()?;
     req_dao
         .web_dao
         .web_setting
@@ -42,8 +79,49 @@ pub async fn site_config_set(
             &auth_data,
             &SiteConfigData {
                 site_tips: &param.site_tips,
-                password_timeout: param.password_timeout,
-                disable_old_password: param.disable_old_password,
+                password: &AccountPasswordConfig {
+                    timeout: param.password_timeout,
+                    disable_old_password: param.disable_old_password,
+                    min_length: param.password_min_length.unwrap_or(password.min_length),
+                    require_lower: param
+                        .password_require_lower
+                        .unwrap_or(password.require_lower),
+                    require_upper: param
+                        .password_require_upper
+                        .unwrap_or(password.require_upper),
+                    require_number: param
+                        .password_require_number
+                        .unwrap_or(password.require_number),
+                    require_symbol: param
+                        .password_require_symbol
+                        .unwrap_or(password.require_symbol),
+                    disable_account_name: param
+                        .password_disable_account_name
+                        .unwrap_or(password.disable_account_name),
+                    history_num: param.password_history_num.unwrap_or(password.history_num),
+                    breached_check: param
+                        .password_breached_check
+                        .unwrap_or(password.breached_check),
+                },
+                register: &AccountRegisterConfig {
+                    invite_only: param.register_invite_only.unwrap_or(register.invite_only),
+                    email_domain: param
+                        .register_email_domain
+                        .as_ref()
+                        .map(|e| {
+                            e.iter()
+                                .map(|d| d.trim().trim_start_matches('@').to_lowercase())
+                                .filter(|d| !d.is_empty())
+                                .collect()
+                        })
+                        .unwrap_or_else(|| register.email_domain.clone()),
+                    need_approve: param.register_need_approve.unwrap_or(register.need_approve),
+                    user_invite: param.register_user_invite.unwrap_or(register.user_invite),
+                    user_invite_max_num: param
+                        .register_user_invite_max_num
+                        .map(|e| e as u32)
+                        .unwrap_or(register.user_invite_max_num),
+                },
             },
             Some(&req_dao.req_env),
         )
@@ -78,11 +156,32 @@ pub async fn site_config_get(req_dao: &UserAuthQueryDao) -> JsonResult<JsonRespo
         .load::<AccountPasswordConfig>(None)
         .await
         .notfound_default()?;
+    let register = req_dao
+        .web_dao
+        .web_setting
+        .setting_dao
+        .single
+        .load::<AccountRegisterConfig>(None)
+        .await
+        .notfound_default()?;
     Ok(JsonResponse::data(JsonData::body(json!({
        "config":{
         "site_tips":site_config.site_tips,
         "dis_old_password":password.disable_old_password,
         "timeout":password.timeout,
+        "password_min_length":password.min_length,
+        "password_require_lower":password.require_lower,
+        "password_require_upper":password.require_upper,
+        "password_require_number":password.require_number,
+        "password_require_symbol":password.require_symbol,
+        "password_disable_account_name":password.disable_account_name,
+        "password_history_num":password.history_num,
+        "password_breached_check":password.breached_check,
+        "register_invite_only":register.invite_only,
+        "register_email_domain":register.email_domain,
+        "register_need_approve":register.need_approve,
+        "register_user_invite":register.user_invite,
+        "register_user_invite_max_num":register.user_invite_max_num,
        }
     }))))
 }
@@ -117,3 +117,42 @@ pub async fn user_logout(
     }
     Ok(JsonResponse::default())
 }
+
+#[derive(Debug, Deserialize)]
+pub struct UserSessionLogoutParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub user_id: u64,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub session_id: Option<u64>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub app_id: Option<u64>,
+}
+//下线用户指定登录,未指定时下线全部登录
+pub async fn user_session_logout(
+    param: &UserSessionLogoutParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminUserManage {},
+        )
+        .await?;
+    let auth = &req_dao.web_dao.web_access.access_dao.auth;
+    let total = match param.session_id {
+        Some(session_id) => {
+            auth.logout_session(param.user_id, session_id).await?;
+            1
+        }
+        None => {
+            auth.logout_user_session(param.user_id, param.app_id, None)
+                .await?
+        }
+    };
+    Ok(JsonResponse::data(JsonData::body(
+        json!({ "total": total }),
+    )))
+}
@@ -0,0 +1,30 @@
+use crate::common::JsonData;
+use crate::common::{JsonResponse, JsonResult, UserAuthQueryDao};
+use crate::dao::access::api::system::admin::CheckAdminUserManage;
+use crate::dao::access::RbacAccessCheckEnv;
+use lsys_access::dao::AccessSession;
+use serde_json::json;
+
+//区域数据升级后修正已存储的用户地址
+pub async fn account_address_normalize(req_dao: &UserAuthQueryDao) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminUserManage {},
+        )
+        .await?;
+    let res = req_dao
+        .web_dao
+        .web_user
+        .account
+        .user_address_normalize(auth_data.user_id(), Some(&req_dao.req_env))
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "total": res.total,
+        "change": res.change,
+        "invalid": res.invalid,
+    }))))
+}
@@ -0,0 +1,94 @@
+use crate::common::JsonData;
+use crate::common::{JsonResponse, JsonResult, LimitParam, UserAuthQueryDao};
+use crate::dao::access::api::system::admin::CheckAdminUserManage;
+use crate::dao::access::RbacAccessCheckEnv;
+use lsys_access::dao::AccessSession;
+use lsys_user::model::AccountDeleteStatus;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, Deserialize)]
+pub struct AccountDeleteListParam {
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub account_id: Option<u64>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_i8")]
+    pub status: Option<i8>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+    pub limit: Option<LimitParam>,
+}
+
+//账号注销申请列表
+pub async fn account_delete_list(
+    param: &AccountDeleteListParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminUserManage {},
+        )
+        .await?;
+    let status = match param.status {
+        Some(e) => Some(AccountDeleteStatus::try_from(e)?),
+        None => None,
+    };
+    let delete_dao = &req_dao.web_dao.web_user.user_dao.account_dao.account_delete;
+    let (res, next) = delete_dao
+        .list_data(
+            param.account_id,
+            status,
+            param.limit.as_ref().map(|e| e.into()).as_ref(),
+        )
+        .await?;
+    let count = if param.count_num.unwrap_or(false) {
+        Some(delete_dao.list_count(param.account_id, status).await?)
+    } else {
+        None
+    };
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "data": bind_vec_user_info_from_req!(req_dao, res, add_user_id, false),
+        "next": next,
+        "total": count,
+    }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountDeleteRestoreParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub account_id: u64,
+}
+
+//撤销账号注销申请
+pub async fn account_delete_restore(
+    param: &AccountDeleteRestoreParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminUserManage {},
+        )
+        .await?;
+    let restore = req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_delete
+        .delete_restore(
+            param.account_id,
+            auth_data.user_id(),
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(
+        json!({ "restore": restore }),
+    )))
+}
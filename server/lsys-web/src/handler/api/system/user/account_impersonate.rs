@@ -0,0 +1,75 @@
+use crate::common::{JsonError, JsonResult, UserAuthQueryDao};
+use crate::dao::access::api::system::admin::{CheckAdminBase, CheckAdminUserImpersonate};
+use crate::dao::access::RbacAccessCheckEnv;
+use crate::dao::ShowUserAuthData;
+use crate::handler::api::auth::user_login_finish;
+use lsys_access::dao::AccessSession;
+use lsys_core::fluent_message;
+use lsys_rbac::dao::RbacError;
+use lsys_user::dao::UserAuthToken;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct AccountImpersonateParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub account_id: u64,
+}
+
+//模拟指定账号登录,返回该账号的登录信息
+//模拟登录会话有效期较短,不可续期,禁止敏感操作,其请求均记录到变更日志
+pub async fn account_impersonate(
+    param: &AccountImpersonateParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<(UserAuthToken, ShowUserAuthData)> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminUserImpersonate {},
+        )
+        .await?;
+    //不允许在模拟登录中再次模拟登录
+    auth_data.impersonate_deny()?;
+    let account_dao = &req_dao.web_dao.web_user.user_dao.account_dao.account;
+    let account = account_dao.find_by_id(&param.account_id).await?;
+    let user = account_dao.cache().get_user(&account).await?;
+    let user = req_dao
+        .web_dao
+        .web_access
+        .access_dao
+        .user
+        .cache()
+        .find_by_id(&user.id)
+        .await?;
+    //不允许模拟自己,root及拥有后台管理权限的账号,避免借模拟登录获得其他管理员的权限
+    let target_admin = user.id == auth_data.user_id()
+        || match req_dao
+            .web_dao
+            .web_rbac
+            .check(
+                &RbacAccessCheckEnv::user(&user, &req_dao.req_env),
+                &CheckAdminBase {},
+            )
+            .await
+        {
+            Ok(()) => true,
+            Err(RbacError::Check(_)) => false,
+            Err(err) => return Err(err.into()),
+        };
+    if target_admin {
+        return Err(JsonError::Message(fluent_message!(
+            "account-impersonate-deny",{
+                "account_id":account.id
+            }
+        )));
+    }
+    let session_body = req_dao
+        .web_dao
+        .web_user
+        .auth
+        .user_impersonate(&account, auth_data.user_id(), Some(&req_dao.req_env))
+        .await?;
+    user_login_finish(session_body, req_dao).await
+}
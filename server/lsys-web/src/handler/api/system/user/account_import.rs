@@ -0,0 +1,382 @@
+use crate::common::JsonData;
+use crate::common::JsonError;
+use crate::common::JsonResponse;
+use crate::common::JsonResult;
+use crate::common::LimitParam;
+use crate::common::UserAuthQueryDao;
+use crate::dao::access::api::system::admin::{CheckAdminUserManage, CheckAdminUserPasswordExport};
+use crate::dao::access::RbacAccessCheckEnv;
+use lsys_access::dao::{AccessSession, AccessSessionData};
+use lsys_core::{fluent_message, now_time};
+use lsys_user::dao::{AccountImportData, AccountImportExportItem};
+use lsys_user::model::AccountImportRowStatus;
+use serde::Deserialize;
+use serde_json::json;
+
+//账号批量导入及导出
+//CSV格式首行为字段名,多个邮箱或手机号使用 ; 分隔,手机号格式为 区号-手机号
+
+const ACCOUNT_IMPORT_CSV_HEADER: [&str; 8] = [
+    "ref_id",
+    "nickname",
+    "username",
+    "email",
+    "mobile",
+    "password",
+    "password_format",
+    "password_hash",
+];
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct AccountImportCsvRow {
+    ref_id: String,
+    nickname: String,
+    username: String,
+    email: String,
+    mobile: String,
+    password: String,
+    password_format: String,
+    password_hash: String,
+}
+
+fn account_import_split(data: &str) -> Vec<String> {
+    data.split(';')
+        .map(|e| e.trim())
+        .filter(|e| !e.is_empty())
+        .map(|e| e.to_string())
+        .collect()
+}
+
+#[allow(clippy::result_large_err)]
+fn account_import_parse(format: &str, data: &str) -> JsonResult<Vec<AccountImportData>> {
+    match format {
+        "csv" => {
+            let mut reader = csv::ReaderBuilder::new()
+                .has_headers(true)
+                .trim(csv::Trim::All)
+                .from_reader(data.as_bytes());
+            let mut out = vec![];
+            for row in reader.deserialize::<AccountImportCsvRow>() {
+                let row = row.map_err(|e| {
+                    JsonError::Message(fluent_message!("account-import-parse-error", e.to_string()))
+                })?;
+                out.push(AccountImportData {
+                    ref_id: row.ref_id,
+                    nickname: row.nickname,
+                    username: row.username,
+                    email: account_import_split(&row.email),
+                    mobile: account_import_split(&row.mobile),
+                    password: row.password,
+                    password_format: row.password_format,
+                    password_hash: row.password_hash,
+                });
+            }
+            Ok(out)
+        }
+        "json" => serde_json::from_str::<Vec<AccountImportData>>(data).map_err(|e| {
+            JsonError::Message(fluent_message!("account-import-parse-error", e.to_string()))
+        }),
+        format => Err(JsonError::Message(fluent_message!(
+            "account-import-bad-format",{
+                "format":format
+            }
+        ))),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountImportAddParam {
+    pub name: String,
+    pub format: String, //csv json
+    pub data: String,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub confirm: Option<bool>, //导入的邮箱及手机号是否标记为已验证
+}
+
+//添加导入,返回导入记录,校验失败的行可通过明细查看
+pub async fn account_import_add(
+    param: &AccountImportAddParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminUserManage {},
+        )
+        .await?;
+    let data = account_import_parse(&param.format, &param.data)?;
+    let import = req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_import
+        .import_add(
+            &param.name,
+            &data,
+            param.confirm.unwrap_or(false),
+            auth_data.user_id(),
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(
+        json!({ "data": import }),
+    )))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountImportRunParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub id: u64,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub batch: Option<u64>,
+}
+
+//执行导入,每次最多执行指定批次,未完成时重复调用
+pub async fn account_import_run(
+    param: &AccountImportRunParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminUserManage {},
+        )
+        .await?;
+    let account_import = &req_dao.web_dao.web_user.user_dao.account_dao.account_import;
+    let import = account_import.find_by_id(&param.id).await?;
+    let import = account_import
+        .import_run(
+            &import,
+            param.batch.unwrap_or(10),
+            auth_data.user_id(),
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(
+        json!({ "data": import }),
+    )))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountImportListParam {
+    pub limit: Option<LimitParam>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+}
+
+pub async fn account_import_list(
+    param: &AccountImportListParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminUserManage {},
+        )
+        .await?;
+    let account_import = &req_dao.web_dao.web_user.user_dao.account_dao.account_import;
+    let (data, next) = account_import
+        .import_list_data(param.limit.as_ref().map(|e| e.into()).as_ref())
+        .await?;
+    let count = if param.count_num.unwrap_or(false) {
+        Some(account_import.import_list_count().await?)
+    } else {
+        None
+    };
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "data": data,
+        "next": next,
+        "total": count,
+    }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountImportRowListParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub import_id: u64,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_i8")]
+    pub status: Option<i8>,
+    pub limit: Option<LimitParam>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+}
+
+//导入明细,包含失败原因及导入后的用户ID
+pub async fn account_import_row_list(
+    param: &AccountImportRowListParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminUserManage {},
+        )
+        .await?;
+    let status = match param.status {
+        Some(e) => Some(AccountImportRowStatus::try_from(e)?),
+        None => None,
+    };
+    let account_import = &req_dao.web_dao.web_user.user_dao.account_dao.account_import;
+    let (data, next) = account_import
+        .row_list_data(
+            param.import_id,
+            status,
+            param.limit.as_ref().map(|e| e.into()).as_ref(),
+        )
+        .await?;
+    let count = if param.count_num.unwrap_or(false) {
+        Some(
+            account_import
+                .row_list_count(param.import_id, status)
+                .await?,
+        )
+    } else {
+        None
+    };
+    let out = data
+        .into_iter()
+        .map(|e| {
+            json!({
+                "id":e.id,
+                "import_id":e.import_id,
+                "row_num":e.row_num,
+                "ref_id":e.ref_id,
+                "status":e.status,
+                "account_id":e.account_id,
+                "message":e.message,
+                "change_time":e.change_time,
+            })
+        })
+        .collect::<Vec<_>>();
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "data": out,
+        "next": next,
+        "total": count,
+    }))))
+}
+
+#[allow(clippy::result_large_err)]
+fn account_export_csv(data: &[AccountImportExportItem]) -> JsonResult<String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer
+        .write_record(ACCOUNT_IMPORT_CSV_HEADER)
+        .map_err(|e| JsonError::Message(fluent_message!("account-export-error", e.to_string())))?;
+    for item in data {
+        writer
+            .write_record([
+                item.data.ref_id.as_str(),
+                item.data.nickname.as_str(),
+                item.data.username.as_str(),
+                item.data.email.join(";").as_str(),
+                item.data.mobile.join(";").as_str(),
+                item.data.password.as_str(),
+                item.data.password_format.as_str(),
+                item.data.password_hash.as_str(),
+            ])
+            .map_err(|e| {
+                JsonError::Message(fluent_message!("account-export-error", e.to_string()))
+            })?;
+    }
+    let body = writer
+        .into_inner()
+        .map_err(|e| JsonError::Message(fluent_message!("account-export-error", e.to_string())))?;
+    Ok(String::from_utf8_lossy(&body).to_string())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountBulkExportParam {
+    pub format: String, //csv json
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub start_id: Option<u64>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub limit: Option<u64>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub password: Option<bool>, //是否导出密码HASH
+}
+
+//按用户ID分段导出,导出格式与导入一致
+//返回文件名,文件内容及下次导出的 start_id
+//导出密码HASH需单独授权并完成二次验证
+pub async fn account_bulk_export(
+    param: &AccountBulkExportParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<(String, String, Option<u64>)> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    let with_password = param.password.unwrap_or(false);
+    if with_password {
+        req_dao
+            .web_dao
+            .web_rbac
+            .check(
+                &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+                &CheckAdminUserPasswordExport {},
+            )
+            .await?;
+        req_dao
+            .web_dao
+            .web_access
+            .access_dao
+            .auth
+            .step_up_check(auth_data.session_body())?;
+    } else {
+        req_dao
+            .web_dao
+            .web_rbac
+            .check(
+                &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+                &CheckAdminUserManage {},
+            )
+            .await?;
+    }
+    if !["csv", "json"].contains(&param.format.as_str()) {
+        return Err(JsonError::Message(fluent_message!(
+            "account-import-bad-format",{
+                "format":&param.format
+            }
+        )));
+    }
+    let (data, next) = req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_import
+        .export_data(
+            param.start_id.unwrap_or(0),
+            param.limit.unwrap_or(500),
+            with_password,
+            auth_data.user_id(),
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    let body = if param.format == "csv" {
+        account_export_csv(&data)?
+    } else {
+        serde_json::to_string(&data.iter().map(|e| &e.data).collect::<Vec<_>>()).map_err(|e| {
+            JsonError::Message(fluent_message!("account-export-error", e.to_string()))
+        })?
+    };
+    Ok((
+        format!(
+            "account-{}.{}",
+            now_time().unwrap_or_default(),
+            param.format
+        ),
+        body,
+        next,
+    ))
+}
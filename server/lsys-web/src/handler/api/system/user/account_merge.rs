@@ -0,0 +1,57 @@
+use crate::common::JsonData;
+use crate::common::{JsonResponse, JsonResult, UserAuthQueryDao};
+use crate::dao::access::api::system::admin::CheckAdminUserManage;
+use crate::dao::access::RbacAccessCheckEnv;
+use lsys_access::dao::AccessSession;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, Deserialize)]
+pub struct AccountMergeParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub target_account_id: u64,
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub source_account_id: u64,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub dry_run: Option<bool>,
+}
+
+//合并重复账号,source 合并到 target,dry_run 时仅返回预览
+pub async fn account_merge(
+    param: &AccountMergeParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminUserManage {},
+        )
+        .await?;
+    let account_dao = &req_dao.web_dao.web_user.user_dao.account_dao.account;
+    let target = account_dao.find_by_id(&param.target_account_id).await?;
+    let source = account_dao.find_by_id(&param.source_account_id).await?;
+    let web_account = &req_dao.web_dao.web_user.account;
+    if param.dry_run.unwrap_or(false) {
+        let data = web_account.account_merge_preview(&target, &source).await?;
+        return Ok(JsonResponse::data(JsonData::body(json!({ "data": data }))));
+    }
+    let (source_user_id, data) = web_account
+        .account_merge(
+            &target,
+            &source,
+            auth_data.user_id(),
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    req_dao
+        .web_dao
+        .web_access
+        .access_dao
+        .auth
+        .logout_user_session(source_user_id, None, None)
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(json!({ "data": data }))))
+}
@@ -0,0 +1,289 @@
+use crate::common::JsonData;
+use crate::common::{JsonResponse, JsonResult, LimitParam, UserAuthQueryDao};
+use crate::dao::access::api::system::admin::CheckAdminUserManage;
+use crate::dao::access::RbacAccessCheckEnv;
+use lsys_access::dao::AccessSession;
+use lsys_user::model::{AccountInviteStatus, AccountRegisterStatus};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+#[derive(Debug, Deserialize)]
+pub struct AccountInviteAddParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub max_num: u64,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub expire_time: Option<u64>,
+    pub note: Option<String>,
+}
+
+//管理员发放邀请码
+pub async fn account_invite_add(
+    param: &AccountInviteAddParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminUserManage {},
+        )
+        .await?;
+    let invite = req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_register
+        .invite_add(
+            param.max_num.min(u32::MAX as u64) as u32,
+            param.expire_time.unwrap_or(0),
+            param.note.as_deref().unwrap_or_default(),
+            auth_data.user_id(),
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "id": invite.id,
+        "invite_code": invite.invite_code,
+    }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountInviteListParam {
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub add_user_id: Option<u64>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_i8")]
+    pub status: Option<i8>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+    pub limit: Option<LimitParam>,
+}
+
+//邀请码列表
+pub async fn account_invite_list(
+    param: &AccountInviteListParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminUserManage {},
+        )
+        .await?;
+    let status = match param.status {
+        Some(e) => Some(AccountInviteStatus::try_from(e)?),
+        None => None,
+    };
+    let register_dao = &req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_register;
+    let (res, next) = register_dao
+        .invite_list_data(
+            param.add_user_id,
+            status,
+            param.limit.as_ref().map(|e| e.into()).as_ref(),
+        )
+        .await?;
+    let count = if param.count_num.unwrap_or(false) {
+        Some(
+            register_dao
+                .invite_list_count(param.add_user_id, status)
+                .await?,
+        )
+    } else {
+        None
+    };
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "data": bind_vec_user_info_from_req!(req_dao, res, add_user_id, false),
+        "next": next,
+        "total": count,
+    }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountInviteDisableParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub id: u64,
+}
+
+//停用邀请码
+pub async fn account_invite_disable(
+    param: &AccountInviteDisableParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminUserManage {},
+        )
+        .await?;
+    let register_dao = &req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_register;
+    let invite = register_dao.invite_find_by_id(&param.id).await?;
+    register_dao
+        .invite_disable(&invite, auth_data.user_id(), Some(&req_dao.req_env))
+        .await?;
+    Ok(JsonResponse::default())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountRegisterListParam {
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_i8")]
+    pub status: Option<i8>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+    pub limit: Option<LimitParam>,
+}
+
+//注册审核列表
+pub async fn account_register_list(
+    param: &AccountRegisterListParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminUserManage {},
+        )
+        .await?;
+    let status = match param.status {
+        Some(e) => Some(AccountRegisterStatus::try_from(e)?),
+        None => None,
+    };
+    let account_dao = &req_dao.web_dao.web_user.user_dao.account_dao;
+    let (res, next) = account_dao
+        .account_register
+        .register_list_data(status, param.limit.as_ref().map(|e| e.into()).as_ref())
+        .await?;
+    let count = if param.count_num.unwrap_or(false) {
+        Some(
+            account_dao
+                .account_register
+                .register_list_count(status)
+                .await?,
+        )
+    } else {
+        None
+    };
+    let account_data = account_dao
+        .account
+        .cache()
+        .find_by_ids(&res.iter().map(|e| e.account_id).collect::<Vec<_>>())
+        .await?;
+    let data = res
+        .into_iter()
+        .map(|e| {
+            let account = account_data.get(&e.account_id);
+            json!({
+                "id": e.id,
+                "account_id": e.account_id,
+                "nickname": account.map(|a| a.nickname.as_str()),
+                "invite_id": e.invite_id,
+                "reg_from": e.reg_from,
+                "status": e.status,
+                "message": e.message,
+                "add_time": e.add_time,
+                "change_user_id": e.change_user_id,
+                "change_time": e.change_time,
+            })
+        })
+        .collect::<Vec<Value>>();
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "data": data,
+        "next": next,
+        "total": count,
+    }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountRegisterApproveParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub id: u64,
+}
+
+//审核通过,启用账号
+pub async fn account_register_approve(
+    param: &AccountRegisterApproveParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminUserManage {},
+        )
+        .await?;
+    let register_dao = &req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_register;
+    let register = register_dao.find_by_id(&param.id).await?;
+    register_dao
+        .register_approve(&register, auth_data.user_id(), Some(&req_dao.req_env))
+        .await?;
+    Ok(JsonResponse::default())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountRegisterRejectParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub id: u64,
+    pub message: Option<String>,
+}
+
+//拒绝注册,删除待审核账号
+pub async fn account_register_reject(
+    param: &AccountRegisterRejectParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminUserManage {},
+        )
+        .await?;
+    let account_dao = &req_dao.web_dao.web_user.user_dao.account_dao;
+    let register = account_dao.account_register.find_by_id(&param.id).await?;
+    let account = account_dao.account.find_by_id(&register.account_id).await?;
+    account_dao
+        .account_register
+        .register_reject(
+            &register,
+            param.message.as_deref().unwrap_or_default(),
+            auth_data.user_id(),
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    req_dao
+        .web_dao
+        .web_user
+        .account
+        .user_delete(&account, auth_data.user_id(), Some(&req_dao.req_env))
+        .await?;
+    Ok(JsonResponse::default())
+}
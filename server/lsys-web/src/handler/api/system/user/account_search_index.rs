@@ -0,0 +1,154 @@
+use crate::common::JsonData;
+use crate::common::{JsonResponse, JsonResult, PageParam, UserAuthQueryDao};
+use crate::dao::access::api::system::admin::CheckAdminUserManage;
+use crate::dao::access::RbacAccessCheckEnv;
+use crate::dao::AccountOptionData;
+use lsys_access::dao::AccessSession;
+use lsys_user::model::{AccountEmailStatus, AccountMobileStatus};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, Deserialize)]
+pub struct AccountSearchIndexParam {
+    pub key_word: Option<String>,
+    pub facet: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub reg_start: Option<u64>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub reg_end: Option<u64>,
+    pub page: Option<PageParam>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub name: Option<bool>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub info: Option<bool>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub address: Option<bool>,
+    pub external: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_vec_i8")]
+    pub email: Option<Vec<i8>>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_vec_i8")]
+    pub mobile: Option<Vec<i8>>,
+}
+
+//基于全文索引搜索账号,支持前缀及模糊匹配,分面筛选
+pub async fn account_search_index(
+    param: &AccountSearchIndexParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminUserManage {},
+        )
+        .await?;
+
+    let email = match param.email {
+        Some(ref e) => Some(
+            e.iter()
+                .map(|t| AccountEmailStatus::try_from(*t))
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        None => None,
+    };
+    let mobile = match param.mobile {
+        Some(ref e) => Some(
+            e.iter()
+                .map(|t| AccountMobileStatus::try_from(*t))
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        None => None,
+    };
+    let external = param
+        .external
+        .as_ref()
+        .map(|e| e.iter().map(|s| s.as_str()).collect::<Vec<_>>());
+    let data_option = AccountOptionData {
+        user: true,
+        name: param.name.unwrap_or(false),
+        info: param.info.unwrap_or(false),
+        address: param.address.unwrap_or(false),
+        email: email.as_deref(),
+        external: external.as_ref().map(|e| e.as_ref()),
+        mobile: mobile.as_deref(),
+    };
+
+    let res = req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_search
+        .search(
+            param.key_word.as_deref().unwrap_or_default(),
+            param.facet.as_deref().unwrap_or_default(),
+            (param.reg_start, param.reg_end),
+            param.page.as_ref().map(|e| e.into()).as_ref(),
+        )
+        .await?;
+
+    let user_data = req_dao
+        .web_dao
+        .web_user
+        .account
+        .list_user(&res.account_ids, &data_option)
+        .await?;
+    //保持索引排序
+    let out = res
+        .account_ids
+        .iter()
+        .filter_map(|uid| user_data.get(uid))
+        .map(|udat| {
+            json!({
+                "user":udat.0,
+                "name":udat.1,
+                "info":udat.2,
+                "address":udat.3,
+                "email":udat.4,
+                "external":udat.5,
+                "mobile":udat.6,
+            })
+        })
+        .collect::<Vec<_>>();
+    let facet = res
+        .facets
+        .into_iter()
+        .map(|(name, num)| {
+            json!({
+                "facet":name,
+                "total":num,
+            })
+        })
+        .collect::<Vec<_>>();
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "data": out,
+        "facet": facet,
+        "total": res.total,
+    }))))
+}
+
+//从数据库重建账号搜索索引
+pub async fn account_search_rebuild(req_dao: &UserAuthQueryDao) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckAdminUserManage {},
+        )
+        .await?;
+    let total = req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_search
+        .rebuild()
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "total": total,
+    }))))
+}
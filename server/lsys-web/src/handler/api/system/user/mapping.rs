@@ -10,8 +10,11 @@ use lsys_logger::dao::ChangeLogData;
 use lsys_rbac::dao::RbacDao;
 use lsys_setting::dao::SettingDao;
 use lsys_user::dao::UserDao;
+use lsys_user::model::AccountDeleteStatus;
 use lsys_user::model::AccountEmailStatus;
+use lsys_user::model::AccountInviteStatus;
 use lsys_user::model::AccountMobileStatus;
+use lsys_user::model::AccountRegisterStatus;
 use lsys_user::model::AccountStatus;
 use serde_json::json;
 
@@ -58,6 +61,21 @@ pub async fn mapping_data(req_dao: &UserAuthQueryDao) -> JsonResult<JsonResponse
             status_json_format!(req_dao, AccountStatus::Init),
             status_json_format!(req_dao, AccountStatus::Enable),
         ],
+        "delete_status":vec![
+            status_json_format!(req_dao, AccountDeleteStatus::Pending),
+            status_json_format!(req_dao, AccountDeleteStatus::Restore),
+            status_json_format!(req_dao, AccountDeleteStatus::Finish),
+            status_json_format!(req_dao, AccountDeleteStatus::Fail),
+        ],
+        "invite_status":vec![
+            status_json_format!(req_dao, AccountInviteStatus::Enable),
+            status_json_format!(req_dao, AccountInviteStatus::Disable),
+        ],
+        "register_status":vec![
+            status_json_format!(req_dao, AccountRegisterStatus::Pending),
+            status_json_format!(req_dao, AccountRegisterStatus::Approved),
+            status_json_format!(req_dao, AccountRegisterStatus::Reject),
+        ],
         "change_type": change_types,
     }))))
 }
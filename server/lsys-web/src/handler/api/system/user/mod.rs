@@ -1,9 +1,23 @@
 mod access;
+mod account_address;
+mod account_impersonate;
+mod account_import;
+mod account_delete;
+mod account_merge;
+mod account_register;
 mod account_search;
+mod account_search_index;
 mod change_log;
 mod mapping;
 
 pub use access::*;
+pub use account_address::*;
+pub use account_impersonate::*;
+pub use account_import::*;
+pub use account_delete::*;
+pub use account_merge::*;
+pub use account_register::*;
 pub use account_search::*;
+pub use account_search_index::*;
 pub use change_log::*;
 pub use mapping::*;
@@ -1,17 +1,25 @@
-use crate::common::JsonResponse;
+use crate::common::{JsonData, JsonResponse};
 use crate::common::{JsonResult, UserAuthQueryDao};
 use lsys_access::dao::AccessSession;
 use lsys_access::dao::AccessSessionData;
 use lsys_core::fluent_message;
-use lsys_user::dao::{AccountError, UserAuthToken};
+use lsys_user::dao::{AccountError, AccountToken, UserAuthToken};
 use serde::Deserialize;
+use serde_json::json;
 #[derive(Debug, Deserialize)]
 pub struct DeleteParam {
     pub password: String,
 }
-//删除用户
+//申请注销当前用户,宽限期内重新登录可撤销
 pub async fn delete(param: &DeleteParam, req_dao: &UserAuthQueryDao) -> JsonResult<JsonResponse> {
     let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    req_dao
+        .web_dao
+        .web_access
+        .access_dao
+        .auth
+        .step_up_check(auth_data.session_body())?;
     let account = req_dao
         .web_dao
         .web_user
@@ -19,7 +27,7 @@ pub async fn delete(param: &DeleteParam, req_dao: &UserAuthQueryDao) -> JsonResu
         .account_dao
         .session_account(auth_data.session_body())
         .await?;
-    if req_dao
+    if !req_dao
         .web_dao
         .web_user
         .user_dao
@@ -34,7 +42,7 @@ pub async fn delete(param: &DeleteParam, req_dao: &UserAuthQueryDao) -> JsonResu
         ))
         .into());
     }
-    req_dao
+    let data = req_dao
         .web_dao
         .web_user
         .account
@@ -45,5 +53,7 @@ pub async fn delete(param: &DeleteParam, req_dao: &UserAuthQueryDao) -> JsonResu
         .write()
         .await
         .set_session_token(UserAuthToken::default());
-    Ok(JsonResponse::default())
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "delete_time": data.delete_time,
+    }))))
 }
@@ -0,0 +1,109 @@
+use crate::common::JsonData;
+use crate::common::{JsonResponse, JsonResult};
+use crate::common::{LimitParam, RequestDao, UserAuthQueryDao};
+use lsys_access::dao::{AccessSession, AccessSessionData};
+use lsys_core::fluent_message;
+use lsys_user::dao::{AccountError, AccountToken};
+use lsys_user::model::AccountExportStatus;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+//个人数据导出
+
+//申请导出,导出完成后邮件通知下载
+pub async fn account_export_add(req_dao: &UserAuthQueryDao) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    req_dao
+        .web_dao
+        .web_access
+        .access_dao
+        .auth
+        .step_up_check(auth_data.session_body())?;
+    let account_dao = &req_dao.web_dao.web_user.user_dao.account_dao;
+    let account = account_dao
+        .session_account(auth_data.session_body())
+        .await?;
+    let export = account_dao
+        .account_export
+        .export_add(&account, auth_data.user_id(), Some(&req_dao.req_env))
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(
+        json!({ "id": export.id }),
+    )))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountExportListParam {
+    pub limit: Option<LimitParam>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+}
+
+pub async fn account_export_list(
+    param: &AccountExportListParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let account_id = auth_data.session_body().account_id()?;
+    let export_dao = &req_dao.web_dao.web_user.user_dao.account_dao.account_export;
+    let (res, next) = export_dao
+        .list_data(account_id, param.limit.as_ref().map(|e| e.into()).as_ref())
+        .await?;
+    let count = if param.count_num.unwrap_or(false) {
+        Some(export_dao.list_count(account_id).await?)
+    } else {
+        None
+    };
+    let data = res
+        .into_iter()
+        .map(|e| {
+            //下载令牌只通过邮件发送给用户
+            json!({
+                "id": e.id,
+                "status": e.status,
+                "file_size": e.file_size,
+                "expire_time": e.expire_time,
+                "message": e.message,
+                "add_time": e.add_time,
+                "finish_time": e.finish_time,
+            })
+        })
+        .collect::<Vec<Value>>();
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "data": data,
+        "next": next,
+        "total": count,
+    }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountExportDownloadParam {
+    pub token: String,
+}
+
+//通过邮件中的下载令牌下载导出文件,返回文件名及内容
+pub async fn account_export_download(
+    param: &AccountExportDownloadParam,
+    req_dao: &RequestDao,
+) -> JsonResult<(String, Vec<u8>)> {
+    let export = req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_export
+        .find_by_download_token(&param.token)
+        .await?;
+    if !AccountExportStatus::Finish.eq(export.status) {
+        return Err(AccountError::System(fluent_message!("account-export-expired")).into());
+    }
+    let body = tokio::fs::read(&export.file_path).await.map_err(|err| {
+        AccountError::System(fluent_message!("account-export-file-error", {
+            "id": export.id,
+            "msg": err
+        }))
+    })?;
+    Ok((format!("account-export-{}.zip", export.account_id), body))
+}
@@ -0,0 +1,132 @@
+use crate::common::JsonData;
+use crate::common::{JsonResponse, JsonResult, LimitParam, UserAuthQueryDao};
+use lsys_access::dao::AccessSession;
+use lsys_core::fluent_message;
+use lsys_user::dao::{AccountError, AccountToken};
+use lsys_user::model::AccountInviteStatus;
+use serde::Deserialize;
+use serde_json::json;
+
+//用户发放的注册邀请码
+
+#[derive(Debug, Deserialize)]
+pub struct AccountInviteAddParam {
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub max_num: Option<u64>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub expire_time: Option<u64>,
+    pub note: Option<String>,
+}
+
+pub async fn account_invite_add(
+    param: &AccountInviteAddParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let register_dao = &req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_register;
+    let config = register_dao.config().await?;
+    if !config.user_invite {
+        return Err(AccountError::System(fluent_message!("account-invite-user-disable")).into());
+    }
+    //用户发放的邀请码使用次数不超过配置上限
+    let limit_num = config.user_invite_max_num.max(1);
+    let max_num = param
+        .max_num
+        .map(|e| e.min(limit_num as u64) as u32)
+        .unwrap_or(1);
+    let invite = register_dao
+        .invite_add(
+            max_num,
+            param.expire_time.unwrap_or(0),
+            param.note.as_deref().unwrap_or_default(),
+            auth_data.user_id(),
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "id": invite.id,
+        "invite_code": invite.invite_code,
+    }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountInviteListParam {
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_i8")]
+    pub status: Option<i8>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+    pub limit: Option<LimitParam>,
+}
+
+pub async fn account_invite_list(
+    param: &AccountInviteListParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let status = match param.status {
+        Some(e) => Some(AccountInviteStatus::try_from(e)?),
+        None => None,
+    };
+    let register_dao = &req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_register;
+    let (res, next) = register_dao
+        .invite_list_data(
+            Some(auth_data.user_id()),
+            status,
+            param.limit.as_ref().map(|e| e.into()).as_ref(),
+        )
+        .await?;
+    let count = if param.count_num.unwrap_or(false) {
+        Some(
+            register_dao
+                .invite_list_count(Some(auth_data.user_id()), status)
+                .await?,
+        )
+    } else {
+        None
+    };
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "data": res,
+        "next": next,
+        "total": count,
+    }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountInviteDisableParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub id: u64,
+}
+
+pub async fn account_invite_disable(
+    param: &AccountInviteDisableParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let register_dao = &req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_register;
+    let invite = register_dao.invite_find_by_id(&param.id).await?;
+    if invite.add_user_id != auth_data.user_id() {
+        return Err(AccountError::System(fluent_message!("account-invite-not-found")).into());
+    }
+    register_dao
+        .invite_disable(&invite, auth_data.user_id(), Some(&req_dao.req_env))
+        .await?;
+    Ok(JsonResponse::default())
+}
@@ -1,6 +1,7 @@
 use crate::common::JsonData;
 use crate::common::{LimitParam, UserAuthQueryDao};
 use lsys_access::dao::AccessSession;
+use lsys_user::dao::AccountToken;
 use serde::Deserialize;
 use serde_json::json;
 
@@ -22,6 +23,7 @@ pub async fn login_history(
     req_dao: &UserAuthQueryDao,
 ) -> JsonResult<JsonResponse> {
     let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
     let (data, next) = req_dao
         .web_dao
         .web_user
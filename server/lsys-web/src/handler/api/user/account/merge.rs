@@ -0,0 +1,73 @@
+use crate::common::JsonData;
+use crate::common::{JsonResponse, JsonResult, UserAuthQueryDao};
+use lsys_access::dao::{AccessSession, AccessSessionData};
+use lsys_core::fluent_message;
+use lsys_user::dao::{UserAuthError, UserAuthToken, ACCOUNT_TOKEN_LOGIN_TYPE};
+use serde::Deserialize;
+use serde_json::json;
+use std::str::FromStr;
+
+#[derive(Debug, Deserialize)]
+pub struct AccountMergeParam {
+    pub source_token: String,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub dry_run: Option<bool>,
+}
+
+//把另一个账号合并到当前账号
+//source_token 为另一账号的登录令牌,用于证明拥有该账号,不接受个人访问令牌
+pub async fn account_merge(
+    param: &AccountMergeParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    let user_dao = &req_dao.web_dao.web_user.user_dao;
+    let source_token = UserAuthToken::from_str(&param.source_token)?;
+    let source_data = user_dao.auth_dao.get_session_data(&source_token).await?;
+    if source_data.session().login_type == ACCOUNT_TOKEN_LOGIN_TYPE {
+        return Err(UserAuthError::System(fluent_message!("account-merge-bad-token")).into());
+    }
+    //来源账号需为本人近期认证的登录,不接受模拟登录
+    source_data.session_body().impersonate_deny()?;
+    req_dao
+        .web_dao
+        .web_access
+        .access_dao
+        .auth
+        .step_up_check(source_data.session_body())?;
+    let target = user_dao
+        .account_dao
+        .session_account(auth_data.session_body())
+        .await?;
+    let source = user_dao
+        .account_dao
+        .session_account(source_data.session_body())
+        .await?;
+    let web_account = &req_dao.web_dao.web_user.account;
+    if param.dry_run.unwrap_or(false) {
+        let data = web_account.account_merge_preview(&target, &source).await?;
+        return Ok(JsonResponse::data(JsonData::body(json!({ "data": data }))));
+    }
+    req_dao
+        .web_dao
+        .web_access
+        .access_dao
+        .auth
+        .step_up_check(auth_data.session_body())?;
+    let (source_user_id, data) = web_account
+        .account_merge(
+            &target,
+            &source,
+            auth_data.user_id(),
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    req_dao
+        .web_dao
+        .web_access
+        .access_dao
+        .auth
+        .logout_user_session(source_user_id, None, None)
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(json!({ "data": data }))))
+}
@@ -1,12 +1,24 @@
 mod del;
+mod export;
 mod info;
+mod invite;
 mod login_history;
+mod merge;
 mod password;
+mod personal_token;
 mod profile;
+mod security;
+mod session;
 pub use del::*;
+pub use export::*;
 pub use info::*;
+pub use invite::*;
 pub use login_history::*;
+pub use merge::*;
 pub use password::*;
+pub use personal_token::*;
 pub use profile::*;
+pub use security::*;
+pub use session::*;
 mod mapping;
 pub use mapping::*;
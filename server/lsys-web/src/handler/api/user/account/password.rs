@@ -3,7 +3,8 @@ use crate::{
     common::{JsonResponse, JsonResult, UserAuthQueryDao},
     dao::SetPasswordData,
 };
-use lsys_access::dao::AccessSession;
+use lsys_access::dao::{AccessSession, AccessSessionData};
+use lsys_user::dao::AccountToken;
 use serde::Deserialize;
 use serde_json::json;
 #[derive(Debug, Deserialize)]
@@ -16,6 +17,13 @@ pub async fn set_password(
     req_dao: &UserAuthQueryDao,
 ) -> JsonResult<JsonResponse> {
     let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    req_dao
+        .web_dao
+        .web_access
+        .access_dao
+        .auth
+        .step_up_check(auth_data.session_body())?;
     let pid = req_dao
         .web_dao
         .web_user
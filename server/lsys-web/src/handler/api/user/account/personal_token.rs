@@ -0,0 +1,141 @@
+use crate::common::JsonData;
+use crate::common::{JsonResponse, JsonResult};
+use crate::common::{LimitParam, UserAuthQueryDao};
+use crate::dao::access::RbacAccessCheckEnv;
+use lsys_access::dao::{AccessSession, AccessSessionData};
+use lsys_core::now_time;
+use lsys_user::dao::{AccountToken, AccountTokenAddParam};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+//当前用户的个人访问令牌
+
+//可用授权范围
+pub async fn personal_token_scope(req_dao: &UserAuthQueryDao) -> JsonResult<JsonResponse> {
+    req_dao.user_session.read().await.get_session_data().await?;
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "data": req_dao.web_dao.web_rbac.token_scope_data(),
+    }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PersonalTokenAddParam {
+    pub name: String,
+    pub scope: Vec<String>,
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub expire_day: u64,
+}
+
+//创建令牌,令牌明文只在创建时返回
+pub async fn personal_token_add(
+    param: &PersonalTokenAddParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    req_dao
+        .web_dao
+        .web_access
+        .access_dao
+        .auth
+        .step_up_check(auth_data.session_body())?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .token_scope_check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &param.scope,
+        )
+        .await?;
+    let (token_data, token) = req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_token
+        .add_token(
+            auth_data.session_body(),
+            &AccountTokenAddParam {
+                token_name: &param.name,
+                token_scope: &param.scope,
+                expire_time: now_time()
+                    .unwrap_or_default()
+                    .saturating_add(param.expire_day.saturating_mul(86400)),
+            },
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "id": token_data.id,
+        "token": token,
+        "expire_time": token_data.expire_time,
+    }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PersonalTokenListParam {
+    pub limit: Option<LimitParam>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+}
+
+pub async fn personal_token_list(
+    param: &PersonalTokenListParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let account_id = auth_data.session_body().account_id()?;
+    let token_dao = &req_dao.web_dao.web_user.user_dao.account_dao.account_token;
+    let (res, next) = token_dao
+        .list_data(account_id, param.limit.as_ref().map(|e| e.into()).as_ref())
+        .await?;
+    let count = if param.count_num.unwrap_or(false) {
+        Some(token_dao.list_count(account_id).await?)
+    } else {
+        None
+    };
+    let data = res
+        .into_iter()
+        .map(|e| {
+            json!({
+                "id": e.id,
+                "name": e.token_name,
+                "scope": AccountToken::token_scope(&e),
+                "expire_time": e.expire_time,
+                "last_used_time": e.last_used_time,
+                "last_used_ip": e.last_used_ip,
+                "add_time": e.add_time,
+            })
+        })
+        .collect::<Vec<Value>>();
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "data": data,
+        "next": next,
+        "total": count,
+    }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PersonalTokenDelParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub id: u64,
+}
+
+//撤销令牌
+pub async fn personal_token_del(
+    param: &PersonalTokenDelParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_token
+        .del_token(auth_data.session_body(), param.id, Some(&req_dao.req_env))
+        .await?;
+    Ok(JsonResponse::default())
+}
@@ -23,7 +23,7 @@ pub async fn email_add(
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::user(auth_data.user(), &req_dao.req_env),
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
             &CheckUserEmailEdit {
                 res_user_id: auth_data.user_id(),
             },
@@ -59,7 +59,7 @@ pub async fn email_send_code(
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::user(auth_data.user(), &req_dao.req_env),
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
             &CheckUserEmailEdit {
                 res_user_id: auth_data.user_id(),
             },
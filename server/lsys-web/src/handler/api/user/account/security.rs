@@ -0,0 +1,147 @@
+use crate::common::JsonData;
+use crate::common::{JsonResponse, JsonResult};
+use crate::common::{LimitParam, UserAuthQueryDao};
+use lsys_access::dao::{AccessSession, AccessSessionData};
+use lsys_user::dao::AccountToken;
+use lsys_user::model::AccountSecurityEventStatus;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+//当前用户的登录设备及异常登录事件
+
+pub async fn login_device_list(req_dao: &UserAuthQueryDao) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let res = req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_security
+        .device_data(auth_data.session_body().account_id()?)
+        .await?;
+    let data = res
+        .into_iter()
+        .map(|e| {
+            json!({
+                "id": e.id,
+                "device_name": e.device_name,
+                "login_ip": e.login_ip,
+                "login_city": e.login_city,
+                "login_num": e.login_num,
+                "last_time": e.last_time,
+                "add_time": e.add_time,
+            })
+        })
+        .collect::<Vec<Value>>();
+    Ok(JsonResponse::data(JsonData::body(json!({ "data": data }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SecurityEventListParam {
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_i8")]
+    pub status: Option<i8>,
+    pub limit: Option<LimitParam>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+}
+
+pub async fn security_event_list(
+    param: &SecurityEventListParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let account_id = auth_data.session_body().account_id()?;
+    let status = if let Some(e) = param.status {
+        Some(match AccountSecurityEventStatus::try_from(e) {
+            Ok(ts) => ts,
+            Err(err) => return Err(err.into()),
+        })
+    } else {
+        None
+    };
+    let security_dao = &req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_security;
+    let (res, next) = security_dao
+        .event_data(
+            account_id,
+            status,
+            param.limit.as_ref().map(|e| e.into()).as_ref(),
+        )
+        .await?;
+    let count = if param.count_num.unwrap_or(false) {
+        Some(security_dao.event_count(account_id, status).await?)
+    } else {
+        None
+    };
+    let data = res
+        .into_iter()
+        .map(|e| {
+            json!({
+                "id": e.id,
+                "event_type": e.event_type.split(',').collect::<Vec<_>>(),
+                "event_data": serde_json::from_str::<Value>(&e.event_data).unwrap_or_default(),
+                "device_name": e.device_name,
+                "login_ip": e.login_ip,
+                "login_city": e.login_city,
+                "status": e.status,
+                "add_time": e.add_time,
+                "change_time": e.change_time,
+            })
+        })
+        .collect::<Vec<Value>>();
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "data": data,
+        "next": next,
+        "total": count,
+    }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SecurityEventParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub id: u64,
+}
+
+//确认为本人登录
+pub async fn security_event_confirm(
+    param: &SecurityEventParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_security
+        .event_confirm(auth_data.session_body(), param.id, Some(&req_dao.req_env))
+        .await?;
+    Ok(JsonResponse::default())
+}
+
+//非本人登录,下线除当前外的全部登录
+pub async fn security_event_secure(
+    param: &SecurityEventParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let total = req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_security
+        .event_secure(auth_data.session_body(), param.id, Some(&req_dao.req_env))
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(
+        json!({ "total": total }),
+    )))
+}
@@ -0,0 +1,208 @@
+use crate::common::JsonData;
+use crate::common::{CaptchaParam, JsonError, JsonResponse, JsonResult};
+use crate::common::{LimitParam, UserAuthQueryDao};
+use lsys_access::dao::{AccessSession, AccessSessionData, SessionDataParam};
+use lsys_core::fluent_message;
+use lsys_user::dao::{
+    login::{EmailCodeLogin, MobileCodeLogin, PasswordReauth},
+    AccountToken,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+//当前用户的登录设备
+
+#[derive(Debug, Deserialize)]
+pub struct SessionListParam {
+    pub limit: Option<LimitParam>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+}
+
+pub async fn session_list(
+    param: &SessionListParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let session_param = SessionDataParam {
+        app_id: None,
+        oauth_app_id: None,
+        user_id: Some(auth_data.user_id()),
+        is_enable: Some(true),
+    };
+    let (res, next) = req_dao
+        .web_dao
+        .web_access
+        .access_dao
+        .user
+        .session_data(
+            &session_param,
+            param.limit.as_ref().map(|e| e.into()).as_ref(),
+        )
+        .await?;
+    let count = if param.count_num.unwrap_or(false) {
+        Some(
+            req_dao
+                .web_dao
+                .web_access
+                .access_dao
+                .user
+                .session_count(&session_param)
+                .await?,
+        )
+    } else {
+        None
+    };
+    let session_id = auth_data.session_body().session_id();
+    let data = res
+        .into_iter()
+        .map(|e| {
+            json!({
+                "id": e.id,
+                "app_id": e.app_id,
+                "oauth_app_id": e.oauth_app_id,
+                "login_type": e.login_type,
+                "login_ip": e.login_ip,
+                "device_id": e.device_id,
+                "device_name": e.device_name,
+                "user_agent": e.user_agent,
+                "add_time": e.add_time,
+                "last_time": e.last_time,
+                "expire_time": e.expire_time,
+                "is_current": e.id == session_id,
+            })
+        })
+        .collect::<Vec<Value>>();
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "data": data,
+        "next": next,
+        "total": count,
+    }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionLogoutParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub session_id: u64,
+}
+
+//下线指定登录设备
+pub async fn session_logout(
+    param: &SessionLogoutParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    req_dao
+        .web_dao
+        .web_access
+        .access_dao
+        .auth
+        .logout_session(auth_data.user_id(), param.session_id)
+        .await?;
+    Ok(JsonResponse::default())
+}
+
+//下线除当前外的全部登录设备
+pub async fn session_logout_other(req_dao: &UserAuthQueryDao) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let total = req_dao
+        .web_dao
+        .web_access
+        .access_dao
+        .auth
+        .logout_user_session(
+            auth_data.user_id(),
+            None,
+            Some(auth_data.session_body().session_id()),
+        )
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(
+        json!({ "total": total }),
+    )))
+}
+
+//重新验证方式,未设置密码的账号使用邮箱或手机验证码
+//验证码通过登录验证码接口发送
+#[derive(Debug, Deserialize)]
+pub struct SessionReauthParam {
+    pub password: Option<String>,
+    pub email: Option<String>,
+    pub area_code: Option<String>,
+    pub mobile: Option<String>,
+    pub code: Option<String>,
+    pub captcha: Option<CaptchaParam>,
+}
+
+//重新验证身份,用于修改密码等敏感操作前
+pub async fn session_reauth(
+    param: &SessionReauthParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let web_user = &req_dao.web_dao.web_user;
+    let account_dao = &web_user.user_dao.account_dao;
+    let session_body = match (
+        param.password.as_deref(),
+        param.email.as_deref(),
+        param.mobile.as_deref(),
+        param.code.as_deref(),
+    ) {
+        (Some(password), _, _, _) => {
+            let account = account_dao
+                .session_account(auth_data.session_body())
+                .await?;
+            web_user
+                .auth
+                .user_reauth(
+                    &PasswordReauth::new(account_dao.clone(), account, password).await?,
+                    param.captcha.as_ref(),
+                    auth_data.session_body(),
+                    Some(&req_dao.req_env),
+                )
+                .await?
+        }
+        (None, Some(email), _, Some(code)) => {
+            web_user
+                .auth
+                .user_reauth(
+                    &EmailCodeLogin::new(
+                        req_dao.web_dao.redis.clone(),
+                        account_dao.clone(),
+                        email,
+                        code,
+                    )
+                    .await?,
+                    param.captcha.as_ref(),
+                    auth_data.session_body(),
+                    Some(&req_dao.req_env),
+                )
+                .await?
+        }
+        (None, None, Some(mobile), Some(code)) => {
+            web_user
+                .auth
+                .user_reauth(
+                    &MobileCodeLogin::new(
+                        req_dao.web_dao.redis.clone(),
+                        account_dao.clone(),
+                        param.area_code.as_deref().unwrap_or_default(),
+                        mobile,
+                        code,
+                    )
+                    .await?,
+                    param.captcha.as_ref(),
+                    auth_data.session_body(),
+                    Some(&req_dao.req_env),
+                )
+                .await?
+        }
+        _ => return Err(JsonError::Message(fluent_message!("auth-reauth-param-bad"))),
+    };
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "auth_time": session_body.session().auth_time,
+    }))))
+}
@@ -2,7 +2,6 @@ use crate::common::JsonData;
 use crate::common::JsonResult;
 use crate::common::{JsonResponse, UserAuthQueryDao};
 use crate::dao::access::api::system::user::CheckUserAppEdit;
-use crate::dao::access::RbacAccessCheckEnv;
 use lsys_access::dao::AccessSession;
 use lsys_app::dao::AppDataParam;
 use serde::Deserialize;
@@ -29,7 +28,11 @@ pub async fn change(param: &ChangeParam, req_dao: &UserAuthQueryDao) -> JsonResu
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserAppEdit {
                 res_user_id: app.user_id,
             },
@@ -97,7 +100,11 @@ pub async fn app_secret_add(
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserAppEdit {
                 res_user_id: app.user_id,
             },
@@ -151,7 +158,11 @@ pub async fn app_secret_change(
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserAppEdit {
                 res_user_id: app.user_id,
             },
@@ -203,7 +214,11 @@ pub async fn app_secret_del(
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserAppEdit {
                 res_user_id: app.user_id,
             },
@@ -252,7 +267,11 @@ pub async fn notify_secret_change(
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserAppEdit {
                 res_user_id: app.user_id,
             },
@@ -1,7 +1,6 @@
 use crate::common::{JsonError, JsonResult};
 use crate::common::{JsonResponse, UserAuthQueryDao};
 use crate::dao::access::api::system::user::CheckUserAppEdit;
-use crate::dao::access::RbacAccessCheckEnv;
 use lsys_access::dao::AccessSession;
 use lsys_app::model::AppRequestStatus;
 use lsys_core::fluent_message;
@@ -49,7 +48,11 @@ pub async fn confirm(param: &ConfirmParam, req_dao: &UserAuthQueryDao) -> JsonRe
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&parent_app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserAppEdit {
                 res_user_id: parent_app.user_id,
             },
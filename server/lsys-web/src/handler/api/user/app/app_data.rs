@@ -4,9 +4,9 @@ use crate::{
     common::{JsonError, JsonResponse, JsonResult, PageParam, UserAuthQueryDao},
     dao::access::api::system::user::CheckUserAppView,
 };
-use lsys_access::dao::AccessSession;
+use lsys_access::dao::{AccessSession, AccessSessionData};
 use lsys_app::dao::{AppRequestParam, UserParentAppDataParam};
-use lsys_app::model::AppRequestType;
+use lsys_app::model::{AppOrgMemberRole, AppRequestType};
 use lsys_app::{
     dao::{AppAttrParam, AppRequestData, UserAppDataParam},
     model::{AppRequestStatus, AppStatus},
@@ -24,6 +24,7 @@ pub struct ShowAppRecord {
     pub client_id: String,
     pub status: i8,
     pub user_id: u64,
+    pub org_id: u64,
     pub change_time: u64,
     pub change_user_id: u64,
     pub parent_app_id: u64,
@@ -45,6 +46,8 @@ pub struct UserAppListParam {
     pub app_id: Option<u64>, //过滤指定APP
     #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
     pub parent_app_id: Option<u64>, //获取指定父APP的子APP
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub org_id: Option<u64>, //0 仅个人应用,>0 获取指定组织的应用
     #[serde(default, deserialize_with = "crate::common::deserialize_option_i8")]
     pub status: Option<i8>,
     pub client_id: Option<String>,
@@ -101,6 +104,7 @@ pub async fn app_list(
         like_client_id: param.client_id.as_deref(),
         client_id: None,
         app_id: param.app_id,
+        org_id: param.org_id,
     };
     let app_attr = AppAttrParam {
         inner_feature: param.attr_inner_feature.unwrap_or(true),
@@ -124,6 +128,16 @@ pub async fn app_list(
             },
         )
         .await?;
+    if let Some(org_id) = param.org_id.filter(|e| *e > 0) {
+        let org = req_dao.web_dao.web_app.app_dao.org.find_by_id(org_id).await?;
+        req_dao
+            .web_dao
+            .web_app
+            .app_dao
+            .org
+            .member_role_check(&org, auth_data.user_id(), AppOrgMemberRole::Member)
+            .await?;
+    }
     let appdata = req_dao
         .web_dao
         .web_app
@@ -144,6 +158,7 @@ pub async fn app_list(
             client_id: e.0.client_id,
             status: e.0.status,
             user_id: e.0.user_id,
+            org_id: e.0.org_id,
             change_time: e.0.change_time,
             change_user_id: e.0.change_user_id,
             parent_app_id: e.0.parent_app_id,
@@ -333,6 +348,12 @@ pub async fn secret_view(
             },
         )
         .await?;
+    req_dao
+        .web_dao
+        .web_access
+        .access_dao
+        .auth
+        .step_up_check(auth_data.session_body())?;
     let mut out_data = Map::new();
     if param.app_secret {
         let app_secret_data = req_dao
@@ -410,12 +431,22 @@ pub async fn sub_app_secret_view(
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&parent_app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserAppView {
                 res_user_id: parent_app.user_id,
             },
         )
         .await?;
+    req_dao
+        .web_dao
+        .web_access
+        .access_dao
+        .auth
+        .step_up_check(auth_data.session_body())?;
     let mut out_data = Map::new();
     if param.app_secret {
         let app_secret_data = req_dao
@@ -513,7 +544,11 @@ pub async fn request_list(
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserAppView {
                 res_user_id: app.user_id,
             },
@@ -689,7 +724,11 @@ pub async fn sub_request_list(
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserAppView {
                 res_user_id: app.user_id,
             },
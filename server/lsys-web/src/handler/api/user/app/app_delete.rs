@@ -1,7 +1,6 @@
 use crate::common::JsonResult;
 use crate::common::{JsonResponse, UserAuthQueryDao};
 use crate::dao::access::api::system::user::CheckUserAppEdit;
-use crate::dao::access::RbacAccessCheckEnv;
 
 use lsys_access::dao::AccessSession;
 use serde::Deserialize;
@@ -26,7 +25,11 @@ pub async fn delete(param: &DeleteParam, req_dao: &UserAuthQueryDao) -> JsonResu
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserAppEdit {
                 res_user_id: app.user_id,
             },
@@ -1,7 +1,6 @@
 use crate::common::{JsonError, JsonResult};
 use crate::common::{JsonResponse, UserAuthQueryDao};
 use crate::dao::access::api::system::user::CheckUserAppEdit;
-use crate::dao::access::RbacAccessCheckEnv;
 
 use lsys_access::dao::AccessSession;
 use lsys_app::model::AppRequestStatus;
@@ -32,7 +31,11 @@ pub async fn request_exter_feature(
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserAppEdit {
                 res_user_id: app.user_id,
             },
@@ -53,6 +56,13 @@ pub async fn request_exter_feature(
                     .await?;
                 featch_key.push(fk.as_str());
             }
+            crate::handler::APP_FEATURE_SCIM => {
+                //SCIM 同步系统用户,子应用不可开通
+                if app.parent_app_id > 0 {
+                    return Err(JsonError::Message(fluent_message!("scim-not-sub-app")));
+                }
+                featch_key.push(fk.as_str());
+            }
             tmp => {
                 featch_key.push(tmp);
             }
@@ -138,7 +148,11 @@ pub async fn confirm_exter_feature(
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&parent_app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserAppEdit {
                 res_user_id: parent_app.user_id,
             },
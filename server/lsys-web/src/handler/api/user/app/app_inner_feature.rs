@@ -2,7 +2,6 @@ use crate::common::JsonResult;
 
 use crate::common::{JsonResponse, UserAuthQueryDao};
 use crate::dao::access::api::system::user::CheckUserAppEdit;
-use crate::dao::access::RbacAccessCheckEnv;
 use lsys_access::dao::AccessSession;
 use serde::Deserialize;
 
@@ -29,7 +28,11 @@ pub async fn request_inner_feature_exter_login_request(
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserAppEdit {
                 res_user_id: app.user_id,
             },
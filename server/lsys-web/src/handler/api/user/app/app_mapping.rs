@@ -5,6 +5,7 @@ use crate::common::UserAuthQueryDao;
 use crate::handler::APP_FEATURE_BARCODE;
 use crate::handler::APP_FEATURE_MAIL;
 use crate::handler::APP_FEATURE_RBAC;
+use crate::handler::APP_FEATURE_SCIM;
 use crate::handler::APP_FEATURE_SMS;
 use lsys_app::dao::SUB_APP_SECRET_NOTIFY_METHOD;
 use lsys_app::model::AppNotifyDataStatus;
@@ -29,6 +30,7 @@ pub async fn mapping_data(req_dao: &UserAuthQueryDao) -> JsonResult<JsonResponse
             const_json_format!(req_dao, APP_FEATURE_MAIL),
             const_json_format!(req_dao, APP_FEATURE_BARCODE),
             const_json_format!(req_dao, APP_FEATURE_RBAC),
+            const_json_format!(req_dao, APP_FEATURE_SCIM),
         ]
     };
     Ok(JsonResponse::data(JsonData::body(json!({
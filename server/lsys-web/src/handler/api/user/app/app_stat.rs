@@ -2,7 +2,6 @@ use crate::common::JsonData;
 use crate::common::UserAuthQueryDao;
 use crate::common::{JsonResponse, JsonResult};
 use crate::dao::access::api::system::user::CheckUserAppView;
-use crate::dao::access::RbacAccessCheckEnv;
 use chrono::{Duration, NaiveDate};
 use lsys_access::dao::AccessSession;
 use lsys_app::model::{AppNotifyDataStatus, AppRequestStatus, AppStatus};
@@ -271,7 +270,11 @@ pub async fn stat(param: &AppStatParam, req_dao: &UserAuthQueryDao) -> JsonResul
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserAppView {
                 res_user_id: app.user_id,
             },
@@ -0,0 +1,237 @@
+use crate::common::JsonData;
+use crate::common::{JsonError, JsonResponse, JsonResult, PageParam, UserAuthQueryDao};
+use crate::dao::access::api::system::user::{CheckUserAppEdit, CheckUserAppView};
+use lsys_access::dao::{AccessSession, SessionBody};
+use lsys_app::model::{AppModel, AppOrgMemberRole, AppTransferStatus};
+use lsys_core::fluent_message;
+use lsys_user::dao::AccountToken;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize)]
+pub struct TransferParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub app_id: u64,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub to_user_id: Option<u64>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub to_org_id: Option<u64>,
+}
+
+//检测当前用户可管理应用
+async fn transfer_app_check(
+    app: &AppModel,
+    session_body: &SessionBody,
+    req_dao: &UserAuthQueryDao,
+    edit: bool,
+) -> JsonResult<()> {
+    let check_env = req_dao
+        .web_dao
+        .web_app
+        .app_check_env(app, session_body, &req_dao.req_env)
+        .await?;
+    let web_rbac = &req_dao.web_dao.web_rbac;
+    if edit {
+        web_rbac
+            .check(
+                &check_env,
+                &CheckUserAppEdit {
+                    res_user_id: app.user_id,
+                },
+            )
+            .await?;
+    } else {
+        web_rbac
+            .check(
+                &check_env,
+                &CheckUserAppView {
+                    res_user_id: app.user_id,
+                },
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+//转移应用所有权,在个人与组织间转移
+//转给其他用户时需对方接受,返回待确认的转移记录ID
+pub async fn transfer(
+    param: &TransferParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    let app_dao = &req_dao.web_dao.web_app.app_dao;
+    let app = app_dao.app.find_by_id(param.app_id).await?;
+    transfer_app_check(&app, &auth_data, req_dao, true).await?;
+    let to_org_id = param.to_org_id.unwrap_or(0);
+    //移出组织需为原组织管理员
+    if app.org_id > 0 && app.org_id != to_org_id {
+        let org = app_dao.org.find_by_id(app.org_id).await?;
+        app_dao
+            .org
+            .member_role_check(&org, auth_data.user_id(), AppOrgMemberRole::Admin)
+            .await?;
+    }
+    let to_user_id = param.to_user_id.unwrap_or_else(|| auth_data.user_id());
+    let to_user = req_dao
+        .web_dao
+        .web_access
+        .access_dao
+        .user
+        .cache()
+        .find_by_id(&to_user_id)
+        .await?;
+    //仅内置账号可持有应用
+    if to_user.app_id > 0 {
+        return Err(JsonError::Message(fluent_message!("app-transfer-bad-user")));
+    }
+    let transfer = app_dao
+        .org
+        .app_transfer(
+            &app,
+            to_user.id,
+            to_org_id,
+            auth_data.user_id(),
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "transfer_id": transfer.map(|e| e.id).unwrap_or_default(),
+    }))))
+}
+
+#[derive(Deserialize)]
+pub struct TransferListParam {
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub app_id: Option<u64>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_i8")]
+    pub status: Option<i8>,
+    pub page: Option<PageParam>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+}
+
+//指定应用时列出该应用发起的转移,否则列出转给当前用户的转移
+pub async fn transfer_list(
+    param: &TransferListParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    let app_dao = &req_dao.web_dao.web_app.app_dao;
+    let status = match param.status {
+        Some(e) => Some(AppTransferStatus::try_from(e)?),
+        None => None,
+    };
+    let (app_id, to_user_id) = match param.app_id {
+        Some(app_id) => {
+            let app = app_dao.app.find_by_id(app_id).await?;
+            transfer_app_check(&app, &auth_data, req_dao, false).await?;
+            (Some(app.id), None)
+        }
+        None => {
+            AccountToken::session_deny(&auth_data)?;
+            (None, Some(auth_data.user_id()))
+        }
+    };
+    let res = app_dao
+        .org
+        .transfer_data(
+            app_id,
+            to_user_id,
+            status,
+            param.page.as_ref().map(|e| e.into()).as_ref(),
+        )
+        .await?
+        .into_iter()
+        .map(|e| {
+            json!({
+                "id": e.id,
+                "app_id": e.app_id,
+                "from_user_id": e.from_user_id,
+                "from_org_id": e.from_org_id,
+                "to_user_id": e.to_user_id,
+                "status": e.status,
+                "user_id": e.user_id,
+                "add_time": e.add_time,
+                "change_time": e.change_time,
+            })
+        })
+        .collect::<Vec<_>>();
+    let total = if param.count_num.unwrap_or(false) {
+        Some(
+            app_dao
+                .org
+                .transfer_count(app_id, to_user_id, status)
+                .await?,
+        )
+    } else {
+        None
+    };
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "data": res,
+        "total": total,
+    }))))
+}
+
+#[derive(Deserialize)]
+pub struct TransferConfirmParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub transfer_id: u64,
+}
+
+//接收用户接受转移
+pub async fn transfer_accept(
+    param: &TransferConfirmParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let org_dao = &req_dao.web_dao.web_app.app_dao.org;
+    let transfer = org_dao.transfer_find_by_id(param.transfer_id).await?;
+    org_dao
+        .transfer_accept(&transfer, auth_data.user_id(), Some(&req_dao.req_env))
+        .await?;
+    Ok(JsonResponse::default())
+}
+
+//接收用户拒绝转移
+pub async fn transfer_reject(
+    param: &TransferConfirmParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let org_dao = &req_dao.web_dao.web_app.app_dao.org;
+    let transfer = org_dao.transfer_find_by_id(param.transfer_id).await?;
+    org_dao
+        .transfer_close(
+            &transfer,
+            AppTransferStatus::Reject,
+            auth_data.user_id(),
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::default())
+}
+
+//可管理应用的用户取消转移
+pub async fn transfer_cancel(
+    param: &TransferConfirmParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    let app_dao = &req_dao.web_dao.web_app.app_dao;
+    let transfer = app_dao.org.transfer_find_by_id(param.transfer_id).await?;
+    let app = app_dao.app.find_by_id(transfer.app_id).await?;
+    transfer_app_check(&app, &auth_data, req_dao, true).await?;
+    app_dao
+        .org
+        .transfer_close(
+            &transfer,
+            AppTransferStatus::Cancel,
+            auth_data.user_id(),
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::default())
+}
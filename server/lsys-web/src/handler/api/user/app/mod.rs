@@ -10,6 +10,7 @@ mod app_notify;
 mod app_oauth_client;
 mod app_oauth_server;
 mod app_stat;
+mod app_transfer;
 mod sub_app;
 
 pub use app_add::*;
@@ -24,4 +25,5 @@ pub use app_notify::*;
 pub use app_oauth_client::*;
 pub use app_oauth_server::*;
 pub use app_stat::*;
+pub use app_transfer::*;
 pub use sub_app::*;
@@ -38,7 +38,11 @@ pub async fn sub_app_request(
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserAppEdit {
                 res_user_id: app.user_id,
             },
@@ -81,6 +85,7 @@ pub async fn sub_app_notify_get_config(
         client_id: None,
         like_client_id: None,
         app_id: Some(param.app_id),
+        org_id: None,
     };
     let apps = req_dao
         .web_dao
@@ -148,7 +153,11 @@ pub async fn sub_app_notify_set_config(
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserAppEdit {
                 res_user_id: app.user_id,
             },
@@ -226,7 +235,11 @@ pub async fn sub_app_list(
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserAppView {
                 res_user_id: app.user_id,
             },
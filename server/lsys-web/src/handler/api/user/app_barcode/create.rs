@@ -51,7 +51,11 @@ pub async fn create_config_add(
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserBarCodeEdit {
                 res_user_id: app.user_id,
             },
@@ -156,7 +160,11 @@ pub async fn create_config_edit(
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserBarCodeEdit {
                 res_user_id: app.user_id,
             },
@@ -337,7 +337,11 @@ pub async fn mailer_message_send(
         .web_dao
         .web_rbac
         .check(
-            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &req_dao
+                .web_dao
+                .web_app
+                .app_check_env(&app, &auth_data, &req_dao.req_env)
+                .await?,
             &CheckUserAppSenderMailSend {
                 res_user_id: app.user_id,
             },
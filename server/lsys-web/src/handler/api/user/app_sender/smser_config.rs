@@ -193,6 +193,7 @@ pub async fn smser_notify_get_config(req_dao: &UserAuthQueryDao) -> JsonResult<J
         client_id: None,
         like_client_id: None,
         app_id: None,
+        org_id: None,
     };
     let apps = req_dao
         .web_dao
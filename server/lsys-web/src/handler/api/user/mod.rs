@@ -3,4 +3,5 @@ pub mod app;
 #[cfg(feature = "barcode")]
 pub mod app_barcode;
 pub mod app_sender;
+pub mod org;
 pub mod rbac;
@@ -0,0 +1,176 @@
+use crate::common::JsonData;
+use crate::common::{JsonError, JsonResponse, JsonResult, PageParam, UserAuthQueryDao};
+use lsys_access::dao::{AccessSession, AccessSessionData};
+use lsys_app::model::{AppOrgInviteStatus, AppOrgMemberRole};
+use lsys_core::fluent_message;
+use lsys_user::dao::AccountToken;
+use lsys_user::model::AccountEmailStatus;
+use serde::Deserialize;
+use serde_json::json;
+
+//邀请有效期
+const ORG_INVITE_TIMEOUT: u64 = 7 * 24 * 3600;
+
+#[derive(Deserialize)]
+pub struct OrgInviteAddParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub org_id: u64,
+    pub email: String,
+    #[serde(deserialize_with = "crate::common::deserialize_i8")]
+    pub role: i8,
+}
+
+pub async fn org_invite_add(
+    param: &OrgInviteAddParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let role = AppOrgMemberRole::try_from(param.role)?;
+    let org_dao = &req_dao.web_dao.web_app.app_dao.org;
+    let org = org_dao.find_by_id(param.org_id).await?;
+    let invite = org_dao
+        .invite_add(
+            &org,
+            &param.email,
+            role,
+            ORG_INVITE_TIMEOUT,
+            auth_data.user_id(),
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    req_dao
+        .web_dao
+        .app_sender
+        .mailer
+        .send_org_invite(
+            &invite.email,
+            &org.name,
+            &invite.invite_code,
+            invite.time_out,
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(
+        json!({ "id": invite.id }),
+    )))
+}
+
+#[derive(Deserialize)]
+pub struct OrgInviteListParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub org_id: u64,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_i8")]
+    pub status: Option<i8>,
+    pub page: Option<PageParam>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+}
+
+pub async fn org_invite_list(
+    param: &OrgInviteListParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let status = match param.status {
+        Some(e) => Some(AppOrgInviteStatus::try_from(e)?),
+        None => None,
+    };
+    let org_dao = &req_dao.web_dao.web_app.app_dao.org;
+    let org = org_dao.find_by_id(param.org_id).await?;
+    org_dao
+        .member_role_check(&org, auth_data.user_id(), AppOrgMemberRole::Admin)
+        .await?;
+    let res = org_dao
+        .invite_data(
+            org.id,
+            status,
+            param.page.as_ref().map(|e| e.into()).as_ref(),
+        )
+        .await?
+        .into_iter()
+        .map(|e| {
+            json!({
+                "id": e.id,
+                "email": e.email,
+                "role": e.role,
+                "status": e.status,
+                "time_out": e.time_out,
+                "user_id": e.user_id,
+                "accept_user_id": e.accept_user_id,
+                "add_time": e.add_time,
+                "change_time": e.change_time,
+            })
+        })
+        .collect::<Vec<_>>();
+    let total = if param.count_num.unwrap_or(false) {
+        Some(org_dao.invite_count(org.id, status).await?)
+    } else {
+        None
+    };
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "data": res,
+        "total": total,
+    }))))
+}
+
+#[derive(Deserialize)]
+pub struct OrgInviteCancelParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub invite_id: u64,
+}
+
+pub async fn org_invite_cancel(
+    param: &OrgInviteCancelParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let org_dao = &req_dao.web_dao.web_app.app_dao.org;
+    let invite = org_dao.invite_find_by_id(param.invite_id).await?;
+    let org = org_dao.find_by_id(invite.org_id).await?;
+    org_dao
+        .invite_cancel(&org, &invite, auth_data.user_id(), Some(&req_dao.req_env))
+        .await?;
+    Ok(JsonResponse::default())
+}
+
+#[derive(Deserialize)]
+pub struct OrgInviteAcceptParam {
+    pub invite_code: String,
+}
+
+//接受邀请,需当前账号已验证受邀邮箱
+pub async fn org_invite_accept(
+    param: &OrgInviteAcceptParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let org_dao = &req_dao.web_dao.web_app.app_dao.org;
+    let invite = org_dao.invite_find_by_code(&param.invite_code).await?;
+    let emails = req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account_email
+        .cache()
+        .find_by_account_id_vec(auth_data.session_body().account_id()?)
+        .await?;
+    if !emails.iter().any(|e| {
+        AccountEmailStatus::Valid.eq(e.status) && e.email.eq_ignore_ascii_case(&invite.email)
+    }) {
+        return Err(JsonError::Message(fluent_message!(
+            "app-org-invite-email-mismatch",
+            { "email": &invite.email }
+        )));
+    }
+    org_dao
+        .invite_accept(&invite, auth_data.user_id(), Some(&req_dao.req_env))
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "org_id": invite.org_id
+    }))))
+}
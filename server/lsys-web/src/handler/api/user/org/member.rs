@@ -0,0 +1,100 @@
+use crate::common::JsonData;
+use crate::common::{JsonResponse, JsonResult, PageParam, UserAuthQueryDao};
+use lsys_access::dao::AccessSession;
+use lsys_app::model::AppOrgMemberRole;
+use lsys_user::dao::AccountToken;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize)]
+pub struct OrgMemberListParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub org_id: u64,
+    pub page: Option<PageParam>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+}
+
+pub async fn org_member_list(
+    param: &OrgMemberListParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let org_dao = &req_dao.web_dao.web_app.app_dao.org;
+    let org = org_dao.find_by_id(param.org_id).await?;
+    org_dao
+        .member_role_check(&org, auth_data.user_id(), AppOrgMemberRole::Member)
+        .await?;
+    let res = org_dao
+        .member_data(org.id, param.page.as_ref().map(|e| e.into()).as_ref())
+        .await?;
+    let total = if param.count_num.unwrap_or(false) {
+        Some(org_dao.member_count(org.id).await?)
+    } else {
+        None
+    };
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "data": bind_vec_user_info_from_req!(req_dao, res, user_id),
+        "total": total,
+    }))))
+}
+
+#[derive(Deserialize)]
+pub struct OrgMemberRoleParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub org_id: u64,
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub user_id: u64,
+    #[serde(deserialize_with = "crate::common::deserialize_i8")]
+    pub role: i8,
+}
+
+pub async fn org_member_role(
+    param: &OrgMemberRoleParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let role = AppOrgMemberRole::try_from(param.role)?;
+    let org_dao = &req_dao.web_dao.web_app.app_dao.org;
+    let org = org_dao.find_by_id(param.org_id).await?;
+    org_dao
+        .member_change_role(
+            &org,
+            param.user_id,
+            role,
+            auth_data.user_id(),
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::default())
+}
+
+#[derive(Deserialize)]
+pub struct OrgMemberRemoveParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub org_id: u64,
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub user_id: u64,
+}
+
+//移除成员,移除自己即退出组织
+pub async fn org_member_remove(
+    param: &OrgMemberRemoveParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let org_dao = &req_dao.web_dao.web_app.app_dao.org;
+    let org = org_dao.find_by_id(param.org_id).await?;
+    org_dao
+        .member_remove(
+            &org,
+            param.user_id,
+            auth_data.user_id(),
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::default())
+}
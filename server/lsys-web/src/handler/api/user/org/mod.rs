@@ -0,0 +1,7 @@
+mod invite;
+mod member;
+mod org_manage;
+
+pub use invite::*;
+pub use member::*;
+pub use org_manage::*;
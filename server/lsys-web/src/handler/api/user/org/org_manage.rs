@@ -0,0 +1,135 @@
+use crate::common::JsonData;
+use crate::common::{JsonResponse, JsonResult, PageParam, UserAuthQueryDao};
+use crate::dao::access::api::system::user::{CheckUserAppEdit, CheckUserAppView};
+use crate::dao::access::RbacAccessCheckEnv;
+use lsys_access::dao::AccessSession;
+use lsys_user::dao::AccountToken;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize)]
+pub struct OrgAddParam {
+    pub name: String,
+}
+
+pub async fn org_add(param: &OrgAddParam, req_dao: &UserAuthQueryDao) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckUserAppEdit {
+                res_user_id: auth_data.user_id(),
+            },
+        )
+        .await?;
+    let id = req_dao
+        .web_dao
+        .web_app
+        .app_dao
+        .org
+        .add_org(&param.name, auth_data.user_id(), Some(&req_dao.req_env))
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(json!({ "id": id }))))
+}
+
+#[derive(Deserialize)]
+pub struct OrgEditParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub org_id: u64,
+    pub name: String,
+}
+
+pub async fn org_edit(
+    param: &OrgEditParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let org_dao = &req_dao.web_dao.web_app.app_dao.org;
+    let org = org_dao.find_by_id(param.org_id).await?;
+    org_dao
+        .edit_org(
+            &org,
+            &param.name,
+            auth_data.user_id(),
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::default())
+}
+
+#[derive(Deserialize)]
+pub struct OrgDeleteParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub org_id: u64,
+}
+
+pub async fn org_delete(
+    param: &OrgDeleteParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    AccountToken::session_deny(&auth_data)?;
+    let org_dao = &req_dao.web_dao.web_app.app_dao.org;
+    let org = org_dao.find_by_id(param.org_id).await?;
+    org_dao
+        .delete_org(&org, auth_data.user_id(), Some(&req_dao.req_env))
+        .await?;
+    Ok(JsonResponse::default())
+}
+
+#[derive(Deserialize)]
+pub struct OrgListParam {
+    pub page: Option<PageParam>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+}
+
+//当前用户加入的组织
+pub async fn org_list(
+    param: &OrgListParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckUserAppView {
+                res_user_id: auth_data.user_id(),
+            },
+        )
+        .await?;
+    let org_dao = &req_dao.web_dao.web_app.app_dao.org;
+    let res = org_dao
+        .user_org_data(
+            auth_data.user_id(),
+            param.page.as_ref().map(|e| e.into()).as_ref(),
+        )
+        .await?;
+    let mut data = Vec::with_capacity(res.len());
+    for (org, member) in res {
+        let attr = org_dao.org_attr(&org).await?;
+        data.push(json!({
+            "id": org.id,
+            "name": org.name,
+            "user_id": org.user_id,
+            "change_time": org.change_time,
+            "role": member.role,
+            "member_count": attr.member_count,
+            "app_count": attr.app_count,
+        }));
+    }
+    let total = if param.count_num.unwrap_or(false) {
+        Some(org_dao.user_org_count(auth_data.user_id()).await?)
+    } else {
+        None
+    };
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "data": data,
+        "total": total,
+    }))))
+}
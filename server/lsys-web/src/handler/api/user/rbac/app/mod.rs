@@ -9,10 +9,7 @@ mod role_user;
 
 use crate::{
     common::{JsonError, JsonResult, RequestDao, UserAuthQueryDao},
-    dao::access::{
-        api::system::user::{CheckUserAppEdit, CheckUserAppView},
-        RbacAccessCheckEnv,
-    },
+    dao::access::api::system::user::{CheckUserAppEdit, CheckUserAppView},
 };
 pub use audit::*;
 pub use check::*;
@@ -54,7 +51,11 @@ async fn app_check_get(
             .web_dao
             .web_rbac
             .check(
-                &RbacAccessCheckEnv::session_body(auth_data, &req_dao.req_env),
+                &req_dao
+                    .web_dao
+                    .web_app
+                    .app_check_env(&app, auth_data, &req_dao.req_env)
+                    .await?,
                 &CheckUserAppEdit {
                     res_user_id: app.user_id,
                 },
@@ -65,7 +66,11 @@ async fn app_check_get(
             .web_dao
             .web_rbac
             .check(
-                &RbacAccessCheckEnv::session_body(auth_data, &req_dao.req_env),
+                &req_dao
+                    .web_dao
+                    .web_app
+                    .app_check_env(&app, auth_data, &req_dao.req_env)
+                    .await?,
                 &CheckUserAppView {
                     res_user_id: app.user_id,
                 },
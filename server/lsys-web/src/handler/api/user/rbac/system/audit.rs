@@ -19,6 +19,8 @@ pub struct SystemAuditParam {
     pub user_ip: Option<String>,
     pub device_id: Option<String>,
     pub request_id: Option<String>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub personal_token: Option<bool>,
     pub res_data: Option<SystemAuditResParam>,
     pub limit: Option<LimitParam>,
     #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
@@ -43,6 +45,7 @@ pub async fn system_audit_data(
                 user_ip: param.user_ip.as_deref(),
                 device_id: param.device_id.as_deref(),
                 request_id: param.request_id.as_deref(),
+                personal_token: param.personal_token,
                 res_data: param.res_data.as_ref().map(|e| (e.res_id, e.op_id)),
             },
             param.limit.as_ref().map(|e| e.into()).as_ref(),
@@ -61,6 +64,7 @@ pub async fn system_audit_data(
                     user_ip: param.user_ip.as_deref(),
                     device_id: param.device_id.as_deref(),
                     request_id: param.request_id.as_deref(),
+                    personal_token: param.personal_token,
                     res_data: param.res_data.as_ref().map(|e| (e.res_id, e.op_id)),
                 })
                 .await?,
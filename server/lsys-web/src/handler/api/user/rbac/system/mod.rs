@@ -1,11 +1,15 @@
 mod audit;
 mod res;
 mod role;
+mod role_delegate;
 mod role_perm;
+mod role_request;
 mod role_user;
 pub use audit::*;
 pub use res::*;
 pub use role::*;
+pub use role_delegate::*;
 pub use role_perm::*;
+pub use role_request::*;
 pub use role_user::*;
 //其他系统用户对当前登录系统用户权限访问控制
@@ -0,0 +1,233 @@
+use crate::common::{JsonData, JsonError, JsonResponse, JsonResult, PageParam, UserAuthQueryDao};
+use crate::dao::access::api::system::user::{CheckUserRbacEdit, CheckUserRbacView};
+use crate::dao::access::RbacAccessCheckEnv;
+use lsys_access::dao::AccessSession;
+use lsys_core::fluent_message;
+use lsys_rbac::dao::{RoleDelegateAddData, RoleDelegateDataParam};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, Deserialize)]
+pub struct SystemRoleDelegateAddParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub delegate_user_id: u64,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub scope_role_id: Option<u64>,
+}
+
+//当前登录用户委托其他用户管理自身角色用户
+pub async fn system_role_delegate_add(
+    param: &SystemRoleDelegateAddParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckUserRbacEdit {
+                res_user_id: auth_data.user_id(),
+            },
+        )
+        .await?;
+    let id = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .add_delegate(
+            &RoleDelegateAddData {
+                user_id: auth_data.user_id(),
+                app_id: 0,
+                delegate_user_id: param.delegate_user_id,
+                scope_role_id: param.scope_role_id.unwrap_or_default(),
+            },
+            auth_data.user_id(),
+            None,
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(json!({ "id": id }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SystemRoleDelegateDelParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub id: u64,
+}
+
+pub async fn system_role_delegate_del(
+    param: &SystemRoleDelegateDelParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    let delegate = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .find_delegate_by_id(&param.id)
+        .await?;
+    if delegate.app_id != 0 {
+        return Err(JsonError::Message(fluent_message!(
+            "role-delegate-not-found",
+            {
+                "id": param.id
+            }
+        )));
+    }
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckUserRbacEdit {
+                res_user_id: delegate.user_id,
+            },
+        )
+        .await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .del_delegate(&delegate, auth_data.user_id(), None, Some(&req_dao.req_env))
+        .await?;
+    Ok(JsonResponse::default())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SystemRoleDelegateDataParam {
+    pub page: Option<PageParam>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+}
+
+async fn system_role_delegate_list(
+    data_param: &RoleDelegateDataParam,
+    param: &SystemRoleDelegateDataParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let res = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .delegate_data(data_param, param.page.as_ref().map(|e| e.into()).as_ref())
+        .await?;
+    let res = bind_vec_user_info_from_req!(
+        req_dao,
+        res,
+        [user_id: "user_data", delegate_user_id: "delegate_user_data"],
+        false
+    );
+    let count = if param.count_num.unwrap_or(false) {
+        Some(
+            req_dao
+                .web_dao
+                .web_rbac
+                .rbac_dao
+                .role
+                .delegate_count(data_param)
+                .await?,
+        )
+    } else {
+        None
+    };
+    Ok(JsonResponse::data(JsonData::body(
+        json!({ "data": res,"total":count}),
+    )))
+}
+
+//当前登录用户添加的委托管理员
+pub async fn system_role_delegate_data(
+    param: &SystemRoleDelegateDataParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckUserRbacView {
+                res_user_id: auth_data.user_id(),
+            },
+        )
+        .await?;
+    system_role_delegate_list(
+        &RoleDelegateDataParam {
+            user_id: Some(auth_data.user_id()),
+            app_id: Some(0),
+            delegate_user_id: None,
+        },
+        param,
+        req_dao,
+    )
+    .await
+}
+
+//当前登录用户被委托管理的用户
+pub async fn system_role_delegate_mine(
+    param: &SystemRoleDelegateDataParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    system_role_delegate_list(
+        &RoleDelegateDataParam {
+            user_id: None,
+            app_id: Some(0),
+            delegate_user_id: Some(auth_data.user_id()),
+        },
+        param,
+        req_dao,
+    )
+    .await
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SystemRoleDelegableParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub role_id: u64,
+    #[serde(deserialize_with = "crate::common::deserialize_bool")]
+    pub delegable: bool,
+}
+
+pub async fn system_role_delegable(
+    param: &SystemRoleDelegableParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    let role = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .find_by_id(&param.role_id)
+        .await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckUserRbacEdit {
+                res_user_id: role.user_id,
+            },
+        )
+        .await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .set_role_delegable(
+            &role,
+            param.delegable,
+            auth_data.user_id(),
+            None,
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::default())
+}
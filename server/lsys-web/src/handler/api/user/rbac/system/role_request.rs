@@ -0,0 +1,275 @@
+use crate::common::{JsonData, JsonError, JsonResponse, JsonResult, PageParam, UserAuthQueryDao};
+use crate::dao::access::api::system::user::{CheckUserRbacEdit, CheckUserRbacView};
+use crate::dao::access::RbacAccessCheckEnv;
+use lsys_access::dao::AccessSession;
+use lsys_core::fluent_message;
+use lsys_rbac::dao::{RoleRequestAddData, RoleRequestDataParam};
+use lsys_rbac::model::RbacRoleRequestStatus;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, Deserialize)]
+pub struct SystemRoleRequestAddParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub role_id: u64,
+    pub reason: String,
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub duration: u64,
+}
+
+//当前登录用户申请临时加入某角色
+pub async fn system_role_request_add(
+    param: &SystemRoleRequestAddParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    let role = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .find_by_id(&param.role_id)
+        .await?;
+    if role.app_id != 0 || auth_data.session().user_app_id != 0 {
+        return Err(JsonError::Message(fluent_message!(
+            "role-request-not-system-role",
+            {
+                "role_id": role.id,
+                "app_id": role.app_id
+            }
+        )));
+    }
+    let id = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .add_request(
+            &role,
+            &RoleRequestAddData {
+                user_id: auth_data.user_id(),
+                reason: &param.reason,
+                duration: param.duration,
+            },
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(json!({ "id": id }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SystemRoleRequestCancelParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub id: u64,
+}
+
+pub async fn system_role_request_cancel(
+    param: &SystemRoleRequestCancelParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    let request = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .find_request_by_id(&param.id)
+        .await?;
+    if request.user_id != auth_data.user_id() {
+        return Err(JsonError::Message(fluent_message!(
+            "role-request-not-found",
+            {
+                "id": param.id
+            }
+        )));
+    }
+    req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .cancel_request(&request, Some(&req_dao.req_env))
+        .await?;
+    Ok(JsonResponse::default())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SystemRoleRequestDataParam {
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub role_id: Option<u64>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_vec_i8")]
+    pub status: Option<Vec<i8>>,
+    pub page: Option<PageParam>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_bool")]
+    pub count_num: Option<bool>,
+}
+
+#[allow(clippy::result_large_err)]
+fn role_request_status_param(
+    status: &Option<Vec<i8>>,
+) -> JsonResult<Option<Vec<RbacRoleRequestStatus>>> {
+    Ok(match status {
+        Some(tmp) => Some(
+            tmp.iter()
+                .map(|e| RbacRoleRequestStatus::try_from(*e))
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        None => None,
+    })
+}
+
+async fn system_role_request_list(
+    data_param: &RoleRequestDataParam<'_>,
+    param: &SystemRoleRequestDataParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let res = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .request_data(data_param, param.page.as_ref().map(|e| e.into()).as_ref())
+        .await?;
+    let res = bind_vec_user_info_from_req!(
+        req_dao,
+        res,
+        [user_id: "user_data", audit_user_id: "audit_user_data"],
+        false
+    );
+    let count = if param.count_num.unwrap_or(false) {
+        Some(
+            req_dao
+                .web_dao
+                .web_rbac
+                .rbac_dao
+                .role
+                .request_count(data_param)
+                .await?,
+        )
+    } else {
+        None
+    };
+    Ok(JsonResponse::data(JsonData::body(
+        json!({ "data": res,"total":count}),
+    )))
+}
+
+//当前登录用户自身的申请记录
+pub async fn system_role_request_mine(
+    param: &SystemRoleRequestDataParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    let status = role_request_status_param(&param.status)?;
+    system_role_request_list(
+        &RoleRequestDataParam {
+            role_user_id: None,
+            role_app_id: Some(0),
+            role_id: param.role_id,
+            user_id: Some(auth_data.user_id()),
+            status: status.as_deref(),
+        },
+        param,
+        req_dao,
+    )
+    .await
+}
+
+//当前登录用户所属角色收到的申请
+pub async fn system_role_request_data(
+    param: &SystemRoleRequestDataParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckUserRbacView {
+                res_user_id: auth_data.user_id(),
+            },
+        )
+        .await?;
+    let status = role_request_status_param(&param.status)?;
+    system_role_request_list(
+        &RoleRequestDataParam {
+            role_user_id: Some(auth_data.user_id()),
+            role_app_id: Some(0),
+            role_id: param.role_id,
+            user_id: None,
+            status: status.as_deref(),
+        },
+        param,
+        req_dao,
+    )
+    .await
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SystemRoleRequestAuditParam {
+    #[serde(deserialize_with = "crate::common::deserialize_u64")]
+    pub id: u64,
+    #[serde(deserialize_with = "crate::common::deserialize_bool")]
+    pub approve: bool,
+    pub note: Option<String>,
+}
+
+pub async fn system_role_request_audit(
+    param: &SystemRoleRequestAuditParam,
+    req_dao: &UserAuthQueryDao,
+) -> JsonResult<JsonResponse> {
+    let auth_data = req_dao.user_session.read().await.get_session_data().await?;
+    let request = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .find_request_by_id(&param.id)
+        .await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(
+            &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
+            &CheckUserRbacEdit {
+                res_user_id: request.role_user_id,
+            },
+        )
+        .await?;
+    let note = param.note.as_deref().unwrap_or_default();
+    if param.approve {
+        let role = req_dao
+            .web_dao
+            .web_rbac
+            .rbac_dao
+            .role
+            .find_by_id(&request.role_id)
+            .await?;
+        let expire_time = req_dao
+            .web_dao
+            .web_rbac
+            .rbac_dao
+            .role
+            .approve_request(
+                &request,
+                &role,
+                auth_data.user_id(),
+                note,
+                Some(&req_dao.req_env),
+            )
+            .await?;
+        Ok(JsonResponse::data(JsonData::body(
+            json!({ "expire_time": expire_time }),
+        )))
+    } else {
+        req_dao
+            .web_dao
+            .web_rbac
+            .rbac_dao
+            .role
+            .reject_request(&request, auth_data.user_id(), note, Some(&req_dao.req_env))
+            .await?;
+        Ok(JsonResponse::default())
+    }
+}
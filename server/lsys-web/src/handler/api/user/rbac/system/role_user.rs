@@ -37,37 +37,49 @@ pub async fn system_role_user_add(
         .find_by_id(&param.role_id)
         .await?;
 
-    req_dao
+    let is_delegate = req_dao
         .web_dao
         .web_rbac
-        .check(
+        .check_role_user_edit(
             &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
             &CheckUserRbacEdit {
                 res_user_id: role.user_id,
             },
-        )
-        .await?;
-
-    req_dao
-        .web_dao
-        .web_rbac
-        .rbac_dao
-        .role
-        .add_user(
             &role,
-            &param
-                .user_data
-                .iter()
-                .map(|e| RoleAddUser {
-                    user_id: e.user_id,
-                    timeout: e.timeout,
-                })
-                .collect::<Vec<_>>(),
-            auth_data.user_id(),
-            None,
-            Some(&req_dao.req_env),
+            role.user_id,
         )
         .await?;
+
+    let add_user_data = param
+        .user_data
+        .iter()
+        .map(|e| RoleAddUser {
+            user_id: e.user_id,
+            timeout: e.timeout,
+        })
+        .collect::<Vec<_>>();
+    let role_dao = &req_dao.web_dao.web_rbac.rbac_dao.role;
+    if is_delegate {
+        role_dao
+            .add_user(
+                &role,
+                &add_user_data,
+                auth_data.user_id(),
+                None,
+                Some(&req_dao.req_env),
+            )
+            .await?;
+    } else {
+        role_dao
+            .admin_add_user(
+                &role,
+                &add_user_data,
+                auth_data.user_id(),
+                None,
+                Some(&req_dao.req_env),
+            )
+            .await?;
+    }
     Ok(JsonResponse::default())
 }
 
@@ -93,30 +105,41 @@ pub async fn system_role_user_del(
         .find_by_id(&param.role_id)
         .await?;
 
-    req_dao
+    let is_delegate = req_dao
         .web_dao
         .web_rbac
-        .check(
+        .check_role_user_edit(
             &RbacAccessCheckEnv::session_body(&auth_data, &req_dao.req_env),
             &CheckUserRbacEdit {
                 res_user_id: role.user_id,
             },
-        )
-        .await?;
-
-    req_dao
-        .web_dao
-        .web_rbac
-        .rbac_dao
-        .role
-        .del_user(
             &role,
-            &param.user_data,
-            auth_data.user_id(),
-            None,
-            Some(&req_dao.req_env),
+            role.user_id,
         )
         .await?;
+
+    let role_dao = &req_dao.web_dao.web_rbac.rbac_dao.role;
+    if is_delegate {
+        role_dao
+            .del_user(
+                &role,
+                &param.user_data,
+                auth_data.user_id(),
+                None,
+                Some(&req_dao.req_env),
+            )
+            .await?;
+    } else {
+        role_dao
+            .admin_del_user(
+                &role,
+                &param.user_data,
+                auth_data.user_id(),
+                None,
+                Some(&req_dao.req_env),
+            )
+            .await?;
+    }
     Ok(JsonResponse::default())
 }
 
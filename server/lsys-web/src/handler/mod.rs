@@ -1,8 +1,10 @@
 //对外接口实现
 pub mod api;
 pub mod rest;
+pub mod scim;
 
 pub const APP_FEATURE_SMS: &str = "sms";
 pub const APP_FEATURE_MAIL: &str = "mail";
 pub const APP_FEATURE_BARCODE: &str = "barcode";
 pub const APP_FEATURE_RBAC: &str = "rbac";
+pub const APP_FEATURE_SCIM: &str = "scim";
@@ -24,6 +24,8 @@ pub struct DoLoginParam {
     pub login_ip: Option<String>,
     pub device_id: Option<String>,
     pub session_data: Option<HashMap<String, Value>>,
+    #[serde(default, deserialize_with = "crate::common::deserialize_option_u64")]
+    pub refresh_time: Option<u64>, //刷新token有效时长,未设置时不创建刷新token
 }
 pub async fn do_login(
     param: &DoLoginParam,
@@ -79,11 +81,13 @@ pub async fn do_login(
                 login_ip: param.login_ip.as_deref(),
                 device_id: param.device_id.as_deref(),
                 device_name: param.device_name.as_deref(),
+                user_agent: None,
                 expire_time: now_time().unwrap_or_default() + param.expire_time,
                 session_data: session_data
                     .iter()
                     .map(|e| (e.0.as_str(), e.1.as_str()))
                     .collect::<Vec<_>>(),
+                impersonate_user_id: 0,
             },
         )
         .await
@@ -99,10 +103,55 @@ pub async fn do_login(
             err => Err(err)?,
         },
     };
+    let refresh_token = match param.refresh_time {
+        Some(refresh_time) if refresh_time > 0 => Some(
+            req_dao
+                .web_dao
+                .web_user
+                .user_dao
+                .auth_code_dao
+                .code_refresh_token(&seession_body, param.expire_time, refresh_time)
+                .await?,
+        ),
+        _ => None,
+    };
     Ok(JsonResponse::data(JsonData::body(json!({
         "token_data": seession_body.token_data(),
         "user_id": seession_body.user_id(),
         "user_nickname": seession_body.user().user_nickname,
+        "refresh_token": refresh_token,
+    }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DoRefreshParam {
+    refresh_token: String,
+}
+//使用刷新token延长登录,每次刷新返回新的刷新token
+pub async fn do_refresh(
+    param: &DoRefreshParam,
+    app: &AppModel,
+    req_dao: &RequestDao,
+) -> JsonResult<JsonResponse> {
+    req_dao
+        .web_dao
+        .web_app
+        .app_dao
+        .exter_login
+        .inner_feature_exter_login_check(app)
+        .await?;
+    let (seession_body, refresh_token) = req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .auth_code_dao
+        .code_refresh(app.id, &param.refresh_token, Some(&req_dao.req_env))
+        .await?;
+    Ok(JsonResponse::data(JsonData::body(json!({
+        "token_data": seession_body.token_data(),
+        "user_id": seession_body.user_id(),
+        "expire_time": seession_body.session().expire_time,
+        "refresh_token": refresh_token,
     }))))
 }
 
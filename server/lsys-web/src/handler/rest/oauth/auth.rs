@@ -244,7 +244,7 @@ pub async fn refresh_token(
     req_dao: &RequestDao,
 ) -> JsonResult<JsonResponse> {
     let app = check_app_secret(req_dao, &param.client_id, &param.client_secret).await?;
-    let new_token = req_dao
+    let (new_token, refresh_token) = req_dao
         .web_dao
         .web_app
         .app_dao
@@ -253,7 +253,7 @@ pub async fn refresh_token(
         .await?;
     let session = SessionRecord {
         access_token: new_token.session_body().token_data().to_owned(),
-        refresh_token: param.refresh_token.to_owned(),
+        refresh_token,
         openid: new_token.session_body().user_id().to_string(),
         scope: req_dao
             .web_dao
@@ -52,7 +52,7 @@ pub async fn access_check(
     req_dao: &RequestDao,
 ) -> JsonResult<JsonResponse> {
     inner_app_rbac_check(app, req_dao).await?;
-    inner_access_check(param, app, req_dao).await?;
+    inner_access_check(param, app, req_dao, false).await?;
     Ok(JsonResponse::default())
 }
 
@@ -80,9 +80,8 @@ pub async fn access_list_check(
     let mut out = Vec::with_capacity(param.menu_res.len());
     for e in param.menu_res.iter() {
         out.push(RbacMenuStatus {
-            status: inner_access_check(&e.check_res, app, req_dao)
+            status: inner_access_check(&e.check_res, app, req_dao, true)
                 .await
-                .map(|_| true)
                 .unwrap_or(false),
             name: e.name.to_owned(),
         })
@@ -90,11 +89,13 @@ pub async fn access_list_check(
     Ok(JsonResponse::data(JsonData::body(json!({"result":out}))))
 }
 
+//batch 为真时批量计算授权结果,未授权返回假,用于菜单等大量检查
 async fn inner_access_check(
     param: &CheckParam,
     app: &AppModel,
     req_dao: &RequestDao,
-) -> JsonResult<()> {
+    batch: bool,
+) -> JsonResult<bool> {
     let user_data = match param.user_param.as_ref() {
         Some(user_data) => Some(
             req_dao
@@ -151,6 +152,7 @@ async fn inner_access_check(
         user_id: user_data.map(|e| e.id).unwrap_or_default(),
         user_login_token: param.token_data.as_deref(),
         session_role,
+        token_scope: None,
     };
     let access_checks = param
         .access
@@ -179,18 +181,14 @@ async fn inner_access_check(
                 .collect::<Vec<_>>()
         })
         .collect::<Vec<_>>();
-    req_dao
-        .web_dao
-        .web_rbac
-        .rbac_dao
-        .access
-        .list_check(
-            &check_env,
-            &access_checks
-                .iter()
-                .map(|e| e.as_slice())
-                .collect::<Vec<_>>(),
-        )
-        .await?;
-    Ok(())
+    let access_checks = access_checks
+        .iter()
+        .map(|e| e.as_slice())
+        .collect::<Vec<_>>();
+    let access = &req_dao.web_dao.web_rbac.rbac_dao.access;
+    if batch {
+        return Ok(access.batch_list_check(&check_env, &access_checks).await?);
+    }
+    access.list_check(&check_env, &access_checks).await?;
+    Ok(true)
 }
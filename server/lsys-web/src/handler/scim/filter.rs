@@ -0,0 +1,195 @@
+use serde_json::Value;
+
+//SCIM 过滤条件比较符,不支持 gt/ge/lt/le
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScimFilterOp {
+    Eq,
+    Ne,
+    Co,
+    Sw,
+    Ew,
+    Pr,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScimFilterItem {
+    pub attr: String,
+    pub op: ScimFilterOp,
+    pub value: Value,
+}
+
+//仅支持 and 连接的比较表达式,如: userName eq "bjensen" and active eq true
+#[derive(Debug, Clone, Default)]
+pub struct ScimFilter(pub Vec<ScimFilterItem>);
+
+impl ScimFilter {
+    pub fn parse(filter: &str) -> Result<Self, String> {
+        let tokens = tokenize(filter)?;
+        let mut items = vec![];
+        let mut iter = tokens.into_iter().peekable();
+        loop {
+            let attr = match iter.next() {
+                Some(Token::Word(e)) => e,
+                Some(e) => return Err(format!("expect attribute,find {:?}", e)),
+                None => return Err("empty filter".to_string()),
+            };
+            let op = match iter.next() {
+                Some(Token::Word(e)) => match e.to_ascii_lowercase().as_str() {
+                    "eq" => ScimFilterOp::Eq,
+                    "ne" => ScimFilterOp::Ne,
+                    "co" => ScimFilterOp::Co,
+                    "sw" => ScimFilterOp::Sw,
+                    "ew" => ScimFilterOp::Ew,
+                    "pr" => ScimFilterOp::Pr,
+                    _ => return Err(format!("unsupported operator:{}", e)),
+                },
+                e => return Err(format!("expect operator,find {:?}", e)),
+            };
+            let value = if op == ScimFilterOp::Pr {
+                Value::Null
+            } else {
+                match iter.next() {
+                    Some(Token::Str(e)) => Value::String(e),
+                    Some(Token::Word(e)) => match e.to_ascii_lowercase().as_str() {
+                        "true" => Value::Bool(true),
+                        "false" => Value::Bool(false),
+                        "null" => Value::Null,
+                        _ => serde_json::from_str::<serde_json::Number>(&e)
+                            .map(Value::Number)
+                            .map_err(|_| format!("bad value:{}", e))?,
+                    },
+                    None => return Err(format!("miss value for {}", attr)),
+                }
+            };
+            items.push(ScimFilterItem { attr, op, value });
+            match iter.next() {
+                None => break,
+                Some(Token::Word(e)) if e.eq_ignore_ascii_case("and") => continue,
+                Some(e) => return Err(format!("unsupported expression:{:?}", e)),
+            }
+        }
+        Ok(Self(items))
+    }
+    //查找指定属性的等值条件,用于定位候选记录
+    pub fn eq_value(&self, attr: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .filter(|e| e.op == ScimFilterOp::Eq && e.attr.eq_ignore_ascii_case(attr))
+            .find_map(|e| e.value.as_str())
+    }
+    //在资源JSON上校验全部条件,字符串比较不区分大小写
+    pub fn matches(&self, resource: &Value) -> bool {
+        self.0.iter().all(|item| {
+            let vals = attr_values(resource, &item.attr);
+            match item.op {
+                ScimFilterOp::Pr => vals.iter().any(|e| !is_empty_value(e)),
+                ScimFilterOp::Ne => !vals.iter().any(|e| value_eq(e, &item.value)),
+                ScimFilterOp::Eq => vals.iter().any(|e| value_eq(e, &item.value)),
+                op => {
+                    let find = match item.value.as_str() {
+                        Some(e) => e.to_lowercase(),
+                        None => return false,
+                    };
+                    vals.iter().filter_map(|e| e.as_str()).any(|e| {
+                        let e = e.to_lowercase();
+                        match op {
+                            ScimFilterOp::Co => e.contains(&find),
+                            ScimFilterOp::Sw => e.starts_with(&find),
+                            _ => e.ends_with(&find),
+                        }
+                    })
+                }
+            }
+        })
+    }
+}
+
+#[derive(Debug)]
+enum Token {
+    Word(String),
+    Str(String),
+}
+
+fn tokenize(filter: &str) -> Result<Vec<Token>, String> {
+    let mut out = vec![];
+    let mut chars = filter.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '"' => {
+                let mut val = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(e) => val.push(e),
+                            None => return Err("bad escape".to_string()),
+                        },
+                        Some(e) => val.push(e),
+                        None => return Err("unterminated string".to_string()),
+                    }
+                }
+                out.push(Token::Str(val));
+            }
+            '(' | ')' | '[' | ']' => return Err(format!("unsupported group:{}", c)),
+            c => {
+                let mut val = c.to_string();
+                while let Some(e) = chars.peek() {
+                    if e.is_whitespace() || ['"', '(', ')', '[', ']'].contains(e) {
+                        break;
+                    }
+                    val.push(*e);
+                    chars.next();
+                }
+                out.push(Token::Word(val));
+            }
+        }
+    }
+    Ok(out)
+}
+
+//按属性路径取值,多值属性展开,属性名不区分大小写
+fn attr_values<'t>(resource: &'t Value, path: &str) -> Vec<&'t Value> {
+    let mut vals = vec![resource];
+    for key in path.split('.') {
+        let mut next = vec![];
+        for val in vals {
+            let items = match val {
+                Value::Array(arr) => arr.iter().collect::<Vec<_>>(),
+                e => vec![e],
+            };
+            for item in items {
+                if let Value::Object(map) = item {
+                    if let Some((_, v)) = map.iter().find(|(k, _)| k.eq_ignore_ascii_case(key)) {
+                        next.push(v);
+                    }
+                }
+            }
+        }
+        vals = next;
+    }
+    vals.into_iter()
+        .flat_map(|e| match e {
+            Value::Array(arr) => arr.iter().collect::<Vec<_>>(),
+            e => vec![e],
+        })
+        .collect()
+}
+
+fn is_empty_value(val: &Value) -> bool {
+    match val {
+        Value::Null => true,
+        Value::String(e) => e.is_empty(),
+        Value::Array(e) => e.is_empty(),
+        Value::Object(e) => e.is_empty(),
+        _ => false,
+    }
+}
+
+fn value_eq(val: &Value, find: &Value) -> bool {
+    match (val, find) {
+        (Value::String(a), Value::String(b)) => a.eq_ignore_ascii_case(b),
+        (Value::Bool(a), Value::String(b)) => a.to_string().eq_ignore_ascii_case(b),
+        (a, b) => a == b,
+    }
+}
@@ -0,0 +1,512 @@
+use super::{
+    scim_error, scim_list_body, scim_list_response, scim_parse_id, scim_patch_operations,
+    scim_time, ScimAuth, ScimFilter, ScimListParam, ScimResponse, SCIM_SCHEMA_GROUP,
+};
+use crate::common::{JsonResult, RequestDao};
+use lsys_core::fluent_message;
+use lsys_rbac::dao::{RbacRoleAddData, RbacRoleUserRangeData, RoleAddUser, RoleDataParam};
+use lsys_rbac::model::{RbacRoleModel, RbacRoleResRange, RbacRoleUserRange};
+use serde_json::{json, Value};
+
+//组成员: 访问用户ID,账号ID,昵称
+struct ScimMember {
+    user_id: u64,
+    account_id: u64,
+    display: String,
+}
+
+//仅系统级自定义用户角色可作为组
+fn scim_group_param(role_name: Option<&str>) -> RoleDataParam<'_> {
+    RoleDataParam {
+        user_id: 0,
+        app_id: Some(0),
+        user_range: Some(RbacRoleUserRange::Custom),
+        res_range: None,
+        role_key: None,
+        role_name,
+        ids: None,
+    }
+}
+
+async fn scim_group_find(id: &str, req_dao: &RequestDao) -> JsonResult<RbacRoleModel> {
+    let id = scim_parse_id(id)?;
+    let role = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .find_by_id(&id)
+        .await?;
+    if role.user_id != 0 || role.app_id != 0 || !RbacRoleUserRange::Custom.eq(role.user_range) {
+        return Err(sqlx::Error::RowNotFound.into());
+    }
+    Ok(role)
+}
+
+//角色关联的是访问用户ID,系统账号的访问用户 user_data 为账号ID
+async fn scim_group_members(
+    role: &RbacRoleModel,
+    req_dao: &RequestDao,
+) -> JsonResult<Vec<ScimMember>> {
+    let role_users = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .role_user_data(role, false, None)
+        .await?;
+    let user_ids = role_users.iter().map(|e| e.user_id).collect::<Vec<_>>();
+    let users = req_dao
+        .web_dao
+        .web_access
+        .access_dao
+        .user
+        .cache()
+        .find_users_by_ids(&user_ids)
+        .await?;
+    Ok(user_ids
+        .into_iter()
+        .filter_map(|id| users.get(id))
+        .filter(|e| e.app_id == 0)
+        .filter_map(|e| {
+            e.user_data
+                .parse::<u64>()
+                .ok()
+                .map(|account_id| ScimMember {
+                    user_id: e.id,
+                    account_id,
+                    display: e.user_nickname,
+                })
+        })
+        .collect())
+}
+
+//账号ID转为访问用户ID
+async fn scim_member_user_ids(account_ids: &[u64], req_dao: &RequestDao) -> JsonResult<Vec<u64>> {
+    let account = &req_dao.web_dao.web_user.user_dao.account_dao.account;
+    let mut out = Vec::with_capacity(account_ids.len());
+    for account_id in account_ids {
+        let user = account.cache().find_by_id(account_id).await?;
+        out.push(account.cache().get_user(&user).await?.id);
+    }
+    Ok(out)
+}
+
+fn scim_group_json(role: &RbacRoleModel, members: Option<&[ScimMember]>) -> Value {
+    let mut data = json!({
+        "schemas":[SCIM_SCHEMA_GROUP],
+        "id":role.id.to_string(),
+        "displayName":role.role_name,
+        "meta":{
+            "resourceType":"Group",
+            "lastModified":scim_time(role.change_time),
+        }
+    });
+    if let Some(members) = members {
+        data["members"] = json!(members
+            .iter()
+            .map(|e| json!({
+                "value":e.account_id.to_string(),
+                "display":e.display,
+                "type":"User",
+            }))
+            .collect::<Vec<_>>());
+    }
+    data
+}
+
+async fn scim_group_data(
+    role: &RbacRoleModel,
+    members: bool,
+    req_dao: &RequestDao,
+) -> JsonResult<Value> {
+    if members {
+        let members = scim_group_members(role, req_dao).await?;
+        Ok(scim_group_json(role, Some(&members)))
+    } else {
+        Ok(scim_group_json(role, None))
+    }
+}
+
+//组名不能重复
+async fn scim_group_unique_check(name: &str, role_id: u64, req_dao: &RequestDao) -> JsonResult<()> {
+    let exist = req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .role_data(&scim_group_param(Some(name)), None)
+        .await?
+        .into_iter()
+        .any(|e| e.id != role_id && e.role_name == name);
+    if exist {
+        return Err(scim_error(
+            409,
+            "uniqueness",
+            fluent_message!("scim-group-exists",{"name":name}),
+        ));
+    }
+    Ok(())
+}
+
+#[allow(clippy::result_large_err)]
+fn scim_group_name(value: &Value) -> JsonResult<String> {
+    match value.as_str().map(|e| e.trim()) {
+        Some(name) if !name.is_empty() => Ok(name.to_string()),
+        _ => Err(scim_error(
+            400,
+            "invalidValue",
+            fluent_message!("scim-bad-value",{"attr":"displayName"}),
+        )),
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn scim_member_values(value: &Value) -> JsonResult<Vec<u64>> {
+    let items = match value {
+        Value::Array(arr) => arr.iter().collect::<Vec<_>>(),
+        Value::Null => vec![],
+        e => vec![e],
+    };
+    let mut out = vec![];
+    for item in items {
+        let id = item
+            .get("value")
+            .and_then(|e| match e {
+                Value::String(s) => s.trim().parse::<u64>().ok(),
+                Value::Number(n) => n.as_u64(),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                scim_error(
+                    400,
+                    "invalidValue",
+                    fluent_message!("scim-bad-value",{"attr":"members"}),
+                )
+            })?;
+        if !out.contains(&id) {
+            out.push(id);
+        }
+    }
+    Ok(out)
+}
+
+pub async fn scim_group_list(
+    param: &ScimListParam,
+    req_dao: &RequestDao,
+) -> JsonResult<ScimResponse> {
+    let role_dao = &req_dao.web_dao.web_rbac.rbac_dao.role;
+    let members = !param.exclude("members");
+    if let Some(filter) = param.filter()? {
+        let roles = if let Some(id) = filter.eq_value("id") {
+            match id.parse::<u64>() {
+                Ok(id) => {
+                    role_dao
+                        .role_data(
+                            &RoleDataParam {
+                                ids: Some(&[id]),
+                                ..scim_group_param(None)
+                            },
+                            None,
+                        )
+                        .await?
+                }
+                Err(_) => vec![],
+            }
+        } else if let Some(name) = filter.eq_value("displayName") {
+            role_dao
+                .role_data(&scim_group_param(Some(name)), None)
+                .await?
+        } else {
+            return Err(scim_error(
+                400,
+                "invalidFilter",
+                fluent_message!("scim-filter-not-support",{
+                    "attrs":"id,displayName"
+                }),
+            ));
+        };
+        let mut items = vec![];
+        for role in roles.iter() {
+            items.push(scim_group_data(role, members, req_dao).await?);
+        }
+        return Ok(scim_list_response(items, Some(&filter), param));
+    }
+    let page = param.page();
+    let total = role_dao.role_count(&scim_group_param(None)).await?;
+    let mut items = vec![];
+    for role in role_dao
+        .role_data(&scim_group_param(None), Some(&page))
+        .await?
+    {
+        items.push(scim_group_data(&role, members, req_dao).await?);
+    }
+    Ok(scim_list_body(items, total, &page))
+}
+
+pub async fn scim_group_get(id: &str, req_dao: &RequestDao) -> JsonResult<ScimResponse> {
+    let role = scim_group_find(id, req_dao).await?;
+    Ok(ScimResponse::ok(
+        scim_group_data(&role, true, req_dao).await?,
+    ))
+}
+
+pub async fn scim_group_create(
+    auth: &ScimAuth,
+    body: &Value,
+    req_dao: &RequestDao,
+) -> JsonResult<ScimResponse> {
+    let name = scim_group_name(body.get("displayName").unwrap_or(&Value::Null))?;
+    let members = scim_member_values(body.get("members").unwrap_or(&Value::Null))?;
+    scim_group_unique_check(&name, 0, req_dao).await?;
+    let user_ids = scim_member_user_ids(&members, req_dao).await?;
+    let role_dao = &req_dao.web_dao.web_rbac.rbac_dao.role;
+    let mut tran = req_dao.web_dao.db.begin().await?;
+    let role = match role_dao
+        .add_role(
+            &RbacRoleAddData {
+                user_id: 0,
+                app_id: Some(0),
+                role_info: RbacRoleUserRangeData::Custom { role_name: &name },
+                res_range: RbacRoleResRange::Include,
+            },
+            auth.op_user_id,
+            Some(&mut tran),
+            Some(&req_dao.req_env),
+        )
+        .await
+    {
+        Ok(role) => role,
+        Err(err) => {
+            tran.rollback().await?;
+            return Err(err.into());
+        }
+    };
+    if !user_ids.is_empty() {
+        let add_user = user_ids
+            .iter()
+            .map(|e| RoleAddUser {
+                user_id: *e,
+                timeout: 0,
+            })
+            .collect::<Vec<_>>();
+        if let Err(err) = role_dao
+            .add_user(
+                &role,
+                &add_user,
+                auth.op_user_id,
+                Some(&mut tran),
+                Some(&req_dao.req_env),
+            )
+            .await
+        {
+            tran.rollback().await?;
+            return Err(err.into());
+        }
+    }
+    tran.commit().await?;
+    let role = scim_group_find(&role.id.to_string(), req_dao).await?;
+    Ok(ScimResponse::new(
+        201,
+        scim_group_data(&role, true, req_dao).await?,
+    ))
+}
+
+pub async fn scim_group_replace(
+    auth: &ScimAuth,
+    id: &str,
+    body: &Value,
+    req_dao: &RequestDao,
+) -> JsonResult<ScimResponse> {
+    let role = scim_group_find(id, req_dao).await?;
+    let name = scim_group_name(body.get("displayName").unwrap_or(&Value::Null))?;
+    let members = scim_member_values(body.get("members").unwrap_or(&Value::Null))?;
+    scim_group_update(auth, role, name, members, req_dao).await
+}
+
+//移除成员路径格式: members[value eq "12"]
+#[allow(clippy::result_large_err)]
+fn scim_member_path_filter(path: &str) -> JsonResult<Option<ScimFilter>> {
+    let filter = match path
+        .strip_prefix("members[")
+        .and_then(|e| e.strip_suffix(']'))
+    {
+        Some(filter) => filter,
+        None => return Ok(None),
+    };
+    ScimFilter::parse(filter).map(Some).map_err(|err| {
+        scim_error(
+            400,
+            "invalidPath",
+            fluent_message!("scim-bad-filter",{
+                "filter":path,
+                "msg":err
+            }),
+        )
+    })
+}
+
+pub async fn scim_group_patch(
+    auth: &ScimAuth,
+    id: &str,
+    body: &Value,
+    req_dao: &RequestDao,
+) -> JsonResult<ScimResponse> {
+    let role = scim_group_find(id, req_dao).await?;
+    let mut name = role.role_name.clone();
+    let old_members = scim_group_members(&role, req_dao)
+        .await?
+        .into_iter()
+        .map(|e| e.account_id)
+        .collect::<Vec<_>>();
+    let mut members = old_members.clone();
+    for (op, path, value) in scim_patch_operations(body)? {
+        let attrs = match path {
+            Some(path) => vec![(path, value)],
+            None => value
+                .as_object()
+                .map(|e| {
+                    e.iter()
+                        .map(|(k, v)| (k.to_owned(), v.to_owned()))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default(),
+        };
+        for (path, value) in attrs {
+            if path.eq_ignore_ascii_case("displayName") {
+                if op != "remove" {
+                    name = scim_group_name(&value)?;
+                }
+                continue;
+            }
+            let path_filter = scim_member_path_filter(&path)?;
+            if path_filter.is_none() && !path.eq_ignore_ascii_case("members") {
+                continue;
+            }
+            let vals = scim_member_values(&value)?;
+            match (op.as_str(), path_filter) {
+                ("remove", Some(filter)) => members.retain(|e| {
+                    !filter.matches(&json!({
+                        "value":e.to_string(),
+                    }))
+                }),
+                ("remove", None) if vals.is_empty() => members.clear(),
+                ("remove", None) => members.retain(|e| !vals.contains(e)),
+                ("add", _) => {
+                    for val in vals {
+                        if !members.contains(&val) {
+                            members.push(val);
+                        }
+                    }
+                }
+                _ => members = vals,
+            }
+        }
+    }
+    if name == role.role_name && members == old_members {
+        return Ok(ScimResponse::ok(
+            scim_group_data(&role, true, req_dao).await?,
+        ));
+    }
+    scim_group_update(auth, role, name, members, req_dao).await
+}
+
+async fn scim_group_update(
+    auth: &ScimAuth,
+    role: RbacRoleModel,
+    name: String,
+    members: Vec<u64>,
+    req_dao: &RequestDao,
+) -> JsonResult<ScimResponse> {
+    let role_dao = &req_dao.web_dao.web_rbac.rbac_dao.role;
+    if name != role.role_name {
+        scim_group_unique_check(&name, role.id, req_dao).await?;
+    }
+    let old_members = scim_group_members(&role, req_dao).await?;
+    let add_ids = members
+        .iter()
+        .filter(|e| !old_members.iter().any(|t| t.account_id == **e))
+        .copied()
+        .collect::<Vec<_>>();
+    let add_user_ids = scim_member_user_ids(&add_ids, req_dao).await?;
+    //已删除账号无法再查到,直接使用关联的访问用户ID移除
+    let del_user_ids = old_members
+        .iter()
+        .filter(|e| !members.contains(&e.account_id))
+        .map(|e| e.user_id)
+        .collect::<Vec<_>>();
+    let mut tran = req_dao.web_dao.db.begin().await?;
+    if name != role.role_name {
+        if let Err(err) = role_dao
+            .edit_role(
+                &role,
+                &RbacRoleUserRangeData::Custom { role_name: &name },
+                auth.op_user_id,
+                Some(&mut tran),
+                Some(&req_dao.req_env),
+            )
+            .await
+        {
+            tran.rollback().await?;
+            return Err(err.into());
+        }
+    }
+    if !add_user_ids.is_empty() {
+        let add_user = add_user_ids
+            .iter()
+            .map(|e| RoleAddUser {
+                user_id: *e,
+                timeout: 0,
+            })
+            .collect::<Vec<_>>();
+        if let Err(err) = role_dao
+            .add_user(
+                &role,
+                &add_user,
+                auth.op_user_id,
+                Some(&mut tran),
+                Some(&req_dao.req_env),
+            )
+            .await
+        {
+            tran.rollback().await?;
+            return Err(err.into());
+        }
+    }
+    if !del_user_ids.is_empty() {
+        if let Err(err) = role_dao
+            .del_user(
+                &role,
+                &del_user_ids,
+                auth.op_user_id,
+                Some(&mut tran),
+                Some(&req_dao.req_env),
+            )
+            .await
+        {
+            tran.rollback().await?;
+            return Err(err.into());
+        }
+    }
+    tran.commit().await?;
+    let role = scim_group_find(&role.id.to_string(), req_dao).await?;
+    Ok(ScimResponse::ok(
+        scim_group_data(&role, true, req_dao).await?,
+    ))
+}
+
+pub async fn scim_group_delete(
+    auth: &ScimAuth,
+    id: &str,
+    req_dao: &RequestDao,
+) -> JsonResult<ScimResponse> {
+    let role = scim_group_find(id, req_dao).await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .rbac_dao
+        .role
+        .del_role(&role, auth.op_user_id, None, Some(&req_dao.req_env))
+        .await?;
+    Ok(ScimResponse::no_content())
+}
@@ -0,0 +1,349 @@
+//SCIM 2.0 用户及组同步接口
+//用户对应系统账号,组对应系统中用户范围为自定义的角色
+mod filter;
+mod group;
+mod user;
+pub use filter::*;
+pub use group::*;
+pub use user::*;
+
+use crate::common::{JsonData, JsonError, JsonResult, RequestDao};
+use crate::dao::access::api::system::admin::{CheckAdminRbacEdit, CheckAdminUserManage};
+use crate::dao::access::rest::CheckRestApp;
+use crate::dao::access::RbacAccessCheckEnv;
+use lsys_app::model::AppModel;
+use lsys_core::{fluent_message, now_time, PageParam};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use subtle::ConstantTimeEq;
+
+pub const SCIM_SCHEMA_USER: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+pub const SCIM_SCHEMA_GROUP: &str = "urn:ietf:params:scim:schemas:core:2.0:Group";
+pub const SCIM_SCHEMA_LIST: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+pub const SCIM_SCHEMA_PATCH: &str = "urn:ietf:params:scim:api:messages:2.0:PatchOp";
+pub const SCIM_SCHEMA_ERROR: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+pub const SCIM_CONTENT_TYPE: &str = "application/scim+json";
+
+//单页最大返回数量
+const SCIM_MAX_COUNT: u64 = 200;
+
+pub struct ScimResponse {
+    pub status: u16,
+    pub body: Option<Value>,
+}
+
+impl ScimResponse {
+    pub fn new(status: u16, body: Value) -> Self {
+        Self {
+            status,
+            body: Some(body),
+        }
+    }
+    pub fn ok(body: Value) -> Self {
+        Self::new(200, body)
+    }
+    pub fn no_content() -> Self {
+        Self {
+            status: 204,
+            body: None,
+        }
+    }
+    //将接口错误转为 SCIM 错误格式
+    pub fn error(err: &JsonError, req_dao: &RequestDao) -> Self {
+        let data = match err {
+            JsonError::Error(err) => err.to_json_data(&req_dao.fluent),
+            JsonError::Message(_) => JsonData::error(),
+            JsonError::JsonResponse(data, _) => data.to_owned(),
+        };
+        let mut status = data.code.parse::<u16>().unwrap_or(500);
+        //业务校验类错误按请求错误返回,避免客户端重试
+        if !(400..600).contains(&status)
+            || (status == 500
+                && ["user_account", "rbac", "valid"].contains(&data.sub_code.as_str()))
+        {
+            status = 400;
+        }
+        let mut body = json!({
+            "schemas":[SCIM_SCHEMA_ERROR],
+            "status":status.to_string(),
+            "detail":req_dao.fluent_error_string(err),
+        });
+        if [
+            "invalidFilter",
+            "invalidValue",
+            "invalidSyntax",
+            "invalidPath",
+            "uniqueness",
+            "mutability",
+        ]
+        .contains(&data.sub_code.as_str())
+        {
+            body["scimType"] = json!(data.sub_code);
+        }
+        Self::new(status, body)
+    }
+}
+
+//构造 SCIM 错误
+pub(crate) fn scim_error(status: u16, scim_type: &str, msg: lsys_core::FluentMessage) -> JsonError {
+    JsonError::JsonResponse(
+        JsonData::default().set_code(status).set_sub_code(scim_type),
+        msg,
+    )
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScimListParam {
+    pub filter: Option<String>,
+    #[serde(rename = "startIndex")]
+    pub start_index: Option<u64>,
+    pub count: Option<u64>,
+    #[serde(rename = "excludedAttributes")]
+    pub excluded_attributes: Option<String>,
+}
+
+impl ScimListParam {
+    //startIndex 从1开始
+    fn page(&self) -> PageParam {
+        PageParam::new(
+            self.start_index.unwrap_or(1).max(1) - 1,
+            self.count.unwrap_or(SCIM_MAX_COUNT).min(SCIM_MAX_COUNT),
+        )
+    }
+    #[allow(clippy::result_large_err)]
+    fn filter(&self) -> JsonResult<Option<ScimFilter>> {
+        match self.filter.as_deref().map(|e| e.trim()) {
+            Some(filter) if !filter.is_empty() => {
+                ScimFilter::parse(filter).map(Some).map_err(|err| {
+                    scim_error(
+                        400,
+                        "invalidFilter",
+                        fluent_message!("scim-bad-filter",{
+                            "filter":filter,
+                            "msg":err
+                        }),
+                    )
+                })
+            }
+            _ => Ok(None),
+        }
+    }
+    fn exclude(&self, attr: &str) -> bool {
+        self.excluded_attributes
+            .as_deref()
+            .map(|e| e.split(',').any(|t| t.trim().eq_ignore_ascii_case(attr)))
+            .unwrap_or(false)
+    }
+}
+
+//按过滤条件查出候选记录后,在内存中校验全部条件并分页
+fn scim_list_response(
+    resources: Vec<Value>,
+    filter: Option<&ScimFilter>,
+    param: &ScimListParam,
+) -> ScimResponse {
+    let resources = resources
+        .into_iter()
+        .filter(|e| filter.map(|f| f.matches(e)).unwrap_or(true))
+        .collect::<Vec<_>>();
+    let page = param.page();
+    let total = resources.len();
+    let items = resources
+        .into_iter()
+        .skip(page.offset as usize)
+        .take(page.limit as usize)
+        .collect::<Vec<_>>();
+    scim_list_body(items, total as i64, &page)
+}
+
+fn scim_list_body(items: Vec<Value>, total: i64, page: &PageParam) -> ScimResponse {
+    ScimResponse::ok(json!({
+        "schemas":[SCIM_SCHEMA_LIST],
+        "totalResults":total,
+        "startIndex":page.offset+1,
+        "itemsPerPage":items.len(),
+        "Resources":items,
+    }))
+}
+
+fn scim_time(time: u64) -> String {
+    chrono::DateTime::from_timestamp(time as i64, 0)
+        .map(|e| e.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+        .unwrap_or_default()
+}
+
+#[allow(clippy::result_large_err)]
+fn scim_parse_id(id: &str) -> JsonResult<u64> {
+    id.trim()
+        .parse::<u64>()
+        .map_err(|_| JsonError::from(sqlx::Error::RowNotFound))
+}
+
+//解析请求内容
+#[allow(clippy::result_large_err)]
+pub fn scim_parse_body(body: &[u8]) -> JsonResult<Value> {
+    serde_json::from_slice::<Value>(body)
+        .ok()
+        .filter(|e| e.is_object())
+        .ok_or_else(|| scim_error(400, "invalidSyntax", fluent_message!("scim-bad-body")))
+}
+
+//读取 PATCH 请求中的操作列表: (op,path,value)
+#[allow(clippy::result_large_err)]
+fn scim_patch_operations(body: &Value) -> JsonResult<Vec<(String, Option<String>, Value)>> {
+    let ops = body
+        .get("Operations")
+        .or_else(|| body.get("operations"))
+        .and_then(|e| e.as_array())
+        .ok_or_else(|| {
+            scim_error(
+                400,
+                "invalidSyntax",
+                fluent_message!("scim-bad-patch",{"msg":"miss Operations"}),
+            )
+        })?;
+    let mut out = Vec::with_capacity(ops.len());
+    for item in ops {
+        let op = item
+            .get("op")
+            .and_then(|e| e.as_str())
+            .map(|e| e.to_ascii_lowercase())
+            .unwrap_or_default();
+        if !["add", "replace", "remove"].contains(&op.as_str()) {
+            return Err(scim_error(
+                400,
+                "invalidSyntax",
+                fluent_message!("scim-bad-patch",{"msg":format!("bad op:{}",op)}),
+            ));
+        }
+        let path = item
+            .get("path")
+            .and_then(|e| e.as_str())
+            .map(|e| e.trim().to_string())
+            .filter(|e| !e.is_empty());
+        let value = item.get("value").cloned().unwrap_or(Value::Null);
+        out.push((op, path, value));
+    }
+    Ok(out)
+}
+
+//SCIM 接口访问的资源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScimResource {
+    User,
+    Group,
+}
+
+//通过认证的应用
+pub struct ScimAuth {
+    pub app: AppModel,
+    pub op_user_id: u64, //应用所属用户,作为操作用户记录
+}
+
+//认证头格式: Authorization: Bearer {client_id}:{app_secret}
+pub async fn scim_auth(
+    authorization: Option<&str>,
+    resource: ScimResource,
+    req_dao: &RequestDao,
+) -> JsonResult<ScimAuth> {
+    let auth_fail = || scim_error(401, "", fluent_message!("scim-auth-fail"));
+    let token = authorization
+        .map(|e| e.trim())
+        .and_then(|e| {
+            e.split_once(' ')
+                .filter(|(t, _)| t.eq_ignore_ascii_case("bearer"))
+                .map(|(_, t)| t.trim())
+        })
+        .ok_or_else(auth_fail)?;
+    let (client_id, secret) = token.split_once(':').ok_or_else(auth_fail)?;
+    let app_cache = req_dao.web_dao.web_app.app_dao.app.cache();
+    let secret_data = app_cache
+        .find_app_secret_by_client_id(client_id)
+        .await
+        .map_err(|_| auth_fail())?;
+    let now = now_time().unwrap_or_default();
+    //密钥使用常量时间比较,避免时序攻击
+    if !secret_data.iter().any(|e| {
+        bool::from(e.secret_data.as_bytes().ct_eq(secret.as_bytes()))
+            && (e.time_out == 0 || e.time_out > now)
+    }) {
+        return Err(auth_fail());
+    }
+    let app = app_cache.find_by_client_id(client_id).await?;
+    //子应用不能管理系统用户
+    if app.parent_app_id > 0 {
+        return Err(scim_error(403, "", fluent_message!("scim-not-sub-app")));
+    }
+    app.app_status_check()?;
+    let app_user = req_dao
+        .web_dao
+        .web_access
+        .access_dao
+        .user
+        .cache()
+        .find_by_id(&app.user_id)
+        .await?;
+    let check_env = RbacAccessCheckEnv::user(&app_user, &req_dao.req_env);
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(&check_env, &CheckRestApp {})
+        .await?;
+    req_dao
+        .web_dao
+        .web_rbac
+        .check(&check_env, &CheckAdminUserManage {})
+        .await?;
+    if resource == ScimResource::Group {
+        req_dao
+            .web_dao
+            .web_rbac
+            .check(&check_env, &CheckAdminRbacEdit {})
+            .await?;
+    }
+    app_cache
+        .exter_feature_check(&app, &[crate::handler::APP_FEATURE_SCIM])
+        .await?;
+    Ok(ScimAuth {
+        op_user_id: app.user_id,
+        app,
+    })
+}
+
+pub fn scim_service_provider_config() -> ScimResponse {
+    ScimResponse::ok(json!({
+        "schemas":["urn:ietf:params:scim:schemas:core:2.0:ServiceProviderConfig"],
+        "patch":{"supported":true},
+        "bulk":{"supported":false,"maxOperations":0,"maxPayloadSize":0},
+        "filter":{"supported":true,"maxResults":SCIM_MAX_COUNT},
+        "changePassword":{"supported":false},
+        "sort":{"supported":false},
+        "etag":{"supported":false},
+        "authenticationSchemes":[{
+            "type":"oauthbearertoken",
+            "name":"App Secret",
+            "description":"Authorization: Bearer {client_id}:{app_secret}",
+        }],
+    }))
+}
+
+pub fn scim_resource_types() -> ScimResponse {
+    let items = vec![
+        json!({
+            "schemas":["urn:ietf:params:scim:schemas:core:2.0:ResourceType"],
+            "id":"User",
+            "name":"User",
+            "endpoint":"/Users",
+            "schema":SCIM_SCHEMA_USER,
+        }),
+        json!({
+            "schemas":["urn:ietf:params:scim:schemas:core:2.0:ResourceType"],
+            "id":"Group",
+            "name":"Group",
+            "endpoint":"/Groups",
+            "schema":SCIM_SCHEMA_GROUP,
+        }),
+    ];
+    let total = items.len() as i64;
+    scim_list_body(items, total, &PageParam::new(0, SCIM_MAX_COUNT))
+}
@@ -0,0 +1,718 @@
+use super::{
+    scim_error, scim_list_body, scim_list_response, scim_parse_id, scim_patch_operations,
+    scim_time, ScimAuth, ScimListParam, ScimResponse, SCIM_SCHEMA_USER,
+};
+use crate::common::{JsonResult, RequestDao};
+use crate::dao::AccountRegData;
+use lsys_core::fluent_message;
+use lsys_user::dao::AccountError;
+use lsys_user::model::{AccountEmailStatus, AccountMobileStatus, AccountModel, AccountStatus};
+use serde_json::{json, Value};
+use sqlx::{MySql, Transaction};
+
+//SCIM externalId 存为外部账号,应用 client_id 作为类型区分来源
+pub const SCIM_EXTERNAL_CONFIG: &str = "scim";
+
+//SCIM 用户可同步的字段
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ScimUserState {
+    user_name: String,
+    display_name: String,
+    active: bool,
+    external_id: String,
+    emails: Vec<String>,
+    phones: Vec<(String, String)>,
+}
+
+impl ScimUserState {
+    fn nickname(&self) -> &str {
+        if self.display_name.is_empty() {
+            &self.user_name
+        } else {
+            &self.display_name
+        }
+    }
+    //按 SCIM 属性路径修改,不支持的属性忽略
+    #[allow(clippy::result_large_err)]
+    fn set_attr(&mut self, op: &str, path: &str, value: &Value) -> JsonResult<()> {
+        let path = path
+            .strip_prefix(SCIM_SCHEMA_USER)
+            .map(|e| e.trim_start_matches(':'))
+            .unwrap_or(path)
+            .to_ascii_lowercase();
+        let attr = path.split(['[', '.']).next().unwrap_or_default();
+        let remove = op == "remove";
+        match attr {
+            "username" => self.user_name = scim_string(value, remove),
+            "displayname" => self.display_name = scim_string(value, remove),
+            "externalid" => self.external_id = scim_string(value, remove),
+            "name" => {
+                let formatted = if path == "name" {
+                    value.get("formatted").cloned().unwrap_or(Value::Null)
+                } else if path == "name.formatted" {
+                    value.to_owned()
+                } else {
+                    return Ok(());
+                };
+                if remove || formatted.is_string() {
+                    self.display_name = scim_string(&formatted, remove);
+                }
+            }
+            "active" => {
+                self.active = match value {
+                    Value::Bool(e) => *e,
+                    Value::String(e) => e.eq_ignore_ascii_case("true"),
+                    _ => {
+                        return Err(scim_error(
+                            400,
+                            "invalidValue",
+                            fluent_message!("scim-bad-value",{"attr":"active"}),
+                        ))
+                    }
+                }
+            }
+            "emails" => {
+                let vals = scim_multi_values(value);
+                scim_multi_set(&mut self.emails, op, path.contains('['), vals);
+            }
+            "phonenumbers" => {
+                let vals = scim_multi_values(value)
+                    .iter()
+                    .map(|e| scim_parse_phone(e))
+                    .collect::<Vec<_>>();
+                scim_multi_set(&mut self.phones, op, path.contains('['), vals);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+    //PUT 请求体转为完整状态
+    #[allow(clippy::result_large_err)]
+    fn from_body(body: &Value) -> JsonResult<Self> {
+        let mut state = Self {
+            active: true,
+            ..Default::default()
+        };
+        if let Some(map) = body.as_object() {
+            for (key, val) in map {
+                if key != "schemas" && key != "id" && key != "meta" {
+                    state.set_attr("replace", key, val)?;
+                }
+            }
+        }
+        state.check()?;
+        Ok(state)
+    }
+    #[allow(clippy::result_large_err)]
+    fn check(&self) -> JsonResult<()> {
+        if self.user_name.is_empty() {
+            return Err(scim_error(
+                400,
+                "invalidValue",
+                fluent_message!("scim-bad-value",{"attr":"userName"}),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn scim_string(value: &Value, remove: bool) -> String {
+    if remove {
+        return "".to_string();
+    }
+    match value {
+        Value::String(e) => e.trim().to_string(),
+        Value::Number(e) => e.to_string(),
+        _ => "".to_string(),
+    }
+}
+
+//多值属性取值: 字符串,{value:..} 或其数组
+fn scim_multi_values(value: &Value) -> Vec<String> {
+    let items = match value {
+        Value::Array(arr) => arr.iter().collect::<Vec<_>>(),
+        Value::Null => vec![],
+        e => vec![e],
+    };
+    items
+        .into_iter()
+        .filter_map(|e| match e {
+            Value::String(s) => Some(s.trim().to_string()),
+            Value::Object(map) => map
+                .get("value")
+                .and_then(|v| v.as_str())
+                .map(|v| v.trim().to_string()),
+            _ => None,
+        })
+        .filter(|e| !e.is_empty())
+        .collect()
+}
+
+//带过滤的路径(如 emails[type eq "work"].value)视为替换全部值
+fn scim_multi_set<T: PartialEq>(data: &mut Vec<T>, op: &str, filter_path: bool, vals: Vec<T>) {
+    match op {
+        "add" if !filter_path => {
+            for val in vals {
+                if !data.contains(&val) {
+                    data.push(val);
+                }
+            }
+        }
+        "remove" if !vals.is_empty() => data.retain(|e| !vals.contains(e)),
+        "remove" => data.clear(),
+        _ => *data = vals,
+    }
+}
+
+//手机号格式: +86 13800138000 或 13800138000
+fn scim_parse_phone(phone: &str) -> (String, String) {
+    let phone = phone.trim();
+    match phone
+        .strip_prefix('+')
+        .and_then(|e| e.split_once([' ', '-']))
+    {
+        Some((area, mobile)) => (
+            area.trim().to_string(),
+            mobile.chars().filter(|e| e.is_ascii_digit()).collect(),
+        ),
+        None => (
+            "".to_string(),
+            phone.chars().filter(|e| e.is_ascii_digit()).collect(),
+        ),
+    }
+}
+
+fn scim_format_phone(area_code: &str, mobile: &str) -> String {
+    if area_code.is_empty() {
+        mobile.to_string()
+    } else {
+        format!("+{} {}", area_code, mobile)
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn not_found<T>(res: Result<T, AccountError>) -> JsonResult<Option<T>> {
+    match res {
+        Ok(e) => Ok(Some(e)),
+        Err(AccountError::Sqlx(sqlx::Error::RowNotFound)) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+async fn scim_user_state(
+    auth: &ScimAuth,
+    account: &AccountModel,
+    req_dao: &RequestDao,
+) -> JsonResult<ScimUserState> {
+    let account_dao = &req_dao.web_dao.web_user.user_dao.account_dao;
+    let user_name = not_found(
+        account_dao
+            .account_name
+            .cache()
+            .find_by_account_id(&account.id)
+            .await,
+    )?
+    .map(|e| e.username)
+    .unwrap_or_default();
+    let external_id = account_dao
+        .account_external
+        .cache()
+        .find_by_account_id_vec(account.id)
+        .await?
+        .into_iter()
+        .find(|e| e.config_name == SCIM_EXTERNAL_CONFIG && e.external_type == auth.app.client_id)
+        .map(|e| e.external_id)
+        .unwrap_or_default();
+    let mut emails = account_dao
+        .account_email
+        .cache()
+        .find_by_account_id_vec(account.id)
+        .await?;
+    emails.sort_by_key(|e| e.id);
+    let mut mobiles = account_dao
+        .account_mobile
+        .cache()
+        .find_by_account_id_vec(account.id)
+        .await?;
+    mobiles.sort_by_key(|e| e.id);
+    Ok(ScimUserState {
+        user_name,
+        display_name: account.nickname.clone(),
+        active: AccountStatus::Enable.eq(account.status),
+        external_id,
+        emails: emails.into_iter().map(|e| e.email).collect(),
+        phones: mobiles
+            .into_iter()
+            .map(|e| (e.area_code, e.mobile))
+            .collect(),
+    })
+}
+
+fn scim_user_json(account: &AccountModel, state: &ScimUserState) -> Value {
+    let mut data = json!({
+        "schemas":[SCIM_SCHEMA_USER],
+        "id":account.id.to_string(),
+        "userName":state.user_name,
+        "displayName":account.nickname,
+        "name":{"formatted":account.nickname},
+        "active":state.active,
+        "emails":state.emails.iter().enumerate().map(|(i,e)|json!({
+            "value":e,
+            "primary":i==0,
+        })).collect::<Vec<_>>(),
+        "phoneNumbers":state.phones.iter().enumerate().map(|(i,(area,mobile))|json!({
+            "value":scim_format_phone(area,mobile),
+            "primary":i==0,
+        })).collect::<Vec<_>>(),
+        "meta":{
+            "resourceType":"User",
+            "created":scim_time(account.add_time),
+            "lastModified":scim_time(account.change_time),
+        }
+    });
+    if !state.external_id.is_empty() {
+        data["externalId"] = json!(state.external_id);
+    }
+    data
+}
+
+async fn scim_user_find(id: &str, req_dao: &RequestDao) -> JsonResult<AccountModel> {
+    let id = scim_parse_id(id)?;
+    Ok(req_dao
+        .web_dao
+        .web_user
+        .user_dao
+        .account_dao
+        .account
+        .cache()
+        .find_by_id(&id)
+        .await?)
+}
+
+async fn scim_user_data(
+    auth: &ScimAuth,
+    account: &AccountModel,
+    req_dao: &RequestDao,
+) -> JsonResult<Value> {
+    let state = scim_user_state(auth, account, req_dao).await?;
+    Ok(scim_user_json(account, &state))
+}
+
+//用户名及外部ID不能被其他账号占用
+async fn scim_user_unique_check(
+    auth: &ScimAuth,
+    state: &ScimUserState,
+    account_id: u64,
+    req_dao: &RequestDao,
+) -> JsonResult<()> {
+    let account_dao = &req_dao.web_dao.web_user.user_dao.account_dao;
+    if let Some(name) = not_found(
+        account_dao
+            .account_name
+            .find_by_name(&state.user_name)
+            .await,
+    )? {
+        if name.account_id != account_id {
+            return Err(scim_error(
+                409,
+                "uniqueness",
+                fluent_message!("scim-user-exists",{"attr":"userName","value":&state.user_name}),
+            ));
+        }
+    }
+    if !state.external_id.is_empty() {
+        if let Some(ext) = not_found(
+            account_dao
+                .account_external
+                .find_by_external(
+                    SCIM_EXTERNAL_CONFIG,
+                    &auth.app.client_id,
+                    &state.external_id,
+                )
+                .await,
+        )? {
+            if ext.account_id != account_id {
+                return Err(scim_error(
+                    409,
+                    "uniqueness",
+                    fluent_message!("scim-user-exists",{"attr":"externalId","value":&state.external_id}),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+//过滤条件需包含 id,userName,externalId,emails.value 其一的等值条件
+async fn scim_user_filter_find(
+    auth: &ScimAuth,
+    filter: &super::ScimFilter,
+    req_dao: &RequestDao,
+) -> JsonResult<Option<AccountModel>> {
+    let account_dao = &req_dao.web_dao.web_user.user_dao.account_dao;
+    let account_id = if let Some(id) = filter.eq_value("id") {
+        id.parse::<u64>().ok()
+    } else if let Some(name) = filter.eq_value("userName") {
+        not_found(account_dao.account_name.find_by_name(name).await)?.map(|e| e.account_id)
+    } else if let Some(ext_id) = filter.eq_value("externalId") {
+        not_found(
+            account_dao
+                .account_external
+                .find_by_external(SCIM_EXTERNAL_CONFIG, &auth.app.client_id, ext_id)
+                .await,
+        )?
+        .map(|e| e.account_id)
+    } else if let Some(email) = filter
+        .eq_value("emails.value")
+        .or_else(|| filter.eq_value("emails"))
+    {
+        not_found(account_dao.account_email.find_by_last_email(email).await)?.map(|e| e.account_id)
+    } else {
+        return Err(scim_error(
+            400,
+            "invalidFilter",
+            fluent_message!("scim-filter-not-support",{
+                "attrs":"id,userName,externalId,emails.value"
+            }),
+        ));
+    };
+    match account_id {
+        Some(id) => not_found(account_dao.account.cache().find_by_id(&id).await),
+        None => Ok(None),
+    }
+}
+
+pub async fn scim_user_list(
+    auth: &ScimAuth,
+    param: &ScimListParam,
+    req_dao: &RequestDao,
+) -> JsonResult<ScimResponse> {
+    if let Some(filter) = param.filter()? {
+        let mut items = vec![];
+        if let Some(account) = scim_user_filter_find(auth, &filter, req_dao).await? {
+            items.push(scim_user_data(auth, &account, req_dao).await?);
+        }
+        return Ok(scim_list_response(items, Some(&filter), param));
+    }
+    let account_dao = &req_dao.web_dao.web_user.user_dao.account_dao;
+    let page = param.page();
+    let total = account_dao.account.list_count().await?;
+    let mut items = vec![];
+    for account in account_dao.account.list_data(Some(&page)).await? {
+        items.push(scim_user_data(auth, &account, req_dao).await?);
+    }
+    Ok(scim_list_body(items, total, &page))
+}
+
+pub async fn scim_user_get(
+    auth: &ScimAuth,
+    id: &str,
+    req_dao: &RequestDao,
+) -> JsonResult<ScimResponse> {
+    let account = scim_user_find(id, req_dao).await?;
+    Ok(ScimResponse::ok(
+        scim_user_data(auth, &account, req_dao).await?,
+    ))
+}
+
+pub async fn scim_user_create(
+    auth: &ScimAuth,
+    body: &Value,
+    req_dao: &RequestDao,
+) -> JsonResult<ScimResponse> {
+    let state = ScimUserState::from_body(body)?;
+    scim_user_unique_check(auth, &state, 0, req_dao).await?;
+    let account = req_dao
+        .web_dao
+        .web_user
+        .auth
+        .reg_user(
+            &AccountRegData {
+                status_enable: state.active,
+                nikename: state.nickname(),
+                passwrod: None,
+                name: Some(&state.user_name),
+                email: state
+                    .emails
+                    .first()
+                    .map(|e| (e.as_str(), AccountEmailStatus::Valid)),
+                mobile: state
+                    .phones
+                    .first()
+                    .map(|(a, m)| (a.as_str(), m.as_str(), AccountMobileStatus::Valid)),
+                external: if state.external_id.is_empty() {
+                    None
+                } else {
+                    Some((
+                        SCIM_EXTERNAL_CONFIG,
+                        auth.app.client_id.as_str(),
+                        state.external_id.as_str(),
+                        state.user_name.as_str(),
+                    ))
+                },
+                info: None,
+                register: None,
+            },
+            auth.op_user_id,
+            Some(&req_dao.req_env),
+        )
+        .await?;
+    //其余邮箱及手机号
+    let old_state = scim_user_state(auth, &account, req_dao).await?;
+    scim_user_apply(auth, &account, &old_state, &state, req_dao).await?;
+    let account = scim_user_find(&account.id.to_string(), req_dao).await?;
+    Ok(ScimResponse::new(
+        201,
+        scim_user_data(auth, &account, req_dao).await?,
+    ))
+}
+
+pub async fn scim_user_replace(
+    auth: &ScimAuth,
+    id: &str,
+    body: &Value,
+    req_dao: &RequestDao,
+) -> JsonResult<ScimResponse> {
+    let account = scim_user_find(id, req_dao).await?;
+    let old_state = scim_user_state(auth, &account, req_dao).await?;
+    let state = ScimUserState::from_body(body)?;
+    scim_user_update(auth, account, old_state, state, req_dao).await
+}
+
+pub async fn scim_user_patch(
+    auth: &ScimAuth,
+    id: &str,
+    body: &Value,
+    req_dao: &RequestDao,
+) -> JsonResult<ScimResponse> {
+    let account = scim_user_find(id, req_dao).await?;
+    let old_state = scim_user_state(auth, &account, req_dao).await?;
+    let mut state = old_state.clone();
+    for (op, path, value) in scim_patch_operations(body)? {
+        match path {
+            Some(path) => state.set_attr(&op, &path, &value)?,
+            None => {
+                if let Some(map) = value.as_object() {
+                    for (key, val) in map {
+                        state.set_attr(&op, key, val)?;
+                    }
+                }
+            }
+        }
+    }
+    state.check()?;
+    scim_user_update(auth, account, old_state, state, req_dao).await
+}
+
+async fn scim_user_update(
+    auth: &ScimAuth,
+    account: AccountModel,
+    old_state: ScimUserState,
+    state: ScimUserState,
+    req_dao: &RequestDao,
+) -> JsonResult<ScimResponse> {
+    if old_state.active && !state.active {
+        //停用即删除账号
+        req_dao
+            .web_dao
+            .web_user
+            .account
+            .user_delete(&account, auth.op_user_id, Some(&req_dao.req_env))
+            .await?;
+        let mut data = scim_user_json(&account, &old_state);
+        data["active"] = json!(false);
+        return Ok(ScimResponse::ok(data));
+    }
+    if old_state != state {
+        scim_user_unique_check(auth, &state, account.id, req_dao).await?;
+        scim_user_apply(auth, &account, &old_state, &state, req_dao).await?;
+    }
+    let account = scim_user_find(&account.id.to_string(), req_dao).await?;
+    Ok(ScimResponse::ok(
+        scim_user_data(auth, &account, req_dao).await?,
+    ))
+}
+
+pub async fn scim_user_delete(
+    auth: &ScimAuth,
+    id: &str,
+    req_dao: &RequestDao,
+) -> JsonResult<ScimResponse> {
+    let account = scim_user_find(id, req_dao).await?;
+    req_dao
+        .web_dao
+        .web_user
+        .account
+        .user_delete(&account, auth.op_user_id, Some(&req_dao.req_env))
+        .await?;
+    Ok(ScimResponse::no_content())
+}
+
+async fn scim_user_apply(
+    auth: &ScimAuth,
+    account: &AccountModel,
+    old_state: &ScimUserState,
+    state: &ScimUserState,
+    req_dao: &RequestDao,
+) -> JsonResult<()> {
+    let mut tran = req_dao.web_dao.db.begin().await?;
+    match scim_user_apply_tran(auth, account, old_state, state, &mut tran, req_dao).await {
+        Ok(()) => {
+            tran.commit().await?;
+            req_dao
+                .web_dao
+                .web_user
+                .user_dao
+                .account_dao
+                .account
+                .search_notify(account.id);
+            Ok(())
+        }
+        Err(err) => {
+            tran.rollback().await?;
+            Err(err)
+        }
+    }
+}
+
+async fn scim_user_apply_tran(
+    auth: &ScimAuth,
+    account: &AccountModel,
+    old_state: &ScimUserState,
+    state: &ScimUserState,
+    tran: &mut Transaction<'_, MySql>,
+    req_dao: &RequestDao,
+) -> JsonResult<()> {
+    let account_dao = &req_dao.web_dao.web_user.user_dao.account_dao;
+    let op_user_id = auth.op_user_id;
+    let env_data = Some(&req_dao.req_env);
+    if old_state.nickname() != state.nickname() {
+        account_dao
+            .account
+            .set_nikename(
+                account,
+                state.nickname(),
+                op_user_id,
+                Some(&mut *tran),
+                env_data,
+            )
+            .await?;
+    }
+    if old_state.user_name != state.user_name {
+        account_dao
+            .account_name
+            .change_account_name(
+                account,
+                &state.user_name,
+                op_user_id,
+                Some(&mut *tran),
+                env_data,
+            )
+            .await?;
+    }
+    if old_state.external_id != state.external_id {
+        for ext in account_dao
+            .account_external
+            .find_by_account_id_vec(&account.id)
+            .await?
+            .into_iter()
+            .filter(|e| {
+                e.config_name == SCIM_EXTERNAL_CONFIG && e.external_type == auth.app.client_id
+            })
+        {
+            account_dao
+                .account_external
+                .del_external(&ext, op_user_id, Some(&mut *tran), env_data)
+                .await?;
+        }
+        if !state.external_id.is_empty() {
+            account_dao
+                .account_external
+                .add_external(
+                    account,
+                    SCIM_EXTERNAL_CONFIG,
+                    &auth.app.client_id,
+                    &state.external_id,
+                    &state.user_name,
+                    op_user_id,
+                    Some(&mut *tran),
+                    env_data,
+                )
+                .await?;
+        }
+    }
+    if old_state.emails != state.emails {
+        for email in account_dao
+            .account_email
+            .find_by_account_id_vec(&account.id)
+            .await?
+        {
+            if !state.emails.contains(&email.email) {
+                account_dao
+                    .account_email
+                    .del_email(&email, op_user_id, Some(&mut *tran), env_data)
+                    .await?;
+            }
+        }
+        for email in state.emails.iter() {
+            if !old_state.emails.contains(email) {
+                account_dao
+                    .account_email
+                    .add_email(
+                        account,
+                        email,
+                        AccountEmailStatus::Valid,
+                        op_user_id,
+                        Some(&mut *tran),
+                        env_data,
+                    )
+                    .await?;
+            }
+        }
+    }
+    if old_state.phones != state.phones {
+        for mobile in account_dao
+            .account_mobile
+            .find_by_account_id_vec(&account.id)
+            .await?
+        {
+            if !state
+                .phones
+                .iter()
+                .any(|(a, m)| *a == mobile.area_code && *m == mobile.mobile)
+            {
+                account_dao
+                    .account_mobile
+                    .del_mobile(&mobile, op_user_id, Some(&mut *tran), env_data)
+                    .await?;
+            }
+        }
+        for (area_code, mobile) in state.phones.iter() {
+            if !old_state
+                .phones
+                .iter()
+                .any(|(a, m)| a == area_code && m == mobile)
+            {
+                account_dao
+                    .account_mobile
+                    .add_mobile(
+                        account,
+                        area_code,
+                        mobile,
+                        AccountMobileStatus::Valid,
+                        op_user_id,
+                        Some(&mut *tran),
+                        env_data,
+                    )
+                    .await?;
+            }
+        }
+    }
+    if !old_state.active && state.active {
+        account_dao
+            .account
+            .enable(account, op_user_id, Some(&mut *tran), env_data)
+            .await?;
+    }
+    Ok(())
+}
@@ -51,3 +51,20 @@ INSERT INTO yaf_user (
     )
 VALUES(1, 0, '1', 'aaaaa', 'root', UNIX_TIMESTAMP());
 
+-- ----------- 组织应用的会话角色,权限检测时按当前用户在应用所属组织的成员身份附加  ---------------
+-- ----------- 角色无默认授权,需在 系统管理-授权管理 中为其配置 global-user 资源的 app-view app-edit 等操作  ---------------
+INSERT INTO yaf_rbac_role (
+        user_id,
+        app_id,
+        role_key,
+        user_range,
+        res_range,
+        role_name,
+        delegable,
+        status,
+        change_user_id,
+        change_time
+    )
+VALUES (0, 0, 'org-member', 2, 1, '组织成员', 0, 1, 1, UNIX_TIMESTAMP()),
+    (0, 0, 'org-admin', 2, 1, '组织管理员', 0, 1, 1, UNIX_TIMESTAMP()),
+    (0, 0, 'org-owner', 2, 1, '组织所有者', 0, 1, 1, UNIX_TIMESTAMP());